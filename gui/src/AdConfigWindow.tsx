@@ -2,7 +2,7 @@ import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 import { cleanPath } from "./pathUtils";
-import type { AdInfo } from "./types";
+import type { AdBreakData, AdInfo, AdPreviewData } from "./types";
 
 const AUDIO_EXTENSIONS = ["mp3", "wav", "flac", "ogg", "aac", "m4a"];
 const DAY_NAMES = ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"];
@@ -12,9 +12,24 @@ interface AdConfigWindowProps {
   onClose: () => void;
 }
 
+/** A break shown under the ad editor: what would air, or what was queued. */
+interface BreakSummary {
+  title: string;
+  rows: { label: string; secs: number }[];
+  totalSecs: number;
+}
+
+function formatTime(secs: number): string {
+  const m = Math.floor(secs / 60);
+  const s = Math.floor(secs % 60);
+  return `${m}:${s.toString().padStart(2, "0")}`;
+}
+
 function AdConfigWindow({ onClose }: AdConfigWindowProps) {
   const [ads, setAds] = useState<AdInfo[]>([]);
   const [selectedIndex, setSelectedIndex] = useState<number | null>(null);
+  const [breakSummary, setBreakSummary] = useState<BreakSummary | null>(null);
+  const [breakError, setBreakError] = useState<string | null>(null);
 
   const loadAds = useCallback(async () => {
     try {
@@ -132,6 +147,36 @@ function AdConfigWindow({ onClose }: AdConfigWindowProps) {
     handleUpdate({ hours: newHours });
   };
 
+  const handlePreviewBreak = async () => {
+    try {
+      const preview = await invoke<AdPreviewData>("preview_ad_break");
+      setBreakSummary({
+        title: "Next break",
+        rows: preview.roll.map((e) => ({ label: e.label, secs: e.duration_secs })),
+        totalSecs: preview.total_secs,
+      });
+      setBreakError(null);
+    } catch (e) {
+      setBreakSummary(null);
+      setBreakError(String(e));
+    }
+  };
+
+  const handleInsertBreak = async () => {
+    try {
+      const queued = await invoke<AdBreakData>("insert_ad_break");
+      const rows = queued.ads.map((a) => ({ label: a.name, secs: a.duration_secs }));
+      if (queued.station_id_secs !== null) {
+        rows.push({ label: "Station ID", secs: queued.station_id_secs });
+      }
+      setBreakSummary({ title: "Queued break", rows, totalSecs: queued.total_secs });
+      setBreakError(null);
+    } catch (e) {
+      setBreakSummary(null);
+      setBreakError(String(e));
+    }
+  };
+
   const formatHour = (h: number): string => {
     if (h === 0) return "12 AM";
     if (h < 12) return `${h} AM`;
@@ -275,7 +320,30 @@ function AdConfigWindow({ onClose }: AdConfigWindowProps) {
             )}
           </div>
         </div>
+        {(breakSummary || breakError) && (
+          <div className="ad-break-summary">
+            {breakError ? (
+              <div className="ad-break-error">{breakError}</div>
+            ) : breakSummary && (
+              <>
+                <div className="ad-break-title">{breakSummary.title}</div>
+                {breakSummary.rows.map((row, i) => (
+                  <div key={i} className="ad-break-row">
+                    <span>{row.label}</span>
+                    <span>{formatTime(row.secs)}</span>
+                  </div>
+                ))}
+                <div className="ad-break-row ad-break-total">
+                  <span>Total</span>
+                  <span>{formatTime(breakSummary.totalSecs)}</span>
+                </div>
+              </>
+            )}
+          </div>
+        )}
         <div className="settings-footer">
+          <button className="settings-btn" onClick={handlePreviewBreak}>Preview Break</button>
+          <button className="settings-btn" onClick={handleInsertBreak}>Insert Break Now</button>
           <button className="settings-btn" onClick={onClose}>Close</button>
         </div>
       </div>
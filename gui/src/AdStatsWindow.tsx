@@ -21,6 +21,7 @@ function AdStatsWindow({ onClose }: AdStatsWindowProps) {
   const [sortDir, setSortDir] = useState<SortDir>("desc");
   const [expandedAd, setExpandedAd] = useState<string | null>(null);
   const [dailyCounts, setDailyCounts] = useState<AdDailyCount[]>([]);
+  const [hourCounts, setHourCounts] = useState<number[]>([]);
   const [failures, setFailures] = useState<AdFailure[]>([]);
   const [exporting, setExporting] = useState(false);
   const [exportMsg, setExportMsg] = useState("");
@@ -58,16 +59,38 @@ function AdStatsWindow({ onClose }: AdStatsWindowProps) {
     if (expandedAd === adName) {
       setExpandedAd(null);
       setDailyCounts([]);
+      setHourCounts([]);
       return;
     }
     setExpandedAd(adName);
+    setHourCounts([]);
     try {
-      const counts = await invoke<AdDailyCount[]>("get_ad_daily_counts", { adName });
+      // With a full range, ask for zero-filled days so gaps show as 0
+      const counts =
+        dateFrom && dateTo
+          ? await invoke<AdDailyCount[]>("get_ad_daily_counts_filled", {
+              adName,
+              start: dateFrom,
+              end: dateTo,
+            })
+          : await invoke<AdDailyCount[]>("get_ad_daily_counts", { adName });
       setDailyCounts(counts);
     } catch (e) {
       console.error("Failed to load daily counts:", e);
       setDailyCounts([]);
     }
+    if (dateFrom && dateTo) {
+      try {
+        const hours = await invoke<number[]>("get_ad_hour_histogram", {
+          adName,
+          start: dateFrom,
+          end: dateTo,
+        });
+        setHourCounts(hours);
+      } catch (e) {
+        console.error("Failed to load hour histogram:", e);
+      }
+    }
   };
 
   const handleSort = (field: SortField) => {
@@ -214,6 +237,27 @@ function AdStatsWindow({ onClose }: AdStatsWindowProps) {
                             </tr>
                           ))
                         )}
+                        {expandedAd === ad.name && hourCounts.some((c) => c > 0) && (
+                          <tr className="ad-stats-detail-row">
+                            <td colSpan={2} className="ad-stats-td">
+                              <div className="ad-stats-hours" title="Plays by hour of day across the range">
+                                {hourCounts.map((count, hour) => (
+                                  <div
+                                    key={hour}
+                                    className="ad-stats-hour"
+                                    title={`${String(hour).padStart(2, "0")}:00 \u2014 ${count} play(s)`}
+                                  >
+                                    <div
+                                      className="ad-stats-hour-bar"
+                                      style={{ height: `${(count / Math.max(...hourCounts)) * 100}%` }}
+                                    />
+                                    <span className="ad-stats-hour-label">{hour}</span>
+                                  </div>
+                                ))}
+                              </div>
+                            </td>
+                          </tr>
+                        )}
                         {expandedAd === ad.name && dailyCounts.length === 0 && (
                           <tr className="ad-stats-detail-row">
                             <td colSpan={2} className="ad-stats-td ad-stats-detail-date">No daily data</td>
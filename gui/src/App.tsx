@@ -2,7 +2,13 @@ import { useState, useEffect, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { open, save } from "@tauri-apps/plugin-dialog";
 import { cleanPath } from "./pathUtils";
-import type { PlaylistInfo, PlaylistProfileInfo, TrackInfo } from "./types";
+import { isTypingTarget, liveAssistAction } from "./liveAssist";
+import type {
+  ConfigResponse,
+  PlaylistInfo,
+  PlaylistProfileInfo,
+  TrackInfo,
+} from "./types";
 import PlaylistView from "./PlaylistView";
 import type { ClipboardData } from "./PlaylistView";
 import TransportBar from "./TransportBar";
@@ -47,6 +53,7 @@ function App() {
   const [selectedProfile, setSelectedProfile] = useState<string>("");
   const [findRequestToken, setFindRequestToken] = useState(0);
   const [editorPath, setEditorPath] = useState<string | null>(null);
+  const [liveAssistEnabled, setLiveAssistEnabled] = useState(false);
   const renameInputRef = useRef<HTMLInputElement>(null);
 
   useEffect(() => {
@@ -197,6 +204,57 @@ function App() {
     return () => window.removeEventListener("keydown", onGlobalKeyDown, true);
   }, [requestOpenFind]);
 
+  // The live-assist setting; re-read whenever the settings window closes.
+  useEffect(() => {
+    if (showSettings) return;
+    invoke<ConfigResponse>("get_config")
+      .then((c) => setLiveAssistEnabled(c.live_assist_hotkeys))
+      .catch((e) => console.error("Failed to load config:", e));
+  }, [showSettings]);
+
+  // Live-assist hotkeys (see liveAssist.ts), only when enabled in settings.
+  // Bubble phase, so dialogs and the editor that handle a key themselves win;
+  // ignored while any overlay is open.
+  useEffect(() => {
+    if (!liveAssistEnabled) return;
+    const onLiveAssistKey = async (event: KeyboardEvent) => {
+      if (event.defaultPrevented || event.repeat) return;
+      if (event.ctrlKey || event.metaKey || event.altKey) return;
+      if (isTypingTarget(event.target)) return;
+      if (document.querySelector(".editor-overlay, .settings-overlay, .playlist-modal-backdrop")) return;
+      const action = liveAssistAction(event.key);
+      if (!action) return;
+      event.preventDefault();
+      try {
+        switch (action.kind) {
+          case "skip":
+            await invoke("transport_skip");
+            await loadTracks();
+            break;
+          case "prev":
+            await invoke("transport_prev");
+            await loadTracks();
+            break;
+          case "pause":
+            await invoke("transport_pause");
+            break;
+          case "stop":
+            await invoke("transport_stop");
+            await loadTracks();
+            break;
+          case "cart":
+            await invoke("play_cart", { index: action.index });
+            break;
+        }
+      } catch (e) {
+        console.error(`Live-assist ${action.kind} failed:`, e);
+      }
+    };
+
+    window.addEventListener("keydown", onLiveAssistKey);
+    return () => window.removeEventListener("keydown", onLiveAssistKey);
+  }, [liveAssistEnabled, loadTracks]);
+
   const handlePlaylistSelect = async (name: string) => {
     setSelectedPlaylist(name);
     setSelectedIndices(new Set());
@@ -233,6 +291,16 @@ function App() {
     }
   };
 
+  const handleToggleLock = async (pl: PlaylistInfo, e: React.MouseEvent) => {
+    e.stopPropagation();
+    try {
+      await invoke("set_playlist_locked", { name: pl.name, locked: !pl.locked });
+      await loadPlaylists();
+    } catch (e) {
+      console.error("Failed to toggle playlist lock:", e);
+    }
+  };
+
   const handleRenameStart = (name: string) => {
     setRenamingTab(name);
     setRenameValue(name);
@@ -326,26 +394,13 @@ function App() {
         // Insert position is after the clicked row
         const insertAt = afterIndex + 1;
         if (clipboard.isCut) {
-          const count = clipboard.sourceIndices.length;
-          // For cut: copy from source, paste at destination, then remove from source
-          await invoke("copy_paste_tracks", {
+          // For cut: move in one call so a failed insert leaves the source intact
+          await invoke("move_tracks", {
             fromPlaylist: clipboard.sourcePlaylist,
             indices: clipboard.sourceIndices,
             toPlaylist: selectedPlaylist,
             at: insertAt,
           });
-          // When cutting within the same playlist, insertion shifts all indices
-          // at or after insertAt by `count` positions — adjust before removing.
-          const removeIndices =
-            clipboard.sourcePlaylist === selectedPlaylist
-              ? clipboard.sourceIndices.map((i) =>
-                  i >= insertAt ? i + count : i,
-                )
-              : clipboard.sourceIndices;
-          await invoke("remove_tracks", {
-            playlist: clipboard.sourcePlaylist,
-            indices: removeIndices,
-          });
           setClipboard(null); // Cut is one-time
         } else {
           // For copy: just copy and paste
@@ -520,6 +575,13 @@ function App() {
                 <>
                   {pl.name}
                   <span className="track-count">{pl.track_count}</span>
+                  <span
+                    className={pl.locked ? "tab-lock locked" : "tab-lock"}
+                    onClick={(e) => handleToggleLock(pl, e)}
+                    title={pl.locked ? "Unlock playlist" : "Lock playlist against edits"}
+                  >
+                    {pl.locked ? "\u{1F512}" : "\u{1F513}"}
+                  </span>
                   <span
                     className="tab-close"
                     onClick={(e) => handleClosePlaylist(pl.name, e)}
@@ -865,11 +865,13 @@ function PlaylistView({
             if (isDropTarget) className += " drop-target";
             if (isFindMatch) className += " find-match";
             if (isFindCurrent) className += " find-current";
+            if (track.error) className += " broken";
             return (
               <tr
                 key={track.index}
                 data-track-index={track.index}
                 className={className}
+                title={track.error ?? undefined}
                 draggable={!editingCell}
                 onClick={(e) => handleRowClick(e, track.index)}
                 onDoubleClick={(e) => {
@@ -5,7 +5,7 @@ import { cleanPath } from "./pathUtils";
 import type { ScheduleEventInfo } from "./types";
 
 const AUDIO_EXTENSIONS = ["mp3", "wav", "flac", "ogg", "aac", "m4a"];
-const MODES = ["overlay", "stop", "insert"];
+const MODES = ["overlay", "stop", "insert", "activate"];
 const DAY_LABELS = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
 
 interface SchedulePaneProps {
@@ -116,6 +116,7 @@ function SchedulePane({ onClose }: SchedulePaneProps) {
     switch (mode.toLowerCase()) {
       case "stop": return "sched-mode-stop";
       case "insert": return "sched-mode-insert";
+      case "activate": return "sched-mode-activate";
       default: return "sched-mode-overlay";
     }
   };
@@ -238,13 +239,21 @@ function SchedulePane({ onClose }: SchedulePaneProps) {
               <div className="schedule-event-main">
                 <span className="schedule-event-time">{evt.time}</span>
                 <span className={`schedule-event-mode ${modeClass(evt.mode)}`}>{evt.mode}</span>
-                <span className="schedule-event-label" title={evt.file}>
+                <span
+                  className="schedule-event-label"
+                  title={evt.fallback_file ? `${evt.file}\nFallback: ${evt.fallback_file}` : evt.file}
+                >
                   {evt.label || evt.file.split(/[/\\]/).pop() || evt.file}
                 </span>
               </div>
               <div className="schedule-event-meta">
                 <span className="schedule-event-priority" title="Priority">P{evt.priority}</span>
                 {evt.days && <span className="schedule-event-days">{evt.days}</span>}
+                {evt.next_fire && (
+                  <span className="schedule-event-days" title="Next trigger">
+                    next {evt.next_fire}
+                  </span>
+                )}
               </div>
               <div className="schedule-event-actions">
                 <button
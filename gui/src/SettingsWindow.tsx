@@ -43,6 +43,7 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
 
   // Crossfade
   const [fadeSecs, setFadeSecs] = useState("0");
+  const [crossfadeOnSkip, setCrossfadeOnSkip] = useState(false);
   const [curveType] = useState("linear");
 
   // Silence
@@ -53,9 +54,13 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
   const [introsFolder, setIntrosFolder] = useState<string | null>(null);
   const [introInterval, setIntroInterval] = useState("0");
   const [introDuck, setIntroDuck] = useState("0.3");
+  const [introOncePerSession, setIntroOncePerSession] = useState(false);
+  const [introMode, setIntroMode] = useState<"before" | "over">("before");
+  const [introGain, setIntroGain] = useState("0");
 
   // Now-Playing
   const [nowPlayingPath, setNowPlayingPath] = useState<string | null>(null);
+  const [nowPlayingDebounceMs, setNowPlayingDebounceMs] = useState("500");
 
   // Streaming
   const [streamOutputEnabled, setStreamOutputEnabled] = useState(false);
@@ -77,6 +82,15 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
   // Audio Output
   const [outputDevices, setOutputDevices] = useState<string[]>([]);
   const [selectedDevice, setSelectedDevice] = useState<string | null>(null);
+  const [monitorDevice, setMonitorDevice] = useState<string | null>(null);
+  const [outputSampleRate, setOutputSampleRate] = useState<number | null>(null);
+  const [agcEnabled, setAgcEnabled] = useState(false);
+  const [replayGainEnabled, setReplayGainEnabled] = useState(false);
+  const [liveAssistHotkeys, setLiveAssistHotkeys] = useState(false);
+  const [agcTargetDb, setAgcTargetDb] = useState("-18");
+  const [agcAttackMs, setAgcAttackMs] = useState("10");
+  const [agcReleaseMs, setAgcReleaseMs] = useState("500");
+  const [agcMaxGainDb, setAgcMaxGainDb] = useState("12");
 
   useEffect(() => {
     (async () => {
@@ -84,12 +98,17 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
         const c = await invoke<ConfigResponse>("get_config");
         setConfig(c);
         setFadeSecs(String(c.crossfade_secs));
+        setCrossfadeOnSkip(c.crossfade_on_skip);
         setSilenceThreshold(String(c.silence_threshold));
         setSilenceDuration(String(c.silence_duration_secs));
         setIntrosFolder(c.intros_folder);
         setIntroInterval(String(c.recurring_intro_interval_secs));
         setIntroDuck(String(c.recurring_intro_duck_volume));
+        setIntroOncePerSession(c.intro_once_per_session);
+        setIntroMode(c.intro_mode);
+        setIntroGain(String(c.intro_gain_db));
         setNowPlayingPath(c.now_playing_path);
+        setNowPlayingDebounceMs(String(c.now_playing_debounce_ms));
         setStreamOutputEnabled(c.stream_output_enabled);
         setStreamOutputUrl(c.stream_output_url);
         setRecordingEnabled(c.recording_enabled);
@@ -98,6 +117,15 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
         setIndexedLocations(c.indexed_locations || []);
         setFavoriteFolders(c.favorite_folders || []);
         setSelectedDevice(c.output_device_name ?? null);
+        setMonitorDevice(c.monitor_device_name ?? null);
+        setOutputSampleRate(c.output_sample_rate ?? null);
+        setAgcEnabled(c.agc_enabled);
+        setReplayGainEnabled(c.replay_gain_enabled);
+        setLiveAssistHotkeys(c.live_assist_hotkeys);
+        setAgcTargetDb(String(c.agc_target_db));
+        setAgcAttackMs(String(c.agc_attack_ms));
+        setAgcReleaseMs(String(c.agc_release_ms));
+        setAgcMaxGainDb(String(c.agc_max_gain_db));
         try {
           const devices = await invoke<string[]>("list_output_devices");
           setOutputDevices(devices);
@@ -124,6 +152,7 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
     setSaving(true);
     try {
       await invoke("set_crossfade", { secs });
+      await invoke("set_crossfade_on_skip", { enabled: crossfadeOnSkip });
       showSaved();
     } catch (e) {
       console.error("Failed to set crossfade:", e);
@@ -184,6 +213,14 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
           duckVolume: duck,
         });
       }
+      await invoke("set_intro_once_per_session", {
+        enabled: introOncePerSession,
+      });
+      await invoke("set_intro_mode", { mode: introMode });
+      const gain = parseFloat(introGain);
+      if (!isNaN(gain)) {
+        await invoke("set_intro_gain", { gainDb: gain });
+      }
       showSaved();
     } catch (e) {
       console.error("Failed to save intro settings:", e);
@@ -225,6 +262,9 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
     setSaving(true);
     try {
       await invoke("set_nowplaying_path", { path: nowPlayingPath || null });
+      await invoke("set_now_playing_debounce", {
+        ms: Math.max(0, Math.round(parseFloat(nowPlayingDebounceMs) || 0)),
+      });
       showSaved();
     } catch (e) {
       console.error("Failed to save now-playing path:", e);
@@ -364,6 +404,17 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
     setSaving(true);
     try {
       await invoke("set_output_device", { name: selectedDevice || null });
+      await invoke("set_monitor_device", { name: monitorDevice || null });
+      await invoke("set_output_sample_rate", { rate: outputSampleRate });
+      await invoke("set_agc", {
+        enabled: agcEnabled,
+        targetDb: parseFloat(agcTargetDb),
+        attackMs: parseFloat(agcAttackMs),
+        releaseMs: parseFloat(agcReleaseMs),
+        maxGainDb: parseFloat(agcMaxGainDb),
+      });
+      await invoke("set_replay_gain", { enabled: replayGainEnabled });
+      await invoke("set_live_assist_hotkeys", { enabled: liveAssistHotkeys });
       showSaved();
     } catch (e) {
       console.error("Failed to set output device:", e);
@@ -609,6 +660,129 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
                       : "Using the system default audio device"}
                   </span>
                 </div>
+                <div className="settings-field">
+                  <label className="settings-label">Monitor Device (Editor)</label>
+                  <select
+                    className="settings-select"
+                    value={monitorDevice ?? ""}
+                    onChange={(e) =>
+                      setMonitorDevice(e.target.value || null)
+                    }
+                  >
+                    <option value="">System Default</option>
+                    {outputDevices.map((name) => (
+                      <option key={name} value={name}>
+                        {name}
+                      </option>
+                    ))}
+                  </select>
+                  <span className="settings-hint">
+                    Editor previews play here (e.g. headphones), separate from the program output
+                  </span>
+                </div>
+                <div className="settings-field">
+                  <label className="settings-label">Output Sample Rate</label>
+                  <select
+                    className="settings-select"
+                    value={outputSampleRate ?? ""}
+                    onChange={(e) =>
+                      setOutputSampleRate(e.target.value ? Number(e.target.value) : null)
+                    }
+                  >
+                    <option value="">Per file (no resampling)</option>
+                    <option value="44100">44.1 kHz</option>
+                    <option value="48000">48 kHz</option>
+                  </select>
+                  <span className="settings-hint">
+                    Resample program audio for outputs that require a fixed rate
+                  </span>
+                </div>
+                <div className="settings-field">
+                  <label className="settings-checkbox-row">
+                    <input
+                      type="checkbox"
+                      checked={agcEnabled}
+                      onChange={(e) => setAgcEnabled(e.target.checked)}
+                    />
+                    <span>Automatic gain control on program output</span>
+                  </label>
+                </div>
+                <div className="settings-field">
+                  <label className="settings-checkbox-row">
+                    <input
+                      type="checkbox"
+                      checked={replayGainEnabled}
+                      onChange={(e) => setReplayGainEnabled(e.target.checked)}
+                    />
+                    <span>Apply ReplayGain tags on air</span>
+                  </label>
+                </div>
+                <div className="settings-field">
+                  <label className="settings-checkbox-row">
+                    <input
+                      type="checkbox"
+                      checked={liveAssistHotkeys}
+                      onChange={(e) => setLiveAssistHotkeys(e.target.checked)}
+                    />
+                    <span>
+                      Live-assist hotkeys (N skip, B back, Space pause, S stop,
+                      1–9 carts)
+                    </span>
+                  </label>
+                </div>
+                <div className="settings-field">
+                  <label className="settings-label">AGC Target (dBFS)</label>
+                  <div className="settings-input-row">
+                    <input
+                      type="number"
+                      className="settings-input"
+                      min={-40}
+                      max={0}
+                      step={1}
+                      value={agcTargetDb}
+                      onChange={(e) => setAgcTargetDb(e.target.value)}
+                    />
+                    <span className="settings-hint">RMS level to ride toward</span>
+                  </div>
+                </div>
+                <div className="settings-field">
+                  <label className="settings-label">AGC Attack / Release (ms)</label>
+                  <div className="settings-input-row">
+                    <input
+                      type="number"
+                      className="settings-input"
+                      min={0}
+                      step={1}
+                      value={agcAttackMs}
+                      onChange={(e) => setAgcAttackMs(e.target.value)}
+                    />
+                    <input
+                      type="number"
+                      className="settings-input"
+                      min={0}
+                      step={50}
+                      value={agcReleaseMs}
+                      onChange={(e) => setAgcReleaseMs(e.target.value)}
+                    />
+                  </div>
+                </div>
+                <div className="settings-field">
+                  <label className="settings-label">AGC Max Gain (dB)</label>
+                  <div className="settings-input-row">
+                    <input
+                      type="number"
+                      className="settings-input"
+                      min={0}
+                      max={24}
+                      step={1}
+                      value={agcMaxGainDb}
+                      onChange={(e) => setAgcMaxGainDb(e.target.value)}
+                    />
+                    <span className="settings-hint">
+                      Most a quiet track is boosted
+                    </span>
+                  </div>
+                </div>
               </div>
             )}
 
@@ -631,6 +805,16 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
                     <span className="settings-hint">0 = disabled</span>
                   </div>
                 </div>
+                <div className="settings-field">
+                  <label className="settings-checkbox-row">
+                    <input
+                      type="checkbox"
+                      checked={crossfadeOnSkip}
+                      onChange={(e) => setCrossfadeOnSkip(e.target.checked)}
+                    />
+                    <span>Crossfade on manual skip</span>
+                  </label>
+                </div>
                 <div className="settings-field">
                   <label className="settings-label">Curve Type</label>
                   <select
@@ -765,6 +949,50 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
                     </span>
                   </div>
                 </div>
+                <div className="settings-field">
+                  <label className="settings-label">Intro Gain (dB)</label>
+                  <div className="settings-input-row">
+                    <input
+                      type="number"
+                      className="settings-input"
+                      min={-24}
+                      max={12}
+                      step={0.5}
+                      value={introGain}
+                      onChange={(e) => setIntroGain(e.target.value)}
+                    />
+                    <span className="settings-hint">
+                      Intro level relative to the file (0 = unchanged)
+                    </span>
+                  </div>
+                </div>
+                <div className="settings-field">
+                  <label className="settings-checkbox-row">
+                    <input
+                      type="checkbox"
+                      checked={introOncePerSession}
+                      onChange={(e) => setIntroOncePerSession(e.target.checked)}
+                    />
+                    <span>Play each artist's intro only once per session</span>
+                  </label>
+                </div>
+                <div className="settings-field">
+                  <label className="settings-label">Intro Placement</label>
+                  <select
+                    className="settings-select"
+                    value={introMode}
+                    onChange={(e) =>
+                      setIntroMode(e.target.value as "before" | "over")
+                    }
+                  >
+                    <option value="before">Before the track</option>
+                    <option value="over">Over the track start (ducked)</option>
+                  </select>
+                  <span className="settings-hint">
+                    Over mode ducks the track to the duck volume while the
+                    intro plays
+                  </span>
+                </div>
               </div>
             )}
 
@@ -801,6 +1029,22 @@ function SettingsWindow({ onClose, initialTab }: SettingsWindowProps) {
                     XML file updated with current/next track info
                   </span>
                 </div>
+                <div className="settings-field">
+                  <label className="settings-label">Update Delay (ms)</label>
+                  <div className="settings-input-row">
+                    <input
+                      type="number"
+                      className="settings-input"
+                      min={0}
+                      step={100}
+                      value={nowPlayingDebounceMs}
+                      onChange={(e) => setNowPlayingDebounceMs(e.target.value)}
+                    />
+                    <span className="settings-hint">
+                      Changes within this window are written once
+                    </span>
+                  </div>
+                </div>
               </div>
             )}
 
@@ -47,6 +47,8 @@ function TransportBar({ onTrackChange, selectedTrackIndex, onPlayingIndexChange
     next_artist: null,
     next_title: null,
     track_path: null,
+    talk_over_secs: null,
+    talk_over_remaining_secs: null,
   });
   const lastReportedIndex = useRef<number | null | undefined>(undefined);
 
@@ -156,6 +158,15 @@ function TransportBar({ onTrackChange, selectedTrackIndex, onPlayingIndexChange
     }
   };
 
+  const handlePrev = async () => {
+    try {
+      await invoke("transport_prev");
+      onTrackChange?.();
+    } catch (e) {
+      console.error("transport_prev error:", e);
+    }
+  };
+
   const handleWaveformSeek = useCallback(async (positionSecs: number) => {
     try {
       await invoke("transport_seek", { positionSecs });
@@ -167,6 +178,8 @@ function TransportBar({ onTrackChange, selectedTrackIndex, onPlayingIndexChange
   const elapsed = displayElapsed;
   const remaining = Math.max(0, state.duration_secs - elapsed);
   const hasTrack = state.track_artist || state.track_title;
+  const talkOverLeft =
+    state.talk_over_secs !== null ? state.talk_over_secs - elapsed : 0;
   const displayPlaytime = formatPlaytime(new Date(baseWallClock.current + elapsed * 1000));
 
   return (
@@ -177,6 +190,11 @@ function TransportBar({ onTrackChange, selectedTrackIndex, onPlayingIndexChange
           <>
             <span className="now-playing-title">{state.track_title ?? "Unknown"}</span>
             <span className="now-playing-artist">{state.track_artist ?? "Unknown"}</span>
+            {talkOverLeft > 0 && (
+              <span className="now-playing-talkover" title="Time until the vocals start">
+                Talk: {Math.ceil(talkOverLeft)}s
+              </span>
+            )}
           </>
         ) : (
           <span className="now-playing-empty">No track loaded</span>
@@ -199,6 +217,9 @@ function TransportBar({ onTrackChange, selectedTrackIndex, onPlayingIndexChange
         <button className="transport-btn" onClick={handleStop} title="Stop">
           {"\u23F9"}
         </button>
+        <button className="transport-btn" onClick={handlePrev} title="Previous">
+          {"\u23EE"}
+        </button>
         <button className="transport-btn" onClick={handleSkip} title="Skip Next">
           {"\u23ED"}
         </button>
@@ -295,6 +295,7 @@ export default function AudioEditorModal({
                 if ("volume_db" in partial) dispatch({ type: "SET_VOLUME", db: partial.volume_db! });
                 if ("speed" in partial) dispatch({ type: "SET_SPEED", speed: partial.speed! });
                 if ("pitch_semitones" in partial) dispatch({ type: "SET_PITCH", semitones: partial.pitch_semitones! });
+                if ("tempo" in partial) dispatch({ type: "SET_TEMPO", tempo: partial.tempo! });
                 if ("fade_in_secs" in partial) dispatch({ type: "SET_FADE_IN", secs: partial.fade_in_secs! });
                 if ("fade_out_secs" in partial) dispatch({ type: "SET_FADE_OUT", secs: partial.fade_out_secs! });
                 if ("normalize" in partial) dispatch({ type: "SET_NORMALIZE", enabled: partial.normalize! });
@@ -405,6 +406,7 @@ export default function AudioEditorModal({
               onSeek={handleSeek}
               onSilenceRegionsLoaded={setSilenceRegions}
               onTrimSilenceEdges={handleTrimSilenceEdges}
+              onSetTrimStart={(secs) => dispatch({ type: "SET_TRIM_START", secs })}
             />
           </div>
         </div>
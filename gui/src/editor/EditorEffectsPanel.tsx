@@ -92,6 +92,17 @@ export default function EditorEffectsPanel({ ops, onChange }: EditorEffectsPanel
         defaultValue={0}
       />
 
+      <SliderRow
+        label="Tempo"
+        value={ops.tempo}
+        min={0.8}
+        max={1.2}
+        step={0.005}
+        format={(v) => `${(v * 100).toFixed(1)}%`}
+        onChange={(v) => onChange({ tempo: v })}
+        defaultValue={1.0}
+      />
+
       <div className="editor-fx-section-title">Fades</div>
 
       <SliderRow
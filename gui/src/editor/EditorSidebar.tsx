@@ -14,6 +14,7 @@ interface EditorSidebarProps {
   onSeek: (secs: number) => void;
   onSilenceRegionsLoaded: (regions: SilenceRegion[]) => void;
   onTrimSilenceEdges: () => void;
+  onSetTrimStart: (secs: number) => void;
 }
 
 const MARKER_COLORS = [
@@ -45,10 +46,12 @@ export default function EditorSidebar({
   onSeek,
   onSilenceRegionsLoaded,
   onTrimSilenceEdges,
+  onSetTrimStart,
 }: EditorSidebarProps) {
   const [editingMarkerId, setEditingMarkerId] = useState<string | null>(null);
   const [editingLabel, setEditingLabel] = useState("");
   const [scanningState, setScanningState] = useState<"idle" | "scanning" | "done">("idle");
+  const [suggestedCueIn, setSuggestedCueIn] = useState<number | null>(null);
   const [colorIndex, setColorIndex] = useState(0);
 
   const handleAddMarker = () => {
@@ -72,6 +75,8 @@ export default function EditorSidebar({
         end_secs: Math.min(r.end_secs, duration),
       }));
       onSilenceRegionsLoaded(clamped);
+      const cueIn = await invoke<number>("suggest_cue_in", { path, thresholdDb: -40.0 });
+      setSuggestedCueIn(cueIn > 0.01 && cueIn < duration ? cueIn : null);
       setScanningState("done");
     } catch (e) {
       console.error("Silence scan failed:", e);
@@ -199,6 +204,16 @@ export default function EditorSidebar({
             </button>
           )}
         </div>
+        {suggestedCueIn !== null && (
+          <div
+            className="editor-sidebar-silence-row"
+            onClick={() => onSetTrimStart(suggestedCueIn)}
+            title="Set trim start to where audio begins"
+          >
+            <span>Suggested cue-in</span>
+            <span className="editor-sidebar-silence-dur">{formatTime(suggestedCueIn)}</span>
+          </div>
+        )}
         {silenceRegions.length === 0 ? (
           <div className="editor-sidebar-empty">
             {scanningState === "done" ? "No silence regions found" : "Not scanned yet"}
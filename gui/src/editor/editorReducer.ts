@@ -113,6 +113,12 @@ export function editorReducer(
         ops: { ...present.ops, pitch_semitones: action.semitones },
       });
 
+    case "SET_TEMPO":
+      return pushHistory(state, {
+        ...present,
+        ops: { ...present.ops, tempo: action.tempo },
+      });
+
     case "SET_FADE_IN":
       return pushHistory(state, {
         ...present,
@@ -8,6 +8,12 @@ export interface EditorPeakData {
   resolution_ms: number;
 }
 
+export interface EditorBatchEntry {
+  path: string;
+  data: EditorPeakData | null;
+  error: string | null;
+}
+
 export interface AudioFileInfo {
   format: string;
   duration_secs: number;
@@ -47,6 +53,7 @@ export interface EditorOperations {
   volume_db: number;
   speed: number;
   pitch_semitones: number;
+  tempo: number;
   fade_in_secs: number;
   fade_out_secs: number;
   normalize: boolean;
@@ -70,6 +77,7 @@ export const defaultOps = (duration: number): EditorOperations => ({
   volume_db: 0,
   speed: 1.0,
   pitch_semitones: 0,
+  tempo: 1.0,
   fade_in_secs: 0,
   fade_out_secs: 0,
   normalize: false,
@@ -93,6 +101,7 @@ export type EditorAction =
   | { type: "SET_VOLUME"; db: number }
   | { type: "SET_SPEED"; speed: number }
   | { type: "SET_PITCH"; semitones: number }
+  | { type: "SET_TEMPO"; tempo: number }
   | { type: "SET_FADE_IN"; secs: number }
   | { type: "SET_FADE_OUT"; secs: number }
   | { type: "SET_NORMALIZE"; enabled: boolean }
@@ -0,0 +1,51 @@
+/**
+ * Live-assist hotkeys: single keys that drive the on-air transport
+ * without reaching for the mouse.
+ *
+ *   n      skip to the next track
+ *   b      back to the previous track
+ *   space  pause / resume
+ *   s      stop
+ *   1–9    fire cart 1–9
+ *
+ * Off unless "Live-assist hotkeys" is turned on in Settings (the engine's
+ * `live_assist_hotkeys`), so a stray keystroke can't change what's on air.
+ */
+export type LiveAssistAction =
+  | { kind: "skip" }
+  | { kind: "prev" }
+  | { kind: "pause" }
+  | { kind: "stop" }
+  | { kind: "cart"; index: number };
+
+/** Map a `KeyboardEvent.key` to its live-assist action, if any. */
+export function liveAssistAction(key: string): LiveAssistAction | null {
+  switch (key) {
+    case "n":
+    case "N":
+      return { kind: "skip" };
+    case "b":
+    case "B":
+      return { kind: "prev" };
+    case " ":
+      return { kind: "pause" };
+    case "s":
+    case "S":
+      return { kind: "stop" };
+  }
+  if (key.length === 1 && key >= "1" && key <= "9") {
+    return { kind: "cart", index: Number(key) };
+  }
+  return null;
+}
+
+/** True when keystrokes at `target` are text entry and must not be hijacked. */
+export function isTypingTarget(target: EventTarget | null): boolean {
+  if (!(target instanceof HTMLElement)) return false;
+  return (
+    target instanceof HTMLInputElement ||
+    target instanceof HTMLTextAreaElement ||
+    target instanceof HTMLSelectElement ||
+    target.isContentEditable
+  );
+}
@@ -4,6 +4,18 @@ export interface PlaylistInfo {
   track_count: number;
   is_active: boolean;
   current_index: number | null;
+  auto_advance: boolean;
+  locked: boolean;
+}
+
+export interface PlaylistStats {
+  total_tracks: number;
+  total_duration_secs: number;
+  /** Distinct artists, case-insensitive; blank artists aren't counted. */
+  unique_artists: number;
+  lecture_count: number;
+  music_count: number;
+  average_duration_secs: number;
 }
 
 export interface TrackInfo {
@@ -16,6 +28,19 @@ export interface TrackInfo {
   played_duration_secs: number | null;
   start_time_display: string | null;
   has_intro: boolean;
+  duration_verified: boolean;
+  no_crossfade_in: boolean;
+  advisory: boolean;
+  gain_trim_db: number;
+  replay_gain_db: number | null;
+  /** Musical key as tagged or analysed, e.g. "Am". */
+  musical_key: string | null;
+  /** The key in Camelot notation, e.g. "8A". */
+  camelot_key: string | null;
+  /** Seconds of intro before the vocals start. */
+  talk_over_secs: number | null;
+  /** Why the file can't be played (missing, empty, ...), or null. */
+  error: string | null;
 }
 
 export interface StatusResponse {
@@ -25,6 +50,8 @@ export interface StatusResponse {
   crossfade_secs: number;
   conflict_policy: string;
   silence_threshold: number;
+  silence_threshold_unit: "linear" | "dbfs";
+  silence_threshold_display: number;
   silence_duration_secs: number;
   intros_folder: string | null;
   now_playing_path: string | null;
@@ -45,24 +72,87 @@ export interface ScheduleEventInfo {
   enabled: boolean;
   label: string | null;
   days: string;
+  rotator: boolean;
+  autostart: boolean;
+  insert_position: string;
+  fallback_file: string | null;
+  start_date: string | null;
+  end_date: string | null;
+  next_fire: string | null;
+  conflicts_with: number[];
 }
 
 export interface ConfigResponse {
   crossfade_secs: number;
+  signon_fade_secs: number;
+  crossfade_lead_secs: number;
+  min_crossfade_track_secs: number;
+  crossfade_on_skip: boolean;
+  /** Single-key live-assist hotkeys are on (off by default). */
+  live_assist_hotkeys: boolean;
+  overlay_gain: number;
+  resume_save_interval_secs: number;
+  save_debounce_ms: number;
+  save_config_immediately: boolean;
   silence_threshold: number;
+  silence_threshold_unit: "linear" | "dbfs";
+  silence_threshold_display: number;
   silence_duration_secs: number;
+  /** Output level (dBFS) below which the dead-air alarm counts silence. */
+  dead_air_threshold_db: number;
+  /** Seconds of silent output while playing before the alarm (0 = off). */
+  dead_air_secs: number;
   intros_folder: string | null;
   recurring_intro_interval_secs: number;
   recurring_intro_duck_volume: number;
+  recurring_intro_duck_ramp_secs: number;
+  intro_once_per_session: boolean;
+  intro_mode: "before" | "over";
+  intro_gain_db: number;
   conflict_policy: string;
+  paused_event_handling: "queue" | "play-over";
+  end_of_playlist_action: "stop" | "loop" | "fallback";
+  end_of_playlist_fallback: string | null;
   now_playing_path: string | null;
+  /** Updates closer together than this are coalesced into one write. */
+  now_playing_debounce_ms: number;
+  now_playing_ad_placeholder: string;
+  heartbeat_path: string | null;
+  /** Aired on loop when there is nothing else to play. */
+  emergency_file: string | null;
   stream_output_enabled: boolean;
   stream_output_url: string;
   recording_enabled: boolean;
   recording_output_dir: string | null;
   indexed_locations: string[];
   favorite_folders: string[];
+  measure_unknown_durations: boolean;
   output_device_name: string | null;
+  monitor_device_name: string | null;
+  output_sample_rate: number | null;
+  /** Program output device buffer (ms, 0 = driver default). */
+  audio_buffer_ms: number;
+  agc_enabled: boolean;
+  agc_target_db: number;
+  agc_attack_ms: number;
+  agc_release_ms: number;
+  agc_max_gain_db: number;
+  channel_mode: "stereo" | "mono_sum" | "swap_lr";
+  /** Apply ReplayGain tags on air (the per-track trim always applies). */
+  replay_gain_enabled: boolean;
+  normalize_ads: boolean;
+  normalize_target_lufs: number;
+  ad_min_repeat_gap: string;
+  station_id_position: "start" | "end" | "both";
+  ad_event_guard_secs: number;
+  ad_failure_retention: number;
+  ad_max_roll_secs: number;
+  ad_fit_best: boolean;
+  ad_insert_timing: "after_current" | "immediate";
+  clean_hours: number[];
+  avoid_artist_repeat: boolean;
+  report_logo_path: string | null;
+  report_header_rgb: [number, number, number] | null;
 }
 
 export interface AdInfo {
@@ -73,6 +163,16 @@ export interface AdInfo {
   scheduled: boolean;
   days: string[];
   hours: number[];
+  normalized_file: string | null;
+  start_date: string | null;
+  end_date: string | null;
+}
+
+export interface CartInfo {
+  index: number;
+  label: string;
+  path: string;
+  overlay: boolean;
 }
 
 export interface LogEntry {
@@ -102,6 +202,100 @@ export interface AdFailure {
   error: string;
 }
 
+export interface AdReportAuditEntry {
+  generated_at: string;
+  ad_name: string;
+  start: string;
+  end: string;
+  company?: string;
+  files: string[];
+}
+
+export interface AdDeliveryReport {
+  ad_name: string;
+  start: string;
+  end: string;
+  /** Eligible hours in the period (one break per hour). */
+  expected: number;
+  /** Eligible hours with at least one confirmed play. */
+  actual: number;
+  shortfall: number;
+  /** Eligible hours ("YYYY-MM-DD_HH") with no confirmed play. */
+  missed_hours: string[];
+  outside_schedule: number;
+}
+
+export interface AdPreviewEntry {
+  /** Ad name, or "Station ID". */
+  label: string;
+  path: string;
+  station_id: boolean;
+  duration_secs: number;
+}
+
+/** What an ad break would play right now (`preview_ad_break`). */
+export interface AdPreviewData {
+  roll: AdPreviewEntry[];
+  forced_repeats: string[];
+  total_secs: number;
+}
+
+export interface AdBreakEntry {
+  name: string;
+  duration_secs: number;
+}
+
+/** An ad break as queued by `insert_ad_break`. */
+export interface AdBreakData {
+  ads: AdBreakEntry[];
+  /** Length of one station ID play, when the break has one. */
+  station_id_secs: number | null;
+  forced_repeats: string[];
+  /** The whole break, station ID play(s) included. */
+  total_secs: number;
+}
+
+export interface BundleAudioReference {
+  kind: "ad" | "station_id" | "schedule" | "cart";
+  name: string;
+  path: string;
+  exists: boolean;
+  bundled_as?: string;
+}
+
+export interface ConfigBundleManifest {
+  created_at: string;
+  include_audio: boolean;
+  entries: string[];
+  audio: BundleAudioReference[];
+}
+
+export interface ConfigImportSummary {
+  playlists_added: string[];
+  playlists_skipped: string[];
+  ads_added: string[];
+  ads_skipped: string[];
+  events_added: number;
+  events_skipped: number;
+}
+
+export interface RelinkSummary {
+  changed: number;
+  missing: number;
+}
+
+export interface TrackIssue {
+  index: number;
+  path: string;
+  problem: string;
+}
+
+export interface ConvertSummary {
+  converted: number;
+  skipped: number;
+  failures: TrackIssue[];
+}
+
 export interface RdsMessageInfo {
   index: number;
   text: string;
@@ -117,6 +311,8 @@ export interface RdsConfigResponse {
   port: number;
   default_message: string;
   messages: RdsMessageInfo[];
+  segmented_ps: boolean;
+  radio_text: boolean;
 }
 
 export interface TransportState {
@@ -130,6 +326,27 @@ export interface TransportState {
   next_artist: string | null;
   next_title: string | null;
   track_path: string | null;
+  /** Intro length the host can talk over, in seconds. */
+  talk_over_secs: number | null;
+  talk_over_remaining_secs: number | null;
+}
+
+/** Payload of the `transport-event` signal. */
+/** Payload of the "dead-air" event. */
+export type DeadAirEvent = "tripped" | "cleared";
+
+export interface TransportEvent {
+  kind: "play" | "stop" | "pause" | "resume" | "skip" | "prev" | "seek" | "track_finished";
+  track_index: number | null;
+  artist: string | null;
+  title: string | null;
+  position_secs: number;
+}
+
+export interface OnAirStatus {
+  on_air: boolean;
+  is_playing: boolean;
+  last_audio_age_secs: number | null;
 }
 
 export interface FileBrowserEntry {
@@ -148,3 +365,18 @@ export interface PlaylistProfileInfo {
   playlist_names: string[];
   playlist_paths: (string | null)[];
 }
+
+export interface ResumeMarker {
+  playlist: string;
+  track_index: number;
+  offset_secs: number;
+  saved_at: string;
+}
+
+export interface PlaylistSearchResult {
+  playlist: string;
+  track_index: number;
+  artist: string;
+  title: string;
+  path: string;
+}
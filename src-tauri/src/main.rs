@@ -1,13 +1,23 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use serde::{Deserialize, Serialize};
+use signal_flow::ad_inserter::RollLengths;
 use signal_flow::app_core::{
-    list_directory_at, search_files_in_locations, AdData, AppCore, ConfigData, FileBrowserEntry,
-    FileSearchResult, LogEntry, PlaylistData, PlaylistProfileData, RdsConfigData,
-    ScheduleEventData, StatusData, TrackData, TransportData,
+    ON_AIR_MAX_STALE, list_directory_at, search_files_in_locations, AdBreakData, AdData, AdPreviewData, AppCore, CartData, ConfigData, ConvertSummary, FileBrowserEntry,
+    FileSearchResult, LogEntry, OnAirData, PlaylistData, PlaylistProfileData, PlaylistSearchResult, RdsConfigData,
+    ScheduleEventData, SeekOutcome, StatusData, TrackData, TrackIssueData, TransportData, TransportEvent,
+    TransportEventKind,
 };
 use signal_flow::audio_runtime::{spawn_audio_runtime, AudioEvent, AudioHandle};
+use signal_flow::crossfade_preview::PREVIEW_CONTEXT_SECS;
+use signal_flow::engine::{Engine, OutputBus, RelinkSummary};
+use signal_flow::facade;
 use signal_flow::level_monitor::LevelMonitor;
+use signal_flow::musical_key::KeyAnalysis;
+use signal_flow::player::CrossfadeCurve;
+use signal_flow::playlist::PlaylistStats;
+use signal_flow::resume::ResumeMarker;
+use signal_flow::scheduler::{ScheduleAction, ScheduleMode};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -56,6 +66,43 @@ fn get_status(state: State<AppState>) -> StatusData {
     state.core.lock().unwrap().get_status()
 }
 
+/// Re-read the state file (e.g. after another process edited it). Playback
+/// keeps running; the reload is refused if it would invalidate the playing
+/// track or drop edits that haven't been saved yet.
+#[tauri::command]
+fn reload_state(state: State<AppState>, app: AppHandle) -> Result<(), String> {
+    let result = {
+        let mut core = state.core.lock().unwrap();
+        let result = core.reload();
+        push_audio_config(&core, &state.audio);
+        result
+    };
+    let _ = app.emit("transport-changed", ());
+    let _ = app.emit("logs-changed", ());
+    result
+}
+
+/// Send the program bus settings kept in the core to the audio thread: at
+/// startup, and whenever the engine is swapped out from under it.
+fn push_audio_config(core: &AppCore, audio: &AudioHandle) {
+    let config = core.get_config();
+    audio.set_buffer_ms(config.audio_buffer_ms);
+    audio.set_output_rate(config.output_sample_rate);
+    audio.set_agc(core.agc_config());
+    audio.set_channel_mode(core.channel_mode());
+}
+
+/// Whether audio is actually flowing (not just "should be playing").
+/// `max_stale_secs` defaults to `ON_AIR_MAX_STALE`.
+#[tauri::command]
+fn on_air_status(state: State<AppState>, max_stale_secs: Option<f64>) -> OnAirData {
+    let max_stale = max_stale_secs
+        .filter(|s| s.is_finite() && *s > 0.0)
+        .map(Duration::from_secs_f64)
+        .unwrap_or(ON_AIR_MAX_STALE);
+    state.core.lock().unwrap().on_air_status(max_stale)
+}
+
 // ── Playlist CRUD ───────────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -86,11 +133,48 @@ fn rename_playlist(
         .rename_playlist(&old_name, new_name)
 }
 
+/// Copy a playlist's tracks into a new playlist; returns the new id.
+#[tauri::command]
+fn duplicate_playlist(
+    state: State<AppState>,
+    name: String,
+    new_name: String,
+) -> Result<u32, String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .duplicate_playlist(&name, new_name)
+}
+
 #[tauri::command]
 fn set_active_playlist(state: State<AppState>, name: String) -> Result<u32, String> {
     state.core.lock().unwrap().set_active_playlist(&name)
 }
 
+/// Lock or unlock a playlist against removing, reordering and shuffling.
+#[tauri::command]
+fn set_playlist_locked(state: State<AppState>, name: String, locked: bool) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_playlist_locked(&name, locked)
+}
+
+#[tauri::command]
+fn set_playlist_auto_advance(
+    state: State<AppState>,
+    name: String,
+    enabled: bool,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_playlist_auto_advance(&name, enabled)
+}
+
 #[tauri::command]
 fn get_playlist_profiles(state: State<AppState>) -> Vec<PlaylistProfileData> {
     state.core.lock().unwrap().get_playlist_profiles()
@@ -111,6 +195,25 @@ fn delete_playlist_profile(state: State<AppState>, name: String) -> Result<(), S
     state.core.lock().unwrap().delete_playlist_profile(&name)
 }
 
+/// Copy named playlists out of another station's state file (read only).
+/// Returns the names they were added under.
+#[tauri::command]
+async fn import_playlists_from_state(
+    state: State<'_, AppState>,
+    path: String,
+    names: Vec<String>,
+) -> Result<Vec<String>, String> {
+    // Phase 1: read the other state file off the lock.
+    let source =
+        tokio::task::spawn_blocking(move || Engine::read_from(std::path::Path::new(&path)))
+            .await
+            .map_err(|e| format!("Import task panicked: {e}"))??;
+
+    // Phase 2: lock briefly to add the copies + save.
+    let mut core = state.core.lock().unwrap();
+    core.import_playlists_from(&source, &names)
+}
+
 #[tauri::command]
 async fn import_m3u_playlist(
     state: State<'_, AppState>,
@@ -120,13 +223,17 @@ async fn import_m3u_playlist(
     use signal_flow::track::Track;
 
     // Phase 1: parse M3U + read all track metadata off the lock.
-    let intros_folder = state.core.lock().unwrap().intros_folder();
+    let (intros_folder, measure) = {
+        let core = state.core.lock().unwrap();
+        (core.intros_folder(), core.measure_unknown_durations())
+    };
     let (name_stem, source_path, track_paths, loaded) =
         tokio::task::spawn_blocking(move || {
             let (stem, src, paths) = signal_flow::app_core::AppCore::parse_m3u_file(&file_path)?;
             let mut tracks = Vec::new();
             for p in &paths {
                 if let Ok(mut t) = Track::from_path(p) {
+                    t.fill_unknown_duration(measure);
                     if let Some(ref folder) = intros_folder {
                         t.has_intro = auto_intro::has_intro(std::path::Path::new(folder), &t.artist);
                     }
@@ -147,6 +254,50 @@ async fn import_m3u_playlist(
         .import_preloaded_m3u(&name_stem, &source_path, loaded)
 }
 
+#[tauri::command]
+fn import_cue(state: State<AppState>, file_path: String) -> Result<String, String> {
+    state.core.lock().unwrap().import_cue(&file_path)
+}
+
+#[tauri::command]
+async fn create_playlist_from_folder(
+    state: State<'_, AppState>,
+    name: String,
+    dir: String,
+    recursive: bool,
+) -> Result<usize, String> {
+    use signal_flow::auto_intro;
+    use signal_flow::track::Track;
+
+    // Phase 1: walk the folder + read all track metadata off the lock.
+    let (intros_folder, measure) = {
+        let core = state.core.lock().unwrap();
+        (core.intros_folder(), core.measure_unknown_durations())
+    };
+    let loaded = tokio::task::spawn_blocking(move || {
+        let files =
+            signal_flow::app_core::collect_audio_files(std::path::Path::new(&dir), recursive)?;
+        let mut tracks = Vec::new();
+        for p in &files {
+            if let Ok(mut t) = Track::from_path(p) {
+                t.fill_unknown_duration(measure);
+                if let Some(ref folder) = intros_folder {
+                    t.has_intro = auto_intro::has_intro(std::path::Path::new(folder), &t.artist);
+                }
+                tracks.push(t);
+            }
+        }
+        Ok::<_, String>(tracks)
+    })
+    .await
+    .map_err(|e| format!("Import task panicked: {e}"))??;
+
+    // Phase 2: lock briefly to create playlist + save.
+    let mut core = state.core.lock().unwrap();
+    core.create_playlist(name.clone())?;
+    core.push_preloaded_tracks(&name, loaded)
+}
+
 #[tauri::command]
 async fn export_playlist_to_m3u(
     state: State<'_, AppState>,
@@ -197,6 +348,216 @@ fn get_playlist_tracks(state: State<AppState>, name: String) -> Result<Vec<Track
     state.core.lock().unwrap().get_playlist_tracks(&name)
 }
 
+#[tauri::command]
+fn get_playlist_stats(state: State<AppState>, name: String) -> Result<PlaylistStats, String> {
+    state.core.lock().unwrap().playlist_stats(&name)
+}
+
+#[tauri::command]
+async fn validate_playlist(
+    state: State<'_, AppState>,
+    name: String,
+    check_decode: bool,
+) -> Result<Vec<TrackIssueData>, String> {
+    let paths = state.core.lock().unwrap().playlist_track_paths(&name)?;
+    tokio::task::spawn_blocking(move || {
+        signal_flow::app_core::validate_track_paths(&paths, check_decode)
+    })
+    .await
+    .map_err(|e| format!("Validation task failed: {}", e))
+}
+
+/// Decode a track end-to-end and store the measured length, which then takes
+/// precedence over the tag duration. Returns the new duration in seconds.
+#[tauri::command]
+async fn verify_track_duration(
+    state: State<'_, AppState>,
+    playlist: String,
+    index: usize,
+) -> Result<f64, String> {
+    let paths = state.core.lock().unwrap().playlist_track_paths(&playlist)?;
+    let count = paths.len();
+    let path = paths
+        .get(index)
+        .cloned()
+        .ok_or_else(|| format!("Track index {} out of range ({} tracks)", index, count))?;
+    let decode_path = path.clone();
+    let measured = tokio::task::spawn_blocking(move || {
+        signal_flow::track::decoded_duration(&decode_path)
+            .ok_or_else(|| format!("Cannot decode '{}'", decode_path.display()))
+    })
+    .await
+    .map_err(|e| format!("Duration task failed: {}", e))??;
+    // Stored only if the playlist still has this file at `index`
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_verified_duration(&playlist, index, &path, measured)?;
+    Ok(measured.as_secs_f64())
+}
+
+/// Set (or clear) when a track's vocals start, for the talk-over countdown.
+#[tauri::command]
+fn set_talk_over(
+    state: State<AppState>,
+    playlist: String,
+    index: usize,
+    secs: Option<f64>,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_talk_over(&playlist, index, secs)
+}
+
+/// Estimate a track's musical key from its audio and store it on the track.
+#[tauri::command]
+async fn analyze_key(
+    state: State<'_, AppState>,
+    playlist: String,
+    index: usize,
+) -> Result<KeyAnalysis, String> {
+    let paths = state.core.lock().unwrap().playlist_track_paths(&playlist)?;
+    let count = paths.len();
+    let path = paths
+        .get(index)
+        .cloned()
+        .ok_or_else(|| format!("Track index {} out of range ({} tracks)", index, count))?;
+    let analyze_path = path.clone();
+    let analysis =
+        tokio::task::spawn_blocking(move || signal_flow::musical_key::estimate_key(&analyze_path))
+            .await
+            .map_err(|e| format!("Key analysis task failed: {}", e))??;
+    // Stored only if the playlist still has this file at `index`
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_track_key(&playlist, index, &path, &analysis.key)?;
+    Ok(analysis)
+}
+
+/// Groups of track indices in a playlist that hold the same audio under
+/// different file names. Decodes every file, without holding the core lock.
+#[tauri::command]
+async fn find_audio_duplicates(
+    state: State<'_, AppState>,
+    playlist: String,
+) -> Result<Vec<Vec<usize>>, String> {
+    let pl = state
+        .core
+        .lock()
+        .unwrap()
+        .engine
+        .find_playlist(&playlist)
+        .cloned()
+        .ok_or_else(|| format!("Playlist '{}' not found", playlist))?;
+    tokio::task::spawn_blocking(move || pl.find_duplicates_by_audio())
+        .await
+        .map_err(|e| format!("Duplicate scan task failed: {}", e))
+}
+
+/// Re-encode tracks to mp3/flac/wav/ogg beside the originals and point the
+/// playlist at the new files. ffmpeg runs without holding the core lock.
+#[tauri::command]
+async fn convert_tracks(
+    state: State<'_, AppState>,
+    playlist: String,
+    indices: Vec<usize>,
+    target_format: String,
+    quality: u8,
+) -> Result<ConvertSummary, String> {
+    use signal_flow::app_core::convert_track_files;
+    use signal_flow::audio_editor::{ConvertFormat, run_ffmpeg};
+
+    let format = ConvertFormat::from_str_loose(&target_format)?;
+    let targets = state
+        .core
+        .lock()
+        .unwrap()
+        .conversion_targets(&playlist, &indices)?;
+    let results = tokio::task::spawn_blocking(move || {
+        convert_track_files(targets, format, quality, run_ffmpeg)
+    })
+    .await
+    .map_err(|e| format!("Conversion task panicked: {e}"))?;
+    state
+        .core
+        .lock()
+        .unwrap()
+        .apply_conversions(&playlist, results)
+}
+
+/// Toggle whether a track starts cleanly (no incoming crossfade overlap).
+#[tauri::command]
+fn set_track_no_crossfade_in(
+    state: State<AppState>,
+    playlist: String,
+    index: usize,
+    enabled: bool,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_track_no_crossfade_in(&playlist, index, enabled)
+}
+
+/// Set a track's gain trim in dB (-24..+12, on top of ReplayGain).
+/// Turn on-air ReplayGain on or off. Turning it on re-reads the tags of
+/// tracks that have no value yet (added before it was read), off the lock.
+#[tauri::command]
+async fn set_replay_gain(state: State<'_, AppState>, enabled: bool) -> Result<usize, String> {
+    let paths = {
+        let mut core = state.core.lock().unwrap();
+        core.set_replay_gain(enabled)?;
+        if !enabled {
+            return Ok(0);
+        }
+        core.replay_gain_refresh_paths()
+    };
+    let gains = tokio::task::spawn_blocking(move || {
+        paths
+            .into_iter()
+            .filter_map(|p| signal_flow::track::read_replay_gain(&p).map(|db| (p, db)))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("ReplayGain task panicked: {e}"))?;
+    state.core.lock().unwrap().apply_replay_gain_tags(&gains)
+}
+
+#[tauri::command]
+fn set_track_gain(
+    state: State<AppState>,
+    playlist: String,
+    index: usize,
+    gain_db: f32,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_track_gain(&playlist, index, gain_db)
+}
+
+/// Flag a track as advisory (explicit); it is skipped during clean hours.
+#[tauri::command]
+fn set_track_advisory(
+    state: State<AppState>,
+    playlist: String,
+    index: usize,
+    advisory: bool,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_track_advisory(&playlist, index, advisory)
+}
+
 #[tauri::command]
 fn add_track(state: State<AppState>, playlist: String, path: String) -> Result<usize, String> {
     state.core.lock().unwrap().add_track(&playlist, &path)
@@ -212,12 +573,12 @@ async fn add_tracks(
     use signal_flow::track::Track;
 
     // Phase 1: extract intros_folder while briefly holding the lock, then drop it.
-    let intros_folder = {
+    let (intros_folder, measure) = {
         let core = state.core.lock().unwrap();
         if core.engine.find_playlist(&playlist).is_none() {
             return Err(format!("Playlist '{}' not found", playlist));
         }
-        core.intros_folder()
+        (core.intros_folder(), core.measure_unknown_durations())
     };
 
     // Phase 2: read all file metadata on a blocking thread (no lock held).
@@ -226,6 +587,7 @@ async fn add_tracks(
         for path_str in &paths {
             match Track::from_path(std::path::Path::new(path_str)) {
                 Ok(mut t) => {
+                    t.fill_unknown_duration(measure);
                     if let Some(ref folder) = intros_folder {
                         t.has_intro = auto_intro::has_intro(std::path::Path::new(folder), &t.artist);
                     }
@@ -270,6 +632,23 @@ fn reorder_track(
         .reorder_track(&playlist, from, to)
 }
 
+/// Shuffle with artist/lecture spacing. Returns the number of unmet gaps.
+#[tauri::command]
+fn shuffle_playlist(
+    state: State<AppState>,
+    app: AppHandle,
+    playlist: String,
+    min_artist_gap: usize,
+    seed: Option<u64>,
+) -> Result<usize, String> {
+    let violations = {
+        let mut core = state.core.lock().unwrap();
+        core.shuffle_playlist(&playlist, min_artist_gap, seed)?
+    };
+    let _ = app.emit("logs-changed", ());
+    Ok(violations)
+}
+
 #[tauri::command]
 fn copy_paste_tracks(
     state: State<AppState>,
@@ -283,6 +662,21 @@ fn copy_paste_tracks(
     core.paste_tracks(&to_playlist, tracks, at)
 }
 
+#[tauri::command]
+fn move_tracks(
+    state: State<AppState>,
+    from_playlist: String,
+    indices: Vec<usize>,
+    to_playlist: String,
+    at: Option<usize>,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .move_tracks(&from_playlist, &indices, &to_playlist, at)
+}
+
 #[tauri::command]
 fn edit_track_metadata(
     state: State<AppState>,
@@ -299,6 +693,27 @@ fn edit_track_metadata(
     )
 }
 
+/// Re-tag several tracks; `title_pattern` may use `{n}` for numbering.
+/// Returns the tracks that could not be updated.
+#[tauri::command]
+fn edit_tracks_metadata(
+    state: State<AppState>,
+    app: AppHandle,
+    playlist: String,
+    indices: Vec<usize>,
+    artist: Option<String>,
+    title_pattern: Option<String>,
+) -> Result<Vec<TrackIssueData>, String> {
+    let failures = state.core.lock().unwrap().edit_tracks_metadata(
+        &playlist,
+        &indices,
+        artist.as_deref(),
+        title_pattern.as_deref(),
+    )?;
+    let _ = app.emit("logs-changed", ());
+    Ok(failures)
+}
+
 // ── File browser / search ───────────────────────────────────────────────
 
 #[tauri::command]
@@ -338,6 +753,11 @@ async fn search_indexed_files(
         .map_err(|e| format!("Search task panicked: {e}"))?
 }
 
+#[tauri::command]
+fn search_playlists(state: State<AppState>, query: String) -> Vec<PlaylistSearchResult> {
+    state.core.lock().unwrap().search_playlists(&query)
+}
+
 // ── Transport controls ─────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -346,17 +766,16 @@ fn transport_play(
     app: AppHandle,
     track_index: Option<usize>,
 ) -> Result<(), String> {
-    // Lock core: prepare play state (updates engine, playback, logs)
-    let (track_path, ..) = {
+    // Updates core state, then hands the file to the audio thread to decode
+    let event = {
         let mut core = state.core.lock().unwrap();
-        core.prepare_play(track_index)?
-    }; // core lock dropped
-
-    // Send play command to audio thread (file decode happens there)
-    state.audio.play(track_path, state.level_monitor.clone());
+        facade::play(&mut core, &state.audio, &state.level_monitor, track_index)?;
+        core.transport_event(TransportEventKind::Play)
+    };
 
     // Emit events so frontend updates immediately
-    let _ = app.emit("transport-changed", ());
+    emit_transport(&app, event);
+    play_released_events(&app);
     let _ = app.emit("logs-changed", ());
 
     Ok(())
@@ -364,18 +783,15 @@ fn transport_play(
 
 #[tauri::command]
 fn transport_stop(state: State<AppState>, app: AppHandle) -> Result<(), String> {
-    // Mark core as stopped BEFORE sending the Stop command to the audio thread.
-    // This ensures any concurrent TrackFinished callback that acquires the core
-    // lock after us sees is_playing=false and aborts the auto-advance.
-    {
+    let event = {
         let mut core = state.core.lock().unwrap();
-        core.on_stop();
-    }
-
-    state.audio.stop();
-    state.level_monitor.reset();
+        let event = core.transport_event(TransportEventKind::Stop);
+        facade::stop(&mut core, &state.audio, &state.level_monitor);
+        event
+    };
 
-    let _ = app.emit("transport-changed", ());
+    emit_transport(&app, event);
+    play_released_events(&app);
     let _ = app.emit("logs-changed", ());
 
     Ok(())
@@ -383,50 +799,100 @@ fn transport_stop(state: State<AppState>, app: AppHandle) -> Result<(), String>
 
 #[tauri::command]
 fn transport_pause(state: State<AppState>, app: AppHandle) -> Result<(), String> {
-    // Toggle pause state in core
-    let now_paused = {
+    let event = {
         let mut core = state.core.lock().unwrap();
-        core.on_pause_toggle()?
+        let paused = facade::pause_toggle(&mut core, &state.audio)?;
+        core.transport_event(if paused {
+            TransportEventKind::Pause
+        } else {
+            TransportEventKind::Resume
+        })
     };
 
-    // Send to audio thread
-    if now_paused {
-        state.audio.pause();
-    } else {
-        state.audio.resume();
-    }
-
-    let _ = app.emit("transport-changed", ());
+    emit_transport(&app, event);
+    play_released_events(&app);
     let _ = app.emit("logs-changed", ());
 
     Ok(())
 }
 
-#[tauri::command]
-fn transport_skip(state: State<AppState>, app: AppHandle) -> Result<(), String> {
-    // Stop current playback
-    state.audio.stop();
-
-    // Advance to next track in core
-    let skip_result = {
-        let mut core = state.core.lock().unwrap();
-        core.prepare_skip()
+/// Play schedule events held during a pause once it has ended (stop,
+/// resume, play, skip or a finished track). Nothing is released while the
+/// transport is still paused.
+fn play_released_events(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
     };
+    let released = state.core.lock().unwrap().take_released_events();
+    for action in released {
+        play_schedule_action(app, &state, action);
+    }
+}
 
-    let (track_path, ..) = match skip_result {
-        Ok(data) => data,
-        Err(ref e) if e == "__end_of_playlist__" => {
-            let _ = app.emit("transport-changed", ());
-            let _ = app.emit("logs-changed", ());
-            return Ok(());
+/// Carry out the audio half of a fired schedule action: inserts are queued
+/// in the active playlist, stop events end the transport and play on the
+/// program bus at unity, overlays mix over it at the overlay gain.
+fn play_schedule_action(app: &AppHandle, state: &AppState, action: ScheduleAction) {
+    let ScheduleAction::Play {
+        mode,
+        file,
+        position,
+    } = action
+    else {
+        return;
+    };
+    let overlay_gain = {
+        let mut core = state.core.lock().unwrap();
+        match mode {
+            ScheduleMode::Insert => {
+                let _ = core.insert_scheduled_file(&file, position);
+                drop(core);
+                let _ = app.emit("transport-changed", ());
+                let _ = app.emit("logs-changed", ());
+                return;
+            }
+            ScheduleMode::Stop if core.playback.is_playing => {
+                let event = core.transport_event(TransportEventKind::Stop);
+                facade::stop(&mut core, &state.audio, &state.level_monitor);
+                emit_transport(app, event);
+            }
+            ScheduleMode::Activate => return,
+            _ => {}
         }
-        Err(e) => return Err(e),
+        core.get_config().overlay_gain
+    };
+    let gain = match mode {
+        ScheduleMode::Overlay => overlay_gain,
+        _ => 1.0,
     };
+    state.audio.play_over(file, gain);
+}
 
-    // Play next track on audio thread
-    state.audio.play(track_path, state.level_monitor.clone());
+#[tauri::command]
+fn transport_skip(state: State<AppState>, app: AppHandle) -> Result<(), String> {
+    let event = {
+        let mut core = state.core.lock().unwrap();
+        facade::skip(&mut core, &state.audio, &state.level_monitor)?;
+        core.transport_event(TransportEventKind::Skip)
+    };
 
-    let _ = app.emit("transport-changed", ());
+    emit_transport(&app, event);
+    play_released_events(&app);
+    let _ = app.emit("logs-changed", ());
+
+    Ok(())
+}
+
+#[tauri::command]
+fn transport_prev(state: State<AppState>, app: AppHandle) -> Result<(), String> {
+    let event = {
+        let mut core = state.core.lock().unwrap();
+        facade::prev(&mut core, &state.audio, &state.level_monitor)?;
+        core.transport_event(TransportEventKind::Prev)
+    };
+
+    emit_transport(&app, event);
+    play_released_events(&app);
     let _ = app.emit("logs-changed", ());
 
     Ok(())
@@ -438,21 +904,67 @@ fn transport_seek(
     app: AppHandle,
     position_secs: f64,
 ) -> Result<(), String> {
-    // Update timing in core
-    {
+    // Seeking to the very end behaves like the track finishing
+    let (outcome, event) = {
         let mut core = state.core.lock().unwrap();
-        core.on_seek(position_secs)?;
+        let outcome = facade::seek(&mut core, &state.audio, &state.level_monitor, position_secs)?;
+        (outcome, core.transport_event(TransportEventKind::Seek))
+    };
+
+    emit_transport(&app, event);
+    if outcome == SeekOutcome::EndOfTrack {
+        let _ = app.emit("logs-changed", ());
     }
 
-    // Seek on audio thread
-    let seek_pos = Duration::from_secs_f64(position_secs.max(0.0));
-    state.audio.seek(seek_pos);
+    Ok(())
+}
+
+/// Send a typed `transport-event` along with the bare `transport-changed`
+/// signal, which stays for listeners that still re-poll the transport.
+fn emit_transport(app: &AppHandle, event: TransportEvent) {
+    let _ = app.emit("transport-event", event);
+    let _ = app.emit("transport-changed", ());
+}
+
+/// Restart the track saved in the resume marker at its saved offset
+/// (e.g. after a reboot mid-program).
+#[tauri::command]
+fn resume_playback(state: State<AppState>, app: AppHandle) -> Result<(), String> {
+    {
+        let mut core = state.core.lock().unwrap();
+        facade::resume(&mut core, &state.audio, &state.level_monitor)?;
+    }
 
     let _ = app.emit("transport-changed", ());
+    play_released_events(&app);
+    let _ = app.emit("logs-changed", ());
 
     Ok(())
 }
 
+#[tauri::command]
+fn get_resume_marker(state: State<AppState>) -> Option<ResumeMarker> {
+    state.core.lock().unwrap().resume_marker()
+}
+
+#[tauri::command]
+fn set_resume_save_interval(state: State<AppState>, secs: f32) -> Result<(), String> {
+    state.core.lock().unwrap().set_resume_save_interval(secs)
+}
+
+#[tauri::command]
+fn set_save_debounce(
+    state: State<AppState>,
+    ms: u64,
+    config_immediately: bool,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_save_debounce(ms, config_immediately)
+}
+
 #[tauri::command]
 fn transport_status(state: State<AppState>) -> TransportData {
     // Simplified: only locks core, no player check needed.
@@ -476,6 +988,41 @@ async fn get_waveform(path: String) -> Result<Vec<f32>, String> {
         .map_err(|e| format!("Waveform task failed: {}", e))?
 }
 
+#[tauri::command]
+async fn get_playlist_waveforms(
+    state: State<'_, AppState>,
+    name: String,
+    peaks: Option<usize>,
+) -> Result<Vec<(usize, Vec<f32>)>, String> {
+    let paths = state.core.lock().unwrap().playlist_track_paths(&name)?;
+    let peaks = peaks.unwrap_or(32);
+    tokio::task::spawn_blocking(move || {
+        signal_flow::waveform::mini_peaks_for_paths(&paths, peaks)
+    })
+    .await
+    .map_err(|e| format!("Waveform task failed: {}", e))
+}
+
+#[tauri::command]
+async fn precompute_waveforms(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<signal_flow::waveform::PrecomputeSummary, String> {
+    let paths = state.core.lock().unwrap().playlist_track_paths(&name)?;
+    tokio::task::spawn_blocking(move || {
+        signal_flow::waveform::precompute_peaks(
+            &paths,
+            signal_flow::waveform::PRECOMPUTE_WORKERS,
+            |progress| {
+                let _ = app.emit("waveform-precompute-progress", progress);
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("Waveform task failed: {}", e))
+}
+
 // ── Schedule ────────────────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -510,6 +1057,107 @@ fn toggle_schedule_event(state: State<AppState>, id: u32) -> Result<bool, String
     state.core.lock().unwrap().toggle_schedule_event(id)
 }
 
+#[tauri::command]
+fn set_schedule_event_rotator(
+    state: State<AppState>,
+    id: u32,
+    rotator: bool,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_schedule_event_rotator(id, rotator)
+}
+
+#[tauri::command]
+fn set_schedule_event_autostart(
+    state: State<AppState>,
+    id: u32,
+    autostart: bool,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_schedule_event_autostart(id, autostart)
+}
+
+/// Where an insert event's file goes: "next", "end" or a 0-based index.
+#[tauri::command]
+fn set_schedule_event_insert_position(
+    state: State<AppState>,
+    id: u32,
+    position: String,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_schedule_event_insert_position(id, &position)
+}
+
+/// File played when the event's own file is missing (None = clear).
+#[tauri::command]
+fn set_schedule_event_fallback(
+    state: State<AppState>,
+    id: u32,
+    fallback: Option<String>,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_schedule_event_fallback(id, fallback.as_deref())
+}
+
+/// Limit an event to a date range (YYYY-MM-DD; None = open-ended).
+#[tauri::command]
+fn set_schedule_event_dates(
+    state: State<AppState>,
+    id: u32,
+    start_date: Option<String>,
+    end_date: Option<String>,
+) -> Result<(), String> {
+    state.core.lock().unwrap().set_schedule_event_dates(
+        id,
+        start_date.as_deref(),
+        end_date.as_deref(),
+    )
+}
+
+/// Fire a schedule event now. `activate` events switch the active playlist
+/// (and start it when autostart is set); audio events are played here (see
+/// `play_schedule_action`). `held` means it waits for the pause to end and
+/// is played once it does.
+#[tauri::command]
+fn fire_schedule_event(
+    state: State<AppState>,
+    app: AppHandle,
+    id: u32,
+) -> Result<ScheduleAction, String> {
+    let action = {
+        let mut core = state.core.lock().unwrap();
+        facade::fire_schedule_event(&mut core, &state.audio, &state.level_monitor, id)
+    };
+    let _ = app.emit("transport-changed", ());
+    let _ = app.emit("logs-changed", ());
+    let action = action?;
+    match action {
+        ScheduleAction::Activate {
+            autostart: true, ..
+        } => transport_play(state, app, None)?,
+        ScheduleAction::Play { .. } => play_schedule_action(&app, &state, action.clone()),
+        _ => {}
+    }
+    Ok(action)
+}
+
+#[tauri::command]
+fn get_schedule_conflicts(state: State<AppState>) -> Vec<(u32, u32)> {
+    state.core.lock().unwrap().get_schedule_conflicts()
+}
+
 // ── Config ──────────────────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -533,22 +1181,140 @@ fn set_output_device(state: State<AppState>, name: Option<String>) -> Result<(),
     Ok(())
 }
 
+/// Route the editor (monitor bus) to its own output device, e.g. headphones,
+/// independently of the program device.
+#[tauri::command]
+fn set_monitor_device(state: State<AppState>, name: Option<String>) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_monitor_device(name.clone())?;
+    state.editor_audio.set_device(name);
+    Ok(())
+}
+
+/// Resample program output to a fixed rate (None = each file's own rate).
+#[tauri::command]
+fn set_output_sample_rate(state: State<AppState>, rate: Option<u32>) -> Result<(), String> {
+    state.core.lock().unwrap().set_output_sample_rate(rate)?;
+    state.audio.set_output_rate(rate);
+    Ok(())
+}
+
+/// Program output device buffer in ms (0 = driver default). Applies when
+/// the output next opens; the position then trails by the buffer opened.
+#[tauri::command]
+fn set_audio_buffer_ms(state: State<AppState>, ms: u32) -> Result<(), String> {
+    state.core.lock().unwrap().set_audio_buffer_ms(ms)?;
+    state.audio.set_buffer_ms(ms);
+    Ok(())
+}
+
+/// Automatic gain control on the program bus; applies from the next track.
+#[tauri::command]
+fn set_agc(
+    state: State<AppState>,
+    enabled: bool,
+    target_db: f32,
+    attack_ms: f32,
+    release_ms: f32,
+    max_gain_db: f32,
+) -> Result<(), String> {
+    let config = {
+        let mut core = state.core.lock().unwrap();
+        core.set_agc(enabled, target_db, attack_ms, release_ms, max_gain_db)?;
+        core.agc_config()
+    };
+    state.audio.set_agc(config);
+    Ok(())
+}
+
+/// Output channel mapping (stereo, mono sum, L/R swap); applies immediately.
+#[tauri::command]
+fn set_channel_mode(state: State<AppState>, mode: String) -> Result<(), String> {
+    let mode = state.core.lock().unwrap().set_channel_mode(&mode)?;
+    state.audio.set_channel_mode(mode);
+    Ok(())
+}
+
+/// Hours (0-23) during which advisory tracks are skipped.
+#[tauri::command]
+fn set_clean_hours(state: State<AppState>, hours: Vec<u8>) -> Result<(), String> {
+    state.core.lock().unwrap().set_clean_hours(hours)
+}
+
+#[tauri::command]
+fn set_avoid_artist_repeat(state: State<AppState>, enabled: bool) -> Result<(), String> {
+    state.core.lock().unwrap().set_avoid_artist_repeat(enabled)
+}
+
+#[tauri::command]
+fn set_measure_unknown_durations(state: State<AppState>, enabled: bool) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_measure_unknown_durations(enabled)
+}
+
 #[tauri::command]
 fn set_crossfade(state: State<AppState>, secs: f32) -> Result<(), String> {
     state.core.lock().unwrap().set_crossfade(secs)
 }
 
+#[tauri::command]
+fn set_signon_fade(state: State<AppState>, secs: f32) -> Result<(), String> {
+    state.core.lock().unwrap().set_signon_fade(secs)
+}
+
+#[tauri::command]
+fn set_crossfade_lead(state: State<AppState>, secs: f32) -> Result<(), String> {
+    state.core.lock().unwrap().set_crossfade_lead(secs)
+}
+
+#[tauri::command]
+fn set_min_crossfade_track(state: State<AppState>, secs: f32) -> Result<(), String> {
+    state.core.lock().unwrap().set_min_crossfade_track(secs)
+}
+
+#[tauri::command]
+fn set_crossfade_on_skip(state: State<AppState>, enabled: bool) -> Result<(), String> {
+    state.core.lock().unwrap().set_crossfade_on_skip(enabled)
+}
+
+#[tauri::command]
+fn set_live_assist_hotkeys(state: State<AppState>, enabled: bool) -> Result<(), String> {
+    state.core.lock().unwrap().set_live_assist_hotkeys(enabled)
+}
+
+#[tauri::command]
+fn set_overlay_gain(state: State<AppState>, gain: f32) -> Result<(), String> {
+    state.core.lock().unwrap().set_overlay_gain(gain)
+}
+
 #[tauri::command]
 fn set_silence_detection(
     state: State<AppState>,
     threshold: f32,
     duration_secs: f32,
+    unit: Option<String>,
 ) -> Result<(), String> {
+    let unit = unit.unwrap_or_else(|| "linear".to_string());
     state
         .core
         .lock()
         .unwrap()
-        .set_silence_detection(threshold, duration_secs)
+        .set_silence_detection_in(threshold, &unit, duration_secs)
+}
+
+#[tauri::command]
+fn set_dead_air_alarm(state: State<AppState>, threshold_db: f32, secs: f32) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_dead_air_alarm(threshold_db, secs)
 }
 
 #[tauri::command]
@@ -569,11 +1335,62 @@ fn set_recurring_intro(
         .set_recurring_intro(interval_secs, duck_volume)
 }
 
+#[tauri::command]
+fn set_recurring_intro_duck_ramp(state: State<AppState>, secs: f32) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_recurring_intro_duck_ramp(secs)
+}
+
+#[tauri::command]
+fn set_intro_mode(state: State<AppState>, mode: String) -> Result<(), String> {
+    state.core.lock().unwrap().set_intro_mode(&mode)
+}
+
+#[tauri::command]
+fn set_intro_once_per_session(state: State<AppState>, enabled: bool) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_intro_once_per_session(enabled)
+}
+
+#[tauri::command]
+fn set_intro_gain(state: State<AppState>, gain_db: f32) -> Result<(), String> {
+    state.core.lock().unwrap().set_intro_gain(gain_db)
+}
+
 #[tauri::command]
 fn set_conflict_policy(state: State<AppState>, policy: String) -> Result<(), String> {
     state.core.lock().unwrap().set_conflict_policy(&policy)
 }
 
+/// What overlay and stop events do during a pause: "queue" or "play-over".
+#[tauri::command]
+fn set_paused_event_handling(state: State<AppState>, handling: String) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_paused_event_handling(&handling)
+}
+
+#[tauri::command]
+fn set_end_of_playlist_action(
+    state: State<AppState>,
+    action: String,
+    fallback: Option<String>,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_end_of_playlist_action(&action, fallback.as_deref())
+}
+
 #[tauri::command]
 fn set_stream_output(
     state: State<AppState>,
@@ -615,6 +1432,31 @@ fn set_nowplaying_path(state: State<AppState>, path: Option<String>) -> Result<(
     state.core.lock().unwrap().set_nowplaying_path(path)
 }
 
+#[tauri::command]
+fn set_now_playing_debounce(state: State<AppState>, ms: u64) -> Result<(), String> {
+    state.core.lock().unwrap().set_now_playing_debounce(ms)
+}
+
+#[tauri::command]
+fn set_now_playing_ad_placeholder(state: State<AppState>, text: String) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_now_playing_ad_placeholder(&text)
+}
+
+#[tauri::command]
+fn set_heartbeat_path(state: State<AppState>, path: Option<String>) -> Result<(), String> {
+    state.core.lock().unwrap().set_heartbeat_path(path)
+}
+
+/// Set (or clear) the file aired on loop when there is nothing else to play.
+#[tauri::command]
+fn set_emergency_file(state: State<AppState>, path: Option<String>) -> Result<(), String> {
+    state.core.lock().unwrap().set_emergency_file(path)
+}
+
 // ── Ads ─────────────────────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -623,8 +1465,145 @@ fn get_ads(state: State<AppState>) -> Vec<AdData> {
 }
 
 #[tauri::command]
-fn add_ad(state: State<AppState>, name: String, mp3_file: String) -> Result<usize, String> {
-    state.core.lock().unwrap().add_ad(name, mp3_file)
+async fn add_ad(
+    state: State<'_, AppState>,
+    name: String,
+    mp3_file: String,
+) -> Result<usize, String> {
+    let (index, target) = {
+        let mut core = state.core.lock().unwrap();
+        let index = core.add_ad_deferred(name, mp3_file.clone())?;
+        (index, core.ad_normalize_target())
+    };
+    if let Some(target) = target {
+        normalize_ad_off_lock(&state, index, PathBuf::from(mp3_file), target).await?;
+    }
+    Ok(index)
+}
+
+/// Make the loudness-normalized copy of an ad's file (two ffmpeg passes)
+/// without holding the core lock, then record it on the ad.
+async fn normalize_ad_off_lock(
+    state: &State<'_, AppState>,
+    index: usize,
+    original: PathBuf,
+    target_lufs: f32,
+) -> Result<(), String> {
+    let source = original.clone();
+    let outcome = tokio::task::spawn_blocking(move || {
+        signal_flow::ad_scheduler::normalize_ad_file(&source, target_lufs)
+    })
+    .await
+    .map_err(|e| format!("Normalization task panicked: {e}"))?;
+    state
+        .core
+        .lock()
+        .unwrap()
+        .apply_ad_normalization(index, &original, outcome)
+}
+
+#[tauri::command]
+fn set_ad_normalization(
+    state: State<AppState>,
+    enabled: bool,
+    target_lufs: f32,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_ad_normalization(enabled, target_lufs)
+}
+
+#[tauri::command]
+fn set_ad_repeat_gap(state: State<AppState>, unit: String, value: u32) -> Result<(), String> {
+    state.core.lock().unwrap().set_ad_repeat_gap(&unit, value)
+}
+
+#[tauri::command]
+fn set_station_id_position(state: State<AppState>, position: String) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_station_id_position(&position)
+}
+
+#[tauri::command]
+fn set_ad_insert_timing(state: State<AppState>, timing: String) -> Result<(), String> {
+    state.core.lock().unwrap().set_ad_insert_timing(&timing)
+}
+
+/// Queue an ad break now; with the "immediate" insert timing the playing
+/// track is cut so the roll starts at once. Returns each ad's length and
+/// the length of the whole break.
+#[tauri::command]
+async fn insert_ad_break(
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<AdBreakData, String> {
+    // Phase 1: measure the candidate ads off the lock.
+    let files = state.core.lock().unwrap().ad_break_files();
+    let lengths = tokio::task::spawn_blocking(move || RollLengths::measure(&files))
+        .await
+        .map_err(|e| format!("Ad break task panicked: {e}"))?;
+
+    // Phase 2: lock briefly to queue the roll.
+    let (queued, event) = {
+        let mut core = state.core.lock().unwrap();
+        let was_playing = core.playback.is_playing;
+        let result =
+            facade::insert_ad_break(&mut core, &state.audio, &state.level_monitor, &lengths)?;
+        let event = (result.interrupt_current && was_playing)
+            .then(|| core.transport_event(TransportEventKind::Skip));
+        (AdBreakData::from_result(&result), event)
+    };
+
+    match event {
+        Some(event) => emit_transport(&app, event),
+        None => {
+            let _ = app.emit("transport-changed", ());
+        }
+    }
+    let _ = app.emit("logs-changed", ());
+
+    Ok(queued)
+}
+
+/// Dry run: which ads (and station ID) a break would play right now.
+#[tauri::command]
+async fn preview_ad_break(state: State<'_, AppState>) -> Result<AdPreviewData, String> {
+    // Phase 1: measure the candidate ads off the lock.
+    let files = state.core.lock().unwrap().ad_break_files();
+    let lengths = tokio::task::spawn_blocking(move || RollLengths::measure(&files))
+        .await
+        .map_err(|e| format!("Ad preview task panicked: {e}"))?;
+
+    // Phase 2: lock briefly to select the roll.
+    state.core.lock().unwrap().preview_ad_break(&lengths)
+}
+
+#[tauri::command]
+fn set_ad_event_guard(state: State<AppState>, secs: u32) -> Result<(), String> {
+    state.core.lock().unwrap().set_ad_event_guard(secs)
+}
+
+#[tauri::command]
+fn set_ad_roll_limit(
+    state: State<AppState>,
+    max_roll_secs: u32,
+    fit_best: bool,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_ad_roll_limit(max_roll_secs, fit_best)
+}
+
+#[tauri::command]
+fn set_ad_failure_retention(state: State<AppState>, max: usize) -> Result<(), String> {
+    state.core.lock().unwrap().set_ad_failure_retention(max)
 }
 
 #[tauri::command]
@@ -638,8 +1617,8 @@ fn toggle_ad(state: State<AppState>, index: usize) -> Result<bool, String> {
 }
 
 #[tauri::command]
-fn update_ad(
-    state: State<AppState>,
+async fn update_ad(
+    state: State<'_, AppState>,
     index: usize,
     name: String,
     enabled: bool,
@@ -647,17 +1626,82 @@ fn update_ad(
     scheduled: bool,
     days: Vec<String>,
     hours: Vec<u8>,
+) -> Result<(), String> {
+    let path = PathBuf::from(&mp3_file);
+    let target = {
+        let mut core = state.core.lock().unwrap();
+        let file_changed =
+            core.update_ad_deferred(index, name, enabled, mp3_file, scheduled, days, hours)?;
+        core.ad_normalize_target().filter(|_| file_changed)
+    };
+    if let Some(target) = target {
+        normalize_ad_off_lock(&state, index, path, target).await?;
+    }
+    Ok(())
+}
+
+/// Limit an ad to campaign flight dates (YYYY-MM-DD; None = open-ended).
+#[tauri::command]
+fn set_ad_flight(
+    state: State<AppState>,
+    index: usize,
+    start_date: Option<String>,
+    end_date: Option<String>,
 ) -> Result<(), String> {
     state
         .core
         .lock()
         .unwrap()
-        .update_ad(index, name, enabled, mp3_file, scheduled, days, hours)
+        .set_ad_flight(index, start_date.as_deref(), end_date.as_deref())
+}
+
+#[tauri::command]
+fn reorder_ad(state: State<AppState>, from: usize, to: usize) -> Result<(), String> {
+    state.core.lock().unwrap().reorder_ad(from, to)
+}
+
+// ── Carts ───────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+fn get_carts(state: State<AppState>) -> Vec<CartData> {
+    state.core.lock().unwrap().get_carts()
+}
+
+#[tauri::command]
+fn add_cart(
+    state: State<AppState>,
+    label: String,
+    path: String,
+    overlay: bool,
+) -> Result<usize, String> {
+    state.core.lock().unwrap().add_cart(label, path, overlay)
 }
 
 #[tauri::command]
-fn reorder_ad(state: State<AppState>, from: usize, to: usize) -> Result<(), String> {
-    state.core.lock().unwrap().reorder_ad(from, to)
+fn remove_cart(state: State<AppState>, index: usize) -> Result<(), String> {
+    state.core.lock().unwrap().remove_cart(index)
+}
+
+/// Fire cart `index` (1-based). Carts play on the program bus: overlay carts
+/// mix over the running transport at the overlay gain; hard-break carts stop
+/// the transport first and play at unity.
+#[tauri::command]
+fn play_cart(state: State<AppState>, app: AppHandle, index: usize) -> Result<(), String> {
+    let (cart, overlay_gain) = {
+        let mut core = state.core.lock().unwrap();
+        let cart = core.cart_to_play(index)?;
+        (cart, core.get_config().overlay_gain)
+    };
+    let audio = state.audio.clone();
+    let gain = if cart.overlay {
+        overlay_gain
+    } else {
+        transport_stop(state, app.clone())?;
+        1.0
+    };
+    audio.play_over(cart.path, gain);
+    let _ = app.emit("logs-changed", ());
+    Ok(())
 }
 
 // ── Ad Statistics & Reports ──────────────────────────────────────────────────
@@ -687,6 +1731,38 @@ fn get_ad_daily_counts(state: State<AppState>, ad_name: String) -> Vec<AdDailyCo
         .collect()
 }
 
+#[tauri::command]
+fn get_ad_daily_counts_filled(
+    state: State<AppState>,
+    ad_name: String,
+    start: String,
+    end: String,
+) -> Result<Vec<AdDailyCountResponse>, String> {
+    let counts = state
+        .core
+        .lock()
+        .unwrap()
+        .get_ad_daily_counts_filled(&ad_name, &start, &end)?;
+    Ok(counts
+        .into_iter()
+        .map(|(date, count)| AdDailyCountResponse { date, count })
+        .collect())
+}
+
+#[tauri::command]
+fn get_ad_hour_histogram(
+    state: State<AppState>,
+    ad_name: String,
+    start: String,
+    end: String,
+) -> [usize; 24] {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .get_ad_hour_histogram(&ad_name, &start, &end)
+}
+
 #[tauri::command]
 fn get_ad_failures(state: State<AppState>) -> Vec<AdFailureResponse> {
     state
@@ -703,8 +1779,22 @@ fn get_ad_failures(state: State<AppState>) -> Vec<AdFailureResponse> {
         .collect()
 }
 
+#[tauri::command]
+fn set_report_branding(
+    state: State<AppState>,
+    logo_path: Option<String>,
+    header_rgb: Option<[u8; 3]>,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_report_branding(logo_path, header_rgb)
+}
+
 #[tauri::command]
 async fn generate_ad_report(
+    state: State<'_, AppState>,
     start: String,
     end: String,
     output_dir: String,
@@ -713,12 +1803,13 @@ async fn generate_ad_report(
 ) -> Result<Vec<String>, String> {
     // Report generation reads/writes files; run on blocking thread pool so we
     // don't stall the async runtime or hold the core mutex.
+    let branding = state.core.lock().unwrap().engine.report_branding.clone();
     tokio::task::spawn_blocking(move || {
         use signal_flow::ad_logger::AdPlayLogger;
         use signal_flow::ad_report::AdReportGenerator;
         use std::path::Path;
         let logger = AdPlayLogger::new(Path::new("."));
-        let reporter = AdReportGenerator::new(&logger);
+        let reporter = AdReportGenerator::new(&logger).with_branding(branding);
         let out_path = Path::new(&output_dir);
         if !out_path.is_dir() {
             return Err(format!("'{}' is not a valid directory", output_dir));
@@ -744,8 +1835,97 @@ async fn generate_ad_report(
     .map_err(|e| format!("Report task panicked: {e}"))?
 }
 
+#[tauri::command]
+fn get_ad_report_audit(state: State<AppState>) -> Vec<signal_flow::ad_report::ReportAuditEntry> {
+    state.core.lock().unwrap().get_ad_report_audit()
+}
+
+#[tauri::command]
+fn get_ad_delivery_report(
+    state: State<AppState>,
+    ad_name: String,
+    start: String,
+    end: String,
+) -> Result<signal_flow::ad_report::DeliveryReport, String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .get_ad_delivery_report(&ad_name, &start, &end)
+}
+
+#[tauri::command]
+async fn generate_ad_rollup(
+    state: State<'_, AppState>,
+    start: String,
+    end: String,
+    granularity: String,
+    output_dir: String,
+) -> Result<Vec<String>, String> {
+    let (logger, branding) = {
+        let core = state.core.lock().unwrap();
+        (core.ad_logger(), core.engine.report_branding.clone())
+    };
+    tokio::task::spawn_blocking(move || {
+        AppCore::write_ad_rollup(&logger, branding, &start, &end, &granularity, &output_dir)
+    })
+    .await
+    .map_err(|e| format!("Report task panicked: {e}"))?
+}
+
 // ── RDS ─────────────────────────────────────────────────────────────────────
 
+#[tauri::command]
+async fn export_config_bundle(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    path: String,
+    include_audio: Option<bool>,
+) -> Result<signal_flow::config_bundle::BundleManifest, String> {
+    let snapshot = state.core.lock().unwrap().config_bundle_snapshot()?;
+    let out = path.clone();
+    let manifest = tokio::task::spawn_blocking(move || {
+        signal_flow::config_bundle::write_bundle(
+            &snapshot,
+            std::path::Path::new("."),
+            std::path::Path::new(&out),
+            include_audio.unwrap_or(false),
+        )
+    })
+    .await
+    .map_err(|e| format!("Export task panicked: {e}"))??;
+    state
+        .core
+        .lock()
+        .unwrap()
+        .log_bundle_export(&path, &manifest);
+    let _ = app.emit("logs-changed", ());
+    Ok(manifest)
+}
+
+#[tauri::command]
+async fn import_config_bundle(
+    state: State<'_, AppState>,
+    app: AppHandle,
+    path: String,
+    merge: bool,
+) -> Result<signal_flow::config_bundle::ImportSummary, String> {
+    let bundle = path.clone();
+    let incoming = tokio::task::spawn_blocking(move || {
+        signal_flow::config_bundle::read_bundle(std::path::Path::new(&bundle))
+    })
+    .await
+    .map_err(|e| format!("Import task panicked: {e}"))??;
+    let summary = {
+        let mut core = state.core.lock().unwrap();
+        let summary = core.apply_config_bundle(&path, incoming, merge)?;
+        push_audio_config(&core, &state.audio);
+        summary
+    };
+    let _ = app.emit("logs-changed", ());
+    Ok(summary)
+}
+
 #[tauri::command]
 fn get_rds_config(state: State<AppState>) -> RdsConfigData {
     state.core.lock().unwrap().get_rds_config()
@@ -803,6 +1983,19 @@ fn update_rds_settings(
         .update_rds_settings(ip, port, default_message)
 }
 
+#[tauri::command]
+fn set_rds_formatting(
+    state: State<AppState>,
+    segmented_ps: bool,
+    radio_text: bool,
+) -> Result<(), String> {
+    state
+        .core
+        .lock()
+        .unwrap()
+        .set_rds_formatting(segmented_ps, radio_text)
+}
+
 // ── Logs ────────────────────────────────────────────────────────────────────
 
 #[tauri::command]
@@ -895,6 +2088,44 @@ fn update_track_path(
         .update_track_path(&playlist, track_index, std::path::Path::new(&new_path))
 }
 
+/// Rewrite a path prefix across a playlist's tracks (moved music library).
+/// `dry_run` only reports how many tracks would change.
+#[tauri::command]
+async fn relink_paths(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    playlist: String,
+    old_prefix: String,
+    new_prefix: String,
+    dry_run: Option<bool>,
+) -> Result<RelinkSummary, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    let plan = state
+        .core
+        .lock()
+        .unwrap()
+        .plan_relink(&playlist, &old_prefix, &new_prefix)?;
+
+    // Check the new paths and read their tags without holding the lock
+    let reads = tokio::task::spawn_blocking(move || {
+        plan.into_iter()
+            .map(|relink| relink.read(!dry_run))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Relink panicked: {e}"))?;
+
+    let summary = state.core.lock().unwrap().apply_relink(
+        &playlist,
+        &old_prefix,
+        &new_prefix,
+        reads,
+        dry_run,
+    )?;
+    let _ = app.emit("logs-changed", ());
+    Ok(summary)
+}
+
 /// Rename / move a track's file on disk then update the playlist path.
 /// Implements the truth-table from the spec:
 ///   original exists + new free  → rename/move (create dirs as needed)
@@ -1304,6 +2535,13 @@ struct EditorStatusData {
     position_secs: f64,
 }
 
+/// Rendered crossfade preview, already playing on the editor audio handle.
+#[derive(Serialize)]
+struct CrossfadePreviewResponse {
+    path: String,
+    duration_secs: f64,
+}
+
 /// Export request sent from the frontend.
 #[derive(Deserialize)]
 struct ExportRequest {
@@ -1314,31 +2552,151 @@ struct ExportRequest {
     operations: signal_flow::audio_editor::EditorOperations,
 }
 
+/// Neighboring playlist track loaded into the editor.
+#[derive(Serialize)]
+struct EditorNeighborResponse {
+    index: usize,
+    path: String,
+    waveform: EditorWaveformResponse,
+}
+
+/// Blocking editor peak generation shared by the waveform/navigation commands.
+/// With `target_peaks`, the peaks are resampled to exactly that many.
+fn load_editor_waveform(
+    path: &str,
+    resolution_ms: u32,
+    target_peaks: Option<usize>,
+) -> Result<EditorWaveformResponse, String> {
+    let data = signal_flow::waveform::generate_editor_peaks_cached(
+        std::path::Path::new(path),
+        resolution_ms.clamp(5, 500),
+    )?;
+    let peaks = match target_peaks {
+        Some(len) => signal_flow::waveform::resample_peaks(&data.peaks, len),
+        None => data.peaks,
+    };
+    Ok(EditorWaveformResponse {
+        num_peaks: peaks.len(),
+        peaks,
+        duration_secs: data.duration_secs,
+        sample_rate: data.sample_rate,
+        resolution_ms: data.resolution_ms,
+    })
+}
+
 /// Fetch high-resolution waveform peaks for the audio editor.
 /// `resolution_ms` = milliseconds per peak (10 → ~100 peaks/sec). Cached on disk.
+/// `target_peaks` fixes the returned peak count for a stable display width.
 #[tauri::command]
 async fn get_editor_waveform(
     path: String,
     resolution_ms: u32,
+    target_peaks: Option<usize>,
 ) -> Result<EditorWaveformResponse, String> {
-    let resolution_ms = resolution_ms.clamp(5, 500);
+    tokio::task::spawn_blocking(move || load_editor_waveform(&path, resolution_ms, target_peaks))
+        .await
+        .map_err(|e| format!("Waveform task panicked: {e}"))?
+}
+
+/// Peaks for just the `start_secs..end_secs` window, for zoomed-in editor
+/// views. Decodes only that window; not cached.
+#[tauri::command]
+async fn get_editor_waveform_range(
+    path: String,
+    start_secs: f64,
+    end_secs: f64,
+    peaks: usize,
+) -> Result<Vec<f32>, String> {
     tokio::task::spawn_blocking(move || {
-        let data = signal_flow::waveform::generate_editor_peaks_cached(
+        signal_flow::waveform::generate_peaks_range(
             std::path::Path::new(&path),
-            resolution_ms,
-        )?;
-        Ok(EditorWaveformResponse {
-            peaks: data.peaks,
-            duration_secs: data.duration_secs,
-            sample_rate: data.sample_rate,
-            num_peaks: data.num_peaks,
-            resolution_ms: data.resolution_ms,
-        })
+            start_secs,
+            end_secs,
+            peaks,
+        )
+    })
+    .await
+    .map_err(|e| format!("Waveform task panicked: {e}"))?
+}
+
+/// Editor peaks for a whole folder of files, computed (or read from the disk
+/// cache) a few files at a time. Each entry carries its peaks or its error.
+#[tauri::command]
+async fn get_editor_waveforms_batch(
+    paths: Vec<String>,
+    resolution_ms: u32,
+) -> Result<Vec<signal_flow::waveform::EditorBatchEntry>, String> {
+    let paths: Vec<std::path::PathBuf> = paths.into_iter().map(Into::into).collect();
+    tokio::task::spawn_blocking(move || {
+        signal_flow::waveform::generate_editor_peaks_batch(
+            &paths,
+            resolution_ms.clamp(5, 500),
+            signal_flow::waveform::EDITOR_BATCH_WORKERS,
+        )
+    })
+    .await
+    .map_err(|e| format!("Waveform task panicked: {e}"))
+}
+
+/// Step the editor to the track after/before the one at `index` (which must
+/// still be `path`) in `playlist` (None = active playlist): stops editor
+/// playback, resets its position, and loads the neighbor's waveform. Returns
+/// None when already at that end of the playlist.
+async fn editor_load_neighbor(
+    state: State<'_, AppState>,
+    index: usize,
+    path: String,
+    playlist: Option<String>,
+    resolution_ms: u32,
+    forward: bool,
+) -> Result<Option<EditorNeighborResponse>, String> {
+    let neighbor = {
+        let core = state.core.lock().unwrap();
+        core.editor_neighbor(playlist.as_deref(), index, &path, forward)?
+    };
+    let Some((index, neighbor)) = neighbor else {
+        return Ok(None);
+    };
+
+    state.editor_audio.stop();
+    *state.editor_info.lock().unwrap() = EditorPlaybackState::default();
+
+    tokio::task::spawn_blocking(move || {
+        let waveform = load_editor_waveform(&neighbor, resolution_ms, None)?;
+        Ok(Some(EditorNeighborResponse {
+            index,
+            path: neighbor,
+            waveform,
+        }))
     })
     .await
     .map_err(|e| format!("Waveform task panicked: {e}"))?
 }
 
+/// Load the next playlist track into the editor.
+#[tauri::command]
+async fn editor_load_next(
+    state: State<'_, AppState>,
+    index: usize,
+    path: String,
+    playlist: Option<String>,
+    resolution_ms: u32,
+) -> Result<Option<EditorNeighborResponse>, String> {
+    editor_load_neighbor(state, index, path, playlist, resolution_ms, true).await
+}
+
+/// Load the previous playlist track into the editor.
+#[tauri::command]
+async fn editor_load_prev(
+    state: State<'_, AppState>,
+    index: usize,
+    path: String,
+    playlist: Option<String>,
+    resolution_ms: u32,
+) -> Result<Option<EditorNeighborResponse>, String> {
+    editor_load_neighbor(state, index, path, playlist, resolution_ms, false).await
+}
+
 /// Return technical information about an audio file using lofty.
 #[tauri::command]
 async fn get_audio_info(path: String) -> Result<AudioFileInfo, String> {
@@ -1433,12 +2791,55 @@ fn editor_status(state: State<AppState>) -> EditorStatusData {
     }
 }
 
+/// Render how `path_a` crossfades into `path_b` (a few seconds either side
+/// of the overlap) to a temp WAV and play it on the editor audio handle.
+/// `curve` is "linear" (default) or "equal_power".
+#[tauri::command]
+async fn editor_preview_crossfade(
+    state: State<'_, AppState>,
+    path_a: String,
+    path_b: String,
+    crossfade_secs: f32,
+    curve: Option<String>,
+) -> Result<CrossfadePreviewResponse, String> {
+    let curve = CrossfadeCurve::from_str_loose(curve.as_deref().unwrap_or(""))?;
+    let out_path = std::env::temp_dir().join("signalflow_crossfade_preview.wav");
+    let out_for_render = out_path.clone();
+    let duration_secs = tokio::task::spawn_blocking(move || {
+        let preview = signal_flow::crossfade_preview::render_crossfade(
+            std::path::Path::new(&path_a),
+            std::path::Path::new(&path_b),
+            crossfade_secs,
+            curve,
+            PREVIEW_CONTEXT_SECS,
+        )?;
+        preview.write_wav(&out_for_render)?;
+        Ok::<_, String>(preview.duration_secs())
+    })
+    .await
+    .map_err(|e| format!("Crossfade preview task panicked: {e}"))??;
+
+    state
+        .editor_audio
+        .play(out_path.clone(), state.editor_level_monitor.clone());
+    let mut info = state.editor_info.lock().unwrap();
+    info.is_playing = true;
+    info.start_secs = 0.0;
+    info.started_at = Some(std::time::Instant::now());
+
+    Ok(CrossfadePreviewResponse {
+        path: out_path.to_string_lossy().to_string(),
+        duration_secs,
+    })
+}
+
 /// Export the edited audio file via ffmpeg.
 /// Builds and runs the filter chain, writes to `output_path`.
 #[tauri::command]
 async fn export_edited_audio(request: ExportRequest) -> Result<String, String> {
     use signal_flow::audio_editor::{build_ffmpeg_args, run_ffmpeg};
 
+    request.operations.validate()?;
     tokio::task::spawn_blocking(move || {
         let args = build_ffmpeg_args(
             &request.input_path,
@@ -1454,6 +2855,31 @@ async fn export_edited_audio(request: ExportRequest) -> Result<String, String> {
     .map_err(|e| format!("Export task panicked: {e}"))?
 }
 
+/// Trim a file to `start_secs..end_secs` (0 = to the end) and overwrite it,
+/// optionally keeping the original as `<name>.bak`. Returns the backup path.
+#[tauri::command]
+async fn editor_trim_in_place(
+    path: String,
+    start_secs: f64,
+    end_secs: f64,
+    keep_backup: bool,
+) -> Result<Option<String>, String> {
+    use signal_flow::audio_editor::{run_ffmpeg, trim_in_place};
+
+    tokio::task::spawn_blocking(move || {
+        let backup = trim_in_place(
+            std::path::Path::new(&path),
+            start_secs,
+            end_secs,
+            keep_backup,
+            run_ffmpeg,
+        )?;
+        Ok(backup.map(|p| p.to_string_lossy().to_string()))
+    })
+    .await
+    .map_err(|e| format!("Trim task panicked: {e}"))?
+}
+
 /// Scan an audio file for silence regions below `threshold_db` dB lasting at
 /// least `min_duration_secs` seconds. Returns a list of silence regions.
 #[tauri::command]
@@ -1473,6 +2899,59 @@ async fn detect_silence_regions(
     .map_err(|e| format!("Silence detection task panicked: {e}"))?
 }
 
+/// Split a long recording into tracks at silences below `threshold_db` dB
+/// lasting at least `min_silence_secs`, merging tracks shorter than
+/// `min_track_secs`. Each track is exported via ffmpeg to `output_dir`
+/// (default: beside the source). Returns the written paths.
+#[tauri::command]
+async fn split_on_silence(
+    path: String,
+    threshold_db: f64,
+    min_silence_secs: f64,
+    min_track_secs: f64,
+    output_dir: Option<String>,
+) -> Result<Vec<String>, String> {
+    use signal_flow::audio_editor::{export_segments, run_ffmpeg};
+
+    tokio::task::spawn_blocking(move || {
+        let path = std::path::Path::new(&path);
+        let segments = signal_flow::audio_editor::split_on_silence(
+            path,
+            threshold_db,
+            min_silence_secs,
+            min_track_secs,
+        )?;
+        let output_dir = match output_dir {
+            Some(dir) => PathBuf::from(dir),
+            None => path
+                .parent()
+                .unwrap_or(std::path::Path::new("."))
+                .to_path_buf(),
+        };
+        let written = export_segments(path, &segments, &output_dir, run_ffmpeg)?;
+        Ok(written
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("Silence split task panicked: {e}"))?
+}
+
+/// Suggested cue-in (seconds) for an audio file: the length of its leading
+/// silence below `threshold_db` dBFS (default -40).
+#[tauri::command]
+async fn suggest_cue_in(path: String, threshold_db: Option<f64>) -> Result<f64, String> {
+    tokio::task::spawn_blocking(move || {
+        signal_flow::audio_editor::suggest_cue_in(
+            std::path::Path::new(&path),
+            threshold_db.unwrap_or(-40.0),
+        )
+    })
+    .await
+    .map_err(|e| format!("Cue-in detection task panicked: {e}"))?
+}
+
 // ── App entry ───────────────────────────────────────────────────────────────
 
 fn main() {
@@ -1486,7 +2965,105 @@ fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .setup(move |app| {
-            let core = Arc::new(Mutex::new(AppCore::new(&state_path)));
+            let mut app_core = AppCore::new(&state_path);
+            // Share the transport's heartbeat so AppCore can report on-air status
+            app_core.heartbeat = level_monitor.heartbeat();
+            let core = Arc::new(Mutex::new(app_core));
+
+            // Touch the heartbeat file (if configured) while audio is flowing
+            let core_for_heartbeat = core.clone();
+            std::thread::Builder::new()
+                .name("heartbeat-file".into())
+                .spawn(move || {
+                    loop {
+                        std::thread::sleep(Duration::from_secs(5));
+                        let result = core_for_heartbeat
+                            .lock()
+                            .unwrap()
+                            .write_heartbeat_file(ON_AIR_MAX_STALE);
+                        if let Err(e) = result {
+                            eprintln!("[Heartbeat] {}", e);
+                        }
+                    }
+                })
+                .expect("failed to spawn heartbeat-file thread");
+
+            // Periodically record the playback position (throttled by
+            // `resume_save_interval_secs`) so a restart can resume mid-track
+            let core_for_resume = core.clone();
+            std::thread::Builder::new()
+                .name("resume-marker".into())
+                .spawn(move || {
+                    loop {
+                        std::thread::sleep(Duration::from_secs(1));
+                        let result = core_for_resume.lock().unwrap().save_resume_marker();
+                        if let Err(e) = result {
+                            eprintln!("[Resume] {}", e);
+                        }
+                    }
+                })
+                .expect("failed to spawn resume-marker thread");
+
+            // Write debounced state changes once `save_debounce_ms` has
+            // passed, so a burst of playlist edits is one state file write
+            let core_for_save = core.clone();
+            std::thread::Builder::new()
+                .name("state-save".into())
+                .spawn(move || {
+                    loop {
+                        std::thread::sleep(Duration::from_millis(250));
+                        let result = core_for_save.lock().unwrap().flush_state_if_due();
+                        if let Err(e) = result {
+                            eprintln!("[State] {}", e);
+                        }
+                    }
+                })
+                .expect("failed to spawn state-save thread");
+
+            // Write now-playing changes once they settle for
+            // `now_playing_debounce_ms`, so a burst of skips writes once. The
+            // text is built under the core lock and written after it.
+            let core_for_now_playing = core.clone();
+            std::thread::Builder::new()
+                .name("now-playing".into())
+                .spawn(move || {
+                    let mut last_written = None;
+                    loop {
+                        std::thread::sleep(Duration::from_millis(100));
+                        let due = core_for_now_playing.lock().unwrap().take_now_playing();
+                        let Some(due) = due.filter(|due| last_written.as_ref() != Some(due)) else {
+                            continue;
+                        };
+                        match due.write() {
+                            Ok(()) => last_written = Some(due),
+                            Err(e) => eprintln!("[NowPlaying] {}", e),
+                        }
+                    }
+                })
+                .expect("failed to spawn now-playing thread");
+
+            // Alert when the program output goes silent while it should be
+            // playing (`dead_air_secs`), e.g. a failed source
+            let core_for_dead_air = core.clone();
+            let level_for_dead_air = level_monitor.clone();
+            let app_for_dead_air = app.handle().clone();
+            std::thread::Builder::new()
+                .name("dead-air".into())
+                .spawn(move || {
+                    loop {
+                        std::thread::sleep(Duration::from_millis(250));
+                        let level = level_for_dead_air.level();
+                        let event = core_for_dead_air
+                            .lock()
+                            .unwrap()
+                            .check_dead_air(level, std::time::Instant::now());
+                        if let Some(event) = event {
+                            let _ = app_for_dead_air.emit("dead-air", event);
+                            let _ = app_for_dead_air.emit("logs-changed", ());
+                        }
+                    }
+                })
+                .expect("failed to spawn dead-air thread");
             let app_handle = app.handle().clone();
             let level_monitor_for_audio = level_monitor.clone();
             let audio_for_callback: Arc<Mutex<Option<AudioHandle>>> = Arc::new(Mutex::new(None));
@@ -1495,7 +3072,8 @@ fn main() {
             let editor_info: Arc<Mutex<EditorPlaybackState>> =
                 Arc::new(Mutex::new(EditorPlaybackState::default()));
             let editor_info_for_cb = editor_info.clone();
-            let editor_audio = spawn_audio_runtime(None, move |event| {
+            let monitor_device = core.lock().unwrap().output_device_for(OutputBus::Monitor);
+            let editor_audio = spawn_audio_runtime(monitor_device, move |event| {
                 // On track end or stop, mark editor as stopped
                 match event {
                     AudioEvent::TrackFinished | AudioEvent::Stopped => {
@@ -1514,40 +3092,42 @@ fn main() {
             let audio = spawn_audio_runtime(initial_device, move |event| {
                 match event {
                     AudioEvent::TrackFinished => {
-                        let next_track = {
-                            let mut core = core_for_audio.lock().unwrap();
-                            // If stop was issued before we acquired the lock,
-                            // don't auto-advance — the user explicitly stopped.
-                            if !core.playback.is_playing {
-                                return;
+                        let audio = audio_for_callback_clone.lock().unwrap().clone();
+                        let mut core = core_for_audio.lock().unwrap();
+                        if let Some(audio) = audio {
+                            // Failures are logged and stop playback
+                            let _ =
+                                facade::track_finished(&mut core, &audio, &level_monitor_for_audio);
+                        }
+                        let event = core.transport_event(TransportEventKind::TrackFinished);
+                        drop(core);
+                        emit_transport(&app_handle, event);
+                        play_released_events(&app_handle);
+                        let _ = app_handle.emit("logs-changed", ());
+                    }
+                    AudioEvent::ExitPoint => {
+                        let audio = audio_for_callback_clone.lock().unwrap().clone();
+                        let mut core = core_for_audio.lock().unwrap();
+                        // Only a crossfade advances here; fade-outs end in TrackFinished
+                        let advanced = match audio {
+                            Some(audio) => {
+                                facade::track_exit(&mut core, &audio, &level_monitor_for_audio)
                             }
-                            core.prepare_skip()
+                            None => Ok(false),
                         };
-
-                        match next_track {
-                            Ok((track_path, ..)) => {
-                                if let Some(audio) =
-                                    audio_for_callback_clone.lock().unwrap().as_ref()
-                                {
-                                    audio.play(track_path, level_monitor_for_audio.clone());
-                                }
-                            }
-                            Err(ref e) if e == "__end_of_playlist__" => {
-                                // End of playlist is handled in AppCore::prepare_skip.
-                            }
-                            Err(e) => {
-                                let mut core = core_for_audio.lock().unwrap();
-                                core.on_stop();
-                                core.log("error", format!("Auto-advance failed: {}", e));
-                            }
+                        if advanced != Ok(false) {
+                            let event = core.transport_event(TransportEventKind::TrackFinished);
+                            drop(core);
+                            emit_transport(&app_handle, event);
+                            play_released_events(&app_handle);
+                            let _ = app_handle.emit("logs-changed", ());
                         }
-                        let _ = app_handle.emit("transport-changed", ());
-                        let _ = app_handle.emit("logs-changed", ());
+                    }
+                    AudioEvent::OutputLatency(latency) => {
+                        core_for_audio.lock().unwrap().set_output_latency(latency);
                     }
                     AudioEvent::PlayError(ref e) => {
-                        let mut c = core_for_audio.lock().unwrap();
-                        c.on_stop();
-                        c.log("error", format!("Audio error: {}", e));
+                        facade::play_error(&mut core_for_audio.lock().unwrap(), e);
                         let _ = app_handle.emit("transport-changed", ());
                         let _ = app_handle.emit("logs-changed", ());
                     }
@@ -1560,6 +3140,7 @@ fn main() {
                     }
                 }
             });
+            push_audio_config(&core.lock().unwrap(), &audio);
             *audio_for_callback.lock().unwrap() = Some(audio.clone());
 
             app.manage(AppState {
@@ -1576,55 +3157,115 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             // Status
             get_status,
+            reload_state,
+            on_air_status,
             // Playlist CRUD
             get_playlists,
             create_playlist,
             delete_playlist,
             rename_playlist,
+            duplicate_playlist,
             set_active_playlist,
+            set_playlist_auto_advance,
+            set_playlist_locked,
             get_playlist_profiles,
             save_playlist_profile,
             load_playlist_profile,
             delete_playlist_profile,
             import_m3u_playlist,
+            import_playlists_from_state,
+            create_playlist_from_folder,
+            import_cue,
             export_playlist_to_m3u,
             // Track operations
             get_playlist_tracks,
+            get_playlist_stats,
+            validate_playlist,
+            verify_track_duration,
+            analyze_key,
+            find_audio_duplicates,
+            convert_tracks,
+            set_talk_over,
+            set_track_no_crossfade_in,
+            set_track_gain,
+            set_replay_gain,
+            set_track_advisory,
             add_track,
             add_tracks,
             remove_tracks,
             reorder_track,
+            shuffle_playlist,
             copy_paste_tracks,
+            move_tracks,
             edit_track_metadata,
+            edit_tracks_metadata,
             list_available_drives,
             list_directory,
             search_indexed_files,
+            search_playlists,
             // Transport
             transport_play,
             transport_stop,
             transport_pause,
             transport_skip,
+            transport_prev,
             transport_seek,
             transport_status,
+            resume_playback,
+            get_resume_marker,
+            set_resume_save_interval,
+            set_save_debounce,
             get_audio_level,
             get_waveform,
+            get_playlist_waveforms,
+            precompute_waveforms,
             // Schedule
             get_schedule,
             add_schedule_event,
             remove_schedule_event,
             toggle_schedule_event,
+            set_schedule_event_rotator,
+            set_schedule_event_autostart,
+            set_schedule_event_dates,
+            set_schedule_event_insert_position,
+            set_schedule_event_fallback,
+            fire_schedule_event,
+            get_schedule_conflicts,
             // Ads
             get_ads,
             add_ad,
+            set_ad_normalization,
+            set_ad_repeat_gap,
+            set_station_id_position,
+            set_ad_insert_timing,
+            insert_ad_break,
+            preview_ad_break,
+            set_ad_event_guard,
+            set_ad_roll_limit,
+            set_ad_failure_retention,
             remove_ad,
             toggle_ad,
             update_ad,
+            set_ad_flight,
             reorder_ad,
+            // Carts
+            get_carts,
+            add_cart,
+            remove_cart,
+            play_cart,
             // Ad Statistics & Reports
             get_ad_stats,
             get_ad_daily_counts,
+            get_ad_daily_counts_filled,
+            get_ad_hour_histogram,
             get_ad_failures,
             generate_ad_report,
+            get_ad_report_audit,
+            get_ad_delivery_report,
+            generate_ad_rollup,
+            set_report_branding,
+            export_config_bundle,
+            import_config_bundle,
             // RDS
             get_rds_config,
             add_rds_message,
@@ -1633,41 +3274,85 @@ fn main() {
             update_rds_message,
             reorder_rds_message,
             update_rds_settings,
+            set_rds_formatting,
             // Logs
             get_logs,
             clear_logs,
             // Config
             get_config,
             set_crossfade,
+            set_signon_fade,
+            set_crossfade_lead,
+            set_min_crossfade_track,
+            set_crossfade_on_skip,
+            set_live_assist_hotkeys,
+            set_overlay_gain,
             set_silence_detection,
+            set_dead_air_alarm,
             set_intros_folder,
             set_recurring_intro,
+            set_recurring_intro_duck_ramp,
+            set_intro_once_per_session,
+            set_intro_mode,
+            set_intro_gain,
             set_conflict_policy,
+            set_paused_event_handling,
+            set_end_of_playlist_action,
             set_stream_output,
             set_recording,
             set_indexed_locations,
             set_favorite_folders,
             set_nowplaying_path,
+            set_now_playing_debounce,
+            set_now_playing_ad_placeholder,
+            set_heartbeat_path,
+            set_emergency_file,
             list_output_devices,
             set_output_device,
+            set_monitor_device,
+            set_output_sample_rate,
+            set_audio_buffer_ms,
+            set_agc,
+            set_channel_mode,
+            set_clean_hours,
+            set_avoid_artist_repeat,
+            set_measure_unknown_durations,
             // File / shell operations
             open_file_location,
             open_in_audacity,
             update_track_path,
+            relink_paths,
             rename_track_file,
             convert_tracks_to_mp3,
             replace_from_macro_output,
             add_am_to_filename,
             // In-app audio editor
             get_editor_waveform,
+            get_editor_waveform_range,
+            get_editor_waveforms_batch,
             get_audio_info,
             editor_play,
             editor_stop,
             editor_seek,
             editor_status,
+            editor_preview_crossfade,
+            editor_load_next,
+            editor_load_prev,
             export_edited_audio,
+            editor_trim_in_place,
             detect_silence_regions,
+            split_on_silence,
+            suggest_cue_in,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Don't lose debounced state changes on the way out
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<AppState>();
+                if let Err(e) = state.core.lock().unwrap().flush() {
+                    eprintln!("[State] Flush on exit failed: {}", e);
+                }
+            }
+        });
 }
@@ -1,11 +1,19 @@
-use crate::ad_scheduler::{AdConfig, AdInsertionMode};
+use crate::ad_scheduler::{
+    AD_HISTORY_MAX, AdConfig, AdInsertTiming, AdInsertionMode, AdPlayRecord, RepeatGap,
+    StationIdPosition, is_hour_start,
+};
+use crate::clock::Clock;
 use crate::engine::Engine;
 use crate::player::Player;
-use chrono::Local;
+use crate::scheduler::{AdRollArbiter, AdRollDecision, InsertPosition};
+use chrono::{DateTime, Local};
 use rodio::{Decoder, Sink};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 /// Result of an ad insertion operation.
@@ -17,6 +25,89 @@ pub struct AdInsertionResult {
     pub ads_inserted: Vec<String>,
     /// Whether the station ID was played/inserted.
     pub station_id_played: bool,
+    /// Ads that played inside `min_repeat_gap` because nothing else fit.
+    pub forced_repeats: Vec<String>,
+    /// Measured length of each ad, in the same order as `ads_inserted`.
+    pub ad_durations: Vec<Duration>,
+    /// Measured length of one station ID play, if it was in the roll.
+    pub station_id_duration: Option<Duration>,
+    /// Length of the whole roll, station ID play(s) included.
+    pub total_duration: Duration,
+    /// The playing track should end now so the queued roll starts at once
+    /// (`AdInsertTiming::Immediate`). False when the roll waits for it.
+    pub interrupt_current: bool,
+}
+
+impl AdInsertionResult {
+    /// Describe a roll of `ads` (and `station_id` placed per `position`),
+    /// timing each entry from `lengths`. Files missing from it count as
+    /// zero length.
+    pub fn for_roll(
+        station_id: Option<&Path>,
+        ads: &[&AdConfig],
+        position: StationIdPosition,
+        forced_repeats: Vec<String>,
+        lengths: &RollLengths,
+    ) -> Self {
+        let ads_inserted: Vec<String> = ads.iter().map(|ad| ad.name.clone()).collect();
+        let ad_durations: Vec<Duration> = ads
+            .iter()
+            .map(|ad| lengths.of(ad.playback_file()))
+            .collect();
+        let station_id_duration = station_id.map(|sid| lengths.of(sid));
+        let id_plays = u32::from(position.at_start()) + u32::from(position.at_end());
+        let total_duration = ad_durations.iter().sum::<Duration>()
+            + station_id_duration.unwrap_or_default() * id_plays;
+        AdInsertionResult {
+            ad_count: ads_inserted.len(),
+            ads_inserted,
+            station_id_played: station_id.is_some(),
+            forced_repeats,
+            ad_durations,
+            station_id_duration,
+            total_duration,
+            interrupt_current: false,
+        }
+    }
+}
+
+/// Decoded lengths of the files a roll may use, so a break decodes each
+/// file once. Decoding is slow; measure the `roll_candidates` before
+/// locking the engine and pass the result in. Anything missing is decoded
+/// when the roll is built.
+#[derive(Debug, Clone, Default)]
+pub struct RollLengths(HashMap<PathBuf, Duration>);
+
+impl RollLengths {
+    /// Decode each of `paths`. Unreadable files count as zero length.
+    pub fn measure(paths: &[PathBuf]) -> Self {
+        let mut lengths = Self::default();
+        lengths.fill(paths.iter().map(PathBuf::as_path));
+        lengths
+    }
+
+    /// Decode whichever of `paths` isn't measured yet.
+    fn fill<'a>(&mut self, paths: impl IntoIterator<Item = &'a Path>) {
+        for path in paths {
+            if !self.0.contains_key(path) {
+                self.0.insert(path.to_path_buf(), measured_length(path));
+            }
+        }
+    }
+
+    /// Measured length of `path`, zero if it wasn't measured.
+    fn of(&self, path: &Path) -> Duration {
+        self.0.get(path).copied().unwrap_or_default()
+    }
+}
+
+/// The roll an insert would build, without playing or recording it.
+#[derive(Debug, Clone)]
+pub struct AdPreview {
+    /// Playback order as `(path, label)`, station ID play(s) included.
+    pub roll: Vec<(PathBuf, String)>,
+    /// What the insert would report.
+    pub result: AdInsertionResult,
 }
 
 /// Stateless service for inserting ads into playback.
@@ -26,17 +117,12 @@ pub struct AdInsertionResult {
 pub struct AdInserterService;
 
 impl AdInserterService {
-    /// Filter ads to only those valid for playback right now.
+    /// Filter ads to only those valid for playback at `clock`'s time.
     ///
-    /// An ad is valid if it's enabled, its file exists, and its schedule
-    /// matches the current day and hour.
-    pub fn collect_valid_ads(ads: &[AdConfig]) -> Vec<&AdConfig> {
-        let now = Local::now();
-        let day = now.format("%A").to_string();
-        let hour = now.hour() as u8;
-        ads.iter()
-            .filter(|ad| ad.is_valid_now(&day, hour))
-            .collect()
+    /// An ad is valid if it's enabled, its file exists, the date is within
+    /// its flight dates, and its schedule matches the current day and hour.
+    pub fn collect_valid_ads<'a>(ads: &'a [AdConfig], clock: &dyn Clock) -> Vec<&'a AdConfig> {
+        ads.iter().filter(|ad| ad.is_valid_at(clock)).collect()
     }
 
     /// Collect valid ads using an explicit day and hour (for testing).
@@ -46,137 +132,411 @@ impl AdInserterService {
             .collect()
     }
 
-    /// Instant ad insertion: stop current playback, play all valid ads
-    /// (and optionally station ID) on a new sink, block until finished.
-    ///
-    /// Returns the result describing what was played, or an error.
-    pub fn insert_instant(
-        player: &Player,
+    /// Pick this break's ads in rotation order, skipping any that would play
+    /// inside `gap` of `history` (oldest first) or of an earlier pick in the
+    /// same break. If nothing fits and the break would otherwise be empty,
+    /// the least recently played ad goes anyway and is also returned as a
+    /// forced repeat.
+    pub fn select_with_repeat_gap<'a>(
+        ads: &[&'a AdConfig],
+        history: &[AdPlayRecord],
+        gap: RepeatGap,
+        now: DateTime<Local>,
+    ) -> (Vec<&'a AdConfig>, Vec<String>) {
+        let mut recent = history.to_vec();
+        let mut remaining = ads.to_vec();
+        let mut selected = Vec::new();
+        let mut forced = Vec::new();
+        while !remaining.is_empty() {
+            let fits = remaining
+                .iter()
+                .position(|ad| !gap.blocks(&ad.name, &recent, now));
+            let pos = match fits {
+                Some(pos) => pos,
+                None if selected.is_empty() => {
+                    let pos = least_recent(&remaining, &recent);
+                    forced.push(remaining[pos].name.clone());
+                    pos
+                }
+                None => break,
+            };
+            let ad = remaining.remove(pos);
+            recent.push(AdPlayRecord {
+                name: ad.name.clone(),
+                at: now,
+            });
+            selected.push(ad);
+        }
+        (selected, forced)
+    }
+
+    /// Trim a selection to fit in `budget`, given each ad's length in
+    /// `lengths` (same order). Ads are taken in order until one would run
+    /// past the budget; then the roll stops there, or with `fit_best` the
+    /// later ads are tried and any that fit the time left are kept. The
+    /// first ad always goes so a break is never empty.
+    pub fn fit_to_roll<'a>(
+        ads: &[&'a AdConfig],
+        lengths: &[Duration],
+        budget: Duration,
+        fit_best: bool,
+    ) -> Vec<&'a AdConfig> {
+        let mut kept = Vec::new();
+        let mut used = Duration::ZERO;
+        for (ad, &len) in ads.iter().zip(lengths) {
+            if !kept.is_empty() && used + len > budget {
+                if fit_best {
+                    continue;
+                }
+                break;
+            }
+            used += len;
+            kept.push(*ad);
+        }
+        kept
+    }
+
+    /// Apply `min_repeat_gap` to the valid ads, logging forced repeats, then
+    /// cap the roll at `max_roll_secs` (less the station ID's share).
+    fn select_for_break<'a>(
+        engine: &Engine,
+        valid_ads: &[&'a AdConfig],
+        station_id: Option<&Path>,
+        now: DateTime<Local>,
+        lengths: &RollLengths,
+    ) -> (Vec<&'a AdConfig>, Vec<String>) {
+        let (mut selected, mut forced) = Self::select_with_repeat_gap(
+            valid_ads,
+            &engine.ad_play_history,
+            engine.ad_inserter.min_repeat_gap,
+            now,
+        );
+        let settings = &engine.ad_inserter;
+        if settings.max_roll_secs > 0 {
+            let position = settings.station_id_position;
+            let id_plays = u32::from(position.at_start()) + u32::from(position.at_end());
+            let reserved = station_id.map(|sid| lengths.of(sid)).unwrap_or_default() * id_plays;
+            let limit = Duration::from_secs(settings.max_roll_secs.into());
+            let budget = limit.saturating_sub(reserved);
+            let lengths: Vec<Duration> = selected
+                .iter()
+                .map(|ad| lengths.of(ad.playback_file()))
+                .collect();
+            let kept = Self::fit_to_roll(&selected, &lengths, budget, settings.fit_best);
+            let dropped = selected
+                .iter()
+                .filter(|ad| !kept.iter().any(|k| k.name == ad.name));
+            for ad in dropped {
+                eprintln!(
+                    "[AdInserter] '{}' left out: roll would exceed {}s",
+                    ad.name, settings.max_roll_secs
+                );
+            }
+            forced.retain(|name| kept.iter().any(|ad| &ad.name == name));
+            selected = kept;
+        }
+        for name in &forced {
+            eprintln!(
+                "[AdInserter] '{}' repeats inside the minimum gap (no alternative)",
+                name
+            );
+        }
+        (selected, forced)
+    }
+
+    /// Playback order for a roll: the ads, with the station ID (if any)
+    /// placed according to `position`. Entries are `(path, label)`.
+    pub fn sequence_roll(
+        station_id: Option<&Path>,
+        ads: &[&AdConfig],
+        position: StationIdPosition,
+    ) -> Vec<(PathBuf, String)> {
+        let id_entry = |sid: &Path| (sid.to_path_buf(), "Station ID".to_string());
+        let mut roll = Vec::new();
+        if let Some(sid) = station_id.filter(|_| position.at_start()) {
+            roll.push(id_entry(sid));
+        }
+        roll.extend(
+            ads.iter()
+                .map(|ad| (ad.playback_file().to_path_buf(), ad.name.clone())),
+        );
+        if let Some(sid) = station_id.filter(|_| position.at_end()) {
+            roll.push(id_entry(sid));
+        }
+        roll
+    }
+
+    /// Station ID file for this break, if enabled, at the top of the hour,
+    /// and present on disk.
+    fn station_id_for(engine: &Engine, is_hour_start: bool) -> Option<&Path> {
+        if !is_hour_start || !engine.ad_inserter.station_id_enabled {
+            return None;
+        }
+        engine
+            .ad_inserter
+            .station_id_file
+            .as_deref()
+            .filter(|p| p.exists())
+    }
+
+    /// Files a break at `clock`'s time could play: the valid ads and, at the
+    /// top of the hour, the station ID. Measure these with
+    /// `RollLengths::measure` off any lock on the engine.
+    pub fn roll_candidates(engine: &Engine, clock: &dyn Clock) -> Vec<PathBuf> {
+        let ads = Self::collect_valid_ads(&engine.ads, clock);
+        let station_id = Self::station_id_for(engine, is_hour_start(clock));
+        ads.iter()
+            .map(|ad| ad.playback_file())
+            .chain(station_id)
+            .map(Path::to_path_buf)
+            .collect()
+    }
+
+    /// Select and sequence the roll for a break at `clock`'s time. Shared by
+    /// both insert modes and `preview`, so a preview matches the real thing.
+    /// Files not already in `lengths` are decoded here.
+    fn build_roll(
         engine: &Engine,
+        clock: &dyn Clock,
         is_hour_start: bool,
-    ) -> Result<AdInsertionResult, String> {
-        let valid_ads = Self::collect_valid_ads(&engine.ads);
+        lengths: &RollLengths,
+    ) -> Result<AdPreview, String> {
+        let valid_ads = Self::collect_valid_ads(&engine.ads, clock);
         if valid_ads.is_empty() {
             return Err("No valid ads to insert".to_string());
         }
+        let station_id = Self::station_id_for(engine, is_hour_start);
+        let mut lengths = lengths.clone();
+        let files = valid_ads.iter().map(|ad| ad.playback_file());
+        lengths.fill(files.chain(station_id));
+        let (ads, forced_repeats) =
+            Self::select_for_break(engine, &valid_ads, station_id, clock.now(), &lengths);
+        let position = engine.ad_inserter.station_id_position;
+        Ok(AdPreview {
+            roll: Self::sequence_roll(station_id, &ads, position),
+            result: AdInsertionResult::for_roll(
+                station_id,
+                &ads,
+                position,
+                forced_repeats,
+                &lengths,
+            ),
+        })
+    }
 
-        // Determine if station ID should play
-        let station_id_path = if is_hour_start
-            && engine.ad_inserter.station_id_enabled
-        {
-            engine
-                .ad_inserter
-                .station_id_file
-                .as_ref()
-                .filter(|p| p.exists())
-        } else {
-            None
-        };
+    /// Dry run: the ads (in order) and station ID placement a break at
+    /// `clock`'s time would get, honoring dayparts, flight dates, the
+    /// repeat gap and the roll length cap. Nothing is queued, played or
+    /// added to the ad history. Lecture and scheduled-event deferrals are
+    /// not checked; the preview shows what would air once the break runs.
+    pub fn preview(
+        engine: &Engine,
+        clock: &dyn Clock,
+        lengths: &RollLengths,
+    ) -> Result<AdPreview, String> {
+        Self::build_roll(engine, clock, is_hour_start(clock), lengths)
+    }
 
-        // Create a new sink for ad playback
-        let sink = player.create_sink()?;
+    /// Append plays at `now` to the engine's ad history, keeping the newest
+    /// entries.
+    fn record_plays(engine: &mut Engine, names: &[String], now: DateTime<Local>) {
+        engine
+            .ad_play_history
+            .extend(names.iter().map(|name| AdPlayRecord {
+                name: name.clone(),
+                at: now,
+            }));
+        let excess = engine.ad_play_history.len().saturating_sub(AD_HISTORY_MAX);
+        engine.ad_play_history.drain(..excess);
+    }
 
-        // Append station ID first if applicable
-        let station_id_played = if let Some(sid_path) = station_id_path {
-            append_to_sink(&sink, sid_path)?;
-            true
-        } else {
-            false
-        };
+    /// Instant ad insertion: play all valid ads (and optionally station
+    /// ID, see `sequence_roll`) on a new sink. `engine.ad_break_active` is
+    /// set until the returned roll has played out or is dropped; wait on it
+    /// with `InstantRoll::wait` after releasing any lock on the engine, so
+    /// other threads see the break while it airs.
+    ///
+    /// Returns the result describing what is playing, or an error.
+    pub fn insert_instant(
+        player: &Player,
+        engine: &mut Engine,
+        clock: &dyn Clock,
+        is_hour_start: bool,
+        lengths: &RollLengths,
+    ) -> Result<(AdInsertionResult, InstantRoll), String> {
+        let AdPreview { roll, result } = Self::build_roll(engine, clock, is_hour_start, lengths)?;
 
-        // Append each valid ad
-        let mut ads_inserted = Vec::new();
-        for ad in &valid_ads {
-            append_to_sink(&sink, &ad.mp3_file)?;
-            ads_inserted.push(ad.name.clone());
+        // Create a new sink for ad playback and queue the whole roll
+        let sink = player.create_sink()?;
+        for (path, _name) in &roll {
+            append_to_sink(&sink, path)?;
         }
 
-        // Block until all audio finishes
-        sink.play();
-        while !sink.empty() {
-            std::thread::sleep(Duration::from_millis(100));
-        }
+        // Now-playing shows the placeholder until the roll ends
+        let on_air = InstantRoll::start(sink, engine.ad_break_active.clone());
+        Self::record_plays(engine, &result.ads_inserted, clock.now());
 
-        Ok(AdInsertionResult {
-            ad_count: ads_inserted.len(),
-            ads_inserted,
-            station_id_played,
-        })
+        Ok((result, on_air))
     }
 
     /// Scheduled ad insertion: insert valid ads as next tracks in the
     /// active playlist. Inserts in reverse order so they play in the
-    /// correct sequence. Optionally prepends station ID.
+    /// correct sequence. Optionally adds the station ID at the start and/or
+    /// end of the roll (`station_id_position`).
+    ///
+    /// With `insert_timing` set to `Immediate`, the result's
+    /// `interrupt_current` asks the caller to end the playing track so the
+    /// roll starts at the next track change rather than after the song.
+    ///
+    /// When `engine.no_ads_during_lectures` is set and the current track is
+    /// a lecture, nothing is inserted and this cycle is skipped.
+    ///
+    /// Dayparts, flight dates and the repeat gap are judged at `clock`'s
+    /// time.
     ///
     /// Returns the result describing what was inserted, or an error.
     pub fn insert_scheduled(
         engine: &mut Engine,
+        clock: &dyn Clock,
         is_hour_start: bool,
+        lengths: &RollLengths,
     ) -> Result<AdInsertionResult, String> {
         if engine.active_playlist().is_none() {
             return Err("No active playlist".to_string());
         }
 
-        let valid_ads = Self::collect_valid_ads(&engine.ads);
-        if valid_ads.is_empty() {
-            return Err("No valid ads to insert".to_string());
+        if engine.no_ads_during_lectures && Self::current_is_lecture(engine) {
+            return Err("Ad insertion deferred: lecture in progress".to_string());
         }
 
         // Build the list of files to insert (in playback order)
-        let mut insertion_files: Vec<(PathBuf, String)> = Vec::new();
-
-        // Station ID first if applicable
-        let station_id_played = if is_hour_start
-            && engine.ad_inserter.station_id_enabled
-        {
-            if let Some(sid_path) = &engine.ad_inserter.station_id_file {
-                if sid_path.exists() {
-                    insertion_files.push((sid_path.clone(), "Station ID".to_string()));
-                    true
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        } else {
-            false
-        };
-
-        // Then each valid ad
-        let mut ads_inserted = Vec::new();
-        for ad in &valid_ads {
-            insertion_files.push((ad.mp3_file.clone(), ad.name.clone()));
-            ads_inserted.push(ad.name.clone());
-        }
+        let AdPreview {
+            roll: insertion_files,
+            mut result,
+        } = Self::build_roll(engine, clock, is_hour_start, lengths)?;
+        result.interrupt_current = engine.ad_inserter.insert_timing == AdInsertTiming::Immediate;
 
         // Insert in reverse order so they end up in the correct sequence
-        // (each insert_next_track places the track right after current)
+        // (each Next insert places the track right after current)
         for (path, _name) in insertion_files.iter().rev() {
-            engine.insert_next_track(path)?;
+            engine.insert_track_at(path, InsertPosition::Next)?;
         }
+        Self::record_plays(engine, &result.ads_inserted, clock.now());
 
-        Ok(AdInsertionResult {
-            ad_count: ads_inserted.len(),
-            ads_inserted,
-            station_id_played,
-        })
+        Ok(result)
+    }
+
+    /// Whether the active playlist's current track is classified as a lecture.
+    fn current_is_lecture(engine: &Engine) -> bool {
+        engine
+            .active_playlist()
+            .and_then(|pl| pl.current_index.and_then(|i| pl.tracks.get(i)))
+            .is_some_and(|t| engine.lecture_detector.is_lecture(&t.artist))
     }
 
-    /// Dispatch to the appropriate insertion mode.
+    /// Check with the `AdRollArbiter` that no high-priority scheduled event
+    /// fires within `event_guard_secs` of `clock`'s time.
+    pub fn check_schedule_clear(engine: &Engine, clock: &dyn Clock) -> Result<(), String> {
+        let arbiter = AdRollArbiter::new(engine.ad_inserter.event_guard_secs);
+        match arbiter.check(&engine.schedule, clock.now().naive_local()) {
+            AdRollDecision::Proceed => Ok(()),
+            AdRollDecision::Defer {
+                event_id,
+                event_time,
+            } => Err(format!(
+                "Ad roll deferred: scheduled event {} fires at {}",
+                event_id,
+                event_time.format("%H:%M:%S")
+            )),
+        }
+    }
+
+    /// Check that a scheduled break can run at `clock`'s time: there is an
+    /// active playlist, no lecture is airing (when ads avoid lectures), no
+    /// priority event is near, and some ad is valid now. An error here is a
+    /// deferral rather than a failed break.
+    pub fn check_break_can_run(engine: &Engine, clock: &dyn Clock) -> Result<(), String> {
+        if engine.active_playlist().is_none() {
+            return Err("No active playlist".to_string());
+        }
+        if engine.no_ads_during_lectures && Self::current_is_lecture(engine) {
+            return Err("Ad insertion deferred: lecture in progress".to_string());
+        }
+        Self::check_schedule_clear(engine, clock)?;
+        if Self::collect_valid_ads(&engine.ads, clock).is_empty() {
+            return Err("No valid ads to insert".to_string());
+        }
+        Ok(())
+    }
+
+    /// Dispatch to the appropriate insertion mode at `clock`'s time. Either
+    /// mode is deferred (returns an error) while a high-priority scheduled
+    /// event is near.
     pub fn run_insertion(
         player: &Player,
         engine: &mut Engine,
         mode: AdInsertionMode,
+        clock: &dyn Clock,
         is_hour_start: bool,
+        lengths: &RollLengths,
     ) -> Result<AdInsertionResult, String> {
+        Self::check_schedule_clear(engine, clock)?;
         match mode {
-            AdInsertionMode::Instant => Self::insert_instant(player, engine, is_hour_start),
-            AdInsertionMode::Scheduled => Self::insert_scheduled(engine, is_hour_start),
+            AdInsertionMode::Instant => {
+                let (result, roll) =
+                    Self::insert_instant(player, engine, clock, is_hour_start, lengths)?;
+                roll.wait();
+                Ok(result)
+            }
+            AdInsertionMode::Scheduled => {
+                Self::insert_scheduled(engine, clock, is_hour_start, lengths)
+            }
         }
     }
 }
 
+/// An instant ad roll on air on its own sink. Dropping it cuts the roll
+/// and clears the engine's `ad_break_active` flag.
+pub struct InstantRoll {
+    sink: Sink,
+    on_air: Arc<AtomicBool>,
+}
+
+impl InstantRoll {
+    fn start(sink: Sink, on_air: Arc<AtomicBool>) -> Self {
+        on_air.store(true, Ordering::Relaxed);
+        sink.play();
+        InstantRoll { sink, on_air }
+    }
+
+    /// Block until the whole roll has played.
+    pub fn wait(self) {
+        while !self.sink.empty() {
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    }
+}
+
+impl Drop for InstantRoll {
+    fn drop(&mut self) {
+        self.on_air.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Decoded length of `path`, zero if it can't be read.
+fn measured_length(path: &Path) -> Duration {
+    crate::track::decoded_duration(path).unwrap_or_default()
+}
+
+/// Index of the ad whose last play in `history` is oldest (never-played first).
+fn least_recent(ads: &[&AdConfig], history: &[AdPlayRecord]) -> usize {
+    ads.iter()
+        .enumerate()
+        .min_by_key(|(_, ad)| history.iter().rposition(|r| r.name == ad.name))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
 /// Decode an audio file and append it to a sink.
 fn append_to_sink(sink: &Sink, path: &Path) -> Result<(), String> {
     let file = File::open(path)
@@ -187,12 +547,12 @@ fn append_to_sink(sink: &Sink, path: &Path) -> Result<(), String> {
     Ok(())
 }
 
-use chrono::Timelike;
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ad_scheduler::AdConfig;
+    use crate::clock::RealClock;
+    use crate::test_audio::write_silence;
 
     fn make_ad(name: &str, enabled: bool, scheduled: bool) -> AdConfig {
         AdConfig {
@@ -202,6 +562,9 @@ mod tests {
             scheduled,
             days: vec![],
             hours: vec![],
+            normalized_file: None,
+            start_date: None,
+            end_date: None,
         }
     }
 
@@ -218,6 +581,9 @@ mod tests {
             scheduled: true,
             days,
             hours,
+            normalized_file: None,
+            start_date: None,
+            end_date: None,
         }
     }
 
@@ -263,12 +629,36 @@ mod tests {
         assert!(ad.is_scheduled_for("Friday", 23));
     }
 
+    #[test]
+    fn collect_valid_ads_skips_ads_outside_flight_dates() {
+        use crate::clock::FixedClock;
+        use chrono::{NaiveDate, TimeZone};
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("january.mp3");
+        std::fs::write(&file, b"fake").unwrap();
+        let mut ad = make_ad("January", true, false);
+        ad.mp3_file = file;
+        ad.start_date = NaiveDate::from_ymd_opt(2026, 1, 1);
+        ad.end_date = NaiveDate::from_ymd_opt(2026, 1, 31);
+        let ads = vec![ad];
+
+        let valid_on = |month, day| {
+            let clock = FixedClock(Local.with_ymd_and_hms(2026, month, day, 9, 0, 0).unwrap());
+            AdInserterService::collect_valid_ads(&ads, &clock).len()
+        };
+        assert_eq!(valid_on(1, 15), 1);
+        assert_eq!(valid_on(1, 31), 1);
+        assert_eq!(valid_on(2, 1), 0);
+        assert_eq!(valid_on(12, 31), 0);
+    }
+
     // --- insert_scheduled tests ---
 
     #[test]
     fn insert_scheduled_returns_error_when_no_active_playlist() {
         let mut engine = Engine::new();
-        let result = AdInserterService::insert_scheduled(&mut engine, false);
+        let result = insert_now(&mut engine, &RealClock);
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("No active playlist"));
     }
@@ -279,19 +669,78 @@ mod tests {
         engine.create_playlist("Main".to_string());
         engine.set_active("Main").unwrap();
         // No ads configured
-        let result = AdInserterService::insert_scheduled(&mut engine, false);
+        let result = insert_now(&mut engine, &RealClock);
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("No valid ads"));
     }
 
+    fn engine_playing(artist: &str) -> Engine {
+        let mut engine = Engine::new();
+        engine.create_playlist("Main".to_string());
+        engine.set_active("Main").unwrap();
+        let pl = engine.active_playlist_mut().unwrap();
+        pl.tracks.push(crate::track::Track {
+            path: PathBuf::from("current.mp3"),
+            title: "Current".into(),
+            artist: artist.into(),
+            duration: Duration::from_secs(60),
+            ..Default::default()
+        });
+        pl.current_index = Some(0);
+        engine
+    }
+
+    #[test]
+    fn insert_scheduled_deferred_during_lecture() {
+        let mut engine = engine_playing("Rabbi Cohen");
+        engine.no_ads_during_lectures = true;
+        let result = insert_now(&mut engine, &RealClock);
+        assert!(result.unwrap_err().contains("lecture"));
+        assert_eq!(engine.active_playlist().unwrap().tracks.len(), 1);
+    }
+
+    #[test]
+    fn insert_scheduled_proceeds_during_music() {
+        let mut engine = engine_playing("The Beatles");
+        engine.no_ads_during_lectures = true;
+        // Guard passes; fails later only because no ads are configured
+        let result = insert_now(&mut engine, &RealClock);
+        assert!(result.unwrap_err().contains("No valid ads"));
+    }
+
+    #[test]
+    fn insert_scheduled_ignores_lecture_when_flag_off() {
+        let mut engine = engine_playing("Rabbi Cohen");
+        let result = insert_now(&mut engine, &RealClock);
+        assert!(result.unwrap_err().contains("No valid ads"));
+    }
+
+    #[test]
+    fn check_break_can_run_reports_deferrals() {
+        let dir = tempfile::tempdir().unwrap();
+        let check = |engine: &Engine| AdInserterService::check_break_can_run(engine, &RealClock);
+        assert!(check(&Engine::new()).is_err());
+
+        let mut engine = engine_with_ad_file(dir.path(), AdInsertTiming::AfterCurrent);
+        assert_eq!(check(&engine), Ok(()));
+
+        engine.active_playlist_mut().unwrap().tracks[0].artist = "Rabbi Cohen".into();
+        engine.no_ads_during_lectures = true;
+        assert!(check(&engine).unwrap_err().contains("lecture"));
+
+        engine.no_ads_during_lectures = false;
+        engine.ads.clear();
+        assert!(check(&engine).unwrap_err().contains("No valid ads"));
+    }
+
     #[test]
     fn insert_scheduled_inserts_in_reverse_order() {
         // This test verifies the logic by checking that tracks are inserted
-        // after the current position. We need real files for insert_next_track
+        // after the current position. We need real files for insert_track_at
         // to work (Track::from_path requires a real file), so we test the
         // ordering logic conceptually.
         //
-        // The key insight: insert_next_track always inserts at current_index + 1.
+        // The key insight: a Next insert always lands at current_index + 1.
         // If we insert [A, B, C] in reverse order (C, B, A), they end up as:
         //   current -> A -> B -> C -> rest_of_playlist
         // Which is the correct playback order.
@@ -340,6 +789,36 @@ mod tests {
         assert!(!engine2.ad_inserter.station_id_enabled);
     }
 
+    fn roll_labels(roll: &[(PathBuf, String)]) -> Vec<&str> {
+        roll.iter().map(|(_, name)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn sequence_roll_bookends_ads_with_both() {
+        let (a, b) = (make_ad("A", true, false), make_ad("B", true, false));
+        let sid = Path::new("station.mp3");
+        let roll = AdInserterService::sequence_roll(Some(sid), &[&a, &b], StationIdPosition::Both);
+        assert_eq!(roll_labels(&roll), ["Station ID", "A", "B", "Station ID"]);
+        assert_eq!(roll[0].0, sid);
+        assert_eq!(roll[3].0, sid);
+    }
+
+    #[test]
+    fn sequence_roll_places_single_id_at_start_or_end() {
+        let (a, b) = (make_ad("A", true, false), make_ad("B", true, false));
+        let sid = Some(Path::new("station.mp3"));
+
+        let start = AdInserterService::sequence_roll(sid, &[&a, &b], StationIdPosition::Start);
+        assert_eq!(roll_labels(&start), ["Station ID", "A", "B"]);
+
+        let end = AdInserterService::sequence_roll(sid, &[&a, &b], StationIdPosition::End);
+        assert_eq!(roll_labels(&end), ["A", "B", "Station ID"]);
+
+        // No station ID this break: ads only, whatever the position
+        let none = AdInserterService::sequence_roll(None, &[&a, &b], StationIdPosition::Both);
+        assert_eq!(roll_labels(&none), ["A", "B"]);
+    }
+
     #[test]
     fn run_insertion_dispatches_to_correct_mode() {
         // Verify that run_insertion calls the right method based on mode.
@@ -354,7 +833,9 @@ mod tests {
                 &player,
                 &mut engine,
                 AdInsertionMode::Scheduled,
+                &RealClock,
                 false,
+                &RollLengths::default(),
             );
             assert!(result.is_err());
             assert!(result.unwrap_err().contains("No active playlist"));
@@ -362,6 +843,31 @@ mod tests {
         // If Player::new() fails (no audio device), test passes trivially
     }
 
+    #[test]
+    fn ad_roll_deferred_near_priority_event() {
+        use crate::clock::FixedClock;
+        use crate::scheduler::{Priority, ScheduleMode, parse_time};
+        use chrono::TimeZone;
+
+        let mut engine = Engine::new();
+        let clock = FixedClock(Local.with_ymd_and_hms(2026, 10, 12, 7, 59, 0).unwrap());
+        assert!(AdInserterService::check_schedule_clear(&engine, &clock).is_ok());
+
+        engine.schedule.add_event(
+            parse_time("08:00").unwrap(),
+            ScheduleMode::Stop,
+            "news.mp3".into(),
+            Priority(9),
+            Some("News".into()),
+            vec![],
+        );
+        let err = AdInserterService::check_schedule_clear(&engine, &clock).unwrap_err();
+        assert!(err.contains("deferred"));
+
+        engine.ad_inserter.event_guard_secs = 0;
+        assert!(AdInserterService::check_schedule_clear(&engine, &clock).is_ok());
+    }
+
     // --- AdInsertionResult tests ---
 
     #[test]
@@ -370,6 +876,11 @@ mod tests {
             ad_count: 3,
             ads_inserted: vec!["Ad1".into(), "Ad2".into(), "Ad3".into()],
             station_id_played: true,
+            forced_repeats: vec![],
+            ad_durations: vec![],
+            station_id_duration: None,
+            total_duration: Duration::ZERO,
+            interrupt_current: false,
         };
         assert_eq!(result.ad_count, 3);
         assert_eq!(result.ads_inserted.len(), 3);
@@ -382,9 +893,280 @@ mod tests {
             ad_count: 0,
             ads_inserted: vec![],
             station_id_played: false,
+            forced_repeats: vec![],
+            ad_durations: vec![],
+            station_id_duration: None,
+            total_duration: Duration::ZERO,
+            interrupt_current: false,
         };
         assert_eq!(result.ad_count, 0);
         assert!(result.ads_inserted.is_empty());
         assert!(!result.station_id_played);
     }
+
+    #[test]
+    fn instant_roll_flags_the_break_until_it_ends() {
+        let engine = Engine::new();
+        let (sink, _output) = Sink::new_idle();
+        let roll = InstantRoll::start(sink, engine.ad_break_active.clone());
+        // Visible without the engine, e.g. to the now-playing thread
+        let flag = engine.ad_break_active.clone();
+        let seen = std::thread::spawn(move || flag.load(Ordering::Relaxed));
+        assert!(seen.join().unwrap());
+
+        roll.wait();
+        assert!(!engine.ad_break_active.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn ad_insertion_result_times_each_ad_and_the_roll() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut a = make_ad("A", true, false);
+        let mut b = make_ad("B", true, false);
+        a.mp3_file = dir.path().join("a.wav");
+        b.mp3_file = dir.path().join("b.wav");
+        let sid = dir.path().join("id.wav");
+        write_silence(&a.mp3_file, 2);
+        write_silence(&b.mp3_file, 3);
+        write_silence(&sid, 1);
+
+        let lengths = RollLengths::measure(&[a.mp3_file.clone(), b.mp3_file.clone(), sid.clone()]);
+        let result = AdInsertionResult::for_roll(
+            Some(&sid),
+            &[&a, &b],
+            StationIdPosition::Both,
+            vec![],
+            &lengths,
+        );
+        assert_eq!(result.ads_inserted, ["A", "B"]);
+        assert_eq!(
+            result.ad_durations,
+            [Duration::from_secs(2), Duration::from_secs(3)]
+        );
+        assert_eq!(result.station_id_duration, Some(Duration::from_secs(1)));
+        // Bookended: the station ID plays twice
+        assert_eq!(result.total_duration, Duration::from_secs(7));
+
+        let no_id =
+            AdInsertionResult::for_roll(None, &[&a, &b], StationIdPosition::Both, vec![], &lengths);
+        assert!(!no_id.station_id_played);
+        assert_eq!(no_id.station_id_duration, None);
+        assert_eq!(no_id.total_duration, Duration::from_secs(5));
+    }
+
+    /// Scheduled insert at `clock`'s time, decoding the ads as it goes.
+    fn insert_now(engine: &mut Engine, clock: &dyn Clock) -> Result<AdInsertionResult, String> {
+        AdInserterService::insert_scheduled(engine, clock, false, &RollLengths::default())
+    }
+
+    fn engine_with_ad_file(dir: &Path, timing: AdInsertTiming) -> Engine {
+        let mut engine = engine_playing("The Beatles");
+        let mut next = engine.active_playlist().unwrap().tracks[0].clone();
+        next.path = PathBuf::from("next.mp3");
+        engine.active_playlist_mut().unwrap().tracks.push(next);
+        let mut ad = make_ad("Spot", true, false);
+        ad.mp3_file = dir.join("spot.wav");
+        write_silence(&ad.mp3_file, 1);
+        engine.ads.push(ad);
+        engine.ad_inserter.insert_timing = timing;
+        engine
+    }
+
+    fn playlist_paths(engine: &Engine) -> Vec<String> {
+        let pl = engine.active_playlist().unwrap();
+        let name = |t: &crate::track::Track| t.path.file_name().unwrap().to_string_lossy().into();
+        pl.tracks.iter().map(name).collect()
+    }
+
+    #[test]
+    fn insert_scheduled_after_current_queues_roll_behind_song() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut engine = engine_with_ad_file(dir.path(), AdInsertTiming::AfterCurrent);
+        let result = insert_now(&mut engine, &RealClock).unwrap();
+        let expected = ["current.mp3", "spot.wav", "next.mp3"];
+        assert_eq!(playlist_paths(&engine), expected);
+        assert_eq!(engine.active_playlist().unwrap().current_index, Some(0));
+        assert!(!result.interrupt_current);
+    }
+
+    #[test]
+    fn insert_scheduled_immediate_also_interrupts_current() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut engine = engine_with_ad_file(dir.path(), AdInsertTiming::Immediate);
+        let result = insert_now(&mut engine, &RealClock).unwrap();
+        // Same queue position; the song is flagged to end at the next change
+        let expected = ["current.mp3", "spot.wav", "next.mp3"];
+        assert_eq!(playlist_paths(&engine), expected);
+        assert!(result.interrupt_current);
+    }
+
+    #[test]
+    fn insert_scheduled_judges_dayparts_and_history_by_the_given_clock() {
+        use crate::clock::FixedClock;
+        use chrono::TimeZone;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut engine = engine_with_ad_file(dir.path(), AdInsertTiming::AfterCurrent);
+        let ad = &mut engine.ads[0];
+        ad.scheduled = true;
+        ad.days = vec!["Monday".into()];
+        ad.hours = vec![9];
+        // 2026-10-12 is a Monday
+        let monday = FixedClock(Local.with_ymd_and_hms(2026, 10, 12, 9, 15, 0).unwrap());
+        let tuesday = FixedClock(Local.with_ymd_and_hms(2026, 10, 13, 9, 15, 0).unwrap());
+
+        let skipped = insert_now(&mut engine, &tuesday);
+        assert!(skipped.unwrap_err().contains("No valid ads"));
+        let result = insert_now(&mut engine, &monday).unwrap();
+        assert_eq!(result.ads_inserted, ["Spot"]);
+        assert_eq!(engine.ad_play_history[0].at, monday.now());
+    }
+
+    #[test]
+    fn preview_matches_insert_without_recording_plays() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut engine = engine_with_ad_file(dir.path(), AdInsertTiming::AfterCurrent);
+        let mut second = make_ad("Second", true, false);
+        second.mp3_file = dir.path().join("second.wav");
+        write_silence(&second.mp3_file, 2);
+        engine.ads.push(second);
+        engine.ad_inserter.min_repeat_gap = RepeatGap::Ads(1);
+        engine.ad_play_history.push(AdPlayRecord {
+            name: "Spot".into(),
+            at: Local::now(),
+        });
+
+        let preview =
+            AdInserterService::preview(&engine, &RealClock, &RollLengths::default()).unwrap();
+        let labels = roll_labels(&preview.roll);
+        assert_eq!(labels, ["Second", "Spot"]);
+        assert_eq!(preview.result.total_duration, Duration::from_secs(3));
+        assert_eq!(engine.ad_play_history.len(), 1);
+        assert_eq!(playlist_paths(&engine), ["current.mp3", "next.mp3"]);
+
+        let inserted = insert_now(&mut engine, &RealClock).unwrap();
+        assert_eq!(inserted.ads_inserted, preview.result.ads_inserted);
+        assert_eq!(engine.ad_play_history.len(), 3);
+    }
+
+    #[test]
+    fn roll_uses_lengths_measured_beforehand() {
+        let dir = tempfile::tempdir().unwrap();
+        let engine = engine_with_ad_file(dir.path(), AdInsertTiming::AfterCurrent);
+        let candidates = AdInserterService::roll_candidates(&engine, &RealClock);
+        assert_eq!(candidates, [engine.ads[0].mp3_file.clone()]);
+
+        // A length already in the map is taken as is, not decoded again
+        let lengths = RollLengths(HashMap::from([(
+            candidates[0].clone(),
+            Duration::from_secs(30),
+        )]));
+        let preview = AdInserterService::preview(&engine, &RealClock, &lengths).unwrap();
+        assert_eq!(preview.result.total_duration, Duration::from_secs(30));
+        let measured = RollLengths::measure(&candidates);
+        let preview = AdInserterService::preview(&engine, &RealClock, &measured).unwrap();
+        assert_eq!(preview.result.total_duration, Duration::from_secs(1));
+    }
+
+    #[test]
+    fn preview_without_valid_ads_is_an_error() {
+        let engine = engine_playing("The Beatles");
+        let result = AdInserterService::preview(&engine, &RealClock, &RollLengths::default());
+        assert!(result.unwrap_err().contains("No valid ads"));
+    }
+
+    // --- repeat gap tests ---
+
+    fn run_breaks(ads: &[AdConfig], gap: RepeatGap, breaks: usize) -> (Vec<String>, usize) {
+        let refs: Vec<&AdConfig> = ads.iter().collect();
+        let now = Local::now();
+        let mut history: Vec<AdPlayRecord> = Vec::new();
+        let mut forced_total = 0;
+        for _ in 0..breaks {
+            let (selected, forced) =
+                AdInserterService::select_with_repeat_gap(&refs, &history, gap, now);
+            forced_total += forced.len();
+            history.extend(selected.iter().map(|ad| AdPlayRecord {
+                name: ad.name.clone(),
+                at: now,
+            }));
+        }
+        (history.into_iter().map(|r| r.name).collect(), forced_total)
+    }
+
+    #[test]
+    fn repeat_gap_two_with_three_ads_never_recurs_within_two() {
+        let ads = vec![
+            make_ad("A", true, false),
+            make_ad("B", true, false),
+            make_ad("C", true, false),
+        ];
+        let (played, forced) = run_breaks(&ads, RepeatGap::Ads(2), 5);
+        assert_eq!(forced, 0);
+        assert_eq!(played.len(), 15);
+        for (i, name) in played.iter().enumerate() {
+            let window = &played[i.saturating_sub(2)..i];
+            assert!(!window.contains(name), "{} repeated at {}", name, i);
+        }
+    }
+
+    #[test]
+    fn repeat_gap_skips_recent_ad_when_alternative_exists() {
+        let ads = [make_ad("A", true, false), make_ad("B", true, false)];
+        let refs: Vec<&AdConfig> = ads.iter().collect();
+        let history = vec![AdPlayRecord {
+            name: "A".into(),
+            at: Local::now(),
+        }];
+        let (selected, forced) = AdInserterService::select_with_repeat_gap(
+            &refs,
+            &history,
+            RepeatGap::Minutes(30),
+            Local::now(),
+        );
+        let names: Vec<&str> = selected.iter().map(|ad| ad.name.as_str()).collect();
+        assert_eq!(names, vec!["B"]);
+        assert!(forced.is_empty());
+    }
+
+    #[test]
+    fn repeat_gap_allows_forced_repeat_when_unavoidable() {
+        let ads = vec![make_ad("Only", true, false)];
+        let (played, forced) = run_breaks(&ads, RepeatGap::Ads(2), 3);
+        assert_eq!(played, vec!["Only", "Only", "Only"]);
+        assert_eq!(forced, 2);
+    }
+
+    // --- roll length tests ---
+
+    fn fit_names(lengths: &[u64], budget: u64, fit_best: bool) -> Vec<String> {
+        let ads: Vec<AdConfig> = (0..lengths.len())
+            .map(|i| make_ad(&format!("Ad{}", i), true, false))
+            .collect();
+        let refs: Vec<&AdConfig> = ads.iter().collect();
+        let lengths: Vec<Duration> = lengths.iter().map(|&s| Duration::from_secs(s)).collect();
+        AdInserterService::fit_to_roll(&refs, &lengths, Duration::from_secs(budget), fit_best)
+            .iter()
+            .map(|ad| ad.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn fit_best_fills_tail_with_shorter_ad() {
+        // 30 + 30 fills 60 of 75; the next 30s ad overruns, the 15s one fits
+        let names = fit_names(&[30, 30, 30, 15], 75, true);
+        assert_eq!(names, vec!["Ad0", "Ad1", "Ad3"]);
+    }
+
+    #[test]
+    fn roll_stops_at_first_overrun_without_fit_best() {
+        let names = fit_names(&[30, 30, 30, 15], 75, false);
+        assert_eq!(names, vec!["Ad0", "Ad1"]);
+    }
+
+    #[test]
+    fn fit_to_roll_keeps_first_ad_even_if_too_long() {
+        assert_eq!(fit_names(&[90, 10], 60, false), vec!["Ad0"]);
+        assert_eq!(fit_names(&[90, 10], 60, true), vec!["Ad0"]);
+    }
 }
@@ -1,4 +1,4 @@
-use chrono::Local;
+use chrono::{Local, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -24,9 +24,12 @@ pub type AdPlayData = HashMap<String, HashMap<String, Vec<u8>>>;
 pub struct AdPlayLogger {
     plays_path: PathBuf,
     failures_path: PathBuf,
+    report_audit_path: PathBuf,
+    max_failures: usize,
 }
 
-const MAX_FAILURES: usize = 50;
+/// Failure records kept by default (see `with_max_failures`).
+pub const DEFAULT_MAX_FAILURES: usize = 50;
 
 impl AdPlayLogger {
     /// Create a new logger storing files in the given directory.
@@ -34,9 +37,33 @@ impl AdPlayLogger {
         Self {
             plays_path: directory.join("ad_plays.json"),
             failures_path: directory.join("ad_failures.json"),
+            report_audit_path: directory.join("ad_report_audit.jsonl"),
+            max_failures: DEFAULT_MAX_FAILURES,
         }
     }
 
+    /// Keep only the newest `max` failures when one is logged.
+    pub fn with_max_failures(mut self, max: usize) -> Self {
+        self.max_failures = max;
+        self
+    }
+
+    /// Path of the per-ad play log (`ad_plays.json`).
+    pub fn plays_path(&self) -> &Path {
+        &self.plays_path
+    }
+
+    /// Path of the failure log (`ad_failures.json`).
+    pub fn failures_path(&self) -> &Path {
+        &self.failures_path
+    }
+
+    /// Path of the report delivery log (`ad_report_audit.jsonl`), one JSON
+    /// entry per generated report (see `AdReportGenerator::audit_history`).
+    pub fn report_audit_path(&self) -> &Path {
+        &self.report_audit_path
+    }
+
     /// Record a play for the given ad at the current date and hour.
     pub fn log_play(&self, ad_name: &str) {
         let now = Local::now();
@@ -63,7 +90,7 @@ impl AdPlayLogger {
         self.save_plays(&data);
     }
 
-    /// Record a failure. Trims to MAX_FAILURES (oldest discarded).
+    /// Record a failure. Trims to the retention cap (oldest discarded).
     pub fn log_failure(&self, ad_names: &[String], error: &str) {
         let now = Local::now();
         let timestamp = now.format("%m-%d-%y %H:%M").to_string();
@@ -74,11 +101,9 @@ impl AdPlayLogger {
             ads: ad_names.to_vec(),
             err: error.to_string(),
         });
-        // Keep only the most recent MAX_FAILURES
-        if failures.len() > MAX_FAILURES {
-            let excess = failures.len() - MAX_FAILURES;
-            failures.drain(..excess);
-        }
+        // Keep only the most recent `max_failures`
+        let excess = failures.len().saturating_sub(self.max_failures);
+        failures.drain(..excess);
         self.save_failures(&failures);
     }
 
@@ -120,6 +145,31 @@ impl AdPlayLogger {
             .unwrap_or_default()
     }
 
+    /// Daily play counts for an ad over every date from `start` to `end`
+    /// (inclusive, MM-DD-YY), in date order. Days without plays count as 0.
+    pub fn get_daily_play_counts_filled(
+        &self,
+        ad_name: &str,
+        start: &str,
+        end: &str,
+    ) -> Result<Vec<(String, usize)>, String> {
+        let first = parse_mm_dd_yy(start)?;
+        let last = parse_mm_dd_yy(end)?;
+        if last < first {
+            return Err(format!("End date {} is before start date {}", end, start));
+        }
+        let counts = self.get_daily_play_counts(ad_name);
+        Ok(first
+            .iter_days()
+            .take_while(|day| *day <= last)
+            .map(|day| {
+                let key = day.format("%m-%d-%y").to_string();
+                let count = counts.get(&key).copied().unwrap_or(0);
+                (key, count)
+            })
+            .collect())
+    }
+
     /// Get all failure records.
     pub fn get_failures(&self) -> Vec<AdFailure> {
         self.load_failures()
@@ -177,6 +227,34 @@ impl AdPlayLogger {
         result
     }
 
+    /// Plays of `ad_name` per clock hour (index 0–23), summed over every
+    /// date in `start`..=`end` (MM-DD-YY).
+    pub fn get_hour_histogram(&self, ad_name: &str, start: &str, end: &str) -> [usize; 24] {
+        let data = self.load_plays();
+        let filtered = Self::filter_by_date_range(&data, start, end);
+        let mut histogram = [0usize; 24];
+        if let Some(dates) = filtered.get(ad_name) {
+            for &hour in dates.values().flatten() {
+                if let Some(slot) = histogram.get_mut(hour as usize) {
+                    *slot += 1;
+                }
+            }
+        }
+        histogram
+    }
+
+    /// Trim the saved failures to the newest `keep`. Returns how many were
+    /// removed; the file is only rewritten when something was.
+    pub fn prune_failures(&self, keep: usize) -> usize {
+        let mut failures = self.load_failures();
+        let excess = failures.len().saturating_sub(keep);
+        if excess > 0 {
+            failures.drain(..excess);
+            self.save_failures(&failures);
+        }
+        excess
+    }
+
     /// Clear all play data and failures.
     pub fn reset_all(&self) {
         self.save_plays(&HashMap::new());
@@ -263,6 +341,12 @@ fn mm_dd_yy_to_iso(date: &str) -> String {
     }
 }
 
+/// Parse an MM-DD-YY date key.
+fn parse_mm_dd_yy(date: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%m-%d-%y")
+        .map_err(|_| format!("Invalid date '{}' (expected MM-DD-YY)", date))
+}
+
 /// Load JSON from a file, returning a default value on missing/corrupt files.
 fn load_json_or_default<T: for<'de> Deserialize<'de> + Default>(path: &Path) -> T {
     match std::fs::read_to_string(path) {
@@ -340,12 +424,46 @@ mod tests {
         }
 
         let failures = logger.get_failures();
-        assert_eq!(failures.len(), MAX_FAILURES);
+        assert_eq!(failures.len(), DEFAULT_MAX_FAILURES);
         // Oldest (0-4) should have been trimmed, first remaining should be #5
         assert_eq!(failures[0].ads[0], "Ad5");
         assert_eq!(failures[49].ads[0], "Ad54");
     }
 
+    #[test]
+    fn log_failure_honours_configured_cap() {
+        let (logger, _dir) = temp_logger();
+        let logger = logger.with_max_failures(3);
+        for i in 0..5 {
+            logger.log_failure(&[format!("Ad{}", i)], "error");
+        }
+
+        let kept: Vec<String> = logger
+            .get_failures()
+            .into_iter()
+            .map(|f| f.ads[0].clone())
+            .collect();
+        assert_eq!(kept, ["Ad2", "Ad3", "Ad4"]);
+    }
+
+    #[test]
+    fn prune_failures_trims_existing_list() {
+        let (logger, _dir) = temp_logger();
+        for i in 0..10 {
+            logger.log_failure(&[format!("Ad{}", i)], "error");
+        }
+
+        assert_eq!(logger.prune_failures(4), 6);
+        let failures = logger.get_failures();
+        assert_eq!(failures.len(), 4);
+        assert_eq!(failures[0].ads[0], "Ad6");
+        assert_eq!(failures[3].ads[0], "Ad9");
+
+        // Already within the cap: nothing to do
+        assert_eq!(logger.prune_failures(4), 0);
+        assert_eq!(logger.get_failures().len(), 4);
+    }
+
     #[test]
     fn get_ad_statistics_returns_sorted() {
         let (logger, _dir) = temp_logger();
@@ -393,6 +511,39 @@ mod tests {
         assert_eq!(counts["01-16-26"], 1);
     }
 
+    #[test]
+    fn get_daily_play_counts_filled_covers_whole_range() {
+        let (logger, _dir) = temp_logger();
+        logger.log_play_at("Ad A", "01-15-26", 9);
+        logger.log_play_at("Ad A", "01-15-26", 10);
+        logger.log_play_at("Ad A", "01-17-26", 14);
+        logger.log_play_at("Ad A", "01-20-26", 8); // outside range
+
+        let counts = logger
+            .get_daily_play_counts_filled("Ad A", "01-14-26", "01-18-26")
+            .unwrap();
+        let expected = vec![
+            ("01-14-26".to_string(), 0),
+            ("01-15-26".to_string(), 2),
+            ("01-16-26".to_string(), 0),
+            ("01-17-26".to_string(), 1),
+            ("01-18-26".to_string(), 0),
+        ];
+        assert_eq!(counts, expected);
+
+        // Year boundary keeps date order
+        let counts = logger
+            .get_daily_play_counts_filled("Ad A", "12-31-25", "01-01-26")
+            .unwrap();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[0].0, "12-31-25");
+
+        let reversed = logger.get_daily_play_counts_filled("Ad A", "01-18-26", "01-14-26");
+        assert!(reversed.is_err());
+        let iso = logger.get_daily_play_counts_filled("Ad A", "2026-01-14", "01-18-26");
+        assert!(iso.is_err());
+    }
+
     #[test]
     fn get_play_hours_for_date() {
         let (logger, _dir) = temp_logger();
@@ -428,6 +579,25 @@ mod tests {
         assert_eq!(stats["2026-01-15_14"]["Ad A"], 1);
     }
 
+    #[test]
+    fn hour_histogram_sums_plays_across_dates() {
+        let (logger, _dir) = temp_logger();
+        logger.log_play_at("Ad A", "01-15-26", 9);
+        logger.log_play_at("Ad A", "01-16-26", 9);
+        logger.log_play_at("Ad A", "01-17-26", 14);
+        logger.log_play_at("Ad B", "01-16-26", 9);
+        logger.log_play_at("Ad A", "01-25-26", 9); // outside range
+
+        let histogram = logger.get_hour_histogram("Ad A", "01-10-26", "01-20-26");
+        assert_eq!(histogram[9], 2);
+        assert_eq!(histogram[14], 1);
+        assert_eq!(histogram.iter().sum::<usize>(), 3);
+        assert_eq!(
+            logger.get_hour_histogram("Ghost", "01-10-26", "01-20-26"),
+            [0; 24]
+        );
+    }
+
     #[test]
     fn reset_all_clears_both_files() {
         let (logger, _dir) = temp_logger();
@@ -1,6 +1,8 @@
 use crate::ad_logger::AdPlayLogger;
-use chrono::Local;
+use crate::ad_scheduler::AdConfig;
+use chrono::{Datelike, Local, NaiveDate};
 use printpdf::*;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
@@ -12,6 +14,43 @@ pub struct ReportResult {
     pub pdf_path: PathBuf,
 }
 
+/// One line of the report delivery log: which report was generated, for
+/// what period and company, into which files, and when.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportAuditEntry {
+    /// Local time the report was written ("YYYY-MM-DD HH:MM:SS").
+    pub generated_at: String,
+    pub ad_name: String,
+    /// Report period as requested (MM-DD-YY, inclusive).
+    pub start: String,
+    pub end: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub company: Option<String>,
+    pub files: Vec<String>,
+}
+
+/// Scheduled versus confirmed plays of one ad over a period, assuming one
+/// ad break per clock hour.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeliveryReport {
+    pub ad_name: String,
+    /// Report period as requested (MM-DD-YY, inclusive).
+    pub start: String,
+    pub end: String,
+    /// Hours in the period the ad was eligible for (flight dates and
+    /// day/hour schedule).
+    pub expected: usize,
+    /// Eligible hours with at least one confirmed play. Extra plays in an
+    /// hour don't make up for an hour that was missed.
+    pub actual: usize,
+    /// Eligible hours with no confirmed play (`expected - actual`).
+    pub shortfall: usize,
+    /// Eligible hours ("YYYY-MM-DD_HH") with no confirmed play.
+    pub missed_hours: Vec<String>,
+    /// Confirmed plays outside the ad's schedule; not counted in `actual`.
+    pub outside_schedule: usize,
+}
+
 /// Result of generating a multi-ad matrix report.
 #[derive(Debug)]
 pub struct MultiReportResult {
@@ -43,9 +82,113 @@ impl ReportFormat {
     }
 }
 
+/// Result of generating a weekly/monthly rollup report.
+#[derive(Debug)]
+pub struct RollupResult {
+    pub csv_path: PathBuf,
+    pub pdf_path: PathBuf,
+}
+
+/// Period size for rollup reports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RollupGranularity {
+    /// Calendar weeks starting on Monday.
+    Weekly,
+    /// Calendar months.
+    Monthly,
+}
+
+impl RollupGranularity {
+    pub fn from_str_loose(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "weekly" | "week" => Some(Self::Weekly),
+            "monthly" | "month" => Some(Self::Monthly),
+            _ => None,
+        }
+    }
+
+    /// Bucket key for a `YYYY-MM-DD` date: the week's Monday, or `YYYY-MM`.
+    fn bucket(&self, date_iso: &str) -> Option<String> {
+        let date = NaiveDate::parse_from_str(date_iso, "%Y-%m-%d").ok()?;
+        Some(match self {
+            Self::Weekly => {
+                let monday =
+                    date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+                monday.format("%Y-%m-%d").to_string()
+            }
+            Self::Monthly => date.format("%Y-%m").to_string(),
+        })
+    }
+
+    fn label(&self) -> &str {
+        match self {
+            Self::Weekly => "Weekly",
+            Self::Monthly => "Monthly",
+        }
+    }
+
+    fn period_header(&self) -> &str {
+        match self {
+            Self::Weekly => "Week Starting",
+            Self::Monthly => "Month",
+        }
+    }
+}
+
+/// Per-period play counts: ad names (columns) and one row per bucket.
+#[derive(Debug, Clone, PartialEq)]
+struct RollupTable {
+    names: Vec<String>,
+    rows: Vec<(String, Vec<usize>)>,
+}
+
+/// Station branding for PDF reports. Unset fields keep the built-in look.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReportBranding {
+    /// PNG or JPEG logo drawn at the top right of each PDF (None = no logo).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_path: Option<String>,
+    /// Table header background as 0–255 RGB (None = default dark blue).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header_rgb: Option<[u8; 3]>,
+}
+
+impl ReportBranding {
+    /// Background color for table header rows.
+    pub fn header_bg(&self) -> Rgb {
+        match self.header_rgb {
+            Some([r, g, b]) => Rgb { r: r as f32 / 255.0, g: g as f32 / 255.0, b: b as f32 / 255.0, icc_profile: None },
+            None => rgb_header_bg(),
+        }
+    }
+
+    /// The decoded logo, or None when unset, missing, or not a readable image.
+    pub fn load_logo(&self) -> Option<RawImage> {
+        let path = self.logo_path.as_deref()?;
+        let bytes = match std::fs::read(path) {
+            Ok(b) => b,
+            Err(e) => {
+                eprintln!("[Report] Logo '{}' unreadable, skipping: {}", path, e);
+                return None;
+            }
+        };
+        let mut warnings = Vec::new();
+        match RawImage::decode_from_bytes(&bytes, &mut warnings) {
+            Ok(image) => Some(image),
+            Err(e) => {
+                eprintln!("[Report] Logo '{}' could not be decoded, skipping: {}", path, e);
+                None
+            }
+        }
+    }
+}
+
 /// Generates verified-play reports from ad play data.
 pub struct AdReportGenerator<'a> {
     logger: &'a AdPlayLogger,
+    branding: ReportBranding,
+    /// Ad configurations, for schedule-aware reports (`delivery_report`).
+    ads: &'a [AdConfig],
 }
 
 /// Hourly play entry for reports.
@@ -65,7 +208,42 @@ struct DailyEntry {
 
 impl<'a> AdReportGenerator<'a> {
     pub fn new(logger: &'a AdPlayLogger) -> Self {
-        Self { logger }
+        Self { logger, branding: ReportBranding::default(), ads: &[] }
+    }
+
+    /// Use `ads`' schedules for `delivery_report`.
+    pub fn with_ads(mut self, ads: &'a [AdConfig]) -> Self {
+        self.ads = ads;
+        self
+    }
+
+    /// Use station branding (logo, header color) in PDF output.
+    pub fn with_branding(mut self, branding: ReportBranding) -> Self {
+        self.branding = branding;
+        self
+    }
+
+    /// Draw the branding logo into the top-right corner (at most 40×15 mm),
+    /// if one is configured and loads.
+    fn draw_logo(&self, doc: &mut PdfDocument, ops: &mut Vec<Op>) {
+        let Some(image) = self.branding.load_logo() else {
+            return;
+        };
+        let (max_w, max_h) = (40.0_f32, 15.0_f32);
+        let (w_px, h_px) = (image.width.max(1) as f32, image.height.max(1) as f32);
+        // Pick the dpi at which the image fills the box without overflowing it
+        let dpi = (w_px * 25.4 / max_w).max(h_px * 25.4 / max_h);
+        let (w_mm, h_mm) = (w_px * 25.4 / dpi, h_px * 25.4 / dpi);
+        let id = doc.add_image(&image);
+        ops.push(Op::UseXobject {
+            id,
+            transform: XObjectTransform {
+                translate_x: Some(Mm(190.0 - w_mm).into_pt()),
+                translate_y: Some(Mm(287.0 - h_mm).into_pt()),
+                dpi: Some(dpi),
+                ..Default::default()
+            },
+        });
     }
 
     /// Generate CSV and PDF reports for all ads with plays in the given period.
@@ -108,16 +286,16 @@ impl<'a> AdReportGenerator<'a> {
             let pdf_path = output_dir.join(format!("REPORT_{}_{}.pdf", safe_name, timestamp));
 
             let csv_content = self.build_csv(ad_name, start, end, &hourly_entries, &daily_entries, total_plays);
-            let _ = std::fs::write(&csv_path, csv_content);
-
             let pdf_bytes = self.build_pdf(ad_name, start, end, company_name, &hourly_entries, &daily_entries, total_plays);
-            let _ = std::fs::write(&pdf_path, pdf_bytes);
+            let written = write_report_files(&csv_path, csv_content, &pdf_path, pdf_bytes);
 
-            results.push(ReportResult {
+            let result = ReportResult {
                 ad_name: ad_name.clone(),
                 csv_path,
                 pdf_path,
-            });
+            };
+            self.audit_if_written(written, &result, start, end, company_name);
+            results.push(result);
         }
 
         results
@@ -149,15 +327,162 @@ impl<'a> AdReportGenerator<'a> {
         let pdf_path = output_dir.join(format!("REPORT_{}_{}.pdf", safe_name, timestamp));
 
         let csv_content = self.build_csv(ad_name, start, end, &hourly_entries, &daily_entries, total_plays);
-        let _ = std::fs::write(&csv_path, csv_content);
-
         let pdf_bytes = self.build_pdf(ad_name, start, end, company_name, &hourly_entries, &daily_entries, total_plays);
-        let _ = std::fs::write(&pdf_path, pdf_bytes);
+        let written = write_report_files(&csv_path, csv_content, &pdf_path, pdf_bytes);
 
-        Some(ReportResult {
+        let result = ReportResult {
             ad_name: ad_name.to_string(),
             csv_path,
             pdf_path,
+        };
+        self.audit_if_written(written, &result, start, end, company_name);
+        Some(result)
+    }
+
+    /// Record `result` in the audit log only if its files were written; a
+    /// report that never reached disk is reported instead of audited.
+    fn audit_if_written(
+        &self,
+        written: Result<(), String>,
+        result: &ReportResult,
+        start: &str,
+        end: &str,
+        company_name: Option<&str>,
+    ) {
+        match written {
+            Ok(()) => self.record_audit(result, start, end, company_name),
+            Err(e) => eprintln!(
+                "[Report] '{}' not written, no audit entry recorded: {}",
+                result.ad_name, e
+            ),
+        }
+    }
+
+    /// Append a delivery entry for `result` to the logger's audit log.
+    /// Failures are reported but don't fail the report itself.
+    fn record_audit(
+        &self,
+        result: &ReportResult,
+        start: &str,
+        end: &str,
+        company_name: Option<&str>,
+    ) {
+        use std::io::Write;
+
+        let entry = ReportAuditEntry {
+            generated_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            ad_name: result.ad_name.clone(),
+            start: start.to_string(),
+            end: end.to_string(),
+            company: company_name.map(str::to_string),
+            files: [&result.csv_path, &result.pdf_path]
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+        };
+        let path = self.logger.report_audit_path();
+        let written = serde_json::to_string(&entry)
+            .map_err(|e| e.to_string())
+            .and_then(|line| {
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| e.to_string())?;
+                writeln!(file, "{}", line).map_err(|e| e.to_string())
+            });
+        if let Err(e) = written {
+            eprintln!(
+                "[Report] Could not write audit entry to '{}': {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    /// Every report generated so far, oldest first. Unreadable lines are
+    /// skipped; a missing log is an empty history.
+    pub fn audit_history(&self) -> Vec<ReportAuditEntry> {
+        let content = std::fs::read_to_string(self.logger.report_audit_path()).unwrap_or_default();
+        let mut entries: Vec<ReportAuditEntry> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        // Appended in order already; the stable sort only guards against edits
+        entries.sort_by(|a, b| a.generated_at.cmp(&b.generated_at));
+        entries
+    }
+
+    /// Compare how often `ad_name` should have aired between `start` and
+    /// `end` (MM-DD-YY, inclusive) with its confirmed plays. Each hour the ad
+    /// was scheduled and in flight counts as one expected play, delivered if
+    /// it has any confirmed play. The ad's current schedule is used for the
+    /// whole period.
+    pub fn delivery_report(
+        &self,
+        ad_name: &str,
+        start: &str,
+        end: &str,
+    ) -> Result<DeliveryReport, String> {
+        let ad = self
+            .ads
+            .iter()
+            .find(|a| a.name == ad_name)
+            .ok_or_else(|| format!("Ad '{}' not found", ad_name))?;
+        let parse = |d: &str| {
+            NaiveDate::parse_from_str(d.trim(), "%m-%d-%y")
+                .map_err(|_| format!("Invalid date '{}' (expected MM-DD-YY)", d))
+        };
+        let (first, last) = (parse(start)?, parse(end)?);
+        if last < first {
+            return Err(format!("End date {} is before start date {}", end, start));
+        }
+
+        // The logger filters on MM-DD-YY text, which can let other years in
+        let (first_iso, last_iso) = (first.to_string(), last.to_string());
+        let hourly = self.logger.get_hourly_confirmed_stats(start, end);
+        let played: BTreeMap<(String, u8), usize> = self
+            .extract_hourly(ad_name, &hourly)
+            .into_iter()
+            .filter(|e| e.date_iso >= first_iso && e.date_iso <= last_iso)
+            .map(|e| ((e.date_iso, e.hour), e.plays))
+            .collect();
+
+        let mut expected = 0;
+        let mut actual = 0;
+        let mut in_schedule = 0;
+        let mut missed_hours = Vec::new();
+        for date in first.iter_days().take_while(|d| *d <= last) {
+            if !ad.is_in_flight(date) {
+                continue;
+            }
+            let day_name = date.format("%A").to_string();
+            let date_iso = date.format("%Y-%m-%d").to_string();
+            for hour in 0..24u8 {
+                if !ad.is_scheduled_for(&day_name, hour) {
+                    continue;
+                }
+                expected += 1;
+                match played.get(&(date_iso.clone(), hour)) {
+                    Some(&plays) => {
+                        actual += 1;
+                        in_schedule += plays;
+                    }
+                    None => missed_hours.push(format!("{}_{:02}", date_iso, hour)),
+                }
+            }
+        }
+        let total: usize = played.values().sum();
+
+        Ok(DeliveryReport {
+            ad_name: ad_name.to_string(),
+            start: start.to_string(),
+            end: end.to_string(),
+            expected,
+            actual,
+            shortfall: expected.saturating_sub(actual),
+            missed_hours,
+            outside_schedule: total - in_schedule,
         })
     }
 
@@ -217,6 +542,63 @@ impl<'a> AdReportGenerator<'a> {
         })
     }
 
+    /// Generate a rollup report (CSV + PDF) aggregating confirmed plays per
+    /// week or month, one column per ad. Returns None if no plays found.
+    pub fn generate_rollup(
+        &self,
+        start: &str,
+        end: &str,
+        granularity: RollupGranularity,
+        output_dir: &Path,
+    ) -> Option<RollupResult> {
+        let table = self.rollup_table(start, end, granularity);
+        if table.rows.is_empty() {
+            return None;
+        }
+
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S").to_string();
+        let kind = granularity.label().to_uppercase();
+        let csv_path = output_dir.join(format!("ROLLUP_{}_{}.csv", kind, timestamp));
+        let pdf_path = output_dir.join(format!("ROLLUP_{}_{}.pdf", kind, timestamp));
+
+        let csv_content = self.build_rollup_csv(&table, granularity, start, end);
+        let _ = std::fs::write(&csv_path, csv_content);
+
+        let pdf_bytes = self.build_rollup_pdf(&table, granularity, start, end);
+        let _ = std::fs::write(&pdf_path, pdf_bytes);
+
+        Some(RollupResult { csv_path, pdf_path })
+    }
+
+    /// Group each ad's daily confirmed plays into period buckets.
+    fn rollup_table(&self, start: &str, end: &str, granularity: RollupGranularity) -> RollupTable {
+        let daily = self.logger.get_daily_confirmed_stats(start, end);
+
+        let mut names: Vec<String> = Vec::new();
+        for ads in daily.values() {
+            for name in ads.keys() {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        names.sort();
+
+        let mut buckets: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (i, name) in names.iter().enumerate() {
+            for entry in self.extract_daily(name, &daily) {
+                if let Some(key) = granularity.bucket(&entry.date_iso) {
+                    buckets.entry(key).or_insert_with(|| vec![0; names.len()])[i] += entry.total;
+                }
+            }
+        }
+
+        RollupTable {
+            names,
+            rows: buckets.into_iter().collect(),
+        }
+    }
+
     // --- CSV builders ---
 
     fn build_csv(
@@ -301,6 +683,43 @@ impl<'a> AdReportGenerator<'a> {
         out
     }
 
+    fn build_rollup_csv(
+        &self,
+        table: &RollupTable,
+        granularity: RollupGranularity,
+        start: &str,
+        end: &str,
+    ) -> String {
+        let now = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let mut out = String::new();
+        let kind = granularity.label();
+        out.push_str(&format!("{} Rollup Report: {} to {}\n", kind, start, end));
+        out.push_str(&format!("Generated: {}\n\n", now));
+
+        out.push_str(granularity.period_header());
+        for name in &table.names {
+            out.push_str(&format!(",{}", name));
+        }
+        out.push_str(",Total\n");
+
+        let mut totals: Vec<usize> = vec![0; table.names.len()];
+        for (period, counts) in &table.rows {
+            out.push_str(period);
+            for (i, count) in counts.iter().enumerate() {
+                out.push_str(&format!(",{}", count));
+                totals[i] += count;
+            }
+            out.push_str(&format!(",{}\n", counts.iter().sum::<usize>()));
+        }
+
+        out.push_str("TOTAL");
+        for t in &totals {
+            out.push_str(&format!(",{}", t));
+        }
+        out.push_str(&format!(",{}\n", totals.iter().sum::<usize>()));
+        out
+    }
+
     // --- PDF builders ---
 
     fn build_pdf(
@@ -324,11 +743,12 @@ impl<'a> AdReportGenerator<'a> {
         let now = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
         let black = rgb_black();
         let white = rgb_white();
-        let hdr_bg = rgb_header_bg();
+        let hdr_bg = self.branding.header_bg();
         let alt = rgb_alt_row();
         let gray = rgb_gray();
 
         let mut ops: Vec<Op> = Vec::new();
+        self.draw_logo(&mut doc, &mut ops);
         let mut y = Mm(277.0);
 
         // Title
@@ -445,11 +865,12 @@ impl<'a> AdReportGenerator<'a> {
         let mut doc = PdfDocument::new("Multi-Ad Report");
         let black = rgb_black();
         let white = rgb_white();
-        let hdr_bg = rgb_header_bg();
+        let hdr_bg = self.branding.header_bg();
         let alt = rgb_alt_row();
         let gray = rgb_gray();
 
         let mut ops: Vec<Op> = Vec::new();
+        self.draw_logo(&mut doc, &mut ops);
         let mut y = Mm(277.0);
 
         // Title
@@ -469,8 +890,8 @@ impl<'a> AdReportGenerator<'a> {
 
         for (i, name) in names.iter().enumerate() {
             let x = Mm(50.0 + i as f32 * col_w);
-            let display = if name.len() > 12 { &name[..12] } else { name };
-            pdf_text(&mut ops, display, x, y - Mm(3.5), BuiltinFont::HelveticaBold, Pt(7.0), &white);
+            let display: String = name.chars().take(12).collect();
+            pdf_text(&mut ops, &display, x, y - Mm(3.5), BuiltinFont::HelveticaBold, Pt(7.0), &white);
         }
         y = y - row_h;
 
@@ -516,6 +937,95 @@ impl<'a> AdReportGenerator<'a> {
         doc.save(&PdfSaveOptions::default(), &mut warnings)
     }
 
+    fn build_rollup_pdf(
+        &self,
+        table: &RollupTable,
+        granularity: RollupGranularity,
+        start: &str,
+        end: &str,
+    ) -> Vec<u8> {
+        let mut doc = PdfDocument::new("Ad Rollup Report");
+        let now = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let black = rgb_black();
+        let white = rgb_white();
+        let hdr_bg = self.branding.header_bg();
+        let alt = rgb_alt_row();
+        let gray = rgb_gray();
+
+        let mut ops: Vec<Op> = Vec::new();
+        self.draw_logo(&mut doc, &mut ops);
+        let mut y = Mm(277.0);
+
+        // Title
+        let title = format!("{} Rollup Report: {} to {}", granularity.label(), start, end);
+        pdf_text(&mut ops, &title, Mm(20.0), y, BuiltinFont::HelveticaBold, Pt(14.0), &black);
+        y = y - Mm(6.0);
+        pdf_text(&mut ops, &format!("Generated: {}", now), Mm(20.0), y, BuiltinFont::Helvetica, Pt(9.0), &black);
+        y = y - Mm(8.0);
+
+        // Columns: period, one per ad, total
+        let table_left = Mm(20.0);
+        let table_right = Mm(190.0);
+        let remaining: f32 = 190.0 - 20.0 - 30.0;
+        let col_w = remaining / (table.names.len() + 1) as f32;
+        let total_x = Mm(50.0 + table.names.len() as f32 * col_w);
+        let row_h = Mm(5.0);
+
+        // Header row
+        pdf_rect_fill(&mut ops, table_left, y - row_h, table_right, y, &hdr_bg);
+        pdf_text(&mut ops, granularity.period_header(), Mm(22.0), y - Mm(3.5), BuiltinFont::HelveticaBold, Pt(7.0), &white);
+        for (i, name) in table.names.iter().enumerate() {
+            let x = Mm(50.0 + i as f32 * col_w);
+            let display: String = name.chars().take(12).collect();
+            pdf_text(&mut ops, &display, x, y - Mm(3.5), BuiltinFont::HelveticaBold, Pt(7.0), &white);
+        }
+        pdf_text(&mut ops, "Total", total_x, y - Mm(3.5), BuiltinFont::HelveticaBold, Pt(7.0), &white);
+        y = y - row_h;
+
+        let mut totals: Vec<usize> = vec![0; table.names.len()];
+
+        for (row_idx, (period, counts)) in table.rows.iter().enumerate() {
+            if y < Mm(30.0) {
+                doc.pages.push(PdfPage::new(Mm(210.0), Mm(297.0), ops));
+                ops = Vec::new();
+                y = Mm(277.0);
+            }
+
+            if row_idx % 2 == 0 {
+                pdf_rect_fill(&mut ops, table_left, y - row_h, table_right, y, &alt);
+            }
+            pdf_text(&mut ops, period, Mm(22.0), y - Mm(3.5), BuiltinFont::Helvetica, Pt(7.0), &black);
+            for (i, count) in counts.iter().enumerate() {
+                totals[i] += count;
+                let x = Mm(50.0 + i as f32 * col_w);
+                pdf_text(&mut ops, &count.to_string(), x, y - Mm(3.5), BuiltinFont::Helvetica, Pt(7.0), &black);
+            }
+            let row_total: usize = counts.iter().sum();
+            pdf_text(&mut ops, &row_total.to_string(), total_x, y - Mm(3.5), BuiltinFont::HelveticaBold, Pt(7.0), &black);
+            y = y - row_h;
+        }
+
+        // Totals row
+        y = y - Mm(1.0);
+        let totals_bg = Rgb { r: 0.85, g: 0.85, b: 0.9, icc_profile: None };
+        pdf_rect_fill(&mut ops, table_left, y - row_h, table_right, y, &totals_bg);
+        pdf_text(&mut ops, "TOTAL", Mm(22.0), y - Mm(3.5), BuiltinFont::HelveticaBold, Pt(7.0), &black);
+        for (i, t) in totals.iter().enumerate() {
+            let x = Mm(50.0 + i as f32 * col_w);
+            pdf_text(&mut ops, &t.to_string(), x, y - Mm(3.5), BuiltinFont::HelveticaBold, Pt(7.0), &black);
+        }
+        let grand_total: usize = totals.iter().sum();
+        pdf_text(&mut ops, &grand_total.to_string(), total_x, y - Mm(3.5), BuiltinFont::HelveticaBold, Pt(7.0), &black);
+
+        // Footer
+        pdf_text(&mut ops, "signalFlow — Radio Automation Engine", Mm(20.0), Mm(10.0), BuiltinFont::Helvetica, Pt(7.0), &gray);
+
+        doc.pages.push(PdfPage::new(Mm(210.0), Mm(297.0), ops));
+
+        let mut warnings = Vec::new();
+        doc.save(&PdfSaveOptions::default(), &mut warnings)
+    }
+
     // --- Data extraction helpers ---
 
     fn extract_hourly(
@@ -621,6 +1131,19 @@ fn pdf_rect_stroke(ops: &mut Vec<Op>, x1: Mm, y1: Mm, x2: Mm, y2: Mm, color: &Rg
     });
 }
 
+/// Write a report's CSV and PDF, stopping at the first failure.
+fn write_report_files(
+    csv_path: &Path,
+    csv_content: String,
+    pdf_path: &Path,
+    pdf_bytes: Vec<u8>,
+) -> Result<(), String> {
+    std::fs::write(csv_path, csv_content)
+        .map_err(|e| format!("Cannot write '{}': {}", csv_path.display(), e))?;
+    std::fs::write(pdf_path, pdf_bytes)
+        .map_err(|e| format!("Cannot write '{}': {}", pdf_path.display(), e))
+}
+
 /// Sanitize a string for use as a filename.
 fn sanitize_filename(name: &str) -> String {
     name.chars()
@@ -880,4 +1403,244 @@ mod tests {
             assert!(pdf_name.ends_with(".pdf"), "PDF name should end with .pdf");
         }
     }
+
+    #[test]
+    fn weekly_rollup_spanning_three_weeks() {
+        let (logger, _dir) = temp_logger();
+        // Week of Mon 2026-01-05
+        logger.log_play_at("Ad Alpha", "01-06-26", 9);
+        logger.log_play_at("Ad Alpha", "01-11-26", 10); // Sunday, same week
+        logger.log_play_at("Ad Beta", "01-07-26", 9);
+        // Week of Mon 2026-01-12
+        logger.log_play_at("Ad Alpha", "01-12-26", 8);
+        logger.log_play_at("Ad Alpha", "01-12-26", 9);
+        // Week of Mon 2026-01-19
+        logger.log_play_at("Ad Beta", "01-20-26", 14);
+
+        let reporter = AdReportGenerator::new(&logger);
+        let table = reporter.rollup_table("01-01-26", "01-31-26", RollupGranularity::Weekly);
+        assert_eq!(table.names, vec!["Ad Alpha", "Ad Beta"]);
+        assert_eq!(
+            table.rows,
+            vec![
+                ("2026-01-05".to_string(), vec![2, 1]),
+                ("2026-01-12".to_string(), vec![2, 0]),
+                ("2026-01-19".to_string(), vec![0, 1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_rollup_splits_at_month_boundary() {
+        let (logger, _dir) = temp_logger();
+        logger.log_play_at("Ad Alpha", "01-31-26", 23);
+        logger.log_play_at("Ad Alpha", "02-01-26", 0);
+        logger.log_play_at("Ad Alpha", "02-01-26", 1);
+
+        let reporter = AdReportGenerator::new(&logger);
+        let table = reporter.rollup_table("01-01-26", "02-28-26", RollupGranularity::Monthly);
+        assert_eq!(
+            table.rows,
+            vec![
+                ("2026-01".to_string(), vec![1]),
+                ("2026-02".to_string(), vec![2]),
+            ]
+        );
+    }
+
+    #[test]
+    fn generate_rollup_writes_csv_and_pdf() {
+        let (logger, dir) = temp_logger();
+        seed_data(&logger);
+
+        let reporter = AdReportGenerator::new(&logger);
+        let monthly = RollupGranularity::Monthly;
+        let result = reporter
+            .generate_rollup("01-10-26", "01-20-26", monthly, dir.path())
+            .unwrap();
+        assert!(result.pdf_path.exists());
+        let csv = std::fs::read_to_string(&result.csv_path).unwrap();
+        assert!(csv.contains("Month,Ad Alpha,Ad Beta,Total"));
+        assert!(csv.contains("2026-01,5,2,7"));
+        assert!(csv.contains("TOTAL,5,2,7"));
+
+        let empty = reporter.generate_rollup("06-01-26", "06-30-26", monthly, dir.path());
+        assert!(empty.is_none());
+    }
+
+    #[test]
+    fn rollup_pdf_truncates_multibyte_names_on_char_boundaries() {
+        let (logger, dir) = temp_logger();
+        // The 12th byte falls inside a multi-byte character
+        logger.log_play_at("Grande Soirée", "01-15-26", 9);
+
+        let reporter = AdReportGenerator::new(&logger);
+        let monthly = RollupGranularity::Monthly;
+        let result = reporter
+            .generate_rollup("01-10-26", "01-20-26", monthly, dir.path())
+            .unwrap();
+        assert!(result.pdf_path.exists());
+    }
+
+    #[test]
+    fn branding_header_color_overrides_default() {
+        assert_eq!(ReportBranding::default().header_bg(), rgb_header_bg());
+        let branding = ReportBranding { logo_path: None, header_rgb: Some([255, 0, 0]) };
+        let rgb = branding.header_bg();
+        assert_eq!((rgb.r, rgb.g, rgb.b), (1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn missing_logo_still_produces_pdf() {
+        let (logger, dir) = temp_logger();
+        seed_data(&logger);
+
+        let branding = ReportBranding {
+            logo_path: Some(dir.path().join("missing.png").to_string_lossy().to_string()),
+            header_rgb: Some([10, 20, 30]),
+        };
+        assert!(branding.load_logo().is_none());
+
+        let reporter = AdReportGenerator::new(&logger).with_branding(branding);
+        let output = dir.path().join("reports");
+        std::fs::create_dir_all(&output).unwrap();
+        let r = reporter.generate_single_report("Ad Alpha", "01-10-26", "01-20-26", None, &output).unwrap();
+        let pdf_bytes = std::fs::read(&r.pdf_path).unwrap();
+        assert!(pdf_bytes.starts_with(b"%PDF"));
+    }
+
+    fn thursday_morning_ad() -> AdConfig {
+        AdConfig {
+            name: "Ad Alpha".to_string(),
+            enabled: true,
+            mp3_file: PathBuf::from("alpha.mp3"),
+            scheduled: true,
+            days: vec!["Thursday".to_string()],
+            hours: vec![9, 10, 11],
+            normalized_file: None,
+            start_date: None,
+            end_date: None,
+        }
+    }
+
+    #[test]
+    fn delivery_report_compares_schedule_with_plays() {
+        let (logger, _dir) = temp_logger();
+        seed_data(&logger);
+        let ads = [thursday_morning_ad()];
+        let reporter = AdReportGenerator::new(&logger).with_ads(&ads);
+
+        // 01-15-26 is a Thursday: 9, 10 and 11 are due; 01-16-26 is a Friday
+        let report = reporter.delivery_report("Ad Alpha", "01-15-26", "01-16-26");
+        let report = report.unwrap();
+        assert_eq!(report.expected, 3);
+        assert_eq!(report.actual, 2);
+        assert_eq!(report.shortfall, 1);
+        assert_eq!(report.missed_hours, ["2026-01-15_11"]);
+        // 14:00 Thursday and both Friday plays fall outside the daypart
+        assert_eq!(report.outside_schedule, 3);
+
+        // A second play at 9:00 doesn't make up for the missed 11:00
+        logger.log_play_at("Ad Alpha", "01-15-26", 9);
+        let report = reporter.delivery_report("Ad Alpha", "01-15-26", "01-16-26");
+        let report = report.unwrap();
+        assert_eq!(report.actual, 2);
+        assert_eq!(report.shortfall, 1);
+        assert_eq!(report.outside_schedule, 3);
+    }
+
+    #[test]
+    fn delivery_report_respects_flight_dates_and_full_delivery() {
+        let (logger, _dir) = temp_logger();
+        seed_data(&logger);
+        logger.log_play_at("Ad Alpha", "01-15-26", 11);
+        let mut ad = thursday_morning_ad();
+        ad.end_date = NaiveDate::from_ymd_opt(2026, 1, 20);
+        let ads = [ad];
+        let reporter = AdReportGenerator::new(&logger).with_ads(&ads);
+
+        // Two Thursdays in range, but the flight ends before the second
+        let report = reporter.delivery_report("Ad Alpha", "01-15-26", "01-22-26");
+        let report = report.unwrap();
+        assert_eq!(report.expected, 3);
+        assert_eq!(report.actual, 3);
+        assert_eq!(report.shortfall, 0);
+        assert!(report.missed_hours.is_empty());
+    }
+
+    #[test]
+    fn delivery_report_errors_for_unknown_ad_or_bad_range() {
+        let (logger, _dir) = temp_logger();
+        let ads = [thursday_morning_ad()];
+        let reporter = AdReportGenerator::new(&logger).with_ads(&ads);
+        let fails = |ad, start, end| reporter.delivery_report(ad, start, end).is_err();
+        assert!(fails("Ad Beta", "01-15-26", "01-16-26"));
+        assert!(fails("Ad Alpha", "2026-01-15", "01-16-26"));
+        assert!(fails("Ad Alpha", "01-16-26", "01-15-26"));
+    }
+
+    #[test]
+    fn generating_a_report_appends_an_audit_entry() {
+        let (logger, dir) = temp_logger();
+        seed_data(&logger);
+
+        let reporter = AdReportGenerator::new(&logger);
+        let output = dir.path().join("reports");
+        std::fs::create_dir_all(&output).unwrap();
+        assert!(reporter.audit_history().is_empty());
+
+        let r = reporter
+            .generate_single_report("Ad Alpha", "01-10-26", "01-20-26", Some("Acme"), &output)
+            .unwrap();
+        let history = reporter.audit_history();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].ad_name, "Ad Alpha");
+        assert_eq!(history[0].start, "01-10-26");
+        assert_eq!(history[0].end, "01-20-26");
+        assert_eq!(history[0].company.as_deref(), Some("Acme"));
+        let csv = r.csv_path.to_string_lossy().to_string();
+        let pdf = r.pdf_path.to_string_lossy().to_string();
+        assert_eq!(history[0].files, vec![csv, pdf]);
+
+        // Unknown ads produce no report and no entry
+        let missing =
+            reporter.generate_single_report("Nope", "01-10-26", "01-20-26", None, &output);
+        assert!(missing.is_none());
+        assert_eq!(reporter.audit_history().len(), 1);
+    }
+
+    #[test]
+    fn unwritten_reports_are_not_audited() {
+        let (logger, dir) = temp_logger();
+        seed_data(&logger);
+
+        let reporter = AdReportGenerator::new(&logger);
+        // The output folder doesn't exist, so neither file can be written
+        let output = dir.path().join("missing");
+        let r = reporter.generate_single_report("Ad Alpha", "01-10-26", "01-20-26", None, &output);
+        assert!(r.is_some());
+        reporter.generate_report("01-10-26", "01-20-26", None, &output);
+        assert!(reporter.audit_history().is_empty());
+    }
+
+    #[test]
+    fn audit_history_is_chronological() {
+        let (logger, dir) = temp_logger();
+        seed_data(&logger);
+
+        let reporter = AdReportGenerator::new(&logger);
+        let output = dir.path().join("reports");
+        std::fs::create_dir_all(&output).unwrap();
+        reporter.generate_report("01-10-26", "01-20-26", None, &output);
+        reporter.generate_single_report("Ad Beta", "01-15-26", "01-15-26", None, &output);
+
+        let history = reporter.audit_history();
+        let names: Vec<&str> = history.iter().map(|e| e.ad_name.as_str()).collect();
+        assert_eq!(names, vec!["Ad Alpha", "Ad Beta", "Ad Beta"]);
+        assert_eq!(history[2].start, "01-15-26");
+        let in_order = history
+            .windows(2)
+            .all(|w| w[0].generated_at <= w[1].generated_at);
+        assert!(in_order);
+    }
 }
@@ -1,7 +1,10 @@
+use crate::audio_editor;
+use crate::clock::{Clock, RealClock};
 use crate::lecture_detector::LectureDetector;
-use chrono::{Local, Timelike};
+use chrono::{DateTime, Local, NaiveDate, Timelike};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -43,6 +46,15 @@ pub struct AdConfig {
     /// Hours of the day this ad plays (0-23). Empty = all hours.
     #[serde(default)]
     pub hours: Vec<u8>,
+    /// Loudness-normalized copy used for insertion; `mp3_file` is untouched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalized_file: Option<PathBuf>,
+    /// First date of the campaign (inclusive). None = no start limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<NaiveDate>,
+    /// Last date of the campaign (inclusive). None = no end.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<NaiveDate>,
 }
 
 impl AdConfig {
@@ -55,6 +67,17 @@ impl AdConfig {
             scheduled: false,
             days: Vec::new(),
             hours: Vec::new(),
+            normalized_file: None,
+            start_date: None,
+            end_date: None,
+        }
+    }
+
+    /// File to insert: the normalized copy when it exists, else the original.
+    pub fn playback_file(&self) -> &Path {
+        match &self.normalized_file {
+            Some(path) if path.exists() => path,
+            _ => &self.mp3_file,
         }
     }
 
@@ -87,6 +110,20 @@ impl AdConfig {
         self.enabled && self.mp3_file.exists() && self.is_scheduled_for(day_name, hour)
     }
 
+    /// Whether `date` falls within the ad's flight dates. Applies whether
+    /// or not the ad uses day/hour scheduling.
+    pub fn is_in_flight(&self, date: NaiveDate) -> bool {
+        self.start_date.is_none_or(|start| date >= start)
+            && self.end_date.is_none_or(|end| date <= end)
+    }
+
+    /// `is_valid_now` for the day and hour read from `clock`, limited to the
+    /// ad's flight dates.
+    pub fn is_valid_at(&self, clock: &dyn Clock) -> bool {
+        self.is_in_flight(clock.now().date_naive())
+            && self.is_valid_now(&clock.day_name(), clock.hour())
+    }
+
     /// Format days for display.
     pub fn days_display(&self) -> String {
         if self.days.is_empty() {
@@ -122,20 +159,250 @@ pub struct AdInserterSettings {
     /// Path to the station ID audio file.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub station_id_file: Option<PathBuf>,
+    /// Write a loudness-normalized copy of each ad when it is added.
+    #[serde(default)]
+    pub normalize_ads: bool,
+    /// Integrated loudness target (LUFS) for normalized ads.
+    #[serde(default = "default_normalize_target_lufs")]
+    pub normalize_target_lufs: f32,
+    /// Minimum spacing before the same ad may play again.
+    #[serde(default)]
+    pub min_repeat_gap: RepeatGap,
+    /// Where the station ID goes in the roll.
+    #[serde(default)]
+    pub station_id_position: StationIdPosition,
+    /// Seconds either side of a high-priority scheduled event during which
+    /// ad rolls are deferred (0 = never defer).
+    #[serde(default = "default_event_guard_secs")]
+    pub event_guard_secs: u32,
+    /// Number of ad failure records kept in `ad_failures.json`; older ones
+    /// are dropped.
+    #[serde(default = "default_failure_retention")]
+    pub failure_retention: usize,
+    /// Longest an ad roll may run, station ID play(s) included, in seconds
+    /// (0 = no limit). Ads that would push past it are left out.
+    #[serde(default)]
+    pub max_roll_secs: u32,
+    /// When an ad would overrun `max_roll_secs`, try the remaining (shorter)
+    /// ads to fill the leftover time instead of ending the roll there.
+    #[serde(default)]
+    pub fit_best: bool,
+    /// Whether a scheduled roll waits for the current song to finish or
+    /// cuts it short.
+    #[serde(default)]
+    pub insert_timing: AdInsertTiming,
 }
 
 fn default_output_mp3() -> PathBuf {
     PathBuf::from("adRoll.mp3")
 }
 
+fn default_normalize_target_lufs() -> f32 {
+    -16.0
+}
+
+fn default_event_guard_secs() -> u32 {
+    120
+}
+
+fn default_failure_retention() -> usize {
+    crate::ad_logger::DEFAULT_MAX_FAILURES
+}
+
 impl Default for AdInserterSettings {
     fn default() -> Self {
         AdInserterSettings {
             output_mp3: default_output_mp3(),
             station_id_enabled: false,
             station_id_file: None,
+            normalize_ads: false,
+            normalize_target_lufs: default_normalize_target_lufs(),
+            min_repeat_gap: RepeatGap::None,
+            station_id_position: StationIdPosition::Start,
+            event_guard_secs: default_event_guard_secs(),
+            failure_retention: default_failure_retention(),
+            max_roll_secs: 0,
+            fit_best: false,
+            insert_timing: AdInsertTiming::AfterCurrent,
+        }
+    }
+}
+
+/// Placement of the station ID within an ad roll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StationIdPosition {
+    /// Before the first ad.
+    #[default]
+    Start,
+    /// After the last ad.
+    End,
+    /// Bookend the ads: once before, once after.
+    Both,
+}
+
+impl fmt::Display for StationIdPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StationIdPosition::Start => write!(f, "start"),
+            StationIdPosition::End => write!(f, "end"),
+            StationIdPosition::Both => write!(f, "both"),
+        }
+    }
+}
+
+impl StationIdPosition {
+    /// Parse a position from a string (case-insensitive).
+    pub fn from_str_loose(s: &str) -> Result<Self, String> {
+        match s.trim().to_lowercase().as_str() {
+            "start" | "first" => Ok(StationIdPosition::Start),
+            "end" | "last" => Ok(StationIdPosition::End),
+            "both" => Ok(StationIdPosition::Both),
+            _ => Err(format!(
+                "Unknown station ID position '{}'. Expected: start, end, both",
+                s
+            )),
         }
     }
+
+    pub fn at_start(self) -> bool {
+        matches!(self, StationIdPosition::Start | StationIdPosition::Both)
+    }
+
+    pub fn at_end(self) -> bool {
+        matches!(self, StationIdPosition::End | StationIdPosition::Both)
+    }
+}
+
+/// When a scheduled-mode roll airs relative to the playing track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdInsertTiming {
+    /// The current track finishes, then the roll plays.
+    #[default]
+    AfterCurrent,
+    /// The current track is cut short and the roll plays right away.
+    Immediate,
+}
+
+impl fmt::Display for AdInsertTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdInsertTiming::AfterCurrent => write!(f, "after_current"),
+            AdInsertTiming::Immediate => write!(f, "immediate"),
+        }
+    }
+}
+
+impl AdInsertTiming {
+    /// Parse a timing from a string (case-insensitive).
+    pub fn from_str_loose(s: &str) -> Result<Self, String> {
+        match s.trim().to_lowercase().replace(['-', ' '], "_").as_str() {
+            "after_current" | "after" => Ok(AdInsertTiming::AfterCurrent),
+            "immediate" | "now" => Ok(AdInsertTiming::Immediate),
+            _ => Err(format!(
+                "Unknown ad insert timing '{}'. Expected: after_current, immediate",
+                s
+            )),
+        }
+    }
+}
+
+/// Keep only this many entries in `Engine.ad_play_history`.
+pub const AD_HISTORY_MAX: usize = 100;
+
+/// Minimum spacing between two plays of the same ad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", tag = "unit", content = "value")]
+pub enum RepeatGap {
+    /// No restriction.
+    #[default]
+    None,
+    /// At least this many other ads must play in between.
+    Ads(u32),
+    /// At least this many minutes must pass.
+    Minutes(u32),
+}
+
+impl fmt::Display for RepeatGap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepeatGap::None => write!(f, "none"),
+            RepeatGap::Ads(n) => write!(f, "{} ads", n),
+            RepeatGap::Minutes(m) => write!(f, "{} min", m),
+        }
+    }
+}
+
+impl RepeatGap {
+    /// Parse a unit ("none", "ads", "minutes") and amount. A zero amount
+    /// means no restriction.
+    pub fn from_parts(unit: &str, value: u32) -> Result<Self, String> {
+        let gap = match unit.trim().to_lowercase().as_str() {
+            "none" | "off" => RepeatGap::None,
+            "ads" | "count" => RepeatGap::Ads(value),
+            "minutes" | "min" => RepeatGap::Minutes(value),
+            _ => {
+                return Err(format!(
+                    "Unknown repeat gap unit '{}'. Expected: none, ads, minutes",
+                    unit
+                ));
+            }
+        };
+        Ok(match gap {
+            RepeatGap::Ads(0) | RepeatGap::Minutes(0) => RepeatGap::None,
+            other => other,
+        })
+    }
+
+    /// Whether playing `name` at `now` would fall inside the gap, given
+    /// `history` (oldest first).
+    pub fn blocks(&self, name: &str, history: &[AdPlayRecord], now: DateTime<Local>) -> bool {
+        match *self {
+            RepeatGap::None => false,
+            RepeatGap::Ads(n) => history
+                .iter()
+                .rev()
+                .take(n as usize)
+                .any(|r| r.name == name),
+            RepeatGap::Minutes(m) => history.iter().any(|r| {
+                r.name == name
+                    && now.signed_duration_since(r.at) < chrono::Duration::minutes(m.into())
+            }),
+        }
+    }
+}
+
+/// One ad play, kept in `Engine.ad_play_history` for repeat spacing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdPlayRecord {
+    pub name: String,
+    pub at: DateTime<Local>,
+}
+
+/// Where the normalized copy of an ad lives: `<stem>.normalized.mp3`
+/// next to the original.
+pub fn normalized_path_for(original: &Path) -> PathBuf {
+    let stem = original
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "ad".to_string());
+    original.with_file_name(format!("{}.normalized.mp3", stem))
+}
+
+/// Run an ffmpeg loudnorm pass over `original`, returning the new file's path.
+pub fn normalize_ad_file(original: &Path, target_lufs: f32) -> Result<PathBuf, String> {
+    if !original.exists() {
+        return Err(format!("Ad file not found: {}", original.display()));
+    }
+    let output = normalized_path_for(original);
+    let args = audio_editor::build_loudnorm_args(
+        &original.to_string_lossy(),
+        &output.to_string_lossy(),
+        target_lufs,
+    );
+    audio_editor::run_ffmpeg(&args)?;
+    Ok(output)
 }
 
 // --- Ad Insertion Mode ---
@@ -192,58 +459,52 @@ impl TrackInfo {
 // --- Time Calculation Helpers ---
 
 /// Minutes remaining in the current hour.
-pub fn minutes_remaining_in_hour() -> f64 {
-    let now = Local::now();
+pub fn minutes_remaining_in_hour(clock: &dyn Clock) -> f64 {
+    let now = clock.now();
     let seconds_left = 3600.0 - (now.minute() * 60 + now.second()) as f64;
     seconds_left / 60.0
 }
 
 /// Seconds until the next hour boundary (:00:00).
-pub fn seconds_until_next_hour() -> u64 {
-    let now = Local::now();
+pub fn seconds_until_next_hour(clock: &dyn Clock) -> u64 {
+    let now = clock.now();
     3600 - (now.minute() * 60 + now.second()) as u64
 }
 
 /// Check if a track that started at `start_time` with the given `duration`
 /// will end before the current hour ends.
-pub fn track_ends_this_hour(start_time: Instant, duration: Duration) -> bool {
+pub fn track_ends_this_hour(start_time: Instant, duration: Duration, clock: &dyn Clock) -> bool {
     let now = Instant::now();
     let remaining_track_secs = if start_time + duration > now {
         (start_time + duration - now).as_secs_f64()
     } else {
         0.0
     };
-    remaining_track_secs <= seconds_until_next_hour() as f64
+    remaining_track_secs <= seconds_until_next_hour(clock) as f64
 }
 
 /// Minutes remaining after the current track ends (within this hour).
-pub fn minutes_remaining_after_track(start_time: Instant, duration: Duration) -> f64 {
+pub fn minutes_remaining_after_track(
+    start_time: Instant,
+    duration: Duration,
+    clock: &dyn Clock,
+) -> f64 {
     let now = Instant::now();
     let track_remaining_secs = if start_time + duration > now {
         (start_time + duration - now).as_secs_f64()
     } else {
         0.0
     };
-    let hour_remaining_secs = seconds_until_next_hour() as f64;
+    let hour_remaining_secs = seconds_until_next_hour(clock) as f64;
     (hour_remaining_secs - track_remaining_secs) / 60.0
 }
 
 /// Check if we're in the first 5 seconds of the hour.
-pub fn is_hour_start() -> bool {
-    let now = Local::now();
+pub fn is_hour_start(clock: &dyn Clock) -> bool {
+    let now = clock.now();
     now.minute() == 0 && now.second() < 5
 }
 
-/// Get the current day name (e.g., "Sunday", "Monday", etc.).
-pub fn current_day_name() -> String {
-    Local::now().format("%A").to_string()
-}
-
-/// Get the current hour (0-23).
-pub fn current_hour() -> u8 {
-    Local::now().hour() as u8
-}
-
 // --- Decision Logic (pure functions) ---
 
 /// Core decision flow for ad scheduling.
@@ -323,12 +584,18 @@ pub struct AdSchedulerHandler {
     waiting_for_track_boundary: Arc<AtomicBool>,
     pending_lecture_check: Arc<AtomicBool>,
     is_hour_start_flag: Arc<AtomicBool>,
+    clock: Arc<dyn Clock>,
     thread_handle: Option<thread::JoinHandle<()>>,
 }
 
 impl AdSchedulerHandler {
-    /// Create a new handler (not yet started).
+    /// Create a new handler (not yet started) on the system clock.
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(RealClock))
+    }
+
+    /// Create a handler that reads the time from `clock`.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
         AdSchedulerHandler {
             running: Arc::new(AtomicBool::new(false)),
             last_hour_checked: Arc::new(Mutex::new(u32::MAX)),
@@ -337,6 +604,7 @@ impl AdSchedulerHandler {
             waiting_for_track_boundary: Arc::new(AtomicBool::new(false)),
             pending_lecture_check: Arc::new(AtomicBool::new(false)),
             is_hour_start_flag: Arc::new(AtomicBool::new(false)),
+            clock,
             thread_handle: None,
         }
     }
@@ -367,6 +635,7 @@ impl AdSchedulerHandler {
         let waiting_for_boundary = self.waiting_for_track_boundary.clone();
         let pending_check = self.pending_lecture_check.clone();
         let hour_start_flag = self.is_hour_start_flag.clone();
+        let clock = self.clock.clone();
 
         let handle = thread::spawn(move || {
             while running.load(Ordering::Relaxed) {
@@ -380,6 +649,7 @@ impl AdSchedulerHandler {
                         &waiting_for_boundary,
                         &pending_check,
                         &hour_start_flag,
+                        clock.as_ref(),
                     );
                 }));
 
@@ -390,7 +660,7 @@ impl AdSchedulerHandler {
                 }
 
                 // Dynamic sleep calculation
-                let secs_to_hour = seconds_until_next_hour() + 2;
+                let secs_to_hour = seconds_until_next_hour(clock.as_ref()) + 2;
                 let last_check = last_track_check.lock().unwrap();
                 let since_check = last_check.elapsed().as_secs();
                 drop(last_check);
@@ -451,24 +721,24 @@ fn scheduler_tick<F, G>(
     waiting_for_boundary: &AtomicBool,
     pending_check: &AtomicBool,
     hour_start_flag: &AtomicBool,
+    clock: &dyn Clock,
 ) where
     F: Fn() -> Option<SchedulerState>,
     G: Fn(AdInsertionMode, bool),
 {
-    let now_hour = current_hour() as u32;
-    let day = current_day_name();
+    let now_hour = clock.hour() as u32;
 
     // HOUR BOUNDARY CHECK
     {
         let mut last = last_hour_checked.lock().unwrap();
         if now_hour != *last {
             *last = now_hour;
-            hour_start_flag.store(is_hour_start(), Ordering::Relaxed);
+            hour_start_flag.store(is_hour_start(clock), Ordering::Relaxed);
 
             // Get state and run decision
             if let Some(state) = get_state() {
                 // Check if any ads are scheduled for this hour
-                let has_valid_ads = state.ads.iter().any(|a| a.is_valid_now(&day, now_hour as u8));
+                let has_valid_ads = state.ads.iter().any(|a| a.is_valid_at(clock));
                 if has_valid_ads {
                     run_lecture_check(
                         &state,
@@ -476,6 +746,7 @@ fn scheduler_tick<F, G>(
                         waiting_for_boundary,
                         pending_check,
                         hour_start_flag.load(Ordering::Relaxed),
+                        clock,
                     );
                 }
             }
@@ -508,6 +779,7 @@ fn scheduler_tick<F, G>(
                         waiting_for_boundary,
                         pending_check,
                         false,
+                        clock,
                     );
                 }
             }
@@ -522,15 +794,16 @@ fn run_lecture_check<G>(
     waiting_for_boundary: &AtomicBool,
     pending_check: &AtomicBool,
     is_hour_start: bool,
+    clock: &dyn Clock,
 ) where
     G: Fn(AdInsertionMode, bool),
 {
-    let mins_remaining = minutes_remaining_in_hour();
+    let mins_remaining = minutes_remaining_in_hour(clock);
     let track_ends = state.current_track.as_ref().map_or(true, |t| {
-        track_ends_this_hour(t.start_time, t.duration)
+        track_ends_this_hour(t.start_time, t.duration, clock)
     });
     let mins_after = state.current_track.as_ref().map_or(0.0, |t| {
-        minutes_remaining_after_track(t.start_time, t.duration)
+        minutes_remaining_after_track(t.start_time, t.duration, clock)
     });
 
     let decision = decide_ad_insertion(
@@ -568,6 +841,8 @@ fn run_lecture_check<G>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::FixedClock;
+    use chrono::TimeZone;
 
     // --- AdConfig tests ---
 
@@ -596,6 +871,9 @@ mod tests {
             scheduled: true,
             days: vec!["Monday".into(), "Wednesday".into()],
             hours: vec![],
+            normalized_file: None,
+            start_date: None,
+            end_date: None,
         };
         assert!(ad.is_scheduled_for("Monday", 10));
         assert!(ad.is_scheduled_for("wednesday", 10));
@@ -611,6 +889,9 @@ mod tests {
             scheduled: true,
             days: vec![],
             hours: vec![9, 10, 14, 15],
+            normalized_file: None,
+            start_date: None,
+            end_date: None,
         };
         assert!(ad.is_scheduled_for("Monday", 9));
         assert!(ad.is_scheduled_for("Monday", 14));
@@ -626,6 +907,9 @@ mod tests {
             scheduled: true,
             days: vec!["Monday".into()],
             hours: vec![9],
+            normalized_file: None,
+            start_date: None,
+            end_date: None,
         };
         assert!(ad.is_scheduled_for("Monday", 9));
         assert!(!ad.is_scheduled_for("Monday", 10));
@@ -641,6 +925,9 @@ mod tests {
             scheduled: true,
             days: vec!["Monday".into(), "Friday".into()],
             hours: vec![9, 10, 14],
+            normalized_file: None,
+            start_date: None,
+            end_date: None,
         };
         let json = serde_json::to_string(&ad).unwrap();
         let loaded: AdConfig = serde_json::from_str(&json).unwrap();
@@ -690,49 +977,193 @@ mod tests {
             output_mp3: "out.mp3".into(),
             station_id_enabled: true,
             station_id_file: Some("station.mp3".into()),
+            normalize_ads: true,
+            normalize_target_lufs: -14.0,
+            min_repeat_gap: RepeatGap::Ads(2),
+            station_id_position: StationIdPosition::Both,
+            event_guard_secs: 60,
+            failure_retention: 200,
+            ..AdInserterSettings::default()
         };
         let json = serde_json::to_string(&settings).unwrap();
         let loaded: AdInserterSettings = serde_json::from_str(&json).unwrap();
         assert_eq!(loaded.output_mp3, PathBuf::from("out.mp3"));
         assert!(loaded.station_id_enabled);
         assert_eq!(loaded.station_id_file, Some(PathBuf::from("station.mp3")));
+        assert_eq!(loaded.station_id_position, StationIdPosition::Both);
+        assert_eq!(loaded.event_guard_secs, 60);
+        assert_eq!(loaded.failure_retention, 200);
+    }
+
+    #[test]
+    fn station_id_position_defaults_to_start() {
+        let loaded: AdInserterSettings =
+            serde_json::from_str(r#"{"output_mp3":"out.mp3"}"#).unwrap();
+        assert_eq!(loaded.station_id_position, StationIdPosition::Start);
+        assert_eq!(loaded.event_guard_secs, 120);
+        assert_eq!(loaded.failure_retention, 50);
+        assert_eq!(
+            StationIdPosition::from_str_loose("END").unwrap(),
+            StationIdPosition::End
+        );
+        assert!(StationIdPosition::from_str_loose("middle").is_err());
+    }
+
+    #[test]
+    fn ad_inserter_settings_normalize_defaults_when_missing() {
+        let loaded: AdInserterSettings =
+            serde_json::from_str(r#"{"output_mp3":"out.mp3"}"#).unwrap();
+        assert!(!loaded.normalize_ads);
+        assert_eq!(loaded.normalize_target_lufs, -16.0);
+        let settings = AdInserterSettings {
+            normalize_ads: true,
+            normalize_target_lufs: -14.0,
+            ..AdInserterSettings::default()
+        };
+        let json = serde_json::to_string(&settings).unwrap();
+        let loaded: AdInserterSettings = serde_json::from_str(&json).unwrap();
+        assert!(loaded.normalize_ads);
+        assert_eq!(loaded.normalize_target_lufs, -14.0);
+    }
+
+    #[test]
+    fn insert_timing_defaults_to_after_current() {
+        let loaded: AdInserterSettings =
+            serde_json::from_str(r#"{"output_mp3":"out.mp3"}"#).unwrap();
+        assert_eq!(loaded.insert_timing, AdInsertTiming::AfterCurrent);
+        let json = r#"{"output_mp3":"out.mp3","insert_timing":"immediate"}"#;
+        let loaded: AdInserterSettings = serde_json::from_str(json).unwrap();
+        assert_eq!(loaded.insert_timing, AdInsertTiming::Immediate);
+        assert_eq!(
+            AdInsertTiming::from_str_loose("After Current").unwrap(),
+            AdInsertTiming::AfterCurrent
+        );
+        assert!(AdInsertTiming::from_str_loose("later").is_err());
+    }
+
+    #[test]
+    fn normalized_path_sits_next_to_original() {
+        let path = normalized_path_for(Path::new("ads/Spring Sale.wav"));
+        assert_eq!(path, Path::new("ads").join("Spring Sale.normalized.mp3"));
+    }
+
+    #[test]
+    fn playback_file_prefers_existing_normalized_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let normalized = dir.path().join("ad.normalized.mp3");
+        let mut ad = AdConfig::new("Ad".into(), dir.path().join("ad.mp3"));
+        ad.normalized_file = Some(normalized.clone());
+        assert_eq!(ad.playback_file(), ad.mp3_file.as_path());
+        std::fs::write(&normalized, b"").unwrap();
+        assert_eq!(ad.playback_file(), normalized.as_path());
     }
 
     // --- Time calculation tests ---
 
+    /// Monday 2026-10-12 at `h:m:s` local time.
+    fn monday_at(h: u32, m: u32, s: u32) -> FixedClock {
+        FixedClock(Local.with_ymd_and_hms(2026, 10, 12, h, m, s).unwrap())
+    }
+
     #[test]
-    fn seconds_until_next_hour_is_positive() {
-        let secs = seconds_until_next_hour();
-        assert!(secs > 0);
-        assert!(secs <= 3600);
+    fn seconds_until_next_hour_from_clock() {
+        assert_eq!(seconds_until_next_hour(&monday_at(9, 15, 30)), 2670);
+        assert_eq!(seconds_until_next_hour(&monday_at(9, 0, 0)), 3600);
+        assert_eq!(seconds_until_next_hour(&monday_at(9, 59, 59)), 1);
     }
 
     #[test]
-    fn minutes_remaining_is_positive() {
-        let mins = minutes_remaining_in_hour();
-        assert!(mins > 0.0);
-        assert!(mins <= 60.0);
+    fn minutes_remaining_from_clock() {
+        assert_eq!(minutes_remaining_in_hour(&monday_at(9, 45, 0)), 15.0);
+        assert!(is_hour_start(&monday_at(9, 0, 4)));
+        assert!(!is_hour_start(&monday_at(9, 0, 5)));
+        assert!(!is_hour_start(&monday_at(9, 30, 0)));
     }
 
     #[test]
     fn track_ends_this_hour_short_track() {
-        // A 30-second track that just started should end this hour
-        // (unless we're in the last 30 seconds of the hour)
+        // A 30-second track that just started ends this hour at :30,
+        // but not with 10 seconds left
         let start = Instant::now();
         let dur = Duration::from_secs(30);
-        // This will almost always be true
-        if minutes_remaining_in_hour() > 1.0 {
-            assert!(track_ends_this_hour(start, dur));
-        }
+        assert!(track_ends_this_hour(start, dur, &monday_at(9, 30, 0)));
+        assert!(!track_ends_this_hour(start, dur, &monday_at(9, 59, 50)));
     }
 
     #[test]
     fn minutes_after_track_positive_for_short_track() {
         let start = Instant::now();
         let dur = Duration::from_secs(30);
-        if minutes_remaining_in_hour() > 1.0 {
-            assert!(minutes_remaining_after_track(start, dur) > 0.0);
-        }
+        assert!(minutes_remaining_after_track(start, dur, &monday_at(9, 30, 0)) > 0.0);
+        assert!(minutes_remaining_after_track(start, dur, &monday_at(9, 59, 50)) < 0.0);
+    }
+
+    #[test]
+    fn fixed_clock_drives_daypart_matching() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("ad.mp3");
+        std::fs::write(&file, b"fake").unwrap();
+        let ad_for = |day: &str| AdConfig {
+            name: format!("{} 9am", day),
+            enabled: true,
+            mp3_file: file.clone(),
+            scheduled: true,
+            days: vec![day.into()],
+            hours: vec![9],
+            normalized_file: None,
+            start_date: None,
+            end_date: None,
+        };
+        let clock = monday_at(9, 0, 0);
+        assert!(ad_for("Monday").is_valid_at(&clock));
+        assert!(!ad_for("Tuesday").is_valid_at(&clock));
+        assert!(!ad_for("Monday").is_valid_at(&monday_at(10, 0, 0)));
+    }
+
+    #[test]
+    fn scheduler_tick_uses_injected_clock() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("ad.mp3");
+        std::fs::write(&file, b"fake").unwrap();
+        let state_for = |day: &str| {
+            let ad = AdConfig {
+                name: "Daypart".into(),
+                enabled: true,
+                mp3_file: file.clone(),
+                scheduled: true,
+                days: vec![day.into()],
+                hours: vec![9],
+                normalized_file: None,
+                start_date: None,
+                end_date: None,
+            };
+            SchedulerState {
+                // Runs past the hour, so the decision is an instant insert
+                current_track: Some(make_track_info("Artist", "Long", 7200)),
+                next_track_artist: Some("Artist".into()),
+                has_next_track: true,
+                lecture_detector: LectureDetector::new(),
+                ads: vec![ad],
+            }
+        };
+        let tick = |day: &str| {
+            let inserted = Mutex::new(Vec::new());
+            scheduler_tick(
+                &|| Some(state_for(day)),
+                &|mode, hour_start| inserted.lock().unwrap().push((mode, hour_start)),
+                &Mutex::new(u32::MAX),
+                &Mutex::new(Instant::now()),
+                &Mutex::new(None),
+                &AtomicBool::new(false),
+                &AtomicBool::new(false),
+                &AtomicBool::new(false),
+                &monday_at(9, 0, 2),
+            );
+            inserted.into_inner().unwrap()
+        };
+
+        assert_eq!(tick("Monday"), vec![(AdInsertionMode::Instant, true)]);
+        assert!(tick("Tuesday").is_empty());
     }
 
     // --- Decision Logic tests ---
@@ -0,0 +1,320 @@
+//! Automatic gain control for the program bus.
+//!
+//! `AgcSource` rides the gain of the program audio toward a target RMS
+//! level, so quiet and loud tracks leave the station at a similar loudness
+//! without a hardware processor. Gain drops with the attack time constant
+//! and recovers with the (slower) release time constant, is capped at a
+//! maximum boost, and output is hard-limited to full scale.
+
+use rodio::Source;
+use rodio::source::SeekError;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Envelope below this RMS (about -60 dBFS) is treated as silence: the gain
+/// is held instead of being ridden up into the noise floor.
+const SILENCE_FLOOR: f32 = 0.001;
+
+/// Length of one gain-update block.
+const BLOCK_SECS: f32 = 0.01;
+
+/// AGC parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AgcConfig {
+    /// Target RMS level in dBFS.
+    pub target_db: f32,
+    /// Time constant for pulling the gain down on loud input.
+    pub attack_ms: f32,
+    /// Time constant for letting the gain back up on quiet input.
+    pub release_ms: f32,
+    /// Largest boost applied to quiet input, in dB.
+    pub max_gain_db: f32,
+}
+
+impl Default for AgcConfig {
+    fn default() -> Self {
+        AgcConfig {
+            target_db: -18.0,
+            attack_ms: 10.0,
+            release_ms: 500.0,
+            max_gain_db: 12.0,
+        }
+    }
+}
+
+impl AgcConfig {
+    /// Target level as a linear RMS amplitude.
+    pub fn target(&self) -> f32 {
+        db_to_linear(self.target_db)
+    }
+
+    /// Maximum boost as a linear gain.
+    pub fn max_gain(&self) -> f32 {
+        db_to_linear(self.max_gain_db)
+    }
+}
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Next gain after `elapsed` of input at RMS `level`, starting from `gain`.
+///
+/// The gain moves exponentially toward `target / level` (capped at the
+/// maximum boost) using the attack time constant when it has to fall and the
+/// release time constant when it may rise. Silence holds the current gain.
+pub fn agc_gain(gain: f32, level: f32, config: &AgcConfig, elapsed: Duration) -> f32 {
+    if level < SILENCE_FLOOR {
+        return gain;
+    }
+    let wanted = (config.target() / level).min(config.max_gain());
+    let tau_ms = if wanted < gain {
+        config.attack_ms
+    } else {
+        config.release_ms
+    };
+    if tau_ms <= 0.0 {
+        return wanted;
+    }
+    let coeff = 1.0 - (-(elapsed.as_secs_f32() * 1000.0) / tau_ms).exp();
+    gain + (wanted - gain) * coeff
+}
+
+/// AGC settings shared with a running `AgcSource`, so the program bus can
+/// be reconfigured (or bypassed with None) without restarting it.
+#[derive(Debug, Clone, Default)]
+pub struct AgcControl(Arc<Mutex<Option<AgcConfig>>>);
+
+impl AgcControl {
+    pub fn new(config: Option<AgcConfig>) -> Self {
+        AgcControl(Arc::new(Mutex::new(config)))
+    }
+
+    /// Replace the settings; the source picks them up at its next block.
+    pub fn set(&self, config: Option<AgcConfig>) {
+        *self.0.lock().unwrap() = config;
+    }
+
+    pub fn get(&self) -> Option<AgcConfig> {
+        *self.0.lock().unwrap()
+    }
+}
+
+/// A Source wrapper that applies `agc_gain` block by block and limits the
+/// result to full scale. With the control set to None, audio passes
+/// through untouched and the gain returns to unity.
+pub struct AgcSource<S> {
+    inner: S,
+    control: AgcControl,
+    config: Option<AgcConfig>,
+    gain: f32,
+    block_size: usize,
+    block_sum_sq: f64,
+    block_pos: usize,
+}
+
+impl<S> AgcSource<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, config: AgcConfig) -> Self {
+        Self::with_control(source, AgcControl::new(Some(config)))
+    }
+
+    /// An AGC stage whose settings follow `control`.
+    pub fn with_control(source: S, control: AgcControl) -> Self {
+        let samples_per_sec = source.sample_rate() as f32 * source.channels() as f32;
+        let block_size = (samples_per_sec * BLOCK_SECS).max(1.0) as usize;
+        AgcSource {
+            inner: source,
+            config: control.get(),
+            control,
+            gain: 1.0,
+            block_size,
+            block_sum_sq: 0.0,
+            block_pos: 0,
+        }
+    }
+
+    /// Gain currently applied to samples.
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+}
+
+impl<S> Iterator for AgcSource<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.inner.next()?;
+
+        self.block_sum_sq += (sample as f64) * (sample as f64);
+        self.block_pos += 1;
+        if self.block_pos >= self.block_size {
+            let rms = (self.block_sum_sq / self.block_size as f64).sqrt() as f32;
+            let elapsed = Duration::from_secs_f32(BLOCK_SECS);
+            // Never wait on the control from the output callback; a busy
+            // lock just means the new settings land a block later
+            if let Ok(config) = self.control.0.try_lock() {
+                self.config = *config;
+            }
+            self.gain = match &self.config {
+                Some(config) => agc_gain(self.gain, rms, config, elapsed),
+                None => 1.0,
+            };
+            self.block_sum_sq = 0.0;
+            self.block_pos = 0;
+        }
+
+        match self.config {
+            Some(_) => Some((sample * self.gain).clamp(-1.0, 1.0)),
+            None => Some(sample),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<S> Source for AgcSource<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run `agc_gain` over `secs` of steady input at `level`.
+    fn ride(level: f32, secs: f32, config: &AgcConfig) -> f32 {
+        let step = Duration::from_secs_f32(BLOCK_SECS);
+        let blocks = (secs / BLOCK_SECS) as usize;
+        (0..blocks).fold(1.0, |gain, _| agc_gain(gain, level, config, step))
+    }
+
+    #[test]
+    fn sustained_loud_input_is_attenuated_to_target() {
+        let config = AgcConfig::default();
+        // 0 dBFS RMS input; target is -18 dBFS
+        let gain = ride(1.0, 1.0, &config);
+        assert!(gain < 1.0);
+        assert!((gain - config.target()).abs() < 0.01, "gain {}", gain);
+    }
+
+    #[test]
+    fn quiet_input_is_boosted_up_to_max_gain() {
+        let config = AgcConfig::default();
+        // -50 dBFS would need +32 dB; the ceiling is +12 dB
+        let gain = ride(db_to_linear(-50.0), 10.0, &config);
+        assert!((gain - config.max_gain()).abs() < 0.01, "gain {}", gain);
+
+        // -24 dBFS needs +6 dB, under the ceiling
+        let gain = ride(db_to_linear(-24.0), 10.0, &config);
+        assert!((gain - db_to_linear(6.0)).abs() < 0.01, "gain {}", gain);
+    }
+
+    #[test]
+    fn attack_is_faster_than_release() {
+        let config = AgcConfig::default();
+        let step = Duration::from_millis(10);
+        let down = 1.0 - agc_gain(1.0, 1.0, &config, step);
+        let up = agc_gain(1.0, db_to_linear(-30.0), &config, step) - 1.0;
+        assert!(down > 0.0 && up > 0.0);
+        assert!(down / (1.0 - config.target()) > up / (config.max_gain() - 1.0));
+    }
+
+    #[test]
+    fn silence_holds_gain() {
+        let config = AgcConfig::default();
+        assert_eq!(agc_gain(2.0, 0.0, &config, Duration::from_secs(1)), 2.0);
+    }
+
+    struct Constant(usize);
+    impl Iterator for Constant {
+        type Item = f32;
+        fn next(&mut self) -> Option<f32> {
+            self.0 = self.0.checked_sub(1)?;
+            Some(0.05)
+        }
+    }
+    impl Source for Constant {
+        fn current_frame_len(&self) -> Option<usize> {
+            None
+        }
+        fn channels(&self) -> u16 {
+            1
+        }
+        fn sample_rate(&self) -> u32 {
+            1000
+        }
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+    }
+
+    #[test]
+    fn agc_source_limits_to_full_scale() {
+        let config = AgcConfig {
+            target_db: 6.0,
+            attack_ms: 0.0,
+            release_ms: 0.0,
+            max_gain_db: 40.0,
+        };
+        let mut source = AgcSource::new(Constant(100), config);
+        let samples: Vec<f32> = source.by_ref().collect();
+        assert_eq!(samples.len(), 100);
+        assert!(samples.iter().all(|s| s.abs() <= 1.0));
+        // Gain reaches target / level, which would push samples past 1.0
+        assert!((source.gain() - config.target() / 0.05).abs() < 0.01);
+        assert_eq!(*samples.last().unwrap(), 1.0);
+    }
+
+    #[test]
+    fn control_changes_reach_a_running_source() {
+        let config = AgcConfig {
+            target_db: 0.0,
+            attack_ms: 0.0,
+            release_ms: 0.0,
+            max_gain_db: 12.0,
+        };
+        let control = AgcControl::new(None);
+        let mut source = AgcSource::with_control(Constant(1000), control.clone());
+        // Off: samples pass through at unity
+        let bypassed: Vec<f32> = source.by_ref().take(20).collect();
+        assert!(bypassed.iter().all(|&s| s == 0.05));
+
+        control.set(Some(config));
+        let ridden: Vec<f32> = source.by_ref().take(100).collect();
+        assert!(*ridden.last().unwrap() > 0.05);
+        assert!(source.gain() > 1.0);
+
+        control.set(None);
+        let restored: Vec<f32> = source.by_ref().take(100).collect();
+        assert_eq!(*restored.last().unwrap(), 0.05);
+        assert_eq!(source.gain(), 1.0);
+    }
+}
@@ -4,27 +4,61 @@
 //! interact with the engine through AppCore methods. This eliminates duplicated
 //! command logic between CLI and GUI, and provides a single point of validation.
 //!
-//! Audio playback (Player, AudioRuntime) is NOT owned by AppCore yet — that
-//! will be added in Step 2 of the unified architecture migration.
+//! Audio playback (Player, AudioRuntime) is NOT owned by AppCore; the
+//! `facade` module pairs the two for transport actions.
 
+use crate::ad_inserter::{AdInserterService, AdInsertionResult, AdPreview, RollLengths};
 use crate::ad_logger::{AdPlayLogger, AdStatistics};
-use crate::ad_report::AdReportGenerator;
-use crate::ad_scheduler::AdConfig;
-use crate::auto_intro;
-use crate::engine::Engine;
+use crate::ad_report::{
+    AdReportGenerator, DeliveryReport, ReportAuditEntry, ReportBranding, RollupGranularity,
+};
+use crate::ad_scheduler::{
+    is_hour_start, normalize_ad_file, AdConfig, AdInsertTiming, RepeatGap, StationIdPosition,
+};
+use crate::agc::AgcConfig;
+use crate::audio_editor::{self, ConvertFormat};
+use crate::auto_intro::{self, IntroCue, IntroMode, IntroSession};
+use crate::channel_map::ChannelMode;
+use crate::clock::{Clock, RealClock};
+use crate::config_bundle::{self, BundleManifest, BundleSnapshot, ImportSummary};
+use crate::date_tokens;
+use crate::engine::{CartSlot, Engine, OutputBus, RelinkMove, RelinkRead, RelinkSummary};
+use crate::level_monitor::{AudioHeartbeat, DeadAirEvent, DeadAirWatchdog};
+use crate::musical_key;
+use crate::now_playing::{NowPlaying, NowPlayingDebouncer, NowPlayingWrite};
+use crate::player::{
+    crossfade_start_for, transition_between, TrackTransition, OVERLAY_GAIN_MAX,
+};
+use crate::playlist::{EndOfPlaylistAction, PlaylistStats};
 use crate::rds::{RdsMessage, RdsSchedule};
-use crate::scheduler::{parse_time, ConflictPolicy, Priority, ScheduleMode};
-use chrono::Local;
+use crate::resume::{resume_path_for, ResumeMarker};
+use crate::scheduler::{
+    paused_disposition, parse_time, ConflictPolicy, InsertPosition, PausedDisposition,
+    PausedEventHandling, Priority, ScheduleAction, ScheduleMode,
+};
+use crate::silence::ThresholdUnit;
+use chrono::{Local, NaiveDate, Timelike};
 use serde::Serialize;
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 // ── Log buffer ──────────────────────────────────────────────────────────────
 
 const LOG_BUFFER_MAX: usize = 500;
 
+/// Default staleness allowed before audio output is considered off air.
+pub const ON_AIR_MAX_STALE: Duration = Duration::from_secs(5);
+
+/// Longest dead-air alarm delay `set_dead_air_alarm` accepts, in seconds.
+pub const DEAD_AIR_MAX_SECS: f32 = 3600.0;
+
+/// Seeks landing within this distance of the track end count as reaching it.
+const SEEK_END_TOLERANCE: Duration = Duration::from_millis(10);
+/// Out-of-range seeks are clamped to this far before the track end.
+const SEEK_END_MARGIN: Duration = Duration::from_millis(500);
+
 #[derive(Debug, Clone, Serialize)]
 pub struct LogEntry {
     pub timestamp: String,
@@ -100,6 +134,14 @@ pub struct PlaybackState {
     /// Incremented by `on_stop`; checked by `prepare_skip` to detect a
     /// stop that races with a natural `TrackFinished` event.
     pub stop_generation: u64,
+    /// When the resume marker was last written for this track.
+    pub resume_saved_at: Option<Instant>,
+    /// The emergency file, while it is airing in place of a playlist track.
+    pub emergency: Option<crate::track::Track>,
+    /// Output latency subtracted from `elapsed`, as reported by the audio
+    /// thread for the device buffer it opened (see
+    /// `Engine::audio_buffer_ms`). Kept across `reset`.
+    pub output_latency: Duration,
 }
 
 impl PlaybackState {
@@ -114,19 +156,30 @@ impl PlaybackState {
             total_paused: Duration::ZERO,
             pause_start: None,
             stop_generation: 0,
+            resume_saved_at: None,
+            emergency: None,
+            output_latency: Duration::ZERO,
         }
     }
 
+    /// Audible position in the current track.
     pub fn elapsed(&self) -> Duration {
+        self.elapsed_at(Instant::now())
+    }
+
+    /// Audible position at `now`: time since the track started, less time
+    /// paused and the output latency still in the device buffer.
+    pub fn elapsed_at(&self, now: Instant) -> Duration {
         match self.start_time {
             Some(start) => {
-                let raw = start.elapsed();
+                let raw = now.saturating_duration_since(start);
                 let paused = if let Some(ps) = self.pause_start {
-                    self.total_paused + ps.elapsed()
+                    self.total_paused + now.saturating_duration_since(ps)
                 } else {
                     self.total_paused
                 };
                 raw.saturating_sub(paused)
+                    .saturating_sub(self.output_latency)
             }
             None => Duration::ZERO,
         }
@@ -141,6 +194,8 @@ impl PlaybackState {
         self.start_time = None;
         self.total_paused = Duration::ZERO;
         self.pause_start = None;
+        self.resume_saved_at = None;
+        self.emergency = None;
         // Bump generation so any in-flight prepare_skip can detect this stop.
         self.stop_generation = self.stop_generation.wrapping_add(1);
     }
@@ -148,6 +203,15 @@ impl PlaybackState {
 
 // ── Response data types ─────────────────────────────────────────────────────
 
+/// Result of `AppCore::on_seek`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeekOutcome {
+    /// Playback continues from this position.
+    Seeked(Duration),
+    /// The seek landed at the end of the track; the caller should advance.
+    EndOfTrack,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct StatusData {
     pub playlist_count: usize,
@@ -156,6 +220,10 @@ pub struct StatusData {
     pub crossfade_secs: f32,
     pub conflict_policy: String,
     pub silence_threshold: f32,
+    /// "linear" or "dbfs" — the unit the threshold was configured in.
+    pub silence_threshold_unit: String,
+    /// `silence_threshold` expressed in `silence_threshold_unit`.
+    pub silence_threshold_display: f32,
     pub silence_duration_secs: f32,
     pub intros_folder: Option<String>,
     pub recurring_intro_interval_secs: f32,
@@ -176,6 +244,8 @@ pub struct PlaylistData {
     pub track_count: usize,
     pub is_active: bool,
     pub current_index: Option<usize>,
+    pub auto_advance: bool,
+    pub locked: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -189,25 +259,102 @@ pub struct TrackData {
     pub played_duration_secs: Option<f64>,
     pub start_time_display: Option<String>,
     pub has_intro: bool,
+    /// True when `duration_secs` was measured by decoding, not read from tags.
+    pub duration_verified: bool,
+    pub no_crossfade_in: bool,
+    pub gain_trim_db: f32,
+    pub replay_gain_db: Option<f32>,
+    pub advisory: bool,
+    /// Key as tagged or analysed (e.g. "Am"), and its Camelot form ("8A").
+    pub musical_key: Option<String>,
+    pub camelot_key: Option<String>,
+    /// Seconds of intro the host can talk over before the vocals.
+    pub talk_over_secs: Option<f64>,
+    /// Why the file can't be played (missing, empty, ...), from a cheap
+    /// stat. None when it looks fine; `validate_playlist` does the deep check.
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ConfigData {
     pub crossfade_secs: f32,
+    pub signon_fade_secs: f32,
+    pub crossfade_lead_secs: f32,
+    pub min_crossfade_track_secs: f32,
+    pub crossfade_on_skip: bool,
+    pub live_assist_hotkeys: bool,
+    pub overlay_gain: f32,
+    pub resume_save_interval_secs: f32,
+    pub save_debounce_ms: u64,
+    pub save_config_immediately: bool,
     pub silence_threshold: f32,
+    /// "linear" or "dbfs" — the unit the threshold was configured in.
+    pub silence_threshold_unit: String,
+    /// `silence_threshold` expressed in `silence_threshold_unit`.
+    pub silence_threshold_display: f32,
     pub silence_duration_secs: f32,
+    pub dead_air_threshold_db: f32,
+    pub dead_air_secs: f32,
     pub intros_folder: Option<String>,
     pub recurring_intro_interval_secs: f32,
     pub recurring_intro_duck_volume: f32,
+    pub recurring_intro_duck_ramp_secs: f32,
+    pub intro_once_per_session: bool,
+    /// "before" or "over".
+    pub intro_mode: String,
+    pub intro_gain_db: f32,
     pub conflict_policy: String,
+    /// "queue" or "play-over".
+    pub paused_event_handling: String,
+    /// "stop", "loop" or "fallback".
+    pub end_of_playlist_action: String,
+    /// Target playlist when `end_of_playlist_action` is "fallback".
+    pub end_of_playlist_fallback: Option<String>,
     pub now_playing_path: Option<String>,
+    pub now_playing_debounce_ms: u64,
+    pub now_playing_ad_placeholder: String,
+    pub heartbeat_path: Option<String>,
+    pub emergency_file: Option<String>,
     pub stream_output_enabled: bool,
     pub stream_output_url: String,
     pub recording_enabled: bool,
     pub recording_output_dir: Option<String>,
     pub indexed_locations: Vec<String>,
     pub favorite_folders: Vec<String>,
+    pub measure_unknown_durations: bool,
     pub output_device_name: Option<String>,
+    /// Editor/monitor output device (None = system default).
+    pub monitor_device_name: Option<String>,
+    /// Fixed output rate in Hz (None = each file's own rate).
+    pub output_sample_rate: Option<u32>,
+    pub audio_buffer_ms: u32,
+    pub agc_enabled: bool,
+    pub agc_target_db: f32,
+    pub agc_attack_ms: f32,
+    pub agc_release_ms: f32,
+    pub agc_max_gain_db: f32,
+    /// "stereo", "mono_sum" or "swap_lr".
+    pub channel_mode: String,
+    pub replay_gain_enabled: bool,
+    pub normalize_ads: bool,
+    pub normalize_target_lufs: f32,
+    /// e.g. "none", "2 ads", "30 min".
+    pub ad_min_repeat_gap: String,
+    /// "start", "end" or "both".
+    pub station_id_position: String,
+    pub ad_event_guard_secs: u32,
+    /// Ad failure records kept.
+    pub ad_failure_retention: usize,
+    /// Ad roll length cap in seconds (0 = none).
+    pub ad_max_roll_secs: u32,
+    pub ad_fit_best: bool,
+    /// "after_current" or "immediate".
+    pub ad_insert_timing: String,
+    /// Hours (0-23) when advisory tracks are skipped.
+    pub clean_hours: Vec<u8>,
+    pub avoid_artist_repeat: bool,
+    pub report_logo_path: Option<String>,
+    pub report_header_rgb: Option<[u8; 3]>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -222,6 +369,39 @@ pub struct TransportData {
     pub next_artist: Option<String>,
     pub next_title: Option<String>,
     pub track_path: Option<String>,
+    /// Length of the current track's talk-over intro, in seconds.
+    pub talk_over_secs: Option<f64>,
+    /// Seconds left until the vocals come in; None when the track has no
+    /// talk-over value or the intro has passed.
+    pub talk_over_remaining_secs: Option<f64>,
+}
+
+/// What happened on the transport, for `TransportEvent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransportEventKind {
+    Play,
+    Stop,
+    Pause,
+    Resume,
+    Skip,
+    /// Stepped back to the previous track.
+    Prev,
+    Seek,
+    /// A track ended on its own and playback moved on (or ended).
+    TrackFinished,
+}
+
+/// Typed payload for transport changes, so listeners can react without
+/// re-polling `get_transport_state`. Describes the track on air after the
+/// change; for `Stop`, the track that was stopped.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransportEvent {
+    pub kind: TransportEventKind,
+    pub track_index: Option<usize>,
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    pub position_secs: f64,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -234,6 +414,20 @@ pub struct ScheduleEventData {
     pub enabled: bool,
     pub label: Option<String>,
     pub days: String,
+    pub rotator: bool,
+    pub autostart: bool,
+    /// `insert` events: "next", "end" or a 0-based playlist index.
+    pub insert_position: String,
+    /// Played when `file` is missing or unreadable.
+    pub fallback_file: Option<String>,
+    /// First/last dates the event runs (YYYY-MM-DD), if limited.
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    /// Next trigger as local "YYYY-MM-DD HH:MM:SS"; None when disabled or
+    /// past its end date.
+    pub next_fire: Option<String>,
+    /// IDs of events this one conflicts with (see `Schedule::find_conflicts`).
+    pub conflicts_with: Vec<u32>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -245,6 +439,79 @@ pub struct AdData {
     pub scheduled: bool,
     pub days: Vec<String>,
     pub hours: Vec<u8>,
+    /// Loudness-normalized copy used for insertion, if one was made.
+    pub normalized_file: Option<String>,
+    /// Campaign flight dates (YYYY-MM-DD), if limited.
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+/// One play in an ad break preview.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdPreviewEntry {
+    /// Ad name, or "Station ID".
+    pub label: String,
+    pub path: String,
+    pub station_id: bool,
+    pub duration_secs: f64,
+}
+
+/// What an ad break would air right now; see `AppCore::preview_ad_break`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdPreviewData {
+    /// Playback order, station ID play(s) included.
+    pub roll: Vec<AdPreviewEntry>,
+    /// Ads that would repeat inside the minimum gap for lack of alternatives.
+    pub forced_repeats: Vec<String>,
+    pub total_secs: f64,
+}
+
+/// One ad in a queued break, with its measured length.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdBreakEntry {
+    pub name: String,
+    pub duration_secs: f64,
+}
+
+/// An ad break as queued by `AppCore::insert_ad_break`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AdBreakData {
+    /// Ads in playback order.
+    pub ads: Vec<AdBreakEntry>,
+    /// Length of one station ID play, when the break has one.
+    pub station_id_secs: Option<f64>,
+    /// Ads that repeat inside the minimum gap for lack of alternatives.
+    pub forced_repeats: Vec<String>,
+    /// The whole break, station ID play(s) included.
+    pub total_secs: f64,
+}
+
+impl AdBreakData {
+    pub fn from_result(result: &AdInsertionResult) -> Self {
+        let ads = result
+            .ads_inserted
+            .iter()
+            .zip(&result.ad_durations)
+            .map(|(name, duration)| AdBreakEntry {
+                name: name.clone(),
+                duration_secs: duration.as_secs_f64(),
+            })
+            .collect();
+        AdBreakData {
+            ads,
+            station_id_secs: result.station_id_duration.map(|d| d.as_secs_f64()),
+            forced_repeats: result.forced_repeats.clone(),
+            total_secs: result.total_duration.as_secs_f64(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CartData {
+    pub index: usize,
+    pub label: String,
+    pub path: String,
+    pub overlay: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -253,6 +520,8 @@ pub struct RdsConfigData {
     pub port: u16,
     pub default_message: String,
     pub messages: Vec<RdsMessageData>,
+    pub segmented_ps: bool,
+    pub radio_text: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -270,6 +539,8 @@ pub struct RdsMessageData {
 pub struct LectureConfigData {
     pub blacklist: Vec<String>,
     pub whitelist: Vec<String>,
+    pub no_ads_during_lectures: bool,
+    pub no_crossfade_for_lectures: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -279,12 +550,53 @@ pub struct FileBrowserEntry {
     pub is_dir: bool,
 }
 
+/// A playlist entry that would fail on air, or that a bulk edit could not update.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackIssueData {
+    pub index: usize,
+    pub path: String,
+    pub problem: String,
+}
+
+/// Outcome of a batch format conversion.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConvertSummary {
+    /// Tracks re-encoded and repointed at the new file.
+    pub converted: usize,
+    /// Tracks already in the target format.
+    pub skipped: usize,
+    pub failures: Vec<TrackIssueData>,
+}
+
+/// Result of converting one playlist entry: its index, the path it was
+/// converted from, and the new path (None when already in the target format).
+pub type TrackConversion = (usize, PathBuf, Result<Option<PathBuf>, String>);
+
+/// Whether audio output is actually advancing, for external monitoring.
+#[derive(Debug, Clone, Serialize)]
+pub struct OnAirData {
+    pub on_air: bool,
+    pub is_playing: bool,
+    /// Seconds since audio last advanced (None = no audio yet this session).
+    pub last_audio_age_secs: Option<f64>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct FileSearchResult {
     pub path: String,
     pub name: String,
 }
 
+/// A playlist track matching a `search_playlists` query.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlaylistSearchResult {
+    pub playlist: String,
+    pub track_index: usize,
+    pub artist: String,
+    pub title: String,
+    pub path: String,
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct PlaylistProfileData {
     pub name: String,
@@ -298,16 +610,51 @@ pub struct AppCore {
     pub engine: Engine,
     pub playback: PlaybackState,
     pub logs: LogBuffer,
+    /// Bumped by the audio path as samples play; share it with the
+    /// transport's `LevelMonitor` via `LevelMonitor::with_heartbeat`.
+    pub heartbeat: AudioHeartbeat,
+    /// Program-output silence alarm; see `check_dead_air`.
+    dead_air: DeadAirWatchdog,
+    /// Pending now-playing write; see `publish_now_playing`.
+    now_playing: NowPlayingDebouncer,
+    /// Last snapshot queued for writing, to detect changes.
+    now_playing_last: Option<NowPlaying>,
+    /// Schedule events that fired during a pause, with their ids, waiting
+    /// for `take_released_events`.
+    held_events: Vec<(u32, ScheduleAction)>,
+    /// Artists whose intro played since playback started; see `take_intro`.
+    intro_session: IntroSession,
 }
 
 impl AppCore {
     /// Create a new AppCore by loading state from a file path.
     pub fn new(state_path: &Path) -> Self {
-        AppCore {
+        let mut core = AppCore {
             engine: Engine::load_from(state_path),
             playback: PlaybackState::new(),
             logs: LogBuffer::new(),
+            heartbeat: AudioHeartbeat::new(),
+            dead_air: DeadAirWatchdog::new(0.0, Duration::ZERO),
+            now_playing: NowPlayingDebouncer::new(Duration::ZERO),
+            now_playing_last: None,
+            held_events: Vec::new(),
+            intro_session: IntroSession::default(),
+        };
+        if let Some(warning) = core.engine.load_warning.take() {
+            core.logs.push("error", warning);
         }
+        if let Some(marker) = core.resume_marker() {
+            core.logs.push(
+                "info",
+                format!(
+                    "Saved position: '{}' track {} at {:.0}s — resume playback to continue",
+                    marker.playlist,
+                    marker.track_index + 1,
+                    marker.offset_secs
+                ),
+            );
+        }
+        core
     }
 
     /// Create a new AppCore with a fresh (empty) engine. For testing.
@@ -316,7 +663,50 @@ impl AppCore {
             engine: Engine::new(),
             playback: PlaybackState::new(),
             logs: LogBuffer::new(),
+            heartbeat: AudioHeartbeat::new(),
+            dead_air: DeadAirWatchdog::new(0.0, Duration::ZERO),
+            now_playing: NowPlayingDebouncer::new(Duration::ZERO),
+            now_playing_last: None,
+            held_events: Vec::new(),
+            intro_session: IntroSession::default(),
+        }
+    }
+
+    /// Re-read the state file into `engine`, picking up changes made by
+    /// another process. Playback state is kept; the reload is refused if
+    /// the playing track would no longer be at the same playlist position,
+    /// or while debounced edits are still waiting to be written (reloading
+    /// would drop them, and writing them would overwrite the other
+    /// process's changes).
+    pub fn reload(&mut self) -> Result<(), String> {
+        let path = self
+            .engine
+            .state_path()
+            .ok_or_else(|| "No state file to reload (in-memory mode)".to_string())?
+            .to_path_buf();
+        if self.engine.has_unsaved_changes() {
+            return Err("Cannot reload: changes made here haven't been saved yet".to_string());
+        }
+        let fresh = Engine::read_from(&path)?;
+
+        if self.playback.is_playing {
+            let playing_path = |engine: &Engine| {
+                let name = self.playback.playlist_name.as_deref()?;
+                let idx = self.playback.track_index?;
+                Some(engine.find_playlist(name)?.tracks.get(idx)?.path.clone())
+            };
+            let current = playing_path(&self.engine);
+            if current.is_some() && playing_path(&fresh) != current {
+                return Err(
+                    "Cannot reload: the playing track moved or was removed on disk".to_string(),
+                );
+            }
         }
+
+        self.engine.replace_with(fresh);
+        self.logs
+            .push("info", format!("State reloaded from {}", path.display()));
+        Ok(())
     }
 
     // ── Status & Config (read-only) ─────────────────────────────────────
@@ -329,6 +719,11 @@ impl AppCore {
             crossfade_secs: self.engine.crossfade_secs,
             conflict_policy: self.engine.conflict_policy.to_string(),
             silence_threshold: self.engine.silence_threshold,
+            silence_threshold_unit: self.engine.silence_threshold_unit.to_string(),
+            silence_threshold_display: self
+                .engine
+                .silence_threshold_unit
+                .express(self.engine.silence_threshold),
             silence_duration_secs: self.engine.silence_duration_secs,
             intros_folder: self.engine.intros_folder.clone(),
             recurring_intro_interval_secs: self.engine.recurring_intro_interval_secs,
@@ -346,20 +741,79 @@ impl AppCore {
     pub fn get_config(&self) -> ConfigData {
         ConfigData {
             crossfade_secs: self.engine.crossfade_secs,
+            signon_fade_secs: self.engine.signon_fade_secs,
+            crossfade_lead_secs: self.engine.crossfade_lead_secs,
+            min_crossfade_track_secs: self.engine.min_crossfade_track_secs,
+            crossfade_on_skip: self.engine.crossfade_on_skip,
+            live_assist_hotkeys: self.engine.live_assist_hotkeys,
+            overlay_gain: self.engine.overlay_gain,
+            resume_save_interval_secs: self.engine.resume_save_interval_secs,
+            save_debounce_ms: self.engine.save_debounce_ms,
+            save_config_immediately: self.engine.save_config_immediately,
             silence_threshold: self.engine.silence_threshold,
+            silence_threshold_unit: self.engine.silence_threshold_unit.to_string(),
+            silence_threshold_display: self
+                .engine
+                .silence_threshold_unit
+                .express(self.engine.silence_threshold),
             silence_duration_secs: self.engine.silence_duration_secs,
+            dead_air_threshold_db: self.engine.dead_air_threshold_db,
+            dead_air_secs: self.engine.dead_air_secs,
             intros_folder: self.engine.intros_folder.clone(),
             recurring_intro_interval_secs: self.engine.recurring_intro_interval_secs,
             recurring_intro_duck_volume: self.engine.recurring_intro_duck_volume,
+            recurring_intro_duck_ramp_secs: self.engine.recurring_intro_duck_ramp_secs,
+            intro_once_per_session: self.engine.intro_once_per_session,
+            intro_mode: self.engine.intro_mode.to_string(),
+            intro_gain_db: self.engine.intro_gain_db,
             conflict_policy: self.engine.conflict_policy.to_string(),
+            paused_event_handling: self.engine.paused_event_handling.to_string(),
+            end_of_playlist_action: self.engine.end_of_playlist_action.to_string(),
+            end_of_playlist_fallback: self
+                .engine
+                .end_of_playlist_action
+                .fallback_playlist()
+                .map(str::to_string),
             now_playing_path: self.engine.now_playing_path.clone(),
+            now_playing_debounce_ms: self.engine.now_playing_debounce_ms,
+            now_playing_ad_placeholder: self.engine.now_playing_ad_placeholder.clone(),
+            heartbeat_path: self.engine.heartbeat_path.clone(),
+            emergency_file: self
+                .engine
+                .emergency_file
+                .as_ref()
+                .map(|p| p.to_string_lossy().to_string()),
             stream_output_enabled: self.engine.stream_output.enabled,
             stream_output_url: self.engine.stream_output.endpoint_url.clone(),
             recording_enabled: self.engine.recording.enabled,
             recording_output_dir: self.engine.recording.output_dir.clone(),
             indexed_locations: self.engine.indexed_locations.clone(),
             favorite_folders: self.engine.favorite_folders.clone(),
+            measure_unknown_durations: self.engine.measure_unknown_durations,
             output_device_name: self.engine.output_device_name.clone(),
+            monitor_device_name: self.engine.monitor_device_name.clone(),
+            output_sample_rate: self.engine.output_sample_rate,
+            audio_buffer_ms: self.engine.audio_buffer_ms,
+            agc_enabled: self.engine.agc_enabled,
+            agc_target_db: self.engine.agc_target_db,
+            agc_attack_ms: self.engine.agc_attack_ms,
+            agc_release_ms: self.engine.agc_release_ms,
+            agc_max_gain_db: self.engine.agc_max_gain_db,
+            channel_mode: self.engine.channel_mode.to_string(),
+            replay_gain_enabled: self.engine.replay_gain_enabled,
+            normalize_ads: self.engine.ad_inserter.normalize_ads,
+            normalize_target_lufs: self.engine.ad_inserter.normalize_target_lufs,
+            ad_min_repeat_gap: self.engine.ad_inserter.min_repeat_gap.to_string(),
+            station_id_position: self.engine.ad_inserter.station_id_position.to_string(),
+            ad_event_guard_secs: self.engine.ad_inserter.event_guard_secs,
+            ad_failure_retention: self.engine.ad_inserter.failure_retention,
+            ad_max_roll_secs: self.engine.ad_inserter.max_roll_secs,
+            ad_fit_best: self.engine.ad_inserter.fit_best,
+            ad_insert_timing: self.engine.ad_inserter.insert_timing.to_string(),
+            clean_hours: self.engine.clean_hours.clone(),
+            avoid_artist_repeat: self.engine.avoid_artist_repeat,
+            report_logo_path: self.engine.report_branding.logo_path.clone(),
+            report_header_rgb: self.engine.report_branding.header_rgb,
         }
     }
 
@@ -371,7 +825,103 @@ impl AppCore {
 
     pub fn set_output_device(&mut self, name: Option<String>) -> Result<(), String> {
         self.engine.output_device_name = name;
-        self.engine.save()
+        self.engine.save_config()
+    }
+
+    /// Set the monitor (editor audition) device, separate from the program
+    /// device.
+    pub fn set_monitor_device(&mut self, name: Option<String>) -> Result<(), String> {
+        self.engine.monitor_device_name = name;
+        self.engine.save_config()
+    }
+
+    /// Resample program output to a fixed rate (8000–192000 Hz), or None
+    /// to play each file at its own rate.
+    pub fn set_output_sample_rate(&mut self, rate: Option<u32>) -> Result<(), String> {
+        if let Some(hz) = rate {
+            if !(8_000..=192_000).contains(&hz) {
+                return Err(format!(
+                    "Sample rate {} Hz is out of range (8000-192000)",
+                    hz
+                ));
+            }
+        }
+        self.engine.output_sample_rate = rate;
+        self.engine.save_config()
+    }
+
+    /// Set the output device buffer (0–2000 ms, 0 = driver default). The
+    /// audio thread opens the device with it and reports the latency it got
+    /// (see `set_output_latency`).
+    pub fn set_audio_buffer_ms(&mut self, ms: u32) -> Result<(), String> {
+        if ms > 2000 {
+            return Err(format!("Buffer latency {} ms is out of range (0-2000)", ms));
+        }
+        self.engine.audio_buffer_ms = ms;
+        self.engine.save_config()
+    }
+
+    /// Record the latency of the output the audio thread opened; the
+    /// reported playback position trails by this much.
+    pub fn set_output_latency(&mut self, latency: Duration) {
+        self.playback.output_latency = latency;
+    }
+
+    /// Configure the program-bus AGC. Target is -40 to 0 dBFS, attack and
+    /// release are non-negative milliseconds, max gain is 0 to 24 dB.
+    pub fn set_agc(
+        &mut self,
+        enabled: bool,
+        target_db: f32,
+        attack_ms: f32,
+        release_ms: f32,
+        max_gain_db: f32,
+    ) -> Result<(), String> {
+        if !(-40.0..=0.0).contains(&target_db) {
+            return Err(format!(
+                "AGC target {} dBFS is out of range (-40 to 0)",
+                target_db
+            ));
+        }
+        if !attack_ms.is_finite() || attack_ms < 0.0 || !release_ms.is_finite() || release_ms < 0.0
+        {
+            return Err("AGC attack and release must be 0 or more ms".to_string());
+        }
+        if !(0.0..=24.0).contains(&max_gain_db) {
+            return Err(format!(
+                "AGC max gain {} dB is out of range (0 to 24)",
+                max_gain_db
+            ));
+        }
+        self.engine.agc_enabled = enabled;
+        self.engine.agc_target_db = target_db;
+        self.engine.agc_attack_ms = attack_ms;
+        self.engine.agc_release_ms = release_ms;
+        self.engine.agc_max_gain_db = max_gain_db;
+        self.engine.save_config()
+    }
+
+    /// AGC settings for the audio runtime (None = off).
+    pub fn agc_config(&self) -> Option<AgcConfig> {
+        self.engine.agc_config()
+    }
+
+    /// Set the output channel mapping ("stereo", "mono_sum" or "swap_lr").
+    pub fn set_channel_mode(&mut self, mode: &str) -> Result<ChannelMode, String> {
+        let mode = ChannelMode::from_str_loose(mode)?;
+        self.engine.channel_mode = mode;
+        self.engine.save_config()?;
+        Ok(mode)
+    }
+
+    /// Channel mapping for the audio runtime.
+    pub fn channel_mode(&self) -> ChannelMode {
+        self.engine.channel_mode
+    }
+
+    /// Device `bus` should open (None = system default).
+    pub fn output_device_for(&self, bus: OutputBus) -> Option<String> {
+        self.engine.output_device_for(bus).map(str::to_string)
     }
 
     // ── Playlist CRUD ───────────────────────────────────────────────────
@@ -386,6 +936,8 @@ impl AppCore {
                 track_count: p.track_count(),
                 is_active: self.engine.active_playlist_id == Some(p.id),
                 current_index: p.current_index,
+                auto_advance: p.auto_advance,
+                locked: p.locked,
             })
             .collect()
     }
@@ -428,12 +980,72 @@ impl AppCore {
         Ok(())
     }
 
+    /// Copy `name`'s tracks into a new playlist `new_name`. The copy starts
+    /// unplayed (no current track, no played durations), unlocked, and with
+    /// no M3U source so saving it can't overwrite the original's file.
+    pub fn duplicate_playlist(&mut self, name: &str, new_name: String) -> Result<u32, String> {
+        if self.engine.find_playlist(&new_name).is_some() {
+            return Err(format!("Playlist '{}' already exists", new_name));
+        }
+        let source = self
+            .engine
+            .find_playlist(name)
+            .ok_or_else(|| format!("Playlist '{}' not found", name))?;
+        let mut tracks = source.tracks.clone();
+        for track in &mut tracks {
+            track.played_duration = None;
+        }
+        let auto_advance = source.auto_advance;
+        let copy = self.engine.create_playlist_mut(new_name);
+        copy.tracks = tracks;
+        copy.auto_advance = auto_advance;
+        let id = copy.id;
+        self.engine.save()?;
+        Ok(id)
+    }
+
+    /// Switch a playlist between automation (auto-advance) and live assist.
+    pub fn set_playlist_auto_advance(&mut self, name: &str, enabled: bool) -> Result<(), String> {
+        let pl = self
+            .engine
+            .find_playlist_mut(name)
+            .ok_or_else(|| format!("Playlist '{}' not found", name))?;
+        pl.auto_advance = enabled;
+        self.engine.save()?;
+        Ok(())
+    }
+
+    /// Lock a playlist against removing, reordering and shuffling (e.g.
+    /// while it is on air), or unlock it again.
+    pub fn set_playlist_locked(&mut self, name: &str, locked: bool) -> Result<(), String> {
+        let pl = self
+            .engine
+            .find_playlist_mut(name)
+            .ok_or_else(|| format!("Playlist '{}' not found", name))?;
+        pl.locked = locked;
+        let name = pl.name.clone();
+        self.engine.save()?;
+        let verb = if locked { "Locked" } else { "Unlocked" };
+        self.logs
+            .push("info", format!("{} playlist '{}'", verb, name));
+        Ok(())
+    }
+
     pub fn set_active_playlist(&mut self, name: &str) -> Result<u32, String> {
         let id = self.engine.set_active(name)?;
         self.engine.save()?;
         Ok(id)
     }
 
+    /// Track, duration, artist and lecture/music totals for a playlist.
+    pub fn playlist_stats(&self, name: &str) -> Result<PlaylistStats, String> {
+        let pl = self
+            .engine
+            .find_playlist(name)
+            .ok_or_else(|| format!("Playlist '{}' not found", name))?;
+        Ok(pl.stats(&self.engine.lecture_detector))
+    }
+
     // ── Track operations ────────────────────────────────────────────────
 
     pub fn get_playlist_tracks(&self, name: &str) -> Result<Vec<TrackData>, String> {
@@ -442,11 +1054,25 @@ impl AppCore {
             .engine
             .find_playlist(name)
             .ok_or_else(|| format!("Playlist '{}' not found", name))?;
+        // Clock times for the playing track and everything after it
+        let projected = match self.playback.track_index {
+            Some(current)
+                if self.playback.is_playing
+                    && self.playback.playlist_name.as_deref() == Some(name) =>
+            {
+                pl.projected_start_times(current, self.playback.elapsed(), Local::now())
+            }
+            _ => Vec::new(),
+        };
         Ok(pl
             .tracks
             .iter()
             .enumerate()
             .map(|(i, t)| {
+                let start_time_display = match projected.get(i).copied().flatten() {
+                    Some(at) => Some(at.format("%H:%M:%S").to_string()),
+                    None => t.played_duration_display(),
+                };
                 let has_intro = intros_folder
                     .map(|folder| auto_intro::has_intro(folder, &t.artist))
                     .unwrap_or(false);
@@ -455,11 +1081,20 @@ impl AppCore {
                     path: t.path.to_string_lossy().to_string(),
                     title: t.title.clone(),
                     artist: t.artist.clone(),
-                    duration_secs: t.duration.as_secs_f64(),
+                    duration_secs: t.effective_duration().as_secs_f64(),
                     duration_display: t.duration_display(),
                     played_duration_secs: t.played_duration.map(|d| d.as_secs_f64()),
-                    start_time_display: t.played_duration_display(),
+                    start_time_display,
                     has_intro,
+                    duration_verified: t.verified_duration.is_some(),
+                    no_crossfade_in: t.no_crossfade_in,
+                    gain_trim_db: t.gain_trim_db,
+                    replay_gain_db: t.replay_gain_db,
+                    advisory: t.advisory,
+                    musical_key: t.musical_key.clone(),
+                    camelot_key: t.musical_key.as_deref().and_then(musical_key::to_camelot),
+                    talk_over_secs: t.talk_over_secs.map(|d| d.as_secs_f64()),
+                    error: crate::track::file_error(&date_tokens::expand_for_today(&t.path)),
                 }
             })
             .collect())
@@ -467,11 +1102,13 @@ impl AppCore {
 
     pub fn add_track(&mut self, playlist: &str, path: &str) -> Result<usize, String> {
         let intros_folder = self.engine.intros_folder.clone();
+        let measure = self.engine.measure_unknown_durations;
         let pl = self
             .engine
             .find_playlist_mut(playlist)
             .ok_or_else(|| format!("Playlist '{}' not found", playlist))?;
         let idx = pl.add_track(Path::new(path))?;
+        pl.tracks[idx].fill_unknown_duration(measure);
         if let Some(ref folder) = intros_folder {
             pl.tracks[idx].has_intro =
                 auto_intro::has_intro(Path::new(folder), &pl.tracks[idx].artist);
@@ -482,6 +1119,7 @@ impl AppCore {
 
     pub fn add_tracks(&mut self, playlist: &str, paths: &[String]) -> Result<usize, String> {
         let intros_folder = self.engine.intros_folder.clone();
+        let measure = self.engine.measure_unknown_durations;
         let pl = self
             .engine
             .find_playlist_mut(playlist)
@@ -490,6 +1128,7 @@ impl AppCore {
         for path in paths {
             match pl.add_track(Path::new(path)) {
                 Ok(idx) => {
+                    pl.tracks[idx].fill_unknown_duration(measure);
                     if let Some(ref folder) = intros_folder {
                         pl.tracks[idx].has_intro =
                             auto_intro::has_intro(Path::new(folder), &pl.tracks[idx].artist);
@@ -503,11 +1142,55 @@ impl AppCore {
         Ok(count)
     }
 
+    /// Create a new playlist from every audio file in `dir`, in natural order.
+    /// Unreadable files are skipped. Returns the number of tracks added.
+    pub fn create_playlist_from_folder(
+        &mut self,
+        name: &str,
+        dir: &str,
+        recursive: bool,
+    ) -> Result<usize, String> {
+        if self.engine.find_playlist(name).is_some() {
+            return Err(format!("Playlist '{}' already exists", name));
+        }
+        let files = collect_audio_files(Path::new(dir), recursive)?;
+        let intros_folder = self.engine.intros_folder.clone();
+        let measure = self.engine.measure_unknown_durations;
+        let mut tracks = Vec::new();
+        for file in &files {
+            match crate::track::Track::from_path(file) {
+                Ok(mut track) => {
+                    track.fill_unknown_duration(measure);
+                    if let Some(ref folder) = intros_folder {
+                        track.has_intro = auto_intro::has_intro(Path::new(folder), &track.artist);
+                    }
+                    tracks.push(track);
+                }
+                Err(e) => eprintln!("Failed to add '{}': {}", file.display(), e),
+            }
+        }
+        self.engine.create_playlist(name.to_string());
+        self.push_preloaded_tracks(name, tracks)
+    }
+
     /// Returns the configured intros folder path (used to pre-load intro flags off the lock).
     pub fn intros_folder(&self) -> Option<String> {
         self.engine.intros_folder.clone()
     }
 
+    /// Whether tracks loaded off the lock should have unknown durations
+    /// measured (see `Track::fill_unknown_duration`).
+    pub fn measure_unknown_durations(&self) -> bool {
+        self.engine.measure_unknown_durations
+    }
+
+    /// Decode tracks with no tagged duration when adding them. Slows imports.
+    pub fn set_measure_unknown_durations(&mut self, enabled: bool) -> Result<(), String> {
+        self.engine.measure_unknown_durations = enabled;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
     /// Push pre-loaded Track objects into a playlist and save. No file IO except the save.
     /// Use this paired with Track::from_path called outside the core lock.
     pub fn push_preloaded_tracks(
@@ -552,86 +1235,637 @@ impl AppCore {
         Ok(())
     }
 
-    pub fn edit_track_metadata(
+    /// Set (or with None, clear) how many seconds into a track the vocals
+    /// start, shown as a talk-over countdown in the transport.
+    pub fn set_talk_over(
         &mut self,
         playlist: &str,
-        track_index: usize,
-        artist: Option<&str>,
-        title: Option<&str>,
+        index: usize,
+        secs: Option<f64>,
     ) -> Result<(), String> {
-        self.engine
-            .edit_track_metadata(playlist, track_index, artist, title)?;
+        let pl = self
+            .engine
+            .find_playlist_mut(playlist)
+            .ok_or_else(|| format!("Playlist '{}' not found", playlist))?;
+        let count = pl.tracks.len();
+        let track = pl
+            .tracks
+            .get_mut(index)
+            .ok_or_else(|| format!("Track index {} out of range ({} tracks)", index, count))?;
+        let talk_over = match secs {
+            Some(s) if s.is_nan() || s <= 0.0 => {
+                return Err(format!("Talk-over time must be positive, got {}", s));
+            }
+            Some(s) if s > track.effective_duration().as_secs_f64() => {
+                return Err("Talk-over time is past the end of the track".to_string());
+            }
+            Some(s) => Some(
+                Duration::try_from_secs_f64(s)
+                    .map_err(|e| format!("Invalid talk-over time {}: {}", s, e))?,
+            ),
+            None => None,
+        };
+        track.talk_over_secs = talk_over;
         self.engine.save()?;
         Ok(())
     }
 
-    pub fn update_track_path(
+    /// The track at `index` in `playlist`, provided it still plays `path`.
+    /// For storing results worked out from that file off the lock, when the
+    /// playlist may have been edited meanwhile.
+    fn track_still_at_mut(
         &mut self,
         playlist: &str,
-        track_index: usize,
-        new_path: &std::path::Path,
+        index: usize,
+        path: &Path,
+    ) -> Result<&mut crate::track::Track, String> {
+        let pl = self
+            .engine
+            .find_playlist_mut(playlist)
+            .ok_or_else(|| format!("Playlist '{}' not found", playlist))?;
+        let count = pl.tracks.len();
+        let track = pl
+            .tracks
+            .get_mut(index)
+            .ok_or_else(|| format!("Track index {} out of range ({} tracks)", index, count))?;
+        if track.path != path {
+            return Err(format!(
+                "Track {} of '{}' is no longer '{}'",
+                index + 1,
+                playlist,
+                path.display()
+            ));
+        }
+        Ok(track)
+    }
+
+    /// Store the musical key of `path`, the track at `index` (e.g. from
+    /// `musical_key::estimate_key`).
+    pub fn set_track_key(
+        &mut self,
+        playlist: &str,
+        index: usize,
+        path: &Path,
+        key: &str,
     ) -> Result<(), String> {
-        self.engine.update_track_path(playlist, track_index, new_path)?;
+        if musical_key::to_camelot(key).is_none() {
+            return Err(format!("Unrecognized key '{}'", key));
+        }
+        let track = self.track_still_at_mut(playlist, index, path)?;
+        track.musical_key = Some(key.trim().to_string());
         self.engine.save()?;
         Ok(())
     }
 
-    pub fn copy_tracks(
-        &self,
-        from_playlist: &str,
-        indices: &[usize],
-    ) -> Result<Vec<crate::track::Track>, String> {
-        self.engine.copy_tracks(from_playlist, indices)
+    /// Store a decode-measured length of `path`, the track at `index` (from
+    /// `track::decoded_duration`). Cue-sheet segments are rejected since the
+    /// measurement covers the whole file.
+    pub fn set_verified_duration(
+        &mut self,
+        playlist: &str,
+        index: usize,
+        path: &Path,
+        duration: Duration,
+    ) -> Result<(), String> {
+        let track = self.track_still_at_mut(playlist, index, path)?;
+        if track.cue_in.is_some() || track.cue_out.is_some() {
+            return Err("Cannot verify the duration of a cue-sheet segment".to_string());
+        }
+        track.verified_duration = Some(duration);
+        self.engine.save()?;
+        Ok(())
     }
 
-    pub fn paste_tracks(
+    /// Mark a track to start cleanly: the previous track fades out without
+    /// overlapping it, regardless of the global crossfade.
+    pub fn set_track_no_crossfade_in(
         &mut self,
-        to_playlist: &str,
-        tracks: Vec<crate::track::Track>,
-        at: Option<usize>,
+        playlist: &str,
+        index: usize,
+        enabled: bool,
     ) -> Result<(), String> {
-        self.engine.paste_tracks(to_playlist, tracks, at)?;
+        let pl = self
+            .engine
+            .find_playlist_mut(playlist)
+            .ok_or_else(|| format!("Playlist '{}' not found", playlist))?;
+        let count = pl.tracks.len();
+        let track = pl
+            .tracks
+            .get_mut(index)
+            .ok_or_else(|| format!("Track index {} out of range ({} tracks)", index, count))?;
+        track.no_crossfade_in = enabled;
         self.engine.save()?;
         Ok(())
     }
 
-    // ── Transport state (read-only snapshot) ────────────────────────────
-    //
-    // Note: actual play/stop/pause/skip/seek require the Player, which is
-    // NOT owned by AppCore yet. These methods provide the state snapshot
-    // that Tauri's transport_status handler needs.
+    /// Set a track's gain trim in dB (applied on top of any ReplayGain).
+    /// Values outside -24..+12 dB are rejected.
+    pub fn set_track_gain(
+        &mut self,
+        playlist: &str,
+        index: usize,
+        gain_db: f32,
+    ) -> Result<(), String> {
+        let gain_db = crate::track::validate_gain_trim(gain_db)?;
+        let pl = self
+            .engine
+            .find_playlist_mut(playlist)
+            .ok_or_else(|| format!("Playlist '{}' not found", playlist))?;
+        let count = pl.tracks.len();
+        let track = pl
+            .tracks
+            .get_mut(index)
+            .ok_or_else(|| format!("Track index {} out of range ({} tracks)", index, count))?;
+        track.gain_trim_db = gain_db;
+        self.engine.save()?;
+        Ok(())
+    }
 
-    pub fn get_transport_state(&self) -> TransportData {
-        let elapsed = self.playback.elapsed();
-        let (artist, title, next_artist, next_title, track_path) =
-            if let (Some(idx), Some(pl_name)) =
-                (self.playback.track_index, &self.playback.playlist_name)
-            {
-                if let Some(pl) = self.engine.find_playlist(pl_name) {
-                    let current = if let Some(track) = pl.tracks.get(idx) {
-                        (
-                            Some(track.artist.clone()),
-                            Some(track.title.clone()),
-                            Some(track.path.to_string_lossy().to_string()),
-                        )
-                    } else {
-                        (None, None, None)
-                    };
-                    let next = if let Some(next_track) = pl.tracks.get(idx + 1) {
-                        (
-                            Some(next_track.artist.clone()),
+    /// Turn on-air ReplayGain on or off. Tracks added before their tag was
+    /// read have no value yet; see `replay_gain_refresh_paths`.
+    pub fn set_replay_gain(&mut self, enabled: bool) -> Result<(), String> {
+        self.engine.replay_gain_enabled = enabled;
+        self.engine.save_config()
+    }
+
+    /// Distinct paths of tracks with no ReplayGain value, to re-read with
+    /// `track::read_replay_gain` (outside the core lock) when it is enabled.
+    pub fn replay_gain_refresh_paths(&self) -> Vec<PathBuf> {
+        let mut seen = HashSet::new();
+        self.engine
+            .playlists
+            .iter()
+            .flat_map(|pl| &pl.tracks)
+            .filter(|t| t.replay_gain_db.is_none())
+            .filter(|t| seen.insert(t.path.clone()))
+            .map(|t| t.path.clone())
+            .collect()
+    }
+
+    /// Store ReplayGain values read from tags on every track with that
+    /// path that has none yet. Returns how many tracks were updated.
+    pub fn apply_replay_gain_tags(&mut self, gains: &[(PathBuf, f32)]) -> Result<usize, String> {
+        let gains: HashMap<&Path, f32> = gains.iter().map(|(p, db)| (p.as_path(), *db)).collect();
+        let mut updated = 0;
+        let playlists = self.engine.playlists.iter_mut();
+        for track in playlists.flat_map(|pl| &mut pl.tracks) {
+            if track.replay_gain_db.is_none() {
+                if let Some(&db) = gains.get(track.path.as_path()) {
+                    track.replay_gain_db = Some(db);
+                    updated += 1;
+                }
+            }
+        }
+        if updated > 0 {
+            self.engine.save()?;
+        }
+        Ok(updated)
+    }
+
+    /// Mark a track as advisory (explicit) or clear the flag.
+    pub fn set_track_advisory(
+        &mut self,
+        playlist: &str,
+        index: usize,
+        advisory: bool,
+    ) -> Result<(), String> {
+        let pl = self
+            .engine
+            .find_playlist_mut(playlist)
+            .ok_or_else(|| format!("Playlist '{}' not found", playlist))?;
+        let count = pl.tracks.len();
+        let track = pl
+            .tracks
+            .get_mut(index)
+            .ok_or_else(|| format!("Track index {} out of range ({} tracks)", index, count))?;
+        track.advisory = advisory;
+        self.engine.save()?;
+        Ok(())
+    }
+
+    /// Shuffle a playlist keeping same-artist tracks `min_artist_gap` apart;
+    /// lectures count as one group so they are spaced out too. `seed` = None
+    /// picks a random one. Returns the number of unmet spacing constraints.
+    pub fn shuffle_playlist(
+        &mut self,
+        playlist: &str,
+        min_artist_gap: usize,
+        seed: Option<u64>,
+    ) -> Result<usize, String> {
+        let detector = &self.engine.lecture_detector;
+        let pl = self
+            .engine
+            .playlists
+            .iter_mut()
+            .find(|p| p.name.eq_ignore_ascii_case(playlist))
+            .ok_or_else(|| format!("Playlist '{}' not found", playlist))?;
+        pl.ensure_unlocked()?;
+        let seed = seed.unwrap_or_else(|| fastrand::u64(..));
+        let violations = pl.shuffle_grouped(seed, min_artist_gap, |t| {
+            if detector.is_lecture(&t.artist) {
+                "\u{1}lecture".to_string()
+            } else {
+                t.artist.to_lowercase()
+            }
+        });
+        let name = pl.name.clone();
+        self.engine.save()?;
+        if violations > 0 {
+            self.logs.push(
+                "warn",
+                format!(
+                    "Shuffled '{}': {} track(s) could not keep a gap of {}",
+                    name, violations, min_artist_gap
+                ),
+            );
+        } else {
+            self.logs.push("info", format!("Shuffled '{}'", name));
+        }
+        Ok(violations)
+    }
+
+    pub fn edit_track_metadata(
+        &mut self,
+        playlist: &str,
+        track_index: usize,
+        artist: Option<&str>,
+        title: Option<&str>,
+    ) -> Result<(), String> {
+        self.engine
+            .edit_track_metadata(playlist, track_index, artist, title)?;
+        self.engine.save()?;
+        Ok(())
+    }
+
+    /// Re-tag several tracks at once. `title_pattern` may contain `{n}`,
+    /// replaced by the track's 1-based position in `indices`. Tracks that
+    /// fail (e.g. read-only files) are reported and the rest still update.
+    pub fn edit_tracks_metadata(
+        &mut self,
+        playlist: &str,
+        indices: &[usize],
+        artist: Option<&str>,
+        title_pattern: Option<&str>,
+    ) -> Result<Vec<TrackIssueData>, String> {
+        if artist.is_none() && title_pattern.is_none() {
+            return Err("Nothing to edit: provide an artist and/or title pattern".to_string());
+        }
+        if self.engine.find_playlist(playlist).is_none() {
+            return Err(format!("Playlist '{}' not found", playlist));
+        }
+
+        let mut failures = Vec::new();
+        for (n, &index) in indices.iter().enumerate() {
+            let title = title_pattern.map(|p| p.replace("{n}", &(n + 1).to_string()));
+            let result = self
+                .engine
+                .edit_track_metadata(playlist, index, artist, title.as_deref());
+            if let Err(problem) = result {
+                let path = self
+                    .engine
+                    .find_playlist(playlist)
+                    .and_then(|pl| pl.tracks.get(index))
+                    .map(|t| t.path.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                failures.push(TrackIssueData {
+                    index,
+                    path,
+                    problem,
+                });
+            }
+        }
+        self.engine.save()?;
+        self.logs.push(
+            if failures.is_empty() { "info" } else { "warn" },
+            format!(
+                "Edited {} of {} track(s) in '{}'",
+                indices.len() - failures.len(),
+                indices.len(),
+                playlist
+            ),
+        );
+        Ok(failures)
+    }
+
+    pub fn update_track_path(
+        &mut self,
+        playlist: &str,
+        track_index: usize,
+        new_path: &std::path::Path,
+    ) -> Result<(), String> {
+        self.engine.update_track_path(playlist, track_index, new_path)?;
+        self.engine.save()?;
+        Ok(())
+    }
+
+    /// Paths of the given tracks, for a conversion run outside the core lock
+    /// (see `convert_track_files`).
+    pub fn conversion_targets(
+        &self,
+        playlist: &str,
+        indices: &[usize],
+    ) -> Result<Vec<(usize, PathBuf)>, String> {
+        let paths = self.playlist_track_paths(playlist)?;
+        indices
+            .iter()
+            .map(|&index| {
+                paths.get(index).map(|p| (index, p.clone())).ok_or_else(|| {
+                    format!(
+                        "Track index {} out of range ({} tracks)",
+                        index,
+                        paths.len()
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Repoint playlist entries at their converted files. An entry that no
+    /// longer holds the path it was converted from (the playlist changed
+    /// meanwhile) is reported as a failure and left alone.
+    pub fn apply_conversions(
+        &mut self,
+        playlist: &str,
+        results: Vec<TrackConversion>,
+    ) -> Result<ConvertSummary, String> {
+        let mut summary = ConvertSummary::default();
+        for (index, original, result) in results {
+            let relinked = match result {
+                Ok(None) => {
+                    summary.skipped += 1;
+                    continue;
+                }
+                Ok(Some(new_path)) => self.relink_converted(playlist, index, &original, &new_path),
+                Err(e) => Err(e),
+            };
+            match relinked {
+                Ok(()) => summary.converted += 1,
+                Err(problem) => summary.failures.push(TrackIssueData {
+                    index,
+                    path: original.to_string_lossy().to_string(),
+                    problem,
+                }),
+            }
+        }
+        if summary.converted > 0 {
+            self.engine.save()?;
+        }
+        let level = if summary.failures.is_empty() {
+            "info"
+        } else {
+            "warn"
+        };
+        self.logs.push(
+            level,
+            format!(
+                "Converted {} track(s) in '{}' ({} already in format, {} failed)",
+                summary.converted,
+                playlist,
+                summary.skipped,
+                summary.failures.len()
+            ),
+        );
+        Ok(summary)
+    }
+
+    fn relink_converted(
+        &mut self,
+        playlist: &str,
+        index: usize,
+        original: &Path,
+        new_path: &Path,
+    ) -> Result<(), String> {
+        let current = self
+            .engine
+            .find_playlist(playlist)
+            .and_then(|pl| pl.tracks.get(index))
+            .map(|t| t.path.as_path());
+        if current != Some(original) {
+            return Err(format!(
+                "Track moved during conversion; '{}' was not relinked",
+                new_path.display()
+            ));
+        }
+        self.engine.update_track_path(playlist, index, new_path)
+    }
+
+    /// Re-encode tracks to `target_format` ("mp3", "flac", "wav" or "ogg")
+    /// at `quality` (see `audio_editor::build_convert_args`) and point the
+    /// playlist at the new files. Originals are kept; tracks already in the
+    /// target format are skipped. Runs `run` while holding `self`, so the
+    /// GUI splits this into `conversion_targets`, `convert_track_files` and
+    /// `apply_conversions` instead.
+    pub fn convert_tracks<F>(
+        &mut self,
+        playlist: &str,
+        indices: &[usize],
+        target_format: &str,
+        quality: u8,
+        run: F,
+    ) -> Result<ConvertSummary, String>
+    where
+        F: Fn(&[String]) -> Result<(), String>,
+    {
+        let format = ConvertFormat::from_str_loose(target_format)?;
+        let targets = self.conversion_targets(playlist, indices)?;
+        let results = convert_track_files(targets, format, quality, run);
+        self.apply_conversions(playlist, results)
+    }
+
+    /// `convert_tracks` to MP3 at the best VBR quality (V0).
+    pub fn convert_tracks_to_mp3<F>(
+        &mut self,
+        playlist: &str,
+        indices: &[usize],
+        run: F,
+    ) -> Result<ConvertSummary, String>
+    where
+        F: Fn(&[String]) -> Result<(), String>,
+    {
+        self.convert_tracks(playlist, indices, "mp3", 0, run)
+    }
+
+    /// Point a playlist's tracks at a moved library: paths starting with
+    /// `old_prefix` are rewritten to `new_prefix` and re-read where the new
+    /// file exists. `dry_run` only counts what would change. Reads the new
+    /// files in place; callers sharing the core behind a lock should use
+    /// `plan_relink`, `RelinkMove::read` and `apply_relink`.
+    pub fn relink_paths(
+        &mut self,
+        playlist: &str,
+        old_prefix: &str,
+        new_prefix: &str,
+        dry_run: bool,
+    ) -> Result<RelinkSummary, String> {
+        let reads = self
+            .plan_relink(playlist, old_prefix, new_prefix)?
+            .into_iter()
+            .map(|relink| relink.read(!dry_run))
+            .collect();
+        self.apply_relink(playlist, old_prefix, new_prefix, reads, dry_run)
+    }
+
+    /// The tracks a relink would move. No file I/O.
+    pub fn plan_relink(
+        &self,
+        playlist: &str,
+        old_prefix: &str,
+        new_prefix: &str,
+    ) -> Result<Vec<RelinkMove>, String> {
+        self.engine.plan_relink(playlist, old_prefix, new_prefix)
+    }
+
+    /// Apply relinks read outside the lock, then save and log.
+    pub fn apply_relink(
+        &mut self,
+        playlist: &str,
+        old_prefix: &str,
+        new_prefix: &str,
+        reads: Vec<RelinkRead>,
+        dry_run: bool,
+    ) -> Result<RelinkSummary, String> {
+        let summary = self.engine.apply_relink(playlist, reads, dry_run)?;
+        if dry_run || summary.changed == 0 {
+            return Ok(summary);
+        }
+        self.engine.save()?;
+        self.logs.push(
+            if summary.missing == 0 { "info" } else { "warn" },
+            format!(
+                "Relinked {} track(s) in '{}' from '{}' to '{}' ({} still missing)",
+                summary.changed, playlist, old_prefix, new_prefix, summary.missing
+            ),
+        );
+        Ok(summary)
+    }
+
+    pub fn copy_tracks(
+        &self,
+        from_playlist: &str,
+        indices: &[usize],
+    ) -> Result<Vec<crate::track::Track>, String> {
+        self.engine.copy_tracks(from_playlist, indices)
+    }
+
+    pub fn paste_tracks(
+        &mut self,
+        to_playlist: &str,
+        tracks: Vec<crate::track::Track>,
+        at: Option<usize>,
+    ) -> Result<(), String> {
+        self.engine.paste_tracks(to_playlist, tracks, at)?;
+        self.engine.save()?;
+        Ok(())
+    }
+
+    /// Move tracks from one playlist to another (or within one). The
+    /// destination insert happens first, so if it fails the source is left
+    /// untouched. Within one playlist, `at` refers to the pre-move order.
+    pub fn move_tracks(
+        &mut self,
+        from_playlist: &str,
+        indices: &[usize],
+        to_playlist: &str,
+        at: Option<usize>,
+    ) -> Result<(), String> {
+        let mut sorted = indices.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        // Checked up front so a locked playlist doesn't leave copies behind.
+        // Names match case-insensitively, so compare ids to spot a move
+        // within one playlist.
+        let unlocked_id = |name: &str| {
+            let pl = self
+                .engine
+                .find_playlist(name)
+                .ok_or_else(|| format!("Playlist '{}' not found", name))?;
+            pl.ensure_unlocked().map(|_| pl.id)
+        };
+        let from_id = unlocked_id(from_playlist)?;
+        let to_id = unlocked_id(to_playlist)?;
+        let tracks = self.engine.copy_tracks(from_playlist, &sorted)?;
+
+        if from_id == to_id {
+            let pl = self
+                .engine
+                .find_playlist_mut(from_playlist)
+                .ok_or_else(|| format!("Playlist '{}' not found", from_playlist))?;
+            if let Some(pos) = at.filter(|&pos| pos > pl.tracks.len()) {
+                return Err(format!(
+                    "Insert position {} out of range (playlist has {} tracks)",
+                    pos,
+                    pl.tracks.len()
+                ));
+            }
+            let at = at.map(|pos| pos - sorted.iter().filter(|&&i| i < pos).count());
+            for &idx in sorted.iter().rev() {
+                pl.remove_track(idx)?;
+            }
+            pl.insert_tracks(tracks, at)?;
+        } else {
+            self.engine.paste_tracks(to_playlist, tracks, at)?;
+            let pl = self
+                .engine
+                .find_playlist_mut(from_playlist)
+                .ok_or_else(|| format!("Playlist '{}' not found", from_playlist))?;
+            for &idx in sorted.iter().rev() {
+                pl.remove_track(idx)?;
+            }
+        }
+        self.engine.save()?;
+        Ok(())
+    }
+
+    // ── Transport state (read-only snapshot) ────────────────────────────
+    //
+    // Note: actual play/stop/pause/skip/seek require the audio thread,
+    // which AppCore does not own — see `facade`. These methods provide the
+    // state snapshot that Tauri's transport_status handler needs.
+
+    pub fn get_transport_state(&self) -> TransportData {
+        let elapsed = self.playback.elapsed();
+        let (artist, title, next_artist, next_title, track_path, talk_over) =
+            if let (Some(idx), Some(pl_name)) =
+                (self.playback.track_index, &self.playback.playlist_name)
+            {
+                if let Some(pl) = self.engine.find_playlist(pl_name) {
+                    let current = if let Some(track) = pl.tracks.get(idx) {
+                        (
+                            Some(track.artist.clone()),
+                            Some(track.title.clone()),
+                            Some(track.path.to_string_lossy().to_string()),
+                            track.talk_over_secs,
+                        )
+                    } else {
+                        (None, None, None, None)
+                    };
+                    let next = if let Some(next_track) = pl.tracks.get(idx + 1) {
+                        (
+                            Some(next_track.artist.clone()),
                             Some(next_track.title.clone()),
                         )
                     } else {
                         (None, None)
                     };
-                    (current.0, current.1, next.0, next.1, current.2)
+                    (current.0, current.1, next.0, next.1, current.2, current.3)
                 } else {
-                    (None, None, None, None, None)
+                    (None, None, None, None, None, None)
                 }
+            } else if let Some(track) = &self.playback.emergency {
+                (
+                    Some(track.artist.clone()),
+                    Some(track.title.clone()),
+                    None,
+                    None,
+                    Some(track.path.to_string_lossy().to_string()),
+                    None,
+                )
             } else {
-                (None, None, None, None, None)
+                (None, None, None, None, None, None)
             };
+        let talk_over_remaining = talk_over
+            .filter(|&intro| elapsed < intro)
+            .map(|intro| (intro - elapsed).as_secs_f64());
 
         TransportData {
             is_playing: self.playback.is_playing,
@@ -644,16 +1878,44 @@ impl AppCore {
             next_artist,
             next_title,
             track_path,
+            talk_over_secs: talk_over.map(|d| d.as_secs_f64()),
+            talk_over_remaining_secs: talk_over_remaining,
+        }
+    }
+
+    /// Snapshot the transport as a `kind` event. Call after the change,
+    /// except for `Stop`, which is taken before stopping so it names the
+    /// track that went off air.
+    pub fn transport_event(&self, kind: TransportEventKind) -> TransportEvent {
+        let state = self.get_transport_state();
+        TransportEvent {
+            kind,
+            track_index: state.track_index,
+            artist: state.track_artist,
+            title: state.track_title,
+            position_secs: state.elapsed_secs,
         }
     }
 
     /// Prepare transport state for playing a track. Returns the track path,
     /// duration, artist, title, playlist name, and resolved index.
+    /// With no active playlist, or an empty one, the emergency file plays
+    /// instead when one is set (see `play_emergency`).
     /// Does NOT actually play audio — the caller must handle Player interaction.
     pub fn prepare_play(
         &mut self,
         track_index: Option<usize>,
     ) -> Result<(PathBuf, Duration, String, String, String, usize), String> {
+        if !self.playback.is_playing {
+            self.intro_session = IntroSession::new(self.engine.intro_once_per_session);
+        }
+        let nothing_to_air = self
+            .engine
+            .active_playlist()
+            .is_none_or(|p| p.tracks.is_empty());
+        if nothing_to_air && self.engine.emergency_file.is_some() {
+            return self.play_emergency("Nothing to play in the active playlist");
+        }
         let pl = self
             .engine
             .active_playlist_mut()
@@ -668,8 +1930,8 @@ impl AppCore {
             ));
         }
 
-        let track_path = pl.tracks[idx].path.clone();
-        let track_duration = pl.tracks[idx].duration;
+        let track_path = date_tokens::expand_for_today(&pl.tracks[idx].path);
+        let track_duration = pl.tracks[idx].effective_duration();
         let track_artist = pl.tracks[idx].artist.clone();
         let track_title = pl.tracks[idx].title.clone();
         let playlist_name = pl.name.clone();
@@ -679,12 +1941,14 @@ impl AppCore {
         // Update playback state
         self.playback.is_playing = true;
         self.playback.is_paused = false;
+        self.playback.emergency = None;
         self.playback.track_index = Some(idx);
         self.playback.playlist_name = Some(playlist_name.clone());
         self.playback.track_duration = track_duration;
         self.playback.start_time = Some(Instant::now());
         self.playback.total_paused = Duration::ZERO;
         self.playback.pause_start = None;
+        self.playback.resume_saved_at = None;
 
         self.logs.push(
             "info",
@@ -704,6 +1968,7 @@ impl AppCore {
     /// Update playback state after stopping.
     pub fn on_stop(&mut self) {
         self.playback.reset();
+        self.clear_resume_marker();
         self.logs.push("info", "Playback stopped".to_string());
     }
 
@@ -729,65 +1994,323 @@ impl AppCore {
 
     /// Prepare for skip: advance to next track in active playlist.
     /// Returns the same tuple as prepare_play for the next track.
-    /// Returns Err("__end_of_playlist__") if there is no next track.
+    /// Past the last track, `end_of_playlist_action` decides: loop and
+    /// fallback return the track they start; stop (or an unusable fallback)
+    /// returns Err("__end_of_playlist__").
     pub fn prepare_skip(
         &mut self,
     ) -> Result<(PathBuf, Duration, String, String, String, usize), String> {
+        let skip_advisory = self.in_clean_hour();
+        if self.playback.emergency.is_some() {
+            // Back to the playlist as soon as it has something, else go again
+            let first = self
+                .engine
+                .active_playlist()
+                .and_then(|p| p.next_airable(0, skip_advisory));
+            return match first {
+                Some(first) => self.prepare_play(Some(first)),
+                None => self.play_emergency("Nothing to play in the active playlist"),
+            };
+        }
+        let avoid_repeat = self.engine.avoid_artist_repeat;
         let pl = self
             .engine
             .active_playlist_mut()
             .ok_or_else(|| "No active playlist".to_string())?;
 
         let current = pl.current_index.unwrap_or(0);
-        let next_idx = current + 1;
-        if next_idx >= pl.tracks.len() {
-            pl.current_index = None;
-            self.engine.save().ok();
-            self.playback.reset();
-            self.logs
-                .push("info", "Reached end of playlist".to_string());
-            return Err("__end_of_playlist__".to_string());
+        let Some(next_idx) = pl.next_airable(current + 1, skip_advisory) else {
+            return self.advance_past_end();
+        };
+        if avoid_repeat {
+            let current_artist = pl.tracks.get(current).map(|t| t.artist.clone());
+            if let Some(artist) = current_artist.filter(|a| !a.is_empty()) {
+                if pl.pull_forward_other_artist(next_idx, &artist, skip_advisory) {
+                    self.logs.push(
+                        "info",
+                        format!(
+                            "Artist rotation: moved a track up to avoid '{}' twice",
+                            artist
+                        ),
+                    );
+                }
+            }
+        }
+        if next_idx > current + 1 {
+            self.logs.push(
+                "info",
+                format!(
+                    "Clean hour: skipped {} advisory track(s)",
+                    next_idx - current - 1
+                ),
+            );
         }
 
-        let track_path = pl.tracks[next_idx].path.clone();
-        let track_duration = pl.tracks[next_idx].duration;
-        let track_artist = pl.tracks[next_idx].artist.clone();
-        let track_title = pl.tracks[next_idx].title.clone();
-        let playlist_name = pl.name.clone();
-        pl.current_index = Some(next_idx);
-        self.engine.save().ok();
-
-        // Update playback state
-        self.playback.is_playing = true;
-        self.playback.is_paused = false;
-        self.playback.track_index = Some(next_idx);
-        self.playback.playlist_name = Some(playlist_name.clone());
-        self.playback.track_duration = track_duration;
-        self.playback.start_time = Some(Instant::now());
-        self.playback.total_paused = Duration::ZERO;
-        self.playback.pause_start = None;
-
-        self.logs.push(
-            "info",
-            format!("Skipped to: {} — {}", track_artist, track_title),
-        );
-
-        Ok((
-            track_path,
-            track_duration,
-            track_artist,
-            track_title,
-            playlist_name,
-            next_idx,
-        ))
+        self.start_active_track(next_idx, "Skipped to")
     }
 
-    /// Update timing after a seek operation.
-    pub fn on_seek(&mut self, position_secs: f64) -> Result<(), String> {
+    /// Prepare for "previous": step back to the track before the current
+    /// one in the active playlist. Returns the same tuple as prepare_play.
+    /// At the first track playback restarts it, or wraps to the last track
+    /// when `end_of_playlist_action` is loop. Advisory tracks are passed
+    /// over in clean hours, as on skip.
+    pub fn prepare_prev(
+        &mut self,
+    ) -> Result<(PathBuf, Duration, String, String, String, usize), String> {
+        let skip_advisory = self.in_clean_hour();
+        if self.playback.emergency.is_some() {
+            let first = self
+                .engine
+                .active_playlist()
+                .and_then(|p| p.next_airable(0, skip_advisory));
+            return match first {
+                Some(first) => self.prepare_play(Some(first)),
+                None => self.play_emergency("Nothing to play in the active playlist"),
+            };
+        }
+        let wrap = self.engine.end_of_playlist_action == EndOfPlaylistAction::Loop;
+        let pl = self
+            .engine
+            .active_playlist_mut()
+            .ok_or_else(|| "No active playlist".to_string())?;
+        if pl.tracks.is_empty() {
+            return Err("Active playlist is empty".to_string());
+        }
+
+        let current = pl.current_index.unwrap_or(0).min(pl.tracks.len() - 1);
+        let last = pl.tracks.len();
+        let prev_idx = match pl.prev_airable(current, skip_advisory) {
+            Some(idx) => idx,
+            None if wrap => pl.prev_airable(last, skip_advisory).unwrap_or(current),
+            None => current,
+        };
+
+        self.start_active_track(prev_idx, "Back to")
+    }
+
+    /// Make track `index` of the active playlist the one on air (shared by
+    /// skip and previous): point the playlist at it, restart the playback
+    /// clock and log `verb` with the track. Returns the same tuple as
+    /// prepare_play.
+    fn start_active_track(
+        &mut self,
+        index: usize,
+        verb: &str,
+    ) -> Result<(PathBuf, Duration, String, String, String, usize), String> {
+        let pl = self
+            .engine
+            .active_playlist_mut()
+            .ok_or_else(|| "No active playlist".to_string())?;
+        let track = pl
+            .tracks
+            .get(index)
+            .ok_or_else(|| format!("Track index {} out of range", index))?;
+        let track_path = date_tokens::expand_for_today(&track.path);
+        let track_duration = track.effective_duration();
+        let track_artist = track.artist.clone();
+        let track_title = track.title.clone();
+        let playlist_name = pl.name.clone();
+        pl.current_index = Some(index);
+        self.engine.save().ok();
+
+        // Update playback state
+        self.playback.is_playing = true;
+        self.playback.is_paused = false;
+        self.playback.track_index = Some(index);
+        self.playback.playlist_name = Some(playlist_name.clone());
+        self.playback.track_duration = track_duration;
+        self.playback.start_time = Some(Instant::now());
+        self.playback.total_paused = Duration::ZERO;
+        self.playback.pause_start = None;
+        self.playback.resume_saved_at = None;
+
+        self.logs.push(
+            "info",
+            format!("{}: {} — {}", verb, track_artist, track_title),
+        );
+
+        Ok((
+            track_path,
+            track_duration,
+            track_artist,
+            track_title,
+            playlist_name,
+            index,
+        ))
+    }
+
+    /// Handle a track ending on its own. Automation playlists advance as
+    /// `prepare_skip` does; a live-assist playlist (`auto_advance` off)
+    /// stops on the finished track and returns Err("__manual_advance__")
+    /// so the operator starts the next one.
+    pub fn on_track_finished(
+        &mut self,
+    ) -> Result<(PathBuf, Duration, String, String, String, usize), String> {
+        let auto_advance = self.engine.active_playlist().is_none_or(|p| p.auto_advance);
+        if auto_advance {
+            return self.prepare_skip();
+        }
+        self.playback.reset();
+        self.clear_resume_marker();
+        self.logs.push(
+            "info",
+            "Live assist: track finished, waiting for next".to_string(),
+        );
+        Err("__manual_advance__".to_string())
+    }
+
+    /// Apply `end_of_playlist_action` once the active playlist runs out.
+    fn advance_past_end(
+        &mut self,
+    ) -> Result<(PathBuf, Duration, String, String, String, usize), String> {
+        let skip_advisory = self.in_clean_hour();
+        match self.engine.end_of_playlist_action.clone() {
+            EndOfPlaylistAction::Stop => {}
+            EndOfPlaylistAction::Loop => {
+                let first = self
+                    .engine
+                    .active_playlist()
+                    .and_then(|p| p.next_airable(0, skip_advisory));
+                if let Some(first) = first {
+                    self.logs
+                        .push("info", "Reached end of playlist — looping".to_string());
+                    return self.prepare_play(Some(first));
+                }
+            }
+            EndOfPlaylistAction::Fallback(name) => {
+                let first = self
+                    .engine
+                    .find_playlist(&name)
+                    .and_then(|p| p.next_airable(0, skip_advisory));
+                if let Some(first) = first {
+                    if let Some(pl) = self.engine.active_playlist_mut() {
+                        pl.current_index = None;
+                    }
+                    self.engine.set_active(&name)?;
+                    self.logs.push(
+                        "info",
+                        format!("Reached end of playlist — falling back to '{}'", name),
+                    );
+                    return self.prepare_play(Some(first));
+                }
+                self.logs.push(
+                    "warn",
+                    format!("Fallback playlist '{}' is missing or empty", name),
+                );
+            }
+        }
+
+        if let Some(pl) = self.engine.active_playlist_mut() {
+            pl.current_index = None;
+        }
+        self.engine.save().ok();
+        // Loop and fallback mean "keep airing"; an explicit stop is honored
+        let keep_airing = self.engine.end_of_playlist_action != EndOfPlaylistAction::Stop;
+        if keep_airing && self.engine.emergency_file.is_some() {
+            // A broken emergency file ends playback like an explicit stop
+            match self.play_emergency("Nothing left to air") {
+                Ok(next) => return Ok(next),
+                Err(e) => self.logs.push("error", e),
+            }
+        }
+        self.playback.reset();
+        self.clear_resume_marker();
+        self.logs
+            .push("info", "Reached end of playlist".to_string());
+        Err("__end_of_playlist__".to_string())
+    }
+
+    /// Start `emergency_file` as a last resort, logging `reason`. Returns
+    /// the same tuple as `prepare_play` with index 0; `prepare_skip` plays
+    /// it again until the active playlist has something to air.
+    fn play_emergency(
+        &mut self,
+        reason: &str,
+    ) -> Result<(PathBuf, Duration, String, String, String, usize), String> {
+        let path = self
+            .engine
+            .emergency_file
+            .clone()
+            .ok_or_else(|| "No emergency file set".to_string())?;
+        let track = crate::track::Track::from_path(&path)
+            .map_err(|e| format!("{}, and the emergency file failed: {}", reason, e))?;
+        let track_path = date_tokens::expand_for_today(&track.path);
+        let track_duration = track.effective_duration();
+        let playlist_name = self
+            .engine
+            .active_playlist()
+            .map(|p| p.name.clone())
+            .unwrap_or_default();
+
+        self.playback.is_playing = true;
+        self.playback.is_paused = false;
+        self.playback.track_index = None;
+        self.playback.playlist_name = None;
+        self.playback.track_duration = track_duration;
+        self.playback.start_time = Some(Instant::now());
+        self.playback.total_paused = Duration::ZERO;
+        self.playback.pause_start = None;
+        self.playback.resume_saved_at = None;
+
+        self.logs.push(
+            "warn",
+            format!("{} — playing emergency file '{}'", reason, path.display()),
+        );
+        let (artist, title) = (track.artist.clone(), track.title.clone());
+        self.playback.emergency = Some(track);
+        Ok((track_path, track_duration, artist, title, playlist_name, 0))
+    }
+
+    /// Whether advisory tracks are being kept off air right now.
+    fn in_clean_hour(&self) -> bool {
+        self.engine.is_clean_hour(Local::now().hour() as u8)
+    }
+
+    /// Cue-in/cue-out of the track currently playing (both None for
+    /// ordinary whole-file tracks). Passed to the audio thread on play.
+    pub fn playing_cue_range(&self) -> (Option<Duration>, Option<Duration>) {
+        match self.playing_track() {
+            Some(t) => (t.cue_in, t.cue_out),
+            None => (None, None),
+        }
+    }
+
+    /// Linear gain for the track currently playing (trim, plus ReplayGain
+    /// when enabled; 1.0 when nothing is playing). Passed to the audio
+    /// thread on play.
+    pub fn playing_gain(&self) -> f32 {
+        self.playing_track()
+            .map_or(1.0, |t| t.playback_gain(self.engine.replay_gain_enabled))
+    }
+
+    /// The playlist track currently playing, if any.
+    pub fn playing_track(&self) -> Option<&crate::track::Track> {
+        self.playback
+            .playlist_name
+            .as_deref()
+            .and_then(|name| self.engine.find_playlist(name))
+            .zip(self.playback.track_index)
+            .and_then(|(pl, idx)| pl.tracks.get(idx))
+    }
+
+    /// Update timing after a seek operation.
+    ///
+    /// Positions past the end are clamped to just before it; a seek landing
+    /// at the end itself returns `SeekOutcome::EndOfTrack` and leaves timing
+    /// untouched. Tracks with unknown (zero) duration are never clamped.
+    pub fn on_seek(&mut self, position_secs: f64) -> Result<SeekOutcome, String> {
         if !self.playback.is_playing {
             return Err("Nothing is playing".to_string());
         }
-        let seek_pos = Duration::from_secs_f64(position_secs.max(0.0));
+        let mut seek_pos = Duration::from_secs_f64(position_secs.max(0.0));
+        let duration = self.playback.track_duration;
+        if !duration.is_zero() {
+            if seek_pos > duration {
+                seek_pos = duration.saturating_sub(SEEK_END_MARGIN);
+            } else if seek_pos + SEEK_END_TOLERANCE >= duration {
+                return Ok(SeekOutcome::EndOfTrack);
+            }
+        }
         if self.playback.is_paused {
             // While paused: represent elapsed as a frozen offset.
             // Set start_time = now - seek_pos and total_paused = seek_pos so
@@ -803,18 +2326,218 @@ impl AppCore {
             self.playback.total_paused = Duration::ZERO;
             self.playback.pause_start = None;
         }
+        Ok(SeekOutcome::Seeked(seek_pos))
+    }
+
+    // ── Resume marker ───────────────────────────────────────────────────
+
+    fn resume_path(&self) -> Option<PathBuf> {
+        self.engine.state_path().map(resume_path_for)
+    }
+
+    /// Saved playback position from a previous run, if any.
+    pub fn resume_marker(&self) -> Option<ResumeMarker> {
+        self.resume_path().and_then(|p| ResumeMarker::load(&p))
+    }
+
+    fn clear_resume_marker(&self) {
+        if let Some(path) = self.resume_path() {
+            ResumeMarker::clear(&path);
+        }
+    }
+
+    /// Write the resume marker if playing and `resume_save_interval_secs`
+    /// has passed since the last write. Cheap to call often; returns true
+    /// when a marker was written.
+    pub fn save_resume_marker(&mut self) -> Result<bool, String> {
+        let interval = self.engine.resume_save_interval_secs;
+        if interval <= 0.0 || !self.playback.is_playing {
+            return Ok(false);
+        }
+        let due = self
+            .playback
+            .resume_saved_at
+            .is_none_or(|t| t.elapsed() >= Duration::from_secs_f32(interval));
+        let (path, playlist, track_index) = match (
+            self.resume_path(),
+            self.playback.playlist_name.clone(),
+            self.playback.track_index,
+        ) {
+            (Some(path), Some(name), Some(idx)) if due => (path, name, idx),
+            _ => return Ok(false),
+        };
+        let marker = ResumeMarker {
+            playlist,
+            track_index,
+            offset_secs: self.playback.elapsed().as_secs_f64(),
+            saved_at: Local::now(),
+        };
+        marker.save(&path)?;
+        self.playback.resume_saved_at = Some(Instant::now());
+        Ok(true)
+    }
+
+    /// Start the track from the saved resume marker at its saved offset
+    /// (clamped inside the track). Returns the track path and offset; the
+    /// caller plays the path and seeks the audio to the offset.
+    pub fn resume_playback(&mut self) -> Result<(PathBuf, Duration), String> {
+        let marker = self
+            .resume_marker()
+            .ok_or_else(|| "No saved playback position".to_string())?;
+        self.engine.set_active(&marker.playlist)?;
+        let (track_path, duration, ..) = self.prepare_play(Some(marker.track_index))?;
+        let target = marker.clamped_offset(duration, SEEK_END_MARGIN);
+        let offset = match self.on_seek(target.as_secs_f64())? {
+            SeekOutcome::Seeked(pos) => pos,
+            SeekOutcome::EndOfTrack => Duration::ZERO,
+        };
+        self.logs.push(
+            "info",
+            format!("Resumed at {:.0}s into the track", offset.as_secs_f64()),
+        );
+        Ok((track_path, offset))
+    }
+
+    pub fn set_resume_save_interval(&mut self, secs: f32) -> Result<(), String> {
+        self.engine.resume_save_interval_secs = secs.max(0.0);
+        self.engine.save_config()?;
         Ok(())
     }
 
+    // ── State file saving ───────────────────────────────────────────────
+
+    /// Write state changes at most every `ms` (0 = on every change). With
+    /// `config_immediately`, settings changes are still written at once.
+    pub fn set_save_debounce(&mut self, ms: u64, config_immediately: bool) -> Result<(), String> {
+        self.engine.save_debounce_ms = ms;
+        self.engine.save_config_immediately = config_immediately;
+        self.engine.save_config()
+    }
+
+    /// Write debounced changes whose window has passed. Cheap to call
+    /// often; returns true when the state file was written.
+    pub fn flush_state_if_due(&self) -> Result<bool, String> {
+        self.engine.flush_if_due()
+    }
+
+    /// Write any pending state changes now. Call before exiting.
+    pub fn flush(&self) -> Result<(), String> {
+        self.engine.flush()
+    }
+
     // ── Waveform ────────────────────────────────────────────────────────
 
     pub fn get_waveform(path: &str) -> Result<Vec<f32>, String> {
         crate::waveform::generate_peaks_cached(Path::new(path))
     }
 
+    /// Track paths of a playlist, in order, as files on disk today (date
+    /// tokens expanded). Lets callers collect paths under the core lock and
+    /// read the files outside it.
+    pub fn playlist_track_paths(&self, name: &str) -> Result<Vec<PathBuf>, String> {
+        let pl = self
+            .engine
+            .find_playlist(name)
+            .ok_or_else(|| format!("Playlist '{}' not found", name))?;
+        let expand = |t: &crate::track::Track| date_tokens::expand_for_today(&t.path);
+        Ok(pl.tracks.iter().map(expand).collect())
+    }
+
+    /// Index and path of the track after (`forward`) or before the one at
+    /// `current_index` in a playlist, for stepping through tracks in the
+    /// editor. `current_path` must still be the file at that index, so a
+    /// playlist edited meanwhile is an error rather than a wrong step (and a
+    /// file listed twice steps from the right copy). `playlist` = None uses
+    /// the active playlist. Returns None at either end.
+    pub fn editor_neighbor(
+        &self,
+        playlist: Option<&str>,
+        current_index: usize,
+        current_path: &str,
+        forward: bool,
+    ) -> Result<Option<(usize, String)>, String> {
+        let pl = match playlist {
+            Some(name) => self
+                .engine
+                .find_playlist(name)
+                .ok_or_else(|| format!("Playlist '{}' not found", name))?,
+            None => self
+                .engine
+                .active_playlist()
+                .ok_or_else(|| "No active playlist".to_string())?,
+        };
+        let in_place = pl
+            .tracks
+            .get(current_index)
+            .is_some_and(|t| t.path == Path::new(current_path));
+        if !in_place {
+            return Err(format!(
+                "'{}' is no longer track {} of playlist '{}'",
+                current_path,
+                current_index + 1,
+                pl.name
+            ));
+        }
+        let index = if forward {
+            current_index + 1
+        } else {
+            match current_index.checked_sub(1) {
+                Some(i) => i,
+                None => return Ok(None),
+            }
+        };
+        Ok(pl
+            .tracks
+            .get(index)
+            .map(|t| (index, t.path.to_string_lossy().to_string())))
+    }
+
+    /// Pre-flight check of a playlist: reports missing files and, when
+    /// `check_decode` is set, files whose audio fails to decode.
+    pub fn validate_playlist(
+        &self,
+        name: &str,
+        check_decode: bool,
+    ) -> Result<Vec<TrackIssueData>, String> {
+        let paths = self.playlist_track_paths(name)?;
+        Ok(validate_track_paths(&paths, check_decode))
+    }
+
+    /// Low-resolution waveform per track for playlist-row rendering.
+    /// Returns one `(index, peaks)` entry per track; unreadable files get an
+    /// empty peak array rather than failing the whole call.
+    pub fn get_playlist_waveforms(
+        &self,
+        name: &str,
+        peaks: usize,
+    ) -> Result<Vec<(usize, Vec<f32>)>, String> {
+        let paths = self.playlist_track_paths(name)?;
+        Ok(crate::waveform::mini_peaks_for_paths(&paths, peaks))
+    }
+
+    /// Decode and disk-cache overview peaks for every track in a playlist on
+    /// a bounded worker pool. Tracks already cached are skipped.
+    pub fn precompute_waveforms<F>(
+        &self,
+        name: &str,
+        on_progress: F,
+    ) -> Result<crate::waveform::PrecomputeSummary, String>
+    where
+        F: Fn(crate::waveform::PrecomputeProgress) + Sync,
+    {
+        let paths = self.playlist_track_paths(name)?;
+        Ok(crate::waveform::precompute_peaks(
+            &paths,
+            crate::waveform::PRECOMPUTE_WORKERS,
+            on_progress,
+        ))
+    }
+
     // ── Schedule ────────────────────────────────────────────────────────
 
     pub fn get_schedule(&self) -> Vec<ScheduleEventData> {
+        let conflicts = self.engine.schedule.find_conflicts();
+        let now = Local::now().naive_local();
         self.engine
             .schedule
             .events_by_time()
@@ -828,10 +2551,35 @@ impl AppCore {
                 enabled: e.enabled,
                 label: e.label.clone(),
                 days: e.days_display(),
+                rotator: e.rotator,
+                autostart: e.autostart,
+                insert_position: e.insert_position.to_string(),
+                fallback_file: e
+                    .fallback_file
+                    .as_ref()
+                    .map(|p| p.to_string_lossy().to_string()),
+                start_date: e.start_date.map(|d| d.to_string()),
+                end_date: e.end_date.map(|d| d.to_string()),
+                next_fire: e
+                    .next_fire(now)
+                    .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()),
+                conflicts_with: conflicts
+                    .iter()
+                    .filter_map(|&(a, b)| match e.id {
+                        id if id == a => Some(b),
+                        id if id == b => Some(a),
+                        _ => None,
+                    })
+                    .collect(),
             })
             .collect()
     }
 
+    /// Conflicting event ID pairs. Advisory; nothing is blocked.
+    pub fn get_schedule_conflicts(&self) -> Vec<(u32, u32)> {
+        self.engine.schedule.find_conflicts()
+    }
+
     pub fn add_schedule_event(
         &mut self,
         time: &str,
@@ -854,101 +2602,642 @@ impl AppCore {
             label.clone(),
             days_vec,
         );
+        // A folder path makes the event a rotator (random file each time)
+        if parsed_mode != ScheduleMode::Activate && Path::new(file).is_dir() {
+            if let Some(event) = self.engine.schedule.find_event_mut(id) {
+                event.rotator = true;
+            }
+        }
         self.engine.save()?;
         let display = label.unwrap_or_else(|| file.to_string());
         self.logs.push(
             "info",
             format!("Schedule event added: {} at {}", display, time),
         );
+        for (a, b) in self.engine.schedule.find_conflicts() {
+            if a == id || b == id {
+                let other = if a == id { b } else { a };
+                self.logs.push(
+                    "warn",
+                    format!(
+                        "Schedule event {} conflicts with event {} at {}",
+                        id, other, time
+                    ),
+                );
+            }
+        }
         Ok(id)
     }
 
-    pub fn remove_schedule_event(&mut self, id: u32) -> Result<(), String> {
-        self.engine.schedule.remove_event(id)?;
+    /// Turn rotator mode on/off for an event. Enabling requires `file` to be
+    /// a folder.
+    pub fn set_schedule_event_rotator(&mut self, id: u32, rotator: bool) -> Result<(), String> {
+        let event = self
+            .engine
+            .schedule
+            .find_event_mut(id)
+            .ok_or_else(|| format!("Schedule event {} not found", id))?;
+        if rotator && !event.file.is_dir() {
+            return Err(format!(
+                "Rotator needs a folder, '{}' is not one",
+                event.file.display()
+            ));
+        }
+        event.rotator = rotator;
         self.engine.save()?;
         Ok(())
     }
 
-    pub fn toggle_schedule_event(&mut self, id: u32) -> Result<bool, String> {
-        let new_state = self.engine.schedule.toggle_event(id)?;
-        self.engine.save()?;
-        Ok(new_state)
+    /// File to play when event `id` fires (a fresh pick for rotators).
+    pub fn resolve_schedule_event_file(&mut self, id: u32) -> Result<PathBuf, String> {
+        let event = self
+            .engine
+            .schedule
+            .find_event_mut(id)
+            .ok_or_else(|| format!("Schedule event {} not found", id))?;
+        event.resolve_file()
     }
 
-    // ── Config setters ──────────────────────────────────────────────────
-
-    pub fn set_crossfade(&mut self, secs: f32) -> Result<(), String> {
-        self.engine.crossfade_secs = secs;
+    /// Set whether an `activate` event starts playback after switching.
+    pub fn set_schedule_event_autostart(&mut self, id: u32, autostart: bool) -> Result<(), String> {
+        let event = self
+            .engine
+            .schedule
+            .find_event_mut(id)
+            .ok_or_else(|| format!("Schedule event {} not found", id))?;
+        if event.mode != ScheduleMode::Activate {
+            return Err(format!("Schedule event {} is not an activate event", id));
+        }
+        event.autostart = autostart;
         self.engine.save()?;
         Ok(())
     }
 
-    pub fn set_silence_detection(
+    /// Set where an `insert` event's file goes in the active playlist:
+    /// "next", "end" or a 0-based index.
+    pub fn set_schedule_event_insert_position(
         &mut self,
-        threshold: f32,
-        duration_secs: f32,
+        id: u32,
+        position: &str,
     ) -> Result<(), String> {
-        self.engine.silence_threshold = threshold;
-        self.engine.silence_duration_secs = duration_secs;
+        let position = InsertPosition::from_str_loose(position)?;
+        let event = self
+            .engine
+            .schedule
+            .find_event_mut(id)
+            .ok_or_else(|| format!("Schedule event {} not found", id))?;
+        if event.mode != ScheduleMode::Insert {
+            return Err(format!("Schedule event {} is not an insert event", id));
+        }
+        event.insert_position = position;
         self.engine.save()?;
         Ok(())
     }
 
-    pub fn set_intros_folder(&mut self, path: Option<String>) -> Result<(), String> {
-        if let Some(ref p) = path {
-            if !Path::new(p).is_dir() {
-                return Err(format!("'{}' is not a valid directory", p));
-            }
-        }
-        self.engine.intros_folder = path.clone();
-        // Refresh has_intro flags on all tracks
-        for pl in &mut self.engine.playlists {
-            for track in &mut pl.tracks {
-                track.has_intro = match &path {
-                    Some(folder) => auto_intro::has_intro(Path::new(folder), &track.artist),
-                    None => false,
-                };
-            }
+    /// Set the file played when event `id`'s own file is missing or
+    /// unreadable. None or an empty path clears it.
+    pub fn set_schedule_event_fallback(
+        &mut self,
+        id: u32,
+        fallback: Option<&str>,
+    ) -> Result<(), String> {
+        let event = self
+            .engine
+            .schedule
+            .find_event_mut(id)
+            .ok_or_else(|| format!("Schedule event {} not found", id))?;
+        if event.mode == ScheduleMode::Activate {
+            return Err(format!(
+                "Schedule event {} activates a playlist and has no file",
+                id
+            ));
         }
+        event.fallback_file = fallback
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from);
         self.engine.save()?;
         Ok(())
     }
 
-    pub fn set_recurring_intro(
+    /// Limit an event to a date range (YYYY-MM-DD, inclusive; None = open).
+    /// Equal dates make a one-shot event.
+    pub fn set_schedule_event_dates(
         &mut self,
-        interval_secs: f32,
-        duck_volume: f32,
+        id: u32,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
     ) -> Result<(), String> {
-        self.engine.recurring_intro_interval_secs = interval_secs;
-        self.engine.recurring_intro_duck_volume = duck_volume;
+        let (start, end) = parse_date_range(start_date, end_date)?;
+        let event = self
+            .engine
+            .schedule
+            .find_event_mut(id)
+            .ok_or_else(|| format!("Schedule event {} not found", id))?;
+        event.start_date = start;
+        event.end_date = end;
         self.engine.save()?;
         Ok(())
     }
 
-    pub fn set_conflict_policy(&mut self, policy: &str) -> Result<(), String> {
-        let parsed = ConflictPolicy::from_str_loose(policy)?;
-        self.engine.conflict_policy = parsed;
-        self.engine.save()?;
-        Ok(())
+    /// Carry out event `id` firing. `activate` events switch the active
+    /// playlist here; a missing playlist is logged and returned as an error.
+    /// For audio events the file picked (primary or fallback) is logged, and
+    /// an event with nothing playable logs an error. Audio actions and
+    /// autostart are left to the caller.
+    ///
+    /// While paused, overlay and stop events follow `paused_disposition`:
+    /// a held event returns `ScheduleAction::Held` and is handed back by
+    /// `take_released_events` once the pause ends; a stop event that fires
+    /// replaces the paused track, which the caller stops along with the
+    /// audio (see `facade::fire_schedule_event`).
+    pub fn fire_schedule_event(&mut self, id: u32) -> Result<ScheduleAction, String> {
+        let event = self
+            .engine
+            .schedule
+            .find_event_mut(id)
+            .ok_or_else(|| format!("Schedule event {} not found", id))?;
+        let priority = event.priority;
+        let action = event.action();
+        let used_fallback = matches!(
+            &action,
+            Ok(ScheduleAction::Play { file, .. }) if event.is_fallback(file)
+        );
+        let action = match action {
+            Ok(action) => action,
+            Err(e) => {
+                self.logs
+                    .push("error", format!("Schedule event {} failed: {}", id, e));
+                return Err(e);
+            }
+        };
+        if let ScheduleAction::Play { mode, .. } = &action {
+            if self.playback.is_playing && self.playback.is_paused {
+                let disposition = paused_disposition(
+                    *mode,
+                    priority,
+                    self.engine.conflict_policy,
+                    self.engine.paused_event_handling,
+                );
+                match disposition {
+                    PausedDisposition::Hold => {
+                        self.logs.push(
+                            "info",
+                            format!("Schedule event {} held until playback resumes", id),
+                        );
+                        self.held_events.push((id, action));
+                        return Ok(ScheduleAction::Held { id });
+                    }
+                    PausedDisposition::FireAndStop => self.logs.push(
+                        "info",
+                        format!("Schedule event {} replaces the paused track", id),
+                    ),
+                    PausedDisposition::Fire => self.logs.push(
+                        "info",
+                        format!("Schedule event {} plays over the paused track", id),
+                    ),
+                }
+            }
+        }
+        match &action {
+            ScheduleAction::Activate { playlist, .. } => {
+                if let Err(e) = self.set_active_playlist(playlist) {
+                    self.logs.push(
+                        "error",
+                        format!("Scheduled activation of '{}' failed: {}", playlist, e),
+                    );
+                    return Err(e);
+                }
+                self.logs.push(
+                    "info",
+                    format!("Scheduled activation: '{}' is now active", playlist),
+                );
+            }
+            ScheduleAction::Play { file, .. } if used_fallback => self.logs.push(
+                "warn",
+                format!(
+                    "Schedule event {}: primary file unavailable, playing fallback '{}'",
+                    id,
+                    file.display()
+                ),
+            ),
+            ScheduleAction::Play { file, .. } => self.logs.push(
+                "info",
+                format!("Schedule event {}: playing '{}'", id, file.display()),
+            ),
+            ScheduleAction::Held { .. } => {}
+        }
+        Ok(action)
     }
 
-    pub fn set_stream_output(&mut self, enabled: bool, endpoint_url: String) -> Result<(), String> {
-        if enabled && endpoint_url.trim().is_empty() {
-            return Err("Streaming endpoint URL is required when streaming is enabled".to_string());
+    /// Events held during a pause, in firing order, once playback is no
+    /// longer paused (resumed or stopped). Empty while still paused. The
+    /// caller plays them as if they had just fired.
+    pub fn take_released_events(&mut self) -> Vec<ScheduleAction> {
+        if self.playback.is_paused || self.held_events.is_empty() {
+            return Vec::new();
         }
-        self.engine.stream_output.enabled = enabled;
-        self.engine.stream_output.endpoint_url = endpoint_url.trim().to_string();
-        self.engine.save()
+        let held = std::mem::take(&mut self.held_events);
+        let ids: Vec<String> = held.iter().map(|(id, _)| id.to_string()).collect();
+        self.logs.push(
+            "info",
+            format!("Releasing held schedule event(s): {}", ids.join(", ")),
+        );
+        held.into_iter().map(|(_, action)| action).collect()
     }
 
-    pub fn set_recording(
+    /// Queue the file of a fired `insert` event in the active playlist at
+    /// `position`. Returns the 0-based index it went in at.
+    pub fn insert_scheduled_file(
         &mut self,
-        enabled: bool,
-        output_dir: Option<String>,
-    ) -> Result<(), String> {
-        if enabled && output_dir.as_deref().unwrap_or("").trim().is_empty() {
-            return Err(
-                "Recording output directory is required when recording is enabled".to_string(),
+        file: &Path,
+        position: InsertPosition,
+    ) -> Result<usize, String> {
+        match self.engine.insert_track_at(file, position) {
+            Ok(index) => {
+                self.logs.push(
+                    "info",
+                    format!("Scheduled insert: '{}' at #{}", file.display(), index + 1),
+                );
+                self.engine.save()?;
+                Ok(index)
+            }
+            Err(e) => {
+                self.logs
+                    .push("error", format!("Scheduled insert failed: {}", e));
+                Err(e)
+            }
+        }
+    }
+
+    pub fn remove_schedule_event(&mut self, id: u32) -> Result<(), String> {
+        self.engine.schedule.remove_event(id)?;
+        self.engine.save()?;
+        Ok(())
+    }
+
+    pub fn toggle_schedule_event(&mut self, id: u32) -> Result<bool, String> {
+        let new_state = self.engine.schedule.toggle_event(id)?;
+        self.engine.save()?;
+        Ok(new_state)
+    }
+
+    // ── Config setters ──────────────────────────────────────────────────
+
+    pub fn set_crossfade(&mut self, secs: f32) -> Result<(), String> {
+        self.engine.crossfade_secs = secs;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    pub fn set_signon_fade(&mut self, secs: f32) -> Result<(), String> {
+        self.engine.signon_fade_secs = secs.max(0.0);
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Set the hours (0-23) during which advisory tracks are skipped.
+    pub fn set_clean_hours(&mut self, mut hours: Vec<u8>) -> Result<(), String> {
+        if let Some(bad) = hours.iter().find(|&&h| h > 23) {
+            return Err(format!("Invalid hour {} (expected 0-23)", bad));
+        }
+        hours.sort_unstable();
+        hours.dedup();
+        self.engine.clean_hours = hours;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Keep the same artist from airing twice in a row on advance.
+    pub fn set_avoid_artist_repeat(&mut self, enabled: bool) -> Result<(), String> {
+        self.engine.avoid_artist_repeat = enabled;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    pub fn set_crossfade_lead(&mut self, secs: f32) -> Result<(), String> {
+        self.engine.crossfade_lead_secs = secs.max(0.0);
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Tracks shorter than `secs` hard-cut instead of crossfading (0 = off).
+    pub fn set_min_crossfade_track(&mut self, secs: f32) -> Result<(), String> {
+        self.engine.min_crossfade_track_secs = secs.max(0.0);
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Crossfade on manual skip instead of hard-cutting.
+    pub fn set_crossfade_on_skip(&mut self, enabled: bool) -> Result<(), String> {
+        self.engine.crossfade_on_skip = enabled;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Turn the GUI's single-key live-assist hotkeys on or off.
+    pub fn set_live_assist_hotkeys(&mut self, enabled: bool) -> Result<(), String> {
+        self.engine.live_assist_hotkeys = enabled;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Fade length for a manual skip out of `from` into the track now
+    /// playing: `crossfade_secs` when `crossfade_on_skip` is set and the pair
+    /// would crossfade in normal play (see `player::transition_between`:
+    /// `no_crossfade_in`, the lecture guard, `min_crossfade_track_secs`),
+    /// otherwise None for a hard cut.
+    pub fn skip_crossfade(&self, from: Option<&crate::track::Track>) -> Option<Duration> {
+        let secs = self.engine.crossfade_secs;
+        if !self.engine.crossfade_on_skip {
+            return None;
+        }
+        let transition = transition_between(
+            secs,
+            from?,
+            self.playing_track(),
+            self.engine.lecture_crossfade_guard(),
+            self.engine.min_crossfade_track_secs,
+        );
+        (transition == TrackTransition::Crossfade).then(|| Duration::from_secs_f32(secs))
+    }
+
+    /// How the playing track hands over to the one auto-advance plays next,
+    /// by the same rules as `player::play_playlist` (see
+    /// `player::transition_between`). Sequential when nothing follows.
+    pub fn exit_transition(&self) -> TrackTransition {
+        match self.playing_track() {
+            Some(current) => transition_between(
+                self.engine.crossfade_secs,
+                current,
+                self.upcoming_track(),
+                self.engine.lecture_crossfade_guard(),
+                self.engine.min_crossfade_track_secs,
+            ),
+            None => TrackTransition::Sequential,
+        }
+    }
+
+    /// Position in the playing track's file at which its exit transition
+    /// starts (see `player::crossfade_start_for`), or None when it simply
+    /// plays to the end. Passed to the audio thread on play.
+    pub fn exit_point(&self) -> Option<Duration> {
+        if self.exit_transition() == TrackTransition::Sequential {
+            return None;
+        }
+        let track = self.playing_track()?;
+        let secs = self.engine.crossfade_secs;
+        let start = crossfade_start_for(track, secs, self.engine.crossfade_lead_secs);
+        Some(track.cue_in.unwrap_or(Duration::ZERO) + start)
+    }
+
+    /// Length of a crossfade, or of the fade-out before a clean start.
+    pub fn crossfade_duration(&self) -> Duration {
+        Duration::from_secs_f32(self.engine.crossfade_secs.max(0.0))
+    }
+
+    /// The track auto-advance would start after the playing one, when it
+    /// comes from the same playlist (end-of-playlist loops and fallbacks
+    /// are not looked ahead to).
+    fn upcoming_track(&self) -> Option<&crate::track::Track> {
+        let pl = self.engine.active_playlist()?;
+        if !pl.auto_advance || self.playback.playlist_name.as_deref() != Some(pl.name.as_str()) {
+            return None;
+        }
+        let current = self.playback.track_index?;
+        let next = pl.next_airable(current + 1, self.in_clean_hour())?;
+        pl.tracks.get(next)
+    }
+
+    /// Set the mixing gain for overlay events and carts (0.0–2.0).
+    pub fn set_overlay_gain(&mut self, gain: f32) -> Result<(), String> {
+        if !(0.0..=OVERLAY_GAIN_MAX).contains(&gain) {
+            return Err(format!(
+                "Overlay gain {} out of range (0.0 to {})",
+                gain, OVERLAY_GAIN_MAX
+            ));
+        }
+        self.engine.overlay_gain = gain;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    pub fn set_silence_detection(
+        &mut self,
+        threshold: f32,
+        duration_secs: f32,
+    ) -> Result<(), String> {
+        self.set_silence_detection_in(threshold, "linear", duration_secs)
+    }
+
+    /// Like `set_silence_detection`, with the threshold given in `unit`
+    /// ("linear" RMS or "dbfs", e.g. -40). Stored linear; the unit is kept
+    /// so the config reads back the way it was entered.
+    pub fn set_silence_detection_in(
+        &mut self,
+        threshold: f32,
+        unit: &str,
+        duration_secs: f32,
+    ) -> Result<(), String> {
+        let unit = ThresholdUnit::from_str_loose(unit)?;
+        if unit == ThresholdUnit::Dbfs && threshold > 0.0 {
+            return Err(format!(
+                "Silence threshold {} dBFS must be 0 or below",
+                threshold
+            ));
+        }
+        self.engine.silence_threshold = unit.to_linear(threshold);
+        self.engine.silence_threshold_unit = unit;
+        self.engine.silence_duration_secs = duration_secs;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Configure the dead-air alarm: output below `threshold_db` dBFS for
+    /// `secs` while playing raises it (0 secs = disabled).
+    pub fn set_dead_air_alarm(&mut self, threshold_db: f32, secs: f32) -> Result<(), String> {
+        if !threshold_db.is_finite() || threshold_db > 0.0 {
+            return Err(format!(
+                "Dead-air threshold {} dBFS must be 0 or below",
+                threshold_db
+            ));
+        }
+        if !(0.0..=DEAD_AIR_MAX_SECS).contains(&secs) {
+            return Err(format!(
+                "Dead-air duration must be between 0 and {} seconds",
+                DEAD_AIR_MAX_SECS
+            ));
+        }
+        self.engine.dead_air_threshold_db = threshold_db;
+        self.engine.dead_air_secs = secs;
+        self.engine.save_config()
+    }
+
+    /// Feed the dead-air watchdog the transport's output `level` (linear
+    /// RMS) at `now`. Silence counts only while playing and not paused; a
+    /// stalled audio path (stale heartbeat) counts as silence. Transitions
+    /// are logged and returned so the caller can raise an alert.
+    pub fn check_dead_air(&mut self, level: f32, now: Instant) -> Option<DeadAirEvent> {
+        let threshold = crate::silence::dbfs_to_linear(self.engine.dead_air_threshold_db);
+        // A hand-edited state file can hold any value; treat it as disabled
+        let after = Duration::try_from_secs_f32(self.engine.dead_air_secs).unwrap_or_default();
+        self.dead_air.configure(threshold, after);
+
+        let playing = self.playback.is_playing && !self.playback.is_paused;
+        let stale_after = Duration::from_secs(1);
+        let flowing = self.heartbeat.is_fresh_at(SystemTime::now(), stale_after);
+        let level = if flowing { level } else { 0.0 };
+        let event = self.dead_air.update(level, playing, now)?;
+        match event {
+            DeadAirEvent::Tripped => self.logs.push(
+                "warn",
+                format!(
+                    "Dead air: output silent for {:.0}s while playing",
+                    self.engine.dead_air_secs
+                ),
+            ),
+            DeadAirEvent::Cleared => self
+                .logs
+                .push("info", "Dead air cleared: output is back".to_string()),
+        }
+        Some(event)
+    }
+
+    pub fn set_intros_folder(&mut self, path: Option<String>) -> Result<(), String> {
+        if let Some(ref p) = path {
+            if !Path::new(p).is_dir() {
+                return Err(format!("'{}' is not a valid directory", p));
+            }
+        }
+        self.engine.intros_folder = path.clone();
+        // Refresh has_intro flags on all tracks
+        for pl in &mut self.engine.playlists {
+            for track in &mut pl.tracks {
+                track.has_intro = match &path {
+                    Some(folder) => auto_intro::has_intro(Path::new(folder), &track.artist),
+                    None => false,
+                };
+            }
+        }
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    pub fn set_recurring_intro(
+        &mut self,
+        interval_secs: f32,
+        duck_volume: f32,
+    ) -> Result<(), String> {
+        self.engine.recurring_intro_interval_secs = interval_secs;
+        self.engine.recurring_intro_duck_volume = duck_volume;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Seconds the main track takes to dip into, and recover from, the duck.
+    pub fn set_recurring_intro_duck_ramp(&mut self, secs: f32) -> Result<(), String> {
+        if !secs.is_finite() || secs < 0.0 {
+            return Err("Duck ramp must be 0 or more seconds".to_string());
+        }
+        self.engine.recurring_intro_duck_ramp_secs = secs;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Play each artist's intro only once per playback session.
+    pub fn set_intro_once_per_session(&mut self, enabled: bool) -> Result<(), String> {
+        self.engine.intro_once_per_session = enabled;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Play auto-intros "before" the track or "over" its start.
+    pub fn set_intro_mode(&mut self, mode: &str) -> Result<(), String> {
+        self.engine.intro_mode = IntroMode::from_str_loose(mode)?;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// The auto-intro for the track that just became current, when an
+    /// intros folder is set and the session hasn't just heard this artist's
+    /// intro (see `IntroSession`; a new session starts with each play from
+    /// stopped). Marks it played. Only for tracks that start cleanly, not
+    /// ones crossfaded into.
+    pub fn take_intro(&mut self) -> Option<IntroCue> {
+        let folder = self.engine.intros_folder.clone()?;
+        let artist = self.playing_track()?.artist.clone();
+        if !self.intro_session.should_play(&artist) {
+            return None;
+        }
+        let path = auto_intro::find_intro(Path::new(&folder), &artist)?;
+        self.intro_session.record(&artist);
+        Some(IntroCue {
+            path,
+            mode: self.engine.intro_mode,
+            gain_db: self.engine.intro_gain_db,
+            duck_volume: self.engine.recurring_intro_duck_volume,
+            duck_ramp: Duration::from_secs_f32(self.engine.recurring_intro_duck_ramp_secs.max(0.0)),
+        })
+    }
+
+    /// Gain for auto-intros relative to their file level, -24 to +12 dB.
+    pub fn set_intro_gain(&mut self, gain_db: f32) -> Result<(), String> {
+        if !(-24.0..=12.0).contains(&gain_db) {
+            return Err("Intro gain must be between -24 and +12 dB".to_string());
+        }
+        self.engine.intro_gain_db = gain_db;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    pub fn set_conflict_policy(&mut self, policy: &str) -> Result<(), String> {
+        let parsed = ConflictPolicy::from_str_loose(policy)?;
+        self.engine.conflict_policy = parsed;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// What overlay and stop events do during a pause: "queue" or "play-over".
+    pub fn set_paused_event_handling(&mut self, handling: &str) -> Result<(), String> {
+        self.engine.paused_event_handling = PausedEventHandling::from_str_loose(handling)?;
+        self.engine.save_config()
+    }
+
+    /// Set what auto-advance does after the last track. A fallback target
+    /// must name an existing playlist.
+    pub fn set_end_of_playlist_action(
+        &mut self,
+        action: &str,
+        fallback: Option<&str>,
+    ) -> Result<(), String> {
+        let parsed = EndOfPlaylistAction::from_parts(action, fallback)?;
+        if let Some(name) = parsed.fallback_playlist() {
+            if self.engine.find_playlist(name).is_none() {
+                return Err(format!("Playlist '{}' not found", name));
+            }
+        }
+        self.engine.end_of_playlist_action = parsed;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    pub fn set_stream_output(&mut self, enabled: bool, endpoint_url: String) -> Result<(), String> {
+        if enabled && endpoint_url.trim().is_empty() {
+            return Err("Streaming endpoint URL is required when streaming is enabled".to_string());
+        }
+        self.engine.stream_output.enabled = enabled;
+        self.engine.stream_output.endpoint_url = endpoint_url.trim().to_string();
+        self.engine.save_config()
+    }
+
+    pub fn set_recording(
+        &mut self,
+        enabled: bool,
+        output_dir: Option<String>,
+    ) -> Result<(), String> {
+        if enabled && output_dir.as_deref().unwrap_or("").trim().is_empty() {
+            return Err(
+                "Recording output directory is required when recording is enabled".to_string(),
             );
         }
         self.engine.recording.enabled = enabled;
@@ -960,7 +3249,7 @@ impl AppCore {
                 Some(trimmed)
             }
         });
-        self.engine.save()
+        self.engine.save_config()
     }
 
     pub fn set_indexed_locations(&mut self, locations: Vec<String>) -> Result<(), String> {
@@ -971,7 +3260,7 @@ impl AppCore {
             .collect();
         self.engine.indexed_locations.sort();
         self.engine.indexed_locations.dedup();
-        self.engine.save()
+        self.engine.save_config()
     }
 
     pub fn set_favorite_folders(&mut self, folders: Vec<String>) -> Result<(), String> {
@@ -982,15 +3271,112 @@ impl AppCore {
             .collect();
         self.engine.favorite_folders.sort();
         self.engine.favorite_folders.dedup();
-        self.engine.save()
+        self.engine.save_config()
     }
 
     pub fn set_nowplaying_path(&mut self, path: Option<String>) -> Result<(), String> {
         self.engine.now_playing_path = path;
-        self.engine.save()?;
+        self.now_playing_last = None;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    pub fn set_now_playing_debounce(&mut self, ms: u64) -> Result<(), String> {
+        self.engine.now_playing_debounce_ms = ms;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Text shown as now-playing while an ad or station ID airs, e.g. the
+    /// station name (empty = blank).
+    pub fn set_now_playing_ad_placeholder(&mut self, text: &str) -> Result<(), String> {
+        self.engine.now_playing_ad_placeholder = text.trim().to_string();
+        self.engine.save_config()
+    }
+
+    /// Queue a now-playing snapshot when what it shows has changed, and
+    /// write the latest one to `now_playing_path` once no change has come
+    /// in for `now_playing_debounce_ms`. A burst of skips therefore writes
+    /// only the track that ends up playing. Cheap to call often; returns
+    /// true when the file was written. Callers sharing the core behind a
+    /// lock should use `take_now_playing` and write after releasing it.
+    pub fn publish_now_playing(&mut self) -> Result<bool, String> {
+        match self.take_now_playing() {
+            Some(due) => due.write().map(|()| true),
+            None => Ok(false),
+        }
+    }
+
+    /// The now-playing file due to be written, if any (see
+    /// `publish_now_playing`), without touching the disk.
+    pub fn take_now_playing(&mut self) -> Option<NowPlayingWrite> {
+        let path = self.engine.now_playing_path.clone()?;
+        let now = Instant::now();
+        let elapsed = self.playback.is_playing.then(|| self.playback.elapsed());
+        let snapshot = NowPlaying::from_engine(&self.engine, elapsed);
+        let changed = self
+            .now_playing_last
+            .as_ref()
+            .is_none_or(|last| !last.same_content(&snapshot));
+        if changed {
+            self.now_playing_last = Some(snapshot.clone());
+            self.now_playing.update(snapshot, now);
+        }
+
+        let window = Duration::from_millis(self.engine.now_playing_debounce_ms);
+        self.now_playing.set_window(window);
+        let snapshot = self.now_playing.take_due(now)?;
+        Some(NowPlayingWrite {
+            path: PathBuf::from(path),
+            xml: snapshot.to_xml(),
+        })
+    }
+
+    // ── On-air heartbeat ────────────────────────────────────────────────
+
+    /// True when audio output advanced within `max_stale`.
+    pub fn is_on_air(&self, max_stale: Duration) -> bool {
+        self.heartbeat.is_fresh_at(SystemTime::now(), max_stale)
+    }
+
+    pub fn on_air_status(&self, max_stale: Duration) -> OnAirData {
+        let now = SystemTime::now();
+        OnAirData {
+            on_air: self.heartbeat.is_fresh_at(now, max_stale),
+            is_playing: self.playback.is_playing,
+            last_audio_age_secs: self.heartbeat.age_at(now).map(|d| d.as_secs_f64()),
+        }
+    }
+
+    pub fn set_heartbeat_path(&mut self, path: Option<String>) -> Result<(), String> {
+        self.engine.heartbeat_path = path.filter(|p| !p.trim().is_empty());
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Set (or with None, clear) the file aired when nothing else can be.
+    pub fn set_emergency_file(&mut self, path: Option<String>) -> Result<(), String> {
+        self.engine.emergency_file = path.filter(|p| !p.trim().is_empty()).map(PathBuf::from);
+        self.engine.save_config()?;
         Ok(())
     }
 
+    /// Rewrite the heartbeat file while on air, so its modification time
+    /// shows when audio was last flowing (file-age checks work as-is).
+    /// Does nothing when off air or when no path is configured.
+    pub fn write_heartbeat_file(&self, max_stale: Duration) -> Result<(), String> {
+        let path = match &self.engine.heartbeat_path {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+        if !self.is_on_air(max_stale) {
+            return Ok(());
+        }
+        let stamp = Local::now().to_rfc3339();
+        fs::write(path, format!("ON AIR {}\n", stamp))
+            .map_err(|e| format!("Cannot write heartbeat file '{}': {}", path, e))
+    }
+
     /// Resolve the target directory path without doing any filesystem IO.
     /// Use `list_directory_at` (free function) in a `spawn_blocking` task for the actual read.
     pub fn resolve_directory_path(&self, path: Option<String>) -> PathBuf {
@@ -1020,6 +3406,38 @@ impl AppCore {
         Ok(results)
     }
 
+    /// Find tracks whose artist, title or file name contains `query`
+    /// (case-insensitive) across every loaded playlist.
+    pub fn search_playlists(&self, query: &str) -> Vec<PlaylistSearchResult> {
+        let needle = query.trim().to_lowercase();
+        if needle.len() < 2 {
+            return Vec::new();
+        }
+        let mut results = Vec::new();
+        for pl in &self.engine.playlists {
+            for (i, t) in pl.tracks.iter().enumerate() {
+                let file_name = t
+                    .path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                if t.artist.to_lowercase().contains(&needle)
+                    || t.title.to_lowercase().contains(&needle)
+                    || file_name.contains(&needle)
+                {
+                    results.push(PlaylistSearchResult {
+                        playlist: pl.name.clone(),
+                        track_index: i,
+                        artist: t.artist.clone(),
+                        title: t.title.clone(),
+                        path: t.path.to_string_lossy().to_string(),
+                    });
+                }
+            }
+        }
+        results
+    }
+
     pub fn get_playlist_profiles(&self) -> Vec<PlaylistProfileData> {
         self.engine
             .playlist_profiles
@@ -1089,11 +3507,8 @@ impl AppCore {
         self.engine.active_playlist_id = None;
 
         for (idx, playlist_name) in profile.playlist_names.into_iter().enumerate() {
-            let id = self.engine.create_playlist(playlist_name);
             let source_path = profile.playlist_paths.get(idx).cloned().flatten();
-            if let Some(playlist) = self.engine.playlists.iter_mut().find(|p| p.id == id) {
-                playlist.source_path = source_path;
-            }
+            self.engine.create_playlist_mut(playlist_name).source_path = source_path;
         }
 
         if let Some(first_name) = self.engine.playlists.first().map(|p| p.name.clone()) {
@@ -1137,14 +3552,8 @@ impl AppCore {
             suffix += 1;
         }
 
-        let id = self.engine.create_playlist(name.clone());
         {
-            let playlist = self
-                .engine
-                .playlists
-                .iter_mut()
-                .find(|p| p.id == id)
-                .ok_or_else(|| "Failed to create imported playlist".to_string())?;
+            let playlist = self.engine.create_playlist_mut(name.clone());
             playlist.source_path = Some(path.to_string_lossy().to_string());
             for src in sources {
                 if let Ok(track) = crate::track::Track::from_path(&src) {
@@ -1201,14 +3610,8 @@ impl AppCore {
             name = format!("{} ({})", base_name, suffix);
             suffix += 1;
         }
-        let id = self.engine.create_playlist(name.clone());
         {
-            let playlist = self
-                .engine
-                .playlists
-                .iter_mut()
-                .find(|p| p.id == id)
-                .ok_or_else(|| "Failed to create imported playlist".to_string())?;
+            let playlist = self.engine.create_playlist_mut(name.clone());
             playlist.source_path = Some(source_path.to_string());
             for track in tracks {
                 playlist.tracks.push(track);
@@ -1219,6 +3622,88 @@ impl AppCore {
         Ok(name)
     }
 
+    /// Copy the playlists called `names` out of another station's state file
+    /// into this engine. Tracks keep referring to the same audio files; the
+    /// other file is only read. A name already in use here gets a " (2)"
+    /// style suffix. Nothing is added unless every name is found. Returns
+    /// the names the copies were added under.
+    pub fn import_playlists_from_state(
+        &mut self,
+        path: &Path,
+        names: &[String],
+    ) -> Result<Vec<String>, String> {
+        let source = Engine::read_from(path)?;
+        self.import_playlists_from(&source, names)
+    }
+
+    /// `import_playlists_from_state` with the other state already read
+    /// (`Engine::read_from`), so the file I/O can happen outside any lock.
+    pub fn import_playlists_from(
+        &mut self,
+        source: &Engine,
+        names: &[String],
+    ) -> Result<Vec<String>, String> {
+        let from = source
+            .state_path()
+            .map(|p| format!(" in '{}'", p.display()))
+            .unwrap_or_default();
+        let mut copies = Vec::with_capacity(names.len());
+        for wanted in names {
+            let pl = source
+                .find_playlist(wanted)
+                .ok_or_else(|| format!("Playlist '{}' not found{}", wanted, from))?;
+            copies.push(pl.clone());
+        }
+
+        let mut added = Vec::with_capacity(copies.len());
+        for copy in copies {
+            let mut name = copy.name.clone();
+            let mut suffix = 2usize;
+            while self.engine.find_playlist(&name).is_some() {
+                name = format!("{} ({})", copy.name, suffix);
+                suffix += 1;
+            }
+            let playlist = self.engine.create_playlist_mut(name.clone());
+            playlist.tracks = copy.tracks;
+            playlist.source_path = copy.source_path;
+            playlist.auto_advance = copy.auto_advance;
+            added.push(name);
+        }
+        self.engine.save()?;
+        Ok(added)
+    }
+
+    /// Import a `.cue` sheet as a new playlist. Each cue track becomes a
+    /// playlist entry pointing into the shared audio file via `cue_in`/`cue_out`.
+    pub fn import_cue(&mut self, file_path: &str) -> Result<String, String> {
+        let path = Path::new(file_path);
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read cue sheet '{}': {}", path.display(), e))?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let sheet = crate::cue::parse_cue(&content, base_dir)?;
+        let total = crate::track::Track::from_path(&sheet.file)?.duration;
+        let tracks = sheet.to_tracks(total);
+
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("Imported")
+            .trim();
+        let base_name = if stem.is_empty() { "Imported" } else { stem };
+        let mut name = base_name.to_string();
+        let mut suffix = 2usize;
+        while self.engine.find_playlist(&name).is_some() {
+            name = format!("{} ({})", base_name, suffix);
+            suffix += 1;
+        }
+
+        self.engine.create_playlist(name.clone());
+        self.push_preloaded_tracks(&name, tracks)?;
+        self.engine.set_active(&name)?;
+        self.engine.save()?;
+        Ok(name)
+    }
+
     /// Extract track paths + optional source path from a playlist (no IO).
     /// Used to prepare data for async M3U export without holding the lock.
     pub fn get_m3u_export_data(
@@ -1320,29 +3805,253 @@ impl AppCore {
                 scheduled: ad.scheduled,
                 days: ad.days.clone(),
                 hours: ad.hours.clone(),
+                normalized_file: ad
+                    .normalized_file
+                    .as_ref()
+                    .map(|p| p.to_string_lossy().to_string()),
+                start_date: ad.start_date.map(|d| d.to_string()),
+                end_date: ad.end_date.map(|d| d.to_string()),
             })
             .collect()
     }
 
+    /// Add an ad, making its loudness-normalized copy first when
+    /// `normalize_ads` is on. Normalizing runs ffmpeg twice, so the app
+    /// uses `add_ad_deferred` and normalizes outside the core lock.
     pub fn add_ad(&mut self, name: String, mp3_file: String) -> Result<usize, String> {
+        let idx = self.add_ad_deferred(name, mp3_file)?;
+        self.normalize_ad_now(idx)?;
+        Ok(idx)
+    }
+
+    /// Add an ad without normalizing it. When `ad_normalize_target` is
+    /// Some, the caller runs `normalize_ad_file` and hands the outcome to
+    /// `apply_ad_normalization`.
+    pub fn add_ad_deferred(&mut self, name: String, mp3_file: String) -> Result<usize, String> {
         let ad = AdConfig::new(name, PathBuf::from(mp3_file));
         let idx = self.engine.add_ad(ad);
         self.engine.save()?;
         Ok(idx)
     }
 
-    pub fn remove_ad(&mut self, index: usize) -> Result<(), String> {
-        self.engine.remove_ad(index)?;
-        self.engine.save()?;
-        Ok(())
+    /// Loudness target for normalizing a newly set ad file, or None when
+    /// `normalize_ads` is off.
+    pub fn ad_normalize_target(&self) -> Option<f32> {
+        let settings = &self.engine.ad_inserter;
+        settings
+            .normalize_ads
+            .then_some(settings.normalize_target_lufs)
     }
 
-    pub fn toggle_ad(&mut self, index: usize) -> Result<bool, String> {
-        let new_state = self.engine.toggle_ad(index)?;
-        self.engine.save()?;
+    /// Record the outcome of normalizing `original` for the ad at `index`.
+    /// Failures are logged and the original is used instead. Ignored if
+    /// the ad's file changed meanwhile.
+    pub fn apply_ad_normalization(
+        &mut self,
+        index: usize,
+        original: &Path,
+        outcome: Result<PathBuf, String>,
+    ) -> Result<(), String> {
+        let normalized = match outcome {
+            Ok(path) => {
+                self.logs
+                    .push("info", format!("Normalized ad: {}", path.display()));
+                Some(path)
+            }
+            Err(e) => {
+                self.logs.push(
+                    "warn",
+                    format!("Ad normalization failed, using original: {}", e),
+                );
+                None
+            }
+        };
+        match self.engine.ads.get_mut(index) {
+            Some(ad) if ad.mp3_file == original => {
+                ad.normalized_file = normalized;
+                self.engine.save()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Normalize the ad at `index` in place, blocking until ffmpeg is done.
+    fn normalize_ad_now(&mut self, index: usize) -> Result<(), String> {
+        let Some(target) = self.ad_normalize_target() else {
+            return Ok(());
+        };
+        let Some(original) = self.engine.ads.get(index).map(|ad| ad.mp3_file.clone()) else {
+            return Ok(());
+        };
+        let outcome = normalize_ad_file(&original, target);
+        self.apply_ad_normalization(index, &original, outcome)
+    }
+
+    pub fn set_ad_normalization(&mut self, enabled: bool, target_lufs: f32) -> Result<(), String> {
+        if !(-70.0..=-5.0).contains(&target_lufs) {
+            return Err(format!(
+                "Target loudness {} LUFS out of range (-70 to -5)",
+                target_lufs
+            ));
+        }
+        self.engine.ad_inserter.normalize_ads = enabled;
+        self.engine.ad_inserter.normalize_target_lufs = target_lufs;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Set the minimum spacing between repeats of the same ad: `unit` is
+    /// "none", "ads" (other ads in between) or "minutes".
+    pub fn set_ad_repeat_gap(&mut self, unit: &str, value: u32) -> Result<(), String> {
+        self.engine.ad_inserter.min_repeat_gap = RepeatGap::from_parts(unit, value)?;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Place the station ID at the "start", "end" or "both" ends of the roll.
+    pub fn set_station_id_position(&mut self, position: &str) -> Result<(), String> {
+        self.engine.ad_inserter.station_id_position = StationIdPosition::from_str_loose(position)?;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Whether scheduled ad rolls wait for the current song ("after_current")
+    /// or cut it short ("immediate").
+    pub fn set_ad_insert_timing(&mut self, timing: &str) -> Result<(), String> {
+        self.engine.ad_inserter.insert_timing = AdInsertTiming::from_str_loose(timing)?;
+        self.engine.save_config()
+    }
+
+    /// Queue an ad break behind the current track now (see
+    /// `AdInserterService::insert_scheduled`). Whether the playing track
+    /// should be cut for it is left to the caller: see the result's
+    /// `interrupt_current` and `facade::insert_ad_break`.
+    /// A break is deferred (an error, logged as a warning but not as a failed
+    /// break) while a high-priority scheduled event is near, a lecture airs,
+    /// or no ad is valid (`AdInserterService::check_break_can_run`). The roll
+    /// is capped at `max_roll_secs`, timed from `lengths` (measure the
+    /// `ad_break_files` before taking any lock on the core).
+    pub fn insert_ad_break(&mut self, lengths: &RollLengths) -> Result<AdInsertionResult, String> {
+        self.insert_ad_break_at(&RealClock, lengths)
+    }
+
+    fn insert_ad_break_at(
+        &mut self,
+        clock: &dyn Clock,
+        lengths: &RollLengths,
+    ) -> Result<AdInsertionResult, String> {
+        if let Err(e) = AdInserterService::check_break_can_run(&self.engine, clock) {
+            self.logs.push("warn", e.clone());
+            return Err(e);
+        }
+        let hour_start = is_hour_start(clock);
+        match AdInserterService::insert_scheduled(&mut self.engine, clock, hour_start, lengths) {
+            Ok(result) => {
+                self.logs.push(
+                    "info",
+                    format!(
+                        "Ad break queued: {} ({:.0}s)",
+                        result.ads_inserted.join(", "),
+                        result.total_duration.as_secs_f64()
+                    ),
+                );
+                self.engine.save()?;
+                Ok(result)
+            }
+            Err(e) => {
+                self.logs.push("error", format!("Ad break failed: {}", e));
+                // Deferrals returned above; this is a break that didn't go in.
+                // In-memory mode writes no files
+                if self.engine.state_path().is_some() {
+                    self.ad_logger().log_failure(&[], &e);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Files an ad break now could play (see
+    /// `AdInserterService::roll_candidates`), for measuring into the
+    /// `RollLengths` that `insert_ad_break` and `preview_ad_break` take.
+    pub fn ad_break_files(&self) -> Vec<PathBuf> {
+        AdInserterService::roll_candidates(&self.engine, &RealClock)
+    }
+
+    /// Dry run of an ad break now: the ads and station ID an insert would
+    /// play, in order. Nothing is queued and no plays are recorded.
+    pub fn preview_ad_break(&self, lengths: &RollLengths) -> Result<AdPreviewData, String> {
+        let AdPreview { roll, result } =
+            AdInserterService::preview(&self.engine, &RealClock, lengths)?;
+        let position = self.engine.ad_inserter.station_id_position;
+        let id_first = usize::from(result.station_id_played && position.at_start());
+        let roll = roll
+            .into_iter()
+            .enumerate()
+            .map(|(i, (path, label))| {
+                let ad = i
+                    .checked_sub(id_first)
+                    .and_then(|j| result.ad_durations.get(j));
+                let (station_id, duration) = match ad {
+                    Some(duration) => (false, *duration),
+                    None => (true, result.station_id_duration.unwrap_or_default()),
+                };
+                AdPreviewEntry {
+                    label,
+                    path: path.to_string_lossy().to_string(),
+                    station_id,
+                    duration_secs: duration.as_secs_f64(),
+                }
+            })
+            .collect();
+        Ok(AdPreviewData {
+            roll,
+            forced_repeats: result.forced_repeats,
+            total_secs: result.total_duration.as_secs_f64(),
+        })
+    }
+
+    /// Seconds around a high-priority scheduled event in which ad rolls wait.
+    pub fn set_ad_event_guard(&mut self, secs: u32) -> Result<(), String> {
+        self.engine.ad_inserter.event_guard_secs = secs;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Cap ad rolls at `max_roll_secs` (0 = no cap). With `fit_best`, ads
+    /// too long for the time left are swapped for shorter ones that fit.
+    pub fn set_ad_roll_limit(&mut self, max_roll_secs: u32, fit_best: bool) -> Result<(), String> {
+        self.engine.ad_inserter.max_roll_secs = max_roll_secs;
+        self.engine.ad_inserter.fit_best = fit_best;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Keep only the newest `max` ad failure records, trimming the saved
+    /// log now if it is longer.
+    pub fn set_ad_failure_retention(&mut self, max: usize) -> Result<(), String> {
+        if max == 0 {
+            return Err("Failure retention must be at least 1".to_string());
+        }
+        self.engine.ad_inserter.failure_retention = max;
+        self.engine.save_config()?;
+        self.ad_logger().prune_failures(max);
+        Ok(())
+    }
+
+    pub fn remove_ad(&mut self, index: usize) -> Result<(), String> {
+        self.engine.remove_ad(index)?;
+        self.engine.save()?;
+        Ok(())
+    }
+
+    pub fn toggle_ad(&mut self, index: usize) -> Result<bool, String> {
+        let new_state = self.engine.toggle_ad(index)?;
+        self.engine.save()?;
         Ok(new_state)
     }
 
+    /// Edit an ad. A new audio file is normalized before returning when
+    /// `normalize_ads` is on (see `add_ad`).
     pub fn update_ad(
         &mut self,
         index: usize,
@@ -1353,19 +4062,65 @@ impl AppCore {
         days: Vec<String>,
         hours: Vec<u8>,
     ) -> Result<(), String> {
+        let file_changed =
+            self.update_ad_deferred(index, name, enabled, mp3_file, scheduled, days, hours)?;
+        if file_changed {
+            self.normalize_ad_now(index)?;
+        }
+        Ok(())
+    }
+
+    /// `update_ad` without normalizing. Returns true when the audio file
+    /// changed, so the caller should normalize it (see `add_ad_deferred`).
+    pub fn update_ad_deferred(
+        &mut self,
+        index: usize,
+        name: String,
+        enabled: bool,
+        mp3_file: String,
+        scheduled: bool,
+        days: Vec<String>,
+        hours: Vec<u8>,
+    ) -> Result<bool, String> {
         let len = self.engine.ads.len();
+        let mp3_file = PathBuf::from(mp3_file);
         let ad = self
             .engine
             .ads
             .get_mut(index)
             .ok_or_else(|| format!("Ad index {} out of range ({} ads)", index, len))?;
+        let file_changed = ad.mp3_file != mp3_file;
         ad.name = name;
         ad.enabled = enabled;
-        ad.mp3_file = PathBuf::from(mp3_file);
+        if file_changed {
+            ad.mp3_file = mp3_file;
+            ad.normalized_file = None;
+        }
         ad.scheduled = scheduled;
         ad.days = days;
         ad.hours = hours;
         self.engine.save()?;
+        Ok(file_changed)
+    }
+
+    /// Limit an ad to campaign flight dates (YYYY-MM-DD, inclusive; None =
+    /// open). Outside them the ad is skipped even when its day/hour match.
+    pub fn set_ad_flight(
+        &mut self,
+        index: usize,
+        start_date: Option<&str>,
+        end_date: Option<&str>,
+    ) -> Result<(), String> {
+        let (start, end) = parse_date_range(start_date, end_date)?;
+        let len = self.engine.ads.len();
+        let ad = self
+            .engine
+            .ads
+            .get_mut(index)
+            .ok_or_else(|| format!("Ad index {} out of range ({} ads)", index, len))?;
+        ad.start_date = start;
+        ad.end_date = end;
+        self.engine.save()?;
         Ok(())
     }
 
@@ -1380,10 +4135,66 @@ impl AppCore {
         Ok(())
     }
 
+    // ── Carts ───────────────────────────────────────────────────────────
+
+    pub fn get_carts(&self) -> Vec<CartData> {
+        self.engine
+            .carts
+            .iter()
+            .map(|cart| CartData {
+                index: cart.index,
+                label: cart.label.clone(),
+                path: cart.path.to_string_lossy().to_string(),
+                overlay: cart.overlay,
+            })
+            .collect()
+    }
+
+    pub fn add_cart(
+        &mut self,
+        label: String,
+        path: String,
+        overlay: bool,
+    ) -> Result<usize, String> {
+        let index = self.engine.add_cart(label, PathBuf::from(path), overlay);
+        self.engine.save()?;
+        Ok(index)
+    }
+
+    pub fn remove_cart(&mut self, index: usize) -> Result<(), String> {
+        self.engine.remove_cart(index)?;
+        self.engine.save()?;
+        Ok(())
+    }
+
+    /// Look up cart `index` for playback, checking that its file exists.
+    pub fn cart_to_play(&mut self, index: usize) -> Result<CartSlot, String> {
+        let cart = self
+            .engine
+            .cart(index)
+            .cloned()
+            .ok_or_else(|| format!("Cart {} not found", index))?;
+        if !cart.path.exists() {
+            return Err(format!("Cart file not found: {}", cart.path.display()));
+        }
+        self.logs.push(
+            "info",
+            format!("Cart {}: {} ({})", cart.index, cart.label, cart.mode()),
+        );
+        Ok(cart)
+    }
+
     // ── Ad Statistics & Reports ──────────────────────────────────────────
 
+    /// The ad play and failure log, with failures capped at the configured
+    /// `failure_retention`.
+    pub fn ad_logger(&self) -> AdPlayLogger {
+        let retention = self.engine.ad_inserter.failure_retention;
+        AdPlayLogger::new(Path::new(".")).with_max_failures(retention)
+    }
+
     pub fn get_ad_stats(&self, start: Option<&str>, end: Option<&str>) -> AdStatistics {
-        let logger = AdPlayLogger::new(Path::new("."));
+        let logger = self.ad_logger();
         match (start, end) {
             (Some(s), Some(e)) => logger.get_ad_statistics_filtered(s, e),
             _ => logger.get_ad_statistics(),
@@ -1391,15 +4202,33 @@ impl AppCore {
     }
 
     pub fn get_ad_daily_counts(&self, ad_name: &str) -> Vec<(String, usize)> {
-        let logger = AdPlayLogger::new(Path::new("."));
+        let logger = self.ad_logger();
         let counts = logger.get_daily_play_counts(ad_name);
         let mut entries: Vec<(String, usize)> = counts.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
         entries
     }
 
+    /// Daily counts for every date in `start`..=`end` (MM-DD-YY), zero-filled
+    /// so charts show continuous days.
+    pub fn get_ad_daily_counts_filled(
+        &self,
+        ad_name: &str,
+        start: &str,
+        end: &str,
+    ) -> Result<Vec<(String, usize)>, String> {
+        let logger = self.ad_logger();
+        logger.get_daily_play_counts_filled(ad_name, start, end)
+    }
+
+    /// Plays per clock hour (0–23) across `start`..=`end` (MM-DD-YY).
+    pub fn get_ad_hour_histogram(&self, ad_name: &str, start: &str, end: &str) -> [usize; 24] {
+        let logger = self.ad_logger();
+        logger.get_hour_histogram(ad_name, start, end)
+    }
+
     pub fn get_ad_failures(&self) -> Vec<crate::ad_logger::AdFailure> {
-        let logger = AdPlayLogger::new(Path::new("."));
+        let logger = self.ad_logger();
         logger.get_failures()
     }
 
@@ -1411,8 +4240,9 @@ impl AppCore {
         ad_name: Option<&str>,
         company_name: Option<&str>,
     ) -> Result<Vec<String>, String> {
-        let logger = AdPlayLogger::new(Path::new("."));
-        let reporter = AdReportGenerator::new(&logger);
+        let logger = self.ad_logger();
+        let reporter =
+            AdReportGenerator::new(&logger).with_branding(self.engine.report_branding.clone());
         let out_path = Path::new(output_dir);
 
         if !out_path.is_dir() {
@@ -1444,6 +4274,86 @@ impl AppCore {
         }
     }
 
+    /// Reports generated so far (ad, period, company, files), oldest first.
+    pub fn get_ad_report_audit(&self) -> Vec<ReportAuditEntry> {
+        let logger = self.ad_logger();
+        AdReportGenerator::new(&logger).audit_history()
+    }
+
+    /// Scheduled versus confirmed plays of `ad_name` over `start`..=`end`
+    /// (MM-DD-YY), to surface under-delivery.
+    pub fn get_ad_delivery_report(
+        &self,
+        ad_name: &str,
+        start: &str,
+        end: &str,
+    ) -> Result<DeliveryReport, String> {
+        let logger = self.ad_logger();
+        AdReportGenerator::new(&logger)
+            .with_ads(&self.engine.ads)
+            .delivery_report(ad_name, start, end)
+    }
+
+    /// Set the logo and header color used in ad report PDFs. `None` clears
+    /// the logo or restores the default header color.
+    pub fn set_report_branding(
+        &mut self,
+        logo_path: Option<String>,
+        header_rgb: Option<[u8; 3]>,
+    ) -> Result<(), String> {
+        let logo_path = logo_path.filter(|p| !p.trim().is_empty());
+        if let Some(path) = logo_path.as_deref().filter(|p| !Path::new(p).is_file()) {
+            return Err(format!("Logo file '{}' not found", path));
+        }
+        self.engine.report_branding = ReportBranding {
+            logo_path,
+            header_rgb,
+        };
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Generate a weekly or monthly rollup report (CSV + PDF) into `output_dir`.
+    /// Returns the written paths, or an empty list when there are no plays.
+    pub fn generate_ad_rollup(
+        &self,
+        start: &str,
+        end: &str,
+        granularity: &str,
+        output_dir: &str,
+    ) -> Result<Vec<String>, String> {
+        let (logger, branding) = (self.ad_logger(), self.engine.report_branding.clone());
+        Self::write_ad_rollup(&logger, branding, start, end, granularity, output_dir)
+    }
+
+    /// `generate_ad_rollup` with the logger and branding already copied out
+    /// (`ad_logger`), so the report can be written without holding the core.
+    pub fn write_ad_rollup(
+        logger: &AdPlayLogger,
+        branding: ReportBranding,
+        start: &str,
+        end: &str,
+        granularity: &str,
+        output_dir: &str,
+    ) -> Result<Vec<String>, String> {
+        let granularity = RollupGranularity::from_str_loose(granularity)
+            .ok_or_else(|| format!("Unknown rollup granularity '{}'", granularity))?;
+        let out_path = Path::new(output_dir);
+        if !out_path.is_dir() {
+            return Err(format!("'{}' is not a valid directory", output_dir));
+        }
+        let reporter = AdReportGenerator::new(logger).with_branding(branding);
+        Ok(reporter
+            .generate_rollup(start, end, granularity, out_path)
+            .map(|r| {
+                vec![
+                    r.csv_path.to_string_lossy().to_string(),
+                    r.pdf_path.to_string_lossy().to_string(),
+                ]
+            })
+            .unwrap_or_default())
+    }
+
     // ── RDS ─────────────────────────────────────────────────────────────
 
     pub fn get_rds_config(&self) -> RdsConfigData {
@@ -1467,6 +4377,8 @@ impl AppCore {
                     hours: m.scheduled.hours.clone(),
                 })
                 .collect(),
+            segmented_ps: self.engine.rds.segmented_ps,
+            radio_text: self.engine.rds.radio_text,
         }
     }
 
@@ -1554,7 +4466,20 @@ impl AppCore {
         self.engine.rds.ip = ip;
         self.engine.rds.port = port;
         self.engine.rds.default_message = default_message;
-        self.engine.save()?;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Choose how RDS text is sent: word-aware 8-char PS frames and/or an
+    /// additional full-length RadioText command.
+    pub fn set_rds_formatting(
+        &mut self,
+        segmented_ps: bool,
+        radio_text: bool,
+    ) -> Result<(), String> {
+        self.engine.rds.segmented_ps = segmented_ps;
+        self.engine.rds.radio_text = radio_text;
+        self.engine.save_config()?;
         Ok(())
     }
 
@@ -1580,9 +4505,24 @@ impl AppCore {
         LectureConfigData {
             blacklist,
             whitelist,
+            no_ads_during_lectures: self.engine.no_ads_during_lectures,
+            no_crossfade_for_lectures: self.engine.no_crossfade_for_lectures,
         }
     }
 
+    pub fn set_no_ads_during_lectures(&mut self, enabled: bool) -> Result<(), String> {
+        self.engine.no_ads_during_lectures = enabled;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
+    /// Hard cuts instead of crossfades into or out of lectures.
+    pub fn set_no_crossfade_for_lectures(&mut self, enabled: bool) -> Result<(), String> {
+        self.engine.no_crossfade_for_lectures = enabled;
+        self.engine.save_config()?;
+        Ok(())
+    }
+
     pub fn lecture_blacklist_add(&mut self, keyword: &str) -> Result<(), String> {
         self.engine.lecture_detector.add_blacklist(keyword);
         self.engine.save()?;
@@ -1611,6 +4551,94 @@ impl AppCore {
         self.engine.lecture_detector.is_lecture(artist)
     }
 
+    // ── Config bundle ───────────────────────────────────────────────────
+
+    /// Zip the engine state, the ad play log and a manifest of referenced
+    /// audio into `path`. Audio files are copied in only with `include_audio`.
+    pub fn export_config_bundle(
+        &mut self,
+        path: &str,
+        include_audio: bool,
+    ) -> Result<BundleManifest, String> {
+        let snapshot = self.config_bundle_snapshot()?;
+        let manifest =
+            config_bundle::write_bundle(&snapshot, Path::new("."), Path::new(path), include_audio)?;
+        self.log_bundle_export(path, &manifest);
+        Ok(manifest)
+    }
+
+    /// The state to bundle, captured so the zip can be written (and audio
+    /// copied into it) without holding the core.
+    pub fn config_bundle_snapshot(&self) -> Result<BundleSnapshot, String> {
+        BundleSnapshot::of(&self.engine)
+    }
+
+    pub fn log_bundle_export(&mut self, path: &str, manifest: &BundleManifest) {
+        self.logs.push(
+            "info",
+            format!(
+                "Exported configuration bundle to {} ({} audio references)",
+                path,
+                manifest.audio.len()
+            ),
+        );
+    }
+
+    /// Load the state from a bundle made by `export_config_bundle`. With
+    /// `merge`, playlists, ads and schedule events are added alongside the
+    /// current ones (name collisions skipped); otherwise the whole state is
+    /// replaced. The bundle is validated before anything changes, and the
+    /// current state is backed up next to the state file first.
+    pub fn import_config_bundle(
+        &mut self,
+        path: &str,
+        merge: bool,
+    ) -> Result<ImportSummary, String> {
+        let incoming = config_bundle::read_bundle(Path::new(path))?;
+        self.apply_config_bundle(path, incoming, merge)
+    }
+
+    /// The locked half of `import_config_bundle`: apply state already read
+    /// from the bundle at `path` (see `config_bundle::read_bundle`).
+    pub fn apply_config_bundle(
+        &mut self,
+        path: &str,
+        incoming: Engine,
+        merge: bool,
+    ) -> Result<ImportSummary, String> {
+        if !merge && self.playback.is_playing {
+            return Err("Stop playback before replacing the whole state".to_string());
+        }
+        if let Some(backup) = self.engine.write_backup()? {
+            self.logs
+                .push("info", format!("Backed up state to {}", backup.display()));
+        }
+
+        let summary = if merge {
+            config_bundle::merge_into(&mut self.engine, incoming)
+        } else {
+            let summary = ImportSummary::replacing(&incoming);
+            self.engine.replace_with(incoming);
+            summary
+        };
+        self.engine.save_config()?;
+
+        let skipped = summary.playlists_skipped.len() + summary.ads_skipped.len();
+        self.logs.push(
+            "info",
+            format!(
+                "Imported configuration bundle {} ({}): {} playlists, {} ads, {} events added, {} skipped",
+                path,
+                if merge { "merge" } else { "replace" },
+                summary.playlists_added.len(),
+                summary.ads_added.len(),
+                summary.events_added,
+                skipped + summary.events_skipped
+            ),
+        );
+        Ok(summary)
+    }
+
     // ── Logs ────────────────────────────────────────────────────────────
 
     pub fn get_logs(&self, since_index: Option<usize>) -> Vec<LogEntry> {
@@ -1628,11 +4656,29 @@ impl AppCore {
 
 // ── Tests ───────────────────────────────────────────────────────────────────
 
+/// Parse an optional YYYY-MM-DD date range, rejecting an end before the start.
+fn parse_date_range(
+    start_date: Option<&str>,
+    end_date: Option<&str>,
+) -> Result<(Option<NaiveDate>, Option<NaiveDate>), String> {
+    let parse = |s: &str| {
+        NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", s))
+    };
+    let start = start_date.map(parse).transpose()?;
+    let end = end_date.map(parse).transpose()?;
+    if let (Some(start), Some(end)) = (start, end) {
+        if end < start {
+            return Err(format!("End date {} is before start date {}", end, start));
+        }
+    }
+    Ok((start, end))
+}
+
 fn is_audio_file(path: &Path) -> bool {
-    const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "flac", "ogg", "aac", "m4a"];
     path.extension()
         .and_then(|ext| ext.to_str())
-        .map(|ext| AUDIO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .map(|ext| auto_intro::AUDIO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
         .unwrap_or(false)
 }
 
@@ -1682,6 +4728,122 @@ pub fn list_directory_at(target: PathBuf) -> Result<Vec<FileBrowserEntry>, Strin
     Ok(entries)
 }
 
+/// Check each path exists and (optionally) decodes and has readable tags.
+/// Date tokens are checked against today's file. Intended to be called from
+/// a `spawn_blocking` task, since decoding touches every file.
+pub fn validate_track_paths(paths: &[PathBuf], check_decode: bool) -> Vec<TrackIssueData> {
+    let mut issues = Vec::new();
+    for (index, path) in paths.iter().enumerate() {
+        let file = date_tokens::expand_for_today(path);
+        let problem = if let Some(error) = crate::track::file_error(&file) {
+            error
+        } else if check_decode && !crate::track::can_decode(&file) {
+            "Audio does not decode (corrupt or truncated)".to_string()
+        } else if check_decode && lofty::read_from_path(&file).is_err() {
+            "Tags are unreadable".to_string()
+        } else {
+            continue;
+        };
+        issues.push(TrackIssueData {
+            index,
+            path: path.to_string_lossy().to_string(),
+            problem,
+        });
+    }
+    issues
+}
+
+/// Re-encode each `(index, path)` to `format` with `run` (normally
+/// `audio_editor::run_ffmpeg`). Intended to be called from a `spawn_blocking`
+/// task; hand the results to `AppCore::apply_conversions`.
+pub fn convert_track_files<F>(
+    tracks: Vec<(usize, PathBuf)>,
+    format: ConvertFormat,
+    quality: u8,
+    run: F,
+) -> Vec<TrackConversion>
+where
+    F: Fn(&[String]) -> Result<(), String>,
+{
+    tracks
+        .into_iter()
+        .map(|(index, path)| {
+            let result = audio_editor::convert_file(&path, format, quality, &run);
+            (index, path, result)
+        })
+        .collect()
+}
+
+/// Collect audio files under `dir` (descending into subfolders when
+/// `recursive`), sorted naturally so "track2" comes before "track10".
+/// Intended to be called from a `spawn_blocking` task.
+pub fn collect_audio_files(dir: &Path, recursive: bool) -> Result<Vec<PathBuf>, String> {
+    if !dir.is_dir() {
+        return Err(format!("'{}' is not a directory", dir.display()));
+    }
+    let mut files = Vec::new();
+    collect_audio_files_into(dir, recursive, &mut files);
+    files.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+    Ok(files)
+}
+
+fn collect_audio_files_into(dir: &Path, recursive: bool, out: &mut Vec<PathBuf>) {
+    let read = match fs::read_dir(dir) {
+        Ok(r) => r,
+        Err(_) => return,
+    };
+    for entry in read.flatten() {
+        let path = dir.join(entry.file_name());
+        if path.is_dir() {
+            if recursive {
+                collect_audio_files_into(&path, recursive, out);
+            }
+        } else if is_audio_file(&path) {
+            out.push(path);
+        }
+    }
+}
+
+/// Case-insensitive comparison that orders embedded digit runs numerically.
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let mut na = String::new();
+                while let Some(c) = a.peek().copied().filter(|c| c.is_ascii_digit()) {
+                    na.push(c);
+                    a.next();
+                }
+                let mut nb = String::new();
+                while let Some(c) = b.peek().copied().filter(|c| c.is_ascii_digit()) {
+                    nb.push(c);
+                    b.next();
+                }
+                let na = na.trim_start_matches('0');
+                let nb = nb.trim_start_matches('0');
+                let ord = na.len().cmp(&nb.len()).then_with(|| na.cmp(nb));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ord = x.to_ascii_lowercase().cmp(&y.to_ascii_lowercase());
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
 /// Search `locations` for audio files matching `query`. Intended to be called
 /// from a `spawn_blocking` task so it doesn't hold the core mutex.
 pub fn search_files_in_locations(locations: &[String], query: &str) -> Vec<FileSearchResult> {
@@ -1743,11 +4905,26 @@ fn collect_matches(path: &Path, query: &str, out: &mut Vec<FileSearchResult>, de
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_audio::write_wav;
 
     fn make_core() -> AppCore {
         AppCore::new_test()
     }
 
+    fn push_mock_track(core: &mut AppCore, playlist: &str, path: &str, artist: &str) {
+        core.engine
+            .find_playlist_mut(playlist)
+            .unwrap()
+            .tracks
+            .push(crate::track::Track {
+                path: path.into(),
+                title: "Song".into(),
+                artist: artist.into(),
+                duration: Duration::from_secs(180),
+                ..Default::default()
+            });
+    }
+
     // -- Playlist CRUD --
 
     #[test]
@@ -1814,23 +4991,71 @@ mod tests {
     }
 
     #[test]
-    fn set_active_playlist() {
+    fn duplicate_playlist_copies_tracks_with_fresh_state() {
         let mut core = make_core();
-        core.create_playlist("Main".to_string()).unwrap();
-        let id = core.set_active_playlist("Main").unwrap();
-        assert!(id > 0);
-
-        let status = core.get_status();
-        assert_eq!(status.active_playlist, Some("Main".to_string()));
-
-        let playlists = core.get_playlists();
-        assert!(playlists[0].is_active);
+        core.create_playlist("Show".to_string()).unwrap();
+        push_mock_track(&mut core, "Show", "a.mp3", "A");
+        push_mock_track(&mut core, "Show", "b.mp3", "B");
+        let original = core.engine.find_playlist_mut("Show").unwrap();
+        original.current_index = Some(1);
+        original.tracks[0].played_duration = Some(Duration::from_secs(42));
+
+        let copy_name = "Show Copy".to_string();
+        core.duplicate_playlist("show", copy_name).unwrap();
+        let copy = core.engine.find_playlist("Show Copy").unwrap();
+        let paths: Vec<_> = copy.tracks.iter().map(|t| t.path.clone()).collect();
+        assert_eq!(paths, [PathBuf::from("a.mp3"), PathBuf::from("b.mp3")]);
+        assert_eq!(copy.current_index, None);
+        assert!(copy.tracks.iter().all(|t| t.played_duration.is_none()));
+        let original = core.engine.find_playlist("Show").unwrap();
+        assert_eq!(original.current_index, Some(1));
+        assert!(original.tracks[0].played_duration.is_some());
     }
 
     #[test]
-    fn set_active_nonexistent_errors() {
+    fn editing_duplicate_leaves_original_alone() {
         let mut core = make_core();
-        assert!(core.set_active_playlist("Ghost").is_err());
+        core.create_playlist("Show".to_string()).unwrap();
+        push_mock_track(&mut core, "Show", "a.mp3", "A");
+        push_mock_track(&mut core, "Show", "b.mp3", "B");
+        core.duplicate_playlist("Show", "Copy".to_string()).unwrap();
+
+        core.remove_tracks("Copy", &[0]).unwrap();
+        core.engine.find_playlist_mut("Copy").unwrap().tracks[0].title = "Edited".into();
+        assert_eq!(core.get_playlist_tracks("Copy").unwrap().len(), 1);
+        let original = core.get_playlist_tracks("Show").unwrap();
+        assert_eq!(original.len(), 2);
+        assert!(original.iter().all(|t| t.title != "Edited"));
+    }
+
+    #[test]
+    fn duplicate_playlist_name_collision_errors() {
+        let mut core = make_core();
+        core.create_playlist("A".to_string()).unwrap();
+        core.create_playlist("B".to_string()).unwrap();
+        assert!(core.duplicate_playlist("A", "b".to_string()).is_err());
+        assert!(core.duplicate_playlist("Missing", "C".to_string()).is_err());
+        assert_eq!(core.get_playlists().len(), 2);
+    }
+
+    #[test]
+    fn set_active_playlist() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        let id = core.set_active_playlist("Main").unwrap();
+        assert!(id > 0);
+
+        let status = core.get_status();
+        assert_eq!(status.active_playlist, Some("Main".to_string()));
+
+        let playlists = core.get_playlists();
+        assert!(playlists[0].is_active);
+    }
+
+    #[test]
+    fn set_active_nonexistent_errors() {
+        let mut core = make_core();
+        assert!(core.set_active_playlist("Ghost").is_err());
     }
 
     // -- Config --
@@ -1854,6 +5079,158 @@ mod tests {
         assert_eq!(core.get_config().crossfade_secs, 3.5);
     }
 
+    #[test]
+    fn set_signon_fade_clamps_negative() {
+        let mut core = make_core();
+        core.set_signon_fade(2.5).unwrap();
+        assert_eq!(core.get_config().signon_fade_secs, 2.5);
+        core.set_signon_fade(-1.0).unwrap();
+        assert_eq!(core.get_config().signon_fade_secs, 0.0);
+    }
+
+    #[test]
+    fn set_crossfade_lead_clamps_negative() {
+        let mut core = make_core();
+        core.set_crossfade_lead(4.0).unwrap();
+        assert_eq!(core.get_config().crossfade_lead_secs, 4.0);
+        core.set_crossfade_lead(-2.0).unwrap();
+        assert_eq!(core.get_config().crossfade_lead_secs, 0.0);
+    }
+
+    #[test]
+    fn set_min_crossfade_track_clamps_negative() {
+        let mut core = make_core();
+        assert_eq!(core.get_config().min_crossfade_track_secs, 0.0);
+        core.set_min_crossfade_track(5.0).unwrap();
+        assert_eq!(core.get_config().min_crossfade_track_secs, 5.0);
+        core.set_min_crossfade_track(-1.0).unwrap();
+        assert_eq!(core.get_config().min_crossfade_track_secs, 0.0);
+    }
+
+    #[test]
+    fn skip_crossfade_follows_flag_and_crossfade_length() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        push_mock_track(&mut core, "Main", "a.mp3", "Alpha");
+        push_mock_track(&mut core, "Main", "b.mp3", "Beta");
+        core.set_crossfade(3.0).unwrap();
+        let from = core.engine.find_playlist("Main").unwrap().tracks[0].clone();
+        core.prepare_play(Some(1)).unwrap();
+        // Off by default: skips stay hard cuts
+        assert_eq!(core.skip_crossfade(Some(&from)), None);
+
+        core.set_crossfade_on_skip(true).unwrap();
+        assert!(core.get_config().crossfade_on_skip);
+        let fade = core.skip_crossfade(Some(&from));
+        assert_eq!(fade, Some(Duration::from_secs(3)));
+        // Nothing was playing before (e.g. an emergency track)
+        assert_eq!(core.skip_crossfade(None), None);
+
+        // Nothing to fade over without a crossfade length
+        core.set_crossfade(0.0).unwrap();
+        assert_eq!(core.skip_crossfade(Some(&from)), None);
+    }
+
+    #[test]
+    fn exit_point_follows_transition_rules() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        push_mock_track(&mut core, "Main", "a.mp3", "Alpha");
+        push_mock_track(&mut core, "Main", "b.mp3", "Beta");
+        core.prepare_play(Some(0)).unwrap();
+        // No crossfade configured: play to the end
+        assert_eq!(core.exit_point(), None);
+
+        core.set_crossfade(3.0).unwrap();
+        core.set_crossfade_lead(1.0).unwrap();
+        assert_eq!(core.exit_transition(), TrackTransition::Crossfade);
+        assert_eq!(core.exit_point(), Some(Duration::from_secs(176)));
+
+        core.set_track_no_crossfade_in("Main", 1, true).unwrap();
+        assert_eq!(core.exit_transition(), TrackTransition::FadeOutOnly);
+        assert_eq!(core.exit_point(), Some(Duration::from_secs(176)));
+        core.set_track_no_crossfade_in("Main", 1, false).unwrap();
+
+        core.set_min_crossfade_track(600.0).unwrap();
+        assert_eq!(core.exit_point(), None);
+        core.set_min_crossfade_track(0.0).unwrap();
+
+        // Live assist waits for the operator; the last track has no partner
+        core.set_playlist_auto_advance("Main", false).unwrap();
+        assert_eq!(core.exit_point(), None);
+        core.set_playlist_auto_advance("Main", true).unwrap();
+        core.prepare_play(Some(1)).unwrap();
+        assert_eq!(core.exit_point(), None);
+    }
+
+    #[test]
+    fn take_intro_carries_mode_and_gain() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Adele.mp3"), b"intro").unwrap();
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        push_mock_track(&mut core, "Main", "a.mp3", "Adele");
+        core.prepare_play(Some(0)).unwrap();
+        // No intros folder, no intro
+        assert!(core.take_intro().is_none());
+
+        let folder = dir.path().to_string_lossy().into_owned();
+        core.set_intros_folder(Some(folder)).unwrap();
+        core.set_intro_mode("over").unwrap();
+        core.set_intro_gain(-6.0).unwrap();
+        let cue = core.take_intro().unwrap();
+        assert_eq!(cue.path, dir.path().join("Adele.mp3"));
+        assert_eq!(cue.mode, IntroMode::Over);
+        assert_eq!(cue.gain_db, -6.0);
+        assert_eq!(cue.duck_volume, 0.3);
+        // Same artist straight after: not again
+        assert!(core.take_intro().is_none());
+    }
+
+    #[test]
+    fn skip_crossfade_respects_transition_rules() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        push_mock_track(&mut core, "Main", "a.mp3", "Alpha");
+        push_mock_track(&mut core, "Main", "b.mp3", "Beta");
+        core.set_crossfade(3.0).unwrap();
+        core.set_crossfade_on_skip(true).unwrap();
+        let from = core.engine.find_playlist("Main").unwrap().tracks[0].clone();
+        core.prepare_play(Some(1)).unwrap();
+        assert!(core.skip_crossfade(Some(&from)).is_some());
+
+        // Too short to crossfade out of
+        core.set_min_crossfade_track(600.0).unwrap();
+        assert_eq!(core.skip_crossfade(Some(&from)), None);
+        core.set_min_crossfade_track(0.0).unwrap();
+
+        // Lecture on either side hard-cuts when the guard is on
+        let mut sermon = from.clone();
+        sermon.artist = "Rabbi Cohen".into();
+        core.set_no_crossfade_for_lectures(true).unwrap();
+        assert_eq!(core.skip_crossfade(Some(&sermon)), None);
+        assert!(core.skip_crossfade(Some(&from)).is_some());
+
+        // Target asks for a clean start
+        core.set_track_no_crossfade_in("Main", 1, true).unwrap();
+        assert_eq!(core.skip_crossfade(Some(&from)), None);
+    }
+
+    #[test]
+    fn set_overlay_gain_validates_range() {
+        let mut core = make_core();
+        assert_eq!(core.get_config().overlay_gain, 1.0);
+        core.set_overlay_gain(0.5).unwrap();
+        assert_eq!(core.get_config().overlay_gain, 0.5);
+        assert!(core.set_overlay_gain(2.5).is_err());
+        assert!(core.set_overlay_gain(-0.1).is_err());
+        assert_eq!(core.get_config().overlay_gain, 0.5);
+    }
+
     #[test]
     fn set_silence_detection() {
         let mut core = make_core();
@@ -1861,6 +5238,20 @@ mod tests {
         let config = core.get_config();
         assert_eq!(config.silence_threshold, 0.02);
         assert_eq!(config.silence_duration_secs, 5.0);
+        assert_eq!(config.silence_threshold_unit, "linear");
+        assert_eq!(config.silence_threshold_display, 0.02);
+    }
+
+    #[test]
+    fn set_silence_detection_in_dbfs_stores_linear() {
+        let mut core = make_core();
+        core.set_silence_detection_in(-40.0, "dbfs", 3.0).unwrap();
+        let config = core.get_config();
+        assert!((config.silence_threshold - 0.01).abs() < 1e-6);
+        assert_eq!(config.silence_threshold_unit, "dbfs");
+        assert!((config.silence_threshold_display + 40.0).abs() < 1e-3);
+        assert!(core.set_silence_detection_in(6.0, "dbfs", 3.0).is_err());
+        assert!(core.set_silence_detection_in(0.01, "volts", 3.0).is_err());
     }
 
     #[test]
@@ -1872,6 +5263,45 @@ mod tests {
         assert_eq!(status.recurring_intro_duck_volume, 0.2);
     }
 
+    #[test]
+    fn set_recurring_intro_duck_ramp() {
+        let mut core = make_core();
+        assert_eq!(core.get_config().recurring_intro_duck_ramp_secs, 0.5);
+        core.set_recurring_intro_duck_ramp(1.25).unwrap();
+        assert_eq!(core.get_config().recurring_intro_duck_ramp_secs, 1.25);
+        assert!(core.set_recurring_intro_duck_ramp(-1.0).is_err());
+        assert_eq!(core.engine.recurring_intro_duck_ramp_secs, 1.25);
+    }
+
+    #[test]
+    fn set_intro_gain() {
+        let mut core = make_core();
+        assert_eq!(core.get_config().intro_gain_db, 0.0);
+        core.set_intro_gain(3.0).unwrap();
+        assert_eq!(core.get_config().intro_gain_db, 3.0);
+        assert!(core.set_intro_gain(20.0).is_err());
+        assert!(core.set_intro_gain(f32::NAN).is_err());
+        assert_eq!(core.engine.intro_gain_db, 3.0);
+    }
+
+    #[test]
+    fn set_intro_once_per_session() {
+        let mut core = make_core();
+        assert!(!core.get_config().intro_once_per_session);
+        core.set_intro_once_per_session(true).unwrap();
+        assert!(core.get_config().intro_once_per_session);
+    }
+
+    #[test]
+    fn set_intro_mode() {
+        let mut core = make_core();
+        assert_eq!(core.get_config().intro_mode, "before");
+        core.set_intro_mode("Over").unwrap();
+        assert_eq!(core.get_config().intro_mode, "over");
+        assert!(core.set_intro_mode("under").is_err());
+        assert_eq!(core.get_config().intro_mode, "over");
+    }
+
     #[test]
     fn set_conflict_policy() {
         let mut core = make_core();
@@ -1988,6 +5418,27 @@ mod tests {
         assert_eq!(events[0].label, Some("News".to_string()));
     }
 
+    #[test]
+    fn schedule_conflicts_surface_in_list() {
+        let mut core = make_core();
+        let a = core
+            .add_schedule_event("14:00", "stop", "news.mp3", None, None, Some(vec![0]))
+            .unwrap();
+        let b = core
+            .add_schedule_event("14:00", "stop", "promo.mp3", None, None, None)
+            .unwrap();
+        core.add_schedule_event("14:00", "overlay", "sting.mp3", None, None, None)
+            .unwrap();
+
+        assert_eq!(core.get_schedule_conflicts(), vec![(a, b)]);
+        let schedule = core.get_schedule();
+        let by_id = |id: u32| schedule.iter().find(|e| e.id == id).unwrap();
+        assert_eq!(by_id(a).conflicts_with, vec![b]);
+        assert_eq!(by_id(b).conflicts_with, vec![a]);
+        assert!(schedule.iter().any(|e| e.conflicts_with.is_empty()));
+        assert!(core.get_logs(None).iter().any(|l| l.level == "warn"));
+    }
+
     #[test]
     fn add_schedule_event_bad_time_errors() {
         let mut core = make_core();
@@ -2004,6 +5455,38 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn folder_schedule_event_becomes_rotator() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().to_string_lossy().to_string();
+        fs::write(temp.path().join("id1.mp3"), b"x").unwrap();
+        fs::write(temp.path().join("id2.mp3"), b"x").unwrap();
+
+        let mut core = make_core();
+        let id = core
+            .add_schedule_event("09:00", "overlay", &dir, None, None, None)
+            .unwrap();
+        assert!(core.get_schedule()[0].rotator);
+
+        let first = core.resolve_schedule_event_file(id).unwrap();
+        let second = core.resolve_schedule_event_file(id).unwrap();
+        assert_ne!(first, second);
+        assert!(first.starts_with(temp.path()));
+    }
+
+    #[test]
+    fn rotator_requires_folder() {
+        let mut core = make_core();
+        let id = core
+            .add_schedule_event("09:00", "overlay", "jingle.mp3", None, None, None)
+            .unwrap();
+        assert!(!core.get_schedule()[0].rotator);
+        assert!(core.set_schedule_event_rotator(id, true).is_err());
+        assert!(core.set_schedule_event_rotator(99, false).is_err());
+        let file = core.resolve_schedule_event_file(id).unwrap();
+        assert_eq!(file, PathBuf::from("jingle.mp3"));
+    }
+
     #[test]
     fn remove_schedule_event() {
         let mut core = make_core();
@@ -2026,6 +5509,164 @@ mod tests {
         assert!(enabled);
     }
 
+    #[test]
+    fn insert_event_position_is_set_and_fired() {
+        let mut core = make_core();
+        let id = core
+            .add_schedule_event("12:00", "insert", "promo.mp3", None, None, None)
+            .unwrap();
+        assert_eq!(core.get_schedule()[0].insert_position, "next");
+
+        core.set_schedule_event_insert_position(id, "end").unwrap();
+        assert_eq!(core.get_schedule()[0].insert_position, "end");
+        match core.fire_schedule_event(id).unwrap() {
+            ScheduleAction::Play { position, .. } => assert_eq!(position, InsertPosition::End),
+            other => panic!("expected play, got {:?}", other),
+        }
+
+        let unknown = core.set_schedule_event_insert_position(id, "middle");
+        assert!(unknown.is_err());
+        let overlay = core
+            .add_schedule_event("13:00", "overlay", "jingle.mp3", None, None, None)
+            .unwrap();
+        let not_insert = core.set_schedule_event_insert_position(overlay, "end");
+        assert!(not_insert.is_err());
+    }
+
+    #[test]
+    fn activate_event_switches_active_playlist() {
+        let mut core = make_core();
+        core.create_playlist("Morning".to_string()).unwrap();
+        core.create_playlist("Evening".to_string()).unwrap();
+        core.set_active_playlist("Morning").unwrap();
+        let id = core
+            .add_schedule_event("18:00", "activate", "Evening", None, None, None)
+            .unwrap();
+        core.set_schedule_event_autostart(id, true).unwrap();
+        assert!(core.get_schedule()[0].autostart);
+
+        let due: Vec<u32> = core
+            .engine
+            .schedule
+            .events_at_time(parse_time("18:00").unwrap(), 1)
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(due, vec![id]);
+
+        let action = core.fire_schedule_event(id).unwrap();
+        assert_eq!(
+            action,
+            ScheduleAction::Activate {
+                playlist: "Evening".to_string(),
+                autostart: true,
+            }
+        );
+        assert_eq!(core.engine.active_playlist().unwrap().name, "Evening");
+    }
+
+    #[test]
+    fn activate_event_for_missing_playlist_errors_and_logs() {
+        let mut core = make_core();
+        core.create_playlist("Morning".to_string()).unwrap();
+        core.set_active_playlist("Morning").unwrap();
+        let id = core
+            .add_schedule_event("18:00", "activate", "Evening", None, None, None)
+            .unwrap();
+
+        assert!(core.fire_schedule_event(id).is_err());
+        assert_eq!(core.engine.active_playlist().unwrap().name, "Morning");
+        let logged = core
+            .get_logs(None)
+            .iter()
+            .any(|l| l.level == "error" && l.message.contains("Evening"));
+        assert!(logged);
+    }
+
+    #[test]
+    fn missing_schedule_file_plays_fallback_and_logs_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let fallback = dir.path().join("generic_id.mp3");
+        std::fs::write(&fallback, b"x").unwrap();
+        let mut core = make_core();
+        let missing = dir.path().join("deleted_id.mp3");
+        let id = core
+            .add_schedule_event("12:00", "stop", missing.to_str().unwrap(), None, None, None)
+            .unwrap();
+        core.set_schedule_event_fallback(id, fallback.to_str())
+            .unwrap();
+        assert_eq!(
+            core.get_schedule()[0].fallback_file.as_deref(),
+            fallback.to_str()
+        );
+
+        let action = core.fire_schedule_event(id).unwrap();
+        assert_eq!(
+            action,
+            ScheduleAction::Play {
+                mode: ScheduleMode::Stop,
+                file: fallback.clone(),
+                position: InsertPosition::Next,
+            }
+        );
+        let logged = core
+            .get_logs(None)
+            .iter()
+            .any(|l| l.level == "warn" && l.message.contains("fallback"));
+        assert!(logged);
+    }
+
+    #[test]
+    fn schedule_file_and_fallback_both_missing_logs_error() {
+        let mut core = make_core();
+        let id = core
+            .add_schedule_event("12:00", "stop", "/nonexistent/id.mp3", None, None, None)
+            .unwrap();
+        core.set_schedule_event_fallback(id, Some("/nonexistent/generic.mp3"))
+            .unwrap();
+
+        assert!(core.fire_schedule_event(id).is_err());
+        let logged = core
+            .get_logs(None)
+            .iter()
+            .any(|l| l.level == "error" && l.message.contains("generic.mp3"));
+        assert!(logged);
+    }
+
+    #[test]
+    fn schedule_reports_next_fire_and_dates() {
+        let mut core = make_core();
+        let id = core
+            .add_schedule_event("14:00", "stop", "news.mp3", None, None, None)
+            .unwrap();
+        let next = core.get_schedule()[0].next_fire.clone().unwrap();
+        assert!(next.ends_with("14:00:00"));
+
+        core.set_schedule_event_dates(id, Some("2020-01-01"), Some("2020-01-01"))
+            .unwrap();
+        let event = &core.get_schedule()[0];
+        assert_eq!(event.start_date.as_deref(), Some("2020-01-01"));
+        assert!(event.next_fire.is_none());
+
+        let backwards = core.set_schedule_event_dates(id, Some("2020-02-01"), Some("2020-01-01"));
+        assert!(backwards.is_err());
+        let bad_format = core.set_schedule_event_dates(id, Some("01/02/2020"), None);
+        assert!(bad_format.is_err());
+        assert!(core.set_schedule_event_dates(99, None, None).is_err());
+        core.set_schedule_event_dates(id, None, None).unwrap();
+        assert!(core.get_schedule()[0].next_fire.is_some());
+    }
+
+    #[test]
+    fn autostart_only_applies_to_activate_events() {
+        let mut core = make_core();
+        let id = core
+            .add_schedule_event("12:00", "overlay", "jingle.mp3", None, None, None)
+            .unwrap();
+        assert!(core.set_schedule_event_autostart(id, true).is_err());
+        assert!(core.set_schedule_event_autostart(99, true).is_err());
+    }
+
     // -- Ads --
 
     #[test]
@@ -2051,6 +5692,46 @@ mod tests {
         assert!(core.get_ads().is_empty());
     }
 
+    #[test]
+    fn cart_crud_keeps_button_numbers_contiguous() {
+        let mut core = make_core();
+        for (i, label) in ["One", "Two", "Three"].iter().enumerate() {
+            let index = core
+                .add_cart(label.to_string(), format!("{}.wav", i), i != 1)
+                .unwrap();
+            assert_eq!(index, i + 1);
+        }
+
+        core.remove_cart(2).unwrap();
+        let carts = core.get_carts();
+        let listed: Vec<(usize, &str)> =
+            carts.iter().map(|c| (c.index, c.label.as_str())).collect();
+        assert_eq!(listed, vec![(1, "One"), (2, "Three")]);
+        assert!(core.remove_cart(5).is_err());
+    }
+
+    #[test]
+    fn cart_to_play_requires_existing_file() {
+        let mut core = make_core();
+        let dir = std::env::temp_dir().join(format!("sf_cart_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let wav = dir.join("sting.wav");
+        write_wav(&wav, &[0; 64], 8000);
+
+        let gone = dir.join("gone.wav").to_string_lossy().to_string();
+        core.add_cart("Gone".to_string(), gone, true).unwrap();
+        let sting = wav.to_string_lossy().to_string();
+        core.add_cart("Sting".to_string(), sting, false).unwrap();
+
+        assert!(core.cart_to_play(1).unwrap_err().contains("not found"));
+        assert!(core.cart_to_play(9).is_err());
+        let cart = core.cart_to_play(2).unwrap();
+        assert_eq!(cart.label, "Sting");
+        assert!(!cart.overlay);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn remove_ad_out_of_range_errors() {
         let mut core = make_core();
@@ -2094,77 +5775,352 @@ mod tests {
     }
 
     #[test]
-    fn reorder_ad() {
+    fn preview_ad_break_lists_roll_without_recording_plays() {
+        let dir = tempfile::tempdir().unwrap();
         let mut core = make_core();
-        core.add_ad("First".to_string(), "1.mp3".to_string())
-            .unwrap();
-        core.add_ad("Second".to_string(), "2.mp3".to_string())
-            .unwrap();
-        core.reorder_ad(0, 1).unwrap();
+        assert!(core.preview_ad_break(&RollLengths::default()).is_err());
 
-        let ads = core.get_ads();
-        assert_eq!(ads[0].name, "Second");
-        assert_eq!(ads[1].name, "First");
+        let wav = dir.path().join("spot.wav");
+        write_wav(&wav, &[0i16; 8000], 8000);
+        core.add_ad("Spot".to_string(), wav.to_string_lossy().to_string())
+            .unwrap();
+        let lengths = RollLengths::measure(&core.ad_break_files());
+        let preview = core.preview_ad_break(&lengths).unwrap();
+        assert_eq!(preview.roll.len(), 1);
+        assert_eq!(preview.roll[0].label, "Spot");
+        assert!(!preview.roll[0].station_id);
+        assert!((preview.total_secs - 1.0).abs() < 0.01);
+        assert!(core.engine.ad_play_history.is_empty());
     }
 
     #[test]
-    fn reorder_ad_out_of_range_errors() {
+    fn ad_break_waits_for_priority_events_and_caps_the_roll() {
+        use crate::clock::FixedClock;
+        use crate::scheduler::{Priority, ScheduleMode, parse_time};
+        use chrono::TimeZone;
+
+        let dir = tempfile::tempdir().unwrap();
         let mut core = make_core();
-        core.add_ad("Ad1".to_string(), "1.mp3".to_string()).unwrap();
-        assert!(core.reorder_ad(0, 5).is_err());
-    }
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        push_mock_track(&mut core, "Main", "a.mp3", "A");
+        for name in ["Spot A", "Spot B"] {
+            let wav = dir.path().join(format!("{}.wav", name));
+            crate::test_audio::write_silence(&wav, 1);
+            core.add_ad(name.to_string(), wav.to_string_lossy().to_string())
+                .unwrap();
+        }
+        core.engine.schedule.add_event(
+            parse_time("08:00").unwrap(),
+            ScheduleMode::Stop,
+            "news.mp3".into(),
+            Priority(9),
+            Some("News".into()),
+            vec![],
+        );
 
-    // -- RDS --
+        // A minute before the news: the break waits and nothing is queued
+        let clock = FixedClock(Local.with_ymd_and_hms(2026, 10, 12, 7, 59, 0).unwrap());
+        let lengths = RollLengths::default();
+        let err = core.insert_ad_break_at(&clock, &lengths).unwrap_err();
+        assert!(err.contains("deferred"));
+        assert_eq!(core.get_playlist_tracks("Main").unwrap().len(), 1);
+        assert!(core.engine.ad_play_history.is_empty());
+
+        // Without the guard it runs, cut to the one ad that fits in 1s
+        core.set_ad_event_guard(0).unwrap();
+        core.set_ad_roll_limit(1, false).unwrap();
+        let result = core.insert_ad_break_at(&clock, &lengths).unwrap();
+        assert_eq!(result.ads_inserted, ["Spot A"]);
+        assert_eq!(core.get_playlist_tracks("Main").unwrap().len(), 2);
+    }
 
     #[test]
-    fn get_default_rds_config() {
-        let core = make_core();
-        let rds = core.get_rds_config();
-        assert_eq!(rds.ip, "127.0.0.1");
-        assert_eq!(rds.port, 10001);
-        assert!(rds.messages.is_empty());
+    fn add_ad_normalization_failure_keeps_original() {
+        let mut core = make_core();
+        core.set_ad_normalization(true, -16.0).unwrap();
+        assert!(core.get_config().normalize_ads);
+        core.add_ad("Missing".to_string(), "missing_ad.mp3".to_string())
+            .unwrap();
+
+        let ads = core.get_ads();
+        assert_eq!(ads[0].mp3_file, "missing_ad.mp3");
+        assert!(ads[0].normalized_file.is_none());
+        let logs = core.get_logs(None);
+        assert!(logs.iter().any(|l| l.level == "warn"));
     }
 
     #[test]
-    fn add_and_get_rds_message() {
+    fn deferred_normalization_applies_only_to_the_same_file() {
         let mut core = make_core();
-        let idx = core.add_rds_message("Test RDS".to_string()).unwrap();
-        assert_eq!(idx, 0);
+        core.set_ad_normalization(true, -16.0).unwrap();
+        let idx = core
+            .add_ad_deferred("Spot".to_string(), "a.mp3".to_string())
+            .unwrap();
+        assert_eq!(core.ad_normalize_target(), Some(-16.0));
+        assert!(core.get_ads()[idx].normalized_file.is_none());
+
+        // The file was swapped while the first copy was being made
+        let (name, file) = ("Spot".to_string(), "b.mp3".to_string());
+        let changed = core.update_ad_deferred(idx, name, true, file, false, vec![], vec![]);
+        assert!(changed.unwrap());
+        let stale = Ok(PathBuf::from("a.normalized.mp3"));
+        core.apply_ad_normalization(idx, Path::new("a.mp3"), stale)
+            .unwrap();
+        assert!(core.get_ads()[idx].normalized_file.is_none());
 
-        let rds = core.get_rds_config();
-        assert_eq!(rds.messages.len(), 1);
-        assert_eq!(rds.messages[0].text, "Test RDS");
-        // RdsMessage::new() defaults to enabled=false
-        assert!(!rds.messages[0].enabled);
+        let fresh = Ok(PathBuf::from("b.normalized.mp3"));
+        core.apply_ad_normalization(idx, Path::new("b.mp3"), fresh)
+            .unwrap();
+        let normalized = core.get_ads()[idx].normalized_file.clone();
+        assert_eq!(normalized.as_deref(), Some("b.normalized.mp3"));
+
+        core.set_ad_normalization(false, -16.0).unwrap();
+        assert_eq!(core.ad_normalize_target(), None);
     }
 
     #[test]
-    fn remove_rds_message() {
+    fn set_ad_normalization_rejects_out_of_range_target() {
         let mut core = make_core();
-        core.add_rds_message("Msg".to_string()).unwrap();
-        core.remove_rds_message(0).unwrap();
-        assert!(core.get_rds_config().messages.is_empty());
+        assert!(core.set_ad_normalization(true, 3.0).is_err());
+        assert!(!core.get_config().normalize_ads);
+        core.set_ad_normalization(false, -23.0).unwrap();
+        assert_eq!(core.get_config().normalize_target_lufs, -23.0);
     }
 
     #[test]
-    fn remove_rds_message_out_of_range_errors() {
+    fn set_ad_repeat_gap() {
         let mut core = make_core();
-        assert!(core.remove_rds_message(0).is_err());
+        assert_eq!(core.get_config().ad_min_repeat_gap, "none");
+        core.set_ad_repeat_gap("ads", 2).unwrap();
+        assert_eq!(core.get_config().ad_min_repeat_gap, "2 ads");
+        core.set_ad_repeat_gap("minutes", 30).unwrap();
+        assert_eq!(core.get_config().ad_min_repeat_gap, "30 min");
+        core.set_ad_repeat_gap("ads", 0).unwrap();
+        assert_eq!(core.get_config().ad_min_repeat_gap, "none");
+        assert!(core.set_ad_repeat_gap("hours", 1).is_err());
     }
 
     #[test]
-    fn toggle_rds_message() {
+    fn set_station_id_position() {
         let mut core = make_core();
-        core.add_rds_message("Msg".to_string()).unwrap();
-        // RdsMessage::new() defaults to enabled=false, so first toggle enables
-        let enabled = core.toggle_rds_message(0).unwrap();
-        assert!(enabled);
-        let disabled = core.toggle_rds_message(0).unwrap();
-        assert!(!disabled);
+        assert_eq!(core.get_config().station_id_position, "start");
+        core.set_station_id_position("both").unwrap();
+        assert_eq!(core.get_config().station_id_position, "both");
+        assert!(core.set_station_id_position("middle").is_err());
+        assert_eq!(core.get_config().station_id_position, "both");
     }
 
     #[test]
-    fn update_rds_message() {
+    fn set_ad_insert_timing() {
+        let mut core = make_core();
+        assert_eq!(core.get_config().ad_insert_timing, "after_current");
+        core.set_ad_insert_timing("immediate").unwrap();
+        assert_eq!(core.get_config().ad_insert_timing, "immediate");
+        assert!(core.set_ad_insert_timing("soon").is_err());
+    }
+
+    #[test]
+    fn set_output_sample_rate_validates_range() {
+        let mut core = make_core();
+        assert_eq!(core.get_config().output_sample_rate, None);
+        core.set_output_sample_rate(Some(48_000)).unwrap();
+        assert_eq!(core.get_config().output_sample_rate, Some(48_000));
+        assert!(core.set_output_sample_rate(Some(1_000)).is_err());
+        assert!(core.set_output_sample_rate(Some(384_000)).is_err());
+        assert_eq!(core.get_config().output_sample_rate, Some(48_000));
+        core.set_output_sample_rate(None).unwrap();
+        assert_eq!(core.get_config().output_sample_rate, None);
+    }
+
+    #[test]
+    fn set_agc_validates_and_reports() {
+        let mut core = make_core();
+        assert!(!core.get_config().agc_enabled);
+        assert_eq!(core.agc_config(), None);
+
+        core.set_agc(true, -16.0, 5.0, 800.0, 9.0).unwrap();
+        let config = core.get_config();
+        assert!(config.agc_enabled);
+        assert_eq!(config.agc_target_db, -16.0);
+        assert_eq!(
+            core.agc_config(),
+            Some(AgcConfig {
+                target_db: -16.0,
+                attack_ms: 5.0,
+                release_ms: 800.0,
+                max_gain_db: 9.0,
+            })
+        );
+
+        assert!(core.set_agc(true, 3.0, 5.0, 800.0, 9.0).is_err());
+        assert!(core.set_agc(true, -16.0, -1.0, 800.0, 9.0).is_err());
+        assert!(core.set_agc(true, -16.0, 5.0, 800.0, 30.0).is_err());
+        assert_eq!(core.get_config().agc_max_gain_db, 9.0);
+    }
+
+    #[test]
+    fn set_channel_mode_persists_and_reports() {
+        let mut core = make_core();
+        assert_eq!(core.channel_mode(), ChannelMode::Stereo);
+        assert_eq!(core.get_config().channel_mode, "stereo");
+
+        assert_eq!(core.set_channel_mode("mono").unwrap(), ChannelMode::MonoSum);
+        assert_eq!(core.get_config().channel_mode, "mono_sum");
+        core.set_channel_mode("swap_lr").unwrap();
+        assert_eq!(core.channel_mode(), ChannelMode::SwapLR);
+
+        assert!(core.set_channel_mode("5.1").is_err());
+        assert_eq!(core.channel_mode(), ChannelMode::SwapLR);
+    }
+
+    #[test]
+    fn program_and_monitor_devices_are_independent() {
+        let mut core = make_core();
+        core.set_output_device(Some("Air Card".to_string()))
+            .unwrap();
+        core.set_monitor_device(Some("Headphones".to_string()))
+            .unwrap();
+        let config = core.get_config();
+        assert_eq!(config.output_device_name.as_deref(), Some("Air Card"));
+        assert_eq!(config.monitor_device_name.as_deref(), Some("Headphones"));
+        assert_eq!(
+            core.output_device_for(OutputBus::Program).as_deref(),
+            Some("Air Card")
+        );
+        assert_eq!(
+            core.output_device_for(OutputBus::Monitor).as_deref(),
+            Some("Headphones")
+        );
+
+        core.set_monitor_device(None).unwrap();
+        assert_eq!(core.output_device_for(OutputBus::Monitor), None);
+        assert_eq!(
+            core.output_device_for(OutputBus::Program).as_deref(),
+            Some("Air Card")
+        );
+    }
+
+    #[test]
+    fn set_ad_event_guard() {
+        let mut core = make_core();
+        assert_eq!(core.get_config().ad_event_guard_secs, 120);
+        core.set_ad_event_guard(30).unwrap();
+        assert_eq!(core.get_config().ad_event_guard_secs, 30);
+    }
+
+    #[test]
+    fn set_ad_roll_limit() {
+        let mut core = make_core();
+        assert_eq!(core.get_config().ad_max_roll_secs, 0);
+        assert!(!core.get_config().ad_fit_best);
+        core.set_ad_roll_limit(180, true).unwrap();
+        assert_eq!(core.get_config().ad_max_roll_secs, 180);
+        assert!(core.get_config().ad_fit_best);
+    }
+
+    #[test]
+    fn set_ad_failure_retention() {
+        let mut core = make_core();
+        assert_eq!(core.get_config().ad_failure_retention, 50);
+        core.set_ad_failure_retention(200).unwrap();
+        assert_eq!(core.get_config().ad_failure_retention, 200);
+        assert!(core.set_ad_failure_retention(0).is_err());
+        assert_eq!(core.get_config().ad_failure_retention, 200);
+    }
+
+    #[test]
+    fn reorder_ad() {
+        let mut core = make_core();
+        core.add_ad("First".to_string(), "1.mp3".to_string())
+            .unwrap();
+        core.add_ad("Second".to_string(), "2.mp3".to_string())
+            .unwrap();
+        core.reorder_ad(0, 1).unwrap();
+
+        let ads = core.get_ads();
+        assert_eq!(ads[0].name, "Second");
+        assert_eq!(ads[1].name, "First");
+    }
+
+    #[test]
+    fn reorder_ad_out_of_range_errors() {
+        let mut core = make_core();
+        core.add_ad("Ad1".to_string(), "1.mp3".to_string()).unwrap();
+        assert!(core.reorder_ad(0, 5).is_err());
+    }
+
+    #[test]
+    fn set_ad_flight_dates() {
+        let mut core = make_core();
+        core.add_ad("Sale".to_string(), "sale.mp3".to_string())
+            .unwrap();
+        core.set_ad_flight(0, Some("2026-01-01"), Some("2026-01-31"))
+            .unwrap();
+        let ad = &core.get_ads()[0];
+        assert_eq!(ad.start_date.as_deref(), Some("2026-01-01"));
+        assert_eq!(ad.end_date.as_deref(), Some("2026-01-31"));
+
+        let reversed = core.set_ad_flight(0, Some("2026-02-01"), Some("2026-01-01"));
+        assert!(reversed.is_err());
+        assert!(core.set_ad_flight(0, Some("01/02/2026"), None).is_err());
+        assert!(core.set_ad_flight(3, None, None).is_err());
+
+        core.set_ad_flight(0, None, None).unwrap();
+        assert!(core.get_ads()[0].end_date.is_none());
+    }
+
+    // -- RDS --
+
+    #[test]
+    fn get_default_rds_config() {
+        let core = make_core();
+        let rds = core.get_rds_config();
+        assert_eq!(rds.ip, "127.0.0.1");
+        assert_eq!(rds.port, 10001);
+        assert!(rds.messages.is_empty());
+    }
+
+    #[test]
+    fn add_and_get_rds_message() {
+        let mut core = make_core();
+        let idx = core.add_rds_message("Test RDS".to_string()).unwrap();
+        assert_eq!(idx, 0);
+
+        let rds = core.get_rds_config();
+        assert_eq!(rds.messages.len(), 1);
+        assert_eq!(rds.messages[0].text, "Test RDS");
+        // RdsMessage::new() defaults to enabled=false
+        assert!(!rds.messages[0].enabled);
+    }
+
+    #[test]
+    fn remove_rds_message() {
+        let mut core = make_core();
+        core.add_rds_message("Msg".to_string()).unwrap();
+        core.remove_rds_message(0).unwrap();
+        assert!(core.get_rds_config().messages.is_empty());
+    }
+
+    #[test]
+    fn remove_rds_message_out_of_range_errors() {
+        let mut core = make_core();
+        assert!(core.remove_rds_message(0).is_err());
+    }
+
+    #[test]
+    fn toggle_rds_message() {
+        let mut core = make_core();
+        core.add_rds_message("Msg".to_string()).unwrap();
+        // RdsMessage::new() defaults to enabled=false, so first toggle enables
+        let enabled = core.toggle_rds_message(0).unwrap();
+        assert!(enabled);
+        let disabled = core.toggle_rds_message(0).unwrap();
+        assert!(!disabled);
+    }
+
+    #[test]
+    fn update_rds_message() {
         let mut core = make_core();
         core.add_rds_message("Original".to_string()).unwrap();
         core.update_rds_message(
@@ -2211,6 +6167,18 @@ mod tests {
         assert_eq!(rds.default_message, "Hello Radio");
     }
 
+    #[test]
+    fn set_rds_formatting() {
+        let mut core = make_core();
+        let rds = core.get_rds_config();
+        assert!(!rds.segmented_ps);
+        assert!(!rds.radio_text);
+        core.set_rds_formatting(true, true).unwrap();
+        let rds = core.get_rds_config();
+        assert!(rds.segmented_ps);
+        assert!(rds.radio_text);
+    }
+
     // -- Lecture Detector --
 
     #[test]
@@ -2238,6 +6206,31 @@ mod tests {
         assert!(core.test_lecture("Rihanna")); // back to starts-with-R
     }
 
+    #[test]
+    fn live_assist_hotkeys_off_until_enabled() {
+        let mut core = make_core();
+        assert!(!core.get_config().live_assist_hotkeys);
+        core.set_live_assist_hotkeys(true).unwrap();
+        assert!(core.get_config().live_assist_hotkeys);
+    }
+
+    #[test]
+    fn set_no_ads_during_lectures_reflected_in_config() {
+        let mut core = make_core();
+        assert!(!core.get_lecture_config().no_ads_during_lectures);
+        core.set_no_ads_during_lectures(true).unwrap();
+        assert!(core.get_lecture_config().no_ads_during_lectures);
+    }
+
+    #[test]
+    fn set_no_crossfade_for_lectures_reflected_in_config() {
+        let mut core = make_core();
+        assert!(!core.get_lecture_config().no_crossfade_for_lectures);
+        core.set_no_crossfade_for_lectures(true).unwrap();
+        assert!(core.get_lecture_config().no_crossfade_for_lectures);
+        assert!(core.engine.lecture_crossfade_guard().is_some());
+    }
+
     // -- Logs --
 
     #[test]
@@ -2259,134 +6252,1149 @@ mod tests {
         assert!(core.get_logs(None).is_empty());
     }
 
-    // -- Status --
+    // -- Now-playing output --
 
     #[test]
-    fn get_status_reflects_state() {
+    fn publish_now_playing_writes_on_change_after_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("nowplaying.xml");
         let mut core = make_core();
-        let status = core.get_status();
-        assert_eq!(status.playlist_count, 0);
-        assert!(status.active_playlist.is_none());
-        assert_eq!(status.schedule_event_count, 0);
-
         core.create_playlist("Main".to_string()).unwrap();
         core.set_active_playlist("Main").unwrap();
-        core.add_schedule_event("12:00", "overlay", "jingle.mp3", None, None, None)
-            .unwrap();
+        push_mock_track(&mut core, "Main", "a.mp3", "First");
+        push_mock_track(&mut core, "Main", "b.mp3", "Second");
+        assert!(!core.publish_now_playing().unwrap());
 
-        let status = core.get_status();
-        assert_eq!(status.playlist_count, 1);
-        assert_eq!(status.active_playlist, Some("Main".to_string()));
-        assert_eq!(status.schedule_event_count, 1);
-    }
+        let path = file.to_string_lossy().to_string();
+        core.set_nowplaying_path(Some(path)).unwrap();
+        core.set_now_playing_debounce(60_000).unwrap();
+        assert!(!core.publish_now_playing().unwrap());
+        assert!(!file.exists());
 
-    // -- Transport state (no audio) --
+        core.set_now_playing_debounce(0).unwrap();
+        assert!(core.publish_now_playing().unwrap());
+        // Nothing changed since the last write
+        assert!(!core.publish_now_playing().unwrap());
 
-    #[test]
-    fn transport_state_defaults() {
-        let core = make_core();
-        let state = core.get_transport_state();
-        assert!(!state.is_playing);
-        assert!(!state.is_paused);
-        assert_eq!(state.elapsed_secs, 0.0);
-        assert!(state.track_index.is_none());
+        core.engine.find_playlist_mut("Main").unwrap().current_index = Some(1);
+        assert!(core.publish_now_playing().unwrap());
+        let xml = std::fs::read_to_string(&file).unwrap();
+        assert!(xml.contains("Second"));
     }
 
     #[test]
-    fn on_stop_resets_playback() {
+    fn take_now_playing_leaves_the_write_to_the_caller() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("nowplaying.xml");
         let mut core = make_core();
-        core.playback.is_playing = true;
-        core.playback.track_index = Some(3);
-        core.on_stop();
-        assert!(!core.playback.is_playing);
-        assert!(core.playback.track_index.is_none());
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        push_mock_track(&mut core, "Main", "a.mp3", "First");
+        core.set_nowplaying_path(Some(file.to_string_lossy().to_string()))
+            .unwrap();
+        core.set_now_playing_debounce(0).unwrap();
+
+        let due = core.take_now_playing().unwrap();
+        assert_eq!(due.path, file);
+        assert!(due.xml.contains("First"));
+        assert!(!file.exists());
+        // Taken once; nothing new until the content changes
+        assert!(core.take_now_playing().is_none());
+
+        due.write().unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), due.xml);
     }
 
     #[test]
-    fn on_pause_toggle_without_playing_errors() {
+    fn now_playing_shows_placeholder_during_ad_roll() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("nowplaying.xml");
         let mut core = make_core();
-        assert!(core.on_pause_toggle().is_err());
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        push_mock_track(&mut core, "Main", "a.mp3", "First");
+        push_mock_track(&mut core, "Main", "b.mp3", "Second");
+        core.engine.find_playlist_mut("Main").unwrap().current_index = Some(0);
+        let spot = dir.path().join("Sponsor - Big Sale.wav");
+        write_wav(&spot, &[0i16; 8000], 8000);
+        core.add_ad("Big Sale".to_string(), spot.to_string_lossy().to_string())
+            .unwrap();
+        core.set_now_playing_ad_placeholder(" WXYZ 104.7 ").unwrap();
+        assert_eq!(core.get_config().now_playing_ad_placeholder, "WXYZ 104.7");
+        core.set_nowplaying_path(Some(file.to_string_lossy().to_string()))
+            .unwrap();
+        core.set_now_playing_debounce(0).unwrap();
+
+        let lengths = RollLengths::default();
+        AdInserterService::insert_scheduled(&mut core.engine, &RealClock, false, &lengths).unwrap();
+        let read = |core: &mut AppCore| {
+            core.publish_now_playing().unwrap();
+            std::fs::read_to_string(&file).unwrap()
+        };
+        let xml = read(&mut core);
+        assert!(xml.contains("<artist>First</artist>"));
+        assert!(!xml.contains("Sponsor") && !xml.contains("Big Sale"));
+
+        // The roll is on air
+        core.engine.find_playlist_mut("Main").unwrap().current_index = Some(1);
+        let xml = read(&mut core);
+        assert!(xml.contains("<title>WXYZ 104.7</title>"));
+        assert!(!xml.contains("First") && !xml.contains("Sponsor"));
+        assert!(xml.contains("<artist>Second</artist>"));
+
+        // Back to music after the roll
+        core.engine.find_playlist_mut("Main").unwrap().current_index = Some(2);
+        let xml = read(&mut core);
+        assert!(xml.contains("<current>\n    <artist>Second</artist>"));
+        assert!(!xml.contains("WXYZ"));
     }
 
+    // -- On-air heartbeat --
+
     #[test]
-    fn on_pause_toggle_pauses_and_resumes() {
-        let mut core = make_core();
-        core.playback.is_playing = true;
-        core.playback.start_time = Some(Instant::now());
+    fn on_air_follows_heartbeat_age() {
+        let core = make_core();
+        assert!(!core.is_on_air(ON_AIR_MAX_STALE));
+        let status = core.on_air_status(ON_AIR_MAX_STALE);
+        assert!(status.last_audio_age_secs.is_none());
 
-        let paused = core.on_pause_toggle().unwrap();
-        assert!(paused);
-        assert!(core.playback.is_paused);
+        core.heartbeat.beat();
+        assert!(core.is_on_air(ON_AIR_MAX_STALE));
 
-        let resumed = core.on_pause_toggle().unwrap();
-        assert!(!resumed);
-        assert!(!core.playback.is_paused);
+        let old = SystemTime::now() - Duration::from_secs(60);
+        core.heartbeat.beat_at(old);
+        assert!(!core.is_on_air(ON_AIR_MAX_STALE));
+        let status = core.on_air_status(ON_AIR_MAX_STALE);
+        assert!(!status.on_air);
+        assert!(status.last_audio_age_secs.unwrap() >= 60.0);
     }
 
     #[test]
-    fn on_seek_updates_timing() {
+    fn dead_air_alarm_logs_sustained_silence_while_playing() {
         let mut core = make_core();
+        core.set_dead_air_alarm(-50.0, 10.0).unwrap();
+        assert!(core.set_dead_air_alarm(3.0, 10.0).is_err());
+        assert!(core.set_dead_air_alarm(f32::NAN, 10.0).is_err());
+        assert!(core.set_dead_air_alarm(-50.0, f32::NAN).is_err());
+        assert!(core.set_dead_air_alarm(-50.0, f32::INFINITY).is_err());
+        let too_long = DEAD_AIR_MAX_SECS + 1.0;
+        assert!(core.set_dead_air_alarm(-50.0, too_long).is_err());
+        let t0 = Instant::now();
+        core.heartbeat.beat();
+
+        // Stopped: silence is expected
+        assert_eq!(core.check_dead_air(0.0, t0), None);
+        let later = t0 + Duration::from_secs(30);
+        assert_eq!(core.check_dead_air(0.0, later), None);
+
         core.playback.is_playing = true;
-        core.playback.start_time = Some(Instant::now());
+        assert_eq!(core.check_dead_air(0.0, t0), None);
+        let tripped = core.check_dead_air(0.0, t0 + Duration::from_secs(11));
+        assert_eq!(tripped, Some(DeadAirEvent::Tripped));
+        let logs = core.get_logs(None);
+        let warned = |l: &LogEntry| l.level == "warn" && l.message.contains("Dead air");
+        assert!(logs.iter().any(warned));
 
-        core.on_seek(30.0).unwrap();
-        let elapsed = core.playback.elapsed();
-        // Should be close to 30 seconds (within tolerance for test timing)
-        assert!(elapsed.as_secs_f64() >= 29.9 && elapsed.as_secs_f64() <= 30.5);
+        let cleared = core.check_dead_air(0.5, t0 + Duration::from_secs(12));
+        assert_eq!(cleared, Some(DeadAirEvent::Cleared));
     }
 
     #[test]
-    fn on_seek_without_playing_errors() {
+    fn dead_air_ignores_unusable_stored_duration() {
         let mut core = make_core();
-        assert!(core.on_seek(10.0).is_err());
+        core.engine.dead_air_secs = f32::INFINITY;
+        core.playback.is_playing = true;
+        core.heartbeat.beat();
+        let t0 = Instant::now();
+        assert_eq!(core.check_dead_air(0.0, t0), None);
+        assert_eq!(core.check_dead_air(0.0, t0 + Duration::from_secs(60)), None);
     }
 
-    // -- Copy/Paste --
+    #[test]
+    fn dead_air_treats_stalled_audio_as_silence() {
+        let mut core = make_core();
+        core.set_dead_air_alarm(-50.0, 5.0).unwrap();
+        core.playback.is_playing = true;
+        // The level is stuck at its last value but no audio has flowed
+        let t0 = Instant::now();
+        assert_eq!(core.check_dead_air(0.5, t0), None);
+        let tripped = core.check_dead_air(0.5, t0 + Duration::from_secs(6));
+        assert_eq!(tripped, Some(DeadAirEvent::Tripped));
+    }
 
     #[test]
-    fn copy_paste_tracks() {
+    fn heartbeat_file_written_only_while_on_air() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("heartbeat.txt");
         let mut core = make_core();
-        core.create_playlist("Src".to_string()).unwrap();
-        core.create_playlist("Dest".to_string()).unwrap();
+        let path = file.to_string_lossy().to_string();
+        core.set_heartbeat_path(Some(path)).unwrap();
 
-        // Manually add test tracks (can't use add_track without real files)
-        let track = crate::track::Track {
-            path: "test.mp3".into(),
-            title: "Song".into(),
-            artist: "Artist".into(),
-            duration: Duration::from_secs(180),
-            played_duration: None,
-            has_intro: false,
-        };
-        core.engine
-            .find_playlist_mut("Src")
-            .unwrap()
-            .tracks
-            .push(track);
+        core.write_heartbeat_file(ON_AIR_MAX_STALE).unwrap();
+        assert!(!file.exists());
 
-        let copied = core.copy_tracks("Src", &[0]).unwrap();
-        assert_eq!(copied.len(), 1);
-        assert_eq!(copied[0].title, "Song");
+        core.heartbeat.beat();
+        core.write_heartbeat_file(ON_AIR_MAX_STALE).unwrap();
+        let content = std::fs::read_to_string(&file).unwrap();
+        assert!(content.starts_with("ON AIR "));
+    }
 
-        core.paste_tracks("Dest", copied, None).unwrap();
-        let tracks = core.engine.find_playlist("Dest").unwrap();
-        assert_eq!(tracks.track_count(), 1);
+    // -- Reload --
+
+    fn playing_core_on_disk(dir: &Path) -> AppCore {
+        let mut core = AppCore::new(&dir.join("state.json"));
+        core.create_playlist("Music".to_string()).unwrap();
+        core.set_active_playlist("Music").unwrap();
+        push_mock_track(&mut core, "Music", "a.mp3", "A");
+        push_mock_track(&mut core, "Music", "b.mp3", "B");
+        core.engine.save().unwrap();
+        core.prepare_play(Some(1)).unwrap();
+        core
     }
+
     #[test]
-    fn search_ignores_single_character_query() {
-        let core = make_core();
-        assert!(core.search_indexed_files("a").unwrap().is_empty());
+    fn talk_over_persists_and_shows_in_transport() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut core = playing_core_on_disk(dir.path());
+        assert_eq!(core.get_transport_state().talk_over_secs, None);
+
+        core.set_talk_over("Music", 1, Some(12.0)).unwrap();
+        let transport = core.get_transport_state();
+        assert_eq!(transport.talk_over_secs, Some(12.0));
+        let remaining = transport.talk_over_remaining_secs.unwrap();
+        assert!(remaining > 11.0 && remaining <= 12.0);
+
+        let restarted = AppCore::new(&dir.path().join("state.json"));
+        let tracks = restarted.get_playlist_tracks("Music").unwrap();
+        assert_eq!(tracks[1].talk_over_secs, Some(12.0));
+        assert_eq!(tracks[0].talk_over_secs, None);
+
+        assert!(core.set_talk_over("Music", 1, Some(-1.0)).is_err());
+        assert!(core.set_talk_over("Music", 1, Some(500.0)).is_err());
+        for bad in [f64::NAN, f64::INFINITY, 1e30] {
+            assert!(core.set_talk_over("Music", 1, Some(bad)).is_err());
+        }
+        assert_eq!(core.get_transport_state().talk_over_secs, Some(12.0));
+        core.set_talk_over("Music", 1, None).unwrap();
+        assert_eq!(core.get_transport_state().talk_over_remaining_secs, None);
     }
 
     #[test]
-    fn search_matches_filename_without_extension() {
-        let temp = tempfile::tempdir().unwrap();
-        let audio_path = temp.path().join("MySong.mp3");
-        fs::write(&audio_path, b"not real audio").unwrap();
+    fn reload_replaces_engine_and_keeps_playback() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut core = playing_core_on_disk(dir.path());
+
+        let mut external = Engine::read_from(&dir.path().join("state.json")).unwrap();
+        external.crossfade_secs = 4.0;
+        external.create_playlist("Added Elsewhere".to_string());
+        external.save().unwrap();
+
+        // A roll on air keeps its flag shared with the reloaded engine
+        let on_air = core.engine.ad_break_active.clone();
+        core.reload().unwrap();
+        let still_shared = std::sync::Arc::ptr_eq(&on_air, &core.engine.ad_break_active);
+        assert!(still_shared);
+        assert_eq!(core.get_config().crossfade_secs, 4.0);
+        assert_eq!(core.get_playlists().len(), 2);
+        let transport = core.get_transport_state();
+        assert!(transport.is_playing);
+        assert_eq!(transport.track_index, Some(1));
+        assert_eq!(transport.track_path.as_deref(), Some("b.mp3"));
+    }
 
-        let mut core = make_core();
-        core.engine
-            .indexed_locations
+    #[test]
+    fn reload_refused_when_playing_track_moves() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut core = playing_core_on_disk(dir.path());
+
+        let mut external = Engine::read_from(&dir.path().join("state.json")).unwrap();
+        external.crossfade_secs = 4.0;
+        let music = external.find_playlist_mut("Music").unwrap();
+        music.tracks.remove(0);
+        external.save().unwrap();
+
+        assert!(core.reload().is_err());
+        assert_eq!(core.get_config().crossfade_secs, 0.0);
+        assert!(core.get_transport_state().is_playing);
+    }
+
+    #[test]
+    fn reload_refused_while_edits_are_pending() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut core = playing_core_on_disk(dir.path());
+        core.set_save_debounce(60_000, true).unwrap();
+        core.create_playlist("Pending".to_string()).unwrap();
+
+        assert!(core.reload().is_err());
+        assert_eq!(core.get_playlists().len(), 2);
+
+        core.flush().unwrap();
+        core.reload().unwrap();
+        assert_eq!(core.get_playlists()[1].name, "Pending");
+    }
+
+    #[test]
+    fn reload_in_memory_errors() {
+        let mut core = make_core();
+        assert!(core.reload().is_err());
+    }
+
+    fn write_other_state(dir: &Path) -> PathBuf {
+        let path = dir.join("other_state.json");
+        let mut other = AppCore::new(&path);
+        other.create_playlist("Morning".to_string()).unwrap();
+        other.create_playlist("Evening".to_string()).unwrap();
+        push_mock_track(&mut other, "Morning", "a.mp3", "A");
+        push_mock_track(&mut other, "Morning", "b.mp3", "B");
+        other.engine.save().unwrap();
+        path
+    }
+
+    #[test]
+    fn import_playlists_from_state_copies_tracks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_other_state(dir.path());
+        let before = fs::read_to_string(&path).unwrap();
+        let mut core = make_core();
+
+        let added = core
+            .import_playlists_from_state(&path, &["morning".to_string()])
+            .unwrap();
+        assert_eq!(added, ["Morning"]);
+        let tracks = core.get_playlist_tracks("Morning").unwrap();
+        let paths: Vec<_> = tracks.iter().map(|t| t.path.as_str()).collect();
+        assert_eq!(paths, ["a.mp3", "b.mp3"]);
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+    }
+
+    #[test]
+    fn import_playlists_from_state_renames_on_collision() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_other_state(dir.path());
+        let mut core = make_core();
+        core.create_playlist("Morning".to_string()).unwrap();
+        push_mock_track(&mut core, "Morning", "ours.mp3", "Us");
+
+        let names = ["Morning".to_string(), "Evening".to_string()];
+        let added = core.import_playlists_from_state(&path, &names).unwrap();
+        assert_eq!(added, ["Morning (2)", "Evening"]);
+        assert_eq!(core.get_playlist_tracks("Morning").unwrap().len(), 1);
+        assert_eq!(core.get_playlist_tracks("Morning (2)").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn import_playlists_from_state_errors_on_missing_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_other_state(dir.path());
+        let mut core = make_core();
+
+        let names = ["Evening".to_string(), "Overnight".to_string()];
+        let err = core.import_playlists_from_state(&path, &names).unwrap_err();
+        assert!(err.contains("Overnight"));
+        // Nothing is added when any name is missing
+        assert!(core.get_playlists().is_empty());
+    }
+
+    // -- Resume marker --
+
+    #[test]
+    fn resume_marker_saved_and_restored_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut core = playing_core_on_disk(dir.path());
+        core.playback.start_time = Some(Instant::now() - Duration::from_secs(42));
+
+        assert!(core.save_resume_marker().unwrap());
+        // Throttled until the interval passes
+        assert!(!core.save_resume_marker().unwrap());
+
+        // "Restart": a fresh core on the same state file
+        let mut restarted = AppCore::new(&dir.path().join("state.json"));
+        let marker = restarted.resume_marker().unwrap();
+        assert_eq!(marker.playlist, "Music");
+        assert_eq!(marker.track_index, 1);
+        assert!((marker.offset_secs - 42.0).abs() < 1.0);
+
+        let (path, offset) = restarted.resume_playback().unwrap();
+        assert_eq!(path, PathBuf::from("b.mp3"));
+        assert!((offset.as_secs_f64() - 42.0).abs() < 1.0);
+        let transport = restarted.get_transport_state();
+        assert!(transport.is_playing);
+        assert_eq!(transport.track_index, Some(1));
+        assert!((transport.elapsed_secs - 42.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn resume_playback_clamps_offset_to_track_duration() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut core = playing_core_on_disk(dir.path());
+        let marker = ResumeMarker {
+            playlist: "Music".to_string(),
+            track_index: 0,
+            offset_secs: 1000.0,
+            saved_at: Local::now(),
+        };
+        let marker_path = resume_path_for(&dir.path().join("state.json"));
+        marker.save(&marker_path).unwrap();
+
+        let (_, offset) = core.resume_playback().unwrap();
+        assert_eq!(offset, Duration::from_secs(180) - SEEK_END_MARGIN);
+    }
+
+    #[test]
+    fn stop_clears_resume_marker_and_zero_interval_disables_saving() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut core = playing_core_on_disk(dir.path());
+        assert!(core.save_resume_marker().unwrap());
+        core.on_stop();
+        assert!(core.resume_marker().is_none());
+
+        core.set_resume_save_interval(0.0).unwrap();
+        core.prepare_play(Some(0)).unwrap();
+        assert!(!core.save_resume_marker().unwrap());
+        assert!(core.resume_marker().is_none());
+        assert!(make_core().resume_playback().is_err());
+    }
+
+    // -- Debounced state saving --
+
+    #[test]
+    fn debounced_edits_write_less_and_flush_persists_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let mut core = AppCore::new(&path);
+        core.set_save_debounce(60_000, true).unwrap();
+        assert_eq!(core.get_config().save_debounce_ms, 60_000);
+        let written = std::fs::read_to_string(&path).unwrap();
+
+        for i in 0..20 {
+            core.create_playlist(format!("P{}", i)).unwrap();
+        }
+        core.rename_playlist("P0", "Morning".to_string()).unwrap();
+        // 21 edits, and the file is still the one the settings change wrote
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), written);
+        assert!(!core.flush_state_if_due().unwrap());
+
+        core.flush().unwrap();
+        let restarted = AppCore::new(&path);
+        assert_eq!(restarted.get_playlists().len(), 20);
+        assert_eq!(restarted.get_playlists()[0].name, "Morning");
+
+        // Settings changes are still written at once, pending edits included
+        core.create_playlist("Evening".to_string()).unwrap();
+        core.set_crossfade(3.0).unwrap();
+        let restarted = AppCore::new(&path);
+        assert_eq!(restarted.get_config().crossfade_secs, 3.0);
+        assert_eq!(restarted.get_playlists().len(), 21);
+    }
+
+    // -- Projected start times --
+
+    #[test]
+    fn playlist_tracks_show_projected_air_times_while_playing() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        for path in ["a.mp3", "b.mp3", "c.mp3"] {
+            push_mock_track(&mut core, "Main", path, "Artist");
+        }
+        let idle = core.get_playlist_tracks("Main").unwrap();
+        assert!(idle.iter().all(|t| t.start_time_display.is_none()));
+
+        core.prepare_play(Some(1)).unwrap();
+        let tracks = core.get_playlist_tracks("Main").unwrap();
+        assert!(tracks[0].start_time_display.is_none());
+        let clock = |i: usize| {
+            let shown = tracks[i].start_time_display.as_deref().unwrap();
+            chrono::NaiveTime::parse_from_str(shown, "%H:%M:%S").unwrap()
+        };
+        // Mock tracks are 180 s long (modulo a midnight rollover)
+        let gap = (clock(2) - clock(1)).num_seconds().rem_euclid(86_400);
+        assert_eq!(gap, 180);
+    }
+
+    // -- Playlist search --
+
+    #[test]
+    fn search_playlists_finds_hits_across_playlists() {
+        let mut core = make_core();
+        core.create_playlist("Morning".to_string()).unwrap();
+        core.create_playlist("Evening".to_string()).unwrap();
+        push_mock_track(&mut core, "Morning", "/music/intro.mp3", "Other");
+        push_mock_track(&mut core, "Morning", "/music/a.mp3", "Beatles");
+        push_mock_track(&mut core, "Evening", "/music/b.mp3", "Stones");
+        push_mock_track(&mut core, "Evening", "/music/beat_it.mp3", "Jackson");
+
+        let hits = core.search_playlists("BEAT");
+        let found: Vec<(&str, usize)> = hits
+            .iter()
+            .map(|h| (h.playlist.as_str(), h.track_index))
+            .collect();
+        // Artist match in Morning, file-name match in Evening
+        assert_eq!(found, [("Morning", 1), ("Evening", 1)]);
+        assert_eq!(hits[0].artist, "Beatles");
+        assert_eq!(hits[1].path, "/music/beat_it.mp3");
+
+        assert!(core.search_playlists("zz-none").is_empty());
+        assert!(core.search_playlists(" b ").is_empty());
+    }
+
+    // -- Status --
+
+    #[test]
+    fn get_status_reflects_state() {
+        let mut core = make_core();
+        let status = core.get_status();
+        assert_eq!(status.playlist_count, 0);
+        assert!(status.active_playlist.is_none());
+        assert_eq!(status.schedule_event_count, 0);
+
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        core.add_schedule_event("12:00", "overlay", "jingle.mp3", None, None, None)
+            .unwrap();
+
+        let status = core.get_status();
+        assert_eq!(status.playlist_count, 1);
+        assert_eq!(status.active_playlist, Some("Main".to_string()));
+        assert_eq!(status.schedule_event_count, 1);
+    }
+
+    // -- Transport state (no audio) --
+
+    #[test]
+    fn transport_state_defaults() {
+        let core = make_core();
+        let state = core.get_transport_state();
+        assert!(!state.is_playing);
+        assert!(!state.is_paused);
+        assert_eq!(state.elapsed_secs, 0.0);
+        assert!(state.track_index.is_none());
+    }
+
+    #[test]
+    fn on_stop_resets_playback() {
+        let mut core = make_core();
+        core.playback.is_playing = true;
+        core.playback.track_index = Some(3);
+        core.on_stop();
+        assert!(!core.playback.is_playing);
+        assert!(core.playback.track_index.is_none());
+    }
+
+    #[test]
+    fn transport_events_describe_each_transition() {
+        let mut core = make_core();
+        core.create_playlist("Music".to_string()).unwrap();
+        core.set_active_playlist("Music").unwrap();
+        push_mock_track(&mut core, "Music", "a.mp3", "Artist A");
+        push_mock_track(&mut core, "Music", "b.mp3", "Artist B");
+
+        core.prepare_play(Some(0)).unwrap();
+        let play = core.transport_event(TransportEventKind::Play);
+        assert_eq!(play.kind, TransportEventKind::Play);
+        assert_eq!(play.track_index, Some(0));
+        assert_eq!(play.artist.as_deref(), Some("Artist A"));
+        assert_eq!(play.title.as_deref(), Some("Song"));
+        assert!(play.position_secs < 1.0);
+
+        core.on_seek(42.0).unwrap();
+        let seek = core.transport_event(TransportEventKind::Seek);
+        assert_eq!(seek.track_index, Some(0));
+        assert!((seek.position_secs - 42.0).abs() < 1.0);
+
+        core.on_pause_toggle().unwrap();
+        let pause = core.transport_event(TransportEventKind::Pause);
+        assert_eq!(pause.track_index, Some(0));
+        assert!((pause.position_secs - 42.0).abs() < 1.0);
+
+        core.prepare_skip().unwrap();
+        let skip = core.transport_event(TransportEventKind::Skip);
+        assert_eq!(skip.track_index, Some(1));
+        assert_eq!(skip.artist.as_deref(), Some("Artist B"));
+        assert!(skip.position_secs < 1.0);
+
+        // Stop is snapshotted before stopping, then the transport is empty
+        let stop = core.transport_event(TransportEventKind::Stop);
+        assert_eq!(stop.track_index, Some(1));
+        core.on_stop();
+        let after = core.transport_event(TransportEventKind::Stop);
+        assert_eq!((after.track_index, after.artist), (None, None));
+        assert_eq!(after.position_secs, 0.0);
+    }
+
+    #[test]
+    fn transport_event_serializes_kind_in_snake_case() {
+        let core = make_core();
+        let event = core.transport_event(TransportEventKind::TrackFinished);
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["kind"], "track_finished");
+        assert!(json["track_index"].is_null());
+    }
+
+    #[test]
+    fn on_pause_toggle_without_playing_errors() {
+        let mut core = make_core();
+        assert!(core.on_pause_toggle().is_err());
+    }
+
+    #[test]
+    fn on_pause_toggle_pauses_and_resumes() {
+        let mut core = make_core();
+        core.playback.is_playing = true;
+        core.playback.start_time = Some(Instant::now());
+
+        let paused = core.on_pause_toggle().unwrap();
+        assert!(paused);
+        assert!(core.playback.is_paused);
+
+        let resumed = core.on_pause_toggle().unwrap();
+        assert!(!resumed);
+        assert!(!core.playback.is_paused);
+    }
+
+    fn paused_core() -> AppCore {
+        let mut core = make_core();
+        core.playback.is_playing = true;
+        core.playback.start_time = Some(Instant::now());
+        core.on_pause_toggle().unwrap();
+        core
+    }
+
+    #[test]
+    fn overlay_while_paused_queues_until_resume() {
+        let mut core = paused_core();
+        let id = core
+            .add_schedule_event("12:00", "overlay", "jingle.mp3", None, None, None)
+            .unwrap();
+
+        let action = core.fire_schedule_event(id).unwrap();
+        assert_eq!(action, ScheduleAction::Held { id });
+        assert!(core.playback.is_paused);
+        assert!(core.take_released_events().is_empty());
+
+        assert!(!core.on_pause_toggle().unwrap());
+        let released = core.take_released_events();
+        assert_eq!(released.len(), 1);
+        assert!(matches!(
+            released[0],
+            ScheduleAction::Play {
+                mode: ScheduleMode::Overlay,
+                ..
+            }
+        ));
+        assert!(core.playback.is_playing && !core.playback.is_paused);
+        assert!(core.take_released_events().is_empty());
+        let logged = core
+            .get_logs(None)
+            .iter()
+            .any(|l| l.message.contains("held"));
+        assert!(logged);
+    }
+
+    #[test]
+    fn play_over_fires_during_pause_and_stop_events_end_it() {
+        let mut core = paused_core();
+        core.set_paused_event_handling("play-over").unwrap();
+        assert_eq!(core.get_config().paused_event_handling, "play-over");
+        let overlay = core
+            .add_schedule_event("12:00", "overlay", "jingle.mp3", None, None, None)
+            .unwrap();
+        let stop = core
+            .add_schedule_event("12:05", "stop", "news.mp3", None, None, None)
+            .unwrap();
+
+        // The overlay plays on its own; the track stays paused under it
+        let action = core.fire_schedule_event(overlay).unwrap();
+        assert!(matches!(action, ScheduleAction::Play { .. }));
+        assert!(core.playback.is_playing && core.playback.is_paused);
+
+        // A stop event takes over the output; stopping the paused track is
+        // left to the caller so the audio stops with it
+        let action = core.fire_schedule_event(stop).unwrap();
+        assert!(matches!(action, ScheduleAction::Play { .. }));
+        assert!(core.playback.is_playing && core.playback.is_paused);
+        assert!(core.take_released_events().is_empty());
+    }
+
+    #[test]
+    fn manual_wins_holds_normal_events_during_pause() {
+        let mut core = paused_core();
+        core.set_paused_event_handling("play-over").unwrap();
+        core.set_conflict_policy("manual-wins").unwrap();
+        let normal = core
+            .add_schedule_event("12:00", "stop", "news.mp3", None, None, None)
+            .unwrap();
+        let urgent = core
+            .add_schedule_event("12:01", "overlay", "alert.mp3", Some(9), None, None)
+            .unwrap();
+
+        let held = core.fire_schedule_event(normal).unwrap();
+        assert_eq!(held, ScheduleAction::Held { id: normal });
+        assert!(core.playback.is_paused);
+        let action = core.fire_schedule_event(urgent).unwrap();
+        assert!(matches!(action, ScheduleAction::Play { .. }));
+
+        // Stopping ends the pause too, so the held event is released
+        core.on_stop();
+        assert_eq!(core.take_released_events().len(), 1);
+        assert!(core.set_paused_event_handling("later").is_err());
+    }
+
+    #[test]
+    fn on_seek_updates_timing() {
+        let mut core = make_core();
+        core.playback.is_playing = true;
+        core.playback.start_time = Some(Instant::now());
+
+        core.on_seek(30.0).unwrap();
+        let elapsed = core.playback.elapsed();
+        // Should be close to 30 seconds (within tolerance for test timing)
+        assert!(elapsed.as_secs_f64() >= 29.9 && elapsed.as_secs_f64() <= 30.5);
+    }
+
+    #[test]
+    fn on_seek_past_end_clamps_below_duration() {
+        let mut core = make_core();
+        core.playback.is_playing = true;
+        core.playback.start_time = Some(Instant::now());
+        core.playback.track_duration = Duration::from_secs(180);
+
+        let outcome = core.on_seek(190.0).unwrap();
+        let SeekOutcome::Seeked(pos) = outcome else {
+            panic!("expected a clamped seek, got {:?}", outcome);
+        };
+        assert!(pos < Duration::from_secs(180));
+        assert!(pos >= Duration::from_secs(179));
+        assert!(core.playback.elapsed() < Duration::from_secs(180));
+    }
+
+    #[test]
+    fn on_seek_to_end_reports_end_of_track() {
+        let mut core = make_core();
+        core.playback.is_playing = true;
+        core.playback.start_time = Some(Instant::now());
+        core.playback.track_duration = Duration::from_secs(180);
+
+        assert_eq!(core.on_seek(180.0).unwrap(), SeekOutcome::EndOfTrack);
+        assert_eq!(
+            core.on_seek(90.0).unwrap(),
+            SeekOutcome::Seeked(Duration::from_secs(90))
+        );
+    }
+
+    #[test]
+    fn elapsed_subtracts_output_latency() {
+        let mut playback = PlaybackState::new();
+        let now = Instant::now();
+        playback.start_time = Some(now - Duration::from_secs(10));
+        assert_eq!(playback.elapsed_at(now), Duration::from_secs(10));
+
+        playback.output_latency = Duration::from_millis(250);
+        assert_eq!(playback.elapsed_at(now), Duration::from_millis(9750));
+
+        // Paused time comes off as well
+        playback.total_paused = Duration::from_secs(2);
+        assert_eq!(playback.elapsed_at(now), Duration::from_millis(7750));
+
+        // Right after a start the buffer hasn't reached the speakers yet
+        playback.total_paused = Duration::ZERO;
+        playback.start_time = Some(now - Duration::from_millis(100));
+        assert_eq!(playback.elapsed_at(now), Duration::ZERO);
+
+        // The latency belongs to the output, not the track
+        playback.reset();
+        assert_eq!(playback.output_latency, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn seek_position_accounts_for_buffer_latency() {
+        let mut core = make_core();
+        assert!(core.set_audio_buffer_ms(5000).is_err());
+        core.set_audio_buffer_ms(200).unwrap();
+        assert_eq!(core.get_config().audio_buffer_ms, 200);
+        // Only the latency the output reports moves the position
+        assert_eq!(core.playback.output_latency, Duration::ZERO);
+        core.set_output_latency(Duration::from_millis(200));
+
+        core.playback.is_playing = true;
+        core.playback.start_time = Some(Instant::now());
+        core.on_seek(30.0).unwrap();
+        // The seeked audio is heard once the buffer ahead of it drains
+        let elapsed = core.playback.elapsed().as_secs_f64();
+        assert!((29.75..=30.3).contains(&elapsed), "elapsed {elapsed}");
+    }
+
+    #[test]
+    fn on_seek_without_playing_errors() {
+        let mut core = make_core();
+        assert!(core.on_seek(10.0).is_err());
+    }
+
+    #[test]
+    fn skip_passes_over_advisory_tracks_in_clean_hours() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        for (path, artist) in [("a.mp3", "A"), ("b.mp3", "B"), ("c.mp3", "C")] {
+            push_mock_track(&mut core, "Main", path, artist);
+        }
+        core.set_track_advisory("Main", 1, true).unwrap();
+        assert!(core.get_playlist_tracks("Main").unwrap()[1].advisory);
+        core.prepare_play(Some(0)).unwrap();
+
+        // Every hour clean, so the result doesn't depend on the wall clock
+        core.set_clean_hours((0..24).collect()).unwrap();
+        let (path, ..) = core.prepare_skip().unwrap();
+        assert_eq!(path, PathBuf::from("c.mp3"));
+        assert_eq!(core.playback.track_index, Some(2));
+
+        // Outside clean hours the advisory track plays as normal
+        core.set_clean_hours(Vec::new()).unwrap();
+        core.prepare_play(Some(0)).unwrap();
+        let (path, ..) = core.prepare_skip().unwrap();
+        assert_eq!(path, PathBuf::from("b.mp3"));
+
+        assert!(core.set_clean_hours(vec![24]).is_err());
+    }
+
+    #[test]
+    fn track_finished_waits_on_live_assist_playlists() {
+        let mut core = make_core();
+        core.create_playlist("Live".to_string()).unwrap();
+        core.set_active_playlist("Live").unwrap();
+        push_mock_track(&mut core, "Live", "a.mp3", "A");
+        push_mock_track(&mut core, "Live", "b.mp3", "B");
+        assert!(core.get_playlists()[0].auto_advance);
+
+        core.set_playlist_auto_advance("Live", false).unwrap();
+        assert!(!core.get_playlists()[0].auto_advance);
+        core.prepare_play(Some(0)).unwrap();
+        assert_eq!(core.on_track_finished().unwrap_err(), "__manual_advance__");
+        assert!(!core.playback.is_playing);
+        assert_eq!(
+            core.engine.find_playlist("Live").unwrap().current_index,
+            Some(0)
+        );
+
+        // Automation moves on to the next track
+        core.set_playlist_auto_advance("Live", true).unwrap();
+        core.prepare_play(Some(0)).unwrap();
+        let (path, ..) = core.on_track_finished().unwrap();
+        assert_eq!(path, PathBuf::from("b.mp3"));
+        assert!(core.playback.is_playing);
+
+        assert!(core.set_playlist_auto_advance("Ghost", false).is_err());
+    }
+
+    #[test]
+    fn locked_playlist_refuses_edits_but_still_plays() {
+        let mut core = make_core();
+        core.create_playlist("Live".to_string()).unwrap();
+        core.create_playlist("Other".to_string()).unwrap();
+        core.set_active_playlist("Live").unwrap();
+        for (path, artist) in [("a.mp3", "A"), ("b.mp3", "B"), ("c.mp3", "C")] {
+            push_mock_track(&mut core, "Live", path, artist);
+        }
+        core.set_playlist_locked("Live", true).unwrap();
+        assert!(core.get_playlists()[0].locked);
+
+        assert!(core.remove_tracks("Live", &[1]).is_err());
+        assert!(core.reorder_track("Live", 0, 2).is_err());
+        assert!(core.shuffle_playlist("Live", 1, Some(7)).is_err());
+        assert!(core.move_tracks("Live", &[0], "Other", None).is_err());
+        assert!(core.move_tracks("Other", &[], "Live", None).is_err());
+        assert_eq!(core.get_playlists()[1].track_count, 0);
+        let tracks = core.get_playlist_tracks("Live").unwrap();
+        let paths: Vec<&str> = tracks.iter().map(|t| t.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.mp3", "b.mp3", "c.mp3"]);
+
+        core.prepare_play(Some(0)).unwrap();
+        core.prepare_skip().unwrap();
+        assert_eq!(
+            core.engine.find_playlist("Live").unwrap().current_index,
+            Some(1)
+        );
+
+        core.set_playlist_locked("Live", false).unwrap();
+        core.remove_tracks("Live", &[2]).unwrap();
+        assert_eq!(core.get_playlists()[0].track_count, 2);
+        assert!(core.set_playlist_locked("Ghost", true).is_err());
+    }
+
+    #[test]
+    fn empty_playlist_plays_emergency_file_on_loop() {
+        let dir = tempfile::tempdir().unwrap();
+        let emergency = dir.path().join("Station - Emergency.wav");
+        write_wav(&emergency, &[0i16; 8000], 8000);
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        let path = emergency.to_string_lossy().to_string();
+        core.set_emergency_file(Some(path)).unwrap();
+
+        let (path, duration, ..) = core.prepare_play(None).unwrap();
+        assert_eq!(path, emergency);
+        assert_eq!(duration, Duration::from_secs(1));
+        assert!(core.playback.is_playing);
+        assert_eq!(
+            core.get_transport_state().track_title.as_deref(),
+            Some("Emergency")
+        );
+
+        // Finishing replays it while the playlist is still empty...
+        let (path, ..) = core.on_track_finished().unwrap();
+        assert_eq!(path, emergency);
+
+        // ...and returns to the playlist once it has a track
+        push_mock_track(&mut core, "Main", "a.mp3", "A");
+        let (path, ..) = core.on_track_finished().unwrap();
+        assert_eq!(path, PathBuf::from("a.mp3"));
+        assert!(core.playback.emergency.is_none());
+        assert_eq!(core.playback.track_index, Some(0));
+    }
+
+    #[test]
+    fn empty_playlist_without_emergency_file_errors() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        assert!(core.get_config().emergency_file.is_none());
+        let err = core.prepare_play(None).unwrap_err();
+        assert!(err.contains("out of range"));
+        assert!(!core.playback.is_playing);
+    }
+
+    #[test]
+    fn loop_with_nothing_airable_falls_back_to_emergency_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let emergency = dir.path().join("emergency.wav");
+        write_wav(&emergency, &[0i16; 8000], 8000);
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        push_mock_track(&mut core, "Main", "a.mp3", "A");
+        core.set_track_advisory("Main", 0, true).unwrap();
+        core.set_clean_hours((0..24).collect()).unwrap();
+        core.set_end_of_playlist_action("loop", None).unwrap();
+        core.prepare_play(Some(0)).unwrap();
+        assert_eq!(core.prepare_skip().unwrap_err(), "__end_of_playlist__");
+
+        let path = emergency.to_string_lossy().to_string();
+        core.set_emergency_file(Some(path)).unwrap();
+        core.prepare_play(Some(0)).unwrap();
+        let (path, ..) = core.prepare_skip().unwrap();
+        assert_eq!(path, emergency);
+
+        // An explicit stop is still honored
+        core.set_end_of_playlist_action("stop", None).unwrap();
+        core.prepare_play(Some(0)).unwrap();
+        assert_eq!(core.prepare_skip().unwrap_err(), "__end_of_playlist__");
+
+        // An unplayable emergency file is logged and playback ends cleanly
+        core.set_end_of_playlist_action("loop", None).unwrap();
+        std::fs::remove_file(&emergency).unwrap();
+        core.prepare_play(Some(0)).unwrap();
+        assert_eq!(core.prepare_skip().unwrap_err(), "__end_of_playlist__");
+        assert!(!core.playback.is_playing);
+        assert!(core.resume_marker().is_none());
+        let logged = core
+            .get_logs(None)
+            .iter()
+            .any(|l| l.level == "error" && l.message.contains("emergency file failed"));
+        assert!(logged);
+    }
+
+    #[test]
+    fn skip_avoids_same_artist_twice_when_enabled() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        for (path, artist) in [("a.mp3", "X"), ("b.mp3", "X"), ("c.mp3", "Y")] {
+            push_mock_track(&mut core, "Main", path, artist);
+        }
+        core.set_avoid_artist_repeat(true).unwrap();
+        assert!(core.get_config().avoid_artist_repeat);
+
+        core.prepare_play(Some(0)).unwrap();
+        let (path, ..) = core.prepare_skip().unwrap();
+        assert_eq!(path, PathBuf::from("c.mp3"));
+        let (path, ..) = core.prepare_skip().unwrap();
+        assert_eq!(path, PathBuf::from("b.mp3"));
+    }
+
+    #[test]
+    fn prev_steps_back_and_stays_on_the_first_track() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        for (path, artist) in [("a.mp3", "A"), ("b.mp3", "B"), ("c.mp3", "C")] {
+            push_mock_track(&mut core, "Main", path, artist);
+        }
+        core.prepare_play(Some(2)).unwrap();
+        core.on_pause_toggle().unwrap();
+
+        let (path, _, artist, _, _, idx) = core.prepare_prev().unwrap();
+        assert_eq!(path, PathBuf::from("b.mp3"));
+        assert_eq!((artist.as_str(), idx), ("B", 1));
+        assert!(core.playback.is_playing && !core.playback.is_paused);
+        assert_eq!(core.playback.track_index, Some(1));
+        let pl = core.engine.find_playlist("Main").unwrap();
+        assert_eq!(pl.current_index, Some(1));
+        let logs = core.get_logs(None);
+        assert_eq!(logs.last().unwrap().message, "Back to: B — Song");
+
+        core.prepare_prev().unwrap();
+        let (path, .., idx) = core.prepare_prev().unwrap();
+        assert_eq!((path, idx), (PathBuf::from("a.mp3"), 0));
+        assert_eq!(core.playback.track_index, Some(0));
+    }
+
+    #[test]
+    fn prev_from_first_track_wraps_when_looping() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        for path in ["a.mp3", "b.mp3", "c.mp3"] {
+            push_mock_track(&mut core, "Main", path, "Artist");
+        }
+        core.set_end_of_playlist_action("loop", None).unwrap();
+        core.prepare_play(Some(0)).unwrap();
+
+        let (path, .., idx) = core.prepare_prev().unwrap();
+        assert_eq!((path, idx), (PathBuf::from("c.mp3"), 2));
+        assert_eq!(core.playback.track_index, Some(2));
+        assert_eq!(core.get_transport_state().track_index, Some(2));
+    }
+
+    #[test]
+    fn skip_keeps_playlist_order_when_artist_guard_off() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        for (path, artist) in [("a.mp3", "X"), ("b.mp3", "X"), ("c.mp3", "Y")] {
+            push_mock_track(&mut core, "Main", path, artist);
+        }
+        core.prepare_play(Some(0)).unwrap();
+        let (path, ..) = core.prepare_skip().unwrap();
+        assert_eq!(path, PathBuf::from("b.mp3"));
+    }
+
+    // -- Copy/Paste --
+
+    #[test]
+    fn copy_paste_tracks() {
+        let mut core = make_core();
+        core.create_playlist("Src".to_string()).unwrap();
+        core.create_playlist("Dest".to_string()).unwrap();
+
+        // Manually add test tracks (can't use add_track without real files)
+        let track = crate::track::Track {
+            path: "test.mp3".into(),
+            title: "Song".into(),
+            artist: "Artist".into(),
+            duration: Duration::from_secs(180),
+            ..Default::default()
+        };
+        core.engine
+            .find_playlist_mut("Src")
+            .unwrap()
+            .tracks
+            .push(track);
+
+        let copied = core.copy_tracks("Src", &[0]).unwrap();
+        assert_eq!(copied.len(), 1);
+        assert_eq!(copied[0].title, "Song");
+
+        core.paste_tracks("Dest", copied, None).unwrap();
+        let tracks = core.engine.find_playlist("Dest").unwrap();
+        assert_eq!(tracks.track_count(), 1);
+    }
+
+    #[test]
+    fn move_tracks_removes_from_source_and_inserts_into_dest() {
+        let mut core = make_core();
+        core.create_playlist("Src".to_string()).unwrap();
+        core.create_playlist("Dest".to_string()).unwrap();
+        for path in ["a.mp3", "b.mp3", "c.mp3"] {
+            push_mock_track(&mut core, "Src", path, "Artist");
+        }
+        push_mock_track(&mut core, "Dest", "x.mp3", "Artist");
+
+        core.move_tracks("Src", &[2, 0], "Dest", Some(0)).unwrap();
+
+        let paths = |core: &AppCore, name: &str| -> Vec<String> {
+            core.engine
+                .find_playlist(name)
+                .unwrap()
+                .tracks
+                .iter()
+                .map(|t| t.path.to_string_lossy().to_string())
+                .collect()
+        };
+        assert_eq!(paths(&core, "Src"), vec!["b.mp3"]);
+        assert_eq!(paths(&core, "Dest"), vec!["a.mp3", "c.mp3", "x.mp3"]);
+    }
+
+    #[test]
+    fn move_tracks_within_playlist_adjusts_insert_position() {
+        let mut core = make_core();
+        core.create_playlist("P".to_string()).unwrap();
+        for path in ["a.mp3", "b.mp3", "c.mp3", "d.mp3"] {
+            push_mock_track(&mut core, "P", path, "Artist");
+        }
+
+        // Move a and b to just before d.
+        core.move_tracks("P", &[0, 1], "P", Some(3)).unwrap();
+
+        let pl = core.engine.find_playlist("P").unwrap();
+        let paths: Vec<_> = pl
+            .tracks
+            .iter()
+            .map(|t| t.path.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(paths, vec!["c.mp3", "a.mp3", "b.mp3", "d.mp3"]);
+
+        // Same playlist under another casing: still a move within it
+        core.move_tracks("P", &[3], "p", Some(0)).unwrap();
+        let pl = core.engine.find_playlist("P").unwrap();
+        let paths: Vec<_> = pl
+            .tracks
+            .iter()
+            .map(|t| t.path.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(paths, vec!["d.mp3", "c.mp3", "a.mp3", "b.mp3"]);
+    }
+
+    #[test]
+    fn move_tracks_failed_insert_leaves_source_intact() {
+        let mut core = make_core();
+        core.create_playlist("Src".to_string()).unwrap();
+        core.create_playlist("Dest".to_string()).unwrap();
+        push_mock_track(&mut core, "Src", "a.mp3", "Artist");
+        push_mock_track(&mut core, "Src", "b.mp3", "Artist");
+
+        assert!(core.move_tracks("Src", &[0], "Dest", Some(5)).is_err());
+        assert!(core.move_tracks("Src", &[0], "Missing", None).is_err());
+        assert!(core.move_tracks("Src", &[0], "Src", Some(9)).is_err());
+
+        assert_eq!(core.engine.find_playlist("Src").unwrap().track_count(), 2);
+        assert_eq!(core.engine.find_playlist("Dest").unwrap().track_count(), 0);
+    }
+    #[test]
+    fn search_ignores_single_character_query() {
+        let core = make_core();
+        assert!(core.search_indexed_files("a").unwrap().is_empty());
+    }
+
+    #[test]
+    fn search_matches_filename_without_extension() {
+        let temp = tempfile::tempdir().unwrap();
+        let audio_path = temp.path().join("MySong.mp3");
+        fs::write(&audio_path, b"not real audio").unwrap();
+
+        let mut core = make_core();
+        core.engine
+            .indexed_locations
             .push(temp.path().to_string_lossy().to_string());
 
         let matches = core.search_indexed_files("mysong").unwrap();
@@ -2445,4 +7453,739 @@ track-a.mp3
         core.delete_playlist_profile("Morning").unwrap();
         assert!(core.get_playlist_profiles().is_empty());
     }
+
+    #[test]
+    fn playlist_waveforms_one_entry_per_track_and_empty_for_unreadable() {
+        let temp = tempfile::tempdir().unwrap();
+        let wav = temp.path().join("tone.wav");
+        let samples: Vec<i16> = (0..8000).map(|i| if i % 2 == 0 { 12000 } else { -12000 }).collect();
+        write_wav(&wav, &samples, 8000);
+
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", &wav.to_string_lossy(), "A");
+        push_mock_track(&mut core, "Main", "missing_track.mp3", "B");
+
+        let waveforms = core.get_playlist_waveforms("Main", 16).unwrap();
+        assert_eq!(waveforms.len(), 2);
+        assert_eq!(waveforms[0].0, 0);
+        assert_eq!(waveforms[0].1.len(), 16);
+        assert!(waveforms[0].1.iter().any(|p| *p > 0.0));
+        assert_eq!(waveforms[1].0, 1);
+        assert!(waveforms[1].1.is_empty());
+
+        assert!(core.get_playlist_waveforms("Ghost", 16).is_err());
+    }
+
+    // -- Folder import --
+
+    #[test]
+    fn natural_cmp_orders_numbers_numerically() {
+        use std::cmp::Ordering;
+        assert_eq!(natural_cmp("track2", "track10"), Ordering::Less);
+        assert_eq!(natural_cmp("Track10", "track9"), Ordering::Greater);
+        assert_eq!(natural_cmp("a01", "a1"), Ordering::Equal);
+        assert_eq!(natural_cmp("abc", "abd"), Ordering::Less);
+    }
+
+    #[test]
+    fn collect_audio_files_natural_order_and_recursion() {
+        let temp = tempfile::tempdir().unwrap();
+        for name in ["track10.mp3", "track2.mp3", "track1.mp3", "notes.txt"] {
+            fs::write(temp.path().join(name), b"").unwrap();
+        }
+        fs::create_dir(temp.path().join("sub")).unwrap();
+        fs::write(temp.path().join("sub").join("track3.mp3"), b"").unwrap();
+
+        let names = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+                .collect()
+        };
+
+        let flat = collect_audio_files(temp.path(), false).unwrap();
+        assert_eq!(names(flat), vec!["track1.mp3", "track2.mp3", "track10.mp3"]);
+
+        let deep = collect_audio_files(temp.path(), true).unwrap();
+        assert_eq!(
+            names(deep),
+            vec!["track3.mp3", "track1.mp3", "track2.mp3", "track10.mp3"]
+        );
+    }
+
+    #[test]
+    fn create_playlist_from_folder_builds_playlist() {
+        let temp = tempfile::tempdir().unwrap();
+        let samples = vec![0i16; 800];
+        for name in ["track10.wav", "track2.wav", "track1.wav"] {
+            write_wav(&temp.path().join(name), &samples, 8000);
+        }
+        fs::create_dir(temp.path().join("sub")).unwrap();
+        write_wav(&temp.path().join("sub").join("track3.wav"), &samples, 8000);
+        let dir = temp.path().to_string_lossy().to_string();
+
+        let mut core = make_core();
+        let added = core
+            .create_playlist_from_folder("Flat", &dir, false)
+            .unwrap();
+        assert_eq!(added, 3);
+        let tracks = core.get_playlist_tracks("Flat").unwrap();
+        let paths: Vec<&str> = tracks.iter().map(|t| t.path.as_str()).collect();
+        assert!(paths[0].ends_with("track1.wav"));
+        assert!(paths[1].ends_with("track2.wav"));
+        assert!(paths[2].ends_with("track10.wav"));
+
+        let added = core
+            .create_playlist_from_folder("Deep", &dir, true)
+            .unwrap();
+        assert_eq!(added, 4);
+        let duplicate = core.create_playlist_from_folder("Deep", &dir, true);
+        assert!(duplicate.is_err());
+    }
+
+    #[test]
+    fn precompute_waveforms_unknown_playlist_errors() {
+        let core = make_core();
+        assert!(core.precompute_waveforms("Ghost", |_| {}).is_err());
+    }
+
+    // -- Cue sheets --
+
+    #[test]
+    fn import_cue_builds_segment_playlist() {
+        let temp = tempfile::tempdir().unwrap();
+        // 10 seconds of silence at 8 kHz
+        write_wav(&temp.path().join("show.wav"), &vec![0i16; 80_000], 8000);
+        let cue = temp.path().join("show.cue");
+        let sheet = r#"PERFORMER "Host"
+FILE "show.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "Part A"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Part B"
+    INDEX 01 00:04:00
+"#;
+        fs::write(&cue, sheet).unwrap();
+
+        let mut core = make_core();
+        let name = core.import_cue(&cue.to_string_lossy()).unwrap();
+        assert_eq!(name, "show");
+        let pl = core.engine.find_playlist("show").unwrap();
+        assert_eq!(pl.tracks.len(), 2);
+        assert_eq!(pl.tracks[0].cue_in, Some(Duration::ZERO));
+        assert_eq!(pl.tracks[0].cue_out, Some(Duration::from_secs(4)));
+        assert_eq!(pl.tracks[0].duration, Duration::from_secs(4));
+        assert_eq!(pl.tracks[1].cue_in, Some(Duration::from_secs(4)));
+        assert_eq!(pl.tracks[1].cue_out, None);
+        assert_eq!(pl.tracks[1].duration.as_secs(), 6);
+
+        core.prepare_play(Some(1)).unwrap();
+        assert_eq!(
+            core.playing_cue_range(),
+            (Some(Duration::from_secs(4)), None)
+        );
+    }
+
+    // -- Bulk metadata --
+
+    #[test]
+    fn edit_tracks_metadata_numbers_titles_and_sets_artist() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut core = make_core();
+        core.create_playlist("Album".to_string()).unwrap();
+        for i in 0..3 {
+            let wav = temp.path().join(format!("track{}.wav", i));
+            write_wav(&wav, &[0i16; 800], 8000);
+            push_mock_track(&mut core, "Album", &wav.to_string_lossy(), "Various");
+        }
+
+        let failures = core
+            .edit_tracks_metadata("Album", &[0, 1, 2], Some("The Band"), Some("Song {n}"))
+            .unwrap();
+        assert!(failures.is_empty(), "{:?}", failures);
+
+        let tracks = &core.engine.find_playlist("Album").unwrap().tracks;
+        let titles: Vec<&str> = tracks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Song 1", "Song 2", "Song 3"]);
+        assert!(tracks.iter().all(|t| t.artist == "The Band"));
+
+        let reread = crate::track::Track::from_path(&tracks[1].path).unwrap();
+        assert_eq!(reread.title, "Song 2");
+        assert_eq!(reread.artist, "The Band");
+    }
+
+    #[test]
+    fn edit_tracks_metadata_reports_failures_per_index() {
+        let temp = tempfile::tempdir().unwrap();
+        let wav = temp.path().join("ok.wav");
+        write_wav(&wav, &[0i16; 800], 8000);
+
+        let mut core = make_core();
+        core.create_playlist("Album".to_string()).unwrap();
+        push_mock_track(&mut core, "Album", "missing_file.wav", "Various");
+        push_mock_track(&mut core, "Album", &wav.to_string_lossy(), "Various");
+
+        let failures = core
+            .edit_tracks_metadata("Album", &[0, 1, 7], Some("The Band"), None)
+            .unwrap();
+        let failed: Vec<usize> = failures.iter().map(|f| f.index).collect();
+        assert_eq!(failed, vec![0, 7]);
+        assert_eq!(failures[0].path, "missing_file.wav");
+        let tracks = &core.engine.find_playlist("Album").unwrap().tracks;
+        assert_eq!(tracks[1].artist, "The Band");
+
+        let nothing = core.edit_tracks_metadata("Album", &[1], None, None);
+        assert!(nothing.is_err());
+        let no_playlist = core.edit_tracks_metadata("Nope", &[1], Some("X"), None);
+        assert!(no_playlist.is_err());
+    }
+
+    // -- Format conversion --
+
+    #[test]
+    fn convert_tracks_relinks_converted_and_skips_target_format() {
+        let temp = tempfile::tempdir().unwrap();
+        let flac = temp.path().join("song.flac");
+        fs::write(&flac, b"not really flac").unwrap();
+        let wav = temp.path().join("already.wav");
+        write_wav(&wav, &[0i16; 800], 8000);
+
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", &flac.to_string_lossy(), "Artist");
+        push_mock_track(&mut core, "Main", &wav.to_string_lossy(), "Artist");
+
+        let fake_ffmpeg = |args: &[String]| -> Result<(), String> {
+            assert!(args.contains(&"pcm_s16le".to_string()));
+            write_wav(Path::new(args.last().unwrap()), &[0i16; 8000], 8000);
+            Ok(())
+        };
+        let summary = core
+            .convert_tracks("Main", &[0, 1], "WAV", 5, fake_ffmpeg)
+            .unwrap();
+        assert_eq!(summary.converted, 1);
+        assert_eq!(summary.skipped, 1);
+        assert!(summary.failures.is_empty(), "{:?}", summary.failures);
+
+        let tracks = &core.engine.find_playlist("Main").unwrap().tracks;
+        assert_eq!(tracks[0].path, temp.path().join("song.wav"));
+        assert_eq!(tracks[0].duration, Duration::from_secs(1));
+        assert_eq!(tracks[1].path, wav);
+        assert!(flac.exists());
+
+        let out_of_range = core.convert_tracks("Main", &[5], "wav", 0, fake_ffmpeg);
+        assert!(out_of_range.is_err());
+        let unknown = core.convert_tracks("Main", &[0], "aiff", 0, fake_ffmpeg);
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn convert_tracks_to_mp3_skips_mp3_and_reports_failures() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", "song.mp3", "Artist");
+        push_mock_track(&mut core, "Main", "missing.wav", "Artist");
+
+        let summary = core
+            .convert_tracks_to_mp3("Main", &[0, 1], |_| Err("ffmpeg not run".to_string()))
+            .unwrap();
+        assert_eq!((summary.converted, summary.skipped), (0, 1));
+        assert_eq!(summary.failures.len(), 1);
+        assert_eq!(summary.failures[0].index, 1);
+        let tracks = &core.engine.find_playlist("Main").unwrap().tracks;
+        assert_eq!(tracks[1].path, PathBuf::from("missing.wav"));
+    }
+
+    // -- Verified duration --
+
+    #[test]
+    fn verified_duration_overrides_wrong_tag_length() {
+        let temp = tempfile::tempdir().unwrap();
+        let wav = temp.path().join("vbr.wav");
+        write_wav(&wav, &[500i16; 8000], 8000);
+
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        // Mock tag claims 180s; the file really holds 1s of audio
+        push_mock_track(&mut core, "Main", &wav.to_string_lossy(), "Artist");
+
+        let decoded = crate::track::decoded_duration(&wav).unwrap();
+        assert_eq!(decoded, Duration::from_secs(1));
+        let verify = |core: &mut AppCore, index, path: &Path| {
+            core.set_verified_duration("Main", index, path, decoded)
+        };
+        verify(&mut core, 0, &wav).unwrap();
+
+        let track = &core.engine.find_playlist("Main").unwrap().tracks[0];
+        assert_eq!(track.verified_duration, Some(decoded));
+        assert_eq!(track.duration, Duration::from_secs(180));
+        let remaining = track.remaining_duration(Duration::from_millis(250));
+        assert_eq!(remaining, Duration::from_millis(750));
+
+        let tracks = core.get_playlist_tracks("Main").unwrap();
+        assert!(tracks[0].duration_verified);
+        assert_eq!(tracks[0].duration_secs, 1.0);
+        let (_, play_duration, ..) = core.prepare_play(Some(0)).unwrap();
+        assert_eq!(play_duration, decoded);
+
+        assert!(verify(&mut core, 5, &wav).is_err());
+        // The playlist changed while the file was being decoded
+        assert!(verify(&mut core, 0, Path::new("other.wav")).is_err());
+    }
+
+    // -- Musical key --
+
+    #[test]
+    fn set_track_key_reports_camelot() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", "a.mp3", "A");
+        let tracks = core.get_playlist_tracks("Main").unwrap();
+        assert!(tracks[0].musical_key.is_none());
+
+        let a = Path::new("a.mp3");
+        core.set_track_key("Main", 0, a, "Am").unwrap();
+        let track = &core.get_playlist_tracks("Main").unwrap()[0];
+        assert_eq!(track.musical_key.as_deref(), Some("Am"));
+        assert_eq!(track.camelot_key.as_deref(), Some("8A"));
+
+        assert!(core.set_track_key("Main", 0, a, "loud").is_err());
+        assert!(core.set_track_key("Main", 3, a, "C").is_err());
+        // Another track now sits at the analyzed index
+        let b = Path::new("b.mp3");
+        assert!(core.set_track_key("Main", 0, b, "C").is_err());
+    }
+
+    #[test]
+    fn set_track_no_crossfade_in_toggles_flag() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", "/music/news_bed.mp3", "News");
+
+        core.set_track_no_crossfade_in("Main", 0, true).unwrap();
+        assert!(core.get_playlist_tracks("Main").unwrap()[0].no_crossfade_in);
+        core.set_track_no_crossfade_in("Main", 0, false).unwrap();
+        assert!(!core.get_playlist_tracks("Main").unwrap()[0].no_crossfade_in);
+
+        assert!(core.set_track_no_crossfade_in("Main", 3, true).is_err());
+        assert!(core.set_track_no_crossfade_in("Nope", 0, true).is_err());
+    }
+
+    #[test]
+    fn replay_gain_refresh_fills_tracks_without_a_value() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.create_playlist("Other".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", "old.mp3", "Old");
+        push_mock_track(&mut core, "Main", "tagged.mp3", "Tagged");
+        push_mock_track(&mut core, "Other", "old.mp3", "Old");
+        core.engine.find_playlist_mut("Main").unwrap().tracks[1].replay_gain_db = Some(-2.0);
+
+        let paths = core.replay_gain_refresh_paths();
+        assert_eq!(paths, vec![PathBuf::from("old.mp3")]);
+
+        let gains = [
+            (PathBuf::from("old.mp3"), -5.0),
+            (PathBuf::from("tagged.mp3"), 9.0),
+        ];
+        assert_eq!(core.apply_replay_gain_tags(&gains).unwrap(), 2);
+        let main = core.get_playlist_tracks("Main").unwrap();
+        assert_eq!(main[0].replay_gain_db, Some(-5.0));
+        assert_eq!(main[1].replay_gain_db, Some(-2.0));
+        let other = core.get_playlist_tracks("Other").unwrap();
+        assert_eq!(other[0].replay_gain_db, Some(-5.0));
+        assert!(core.replay_gain_refresh_paths().is_empty());
+    }
+
+    #[test]
+    fn set_track_gain_applies_on_top_of_replay_gain() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", "/music/quiet.mp3", "Quiet");
+        core.engine.find_playlist_mut("Main").unwrap().tracks[0].replay_gain_db = Some(-3.0);
+
+        core.set_track_gain("Main", 0, 6.0).unwrap();
+        let tracks = core.get_playlist_tracks("Main").unwrap();
+        assert_eq!(tracks[0].gain_trim_db, 6.0);
+
+        assert_eq!(core.playing_gain(), 1.0);
+        core.set_active_playlist("Main").unwrap();
+        core.prepare_play(Some(0)).unwrap();
+        // ReplayGain is off by default: only the trim applies
+        assert!(!core.get_config().replay_gain_enabled);
+        assert!((core.playing_gain() - 10f32.powf(6.0 / 20.0)).abs() < 1e-5);
+        core.set_replay_gain(true).unwrap();
+        let expected = 10f32.powf(3.0 / 20.0);
+        assert!((core.playing_gain() - expected).abs() < 1e-5);
+
+        assert!(core.set_track_gain("Main", 0, 40.0).is_err());
+        assert!(core.set_track_gain("Main", 0, -100.0).is_err());
+        assert!(core.set_track_gain("Main", 4, 0.0).is_err());
+        let tracks = core.get_playlist_tracks("Main").unwrap();
+        assert_eq!(tracks[0].gain_trim_db, 6.0);
+    }
+
+    // -- Shuffle --
+
+    #[test]
+    fn shuffle_playlist_separates_lectures() {
+        let mut core = make_core();
+        core.create_playlist("Mix".to_string()).unwrap();
+        let artists = ["Rabbi A", "Rav B", "Rabbi C", "Band", "Singer", "Choir"];
+        for (i, artist) in artists.iter().enumerate() {
+            push_mock_track(&mut core, "Mix", &format!("{}.mp3", i), artist);
+        }
+
+        for seed in 0..10 {
+            let violations = core.shuffle_playlist("Mix", 1, Some(seed)).unwrap();
+            assert_eq!(violations, 0);
+            let pl = core.engine.find_playlist("Mix").unwrap();
+            assert_eq!(pl.tracks.len(), 6);
+            for pair in pl.tracks.windows(2) {
+                let both_lectures = core.engine.lecture_detector.is_lecture(&pair[0].artist)
+                    && core.engine.lecture_detector.is_lecture(&pair[1].artist);
+                assert!(!both_lectures, "seed {}", seed);
+            }
+        }
+        assert!(core.shuffle_playlist("Nope", 1, None).is_err());
+    }
+
+    // -- Editor navigation --
+
+    #[test]
+    fn editor_neighbor_steps_and_stops_at_ends() {
+        let mut core = make_core();
+        core.create_playlist("Show".to_string()).unwrap();
+        for path in ["a.mp3", "b.mp3", "c.mp3"] {
+            push_mock_track(&mut core, "Show", path, "Artist");
+        }
+
+        let step = |index, path, forward| core.editor_neighbor(Some("Show"), index, path, forward);
+        assert_eq!(step(0, "a.mp3", true), Ok(Some((1, "b.mp3".to_string()))));
+        assert_eq!(step(1, "b.mp3", false), Ok(Some((0, "a.mp3".to_string()))));
+        assert_eq!(step(2, "c.mp3", true), Ok(None));
+        assert_eq!(step(0, "a.mp3", false), Ok(None));
+        assert!(step(5, "z.mp3", true).is_err());
+        let missing = core.editor_neighbor(Some("Nope"), 0, "a.mp3", true);
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn editor_neighbor_steps_from_the_given_copy_of_a_repeated_file() {
+        let mut core = make_core();
+        core.create_playlist("Show".to_string()).unwrap();
+        for path in ["a.mp3", "b.mp3", "a.mp3", "c.mp3"] {
+            push_mock_track(&mut core, "Show", path, "Artist");
+        }
+
+        let step = |index, path| core.editor_neighbor(Some("Show"), index, path, true);
+        assert_eq!(step(2, "a.mp3"), Ok(Some((3, "c.mp3".to_string()))));
+        // The index no longer holds the file being edited
+        assert!(step(1, "a.mp3").unwrap_err().contains("no longer"));
+    }
+
+    #[test]
+    fn editor_neighbor_defaults_to_active_playlist() {
+        let mut core = make_core();
+        core.create_playlist("Show".to_string()).unwrap();
+        push_mock_track(&mut core, "Show", "a.mp3", "Artist");
+        push_mock_track(&mut core, "Show", "b.mp3", "Artist");
+        core.set_active_playlist("Show").unwrap();
+
+        let next = core.editor_neighbor(None, 0, "a.mp3", true).unwrap();
+        assert_eq!(next, Some((1, "b.mp3".to_string())));
+    }
+
+    // -- Validation --
+
+    #[test]
+    fn playlist_tracks_report_missing_file_error() {
+        let temp = tempfile::tempdir().unwrap();
+        let good = temp.path().join("good.wav");
+        write_wav(&good, &[1000i16; 800], 8000);
+
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", &good.to_string_lossy(), "A");
+        push_mock_track(&mut core, "Main", "missing_track.mp3", "B");
+
+        let tracks = core.get_playlist_tracks("Main").unwrap();
+        assert_eq!(tracks[0].error, None);
+        assert_eq!(tracks[1].error.as_deref(), Some("File not found"));
+    }
+
+    #[test]
+    fn validate_playlist_flags_missing_and_undecodable() {
+        let temp = tempfile::tempdir().unwrap();
+        let good = temp.path().join("good.wav");
+        write_wav(&good, &[1000i16; 800], 8000);
+        let bad = temp.path().join("bad.mp3");
+        fs::write(&bad, b"not really audio").unwrap();
+
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", &good.to_string_lossy(), "A");
+        push_mock_track(&mut core, "Main", &bad.to_string_lossy(), "B");
+        push_mock_track(&mut core, "Main", "missing_track.mp3", "C");
+
+        let quick = core.validate_playlist("Main", false).unwrap();
+        assert_eq!(quick.len(), 1);
+        assert_eq!(quick[0].index, 2);
+
+        let full = core.validate_playlist("Main", true).unwrap();
+        let indices: Vec<usize> = full.iter().map(|i| i.index).collect();
+        assert_eq!(indices, vec![1, 2]);
+        assert!(full[0].problem.contains("decode"));
+    }
+
+    // -- Relink --
+
+    #[test]
+    fn relink_paths_rewrites_prefix_and_rereads_metadata() {
+        let temp = tempfile::tempdir().unwrap();
+        let new_root = temp.path().join("Archive");
+        fs::create_dir_all(&new_root).unwrap();
+        write_wav(&new_root.join("New Band - Tune.wav"), &[0; 800], 8000);
+
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", "/old/Music/New Band - Tune.wav", "Old");
+        push_mock_track(&mut core, "Main", "/old/Music/gone.mp3", "Gone");
+        push_mock_track(&mut core, "Main", "/elsewhere/keep.mp3", "Keep");
+        let new_prefix = new_root.to_string_lossy().to_string();
+
+        let summary = core
+            .relink_paths("Main", "/old/Music", &new_prefix, false)
+            .unwrap();
+        assert_eq!(summary.changed, 2);
+        assert_eq!(summary.missing, 1);
+
+        let tracks = core.get_playlist_tracks("Main").unwrap();
+        assert_eq!(
+            PathBuf::from(&tracks[0].path),
+            new_root.join("New Band - Tune.wav")
+        );
+        assert_eq!(tracks[0].artist, "New Band");
+        assert_eq!(tracks[0].title, "Tune");
+        assert_eq!(PathBuf::from(&tracks[1].path), new_root.join("gone.mp3"));
+        assert_eq!(tracks[1].artist, "Gone");
+        assert_eq!(tracks[2].path, "/elsewhere/keep.mp3");
+    }
+
+    #[test]
+    fn date_token_paths_are_checked_against_todays_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let new_root = temp.path().join("Daily");
+        fs::create_dir_all(&new_root).unwrap();
+        let stored = new_root.join("news_{YYYY}{MM}{DD}.wav");
+        let today = date_tokens::expand_for_today(&stored);
+        write_wav(&today, &[0; 800], 8000);
+
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", "/old/news_{YYYY}{MM}{DD}.wav", "News");
+        let new_prefix = new_root.to_string_lossy().to_string();
+        let summary = core
+            .relink_paths("Main", "/old", &new_prefix, false)
+            .unwrap();
+        assert_eq!(summary.missing, 0);
+        // The tokens stay in the playlist
+        let tracks = core.get_playlist_tracks("Main").unwrap();
+        assert_eq!(PathBuf::from(&tracks[0].path), stored);
+
+        assert_eq!(core.playlist_track_paths("Main").unwrap(), vec![today]);
+        assert!(core.validate_playlist("Main", true).unwrap().is_empty());
+        assert!(validate_track_paths(&[stored], true).is_empty());
+    }
+
+    #[test]
+    fn relink_paths_dry_run_changes_nothing() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", "/old/Music/a.mp3", "A");
+        push_mock_track(&mut core, "Main", "/old/Music/b.mp3", "B");
+
+        let summary = core
+            .relink_paths("Main", "/old/Music", "/new/Archive", true)
+            .unwrap();
+        assert_eq!(summary.changed, 2);
+        let tracks = core.get_playlist_tracks("Main").unwrap();
+        assert_eq!(tracks[0].path, "/old/Music/a.mp3");
+        assert_eq!(tracks[1].path, "/old/Music/b.mp3");
+
+        assert!(core.relink_paths("Nope", "/old", "/new", true).is_err());
+        assert!(core.relink_paths("Main", "/", "/new", true).is_err());
+    }
+
+    #[test]
+    fn relink_skips_tracks_that_moved_while_reading() {
+        let mut core = make_core();
+        core.create_playlist("Main".to_string()).unwrap();
+        push_mock_track(&mut core, "Main", "/old/a.mp3", "A");
+        push_mock_track(&mut core, "Main", "/old/b.mp3", "B");
+
+        let plan = core.plan_relink("Main", "/old", "/new").unwrap();
+        assert_eq!(plan.len(), 2);
+        // Reading happens without the core, so the playlist can change meanwhile
+        let reads = plan.into_iter().map(|m| m.read(true)).collect();
+        core.remove_tracks("Main", &[0]).unwrap();
+
+        let summary = core.apply_relink("Main", "/old", "/new", reads, false);
+        assert_eq!(summary.unwrap().changed, 0);
+        let tracks = core.get_playlist_tracks("Main").unwrap();
+        assert_eq!(tracks[0].path, "/old/b.mp3");
+    }
+
+    // -- Config bundle --
+
+    #[test]
+    fn export_config_bundle_lists_ad_and_schedule_audio() {
+        use std::io::Read;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut core = make_core();
+        core.engine
+            .ads
+            .push(AdConfig::new("Promo".into(), dir.path().join("promo.mp3")));
+        core.add_schedule_event(
+            "08:00",
+            "stop",
+            dir.path().join("news.mp3").to_str().unwrap(),
+            Some(9),
+            Some("News".into()),
+            None,
+        )
+        .unwrap();
+        let out = dir.path().join("bundle.zip");
+
+        let manifest = core
+            .export_config_bundle(out.to_str().unwrap(), false)
+            .unwrap();
+        let kinds: Vec<&str> = manifest.audio.iter().map(|a| a.kind.as_str()).collect();
+        assert_eq!(kinds, ["ad", "schedule"]);
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&out).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        assert!(names.contains(&config_bundle::STATE_ENTRY));
+        assert!(names.contains(&config_bundle::MANIFEST_ENTRY));
+        let mut json = String::new();
+        archive
+            .by_name(config_bundle::MANIFEST_ENTRY)
+            .unwrap()
+            .read_to_string(&mut json)
+            .unwrap();
+        assert!(json.contains("promo.mp3"));
+        assert!(json.contains("news.mp3"));
+    }
+
+    /// Core with playlists `names` and one ad per entry in `ads`.
+    fn core_with(names: &[&str], ads: &[&str]) -> AppCore {
+        let mut core = make_core();
+        for name in names {
+            core.create_playlist(name.to_string()).unwrap();
+        }
+        for ad in ads {
+            core.engine
+                .ads
+                .push(AdConfig::new(ad.to_string(), format!("{}.mp3", ad).into()));
+        }
+        core
+    }
+
+    fn bundle_of(core: &mut AppCore, dir: &Path) -> String {
+        let out = dir.join("bundle.zip");
+        let out = out.to_str().unwrap().to_string();
+        core.export_config_bundle(&out, false).unwrap();
+        out
+    }
+
+    #[test]
+    fn import_bundle_replace_swaps_everything() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut source = core_with(&["Morning", "Evening"], &["Promo"]);
+        source.set_crossfade(3.0).unwrap();
+        push_mock_track(&mut source, "Morning", "m.mp3", "M");
+        let bundle = bundle_of(&mut source, dir.path());
+
+        let mut core = AppCore::new(&dir.path().join("state.json"));
+        core.create_playlist("Old".to_string()).unwrap();
+        core.engine.save().unwrap();
+        let summary = core.import_config_bundle(&bundle, false).unwrap();
+
+        let names: Vec<&str> = core.engine.playlists.iter().map(|p| &*p.name).collect();
+        assert_eq!(names, ["Morning", "Evening"]);
+        let morning = core.engine.find_playlist("Morning").unwrap();
+        assert_eq!(morning.tracks.len(), 1);
+        assert_eq!(core.engine.ads.len(), 1);
+        assert_eq!(core.get_config().crossfade_secs, 3.0);
+        assert_eq!(summary.playlists_added, ["Morning", "Evening"]);
+        // State file path is kept, and the old state was backed up beside it
+        let state_path = dir.path().join("state.json");
+        assert_eq!(core.engine.state_path(), Some(state_path.as_path()));
+        let backups = fs::read_dir(dir.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with("state.backup-"))
+            .count();
+        assert_eq!(backups, 1);
+    }
+
+    #[test]
+    fn import_bundle_merge_keeps_existing_and_skips_collisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut source = core_with(&["Morning", "Evening"], &["Promo", "Sale"]);
+        push_mock_track(&mut source, "Evening", "e.mp3", "E");
+        let bundle = bundle_of(&mut source, dir.path());
+
+        let mut core = core_with(&["Morning", "Local"], &["promo"]);
+        push_mock_track(&mut core, "Morning", "mine.mp3", "Mine");
+        let summary = core.import_config_bundle(&bundle, true).unwrap();
+
+        let names: Vec<&str> = core.engine.playlists.iter().map(|p| &*p.name).collect();
+        assert_eq!(names, ["Morning", "Local", "Evening"]);
+        // The existing Morning playlist is untouched
+        let morning = core.engine.find_playlist("Morning").unwrap();
+        assert_eq!(morning.tracks[0].path, PathBuf::from("mine.mp3"));
+        let evening = core.engine.find_playlist("Evening").unwrap();
+        assert_eq!(evening.tracks.len(), 1);
+        let ids: Vec<u32> = core.engine.playlists.iter().map(|p| p.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+
+        assert_eq!(summary.playlists_skipped, ["Morning"]);
+        assert_eq!(summary.ads_added, ["Sale"]);
+        assert_eq!(summary.ads_skipped, ["Promo"]);
+        assert_eq!(core.engine.ads.len(), 2);
+    }
+
+    #[test]
+    fn import_corrupt_bundle_leaves_state_alone() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut core = core_with(&["Morning"], &["Promo"]);
+
+        let not_zip = dir.path().join("not_a_bundle.zip");
+        fs::write(&not_zip, b"definitely not a zip").unwrap();
+        let result = core.import_config_bundle(not_zip.to_str().unwrap(), false);
+        assert!(result.is_err());
+
+        // A real zip whose state JSON is broken
+        let bad_state = dir.path().join("bad_state.zip");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&bad_state).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file(config_bundle::STATE_ENTRY, options).unwrap();
+        zip.write_all(br#"{"playlists": [oops"#).unwrap();
+        zip.finish().unwrap();
+        let err = core
+            .import_config_bundle(bad_state.to_str().unwrap(), true)
+            .unwrap_err();
+        assert!(err.contains("Corrupt"));
+
+        assert_eq!(core.engine.playlists.len(), 1);
+        assert_eq!(core.engine.playlists[0].name, "Morning");
+        assert_eq!(core.engine.ads.len(), 1);
+    }
 }
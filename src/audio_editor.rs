@@ -3,11 +3,19 @@
 //! All edits are non-destructive (stored as an operation list in the frontend).
 //! On export, this module builds a single ffmpeg invocation with an `-af` filter chain.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 // ── Operation types ──────────────────────────────────────────────────────────
 
+/// Allowed range for the pitch-preserving `tempo` factor.
+pub const TEMPO_MIN: f64 = 0.25;
+pub const TEMPO_MAX: f64 = 4.0;
+
+fn default_tempo() -> f64 {
+    1.0
+}
+
 /// A time-range to cut (remove) from the output.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CutRegion {
@@ -28,6 +36,10 @@ pub struct EditorOperations {
     pub speed: f64,
     /// Pitch shift in semitones (0.0 = no change, ±12 range).
     pub pitch_semitones: f64,
+    /// Pitch-preserving tempo factor for fitting a slot (1.0 = original,
+    /// `TEMPO_MIN`–`TEMPO_MAX`). Applied as atempo stages after `speed`.
+    #[serde(default = "default_tempo")]
+    pub tempo: f64,
     /// Fade-in duration in seconds (0.0 = no fade).
     pub fade_in_secs: f64,
     /// Fade-out duration in seconds (0.0 = no fade).
@@ -41,6 +53,19 @@ pub struct EditorOperations {
     pub total_duration_secs: f64,
 }
 
+impl EditorOperations {
+    /// Reject operations ffmpeg cannot apply.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.tempo.is_finite() || !(TEMPO_MIN..=TEMPO_MAX).contains(&self.tempo) {
+            return Err(format!(
+                "Tempo {} out of range ({}–{})",
+                self.tempo, TEMPO_MIN, TEMPO_MAX
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// A detected silence region in an audio file.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SilenceRegion {
@@ -99,6 +124,11 @@ pub fn build_ffmpeg_args(
         filters.extend(build_atempo_chain(ops.speed));
     }
 
+    // Tempo (pitch-preserving, fine-grained for slot fitting)
+    if (ops.tempo - 1.0).abs() > 0.0001 && ops.tempo > 0.0 {
+        filters.extend(build_atempo_chain(ops.tempo));
+    }
+
     // Pitch shift — asetrate changes perceived pitch, aresample corrects playback rate
     if ops.pitch_semitones.abs() > 0.01 {
         let ratio = 2.0_f64.powf(ops.pitch_semitones / 12.0);
@@ -143,6 +173,21 @@ pub fn build_ffmpeg_args(
     args
 }
 
+/// Build ffmpeg arguments for a standalone EBU R128 loudness-normalize pass
+/// to `target_lufs` integrated loudness (true peak -1.5 dBTP, LRA 11).
+pub fn build_loudnorm_args(input_path: &str, output_path: &str, target_lufs: f32) -> Vec<String> {
+    vec![
+        "-y".into(),
+        "-i".into(),
+        input_path.to_string(),
+        "-af".into(),
+        format!("loudnorm=I={:.1}:TP=-1.5:LRA=11", target_lufs),
+        "-q:a".into(),
+        "2".into(),
+        output_path.to_string(),
+    ]
+}
+
 /// Build an atempo filter chain, chaining multiple steps when speed is outside [0.5, 2.0].
 fn build_atempo_chain(speed: f64) -> Vec<String> {
     if speed <= 0.0 {
@@ -178,6 +223,236 @@ pub fn run_ffmpeg(args: &[String]) -> Result<(), String> {
     }
 }
 
+// ── Trim in place ────────────────────────────────────────────────────────────
+
+/// Build ffmpeg arguments that keep `start_secs..end_secs` of `input_path`
+/// (`end_secs` 0 = to the end), encoding to `output_path` in the same
+/// container as the input. Only WAV and MP3 inputs can be trimmed; others
+/// are an error rather than MP3 written under their own extension.
+pub fn build_trim_args(
+    input_path: &str,
+    output_path: &str,
+    start_secs: f64,
+    end_secs: f64,
+) -> Result<Vec<String>, String> {
+    let ext = Path::new(input_path)
+        .extension()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_ascii_lowercase();
+    let format = match ext.as_str() {
+        "wav" | "mp3" => ext,
+        _ => {
+            return Err(format!(
+                "Cannot trim '{}': only WAV and MP3 files are supported",
+                input_path
+            ));
+        }
+    };
+    let ops = EditorOperations {
+        trim_start_secs: start_secs,
+        trim_end_secs: end_secs,
+        volume_db: 0.0,
+        speed: 1.0,
+        pitch_semitones: 0.0,
+        tempo: 1.0,
+        fade_in_secs: 0.0,
+        fade_out_secs: 0.0,
+        normalize: false,
+        cuts: vec![],
+        total_duration_secs: 0.0,
+    };
+    Ok(build_ffmpeg_args(input_path, output_path, &ops, &format, 2))
+}
+
+/// Where `trim_in_place` keeps the original when asked to: `<name>.bak`.
+pub fn trim_backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Trim `path` to `start_secs..end_secs` and overwrite it. `run` gets the
+/// ffmpeg arguments (normally `run_ffmpeg`) and writes to a temp file beside
+/// the original, which is renamed over it only once that succeeds, so a
+/// failed run leaves the original untouched. With `keep_backup`, the
+/// original is copied to `trim_backup_path` first. Returns the backup path.
+pub fn trim_in_place<F>(
+    path: &Path,
+    start_secs: f64,
+    end_secs: f64,
+    keep_backup: bool,
+    run: F,
+) -> Result<Option<PathBuf>, String>
+where
+    F: FnOnce(&[String]) -> Result<(), String>,
+{
+    if !path.is_file() {
+        return Err(format!("'{}' is not a file", path.display()));
+    }
+    if !start_secs.is_finite() || start_secs < 0.0 {
+        return Err("Trim start must be 0 or more seconds".to_string());
+    }
+    if end_secs > 0.0 && end_secs <= start_secs {
+        return Err("Trim end must be after trim start".to_string());
+    }
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(".trim-{}", file_name));
+    let args = build_trim_args(
+        &path.to_string_lossy(),
+        &temp.to_string_lossy(),
+        start_secs,
+        end_secs,
+    )?;
+    let finish = || -> Result<Option<PathBuf>, String> {
+        run(&args)?;
+        if !temp.is_file() {
+            return Err("ffmpeg produced no output".to_string());
+        }
+        let backup = if keep_backup {
+            let backup = trim_backup_path(path);
+            std::fs::copy(path, &backup)
+                .map_err(|e| format!("Cannot write backup '{}': {}", backup.display(), e))?;
+            Some(backup)
+        } else {
+            None
+        };
+        std::fs::rename(&temp, path)
+            .map_err(|e| format!("Cannot replace '{}': {}", path.display(), e))?;
+        Ok(backup)
+    };
+    let result = finish();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+// ── Format conversion ────────────────────────────────────────────────────────
+
+/// Target format for `convert_file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertFormat {
+    Mp3,
+    Flac,
+    Wav,
+    Ogg,
+}
+
+impl ConvertFormat {
+    /// Parse "mp3", "flac", "wav" or "ogg" (case-insensitive).
+    pub fn from_str_loose(s: &str) -> Result<Self, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mp3" => Ok(ConvertFormat::Mp3),
+            "flac" => Ok(ConvertFormat::Flac),
+            "wav" => Ok(ConvertFormat::Wav),
+            "ogg" => Ok(ConvertFormat::Ogg),
+            other => Err(format!(
+                "Unknown format '{}' (expected mp3, flac, wav or ogg)",
+                other
+            )),
+        }
+    }
+
+    /// File extension for this format, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ConvertFormat::Mp3 => "mp3",
+            ConvertFormat::Flac => "flac",
+            ConvertFormat::Wav => "wav",
+            ConvertFormat::Ogg => "ogg",
+        }
+    }
+
+    /// Whether `path` already has this format's extension.
+    pub fn matches(self, path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case(self.extension()))
+    }
+}
+
+/// Build ffmpeg arguments that re-encode `input_path` to `output_path` as
+/// `format`. `quality` is clamped per format: MP3 VBR 0 (best) – 9, OGG
+/// Vorbis 0 – 10 (best), FLAC compression level 0 – 12; WAV is always
+/// 16-bit PCM and ignores it. Cover art streams are dropped.
+pub fn build_convert_args(
+    input_path: &str,
+    output_path: &str,
+    format: ConvertFormat,
+    quality: u8,
+) -> Vec<String> {
+    let (codec, quality_flag, max_quality) = match format {
+        ConvertFormat::Mp3 => ("libmp3lame", Some("-q:a"), 9),
+        ConvertFormat::Ogg => ("libvorbis", Some("-q:a"), 10),
+        ConvertFormat::Flac => ("flac", Some("-compression_level"), 12),
+        ConvertFormat::Wav => ("pcm_s16le", None, 0),
+    };
+    let mut args: Vec<String> = vec![
+        "-y".into(),
+        "-i".into(),
+        input_path.to_string(),
+        "-vn".into(),
+        "-c:a".into(),
+        codec.into(),
+    ];
+    if let Some(flag) = quality_flag {
+        args.push(flag.into());
+        args.push(quality.min(max_quality).to_string());
+    }
+    args.push(output_path.to_string());
+    args
+}
+
+/// Re-encode `path` to `format` beside it (same name, new extension) using
+/// `run` (normally `run_ffmpeg`). The original is left in place. Returns
+/// the new path, or None when the file is already in that format. An
+/// existing file at the new path is never overwritten.
+pub fn convert_file<F>(
+    path: &Path,
+    format: ConvertFormat,
+    quality: u8,
+    run: F,
+) -> Result<Option<PathBuf>, String>
+where
+    F: FnOnce(&[String]) -> Result<(), String>,
+{
+    if format.matches(path) {
+        return Ok(None);
+    }
+    if !path.is_file() {
+        return Err(format!("'{}' is not a file", path.display()));
+    }
+    let output = path.with_extension(format.extension());
+    if output.exists() {
+        return Err(format!("'{}' already exists", output.display()));
+    }
+
+    let file_name = output.file_name().unwrap_or_default().to_string_lossy();
+    let temp = output.with_file_name(format!(".convert-{}", file_name));
+    let args = build_convert_args(
+        &path.to_string_lossy(),
+        &temp.to_string_lossy(),
+        format,
+        quality,
+    );
+    let finish = || -> Result<(), String> {
+        run(&args)?;
+        if !temp.is_file() {
+            return Err("ffmpeg produced no output".to_string());
+        }
+        std::fs::rename(&temp, &output)
+            .map_err(|e| format!("Cannot write '{}': {}", output.display(), e))
+    };
+    match finish() {
+        Ok(()) => Ok(Some(output)),
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp);
+            Err(e)
+        }
+    }
+}
+
 // ── Silence detection ────────────────────────────────────────────────────────
 
 /// Scan an audio file for silence regions using `ffmpeg silencedetect`.
@@ -208,6 +483,13 @@ pub fn detect_silence_regions(
     parse_silence_output(&stderr)
 }
 
+/// Suggested cue-in (seconds) for `path`: where audio first rises above
+/// `threshold_db` dBFS, skipping any lead-in silence.
+pub fn suggest_cue_in(path: &Path, threshold_db: f64) -> Result<f64, String> {
+    let threshold = crate::silence::dbfs_to_linear(threshold_db as f32);
+    crate::silence::detect_leading_silence(path, threshold).map(|d| d.as_secs_f64())
+}
+
 fn parse_silence_output(stderr: &str) -> Result<Vec<SilenceRegion>, String> {
     let mut regions: Vec<SilenceRegion> = Vec::new();
     let mut current_start: Option<f64> = None;
@@ -249,6 +531,96 @@ fn parse_silence_output(stderr: &str) -> Result<Vec<SilenceRegion>, String> {
     Ok(regions)
 }
 
+// ── Silence split ────────────────────────────────────────────────────────────
+
+/// Audio segments of a `total_secs` long recording between the given
+/// silences, as `(start, end)` seconds. Segments shorter than
+/// `min_track_secs` are merged into the previous segment (or the next one,
+/// for the first), taking the silence between them along.
+pub fn segments_between_silences(
+    silences: &[SilenceRegion],
+    total_secs: f64,
+    min_track_secs: f64,
+) -> Vec<(f64, f64)> {
+    let mut spans = Vec::new();
+    let mut cursor = 0.0;
+    for silence in silences {
+        let start = silence.start_secs.clamp(0.0, total_secs);
+        if start > cursor {
+            spans.push((cursor, start));
+        }
+        cursor = cursor.max(silence.end_secs.min(total_secs));
+    }
+    if total_secs > cursor {
+        spans.push((cursor, total_secs));
+    }
+
+    let mut segments: Vec<(f64, f64)> = Vec::new();
+    for (start, end) in spans {
+        let n = segments.len();
+        match segments.last_mut() {
+            Some(last) if end - start < min_track_secs => last.1 = end,
+            // The first segment was too short: fold it into this one
+            Some(last) if n == 1 && last.1 - last.0 < min_track_secs => last.1 = end,
+            _ => segments.push((start, end)),
+        }
+    }
+    segments
+}
+
+/// Split points for a long recording (e.g. a digitized reel) at silences
+/// below `threshold_db` lasting at least `min_silence_secs`. Returns the
+/// `(start, end)` seconds of each track; see `segments_between_silences`.
+pub fn split_on_silence(
+    path: &Path,
+    threshold_db: f64,
+    min_silence_secs: f64,
+    min_track_secs: f64,
+) -> Result<Vec<(f64, f64)>, String> {
+    let total = crate::track::decoded_duration(path)
+        .ok_or_else(|| format!("Cannot decode '{}'", path.display()))?
+        .as_secs_f64();
+    let silences = detect_silence_regions(path, threshold_db, min_silence_secs)?;
+    Ok(segments_between_silences(&silences, total, min_track_secs))
+}
+
+/// Write each segment of `path` to `output_dir` as `<stem> 01.<ext>`,
+/// `<stem> 02.<ext>`, … in the source's format. `run` gets the ffmpeg
+/// arguments for each segment (normally `run_ffmpeg`). Existing files are
+/// never overwritten. Returns the written paths in order.
+pub fn export_segments<F>(
+    path: &Path,
+    segments: &[(f64, f64)],
+    output_dir: &Path,
+    mut run: F,
+) -> Result<Vec<PathBuf>, String>
+where
+    F: FnMut(&[String]) -> Result<(), String>,
+{
+    if !output_dir.is_dir() {
+        return Err(format!("'{}' is not a directory", output_dir.display()));
+    }
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = path.extension().unwrap_or_default().to_string_lossy();
+    let outputs: Vec<PathBuf> = (1..=segments.len())
+        .map(|n| output_dir.join(format!("{} {:02}.{}", stem, n, ext)))
+        .collect();
+    if let Some(existing) = outputs.iter().find(|p| p.exists()) {
+        return Err(format!("'{}' already exists", existing.display()));
+    }
+
+    for (&(start, end), output) in segments.iter().zip(&outputs) {
+        let args = build_trim_args(
+            &path.to_string_lossy(),
+            &output.to_string_lossy(),
+            start,
+            end,
+        )?;
+        run(&args)?;
+    }
+    Ok(outputs)
+}
+
 // ── Tests ────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -262,6 +634,7 @@ mod tests {
             volume_db: 0.0,
             speed: 1.0,
             pitch_semitones: 0.0,
+            tempo: 1.0,
             fade_in_secs: 0.0,
             fade_out_secs: 0.0,
             normalize: false,
@@ -310,6 +683,15 @@ mod tests {
         assert!(!args.contains(&"-q:a".to_string()));
     }
 
+    #[test]
+    fn loudnorm_args_target_lufs() {
+        let args = build_loudnorm_args("ads/spot.mp3", "ads/spot.normalized.mp3", -16.0);
+        assert_eq!(args[..3], ["-y", "-i", "ads/spot.mp3"]);
+        let af_pos = args.iter().position(|a| a == "-af").expect("-af present");
+        assert_eq!(args[af_pos + 1], "loudnorm=I=-16.0:TP=-1.5:LRA=11");
+        assert_eq!(args.last().unwrap(), "ads/spot.normalized.mp3");
+    }
+
     #[test]
     fn atempo_chain_fast() {
         let chain = build_atempo_chain(4.0);
@@ -333,6 +715,41 @@ mod tests {
         assert!(chain[0].contains("1.5"));
     }
 
+    fn atempo_stages(args: &[String]) -> Vec<f64> {
+        let af_pos = args.iter().position(|a| a == "-af").expect("-af present");
+        args[af_pos + 1]
+            .split(',')
+            .filter_map(|f| f.strip_prefix("atempo="))
+            .map(|v| v.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn build_args_tempo_chains_stages_outside_half_to_double() {
+        let mut ops = no_ops();
+        ops.tempo = 0.25;
+        let args = build_ffmpeg_args("in.mp3", "out.mp3", &ops, "mp3", 2);
+        assert_eq!(atempo_stages(&args), [0.5, 0.5]);
+
+        ops.tempo = 1.5;
+        let args = build_ffmpeg_args("in.mp3", "out.mp3", &ops, "mp3", 2);
+        assert_eq!(atempo_stages(&args), [1.5]);
+    }
+
+    #[test]
+    fn validate_rejects_tempo_out_of_range() {
+        let mut ops = no_ops();
+        assert!(ops.validate().is_ok());
+        ops.tempo = 0.97;
+        assert!(ops.validate().is_ok());
+        ops.tempo = 0.1;
+        assert!(ops.validate().is_err());
+        ops.tempo = 5.0;
+        assert!(ops.validate().is_err());
+        ops.tempo = f64::NAN;
+        assert!(ops.validate().is_err());
+    }
+
     #[test]
     fn parse_silence_basic() {
         let stderr = "\
@@ -355,6 +772,76 @@ mod tests {
         assert_eq!(regions.len(), 2);
     }
 
+    /// silencedetect output for three 4 s tones with 1 s of silence
+    /// between them and 0.5 s of lead-in and tail.
+    const THREE_TONES: &str = "\
+[silencedetect @ 0xabc] silence_start: 0\n\
+[silencedetect @ 0xabc] silence_end: 0.5 | silence_duration: 0.5\n\
+[silencedetect @ 0xabc] silence_start: 4.52\n\
+[silencedetect @ 0xabc] silence_end: 5.48 | silence_duration: 0.96\n\
+[silencedetect @ 0xabc] silence_start: 9.51\n\
+[silencedetect @ 0xabc] silence_end: 10.5 | silence_duration: 0.99\n\
+[silencedetect @ 0xabc] silence_start: 14.5\n";
+
+    #[test]
+    fn split_finds_three_tracks_between_silences() {
+        let silences = parse_silence_output(THREE_TONES).unwrap();
+        let segments = segments_between_silences(&silences, 15.0, 2.0);
+        assert_eq!(segments.len(), 3);
+        let expected = [(0.5, 4.5), (5.5, 9.5), (10.5, 14.5)];
+        for ((start, end), (want_start, want_end)) in segments.iter().zip(expected) {
+            assert!((start - want_start).abs() < 0.05, "start {start}");
+            assert!((end - want_end).abs() < 0.05, "end {end}");
+        }
+    }
+
+    #[test]
+    fn split_merges_tracks_shorter_than_minimum() {
+        let silences = parse_silence_output(THREE_TONES).unwrap();
+        // Every tone is under the minimum, so they all end up in one track
+        let segments = segments_between_silences(&silences, 15.0, 5.0);
+        assert_eq!(segments, vec![(0.5, 14.5)]);
+
+        // A short middle track joins the one before it
+        let silences = parse_silence_output(
+            "silence_start: 10\nsilence_end: 11\nsilence_start: 12\nsilence_end: 13\n",
+        )
+        .unwrap();
+        let segments = segments_between_silences(&silences, 30.0, 5.0);
+        assert_eq!(segments, vec![(0.0, 12.0), (13.0, 30.0)]);
+
+        // A short first track joins the one after it
+        let silences = parse_silence_output("silence_start: 1\nsilence_end: 2\n").unwrap();
+        let segments = segments_between_silences(&silences, 20.0, 5.0);
+        assert_eq!(segments, vec![(0.0, 20.0)]);
+        assert!(segments_between_silences(&[], 0.0, 5.0).is_empty());
+    }
+
+    #[test]
+    fn export_segments_writes_numbered_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Reel 3.wav");
+        std::fs::write(&path, b"reel").unwrap();
+
+        let segments = [(0.5, 4.5), (5.5, 9.5)];
+        let mut calls = Vec::new();
+        let written = export_segments(&path, &segments, dir.path(), |args| {
+            calls.push(args.to_vec());
+            write_output(args, b"segment")
+        })
+        .unwrap();
+        let expected = ["Reel 3 01.wav", "Reel 3 02.wav"].map(|name| dir.path().join(name));
+        assert_eq!(written, expected);
+        assert!(calls[1].windows(2).any(|w| w == ["-ss", "5.500000"]));
+        assert!(calls[1].windows(2).any(|w| w == ["-to", "9.500000"]));
+
+        // Running again would overwrite: refused before ffmpeg runs
+        let again = export_segments(&path, &segments, dir.path(), |_| {
+            Err("ffmpeg should not run".to_string())
+        });
+        assert!(again.unwrap_err().contains("already exists"));
+    }
+
     #[test]
     fn build_args_normalize() {
         let mut ops = no_ops();
@@ -373,4 +860,166 @@ mod tests {
         assert!(args[af_pos + 1].contains("aselect"));
         assert!(args[af_pos + 1].contains("asetpts"));
     }
+
+    #[test]
+    fn build_trim_args_keeps_container() {
+        let args = build_trim_args("in.wav", "out.wav", 1.5, 0.0).unwrap();
+        assert!(args.contains(&"-ss".to_string()));
+        assert!(!args.contains(&"-to".to_string()));
+        assert!(args.contains(&"pcm_s16le".to_string()));
+
+        let args = build_trim_args("in.MP3", "out.MP3", 0.0, 10.0).unwrap();
+        assert!(!args.contains(&"-ss".to_string()));
+        assert!(args.contains(&"-to".to_string()));
+        assert!(args.contains(&"-q:a".to_string()));
+    }
+
+    #[test]
+    fn trim_refuses_containers_it_cannot_keep() {
+        assert!(build_trim_args("in.flac", "out.flac", 1.0, 0.0).is_err());
+        assert!(build_trim_args("in", "out", 1.0, 0.0).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("song.m4a");
+        std::fs::write(&path, b"original").unwrap();
+        let err = trim_in_place(&path, 1.0, 0.0, true, |_| panic!("ffmpeg should not run"));
+        assert!(err.unwrap_err().contains("only WAV and MP3"));
+        assert_eq!(std::fs::read(&path).unwrap(), b"original");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    /// Stub for `run_ffmpeg`: writes `contents` to the output argument.
+    fn write_output(args: &[String], contents: &[u8]) -> Result<(), String> {
+        std::fs::write(args.last().unwrap(), contents).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn trim_in_place_replaces_original_and_keeps_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("song.mp3");
+        std::fs::write(&path, b"original").unwrap();
+
+        let backup = trim_in_place(&path, 1.0, 5.0, true, |args| write_output(args, b"trimmed"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(backup, dir.path().join("song.mp3.bak"));
+        assert_eq!(std::fs::read(&path).unwrap(), b"trimmed");
+        assert_eq!(std::fs::read(&backup).unwrap(), b"original");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn trim_in_place_without_backup_leaves_no_extra_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("song.mp3");
+        std::fs::write(&path, b"original").unwrap();
+
+        let backup = trim_in_place(&path, 1.0, 0.0, false, |args| {
+            write_output(args, b"trimmed")
+        });
+        assert_eq!(backup.unwrap(), None);
+        assert_eq!(std::fs::read(&path).unwrap(), b"trimmed");
+        assert!(!trim_backup_path(&path).exists());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn trim_in_place_preserves_original_on_ffmpeg_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("song.mp3");
+        std::fs::write(&path, b"original").unwrap();
+
+        let result = trim_in_place(&path, 1.0, 5.0, true, |args| {
+            // A partial write followed by a failure
+            write_output(args, b"partial")?;
+            Err("ffmpeg exited with status 1".to_string())
+        });
+        assert!(result.is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), b"original");
+        assert!(!trim_backup_path(&path).exists());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        assert!(trim_in_place(&path, 5.0, 2.0, false, |_| Ok(())).is_err());
+        let missing = dir.path().join("missing.mp3");
+        assert!(trim_in_place(&missing, 0.0, 0.0, false, |_| Ok(())).is_err());
+    }
+
+    #[test]
+    fn convert_args_per_format() {
+        let args = build_convert_args("in.mp3", "out.flac", ConvertFormat::Flac, 5);
+        assert_eq!(
+            args,
+            [
+                "-y",
+                "-i",
+                "in.mp3",
+                "-vn",
+                "-c:a",
+                "flac",
+                "-compression_level",
+                "5",
+                "out.flac"
+            ]
+        );
+
+        let args = build_convert_args("in.mp3", "out.wav", ConvertFormat::Wav, 5);
+        assert_eq!(
+            args,
+            ["-y", "-i", "in.mp3", "-vn", "-c:a", "pcm_s16le", "out.wav"]
+        );
+
+        let args = build_convert_args("in.flac", "out.mp3", ConvertFormat::Mp3, 0);
+        assert!(args.windows(2).any(|w| w == ["-c:a", "libmp3lame"]));
+        assert!(args.windows(2).any(|w| w == ["-q:a", "0"]));
+
+        // Quality is clamped to each format's range
+        let args = build_convert_args("in.wav", "out.ogg", ConvertFormat::Ogg, 50);
+        assert!(args.windows(2).any(|w| w == ["-c:a", "libvorbis"]));
+        assert!(args.windows(2).any(|w| w == ["-q:a", "10"]));
+    }
+
+    #[test]
+    fn convert_format_parses_and_matches_extensions() {
+        assert_eq!(
+            ConvertFormat::from_str_loose(" FLAC ").unwrap(),
+            ConvertFormat::Flac
+        );
+        assert!(ConvertFormat::from_str_loose("aiff").is_err());
+        assert!(ConvertFormat::Mp3.matches(Path::new("a/Song.MP3")));
+        assert!(!ConvertFormat::Mp3.matches(Path::new("a/song.wav")));
+    }
+
+    #[test]
+    fn convert_file_writes_beside_original_and_skips_same_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("song.wav");
+        std::fs::write(&path, b"original").unwrap();
+
+        let skipped = convert_file(&path, ConvertFormat::Wav, 0, |_| {
+            Err("ffmpeg should not run".to_string())
+        });
+        assert_eq!(skipped.unwrap(), None);
+
+        let converted = convert_file(&path, ConvertFormat::Flac, 5, |args| {
+            write_output(args, b"flac")
+        });
+        let output = dir.path().join("song.flac");
+        assert_eq!(converted.unwrap(), Some(output.clone()));
+        assert_eq!(std::fs::read(&output).unwrap(), b"flac");
+        assert_eq!(std::fs::read(&path).unwrap(), b"original");
+
+        // Never overwrites an existing file
+        let again = convert_file(&path, ConvertFormat::Flac, 5, |args| {
+            write_output(args, b"x")
+        });
+        assert!(again.is_err());
+        assert_eq!(std::fs::read(&output).unwrap(), b"flac");
+
+        let failed = convert_file(&path, ConvertFormat::Ogg, 5, |args| {
+            write_output(args, b"partial")?;
+            Err("ffmpeg exited with status 1".to_string())
+        });
+        assert!(failed.is_err());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
 }
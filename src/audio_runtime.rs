@@ -5,8 +5,12 @@
 //! naturally Send+Sync. Track-end detection happens inside the thread loop
 //! via `recv_timeout` + `player.is_empty()`.
 
+use crate::agc::AgcConfig;
+use crate::auto_intro::{IntroCue, IntroMode};
+use crate::channel_map::ChannelMode;
 use crate::level_monitor::LevelMonitor;
-use crate::player::Player;
+use crate::player::{Player, PreparedSource};
+use rodio::Sink;
 use std::path::PathBuf;
 use std::sync::mpsc;
 use std::time::Duration;
@@ -18,7 +22,36 @@ pub enum AudioCmd {
     Play {
         path: PathBuf,
         level_monitor: LevelMonitor,
+        /// Start offset into the file (cue-sheet segment).
+        cue_in: Option<Duration>,
+        /// Offset at which the segment ends and `TrackFinished` fires.
+        cue_out: Option<Duration>,
+        /// Linear playback gain (see `Track::playback_gain`).
+        gain: f32,
+        /// Fade the playing track out while this one fades in over this
+        /// long. Zero cuts straight over.
+        crossfade: Duration,
+        /// Fade this track in from silence over this long when it cuts in
+        /// (the sign-on fade). Not applied to crossfades or to a track held
+        /// for an intro.
+        fade_in: Duration,
     },
+    /// Play this auto-intro with the next `Play`: before it (the track is
+    /// held until the intro ends) or over its start.
+    QueueIntro(IntroCue),
+    /// Mix a file over the program on its own sink at linear `gain`
+    /// (carts, scheduled overlays). It plays to the end; `Stop` leaves it.
+    PlayOver {
+        path: PathBuf,
+        gain: f32,
+    },
+    /// Emit `AudioEvent::ExitPoint` once the playing track reaches this
+    /// position in its file (None = only `TrackFinished` at its end). Every
+    /// `Play` clears it.
+    SetExitPoint(Option<Duration>),
+    /// Fade the playing track out over this long; `TrackFinished` follows
+    /// once it falls silent.
+    FadeOut(Duration),
     Stop,
     Pause,
     Resume,
@@ -26,6 +59,16 @@ pub enum AudioCmd {
     /// Recreate the player on a different output device.
     /// None = use default device.
     SetDevice(Option<String>),
+    /// Resample every following track to this rate (None = file's own rate).
+    SetOutputRate(Option<u32>),
+    /// Open the output with a device buffer of about this many ms (0 = the
+    /// driver's default). Takes effect the next time the output opens: at
+    /// once while idle, else on the next device switch.
+    SetBufferMs(u32),
+    /// Ride the gain of the program bus (None = AGC off).
+    SetAgc(Option<AgcConfig>),
+    /// Map the output channels of the program bus.
+    SetChannelMode(ChannelMode),
     Shutdown,
 }
 
@@ -34,11 +77,16 @@ pub enum AudioCmd {
 pub enum AudioEvent {
     Playing,
     PlayError(String),
+    /// The track reached the position set with `AudioCmd::SetExitPoint`.
+    ExitPoint,
     TrackFinished,
     Stopped,
     Paused,
     Resumed,
     Seeked(f64),
+    /// The output opened; this is how far the speakers run behind it (see
+    /// `Player::output_latency`).
+    OutputLatency(Duration),
 }
 
 // ── Handle ───────────────────────────────────────────────────────────────────
@@ -52,7 +100,81 @@ pub struct AudioHandle {
 
 impl AudioHandle {
     pub fn play(&self, path: PathBuf, level_monitor: LevelMonitor) {
-        let _ = self.tx.send(AudioCmd::Play { path, level_monitor });
+        self.play_range(path, level_monitor, None, None, 1.0);
+    }
+
+    /// Play only the `cue_in..cue_out` segment of a file at linear `gain`.
+    pub fn play_range(
+        &self,
+        path: PathBuf,
+        level_monitor: LevelMonitor,
+        cue_in: Option<Duration>,
+        cue_out: Option<Duration>,
+        gain: f32,
+    ) {
+        self.crossfade_range(path, level_monitor, cue_in, cue_out, gain, Duration::ZERO);
+    }
+
+    /// `play_range`, crossfading from whatever is playing over `fade`
+    /// instead of cutting. Zero `fade` (or nothing playing) cuts over.
+    pub fn crossfade_range(
+        &self,
+        path: PathBuf,
+        level_monitor: LevelMonitor,
+        cue_in: Option<Duration>,
+        cue_out: Option<Duration>,
+        gain: f32,
+        fade: Duration,
+    ) {
+        let _ = self.tx.send(AudioCmd::Play {
+            path,
+            level_monitor,
+            cue_in,
+            cue_out,
+            gain,
+            crossfade: fade,
+            fade_in: Duration::ZERO,
+        });
+    }
+
+    /// `play_range`, fading in from silence over `fade` (see
+    /// `AudioCmd::Play::fade_in`).
+    pub fn fade_in_range(
+        &self,
+        path: PathBuf,
+        level_monitor: LevelMonitor,
+        cue_in: Option<Duration>,
+        cue_out: Option<Duration>,
+        gain: f32,
+        fade: Duration,
+    ) {
+        let _ = self.tx.send(AudioCmd::Play {
+            path,
+            level_monitor,
+            cue_in,
+            cue_out,
+            gain,
+            crossfade: Duration::ZERO,
+            fade_in: fade,
+        });
+    }
+
+    /// Play `intro` with the next track (see `AudioCmd::QueueIntro`).
+    pub fn queue_intro(&self, intro: IntroCue) {
+        let _ = self.tx.send(AudioCmd::QueueIntro(intro));
+    }
+
+    /// Mix `path` over the program at linear `gain` (see `AudioCmd::PlayOver`).
+    pub fn play_over(&self, path: PathBuf, gain: f32) {
+        let _ = self.tx.send(AudioCmd::PlayOver { path, gain });
+    }
+
+    pub fn set_exit_point(&self, at: Option<Duration>) {
+        let _ = self.tx.send(AudioCmd::SetExitPoint(at));
+    }
+
+    pub fn fade_out(&self, fade: Duration) {
+        let _ = self.tx.send(AudioCmd::FadeOut(fade));
     }
 
     pub fn stop(&self) {
@@ -75,6 +197,22 @@ impl AudioHandle {
         let _ = self.tx.send(AudioCmd::SetDevice(device_name));
     }
 
+    pub fn set_output_rate(&self, sample_rate: Option<u32>) {
+        let _ = self.tx.send(AudioCmd::SetOutputRate(sample_rate));
+    }
+
+    pub fn set_buffer_ms(&self, buffer_ms: u32) {
+        let _ = self.tx.send(AudioCmd::SetBufferMs(buffer_ms));
+    }
+
+    pub fn set_agc(&self, config: Option<AgcConfig>) {
+        let _ = self.tx.send(AudioCmd::SetAgc(config));
+    }
+
+    pub fn set_channel_mode(&self, mode: ChannelMode) {
+        let _ = self.tx.send(AudioCmd::SetChannelMode(mode));
+    }
+
     pub fn shutdown(&self) {
         let _ = self.tx.send(AudioCmd::Shutdown);
     }
@@ -82,6 +220,14 @@ impl AudioHandle {
 
 // ── Runtime ──────────────────────────────────────────────────────────────────
 
+/// A decoded track waiting for its `IntroMode::Before` intro to end.
+struct HeldTrack {
+    intro: Sink,
+    source: PreparedSource,
+    gain: f32,
+    cue_in: Option<Duration>,
+}
+
 /// Spawn the audio runtime on a dedicated thread.
 ///
 /// `on_event` is called from the audio thread whenever a state change occurs.
@@ -115,21 +261,33 @@ fn audio_thread_loop<F>(
 {
     let mut player: Option<Player> = None;
     let mut device_name: Option<String> = initial_device;
+    let mut output_rate: Option<u32> = None;
+    let mut buffer_ms = 0;
+    let mut agc: Option<AgcConfig> = None;
+    let mut channel_mode = ChannelMode::default();
     let mut was_playing = false;
     let mut last_seek: Option<std::time::Instant> = None;
+    let mut stop_at: Option<Duration> = None;
+    let mut exit_at: Option<Duration> = None;
+    let mut pending_intro: Option<IntroCue> = None;
+    let mut held: Option<HeldTrack> = None;
 
     loop {
         // Poll for commands with a short timeout to detect track end
         match rx.recv_timeout(Duration::from_millis(50)) {
             Ok(cmd) => match cmd {
-                AudioCmd::Play { path, level_monitor } => {
+                AudioCmd::Play {
+                    path,
+                    level_monitor,
+                    cue_in,
+                    cue_out,
+                    gain,
+                    crossfade,
+                    fade_in,
+                } => {
                     // Lazy-init player on first use
                     if player.is_none() {
-                        let result = match &device_name {
-                            Some(name) => Player::new_with_device(name),
-                            None => Player::new(),
-                        };
-                        match result {
+                        match open_player(&device_name, buffer_ms, agc, channel_mode, &on_event) {
                             Ok(p) => player = Some(p),
                             Err(e) => {
                                 on_event(AudioEvent::PlayError(e));
@@ -138,15 +296,50 @@ fn audio_thread_loop<F>(
                         }
                     }
 
-                    let p = player.as_ref().unwrap();
+                    let p = player.as_mut().unwrap();
 
                     // Decode file ON the audio thread (no lock contention)
-                    match Player::prepare_file_with_level(&path, level_monitor.clone()) {
+                    let monitor = level_monitor.clone();
+                    match Player::prepare_file_with_level(&path, monitor, output_rate) {
                         Ok(prepared) => {
                             level_monitor.reset();
-                            p.stop_and_play_prepared(prepared);
-                            was_playing = true;
-                            on_event(AudioEvent::Playing);
+                            held = None;
+                            stop_at = cue_out;
+                            exit_at = None;
+                            let intro = pending_intro.take();
+                            let before = intro.as_ref().filter(|c| c.mode == IntroMode::Before);
+                            if let Some(cue) = before {
+                                // An intro that fails to play doesn't hold the track up
+                                if let Ok(intro) = p.play_intro(&cue.path, cue.gain_db) {
+                                    p.stop();
+                                    was_playing = false;
+                                    held = Some(HeldTrack {
+                                        intro,
+                                        source: prepared,
+                                        gain,
+                                        cue_in,
+                                    });
+                                    continue;
+                                }
+                            }
+                            match start_prepared(p, prepared, gain, crossfade, fade_in, cue_in) {
+                                Ok(seeked) => {
+                                    if seeked {
+                                        last_seek = Some(std::time::Instant::now());
+                                    }
+                                    if let Some(cue) = intro.filter(|c| c.mode == IntroMode::Over) {
+                                        let _ = p.play_intro_over(
+                                            &cue.path,
+                                            cue.gain_db,
+                                            cue.duck_volume,
+                                            cue.duck_ramp,
+                                        );
+                                    }
+                                    was_playing = true;
+                                    on_event(AudioEvent::Playing);
+                                }
+                                Err(e) => on_event(AudioEvent::PlayError(e)),
+                            }
                         }
                         Err(e) => {
                             on_event(AudioEvent::PlayError(e));
@@ -154,11 +347,42 @@ fn audio_thread_loop<F>(
                     }
                 }
 
+                AudioCmd::QueueIntro(intro) => {
+                    pending_intro = Some(intro);
+                }
+
+                AudioCmd::PlayOver { path, gain } => {
+                    if player.is_none() {
+                        match open_player(&device_name, buffer_ms, agc, channel_mode, &on_event) {
+                            Ok(p) => player = Some(p),
+                            Err(e) => {
+                                on_event(AudioEvent::PlayError(e));
+                                continue;
+                            }
+                        }
+                    }
+                    if let Err(e) = player.as_ref().unwrap().play_over(&path, gain) {
+                        on_event(AudioEvent::PlayError(e));
+                    }
+                }
+
+                AudioCmd::SetExitPoint(at) => {
+                    exit_at = at;
+                }
+
+                AudioCmd::FadeOut(fade) => {
+                    if let Some(p) = &player {
+                        p.fade_out(fade);
+                    }
+                }
+
                 AudioCmd::Stop => {
                     if let Some(p) = &player {
                         p.stop();
                     }
                     was_playing = false;
+                    exit_at = None;
+                    held = None;
                     on_event(AudioEvent::Stopped);
                 }
 
@@ -166,6 +390,9 @@ fn audio_thread_loop<F>(
                     if let Some(p) = &player {
                         p.pause();
                     }
+                    if let Some(h) = &held {
+                        h.intro.pause();
+                    }
                     on_event(AudioEvent::Paused);
                 }
 
@@ -173,6 +400,9 @@ fn audio_thread_loop<F>(
                     if let Some(p) = &player {
                         p.resume();
                     }
+                    if let Some(h) = &held {
+                        h.intro.play();
+                    }
                     on_event(AudioEvent::Resumed);
                 }
 
@@ -196,13 +426,11 @@ fn audio_thread_loop<F>(
                         p.stop();
                     }
                     was_playing = false;
+                    exit_at = None;
+                    held = None;
                     device_name = new_device;
                     // Create a new player on the requested device
-                    let result = match &device_name {
-                        Some(name) => Player::new_with_device(name),
-                        None => Player::new(),
-                    };
-                    match result {
+                    match open_player(&device_name, buffer_ms, agc, channel_mode, &on_event) {
                         Ok(p) => {
                             player = Some(p);
                         }
@@ -215,6 +443,32 @@ fn audio_thread_loop<F>(
                     }
                 }
 
+                AudioCmd::SetOutputRate(rate) => {
+                    output_rate = rate;
+                }
+
+                AudioCmd::SetBufferMs(ms) => {
+                    buffer_ms = ms;
+                    // Reopen on the next play, unless that would cut audio
+                    if player.as_ref().is_some_and(|p| p.is_empty()) && held.is_none() {
+                        player = None;
+                    }
+                }
+
+                AudioCmd::SetAgc(config) => {
+                    agc = config;
+                    if let Some(p) = &player {
+                        p.set_agc(config);
+                    }
+                }
+
+                AudioCmd::SetChannelMode(mode) => {
+                    channel_mode = mode;
+                    if let Some(p) = &player {
+                        p.set_channel_mode(mode);
+                    }
+                }
+
                 AudioCmd::Shutdown => {
                     if let Some(p) = &player {
                         p.stop();
@@ -224,6 +478,20 @@ fn audio_thread_loop<F>(
             },
 
             Err(mpsc::RecvTimeoutError::Timeout) => {
+                // Start a held track once its intro has played out
+                if let (Some(h), Some(p)) = (held.take_if(|h| h.intro.empty()), player.as_mut()) {
+                    let no_fade = Duration::ZERO;
+                    match start_prepared(p, h.source, h.gain, no_fade, no_fade, h.cue_in) {
+                        Ok(seeked) => {
+                            if seeked {
+                                last_seek = Some(std::time::Instant::now());
+                            }
+                            was_playing = true;
+                            on_event(AudioEvent::Playing);
+                        }
+                        Err(e) => on_event(AudioEvent::PlayError(e)),
+                    }
+                }
                 // Check for natural track end, but skip the check briefly after a seek
                 // because rodio's try_seek flushes the buffer, making is_empty() transiently true.
                 let seek_cooldown = last_seek
@@ -231,7 +499,15 @@ fn audio_thread_loop<F>(
                     .unwrap_or(false);
                 if was_playing && !seek_cooldown {
                     if let Some(p) = &player {
-                        if p.is_empty() {
+                        if exit_at.is_some_and(|at| p.position() >= at) {
+                            exit_at = None;
+                            on_event(AudioEvent::ExitPoint);
+                        }
+                        let past_cue_out = stop_at.is_some_and(|end| p.position() >= end);
+                        if past_cue_out {
+                            p.stop();
+                        }
+                        if past_cue_out || p.is_empty() {
                             was_playing = false;
                             on_event(AudioEvent::TrackFinished);
                         }
@@ -247,6 +523,48 @@ fn audio_thread_loop<F>(
     }
 }
 
+/// Start `prepared` at `gain`, crossfading over `crossfade` when something
+/// is playing, then seek to `cue_in`. Returns whether it seeked.
+fn start_prepared(
+    player: &mut Player,
+    prepared: PreparedSource,
+    gain: f32,
+    crossfade: Duration,
+    fade_in: Duration,
+    cue_in: Option<Duration>,
+) -> Result<bool, String> {
+    if crossfade.is_zero() || player.is_empty() {
+        player.set_volume(gain);
+        player.stop_and_fade_in_prepared(prepared, fade_in);
+    } else {
+        player.crossfade_to_prepared(prepared, gain, crossfade);
+    }
+    match cue_in.filter(|d| !d.is_zero()) {
+        Some(start) => player.try_seek(start).map(|()| true),
+        None => Ok(false),
+    }
+}
+
+/// Open a player on `device_name` (None = system default) with a device
+/// buffer of about `buffer_ms`, the bus AGC set to `agc` and its channels
+/// mapped by `channel_mode`, and report its output latency.
+fn open_player<F>(
+    device_name: &Option<String>,
+    buffer_ms: u32,
+    agc: Option<AgcConfig>,
+    channel_mode: ChannelMode,
+    on_event: &F,
+) -> Result<Player, String>
+where
+    F: Fn(AudioEvent),
+{
+    let player = Player::with_buffer(device_name.as_deref(), buffer_ms)?;
+    player.set_agc(agc);
+    player.set_channel_mode(channel_mode);
+    on_event(AudioEvent::OutputLatency(player.output_latency()));
+    Ok(player)
+}
+
 // ── Tests ────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
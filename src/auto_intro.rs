@@ -1,7 +1,12 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-/// Supported audio extensions for intro files.
-const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "flac", "ogg", "aac", "m4a"];
+/// Supported audio extensions (lowercase) for intro files, and for the other
+/// folder scans that pick audio out of a directory.
+pub(crate) const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "flac", "ogg", "aac", "m4a"];
 
 /// Search for artist intro files in the given folder.
 ///
@@ -73,6 +78,91 @@ pub fn has_intro(intros_folder: &Path, artist: &str) -> bool {
     !find_all_intros(intros_folder, artist).is_empty()
 }
 
+/// Which artists have had their intro played during one playback session
+/// (from `play` until stop). A fresh session is started for every play.
+#[derive(Debug, Default)]
+pub struct IntroSession {
+    /// Play each artist's intro at most once per session.
+    once_per_session: bool,
+    last_artist: Option<String>,
+    played: HashSet<String>,
+}
+
+impl IntroSession {
+    pub fn new(once_per_session: bool) -> Self {
+        Self {
+            once_per_session,
+            ..Self::default()
+        }
+    }
+
+    /// Whether `artist` should get its intro. Consecutive tracks by the same
+    /// artist never replay it; with `once_per_session`, neither does any
+    /// later track by an artist whose intro already played.
+    pub fn should_play(&self, artist: &str) -> bool {
+        let same_as_last = self
+            .last_artist
+            .as_ref()
+            .is_some_and(|a| a.eq_ignore_ascii_case(artist));
+        if same_as_last {
+            return false;
+        }
+        !(self.once_per_session && self.played.contains(&artist.to_lowercase()))
+    }
+
+    /// Note that `artist`'s intro just played.
+    pub fn record(&mut self, artist: &str) {
+        self.last_artist = Some(artist.to_string());
+        self.played.insert(artist.to_lowercase());
+    }
+}
+
+/// Where an auto-intro plays relative to its track.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IntroMode {
+    /// The intro plays in full, then the track starts.
+    #[default]
+    Before,
+    /// The track starts with the intro, ducked under it until it ends.
+    Over,
+}
+
+/// An auto-intro to play as a track starts (see `AppCore::take_intro`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntroCue {
+    pub path: PathBuf,
+    pub mode: IntroMode,
+    /// See `Engine::intro_gain_db`.
+    pub gain_db: f32,
+    /// Track volume under an `Over` intro, and the ramp back up after it.
+    pub duck_volume: f32,
+    pub duck_ramp: Duration,
+}
+
+impl fmt::Display for IntroMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntroMode::Before => write!(f, "before"),
+            IntroMode::Over => write!(f, "over"),
+        }
+    }
+}
+
+impl IntroMode {
+    /// Parse a mode from a string (case-insensitive).
+    pub fn from_str_loose(s: &str) -> Result<Self, String> {
+        match s.trim().to_lowercase().as_str() {
+            "before" => Ok(IntroMode::Before),
+            "over" => Ok(IntroMode::Over),
+            _ => Err(format!(
+                "Unknown intro mode '{}'. Expected: before, over",
+                s
+            )),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -187,4 +277,32 @@ mod tests {
     fn find_intro_nonexistent_folder() {
         assert!(find_intro(Path::new("Z:\\nonexistent_folder_xyz"), "Adele").is_none());
     }
+
+    #[test]
+    fn intro_session_skips_consecutive_same_artist() {
+        let mut session = IntroSession::new(false);
+        assert!(session.should_play("Adele"));
+        session.record("Adele");
+        assert!(!session.should_play("adele"));
+        assert!(session.should_play("Beatles"));
+        session.record("Beatles");
+        // Without once-per-session, a later Adele gets the intro again.
+        assert!(session.should_play("Adele"));
+    }
+
+    #[test]
+    fn intro_session_once_per_session_skips_repeat_artist() {
+        let mut session = IntroSession::new(true);
+        assert!(session.should_play("Adele"));
+        session.record("Adele");
+        assert!(session.should_play("Beatles"));
+        session.record("Beatles");
+        assert!(!session.should_play("Adele"));
+        assert!(!session.should_play("ADELE"));
+        assert!(session.should_play("Coldplay"));
+
+        // A new session (play after stop) starts clean.
+        let fresh = IntroSession::new(true);
+        assert!(fresh.should_play("Adele"));
+    }
 }
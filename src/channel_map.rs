@@ -0,0 +1,290 @@
+//! Output channel mapping for the program bus.
+//!
+//! `ChannelMapSource` rewrites each stereo frame of the bus mix before it
+//! reaches the output: unchanged, summed to mono (for AM and other mono
+//! transmitters), or with left and right swapped (for miswired studios).
+//! Sources that aren't stereo pass through untouched.
+
+use rodio::Source;
+use rodio::source::SeekError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::Duration;
+
+/// Gain applied to L+R in `MonoSum`: the average of the two channels
+/// (-6 dB), so the sum of two full-scale channels never exceeds full scale.
+pub const MONO_SUM_GAIN: f32 = 0.5;
+
+/// How the two output channels are fed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelMode {
+    /// Left to left, right to right.
+    #[default]
+    Stereo,
+    /// The average of L and R on both channels.
+    MonoSum,
+    /// Left and right exchanged.
+    #[serde(rename = "swap_lr")]
+    SwapLR,
+}
+
+impl fmt::Display for ChannelMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChannelMode::Stereo => write!(f, "stereo"),
+            ChannelMode::MonoSum => write!(f, "mono_sum"),
+            ChannelMode::SwapLR => write!(f, "swap_lr"),
+        }
+    }
+}
+
+impl ChannelMode {
+    /// Parse a mode from a string (case-insensitive).
+    pub fn from_str_loose(s: &str) -> Result<Self, String> {
+        match s.trim().to_lowercase().replace(['-', ' '], "_").as_str() {
+            "stereo" => Ok(ChannelMode::Stereo),
+            "mono_sum" | "mono" => Ok(ChannelMode::MonoSum),
+            "swap_lr" | "swap" => Ok(ChannelMode::SwapLR),
+            _ => Err(format!(
+                "Unknown channel mode '{}'. Expected: stereo, mono_sum, swap_lr",
+                s
+            )),
+        }
+    }
+
+    /// Map one stereo frame.
+    pub fn map_frame(self, left: f32, right: f32) -> (f32, f32) {
+        match self {
+            ChannelMode::Stereo => (left, right),
+            ChannelMode::MonoSum => {
+                let mono = (left + right) * MONO_SUM_GAIN;
+                (mono, mono)
+            }
+            ChannelMode::SwapLR => (right, left),
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            ChannelMode::Stereo => 0,
+            ChannelMode::MonoSum => 1,
+            ChannelMode::SwapLR => 2,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => ChannelMode::MonoSum,
+            2 => ChannelMode::SwapLR,
+            _ => ChannelMode::Stereo,
+        }
+    }
+}
+
+/// Shared handle to a `ChannelMapSource`'s mode, so the mapping can change
+/// while the source plays.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelMapControl(Arc<AtomicU8>);
+
+impl ChannelMapControl {
+    pub fn new(mode: ChannelMode) -> Self {
+        ChannelMapControl(Arc::new(AtomicU8::new(mode.to_u8())))
+    }
+
+    /// Replace the mode; the source picks it up at its next frame.
+    pub fn set(&self, mode: ChannelMode) {
+        self.0.store(mode.to_u8(), Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> ChannelMode {
+        ChannelMode::from_u8(self.0.load(Ordering::Relaxed))
+    }
+}
+
+/// A Source wrapper that applies a `ChannelMode` to interleaved stereo.
+pub struct ChannelMapSource<S> {
+    inner: S,
+    control: ChannelMapControl,
+    /// Right sample of the current frame, waiting to be yielded.
+    pending: Option<f32>,
+}
+
+impl<S> ChannelMapSource<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, mode: ChannelMode) -> Self {
+        Self::with_control(source, ChannelMapControl::new(mode))
+    }
+
+    /// A mapping stage whose mode follows `control`.
+    pub fn with_control(source: S, control: ChannelMapControl) -> Self {
+        ChannelMapSource {
+            inner: source,
+            control,
+            pending: None,
+        }
+    }
+}
+
+impl<S> Iterator for ChannelMapSource<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if let Some(right) = self.pending.take() {
+            return Some(right);
+        }
+        let left = self.inner.next()?;
+        let mode = self.control.get();
+        if mode == ChannelMode::Stereo || self.inner.channels() != 2 {
+            return Some(left);
+        }
+        // A trailing half frame has nothing to pair with; pass it through
+        let Some(right) = self.inner.next() else {
+            return Some(left);
+        };
+        let (left, right) = mode.map_frame(left, right);
+        self.pending = Some(right);
+        Some(left)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (low, high) = self.inner.size_hint();
+        let extra = usize::from(self.pending.is_some());
+        (low + extra, high.map(|h| h + extra))
+    }
+}
+
+impl<S> Source for ChannelMapSource<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        let extra = usize::from(self.pending.is_some());
+        self.inner.current_frame_len().map(|len| len + extra)
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.pending = None;
+        self.inner.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Interleaved samples at 1 kHz with the given channel count.
+    struct Samples(std::vec::IntoIter<f32>, u16);
+
+    impl Iterator for Samples {
+        type Item = f32;
+        fn next(&mut self) -> Option<f32> {
+            self.0.next()
+        }
+    }
+
+    impl Source for Samples {
+        fn current_frame_len(&self) -> Option<usize> {
+            None
+        }
+        fn channels(&self) -> u16 {
+            self.1
+        }
+        fn sample_rate(&self) -> u32 {
+            1000
+        }
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+    }
+
+    fn mapped(samples: &[f32], channels: u16, mode: ChannelMode) -> Vec<f32> {
+        let source = Samples(samples.to_vec().into_iter(), channels);
+        ChannelMapSource::new(source, mode).collect()
+    }
+
+    #[test]
+    fn stereo_passes_through_unchanged() {
+        assert_eq!(ChannelMode::Stereo.map_frame(0.25, -0.5), (0.25, -0.5));
+        let input = [0.1, 0.2, 0.3, 0.4];
+        assert_eq!(mapped(&input, 2, ChannelMode::Stereo), input);
+    }
+
+    #[test]
+    fn mono_sum_averages_channels() {
+        let (l, r) = ChannelMode::MonoSum.map_frame(0.5, 0.3);
+        assert_eq!(l, r);
+        assert!((l - 0.4).abs() < 1e-6);
+
+        // Full-scale audio on both channels stays at full scale
+        assert_eq!(ChannelMode::MonoSum.map_frame(1.0, 1.0), (1.0, 1.0));
+        assert_eq!(ChannelMode::MonoSum.map_frame(-1.0, -1.0), (-1.0, -1.0));
+
+        // A left-only source reaches both channels
+        let out = mapped(&[0.6, 0.0, 0.2, 0.0], 2, ChannelMode::MonoSum);
+        let expected = [0.6, 0.6, 0.2, 0.2].map(|s| s * MONO_SUM_GAIN);
+        for (got, want) in out.iter().zip(expected) {
+            assert!((got - want).abs() < 1e-6, "{:?}", out);
+        }
+    }
+
+    #[test]
+    fn swap_exchanges_left_and_right() {
+        assert_eq!(ChannelMode::SwapLR.map_frame(0.1, 0.9), (0.9, 0.1));
+        let out = mapped(&[0.1, 0.9, 0.2, 0.8], 2, ChannelMode::SwapLR);
+        assert_eq!(out, [0.9, 0.1, 0.8, 0.2]);
+    }
+
+    #[test]
+    fn non_stereo_sources_are_untouched() {
+        let input = [0.1, 0.2, 0.3];
+        assert_eq!(mapped(&input, 1, ChannelMode::SwapLR), input);
+        assert_eq!(mapped(&input, 1, ChannelMode::MonoSum), input);
+    }
+
+    #[test]
+    fn control_changes_the_mode_mid_stream() {
+        let control = ChannelMapControl::default();
+        let source = Samples(vec![0.1, 0.9, 0.2, 0.8].into_iter(), 2);
+        let mut map = ChannelMapSource::with_control(source, control.clone());
+        assert_eq!((map.next(), map.next()), (Some(0.1), Some(0.9)));
+        control.set(ChannelMode::SwapLR);
+        assert_eq!(control.get(), ChannelMode::SwapLR);
+        assert_eq!((map.next(), map.next()), (Some(0.8), Some(0.2)));
+    }
+
+    #[test]
+    fn channel_mode_parses_and_round_trips() {
+        assert_eq!(
+            ChannelMode::from_str_loose("Mono Sum").unwrap(),
+            ChannelMode::MonoSum
+        );
+        assert_eq!(
+            ChannelMode::from_str_loose("swap-lr").unwrap(),
+            ChannelMode::SwapLR
+        );
+        assert!(ChannelMode::from_str_loose("quad").is_err());
+        let json = serde_json::to_string(&ChannelMode::SwapLR).unwrap();
+        assert_eq!(json, "\"swap_lr\"");
+        assert_eq!(ChannelMode::SwapLR.to_string(), "swap_lr");
+    }
+}
@@ -0,0 +1,56 @@
+//! Time source for time-dependent logic (ad dayparting, hour boundaries).
+//!
+//! Production code uses `RealClock`; tests inject a `FixedClock` so day/hour
+//! behavior can be checked without sleeping or depending on wall time.
+
+use chrono::{DateTime, Local, Timelike};
+
+/// Source of the current local time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Local>;
+
+    /// Current day name (e.g. "Monday").
+    fn day_name(&self) -> String {
+        self.now().format("%A").to_string()
+    }
+
+    /// Current hour (0-23).
+    fn hour(&self) -> u8 {
+        self.now().hour() as u8
+    }
+}
+
+/// The system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealClock;
+
+impl Clock for RealClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+/// A clock stopped at one instant.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Local>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Local> {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn fixed_clock_reports_day_and_hour() {
+        // 2026-10-12 is a Monday
+        let clock = FixedClock(Local.with_ymd_and_hms(2026, 10, 12, 9, 30, 0).unwrap());
+        assert_eq!(clock.day_name(), "Monday");
+        assert_eq!(clock.hour(), 9);
+        assert_eq!(clock.now(), clock.now());
+    }
+}
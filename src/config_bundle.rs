@@ -0,0 +1,438 @@
+//! Configuration bundle — a zip of the engine state, the ad play log and a
+//! manifest of referenced audio, for support and for moving a station to a
+//! new machine.
+//!
+//! Audio is listed in the manifest but only copied into the zip (under
+//! `audio/`) when explicitly requested, since ad and event libraries can be
+//! large. Importing reads back only the engine state.
+
+use crate::ad_logger::AdPlayLogger;
+use crate::engine::Engine;
+use crate::scheduler::ScheduleMode;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+pub const STATE_ENTRY: &str = "signalflow_state.json";
+pub const MANIFEST_ENTRY: &str = "manifest.json";
+pub const AD_PLAYS_ENTRY: &str = "ad_plays.json";
+pub const AD_FAILURES_ENTRY: &str = "ad_failures.json";
+
+/// One audio file or folder referenced by the configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioReference {
+    /// "ad", "station_id", "schedule" or "cart".
+    pub kind: String,
+    /// Ad name, event label or cart label.
+    pub name: String,
+    pub path: PathBuf,
+    pub exists: bool,
+    /// Entry name inside the zip when the audio was bundled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundled_as: Option<String>,
+}
+
+/// `manifest.json` inside the bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub created_at: String,
+    pub include_audio: bool,
+    /// Zip entries besides the manifest itself.
+    pub entries: Vec<String>,
+    pub audio: Vec<AudioReference>,
+}
+
+/// The bundle contents taken from the engine: its serialized state and the
+/// audio it refers to. Capturing this is cheap, so it can be done while the
+/// engine is locked and the zip written afterwards without it.
+#[derive(Debug, Clone)]
+pub struct BundleSnapshot {
+    state: Vec<u8>,
+    audio: Vec<AudioReference>,
+}
+
+impl BundleSnapshot {
+    pub fn of(engine: &Engine) -> Result<Self, String> {
+        let state =
+            serde_json::to_vec_pretty(engine).map_err(|e| format!("Serialize error: {}", e))?;
+        Ok(BundleSnapshot {
+            state,
+            audio: referenced_paths(engine),
+        })
+    }
+}
+
+/// Audio paths the engine refers to: ads, the station ID, schedule events
+/// (rotator folders and fallback files included) and carts.
+pub fn audio_references(engine: &Engine) -> Vec<AudioReference> {
+    let mut refs = referenced_paths(engine);
+    for reference in &mut refs {
+        reference.exists = reference.path.exists();
+    }
+    refs
+}
+
+/// `audio_references` without touching the filesystem (`exists` is false).
+fn referenced_paths(engine: &Engine) -> Vec<AudioReference> {
+    let reference = |kind: &str, name: &str, path: &Path| AudioReference {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        path: path.to_path_buf(),
+        exists: false,
+        bundled_as: None,
+    };
+    let mut refs: Vec<AudioReference> = engine
+        .ads
+        .iter()
+        .map(|ad| reference("ad", &ad.name, &ad.mp3_file))
+        .collect();
+    if let Some(sid) = &engine.ad_inserter.station_id_file {
+        refs.push(reference("station_id", "Station ID", sid));
+    }
+    for event in &engine.schedule.events {
+        // `activate` events name a playlist, not a file
+        if event.mode == ScheduleMode::Activate {
+            continue;
+        }
+        let name = event
+            .label
+            .clone()
+            .unwrap_or_else(|| format!("Event {}", event.id));
+        refs.push(reference("schedule", &name, &event.file));
+        if let Some(fallback) = &event.fallback_file {
+            let fallback_name = format!("{} (fallback)", name);
+            refs.push(reference("schedule", &fallback_name, fallback));
+        }
+    }
+    for cart in &engine.carts {
+        refs.push(reference("cart", &cart.label, &cart.path));
+    }
+    refs
+}
+
+/// Write the bundle to `out`. The ad play log is read from `ad_log_dir`
+/// (missing log files are left out). With `include_audio`, every referenced
+/// file that exists is streamed into `audio/`; folders are only listed.
+/// Returns the manifest that was written.
+pub fn write_bundle(
+    snapshot: &BundleSnapshot,
+    ad_log_dir: &Path,
+    out: &Path,
+    include_audio: bool,
+) -> Result<BundleManifest, String> {
+    let file =
+        File::create(out).map_err(|e| format!("Cannot create '{}': {}", out.display(), e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut entries = Vec::new();
+
+    add_entry(&mut zip, STATE_ENTRY, &snapshot.state, options)?;
+    entries.push(STATE_ENTRY.to_string());
+
+    let logger = AdPlayLogger::new(ad_log_dir);
+    for (name, path) in [
+        (AD_PLAYS_ENTRY, logger.plays_path()),
+        (AD_FAILURES_ENTRY, logger.failures_path()),
+    ] {
+        if let Ok(mut log) = File::open(path) {
+            add_file_entry(&mut zip, name, &mut log, options)?;
+            entries.push(name.to_string());
+        }
+    }
+
+    let mut audio = snapshot.audio.clone();
+    for reference in &mut audio {
+        reference.exists = reference.path.exists();
+    }
+    if include_audio {
+        for (i, reference) in audio.iter_mut().enumerate() {
+            if !reference.path.is_file() {
+                continue;
+            }
+            let mut source = File::open(&reference.path)
+                .map_err(|e| format!("Cannot read '{}': {}", reference.path.display(), e))?;
+            let file_name = reference
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            // Index prefix keeps same-named files from different folders apart
+            let entry = format!("audio/{:03}_{}", i + 1, file_name);
+            add_file_entry(&mut zip, &entry, &mut source, options)?;
+            entries.push(entry.clone());
+            reference.bundled_as = Some(entry);
+        }
+    }
+
+    let manifest = BundleManifest {
+        created_at: Local::now().to_rfc3339(),
+        include_audio,
+        entries,
+        audio,
+    };
+    let json =
+        serde_json::to_vec_pretty(&manifest).map_err(|e| format!("Serialize error: {}", e))?;
+    add_entry(&mut zip, MANIFEST_ENTRY, &json, options)?;
+    zip.finish()
+        .map_err(|e| format!("Cannot finish '{}': {}", out.display(), e))?;
+    Ok(manifest)
+}
+
+/// Read and validate the engine state stored in a bundle, migrating it
+/// forward like a state file if it was exported by an older build.
+pub fn read_bundle(path: &Path) -> Result<Engine, String> {
+    let file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path.display(), e))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| format!("'{}' is not a valid bundle: {}", path.display(), e))?;
+    let mut json = String::new();
+    archive
+        .by_name(STATE_ENTRY)
+        .map_err(|_| format!("Bundle '{}' has no {}", path.display(), STATE_ENTRY))?
+        .read_to_string(&mut json)
+        .map_err(|e| format!("Cannot read {} from bundle: {}", STATE_ENTRY, e))?;
+    Engine::parse_state(&json).map_err(|e| format!("Corrupt state in bundle: {}", e))
+}
+
+/// What an import brought in and what it left out.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ImportSummary {
+    pub playlists_added: Vec<String>,
+    /// Incoming playlists whose name was already taken (merge only).
+    pub playlists_skipped: Vec<String>,
+    pub ads_added: Vec<String>,
+    pub ads_skipped: Vec<String>,
+    pub events_added: usize,
+    pub events_skipped: usize,
+}
+
+impl ImportSummary {
+    /// Summary for replacing the whole state with `incoming`.
+    pub fn replacing(incoming: &Engine) -> Self {
+        ImportSummary {
+            playlists_added: incoming.playlists.iter().map(|p| p.name.clone()).collect(),
+            ads_added: incoming.ads.iter().map(|a| a.name.clone()).collect(),
+            events_added: incoming.schedule.len(),
+            ..ImportSummary::default()
+        }
+    }
+}
+
+/// Add `incoming`'s playlists, ads and schedule events to `target`, skipping
+/// playlists and ads whose name is already taken (case-insensitive) and
+/// events that duplicate an existing one's time and label or file. Added
+/// items get fresh IDs but keep their own settings (a playlist's tracks,
+/// mode and lock); all other `target` settings are left alone.
+pub fn merge_into(target: &mut Engine, incoming: Engine) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+
+    for playlist in incoming.playlists {
+        if target.find_playlist(&playlist.name).is_some() {
+            summary.playlists_skipped.push(playlist.name);
+            continue;
+        }
+        target.create_playlist(playlist.name.clone());
+        if let Some(added) = target.find_playlist_mut(&playlist.name) {
+            added.tracks = playlist.tracks;
+            added.source_path = playlist.source_path;
+            added.auto_advance = playlist.auto_advance;
+            added.locked = playlist.locked;
+        }
+        summary.playlists_added.push(playlist.name);
+    }
+
+    for ad in incoming.ads {
+        let taken = target
+            .ads
+            .iter()
+            .any(|a| a.name.eq_ignore_ascii_case(&ad.name));
+        if taken {
+            summary.ads_skipped.push(ad.name);
+        } else {
+            summary.ads_added.push(ad.name.clone());
+            target.add_ad(ad);
+        }
+    }
+
+    for event in incoming.schedule.events {
+        let duplicate = target.schedule.events.iter().any(|e| {
+            e.time == event.time
+                && ((e.label.is_some() && e.label == event.label) || e.file == event.file)
+        });
+        if duplicate {
+            summary.events_skipped += 1;
+            continue;
+        }
+        let id = target.schedule.add_event(
+            event.time,
+            event.mode,
+            event.file,
+            event.priority,
+            event.label,
+            event.days,
+        );
+        if let Some(added) = target.schedule.find_event_mut(id) {
+            added.enabled = event.enabled;
+            added.rotator = event.rotator;
+            added.autostart = event.autostart;
+            added.start_date = event.start_date;
+            added.end_date = event.end_date;
+            added.fallback_file = event.fallback_file;
+        }
+        summary.events_added += 1;
+    }
+    summary
+}
+
+fn add_entry(
+    zip: &mut ZipWriter<File>,
+    name: &str,
+    data: &[u8],
+    options: SimpleFileOptions,
+) -> Result<(), String> {
+    zip.start_file(name, options)
+        .map_err(|e| format!("Cannot add '{}' to bundle: {}", name, e))?;
+    zip.write_all(data)
+        .map_err(|e| format!("Cannot write '{}' to bundle: {}", name, e))
+}
+
+/// Like `add_entry`, but copies from `source` in chunks so large audio
+/// files are never held in memory whole.
+fn add_file_entry(
+    zip: &mut ZipWriter<File>,
+    name: &str,
+    source: &mut File,
+    options: SimpleFileOptions,
+) -> Result<(), String> {
+    zip.start_file(name, options)
+        .map_err(|e| format!("Cannot add '{}' to bundle: {}", name, e))?;
+    io::copy(source, zip)
+        .map(|_| ())
+        .map_err(|e| format!("Cannot write '{}' to bundle: {}", name, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ad_scheduler::AdConfig;
+    use crate::scheduler::{Priority, parse_time};
+    use std::fs;
+
+    fn entry_names(path: &Path) -> Vec<String> {
+        let archive = ZipArchive::new(File::open(path).unwrap()).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        names
+    }
+
+    fn read_manifest(path: &Path) -> BundleManifest {
+        let mut archive = ZipArchive::new(File::open(path).unwrap()).unwrap();
+        let mut json = String::new();
+        archive
+            .by_name(MANIFEST_ENTRY)
+            .unwrap()
+            .read_to_string(&mut json)
+            .unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn snapshot(engine: &Engine) -> BundleSnapshot {
+        BundleSnapshot::of(engine).unwrap()
+    }
+
+    fn engine_with_audio(dir: &Path) -> Engine {
+        let ad_file = dir.join("spring_sale.mp3");
+        fs::write(&ad_file, b"ad audio").unwrap();
+        let mut engine = Engine::new();
+        engine
+            .ads
+            .push(AdConfig::new("Spring Sale".into(), ad_file));
+        engine.schedule.add_event(
+            parse_time("08:00").unwrap(),
+            ScheduleMode::Stop,
+            dir.join("news.mp3"),
+            Priority::HIGH,
+            Some("News".into()),
+            vec![],
+        );
+        engine
+    }
+
+    #[test]
+    fn bundle_contains_state_log_and_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let engine = engine_with_audio(dir.path());
+        let logger = AdPlayLogger::new(dir.path());
+        logger.log_play_at("Spring Sale", "01-15-26", 9);
+        let out = dir.path().join("bundle.zip");
+
+        let manifest = write_bundle(&snapshot(&engine), dir.path(), &out, false).unwrap();
+        assert_eq!(
+            entry_names(&out),
+            [AD_PLAYS_ENTRY, MANIFEST_ENTRY, STATE_ENTRY]
+        );
+        assert_eq!(manifest.entries, [STATE_ENTRY, AD_PLAYS_ENTRY]);
+
+        let listed = read_manifest(&out);
+        let paths: Vec<(&str, &Path)> = listed
+            .audio
+            .iter()
+            .map(|a| (a.kind.as_str(), a.path.as_path()))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("ad", dir.path().join("spring_sale.mp3").as_path()),
+                ("schedule", dir.path().join("news.mp3").as_path()),
+            ]
+        );
+        assert!(listed.audio[0].exists);
+        assert!(!listed.audio[1].exists);
+        assert!(listed.audio.iter().all(|a| a.bundled_as.is_none()));
+    }
+
+    #[test]
+    fn include_audio_bundles_existing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let engine = engine_with_audio(dir.path());
+        let out = dir.path().join("bundle.zip");
+
+        let manifest = write_bundle(&snapshot(&engine), dir.path(), &out, true).unwrap();
+        assert_eq!(
+            entry_names(&out),
+            ["audio/001_spring_sale.mp3", MANIFEST_ENTRY, STATE_ENTRY]
+        );
+        assert_eq!(
+            manifest.audio[0].bundled_as.as_deref(),
+            Some("audio/001_spring_sale.mp3")
+        );
+        // Missing schedule file is listed, not bundled
+        assert!(manifest.audio[1].bundled_as.is_none());
+    }
+
+    #[test]
+    fn merged_playlists_round_trip_mode_and_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut engine = Engine::new();
+        engine.create_playlist("Live".into());
+        engine.create_playlist("Auto".into());
+        let live = engine.find_playlist_mut("Live").unwrap();
+        live.auto_advance = false;
+        live.locked = true;
+        let out = dir.path().join("bundle.zip");
+        write_bundle(&snapshot(&engine), dir.path(), &out, false).unwrap();
+
+        let mut target = Engine::new();
+        let summary = merge_into(&mut target, read_bundle(&out).unwrap());
+        assert_eq!(summary.playlists_added, ["Live", "Auto"]);
+        let live = target.find_playlist("Live").unwrap();
+        assert!(!live.auto_advance);
+        assert!(live.locked);
+        let auto = target.find_playlist("Auto").unwrap();
+        assert!(auto.auto_advance);
+        assert!(!auto.locked);
+    }
+}
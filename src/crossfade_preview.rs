@@ -0,0 +1,171 @@
+//! Crossfade preview — renders the join between two tracks offline so it can
+//! be auditioned in the editor before airing.
+//!
+//! Gains come from `player::crossfade_gains`, the same curve the transport
+//! uses when fading between tracks.
+
+use crate::player::{crossfade_gains, decode_with_m4a_fallback, CrossfadeCurve};
+use rodio::Source;
+use rodio::source::UniformSourceIterator;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+
+/// Seconds of each track played around the overlap so the join has context.
+pub const PREVIEW_CONTEXT_SECS: f32 = 5.0;
+
+/// Rendered preview audio: interleaved f32 samples.
+#[derive(Debug, Clone)]
+pub struct CrossfadePreview {
+    pub samples: Vec<f32>,
+    pub channels: u16,
+    pub sample_rate: u32,
+}
+
+impl CrossfadePreview {
+    /// Number of frames (samples per channel).
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+
+    pub fn duration_secs(&self) -> f64 {
+        self.frames() as f64 / self.sample_rate.max(1) as f64
+    }
+
+    /// Write the preview as a 16-bit PCM WAV file.
+    pub fn write_wav(&self, path: &Path) -> Result<(), String> {
+        let block_align = self.channels as u32 * 2;
+        let data_len = (self.samples.len() * 2) as u32;
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        bytes.extend_from_slice(&self.channels.to_le_bytes());
+        bytes.extend_from_slice(&self.sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(self.sample_rate * block_align).to_le_bytes());
+        bytes.extend_from_slice(&(block_align as u16).to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for s in &self.samples {
+            let v = (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        fs::write(path, bytes)
+            .map_err(|e| format!("Cannot write preview '{}': {}", path.display(), e))
+    }
+}
+
+/// Render the last `crossfade_secs + context_secs` of `path_a` overlapped
+/// with the first `crossfade_secs + context_secs` of `path_b`.
+///
+/// The result is A's tail, then the overlap mixed with `curve`, then B's
+/// head: `tail_a + head_b - overlap` frames. `path_b` is converted to
+/// `path_a`'s channel count and sample rate. The overlap shrinks if either
+/// track is shorter than the crossfade.
+pub fn render_crossfade(
+    path_a: &Path,
+    path_b: &Path,
+    crossfade_secs: f32,
+    curve: CrossfadeCurve,
+    context_secs: f32,
+) -> Result<CrossfadePreview, String> {
+    if !crossfade_secs.is_finite() || crossfade_secs <= 0.0 {
+        return Err("Crossfade length must be greater than 0".to_string());
+    }
+    let source_a = decode_with_m4a_fallback(path_a)?;
+    let channels = source_a.channels().max(1);
+    let sample_rate = source_a.sample_rate();
+    let ch = channels as usize;
+
+    let segment_secs = crossfade_secs + context_secs.max(0.0);
+    let segment_frames = (segment_secs * sample_rate as f32).round() as usize;
+    let crossfade_frames = (crossfade_secs * sample_rate as f32).round() as usize;
+
+    // Keep only A's tail; avoids buffering the whole track.
+    let mut tail_a: VecDeque<f32> = VecDeque::with_capacity(segment_frames * ch);
+    for sample in source_a.convert_samples::<f32>() {
+        if tail_a.len() == segment_frames * ch {
+            tail_a.pop_front();
+        }
+        tail_a.push_back(sample);
+    }
+    let tail_a: Vec<f32> = tail_a.into();
+
+    let source_b = decode_with_m4a_fallback(path_b)?;
+    let head_b: Vec<f32> = UniformSourceIterator::<_, f32>::new(
+        source_b.convert_samples::<f32>(),
+        channels,
+        sample_rate,
+    )
+    .take(segment_frames * ch)
+    .collect();
+
+    let frames_a = tail_a.len() / ch;
+    let frames_b = head_b.len() / ch;
+    let overlap = crossfade_frames.min(frames_a).min(frames_b);
+    let pre = frames_a - overlap;
+
+    let mut samples = Vec::with_capacity((frames_a + frames_b - overlap) * ch);
+    samples.extend_from_slice(&tail_a[..pre * ch]);
+    for frame in 0..overlap {
+        let (gain_out, gain_in) = crossfade_gains(frame as f32 / overlap as f32, curve);
+        for c in 0..ch {
+            let a = tail_a[(pre + frame) * ch + c];
+            let b = head_b[frame * ch + c];
+            samples.push(a * gain_out + b * gain_in);
+        }
+    }
+    samples.extend_from_slice(&head_b[overlap * ch..frames_b * ch]);
+
+    Ok(CrossfadePreview {
+        samples,
+        channels,
+        sample_rate,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_audio::write_wav;
+
+    #[test]
+    fn rendered_length_is_tail_plus_head_minus_overlap() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.wav");
+        let b = dir.path().join("b.wav");
+        // 8000 Hz mono: A is 10 s, B is 6 s
+        write_wav(&a, &vec![8000; 80_000], 8000);
+        write_wav(&b, &vec![-8000; 48_000], 8000);
+
+        let preview = render_crossfade(&a, &b, 2.0, CrossfadeCurve::Linear, 1.0).unwrap();
+        // Tail of A (3 s) + head of B (3 s) - overlap (2 s) = 4 s
+        assert_eq!(preview.sample_rate, 8000);
+        assert_eq!(preview.channels, 1);
+        assert_eq!(preview.frames(), 32_000);
+        assert!((preview.duration_secs() - 4.0).abs() < 1e-9);
+
+        // First second is pure A, last second pure B, mid-fade cancels out
+        let level = 8000.0 / i16::MAX as f32;
+        assert!((preview.samples[0] - level).abs() < 1e-3);
+        assert!((preview.samples[31_999] + level).abs() < 1e-3);
+        assert!(preview.samples[16_000].abs() < 1e-3);
+    }
+
+    #[test]
+    fn overlap_shrinks_to_shorter_track() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.wav");
+        let b = dir.path().join("b.wav");
+        write_wav(&a, &vec![1000; 80_000], 8000);
+        write_wav(&b, &vec![1000; 4_000], 8000);
+
+        let preview = render_crossfade(&a, &b, 2.0, CrossfadeCurve::EqualPower, 0.0).unwrap();
+        // A contributes 2 s, B only 0.5 s, which is all overlap
+        assert_eq!(preview.frames(), 16_000);
+        assert!(render_crossfade(&a, &b, 0.0, CrossfadeCurve::Linear, 0.0).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+//! CUE sheet parsing — turns one long recording plus its `.cue` file into
+//! a list of virtual tracks that share the same audio file.
+
+use crate::track::Track;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// CUE timestamps are `MM:SS:FF` with 75 frames per second.
+const FRAMES_PER_SEC: u64 = 75;
+
+/// One `TRACK` entry of a cue sheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CueTrack {
+    pub number: u32,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    /// Offset of `INDEX 01` into the audio file.
+    pub start: Duration,
+}
+
+/// A parsed cue sheet referencing a single audio file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CueSheet {
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    /// Audio file, resolved relative to the cue sheet's folder.
+    pub file: PathBuf,
+    pub tracks: Vec<CueTrack>,
+}
+
+/// Parse a `MM:SS:FF` cue timestamp.
+pub fn parse_cue_time(s: &str) -> Result<Duration, String> {
+    let parts: Vec<&str> = s.trim().split(':').collect();
+    if parts.len() != 3 {
+        return Err(format!("Invalid cue time '{}': expected MM:SS:FF", s));
+    }
+    let num = |p: &str| {
+        p.parse::<u64>()
+            .map_err(|_| format!("Invalid cue time '{}'", s))
+    };
+    let (mins, secs, frames) = (num(parts[0])?, num(parts[1])?, num(parts[2])?);
+    if secs >= 60 || frames >= FRAMES_PER_SEC {
+        return Err(format!("Invalid cue time '{}'", s));
+    }
+    let total_frames = (mins * 60 + secs) * FRAMES_PER_SEC + frames;
+    Ok(Duration::from_nanos(
+        total_frames * 1_000_000_000 / FRAMES_PER_SEC,
+    ))
+}
+
+/// A `TRACK` block still being read; `INDEX 01` may not have appeared yet.
+struct PendingTrack {
+    number: u32,
+    title: Option<String>,
+    performer: Option<String>,
+    start: Option<Duration>,
+}
+
+impl PendingTrack {
+    fn finish(self) -> Result<CueTrack, String> {
+        let start = self
+            .start
+            .ok_or_else(|| format!("Track {} has no INDEX 01", self.number))?;
+        Ok(CueTrack {
+            number: self.number,
+            title: self.title,
+            performer: self.performer,
+            start,
+        })
+    }
+}
+
+/// Parse cue sheet text. `base_dir` resolves a relative `FILE` path.
+/// Only single-file cue sheets are supported.
+pub fn parse_cue(content: &str, base_dir: &Path) -> Result<CueSheet, String> {
+    let mut title = None;
+    let mut performer = None;
+    let mut file: Option<PathBuf> = None;
+    let mut tracks: Vec<CueTrack> = Vec::new();
+    let mut current: Option<PendingTrack> = None;
+
+    for raw_line in content.lines() {
+        let line = raw_line.trim();
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        match keyword.to_ascii_uppercase().as_str() {
+            "FILE" => {
+                if file.is_some() {
+                    return Err("Multi-file cue sheets are not supported".to_string());
+                }
+                let name = quoted_or_first_word(rest);
+                let path = Path::new(&name);
+                file = Some(if path.is_absolute() {
+                    path.to_path_buf()
+                } else {
+                    base_dir.join(path)
+                });
+            }
+            "TRACK" => {
+                if let Some(pending) = current.take() {
+                    tracks.push(pending.finish()?);
+                }
+                let number = rest
+                    .split_whitespace()
+                    .next()
+                    .and_then(|n| n.parse::<u32>().ok())
+                    .ok_or_else(|| format!("Invalid TRACK line '{}'", line))?;
+                current = Some(PendingTrack {
+                    number,
+                    title: None,
+                    performer: None,
+                    start: None,
+                });
+            }
+            "TITLE" => match current.as_mut() {
+                Some(pending) => pending.title = Some(unquote(rest)),
+                None => title = Some(unquote(rest)),
+            },
+            "PERFORMER" => match current.as_mut() {
+                Some(pending) => pending.performer = Some(unquote(rest)),
+                None => performer = Some(unquote(rest)),
+            },
+            "INDEX" => {
+                let mut parts = rest.split_whitespace();
+                let index = parts.next().unwrap_or("");
+                let time = parts.next().unwrap_or("");
+                if index == "01" {
+                    let pending = current
+                        .as_mut()
+                        .ok_or_else(|| "INDEX outside of TRACK".to_string())?;
+                    pending.start = Some(parse_cue_time(time)?);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(pending) = current.take() {
+        tracks.push(pending.finish()?);
+    }
+
+    let file = file.ok_or_else(|| "Cue sheet has no FILE entry".to_string())?;
+    if tracks.is_empty() {
+        return Err("Cue sheet has no tracks".to_string());
+    }
+    Ok(CueSheet {
+        title,
+        performer,
+        file,
+        tracks,
+    })
+}
+
+impl CueSheet {
+    /// Build playlist tracks pointing into the shared audio file.
+    /// Each track ends where the next begins; the last ends at `total`.
+    pub fn to_tracks(&self, total: Duration) -> Vec<Track> {
+        self.tracks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                let next_start = self.tracks.get(i + 1).map(|n| n.start);
+                let end = next_start.unwrap_or(total);
+                Track {
+                    path: self.file.clone(),
+                    title: t
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| format!("Track {:02}", t.number)),
+                    artist: t
+                        .performer
+                        .clone()
+                        .or_else(|| self.performer.clone())
+                        .unwrap_or_else(|| "Unknown".to_string()),
+                    duration: end.saturating_sub(t.start),
+                    played_duration: None,
+                    has_intro: false,
+                    cue_in: Some(t.start),
+                    cue_out: next_start,
+                    verified_duration: None,
+                    no_crossfade_in: false,
+                    replay_gain_db: None,
+                    gain_trim_db: 0.0,
+                    advisory: false,
+                    musical_key: None,
+                    talk_over_secs: None,
+                }
+            })
+            .collect()
+    }
+}
+
+fn unquote(s: &str) -> String {
+    s.trim().trim_matches('"').to_string()
+}
+
+/// `"My File.wav" WAVE` -> `My File.wav`; `file.wav WAVE` -> `file.wav`.
+fn quoted_or_first_word(s: &str) -> String {
+    let s = s.trim();
+    if let Some(stripped) = s.strip_prefix('"') {
+        stripped.split('"').next().unwrap_or("").to_string()
+    } else {
+        s.split_whitespace().next().unwrap_or("").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_TRACKS: &str = r#"PERFORMER "Live Band"
+TITLE "Concert 1998"
+FILE "concert.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "Opening"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Encore"
+    PERFORMER "Guest Singer"
+    INDEX 00 03:29:00
+    INDEX 01 03:30:37
+"#;
+
+    #[test]
+    fn parse_cue_time_converts_frames() {
+        assert_eq!(parse_cue_time("00:00:00").unwrap(), Duration::ZERO);
+        assert_eq!(parse_cue_time("01:02:00").unwrap(), Duration::from_secs(62));
+        assert_eq!(
+            parse_cue_time("00:00:75").unwrap_err(),
+            "Invalid cue time '00:00:75'"
+        );
+        let t = parse_cue_time("00:01:15").unwrap();
+        assert!((t.as_secs_f64() - 1.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn parse_two_track_cue() {
+        let sheet = parse_cue(TWO_TRACKS, Path::new("/music")).unwrap();
+        assert_eq!(sheet.file, Path::new("/music").join("concert.wav"));
+        assert_eq!(sheet.title.as_deref(), Some("Concert 1998"));
+        assert_eq!(sheet.tracks.len(), 2);
+        assert_eq!(sheet.tracks[0].start, Duration::ZERO);
+        let second = sheet.tracks[1].start.as_secs_f64();
+        assert!((second - (210.0 + 37.0 / 75.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn to_tracks_derives_offsets_and_durations() {
+        let sheet = parse_cue(TWO_TRACKS, Path::new("/music")).unwrap();
+        let tracks = sheet.to_tracks(Duration::from_secs(420));
+        assert_eq!(tracks.len(), 2);
+
+        assert_eq!(tracks[0].title, "Opening");
+        assert_eq!(tracks[0].artist, "Live Band");
+        assert_eq!(tracks[0].cue_in, Some(Duration::ZERO));
+        assert_eq!(tracks[0].cue_out, Some(sheet.tracks[1].start));
+        assert_eq!(tracks[0].duration, sheet.tracks[1].start);
+
+        assert_eq!(tracks[1].artist, "Guest Singer");
+        assert_eq!(tracks[1].cue_in, Some(sheet.tracks[1].start));
+        assert_eq!(tracks[1].cue_out, None);
+        assert_eq!(
+            tracks[1].duration,
+            Duration::from_secs(420) - sheet.tracks[1].start
+        );
+        assert!(tracks.iter().all(|t| t.path == sheet.file));
+    }
+
+    #[test]
+    fn parse_cue_rejects_missing_file_and_multi_file() {
+        assert!(parse_cue("TRACK 01 AUDIO\nINDEX 01 00:00:00", Path::new(".")).is_err());
+        let multi = "FILE \"a.wav\" WAVE\nTRACK 01 AUDIO\nINDEX 01 00:00:00\nFILE \"b.wav\" WAVE";
+        let err = parse_cue(multi, Path::new(".")).unwrap_err();
+        assert!(err.contains("Multi-file"));
+    }
+}
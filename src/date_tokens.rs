@@ -0,0 +1,62 @@
+//! Date tokens in file paths, for daily material named by date.
+//!
+//! A path like `news_{YYYY}{MM}{DD}.mp3` is stored as written and expanded
+//! when it is played or triggered, so it always picks up that day's file.
+
+use chrono::{Datelike, NaiveDate};
+use std::path::{Path, PathBuf};
+
+/// Whether `path` contains any date token.
+pub fn has_date_tokens(path: &Path) -> bool {
+    let text = path.to_string_lossy();
+    ["{YYYY}", "{MM}", "{DD}"].iter().any(|t| text.contains(t))
+}
+
+/// Replace `{YYYY}`, `{MM}` and `{DD}` in `path` with `now`'s four-digit
+/// year and zero-padded month and day. Paths without tokens come back
+/// unchanged.
+pub fn expand_date_tokens(path: &Path, now: NaiveDate) -> PathBuf {
+    if !has_date_tokens(path) {
+        return path.to_path_buf();
+    }
+    let expanded = path
+        .to_string_lossy()
+        .replace("{YYYY}", &format!("{:04}", now.year()))
+        .replace("{MM}", &format!("{:02}", now.month()))
+        .replace("{DD}", &format!("{:02}", now.day()));
+    PathBuf::from(expanded)
+}
+
+/// `expand_date_tokens` for today's local date.
+pub fn expand_for_today(path: &Path) -> PathBuf {
+    expand_date_tokens(path, chrono::Local::now().date_naive())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_expand_zero_padded() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 7).unwrap();
+        let path = Path::new("daily/{YYYY}/news_{YYYY}{MM}{DD}.mp3");
+        assert_eq!(
+            expand_date_tokens(path, date),
+            PathBuf::from("daily/2026/news_20260307.mp3")
+        );
+
+        let date = NaiveDate::from_ymd_opt(2026, 12, 25).unwrap();
+        assert_eq!(
+            expand_date_tokens(Path::new("{MM}-{DD}.wav"), date),
+            PathBuf::from("12-25.wav")
+        );
+    }
+
+    #[test]
+    fn token_free_path_is_unchanged() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 7).unwrap();
+        let path = Path::new("music/{artist} - song.mp3");
+        assert!(!has_date_tokens(path));
+        assert_eq!(expand_date_tokens(path, date), path);
+    }
+}
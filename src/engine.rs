@@ -1,18 +1,115 @@
-use crate::ad_scheduler::{AdConfig, AdInserterSettings};
+use crate::ad_report::ReportBranding;
+use crate::ad_scheduler::{AdConfig, AdInserterSettings, AdPlayRecord};
+use crate::agc::AgcConfig;
+use crate::auto_intro::IntroMode;
+use crate::channel_map::ChannelMode;
+use crate::date_tokens;
 use crate::lecture_detector::LectureDetector;
-use crate::playlist::Playlist;
+use crate::playlist::{EndOfPlaylistAction, Playlist};
 use crate::rds::RdsConfig;
-use crate::scheduler::{ConflictPolicy, Schedule};
+use crate::scheduler::{
+    ConflictPolicy, InsertPosition, PausedEventHandling, Schedule, ScheduleMode,
+};
+use crate::silence::ThresholdUnit;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 const STATE_FILE: &str = "signalflow_state.json";
 
+/// Schema version written to the state file. Each entry in `MIGRATIONS`
+/// upgrades one version, so adding a migration bumps the schema.
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32 + 1;
+
+/// One state migration: edits raw state JSON from one version to the next.
+type Migration = fn(&mut serde_json::Map<String, serde_json::Value>);
+
+/// Ordered state migrations: `MIGRATIONS[n]` upgrades raw JSON from
+/// version `n + 1` to `n + 2`. Add one when a saved field is renamed or
+/// reshaped, so older files keep their values.
+const MIGRATIONS: &[Migration] = &[];
+
+/// State files written before versioning carry no `schema_version`.
+fn legacy_schema_version() -> u32 {
+    1
+}
+
+/// Saved files are always stamped with the current schema.
+fn serialize_schema_version<S: serde::Serializer>(_: &u32, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_u32(SCHEMA_VERSION)
+}
+
+/// Apply the migrations from `from_version` up to `SCHEMA_VERSION` to raw
+/// state JSON, then stamp the current version. Files from a newer build are
+/// left as they are.
+pub fn migrate(state: &mut serde_json::Value, from_version: u32) {
+    migrate_with(MIGRATIONS, state, from_version);
+}
+
+/// `migrate` over an explicit migration list; the target version is one
+/// past its last step.
+fn migrate_with(migrations: &[Migration], state: &mut serde_json::Value, from_version: u32) {
+    let Some(obj) = state.as_object_mut() else {
+        return;
+    };
+    let target = migrations.len() as u32 + 1;
+    if from_version > target {
+        return;
+    }
+    let start = from_version.saturating_sub(1) as usize;
+    for step in &migrations[start..] {
+        step(obj);
+    }
+    obj.insert("schema_version".to_string(), target.into());
+}
+
 fn default_duck_volume() -> f32 {
     0.3
 }
 
+fn default_duck_ramp_secs() -> f32 {
+    0.5
+}
+
+fn default_now_playing_debounce_ms() -> u64 {
+    crate::now_playing::DEFAULT_DEBOUNCE_MS
+}
+
+fn default_agc_target_db() -> f32 {
+    AgcConfig::default().target_db
+}
+
+fn default_agc_attack_ms() -> f32 {
+    AgcConfig::default().attack_ms
+}
+
+fn default_agc_release_ms() -> f32 {
+    AgcConfig::default().release_ms
+}
+
+fn default_agc_max_gain_db() -> f32 {
+    AgcConfig::default().max_gain_db
+}
+
+fn default_overlay_gain() -> f32 {
+    1.0
+}
+
+fn default_dead_air_threshold_db() -> f32 {
+    -50.0
+}
+
+fn default_resume_save_interval_secs() -> f32 {
+    10.0
+}
+
+fn default_save_config_immediately() -> bool {
+    true
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlaylistProfile {
     pub name: String,
@@ -21,6 +118,87 @@ pub struct PlaylistProfile {
     pub playlist_paths: Vec<Option<String>>,
 }
 
+/// Outcome of `Engine::relink_paths`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct RelinkSummary {
+    /// Tracks whose path matched the old prefix (rewritten unless dry run).
+    pub changed: usize,
+    /// Of those, tracks whose new path doesn't exist either.
+    pub missing: usize,
+}
+
+/// A track `Engine::plan_relink` matched: where it is and where it moves.
+#[derive(Debug, Clone)]
+pub struct RelinkMove {
+    pub index: usize,
+    pub old_path: PathBuf,
+    pub new_path: PathBuf,
+    /// Cue-sheet segments keep their own tags.
+    pub segment: bool,
+}
+
+/// A `RelinkMove` checked against the disk by `RelinkMove::read`.
+#[derive(Debug)]
+pub struct RelinkRead {
+    pub relink: RelinkMove,
+    /// Whether the new path (today's file, for date tokens) exists.
+    pub exists: bool,
+    /// Tags read from the new file, if it exists and isn't a segment.
+    pub tags: Option<crate::track::Track>,
+}
+
+impl RelinkMove {
+    /// Check the new path and, with `read_tags`, read its tags. Does file
+    /// I/O — call this OUTSIDE any lock, then pass the result to
+    /// `Engine::apply_relink`.
+    pub fn read(self, read_tags: bool) -> RelinkRead {
+        let file = date_tokens::expand_for_today(&self.new_path);
+        let exists = file.is_file();
+        let tags = if read_tags && exists && !self.segment {
+            crate::track::Track::from_path(&file).ok()
+        } else {
+            None
+        };
+        RelinkRead {
+            relink: self,
+            exists,
+            tags,
+        }
+    }
+}
+
+/// Audio output bus. Program feeds air; monitor is for auditioning (the
+/// editor) on a separate device such as headphones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputBus {
+    Program,
+    Monitor,
+}
+
+/// An instant-play cart (stinger / sound effect) bound to a numbered button.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CartSlot {
+    /// Button number, 1-based and contiguous in list order.
+    pub index: usize,
+    pub label: String,
+    pub path: PathBuf,
+    /// true = mix over current audio; false = hard break (stop, then play).
+    #[serde(default)]
+    pub overlay: bool,
+}
+
+impl CartSlot {
+    /// Playback mode used when the cart fires.
+    pub fn mode(&self) -> ScheduleMode {
+        if self.overlay {
+            ScheduleMode::Overlay
+        } else {
+            ScheduleMode::Stop
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamOutputConfig {
     #[serde(default)]
@@ -57,17 +235,66 @@ impl Default for RecordingConfig {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Engine {
+    /// State file schema version; `SCHEMA_VERSION` once loaded or saved.
+    #[serde(
+        default = "legacy_schema_version",
+        serialize_with = "serialize_schema_version"
+    )]
+    pub schema_version: u32,
     pub playlists: Vec<Playlist>,
     pub active_playlist_id: Option<u32>,
     next_id: u32,
     #[serde(default)]
     pub crossfade_secs: f32,
+    /// Fade-in applied to the first track when playback starts from silence
+    /// (sign-on). Subsequent tracks are unaffected. 0 = disabled.
+    #[serde(default)]
+    pub signon_fade_secs: f32,
+    /// Extra seconds before the fade window at which the crossfade starts,
+    /// for tracks with long tails. 0 = fade begins `crossfade_secs` before end.
+    #[serde(default)]
+    pub crossfade_lead_secs: f32,
+    /// Tracks shorter than this always hard-cut into the next one, so
+    /// jingles and stingers aren't swallowed by the fade. 0 = disabled.
+    #[serde(default)]
+    pub min_crossfade_track_secs: f32,
+    /// Manual skips crossfade over `crossfade_secs` instead of cutting.
+    #[serde(default)]
+    pub crossfade_on_skip: bool,
+    /// Single-key live-assist hotkeys in the GUI (skip, previous, pause,
+    /// stop, carts). Off by default so stray keystrokes can't touch air.
+    #[serde(default)]
+    pub live_assist_hotkeys: bool,
+    /// Mixing gain for overlay events and carts (0.0–2.0, default 1.0 = unity).
+    #[serde(default = "default_overlay_gain")]
+    pub overlay_gain: f32,
+    /// How often the playback resume marker is written (0 = disabled).
+    #[serde(default = "default_resume_save_interval_secs")]
+    pub resume_save_interval_secs: f32,
+    /// State changes are written at most this often, in ms: `save` marks
+    /// the state dirty and `flush_if_due` writes it (0 = write on every save).
+    #[serde(default)]
+    pub save_debounce_ms: u64,
+    /// Settings changes (`save_config`) bypass `save_debounce_ms` and are
+    /// written at once (default true).
+    #[serde(default = "default_save_config_immediately")]
+    pub save_config_immediately: bool,
     /// RMS threshold below which audio is considered silent (e.g., 0.01).
     #[serde(default)]
     pub silence_threshold: f32,
+    /// Unit the user entered the threshold in; `silence_threshold` stays linear.
+    #[serde(default)]
+    pub silence_threshold_unit: ThresholdUnit,
     /// Seconds of continuous silence before auto-skip (0 = disabled).
     #[serde(default)]
     pub silence_duration_secs: f32,
+    /// Output level (dBFS) below which the dead-air alarm counts as silence.
+    #[serde(default = "default_dead_air_threshold_db")]
+    pub dead_air_threshold_db: f32,
+    /// Seconds of silent output while playing before the dead-air alarm
+    /// fires (0 = disabled).
+    #[serde(default)]
+    pub dead_air_secs: f32,
     /// Path to folder containing artist intro files (None = disabled).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub intros_folder: Option<String>,
@@ -77,6 +304,9 @@ pub struct Engine {
     /// How to resolve conflicts between manual playback and scheduled events.
     #[serde(default)]
     pub conflict_policy: ConflictPolicy,
+    /// What overlay and stop events do when they fire during a pause.
+    #[serde(default)]
+    pub paused_event_handling: PausedEventHandling,
     /// Interval in seconds for recurring intro overlays (0 = disabled).
     /// When > 0, re-plays the artist intro every N seconds during track playback.
     #[serde(default)]
@@ -84,18 +314,71 @@ pub struct Engine {
     /// Volume level for main track during recurring intro overlay (0.0–1.0, default 0.3).
     #[serde(default = "default_duck_volume")]
     pub recurring_intro_duck_volume: f32,
+    /// Seconds to ramp the main track into and out of the duck (default 0.5).
+    #[serde(default = "default_duck_ramp_secs")]
+    pub recurring_intro_duck_ramp_secs: f32,
+    /// Play each artist's intro only the first time they come up after play.
+    #[serde(default)]
+    pub intro_once_per_session: bool,
+    /// Play auto-intros before the track, or over its start with the track
+    /// ducked to `recurring_intro_duck_volume`.
+    #[serde(default)]
+    pub intro_mode: IntroMode,
+    /// Gain in dB applied to every auto-intro (0 = the file's own level).
+    #[serde(default)]
+    pub intro_gain_db: f32,
     /// Path for now-playing XML export (None = disabled).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub now_playing_path: Option<String>,
+    /// Now-playing updates arriving within this many milliseconds of each
+    /// other are coalesced into one write of the latest (default 500).
+    #[serde(default = "default_now_playing_debounce_ms")]
+    pub now_playing_debounce_ms: u64,
+    /// Shown as the now-playing title while an ad or station ID airs, in
+    /// place of the ad's own tags (e.g. the station name; empty = blank).
+    #[serde(default)]
+    pub now_playing_ad_placeholder: String,
+    /// File touched while audio is on air, for external monitoring (None = disabled).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_path: Option<String>,
+    /// Played on loop as a last resort when there is nothing else to air
+    /// (empty active playlist, or a loop/fallback with nothing playable).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emergency_file: Option<PathBuf>,
     /// Ad definitions for the ad scheduler/inserter system.
     #[serde(default)]
     pub ads: Vec<AdConfig>,
     /// Ad inserter service settings (output path, station ID).
     #[serde(default)]
     pub ad_inserter: AdInserterSettings,
+    /// Recent ad plays (oldest first), consulted for `min_repeat_gap`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ad_play_history: Vec<AdPlayRecord>,
     /// Lecture detector with blacklist/whitelist (shared between ad scheduler and RDS).
     #[serde(default)]
     pub lecture_detector: LectureDetector,
+    /// Skip scheduled ad insertion while the current track is a lecture.
+    #[serde(default)]
+    pub no_ads_during_lectures: bool,
+    /// Hard cuts instead of crossfades into or out of lectures.
+    #[serde(default)]
+    pub no_crossfade_for_lectures: bool,
+    /// On advance, when the next track is by the current artist, play the
+    /// nearest track by someone else first.
+    #[serde(default)]
+    pub avoid_artist_repeat: bool,
+    /// Logo and header color used in ad report PDFs.
+    #[serde(default)]
+    pub report_branding: ReportBranding,
+    /// Hours (0-23) during which advisory tracks are skipped on advance.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clean_hours: Vec<u8>,
+    /// What auto-advance does after the last track of the active playlist.
+    #[serde(default)]
+    pub end_of_playlist_action: EndOfPlaylistAction,
+    /// Instant-play carts, ordered by button number.
+    #[serde(default)]
+    pub carts: Vec<CartSlot>,
     /// RDS (Radio Data System) message rotation configuration.
     #[serde(default)]
     pub rds: RdsConfig,
@@ -111,43 +394,140 @@ pub struct Engine {
     /// User-pinned favorite folders shown in the file browser pane.
     #[serde(default)]
     pub favorite_folders: Vec<String>,
+    /// Decode tracks whose tags report no duration when they are added,
+    /// so backtiming works for them (see `Track::fill_unknown_duration`).
+    #[serde(default)]
+    pub measure_unknown_durations: bool,
     /// Saved named profiles of open playlists.
     #[serde(default)]
     pub playlist_profiles: Vec<PlaylistProfile>,
     /// Preferred audio output device name (None = system default).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_device_name: Option<String>,
+    /// Monitor (cue/headphone) output device for the editor, chosen
+    /// independently of the program device (None = system default).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor_device_name: Option<String>,
+    /// Fixed output sample rate; decoded audio at any other rate is
+    /// resampled to it (None = play each file at its own rate).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_sample_rate: Option<u32>,
+    /// Output device buffer in ms (0 = the driver's default). The program
+    /// output opens with a buffer of about this size, and the playback
+    /// position is reported late by the buffer actually opened so the UI
+    /// matches what is heard.
+    #[serde(default)]
+    pub audio_buffer_ms: u32,
+    /// Ride program gain toward `agc_target_db` (see `agc::AgcSource`).
+    #[serde(default)]
+    pub agc_enabled: bool,
+    /// AGC target RMS level in dBFS.
+    #[serde(default = "default_agc_target_db")]
+    pub agc_target_db: f32,
+    /// AGC time constant for pulling gain down, in ms.
+    #[serde(default = "default_agc_attack_ms")]
+    pub agc_attack_ms: f32,
+    /// AGC time constant for letting gain back up, in ms.
+    #[serde(default = "default_agc_release_ms")]
+    pub agc_release_ms: f32,
+    /// Largest boost the AGC applies to quiet audio, in dB.
+    #[serde(default = "default_agc_max_gain_db")]
+    pub agc_max_gain_db: f32,
+    /// How the program output's left and right channels are fed.
+    #[serde(default)]
+    pub channel_mode: ChannelMode,
+    /// Apply each track's ReplayGain tag on air, on top of its trim (off
+    /// by default; the trim always applies).
+    #[serde(default)]
+    pub replay_gain_enabled: bool,
     /// Runtime-only: path to the state file. Not serialized.
     #[serde(skip)]
     state_path: Option<PathBuf>,
+    /// Runtime-only: set by `load_from` when the state file was corrupt or
+    /// unreadable, so the caller can surface it.
+    #[serde(skip)]
+    pub load_warning: Option<String>,
+    /// Runtime-only: an instant ad roll is on air (see
+    /// `AdInserterService::insert_instant`). Shared so the thread waiting
+    /// out the roll can clear it without holding the engine.
+    #[serde(skip)]
+    pub ad_break_active: Arc<AtomicBool>,
+    /// Runtime-only: when the oldest change not yet written by a debounced
+    /// `save` was made (None = nothing pending).
+    #[serde(skip)]
+    unsaved_since: Mutex<Option<Instant>>,
 }
 
 impl Engine {
     pub fn new() -> Self {
         Engine {
+            schema_version: SCHEMA_VERSION,
             playlists: Vec::new(),
             active_playlist_id: None,
             next_id: 1,
             crossfade_secs: 0.0,
+            signon_fade_secs: 0.0,
+            crossfade_lead_secs: 0.0,
+            min_crossfade_track_secs: 0.0,
+            crossfade_on_skip: false,
+            live_assist_hotkeys: false,
+            overlay_gain: 1.0,
+            resume_save_interval_secs: default_resume_save_interval_secs(),
+            save_debounce_ms: 0,
+            save_config_immediately: true,
             silence_threshold: 0.01,
+            silence_threshold_unit: ThresholdUnit::Linear,
             silence_duration_secs: 0.0,
+            dead_air_threshold_db: default_dead_air_threshold_db(),
+            dead_air_secs: 0.0,
             intros_folder: None,
             recurring_intro_interval_secs: 0.0,
             recurring_intro_duck_volume: 0.3,
+            recurring_intro_duck_ramp_secs: default_duck_ramp_secs(),
+            intro_once_per_session: false,
+            intro_mode: IntroMode::Before,
+            intro_gain_db: 0.0,
             schedule: Schedule::new(),
             conflict_policy: ConflictPolicy::default(),
+            paused_event_handling: PausedEventHandling::default(),
             now_playing_path: None,
+            now_playing_debounce_ms: default_now_playing_debounce_ms(),
+            now_playing_ad_placeholder: String::new(),
+            heartbeat_path: None,
+            emergency_file: None,
             ads: Vec::new(),
             ad_inserter: AdInserterSettings::default(),
+            ad_play_history: Vec::new(),
             lecture_detector: LectureDetector::new(),
+            no_ads_during_lectures: false,
+            no_crossfade_for_lectures: false,
+            avoid_artist_repeat: false,
+            report_branding: ReportBranding::default(),
+            clean_hours: Vec::new(),
+            end_of_playlist_action: EndOfPlaylistAction::Stop,
+            carts: Vec::new(),
             rds: RdsConfig::default(),
             stream_output: StreamOutputConfig::default(),
             recording: RecordingConfig::default(),
             indexed_locations: Vec::new(),
             favorite_folders: Vec::new(),
+            measure_unknown_durations: false,
             playlist_profiles: Vec::new(),
             output_device_name: None,
+            monitor_device_name: None,
+            output_sample_rate: None,
+            audio_buffer_ms: 0,
+            agc_enabled: false,
+            agc_target_db: default_agc_target_db(),
+            agc_attack_ms: default_agc_attack_ms(),
+            agc_release_ms: default_agc_release_ms(),
+            agc_max_gain_db: default_agc_max_gain_db(),
+            channel_mode: ChannelMode::default(),
+            replay_gain_enabled: false,
             state_path: None,
+            load_warning: None,
+            ad_break_active: Arc::default(),
+            unsaved_since: Mutex::new(None),
         }
     }
 
@@ -161,26 +541,97 @@ impl Engine {
         Self::load_from(Path::new(STATE_FILE))
     }
 
-    /// Load engine state from a specific path.
+    /// Load engine state from a specific path. A missing file starts fresh.
+    /// A corrupt file is moved aside (see `quarantine_corrupt_state`) before
+    /// starting fresh, so the next save can't overwrite it. A file that
+    /// can't be read, or a corrupt one that can't be moved aside, leaves
+    /// the engine in memory only so it is never saved over. `load_warning`
+    /// says what happened.
     pub fn load_from(path: &Path) -> Self {
-        if path.exists() {
-            match fs::read_to_string(path) {
-                Ok(data) => match serde_json::from_str::<Engine>(&data) {
-                    Ok(mut engine) => {
+        let mut engine = Engine::new();
+        match fs::read_to_string(path) {
+            Ok(data) => match Self::parse_state(&data) {
+                Ok(mut loaded) => {
+                    loaded.state_path = Some(path.to_path_buf());
+                    loaded.migrate_unc_paths();
+                    return loaded;
+                }
+                Err(e) => match quarantine_corrupt_state(path, &e) {
+                    Ok(warning) => {
                         engine.state_path = Some(path.to_path_buf());
-                        engine.migrate_unc_paths();
-                        return engine;
+                        engine.load_warning = Some(warning);
                     }
-                    Err(e) => eprintln!("Warning: corrupt state file, starting fresh: {}", e),
+                    Err(warning) => engine.load_warning = Some(warning),
                 },
-                Err(e) => eprintln!("Warning: could not read state file: {}", e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                engine.state_path = Some(path.to_path_buf());
+            }
+            Err(e) => {
+                let warning = format!(
+                    "State file '{}' could not be read ({}). Settings start fresh and \
+                     won't be saved, so the file is left as it is.",
+                    path.display(),
+                    e
+                );
+                eprintln!("WARNING: {}", warning);
+                engine.load_warning = Some(warning);
             }
         }
-        let mut engine = Engine::new();
-        engine.state_path = Some(path.to_path_buf());
         engine
     }
 
+    /// Strictly re-read a state file. Unlike `load_from`, a missing or
+    /// corrupt file is an error rather than a fresh engine.
+    pub fn read_from(path: &Path) -> Result<Self, String> {
+        let data = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read state file '{}': {}", path.display(), e))?;
+        let mut engine = Self::parse_state(&data)
+            .map_err(|e| format!("Corrupt state file '{}': {}", path.display(), e))?;
+        engine.state_path = Some(path.to_path_buf());
+        engine.migrate_unc_paths();
+        Ok(engine)
+    }
+
+    /// Parse state file JSON, migrating older schemas forward first.
+    pub(crate) fn parse_state(data: &str) -> Result<Self, String> {
+        let mut value: serde_json::Value = serde_json::from_str(data).map_err(|e| e.to_string())?;
+        let from_version = value
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as u32)
+            .unwrap_or_else(legacy_schema_version);
+        migrate(&mut value, from_version);
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    /// Swap in `incoming` wholesale, keeping this engine's state file path
+    /// and its `ad_break_active` flag (an instant roll on air holds a clone).
+    pub fn replace_with(&mut self, incoming: Engine) {
+        let state_path = self.state_path.take();
+        let ad_break_active = self.ad_break_active.clone();
+        *self = incoming;
+        self.state_path = state_path;
+        self.ad_break_active = ad_break_active;
+    }
+
+    /// Copy the current state to a timestamped backup next to the state
+    /// file. Returns the backup path, or None in in-memory mode.
+    pub fn write_backup(&self) -> Result<Option<PathBuf>, String> {
+        let path = match &self.state_path {
+            Some(p) => p,
+            None => return Ok(None),
+        };
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let backup = path.with_file_name(format!("{}.backup-{}.json", stem, stamp));
+        let json =
+            serde_json::to_string_pretty(self).map_err(|e| format!("Serialize error: {}", e))?;
+        fs::write(&backup, json)
+            .map_err(|e| format!("Cannot write backup '{}': {}", backup.display(), e))?;
+        Ok(Some(backup))
+    }
+
     /// Rewrite `\\?\UNC\...` paths in all playlist tracks to use mapped drive
     /// letters where possible. On Windows, `DirEntry::path()` returns verbatim
     /// UNC paths for files on mapped network drives, which is confusing in the UI.
@@ -204,9 +655,60 @@ impl Engine {
         }
     }
 
-    /// Persist current state to JSON.
+    /// Persist current state to JSON. With `save_debounce_ms` set, this only
+    /// marks the state dirty; `flush_if_due` or `flush` writes it later.
     /// When `state_path` is None (in-memory / test mode), this is a no-op.
     pub fn save(&self) -> Result<(), String> {
+        if self.save_debounce_ms == 0 || self.state_path.is_none() {
+            return self.write_state();
+        }
+        self.unsaved_since
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Instant::now);
+        Ok(())
+    }
+
+    /// Persist a settings change: written at once when
+    /// `save_config_immediately` is on, otherwise debounced like `save`.
+    pub fn save_config(&self) -> Result<(), String> {
+        if self.save_config_immediately {
+            self.write_state()
+        } else {
+            self.save()
+        }
+    }
+
+    /// Whether a debounced `save` is still waiting to be written.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.unsaved_since.lock().unwrap().is_some()
+    }
+
+    /// Write pending changes once the oldest is `save_debounce_ms` old, so a
+    /// burst of edits costs one write. Returns whether it wrote.
+    pub fn flush_if_due(&self) -> Result<bool, String> {
+        let window = Duration::from_millis(self.save_debounce_ms);
+        let due = self
+            .unsaved_since
+            .lock()
+            .unwrap()
+            .is_some_and(|since| since.elapsed() >= window);
+        if due {
+            self.write_state()?;
+        }
+        Ok(due)
+    }
+
+    /// Write pending changes now, e.g. on shutdown. No-op when none are.
+    pub fn flush(&self) -> Result<(), String> {
+        if self.has_unsaved_changes() {
+            self.write_state()?;
+        }
+        Ok(())
+    }
+
+    /// Serialize and write the whole state file, clearing any pending save.
+    fn write_state(&self) -> Result<(), String> {
         let path = match &self.state_path {
             Some(p) => p.as_path(),
             None => return Ok(()), // In-memory mode — skip file I/O
@@ -219,15 +721,21 @@ impl Engine {
         let json =
             serde_json::to_string_pretty(self).map_err(|e| format!("Serialize error: {}", e))?;
         fs::write(path, json).map_err(|e| format!("Write error: {}", e))?;
+        *self.unsaved_since.lock().unwrap() = None;
         Ok(())
     }
 
     /// Create a new playlist with the given name. Returns its ID.
     pub fn create_playlist(&mut self, name: String) -> u32 {
+        self.create_playlist_mut(name).id
+    }
+
+    /// Create an empty playlist and return it for filling in.
+    pub fn create_playlist_mut(&mut self, name: String) -> &mut Playlist {
         let id = self.next_id;
         self.next_id += 1;
         self.playlists.push(Playlist::new(id, name));
-        id
+        self.playlists.last_mut().unwrap()
     }
 
     /// Find a playlist by name (case-insensitive).
@@ -290,19 +798,34 @@ impl Engine {
         Ok(tracks)
     }
 
-    /// Insert a track as the next item in the active playlist (after current_index).
-    /// Used by the scheduler's Insert mode to queue a file as the next track.
+    /// Insert a track into the active playlist at `position`: after
+    /// current_index (`Next`, used by the scheduler's Insert mode and ad
+    /// breaks), after the last track (`End`) or at an explicit index.
     /// Returns the insertion position (0-based) or an error.
-    pub fn insert_next_track(&mut self, path: &std::path::Path) -> Result<usize, String> {
-        let track = crate::track::Track::from_path(path)?;
+    pub fn insert_track_at(
+        &mut self,
+        path: &std::path::Path,
+        position: InsertPosition,
+    ) -> Result<usize, String> {
+        let mut track = crate::track::Track::from_path(path)?;
+        track.fill_unknown_duration(self.measure_unknown_durations);
+        self.insert_into_active(track, position)
+    }
+
+    fn insert_into_active(
+        &mut self,
+        track: crate::track::Track,
+        position: InsertPosition,
+    ) -> Result<usize, String> {
         let pl = self
             .active_playlist_mut()
             .ok_or_else(|| "No active playlist".to_string())?;
-        let insert_pos = match pl.current_index {
-            Some(i) => i + 1,
-            None => 0,
-        };
+        let insert_pos = position.resolve(pl.current_index, pl.tracks.len());
         pl.insert_tracks(vec![track], Some(insert_pos))?;
+        // Keep pointing at the same track when inserting ahead of it
+        if let Some(current) = pl.current_index.filter(|&i| insert_pos <= i) {
+            pl.current_index = Some(current + 1);
+        }
         Ok(insert_pos)
     }
 
@@ -355,6 +878,99 @@ impl Engine {
         Ok(())
     }
 
+    /// Rewrite `old_prefix` to `new_prefix` in a playlist's track paths
+    /// (a moved music library). Prefixes match whole path components,
+    /// case-insensitively on Windows. Tracks whose new path exists get their
+    /// tags re-read; cue-sheet segments keep theirs. Date-token paths keep
+    /// their tokens and are checked against today's file. With `dry_run`,
+    /// only counts what would change.
+    ///
+    /// Reads the new files in place; to keep that I/O out of a lock, use
+    /// `plan_relink`, `RelinkMove::read` and `apply_relink` instead.
+    pub fn relink_paths(
+        &mut self,
+        playlist_name: &str,
+        old_prefix: &str,
+        new_prefix: &str,
+        dry_run: bool,
+    ) -> Result<RelinkSummary, String> {
+        let reads = self
+            .plan_relink(playlist_name, old_prefix, new_prefix)?
+            .into_iter()
+            .map(|relink| relink.read(!dry_run))
+            .collect();
+        self.apply_relink(playlist_name, reads, dry_run)
+    }
+
+    /// The tracks in a playlist whose paths start with `old_prefix`, and
+    /// where `relink_paths` would move them. Touches no files.
+    pub fn plan_relink(
+        &self,
+        playlist_name: &str,
+        old_prefix: &str,
+        new_prefix: &str,
+    ) -> Result<Vec<RelinkMove>, String> {
+        if old_prefix.trim_end_matches(['/', '\\']).is_empty() {
+            return Err("Old path prefix must not be empty".to_string());
+        }
+        let pl = self
+            .find_playlist(playlist_name)
+            .ok_or_else(|| format!("Playlist '{}' not found", playlist_name))?;
+        let moves = pl
+            .tracks
+            .iter()
+            .enumerate()
+            .filter_map(|(index, track)| {
+                let new_path = relink_path(&track.path, old_prefix, new_prefix, cfg!(windows))?;
+                Some(RelinkMove {
+                    index,
+                    old_path: track.path.clone(),
+                    new_path,
+                    segment: track.cue_in.is_some() || track.cue_out.is_some(),
+                })
+            })
+            .collect();
+        Ok(moves)
+    }
+
+    /// Apply relinks read by `RelinkMove::read`. Tracks that moved or
+    /// changed path since `plan_relink` are left alone and not counted.
+    /// With `dry_run`, only counts.
+    pub fn apply_relink(
+        &mut self,
+        playlist_name: &str,
+        reads: Vec<RelinkRead>,
+        dry_run: bool,
+    ) -> Result<RelinkSummary, String> {
+        let pl = self
+            .find_playlist_mut(playlist_name)
+            .ok_or_else(|| format!("Playlist '{}' not found", playlist_name))?;
+        let mut summary = RelinkSummary::default();
+        for read in reads {
+            let Some(track) = pl.tracks.get_mut(read.relink.index) else {
+                continue;
+            };
+            if track.path != read.relink.old_path {
+                continue;
+            }
+            summary.changed += 1;
+            if !read.exists {
+                summary.missing += 1;
+            }
+            if dry_run {
+                continue;
+            }
+            if let Some(reloaded) = read.tags {
+                track.title = reloaded.title;
+                track.artist = reloaded.artist;
+                track.duration = reloaded.duration;
+                track.replay_gain_db = reloaded.replay_gain_db;
+            }
+            track.path = read.relink.new_path;
+        }
+        Ok(summary)
+    }
+
     // --- Ad management ---
 
 
@@ -364,6 +980,22 @@ impl Engine {
         self.ads.len() - 1
     }
 
+    /// Whether `path` is one of the configured ads (original or normalized
+    /// copy) or the station ID. Relative configured paths match any track
+    /// path ending with them.
+    pub fn is_ad_or_station_id(&self, path: &Path) -> bool {
+        let same = |configured: &Path| {
+            path == configured || (configured.is_relative() && path.ends_with(configured))
+        };
+        let ad_files = self
+            .ads
+            .iter()
+            .flat_map(|ad| std::iter::once(&ad.mp3_file).chain(&ad.normalized_file));
+        ad_files
+            .chain(&self.ad_inserter.station_id_file)
+            .any(|file| same(file))
+    }
+
     /// Remove an ad by index (0-based). Returns the removed ad.
     pub fn remove_ad(&mut self, index: usize) -> Result<AdConfig, String> {
         if index >= self.ads.len() {
@@ -395,6 +1027,41 @@ impl Engine {
         Ok(ad.enabled)
     }
 
+    // --- Cart management ---
+
+    /// Append a cart. Returns its button number (1-based).
+    pub fn add_cart(&mut self, label: String, path: PathBuf, overlay: bool) -> usize {
+        let index = self.carts.len() + 1;
+        self.carts.push(CartSlot {
+            index,
+            label,
+            path,
+            overlay,
+        });
+        index
+    }
+
+    /// Remove a cart by button number; later carts shift down to fill the gap.
+    pub fn remove_cart(&mut self, index: usize) -> Result<CartSlot, String> {
+        if index == 0 || index > self.carts.len() {
+            return Err(format!(
+                "Cart {} out of range ({} carts)",
+                index,
+                self.carts.len()
+            ));
+        }
+        let removed = self.carts.remove(index - 1);
+        for (i, cart) in self.carts.iter_mut().enumerate() {
+            cart.index = i + 1;
+        }
+        Ok(removed)
+    }
+
+    /// Look up a cart by button number.
+    pub fn cart(&self, index: usize) -> Option<&CartSlot> {
+        index.checked_sub(1).and_then(|i| self.carts.get(i))
+    }
+
     /// Get the path of the currently playing track from the active playlist.
     pub fn current_track_path(&self) -> Option<&Path> {
         let pl = self.active_playlist()?;
@@ -426,6 +1093,36 @@ impl Engine {
             .unwrap_or(false)
     }
 
+    /// Whether advisory tracks are kept off air at `hour` (0-23).
+    pub fn is_clean_hour(&self, hour: u8) -> bool {
+        self.clean_hours.contains(&hour)
+    }
+
+    /// Program-bus AGC settings; None unless `agc_enabled` is on.
+    pub fn agc_config(&self) -> Option<AgcConfig> {
+        self.agc_enabled.then_some(AgcConfig {
+            target_db: self.agc_target_db,
+            attack_ms: self.agc_attack_ms,
+            release_ms: self.agc_release_ms,
+            max_gain_db: self.agc_max_gain_db,
+        })
+    }
+
+    /// Output device `bus` should open (None = system default).
+    pub fn output_device_for(&self, bus: OutputBus) -> Option<&str> {
+        match bus {
+            OutputBus::Program => self.output_device_name.as_deref(),
+            OutputBus::Monitor => self.monitor_device_name.as_deref(),
+        }
+    }
+
+    /// Detector for `play_playlist`'s lecture hard cuts; None unless
+    /// `no_crossfade_for_lectures` is on.
+    pub fn lecture_crossfade_guard(&self) -> Option<&LectureDetector> {
+        self.no_crossfade_for_lectures
+            .then_some(&self.lecture_detector)
+    }
+
     /// Paste (insert) tracks into a playlist at a position, or append.
     pub fn paste_tracks(
         &mut self,
@@ -440,6 +1137,31 @@ impl Engine {
     }
 }
 
+/// `path` with `old_prefix` replaced by `new_prefix`, or None when it
+/// doesn't start with `old_prefix` as whole components (`D:\Music` does not
+/// match `D:\Music2`). Trailing separators on either prefix are ignored.
+pub fn relink_path(
+    path: &Path,
+    old_prefix: &str,
+    new_prefix: &str,
+    ignore_case: bool,
+) -> Option<PathBuf> {
+    let path = path.to_string_lossy();
+    let old = old_prefix.trim_end_matches(['/', '\\']);
+    let head = path.get(..old.len())?;
+    let matches = if ignore_case {
+        head.to_lowercase() == old.to_lowercase()
+    } else {
+        head == old
+    };
+    let rest = &path[old.len()..];
+    if !matches || !(rest.is_empty() || rest.starts_with(['/', '\\'])) {
+        return None;
+    }
+    let new = new_prefix.trim_end_matches(['/', '\\']);
+    Some(PathBuf::from(format!("{}{}", new, rest)))
+}
+
 /// Build a map of UNC share roots to drive letters by querying Windows drive mappings.
 /// Returns e.g. `[("\\\\RadioNAS\\104.7", "G:")]`. Empty on non-Windows or if no mapped drives.
 fn build_unc_to_drive_map() -> Vec<(String, String)> {
@@ -507,6 +1229,39 @@ fn rewrite_unc_path(path_str: &str, mapping: &[(String, String)]) -> Option<Stri
     None
 }
 
+/// Rename a state file that failed to parse to
+/// `<stem>.corrupt-<timestamp>.json` next to it, so its contents can be
+/// recovered by hand. Returns the warning to show (it is also printed):
+/// Ok once the file is out of the way, Err if it is still in place.
+fn quarantine_corrupt_state(path: &Path, error: &str) -> Result<String, String> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let mut quarantine = path.with_file_name(format!("{}.corrupt-{}.json", stem, stamp));
+    let mut n = 2;
+    while quarantine.exists() {
+        quarantine = path.with_file_name(format!("{}.corrupt-{}-{}.json", stem, stamp, n));
+        n += 1;
+    }
+    let result = match fs::rename(path, &quarantine) {
+        Ok(()) => Ok(format!(
+            "State file '{}' is corrupt ({}). It was moved to '{}' and settings start fresh.",
+            path.display(),
+            error,
+            quarantine.display()
+        )),
+        Err(e) => Err(format!(
+            "State file '{}' is corrupt ({}) and could not be moved aside ({}). \
+             Settings start fresh and won't be saved, so the file is left as it is.",
+            path.display(),
+            error,
+            e
+        )),
+    };
+    let (Ok(warning) | Err(warning)) = &result;
+    eprintln!("WARNING: {}", warning);
+    result
+}
+
 impl Default for Engine {
     fn default() -> Self {
         Self::new()
@@ -526,6 +1281,17 @@ mod tests {
         assert_eq!(engine.playlists.len(), 2);
     }
 
+    #[test]
+    fn create_playlist_mut_returns_the_new_playlist() {
+        let mut engine = Engine::new();
+        let first = engine.create_playlist("A".to_string());
+        let pl = engine.create_playlist_mut("B".to_string());
+        pl.auto_advance = true;
+        let id = pl.id;
+        assert_ne!(id, first);
+        assert!(engine.find_playlist("B").unwrap().auto_advance);
+    }
+
     #[test]
     fn find_playlist_case_insensitive() {
         let mut engine = Engine::new();
@@ -573,6 +1339,76 @@ mod tests {
         assert_eq!(engine.crossfade_secs, 0.0);
     }
 
+    #[test]
+    fn signon_fade_defaults_when_missing_from_json() {
+        let json = r#"{"playlists":[],"active_playlist_id":null,"next_id":1}"#;
+        let engine: Engine = serde_json::from_str(json).unwrap();
+        assert_eq!(engine.signon_fade_secs, 0.0);
+    }
+
+    #[test]
+    fn crossfade_lead_defaults_when_missing_from_json() {
+        let json = r#"{"playlists":[],"active_playlist_id":null,"next_id":1}"#;
+        let engine: Engine = serde_json::from_str(json).unwrap();
+        assert_eq!(engine.crossfade_lead_secs, 0.0);
+    }
+
+    #[test]
+    fn min_crossfade_track_secs_defaults_off() {
+        let json = r#"{"playlists":[],"active_playlist_id":null,"next_id":1}"#;
+        let engine: Engine = serde_json::from_str(json).unwrap();
+        assert_eq!(engine.min_crossfade_track_secs, 0.0);
+    }
+
+    #[test]
+    fn no_ads_during_lectures_defaults_off() {
+        let json = r#"{"playlists":[],"active_playlist_id":null,"next_id":1}"#;
+        let engine: Engine = serde_json::from_str(json).unwrap();
+        assert!(!engine.no_ads_during_lectures);
+        assert!(!engine.no_crossfade_for_lectures);
+    }
+
+    #[test]
+    fn lecture_crossfade_guard_follows_flag() {
+        let mut engine = Engine::new();
+        assert!(engine.lecture_crossfade_guard().is_none());
+        engine.no_crossfade_for_lectures = true;
+        let guard = engine.lecture_crossfade_guard().unwrap();
+        assert!(guard.is_lecture("Rabbi Cohen"));
+    }
+
+    #[test]
+    fn output_buses_route_to_their_own_devices() {
+        let mut engine = Engine::new();
+        assert_eq!(engine.output_device_for(OutputBus::Program), None);
+        assert_eq!(engine.output_device_for(OutputBus::Monitor), None);
+
+        engine.output_device_name = Some("Air Card".to_string());
+        assert_eq!(
+            engine.output_device_for(OutputBus::Program),
+            Some("Air Card")
+        );
+        // An unset monitor bus stays on the system default, never on air
+        assert_eq!(engine.output_device_for(OutputBus::Monitor), None);
+
+        engine.monitor_device_name = Some("Headphones".to_string());
+        assert_eq!(
+            engine.output_device_for(OutputBus::Monitor),
+            Some("Headphones")
+        );
+        assert_eq!(
+            engine.output_device_for(OutputBus::Program),
+            Some("Air Card")
+        );
+
+        let json = serde_json::to_string(&engine).unwrap();
+        let loaded: Engine = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            loaded.output_device_for(OutputBus::Monitor),
+            Some("Headphones")
+        );
+    }
+
     #[test]
     fn silence_fields_default_correctly() {
         let engine = Engine::new();
@@ -597,6 +1433,7 @@ mod tests {
         let engine: Engine = serde_json::from_str(json).unwrap();
         assert_eq!(engine.silence_threshold, 0.0);
         assert_eq!(engine.silence_duration_secs, 0.0);
+        assert_eq!(engine.silence_threshold_unit, ThresholdUnit::Linear);
     }
 
     #[test]
@@ -630,8 +1467,7 @@ mod tests {
             title: name.into(),
             artist: "X".into(),
             duration: std::time::Duration::new(60, 0),
-            played_duration: None,
-            has_intro: false,
+            ..Default::default()
         }
     }
 
@@ -705,6 +1541,30 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn relink_path_rewrites_matching_prefix() {
+        let path = Path::new(r"D:\Music\Band\song.mp3");
+        let moved = relink_path(path, r"D:\Music", r"E:\Archive", true);
+        assert_eq!(moved, Some(PathBuf::from(r"E:\Archive\Band\song.mp3")));
+        // Trailing separators don't matter
+        let moved = relink_path(Path::new("/old/lib/a.mp3"), "/old/lib/", "/new", false);
+        assert_eq!(moved, Some(PathBuf::from("/new/a.mp3")));
+    }
+
+    #[test]
+    fn relink_path_case_handling_and_component_boundaries() {
+        let path = Path::new(r"d:\music\song.mp3");
+        assert_eq!(
+            relink_path(path, r"D:\Music", r"E:\Archive", true),
+            Some(PathBuf::from(r"E:\Archive\song.mp3"))
+        );
+        assert_eq!(relink_path(path, r"D:\Music", r"E:\Archive", false), None);
+        // A sibling folder sharing the prefix text is not a match
+        let sibling = Path::new(r"D:\Music2\song.mp3");
+        assert_eq!(relink_path(sibling, r"D:\Music", r"E:\Archive", true), None);
+        assert_eq!(relink_path(Path::new("x.mp3"), "/old", "/new", false), None);
+    }
+
     #[test]
     fn copy_paste_across_playlists() {
         let mut engine = Engine::new();
@@ -760,6 +1620,65 @@ mod tests {
         assert_eq!(pl.tracks[3].title, "C");
     }
 
+    #[test]
+    fn insert_track_at_next_goes_after_current() {
+        let mut engine = Engine::new();
+        engine.create_playlist("Main".to_string());
+        engine.set_active("Main").unwrap();
+        let pl = engine.active_playlist_mut().unwrap();
+        pl.tracks = vec![make_track("A"), make_track("B"), make_track("C")];
+        pl.current_index = Some(1);
+
+        let pos = engine
+            .insert_into_active(make_track("X"), InsertPosition::Next)
+            .unwrap();
+        assert_eq!(pos, 2);
+        let pl = engine.active_playlist().unwrap();
+        let titles: Vec<&str> = pl.tracks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["A", "B", "X", "C"]);
+        assert_eq!(pl.current_index, Some(1));
+    }
+
+    #[test]
+    fn insert_track_at_end_appends_without_moving_current() {
+        let mut engine = Engine::new();
+        engine.create_playlist("Main".to_string());
+        engine.set_active("Main").unwrap();
+        let pl = engine.active_playlist_mut().unwrap();
+        pl.tracks = vec![make_track("A"), make_track("B"), make_track("C")];
+        pl.current_index = Some(1);
+
+        let pos = engine
+            .insert_into_active(make_track("X"), InsertPosition::End)
+            .unwrap();
+        assert_eq!(pos, 3);
+        let pl = engine.active_playlist().unwrap();
+        let titles: Vec<&str> = pl.tracks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["A", "B", "C", "X"]);
+        assert_eq!(pl.current_index, Some(1));
+    }
+
+    #[test]
+    fn insert_track_at_index_keeps_current_track() {
+        let mut engine = Engine::new();
+        engine.create_playlist("Main".to_string());
+        engine.set_active("Main").unwrap();
+        let pl = engine.active_playlist_mut().unwrap();
+        pl.tracks = vec![make_track("A"), make_track("B"), make_track("C")];
+        pl.current_index = Some(1);
+
+        engine
+            .insert_into_active(make_track("X"), InsertPosition::Index(0))
+            .unwrap();
+        let pl = engine.active_playlist().unwrap();
+        assert_eq!(pl.tracks[0].title, "X");
+        assert_eq!(pl.current_index, Some(2));
+        assert_eq!(pl.tracks[2].title, "B");
+
+        let past_end = engine.insert_into_active(make_track("Y"), InsertPosition::Index(9));
+        assert!(past_end.is_err());
+    }
+
     #[test]
     fn insert_next_track_no_active_playlist_errors() {
         let mut engine = Engine::new();
@@ -773,6 +1692,7 @@ mod tests {
         let engine = Engine::new();
         assert_eq!(engine.recurring_intro_interval_secs, 0.0);
         assert_eq!(engine.recurring_intro_duck_volume, 0.3);
+        assert_eq!(engine.recurring_intro_duck_ramp_secs, 0.5);
     }
 
     #[test]
@@ -780,10 +1700,12 @@ mod tests {
         let mut engine = Engine::new();
         engine.recurring_intro_interval_secs = 900.0;
         engine.recurring_intro_duck_volume = 0.2;
+        engine.recurring_intro_duck_ramp_secs = 1.5;
         let json = serde_json::to_string(&engine).unwrap();
         let loaded: Engine = serde_json::from_str(&json).unwrap();
         assert_eq!(loaded.recurring_intro_interval_secs, 900.0);
         assert_eq!(loaded.recurring_intro_duck_volume, 0.2);
+        assert_eq!(loaded.recurring_intro_duck_ramp_secs, 1.5);
     }
 
     #[test]
@@ -792,6 +1714,7 @@ mod tests {
         let engine: Engine = serde_json::from_str(json).unwrap();
         assert_eq!(engine.recurring_intro_interval_secs, 0.0);
         assert_eq!(engine.recurring_intro_duck_volume, 0.3);
+        assert_eq!(engine.recurring_intro_duck_ramp_secs, 0.5);
     }
 
     #[test]
@@ -879,8 +1802,7 @@ mod tests {
             title: "Test".into(),
             artist: "Artist".into(),
             duration: std::time::Duration::new(60, 0),
-            played_duration: None,
-            has_intro: false,
+            ..Default::default()
         });
         assert_eq!(engine.active_playlist().unwrap().track_count(), 1);
     }
@@ -917,4 +1839,232 @@ mod tests {
         assert_eq!(engine.rds.port, 10001);
         assert!(engine.rds.messages.is_empty());
     }
+
+    #[test]
+    fn carts_default_empty_when_missing_from_json() {
+        let json = r#"{"playlists":[],"active_playlist_id":null,"next_id":1}"#;
+        let engine: Engine = serde_json::from_str(json).unwrap();
+        assert!(engine.carts.is_empty());
+    }
+
+    #[test]
+    fn add_cart_numbers_in_order() {
+        let mut engine = Engine::new();
+        assert_eq!(engine.add_cart("Laser".into(), "laser.wav".into(), true), 1);
+        assert_eq!(engine.add_cart("Horn".into(), "horn.wav".into(), false), 2);
+        let labels: Vec<&str> = engine.carts.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, vec!["Laser", "Horn"]);
+        assert_eq!(engine.cart(2).unwrap().path, PathBuf::from("horn.wav"));
+        assert!(engine.cart(0).is_none());
+        assert!(engine.cart(3).is_none());
+    }
+
+    #[test]
+    fn remove_cart_shifts_later_slots() {
+        let mut engine = Engine::new();
+        engine.add_cart("A".into(), "a.wav".into(), true);
+        engine.add_cart("B".into(), "b.wav".into(), true);
+        engine.add_cart("C".into(), "c.wav".into(), false);
+
+        let removed = engine.remove_cart(1).unwrap();
+        assert_eq!(removed.label, "A");
+        assert_eq!(engine.carts.len(), 2);
+        assert_eq!(engine.cart(1).unwrap().label, "B");
+        assert_eq!(engine.cart(2).unwrap().label, "C");
+        assert_eq!(engine.carts[1].index, 2);
+        assert!(engine.remove_cart(3).is_err());
+        assert!(engine.remove_cart(0).is_err());
+    }
+
+    #[test]
+    fn cart_mode_follows_overlay_flag() {
+        let mut engine = Engine::new();
+        engine.add_cart("Over".into(), "o.wav".into(), true);
+        engine.add_cart("Break".into(), "b.wav".into(), false);
+        assert_eq!(engine.cart(1).unwrap().mode(), ScheduleMode::Overlay);
+        assert_eq!(engine.cart(2).unwrap().mode(), ScheduleMode::Stop);
+    }
+
+    fn corrupt_copies(dir: &Path) -> Vec<PathBuf> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|p| p.to_string_lossy().contains(".corrupt-"))
+            .collect()
+    }
+
+    #[test]
+    fn load_from_quarantines_corrupt_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("signalflow_state.json");
+        std::fs::write(&path, "{\"playlists\": [ not json").unwrap();
+
+        let engine = Engine::load_from(&path);
+        assert!(engine.playlists.is_empty());
+        assert_eq!(engine.state_path(), Some(path.as_path()));
+        assert!(engine.load_warning.as_deref().unwrap().contains("corrupt"));
+        assert!(!path.exists());
+
+        let copies = corrupt_copies(dir.path());
+        assert_eq!(copies.len(), 1);
+        let name = copies[0].file_name().unwrap().to_string_lossy().to_string();
+        assert!(name.starts_with("signalflow_state.corrupt-"));
+        assert!(name.ends_with(".json"));
+        let kept = std::fs::read_to_string(&copies[0]).unwrap();
+        assert_eq!(kept, "{\"playlists\": [ not json");
+    }
+
+    #[test]
+    fn load_from_unreadable_state_is_never_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        // A directory where the file should be: it exists but can't be read
+        let path = dir.path().join("signalflow_state.json");
+        std::fs::create_dir(&path).unwrap();
+
+        let mut engine = Engine::load_from(&path);
+        let warning = engine.load_warning.clone().unwrap();
+        assert!(warning.contains("could not be read"));
+        assert_eq!(engine.state_path(), None);
+        engine.create_playlist("Main".to_string());
+        engine.save().unwrap();
+        assert!(path.is_dir());
+        assert!(corrupt_copies(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn load_from_valid_state_does_not_quarantine() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("signalflow_state.json");
+        let mut engine = Engine::load_from(&path);
+        assert!(engine.load_warning.is_none());
+        engine.create_playlist("Main".to_string());
+        engine.save().unwrap();
+
+        let loaded = Engine::load_from(&path);
+        assert_eq!(loaded.playlists.len(), 1);
+        assert!(loaded.load_warning.is_none());
+        assert!(path.exists());
+        assert!(corrupt_copies(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn debounced_saves_coalesce_until_flushed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("signalflow_state.json");
+        let mut engine = Engine::load_from(&path);
+        engine.save_debounce_ms = 60_000;
+
+        for i in 0..10 {
+            engine.create_playlist(format!("P{}", i));
+            engine.save().unwrap();
+        }
+        // Ten saves, no writes yet
+        assert!(!path.exists());
+        assert!(engine.has_unsaved_changes());
+        assert!(!engine.flush_if_due().unwrap());
+
+        engine.flush().unwrap();
+        assert!(!engine.has_unsaved_changes());
+        assert_eq!(Engine::read_from(&path).unwrap().playlists.len(), 10);
+
+        // Once the window has passed, the pending burst goes out in one write
+        std::fs::remove_file(&path).unwrap();
+        engine.save_debounce_ms = 20;
+        for i in 0..5 {
+            engine.create_playlist(format!("Q{}", i));
+            engine.save().unwrap();
+        }
+        assert!(!path.exists());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(engine.flush_if_due().unwrap());
+        assert_eq!(Engine::read_from(&path).unwrap().playlists.len(), 15);
+        assert!(!engine.flush_if_due().unwrap());
+    }
+
+    #[test]
+    fn config_saves_skip_the_debounce_unless_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("signalflow_state.json");
+        let mut engine = Engine::load_from(&path);
+        engine.save_debounce_ms = 60_000;
+        engine.create_playlist("Main".to_string());
+        engine.save().unwrap();
+
+        engine.crossfade_secs = 2.0;
+        engine.save_config().unwrap();
+        let saved = Engine::read_from(&path).unwrap();
+        assert_eq!(saved.crossfade_secs, 2.0);
+        // The full-state write also carried the pending playlist edit
+        assert_eq!(saved.playlists.len(), 1);
+        assert!(!engine.has_unsaved_changes());
+
+        engine.save_config_immediately = false;
+        engine.crossfade_secs = 4.0;
+        engine.save_config().unwrap();
+        assert!(engine.has_unsaved_changes());
+        assert_eq!(Engine::read_from(&path).unwrap().crossfade_secs, 2.0);
+    }
+
+    #[test]
+    fn unversioned_state_loads_and_is_stamped_on_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let v1 = r#"{"playlists":[],"active_playlist_id":null,"next_id":1,"crossfade_secs":2.5}"#;
+        std::fs::write(&path, v1).unwrap();
+
+        let engine = Engine::read_from(&path).unwrap();
+        assert_eq!(engine.crossfade_secs, 2.5);
+        assert_eq!(engine.schema_version, SCHEMA_VERSION);
+
+        engine.save().unwrap();
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["schema_version"], SCHEMA_VERSION);
+        assert_eq!(saved["crossfade_secs"], 2.5);
+    }
+
+    /// Sample v1 -> v2 step for the framework tests: `old_name` became
+    /// `new_name`.
+    fn rename_old_name(state: &mut serde_json::Map<String, serde_json::Value>) {
+        if let Some(value) = state.remove("old_name") {
+            state.entry("new_name").or_insert(value);
+        }
+    }
+
+    #[test]
+    fn migrations_move_renamed_fields_and_stamp_version() {
+        let steps: &[Migration] = &[rename_old_name];
+        let mut state = serde_json::json!({"old_name": "Speakers"});
+        migrate_with(steps, &mut state, 1);
+        assert_eq!(state["new_name"], "Speakers");
+        assert!(state.get("old_name").is_none());
+        assert_eq!(state["schema_version"], 2);
+
+        // A value already under the new name wins
+        let mut state = serde_json::json!({"old_name": "Old", "new_name": "New"});
+        migrate_with(steps, &mut state, 1);
+        assert_eq!(state["new_name"], "New");
+    }
+
+    #[test]
+    fn migrate_leaves_current_and_newer_state_alone() {
+        let steps: &[Migration] = &[rename_old_name];
+        let mut state = serde_json::json!({"old_name": "Speakers"});
+        migrate_with(steps, &mut state, 2);
+        assert_eq!(state["old_name"], "Speakers");
+
+        let mut newer = serde_json::json!({"schema_version": SCHEMA_VERSION + 1});
+        migrate(&mut newer, SCHEMA_VERSION + 1);
+        assert_eq!(newer["schema_version"], SCHEMA_VERSION + 1);
+    }
+
+    #[test]
+    fn new_engine_is_current_schema() {
+        assert_eq!(Engine::new().schema_version, SCHEMA_VERSION);
+        let json = r#"{"playlists":[],"active_playlist_id":null,"next_id":1}"#;
+        let engine: Engine = serde_json::from_str(json).unwrap();
+        assert_eq!(engine.schema_version, 1);
+        let out: serde_json::Value = serde_json::to_value(&engine).unwrap();
+        assert_eq!(out["schema_version"], SCHEMA_VERSION);
+    }
 }
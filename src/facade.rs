@@ -0,0 +1,914 @@
+//! SignalFlow — embeddable facade over `AppCore` and the audio thread.
+//!
+//! `AppCore` keeps station state but does not own playback, so every
+//! transport action has two halves: update the core, then tell the audio
+//! thread. The free functions here do both for callers that keep `AppCore`
+//! behind their own lock (the Tauri app); `SignalFlow` owns the core and an
+//! audio output for tools that want to drive the station directly.
+
+use crate::ad_inserter::{AdInsertionResult, RollLengths};
+use crate::app_core::{AppCore, SeekOutcome, TransportData};
+use crate::audio_runtime::{AudioEvent, AudioHandle, spawn_audio_runtime};
+use crate::auto_intro::IntroCue;
+use crate::level_monitor::LevelMonitor;
+use crate::player::{TrackTransition, signon_fade_for};
+use crate::scheduler::{ScheduleAction, ScheduleMode};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// The audio-thread commands transport actions need. Implemented by
+/// `AudioHandle`; tests substitute a recorder.
+pub trait AudioControl {
+    fn play_range(
+        &self,
+        path: PathBuf,
+        level_monitor: LevelMonitor,
+        cue_in: Option<Duration>,
+        cue_out: Option<Duration>,
+        gain: f32,
+    );
+    /// `play_range`, crossfading out of the current track over `fade`.
+    fn crossfade_range(
+        &self,
+        path: PathBuf,
+        level_monitor: LevelMonitor,
+        cue_in: Option<Duration>,
+        cue_out: Option<Duration>,
+        gain: f32,
+        fade: Duration,
+    );
+    /// `play_range`, fading in from silence over `fade` (the sign-on fade).
+    fn fade_in_range(
+        &self,
+        path: PathBuf,
+        level_monitor: LevelMonitor,
+        cue_in: Option<Duration>,
+        cue_out: Option<Duration>,
+        gain: f32,
+        fade: Duration,
+    );
+    /// Report `AudioEvent::ExitPoint` once the track reaches `at` in its
+    /// file (None = only at its end).
+    fn set_exit_point(&self, at: Option<Duration>);
+    /// Fade the playing track out; `TrackFinished` follows.
+    fn fade_out(&self, fade: Duration);
+    /// Play `intro` with the next track sent.
+    fn queue_intro(&self, intro: IntroCue);
+    fn stop(&self);
+    fn pause(&self);
+    fn resume(&self);
+    fn seek(&self, position: Duration);
+}
+
+impl AudioControl for AudioHandle {
+    fn play_range(
+        &self,
+        path: PathBuf,
+        level_monitor: LevelMonitor,
+        cue_in: Option<Duration>,
+        cue_out: Option<Duration>,
+        gain: f32,
+    ) {
+        AudioHandle::play_range(self, path, level_monitor, cue_in, cue_out, gain);
+    }
+
+    fn crossfade_range(
+        &self,
+        path: PathBuf,
+        level_monitor: LevelMonitor,
+        cue_in: Option<Duration>,
+        cue_out: Option<Duration>,
+        gain: f32,
+        fade: Duration,
+    ) {
+        AudioHandle::crossfade_range(self, path, level_monitor, cue_in, cue_out, gain, fade);
+    }
+
+    fn fade_in_range(
+        &self,
+        path: PathBuf,
+        level_monitor: LevelMonitor,
+        cue_in: Option<Duration>,
+        cue_out: Option<Duration>,
+        gain: f32,
+        fade: Duration,
+    ) {
+        AudioHandle::fade_in_range(self, path, level_monitor, cue_in, cue_out, gain, fade);
+    }
+
+    fn set_exit_point(&self, at: Option<Duration>) {
+        AudioHandle::set_exit_point(self, at);
+    }
+
+    fn fade_out(&self, fade: Duration) {
+        AudioHandle::fade_out(self, fade);
+    }
+
+    fn queue_intro(&self, intro: IntroCue) {
+        AudioHandle::queue_intro(self, intro);
+    }
+
+    fn stop(&self) {
+        AudioHandle::stop(self);
+    }
+
+    fn pause(&self) {
+        AudioHandle::pause(self);
+    }
+
+    fn resume(&self) {
+        AudioHandle::resume(self);
+    }
+
+    fn seek(&self, position: Duration) {
+        AudioHandle::seek(self, position);
+    }
+}
+
+/// Send the track the core has just made current to the audio thread, with
+/// its cue range, gain and exit point.
+fn send_current(core: &AppCore, audio: &impl AudioControl, monitor: &LevelMonitor, path: PathBuf) {
+    let (cue_in, cue_out) = core.playing_cue_range();
+    audio.play_range(path, monitor.clone(), cue_in, cue_out, core.playing_gain());
+    audio.set_exit_point(core.exit_point());
+}
+
+/// `send_current` for a track starting cleanly, with its auto-intro if it
+/// has one due (see `AppCore::take_intro`). A non-zero `fade_in` fades the
+/// track in from silence.
+fn start_current(
+    core: &mut AppCore,
+    audio: &impl AudioControl,
+    monitor: &LevelMonitor,
+    path: PathBuf,
+    fade_in: Duration,
+) {
+    if let Some(intro) = core.take_intro() {
+        audio.queue_intro(intro);
+    }
+    if fade_in.is_zero() {
+        send_current(core, audio, monitor, path);
+        return;
+    }
+    let (cue_in, cue_out) = core.playing_cue_range();
+    let gain = core.playing_gain();
+    audio.fade_in_range(path, monitor.clone(), cue_in, cue_out, gain, fade_in);
+    audio.set_exit_point(core.exit_point());
+}
+
+/// `send_current`, crossfading out of the previous track over `fade`.
+fn crossfade_current(
+    core: &AppCore,
+    audio: &impl AudioControl,
+    monitor: &LevelMonitor,
+    path: PathBuf,
+    fade: Duration,
+) {
+    let (cue_in, cue_out) = core.playing_cue_range();
+    let gain = core.playing_gain();
+    audio.crossfade_range(path, monitor.clone(), cue_in, cue_out, gain, fade);
+    audio.set_exit_point(core.exit_point());
+}
+
+/// Play `track_index` (or the current track) of the active playlist.
+/// Starting from silence, the track fades in over the engine's
+/// `signon_fade_secs` (see `player::signon_fade_for`).
+pub fn play(
+    core: &mut AppCore,
+    audio: &impl AudioControl,
+    monitor: &LevelMonitor,
+    track_index: Option<usize>,
+) -> Result<(), String> {
+    let signon = signon_fade_for(core.playback.is_playing, core.engine.signon_fade_secs);
+    let (track_path, ..) = core.prepare_play(track_index)?;
+    start_current(core, audio, monitor, track_path, signon);
+    Ok(())
+}
+
+/// Stop playback. The core is marked stopped before the audio thread hears
+/// about it, so a `TrackFinished` racing the stop does not auto-advance.
+pub fn stop(core: &mut AppCore, audio: &impl AudioControl, monitor: &LevelMonitor) {
+    core.on_stop();
+    audio.stop();
+    monitor.reset();
+}
+
+/// Toggle pause. Returns true if now paused.
+pub fn pause_toggle(core: &mut AppCore, audio: &impl AudioControl) -> Result<bool, String> {
+    let now_paused = core.on_pause_toggle()?;
+    if now_paused {
+        audio.pause();
+    } else {
+        audio.resume();
+    }
+    Ok(now_paused)
+}
+
+/// Stop the current track and start the next one, or crossfade into it
+/// when the engine has `crossfade_on_skip` set and the pair is eligible
+/// (see `AppCore::skip_crossfade`). Returns false when the playlist ended
+/// and nothing was started.
+pub fn skip(
+    core: &mut AppCore,
+    audio: &impl AudioControl,
+    monitor: &LevelMonitor,
+) -> Result<bool, String> {
+    let from = core.playing_track().cloned();
+    let next = core.prepare_skip();
+    let fade = match next {
+        Ok(_) => core.skip_crossfade(from.as_ref()),
+        Err(_) => None,
+    };
+    if fade.is_none() {
+        audio.stop();
+    }
+    match next {
+        Ok((track_path, ..)) => {
+            match fade {
+                Some(fade) => crossfade_current(core, audio, monitor, track_path, fade),
+                None => start_current(core, audio, monitor, track_path, Duration::ZERO),
+            }
+            Ok(true)
+        }
+        Err(ref e) if e == "__end_of_playlist__" => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Stop the current track and start the previous one (see
+/// `AppCore::prepare_prev`). Always a hard cut. If there is nothing to step
+/// back to, the current track keeps playing.
+pub fn prev(
+    core: &mut AppCore,
+    audio: &impl AudioControl,
+    monitor: &LevelMonitor,
+) -> Result<(), String> {
+    let (track_path, ..) = core.prepare_prev()?;
+    audio.stop();
+    start_current(core, audio, monitor, track_path, Duration::ZERO);
+    Ok(())
+}
+
+/// Seek within the current track. Seeking to the very end skips to the
+/// next track, as if it had finished.
+pub fn seek(
+    core: &mut AppCore,
+    audio: &impl AudioControl,
+    monitor: &LevelMonitor,
+    position_secs: f64,
+) -> Result<SeekOutcome, String> {
+    let outcome = core.on_seek(position_secs)?;
+    match outcome {
+        // Cue-sheet segments are offset into the file
+        SeekOutcome::Seeked(pos) => {
+            let (cue_in, _) = core.playing_cue_range();
+            audio.seek(cue_in.unwrap_or(Duration::ZERO) + pos);
+        }
+        SeekOutcome::EndOfTrack => {
+            skip(core, audio, monitor)?;
+        }
+    }
+    Ok(outcome)
+}
+
+/// Restart the track saved in the resume marker at its saved offset.
+pub fn resume(
+    core: &mut AppCore,
+    audio: &impl AudioControl,
+    monitor: &LevelMonitor,
+) -> Result<(), String> {
+    let (track_path, offset) = core.resume_playback()?;
+    let (cue_in, _) = core.playing_cue_range();
+    send_current(core, audio, monitor, track_path);
+    if !offset.is_zero() {
+        audio.seek(cue_in.unwrap_or(Duration::ZERO) + offset);
+    }
+    Ok(())
+}
+
+/// Handle the audio thread reporting the end of a track: start the next
+/// one unless playback was stopped meanwhile, the playlist ended, or it is
+/// a live-assist playlist. Returns true if a track was started. Other
+/// errors stop playback and are logged.
+pub fn track_finished(
+    core: &mut AppCore,
+    audio: &impl AudioControl,
+    monitor: &LevelMonitor,
+) -> Result<bool, String> {
+    if !core.playback.is_playing {
+        return Ok(false);
+    }
+    match core.on_track_finished() {
+        Ok((track_path, ..)) => {
+            start_current(core, audio, monitor, track_path, Duration::ZERO);
+            Ok(true)
+        }
+        Err(ref e) if e == "__end_of_playlist__" || e == "__manual_advance__" => Ok(false),
+        Err(e) => {
+            core.on_stop();
+            core.log("error", format!("Auto-advance failed: {}", e));
+            Err(e)
+        }
+    }
+}
+
+/// Handle the audio thread reporting that the playing track reached its
+/// exit point (see `AppCore::exit_point`): crossfade into the next track,
+/// or fade this one out so the next starts cleanly on `track_finished`,
+/// as `AppCore::exit_transition` decides now. Returns true if a track was
+/// started. Other errors stop playback and are logged.
+pub fn track_exit(
+    core: &mut AppCore,
+    audio: &impl AudioControl,
+    monitor: &LevelMonitor,
+) -> Result<bool, String> {
+    if !core.playback.is_playing || core.playback.is_paused {
+        return Ok(false);
+    }
+    let fade = core.crossfade_duration();
+    match core.exit_transition() {
+        TrackTransition::Sequential => Ok(false),
+        TrackTransition::FadeOutOnly => {
+            audio.fade_out(fade);
+            Ok(false)
+        }
+        TrackTransition::Crossfade => match core.on_track_finished() {
+            Ok((track_path, ..)) => {
+                crossfade_current(core, audio, monitor, track_path, fade);
+                Ok(true)
+            }
+            Err(ref e) if e == "__end_of_playlist__" || e == "__manual_advance__" => Ok(false),
+            Err(e) => {
+                audio.stop();
+                core.on_stop();
+                core.log("error", format!("Auto-advance failed: {}", e));
+                Err(e)
+            }
+        },
+    }
+}
+
+/// Queue an ad break (see `AppCore::insert_ad_break`). When the result
+/// asks to interrupt the current track (`AdInsertTiming::Immediate`), the
+/// playing track is skipped so the roll starts now; otherwise the roll
+/// follows it.
+pub fn insert_ad_break(
+    core: &mut AppCore,
+    audio: &impl AudioControl,
+    monitor: &LevelMonitor,
+    lengths: &RollLengths,
+) -> Result<AdInsertionResult, String> {
+    let result = core.insert_ad_break(lengths)?;
+    if result.interrupt_current && core.playback.is_playing {
+        skip(core, audio, monitor)?;
+    }
+    Ok(result)
+}
+
+/// Fire schedule event `id` (see `AppCore::fire_schedule_event`). A stop
+/// event that fires during a pause ends the paused track here, audio and
+/// all; playing the returned action is left to the caller.
+pub fn fire_schedule_event(
+    core: &mut AppCore,
+    audio: &impl AudioControl,
+    monitor: &LevelMonitor,
+    id: u32,
+) -> Result<ScheduleAction, String> {
+    let was_paused = core.playback.is_playing && core.playback.is_paused;
+    let action = core.fire_schedule_event(id)?;
+    let stops = matches!(
+        action,
+        ScheduleAction::Play {
+            mode: ScheduleMode::Stop,
+            ..
+        }
+    );
+    if was_paused && stops {
+        stop(core, audio, monitor);
+    }
+    Ok(action)
+}
+
+/// Handle the audio thread failing to play a track.
+pub fn play_error(core: &mut AppCore, error: &str) {
+    core.on_stop();
+    core.log("error", format!("Audio error: {}", error));
+}
+
+/// An `AppCore` together with the audio output it drives.
+pub struct SignalFlow<A: AudioControl = AudioHandle> {
+    pub core: AppCore,
+    audio: A,
+    level_monitor: LevelMonitor,
+}
+
+impl SignalFlow<AudioHandle> {
+    /// Load the station from `state_path` and start an audio thread on the
+    /// configured output device. Audio events arrive on the returned
+    /// receiver; pass each one to `handle_event` so tracks advance.
+    pub fn open(state_path: &Path) -> (Self, mpsc::Receiver<AudioEvent>) {
+        let level_monitor = LevelMonitor::new();
+        let mut core = AppCore::new(state_path);
+        core.heartbeat = level_monitor.heartbeat();
+        let config = core.get_config();
+        let (tx, rx) = mpsc::channel();
+        let audio = spawn_audio_runtime(config.output_device_name, move |event| {
+            let _ = tx.send(event);
+        });
+        audio.set_buffer_ms(config.audio_buffer_ms);
+        audio.set_output_rate(config.output_sample_rate);
+        audio.set_agc(core.agc_config());
+        (SignalFlow::new(core, audio, level_monitor), rx)
+    }
+}
+
+impl<A: AudioControl> SignalFlow<A> {
+    pub fn new(core: AppCore, audio: A, level_monitor: LevelMonitor) -> Self {
+        SignalFlow {
+            core,
+            audio,
+            level_monitor,
+        }
+    }
+
+    pub fn audio(&self) -> &A {
+        &self.audio
+    }
+
+    pub fn level_monitor(&self) -> &LevelMonitor {
+        &self.level_monitor
+    }
+
+    /// See [`play`].
+    pub fn play(&mut self, track_index: Option<usize>) -> Result<(), String> {
+        play(
+            &mut self.core,
+            &self.audio,
+            &self.level_monitor,
+            track_index,
+        )
+    }
+
+    /// See [`stop`].
+    pub fn stop(&mut self) {
+        stop(&mut self.core, &self.audio, &self.level_monitor);
+    }
+
+    /// See [`pause_toggle`].
+    pub fn pause_toggle(&mut self) -> Result<bool, String> {
+        pause_toggle(&mut self.core, &self.audio)
+    }
+
+    /// See [`skip`].
+    pub fn skip(&mut self) -> Result<bool, String> {
+        skip(&mut self.core, &self.audio, &self.level_monitor)
+    }
+
+    /// See [`prev`].
+    pub fn prev(&mut self) -> Result<(), String> {
+        prev(&mut self.core, &self.audio, &self.level_monitor)
+    }
+
+    /// See [`seek`].
+    pub fn seek(&mut self, position_secs: f64) -> Result<SeekOutcome, String> {
+        seek(
+            &mut self.core,
+            &self.audio,
+            &self.level_monitor,
+            position_secs,
+        )
+    }
+
+    /// See [`resume`].
+    pub fn resume(&mut self) -> Result<(), String> {
+        resume(&mut self.core, &self.audio, &self.level_monitor)
+    }
+
+    /// See [`insert_ad_break`]. The roll's files are measured first.
+    pub fn insert_ad_break(&mut self) -> Result<AdInsertionResult, String> {
+        let lengths = RollLengths::measure(&self.core.ad_break_files());
+        insert_ad_break(&mut self.core, &self.audio, &self.level_monitor, &lengths)
+    }
+
+    /// See [`fire_schedule_event`].
+    pub fn fire_schedule_event(&mut self, id: u32) -> Result<ScheduleAction, String> {
+        fire_schedule_event(&mut self.core, &self.audio, &self.level_monitor, id)
+    }
+
+    pub fn status(&self) -> TransportData {
+        self.core.get_transport_state()
+    }
+
+    /// Apply an event from the audio thread: advance on `TrackFinished`
+    /// (or `ExitPoint`, see [`track_exit`]), stop on `PlayError`, take up
+    /// the latency of a newly opened output. Other events need no action.
+    pub fn handle_event(&mut self, event: &AudioEvent) -> Result<(), String> {
+        match event {
+            AudioEvent::ExitPoint => {
+                track_exit(&mut self.core, &self.audio, &self.level_monitor)?;
+            }
+            AudioEvent::TrackFinished => {
+                track_finished(&mut self.core, &self.audio, &self.level_monitor)?;
+            }
+            AudioEvent::PlayError(e) => play_error(&mut self.core, e),
+            AudioEvent::OutputLatency(latency) => self.core.set_output_latency(*latency),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ad_scheduler::AdConfig;
+    use crate::app_core::AdBreakData;
+    use std::cell::RefCell;
+
+    #[derive(Debug, PartialEq)]
+    enum Sent {
+        Play(PathBuf),
+        Crossfade(PathBuf, Duration),
+        FadeIn(PathBuf, Duration),
+        ExitPoint(Duration),
+        FadeOut(Duration),
+        Intro(PathBuf),
+        Stop,
+        Pause,
+        Resume,
+        Seek(Duration),
+    }
+
+    /// Records the commands a transport action sends.
+    #[derive(Default)]
+    struct MockAudio(RefCell<Vec<Sent>>);
+
+    impl MockAudio {
+        fn take(&self) -> Vec<Sent> {
+            self.0.borrow_mut().drain(..).collect()
+        }
+    }
+
+    impl AudioControl for MockAudio {
+        fn play_range(
+            &self,
+            path: PathBuf,
+            _level_monitor: LevelMonitor,
+            _cue_in: Option<Duration>,
+            _cue_out: Option<Duration>,
+            _gain: f32,
+        ) {
+            self.0.borrow_mut().push(Sent::Play(path));
+        }
+        fn crossfade_range(
+            &self,
+            path: PathBuf,
+            _level_monitor: LevelMonitor,
+            _cue_in: Option<Duration>,
+            _cue_out: Option<Duration>,
+            _gain: f32,
+            fade: Duration,
+        ) {
+            self.0.borrow_mut().push(Sent::Crossfade(path, fade));
+        }
+        fn fade_in_range(
+            &self,
+            path: PathBuf,
+            _level_monitor: LevelMonitor,
+            _cue_in: Option<Duration>,
+            _cue_out: Option<Duration>,
+            _gain: f32,
+            fade: Duration,
+        ) {
+            self.0.borrow_mut().push(Sent::FadeIn(path, fade));
+        }
+        fn set_exit_point(&self, at: Option<Duration>) {
+            // None follows every plain play, so only set points are kept
+            if let Some(at) = at {
+                self.0.borrow_mut().push(Sent::ExitPoint(at));
+            }
+        }
+        fn fade_out(&self, fade: Duration) {
+            self.0.borrow_mut().push(Sent::FadeOut(fade));
+        }
+        fn queue_intro(&self, intro: IntroCue) {
+            self.0.borrow_mut().push(Sent::Intro(intro.path));
+        }
+        fn stop(&self) {
+            self.0.borrow_mut().push(Sent::Stop);
+        }
+        fn pause(&self) {
+            self.0.borrow_mut().push(Sent::Pause);
+        }
+        fn resume(&self) {
+            self.0.borrow_mut().push(Sent::Resume);
+        }
+        fn seek(&self, position: Duration) {
+            self.0.borrow_mut().push(Sent::Seek(position));
+        }
+    }
+
+    fn make_track(name: &str) -> crate::track::Track {
+        crate::track::Track {
+            path: format!("{}.mp3", name).into(),
+            title: name.into(),
+            artist: name.into(),
+            duration: Duration::from_secs(180),
+            ..Default::default()
+        }
+    }
+
+    fn make_flow(tracks: &[&str]) -> SignalFlow<MockAudio> {
+        let mut core = AppCore::new_test();
+        core.create_playlist("Main".to_string()).unwrap();
+        core.set_active_playlist("Main").unwrap();
+        let pl = core.engine.find_playlist_mut("Main").unwrap();
+        pl.tracks.extend(tracks.iter().map(|name| make_track(name)));
+        SignalFlow::new(core, MockAudio::default(), LevelMonitor::new())
+    }
+
+    #[test]
+    fn play_updates_state_and_dispatches() {
+        let mut flow = make_flow(&["a", "b"]);
+        flow.play(Some(1)).unwrap();
+        assert!(flow.core.playback.is_playing);
+        assert_eq!(flow.core.playback.track_index, Some(1));
+        assert_eq!(flow.status().track_title.as_deref(), Some("b"));
+        assert_eq!(flow.audio().take(), vec![Sent::Play("b.mp3".into())]);
+
+        assert!(flow.play(Some(5)).is_err());
+        assert!(flow.audio().take().is_empty());
+    }
+
+    #[test]
+    fn play_from_silence_fades_in_over_the_signon_fade() {
+        let mut flow = make_flow(&["a", "b"]);
+        flow.core.set_signon_fade(2.0).unwrap();
+        flow.play(Some(0)).unwrap();
+        let signed_on = vec![Sent::FadeIn("a.mp3".into(), Duration::from_secs(2))];
+        assert_eq!(flow.audio().take(), signed_on);
+
+        // Already on air: later tracks start without it
+        flow.play(Some(1)).unwrap();
+        assert_eq!(flow.audio().take(), vec![Sent::Play("b.mp3".into())]);
+        flow.stop();
+        flow.audio().take();
+
+        flow.play(Some(0)).unwrap();
+        assert_eq!(flow.audio().take(), signed_on);
+    }
+
+    #[test]
+    fn skip_stops_then_plays_next() {
+        let mut flow = make_flow(&["a", "b"]);
+        flow.play(Some(0)).unwrap();
+        flow.audio().take();
+
+        assert!(flow.skip().unwrap());
+        assert_eq!(flow.core.playback.track_index, Some(1));
+        assert_eq!(
+            flow.audio().take(),
+            vec![Sent::Stop, Sent::Play("b.mp3".into())]
+        );
+
+        // Past the last track the default action stops
+        assert!(!flow.skip().unwrap());
+        assert!(!flow.core.playback.is_playing);
+        assert_eq!(flow.audio().take(), vec![Sent::Stop]);
+    }
+
+    #[test]
+    fn failed_prev_leaves_the_current_track_playing() {
+        let mut flow = make_flow(&["a"]);
+        flow.play(Some(0)).unwrap();
+        flow.audio().take();
+
+        let pl = flow.core.engine.find_playlist_mut("Main").unwrap();
+        pl.tracks.clear();
+        assert!(flow.prev().is_err());
+        assert!(flow.audio().take().is_empty());
+        assert!(flow.core.playback.is_playing);
+    }
+
+    #[test]
+    fn ad_break_interrupts_the_current_track_only_when_immediate() {
+        let dir = tempfile::tempdir().unwrap();
+        let spot = dir.path().join("spot.wav");
+        crate::test_audio::write_silence(&spot, 1);
+        let mut flow = make_flow(&["a", "b"]);
+        let ad = AdConfig::new("Spot".into(), spot.clone());
+        flow.core.engine.ads.push(ad);
+        flow.play(Some(0)).unwrap();
+        flow.audio().take();
+
+        // After the current track: queued behind it, nothing sent to audio
+        let queued = AdBreakData::from_result(&flow.insert_ad_break().unwrap());
+        assert_eq!(queued.ads[0].name, "Spot");
+        assert!((queued.ads[0].duration_secs - 1.0).abs() < 0.01);
+        assert!((queued.total_secs - 1.0).abs() < 0.01);
+        assert_eq!(flow.core.playback.track_index, Some(0));
+        assert!(flow.audio().take().is_empty());
+
+        flow.core.set_ad_insert_timing("immediate").unwrap();
+        let result = flow.insert_ad_break().unwrap();
+        assert!(result.interrupt_current);
+        assert_eq!(flow.core.playback.track_index, Some(1));
+        assert_eq!(flow.audio().take(), vec![Sent::Stop, Sent::Play(spot)]);
+    }
+
+    #[test]
+    fn skip_crossfades_when_enabled() {
+        let mut flow = make_flow(&["a", "b"]);
+        flow.core.set_crossfade(3.0).unwrap();
+        flow.core.set_crossfade_on_skip(true).unwrap();
+        flow.play(Some(0)).unwrap();
+        flow.audio().take();
+
+        assert!(flow.skip().unwrap());
+        assert_eq!(flow.core.playback.track_index, Some(1));
+        assert_eq!(
+            flow.audio().take(),
+            vec![Sent::Crossfade("b.mp3".into(), Duration::from_secs(3))]
+        );
+
+        // Nothing to fade into at the end: stop as before
+        assert!(!flow.skip().unwrap());
+        assert_eq!(flow.audio().take(), vec![Sent::Stop]);
+    }
+
+    #[test]
+    fn exit_point_crossfades_into_next_track() {
+        let mut flow = make_flow(&["a", "b", "c"]);
+        flow.core.set_crossfade(3.0).unwrap();
+        flow.play(Some(0)).unwrap();
+        let exit = Sent::ExitPoint(Duration::from_secs(177));
+        assert_eq!(flow.audio().take(), vec![Sent::Play("a.mp3".into()), exit]);
+
+        flow.handle_event(&AudioEvent::ExitPoint).unwrap();
+        assert_eq!(flow.core.playback.track_index, Some(1));
+        let fade = Duration::from_secs(3);
+        let exit = Sent::ExitPoint(Duration::from_secs(177));
+        assert_eq!(
+            flow.audio().take(),
+            vec![Sent::Crossfade("b.mp3".into(), fade), exit]
+        );
+    }
+
+    #[test]
+    fn exit_point_fades_out_before_a_clean_start() {
+        let mut flow = make_flow(&["a", "b"]);
+        flow.core.set_crossfade(3.0).unwrap();
+        let core = &mut flow.core;
+        core.set_track_no_crossfade_in("Main", 1, true).unwrap();
+        flow.play(Some(0)).unwrap();
+        flow.audio().take();
+
+        flow.handle_event(&AudioEvent::ExitPoint).unwrap();
+        assert_eq!(flow.core.playback.track_index, Some(0));
+        assert_eq!(
+            flow.audio().take(),
+            vec![Sent::FadeOut(Duration::from_secs(3))]
+        );
+
+        // The next track starts once the fade-out has run down
+        flow.handle_event(&AudioEvent::TrackFinished).unwrap();
+        assert_eq!(flow.audio().take(), vec![Sent::Play("b.mp3".into())]);
+    }
+
+    #[test]
+    fn lecture_transitions_play_to_the_end() {
+        let mut flow = make_flow(&["a", "Rabbi"]);
+        flow.core.set_crossfade(3.0).unwrap();
+        flow.core.set_no_crossfade_for_lectures(true).unwrap();
+        flow.play(Some(0)).unwrap();
+        assert_eq!(flow.audio().take(), vec![Sent::Play("a.mp3".into())]);
+    }
+
+    #[test]
+    fn clean_starts_queue_the_artist_intro_once_per_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let intro = dir.path().join("Adele.mp3");
+        std::fs::write(&intro, b"intro").unwrap();
+        let mut flow = make_flow(&["Adele", "Beatles", "Adele"]);
+        let folder = dir.path().to_string_lossy().into_owned();
+        flow.core.set_intros_folder(Some(folder)).unwrap();
+        flow.core.set_intro_once_per_session(true).unwrap();
+
+        flow.play(Some(0)).unwrap();
+        assert_eq!(
+            flow.audio().take(),
+            vec![Sent::Intro(intro.clone()), Sent::Play("Adele.mp3".into())]
+        );
+        // Beatles has no intro; Adele's already played this session
+        flow.skip().unwrap();
+        flow.skip().unwrap();
+        assert_eq!(
+            flow.audio().take(),
+            vec![
+                Sent::Stop,
+                Sent::Play("Beatles.mp3".into()),
+                Sent::Stop,
+                Sent::Play("Adele.mp3".into()),
+            ]
+        );
+
+        // Playing again after a stop starts a new session
+        flow.stop();
+        flow.play(Some(0)).unwrap();
+        assert_eq!(
+            flow.audio().take(),
+            vec![
+                Sent::Stop,
+                Sent::Intro(intro),
+                Sent::Play("Adele.mp3".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn skip_into_no_crossfade_in_track_hard_cuts() {
+        let mut flow = make_flow(&["a", "b"]);
+        flow.core.set_crossfade(3.0).unwrap();
+        flow.core.set_crossfade_on_skip(true).unwrap();
+        let core = &mut flow.core;
+        core.set_track_no_crossfade_in("Main", 1, true).unwrap();
+        flow.play(Some(0)).unwrap();
+        flow.audio().take();
+
+        assert!(flow.skip().unwrap());
+        assert_eq!(
+            flow.audio().take(),
+            vec![Sent::Stop, Sent::Play("b.mp3".into())]
+        );
+    }
+
+    #[test]
+    fn prev_stops_then_plays_previous() {
+        let mut flow = make_flow(&["a", "b", "c"]);
+        flow.play(Some(2)).unwrap();
+        flow.audio().take();
+
+        flow.prev().unwrap();
+        assert_eq!(flow.core.playback.track_index, Some(1));
+        assert_eq!(
+            flow.audio().take(),
+            vec![Sent::Stop, Sent::Play("b.mp3".into())]
+        );
+    }
+
+    #[test]
+    fn stop_event_during_pause_stops_the_audio() {
+        let mut flow = make_flow(&["a"]);
+        flow.core.set_paused_event_handling("play-over").unwrap();
+        let id = flow
+            .core
+            .add_schedule_event("12:00", "stop", "news.mp3", None, None, None)
+            .unwrap();
+        flow.play(None).unwrap();
+        assert!(flow.pause_toggle().unwrap());
+        flow.audio().take();
+
+        let action = flow.fire_schedule_event(id).unwrap();
+        assert!(matches!(action, ScheduleAction::Play { .. }));
+        assert!(!flow.core.playback.is_playing && !flow.core.playback.is_paused);
+        assert_eq!(flow.audio().take(), vec![Sent::Stop]);
+    }
+
+    #[test]
+    fn stop_pause_and_seek_dispatch() {
+        let mut flow = make_flow(&["a"]);
+        flow.play(None).unwrap();
+        assert!(flow.pause_toggle().unwrap());
+        assert!(!flow.pause_toggle().unwrap());
+        flow.seek(30.0).unwrap();
+        flow.stop();
+        assert!(!flow.core.playback.is_playing);
+        assert_eq!(
+            flow.audio().take(),
+            vec![
+                Sent::Play("a.mp3".into()),
+                Sent::Pause,
+                Sent::Resume,
+                Sent::Seek(Duration::from_secs(30)),
+                Sent::Stop,
+            ]
+        );
+        assert!(flow.pause_toggle().is_err());
+    }
+
+    #[test]
+    fn track_finished_after_stop_does_not_advance() {
+        let mut flow = make_flow(&["a", "b"]);
+        flow.play(Some(0)).unwrap();
+        flow.stop();
+        flow.audio().take();
+        flow.handle_event(&AudioEvent::TrackFinished).unwrap();
+        assert!(flow.audio().take().is_empty());
+
+        flow.play(Some(0)).unwrap();
+        flow.audio().take();
+        flow.handle_event(&AudioEvent::TrackFinished).unwrap();
+        assert_eq!(flow.audio().take(), vec![Sent::Play("b.mp3".into())]);
+    }
+}
@@ -1,22 +1,84 @@
 use rodio::Source;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use serde::Serialize;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Shared atomic storing when audio last advanced (ms since the Unix epoch,
+/// 0 = never). Bumped by `LevelSource` once per analysis window, so it goes
+/// stale when output stalls, pauses or stops.
+#[derive(Clone, Default)]
+pub struct AudioHeartbeat {
+    last_frame_ms: Arc<AtomicU64>,
+}
+
+impl AudioHeartbeat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that audio advanced now.
+    pub fn beat(&self) {
+        self.beat_at(SystemTime::now());
+    }
+
+    /// Record that audio advanced at `at`.
+    pub fn beat_at(&self, at: SystemTime) {
+        self.last_frame_ms.store(epoch_ms(at), Ordering::Relaxed);
+    }
+
+    /// When audio last advanced, if ever.
+    pub fn last_frame(&self) -> Option<SystemTime> {
+        match self.last_frame_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(UNIX_EPOCH + Duration::from_millis(ms)),
+        }
+    }
+
+    /// How long before `now` audio last advanced (None = never).
+    pub fn age_at(&self, now: SystemTime) -> Option<Duration> {
+        self.last_frame()
+            .map(|last| now.duration_since(last).unwrap_or(Duration::ZERO))
+    }
+
+    /// Whether audio advanced within `max_stale` of `now`.
+    pub fn is_fresh_at(&self, now: SystemTime, max_stale: Duration) -> bool {
+        self.age_at(now).is_some_and(|age| age <= max_stale)
+    }
+}
+
+fn epoch_ms(at: SystemTime) -> u64 {
+    at.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
 
 /// Shared atomic storing the current audio RMS level as f32 bits.
 /// Updated by `LevelSource` on the audio thread, read by IPC on the main thread.
 #[derive(Clone)]
 pub struct LevelMonitor {
     level: Arc<AtomicU32>,
+    heartbeat: AudioHeartbeat,
 }
 
 impl LevelMonitor {
     pub fn new() -> Self {
+        Self::with_heartbeat(AudioHeartbeat::new())
+    }
+
+    /// A monitor whose sources also bump `heartbeat` as audio plays.
+    pub fn with_heartbeat(heartbeat: AudioHeartbeat) -> Self {
         LevelMonitor {
             level: Arc::new(AtomicU32::new(0)),
+            heartbeat,
         }
     }
 
+    /// The on-air heartbeat shared with this monitor.
+    pub fn heartbeat(&self) -> AudioHeartbeat {
+        self.heartbeat.clone()
+    }
+
     /// Get the current RMS level (0.0–1.0+).
     pub fn level(&self) -> f32 {
         f32::from_bits(self.level.load(Ordering::Relaxed))
@@ -32,6 +94,78 @@ impl LevelMonitor {
     }
 }
 
+/// Change reported by `DeadAirWatchdog::update`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeadAirEvent {
+    /// Output has been below the threshold for the whole alarm duration.
+    Tripped,
+    /// Audio came back (or playback stopped) after the alarm tripped.
+    Cleared,
+}
+
+/// Raises an alarm when the program output stays below `threshold` (linear
+/// RMS) for `after` while playback is supposed to be running. Dips shorter
+/// than `after` don't trip it. Feed it the monitor level periodically.
+#[derive(Debug, Clone)]
+pub struct DeadAirWatchdog {
+    threshold: f32,
+    after: Duration,
+    silent_since: Option<Instant>,
+    tripped: bool,
+}
+
+impl DeadAirWatchdog {
+    /// `after` of zero disables the alarm.
+    pub fn new(threshold: f32, after: Duration) -> Self {
+        DeadAirWatchdog {
+            threshold,
+            after,
+            silent_since: None,
+            tripped: false,
+        }
+    }
+
+    /// Change the threshold and duration, restarting any silence in progress.
+    pub fn configure(&mut self, threshold: f32, after: Duration) {
+        if self.threshold != threshold || self.after != after {
+            self.threshold = threshold;
+            self.after = after;
+            self.silent_since = None;
+        }
+    }
+
+    /// Whether the alarm is currently raised.
+    pub fn is_tripped(&self) -> bool {
+        self.tripped
+    }
+
+    /// Record the output `level` at `now`. `playing` is false while stopped
+    /// or paused, when silence is expected. Returns the alarm's transition,
+    /// if any.
+    pub fn update(&mut self, level: f32, playing: bool, now: Instant) -> Option<DeadAirEvent> {
+        if !playing || self.after.is_zero() || level >= self.threshold {
+            self.silent_since = None;
+            return self.clear();
+        }
+        let since = *self.silent_since.get_or_insert(now);
+        if !self.tripped && now.duration_since(since) >= self.after {
+            self.tripped = true;
+            return Some(DeadAirEvent::Tripped);
+        }
+        None
+    }
+
+    fn clear(&mut self) -> Option<DeadAirEvent> {
+        if self.tripped {
+            self.tripped = false;
+            Some(DeadAirEvent::Cleared)
+        } else {
+            None
+        }
+    }
+}
+
 /// A Source wrapper that measures RMS amplitude and updates a `LevelMonitor`.
 /// Passes all samples through unchanged.
 pub struct LevelSource<S> {
@@ -80,6 +214,7 @@ where
         if self.window_pos >= self.window_size {
             let rms = (self.window_sum_sq / self.window_size as f64).sqrt() as f32;
             self.monitor.set_level(rms);
+            self.monitor.heartbeat.beat();
             self.window_sum_sq = 0.0;
             self.window_pos = 0;
         }
@@ -220,4 +355,89 @@ mod tests {
         assert_eq!(wrapped.sample_rate(), 44100);
         assert_eq!(wrapped.channels(), 2);
     }
+
+    #[test]
+    fn heartbeat_freshness_tracks_age() {
+        let heartbeat = AudioHeartbeat::new();
+        let now = SystemTime::now();
+        assert!(!heartbeat.is_fresh_at(now, Duration::from_secs(5)));
+
+        heartbeat.beat_at(now - Duration::from_secs(2));
+        assert!(heartbeat.is_fresh_at(now, Duration::from_secs(5)));
+
+        heartbeat.beat_at(now - Duration::from_secs(30));
+        assert!(!heartbeat.is_fresh_at(now, Duration::from_secs(5)));
+        let age = heartbeat.age_at(now).unwrap();
+        assert!((age.as_secs_f64() - 30.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn dead_air_trips_on_sustained_silence_while_playing() {
+        let mut watchdog = DeadAirWatchdog::new(0.01, Duration::from_secs(10));
+        let t0 = Instant::now();
+        let at = |secs: u64| t0 + Duration::from_secs(secs);
+        assert_eq!(watchdog.update(0.2, true, at(0)), None);
+        assert_eq!(watchdog.update(0.0, true, at(1)), None);
+        assert_eq!(watchdog.update(0.001, true, at(8)), None);
+        assert_eq!(
+            watchdog.update(0.0, true, at(11)),
+            Some(DeadAirEvent::Tripped)
+        );
+        // Reported once, not on every poll
+        assert_eq!(watchdog.update(0.0, true, at(15)), None);
+        assert!(watchdog.is_tripped());
+        assert_eq!(
+            watchdog.update(0.3, true, at(16)),
+            Some(DeadAirEvent::Cleared)
+        );
+        assert!(!watchdog.is_tripped());
+    }
+
+    #[test]
+    fn dead_air_ignores_brief_dips() {
+        let mut watchdog = DeadAirWatchdog::new(0.01, Duration::from_secs(10));
+        let t0 = Instant::now();
+        let at = |secs: u64| t0 + Duration::from_secs(secs);
+        // Two 6 s gaps separated by audio never add up to an alarm
+        for (secs, level) in [(0, 0.0), (6, 0.0), (7, 0.2), (8, 0.0), (14, 0.0), (15, 0.2)] {
+            assert_eq!(watchdog.update(level, true, at(secs)), None);
+        }
+        assert!(!watchdog.is_tripped());
+    }
+
+    #[test]
+    fn dead_air_ignores_silence_while_stopped_or_disabled() {
+        let mut watchdog = DeadAirWatchdog::new(0.01, Duration::from_secs(10));
+        let t0 = Instant::now();
+        assert_eq!(watchdog.update(0.0, false, t0), None);
+        let later = t0 + Duration::from_secs(60);
+        assert_eq!(watchdog.update(0.0, false, later), None);
+
+        // Stopping clears a raised alarm
+        watchdog.update(0.0, true, t0);
+        assert_eq!(
+            watchdog.update(0.0, true, later),
+            Some(DeadAirEvent::Tripped)
+        );
+        let stopped = later + Duration::from_secs(1);
+        assert_eq!(
+            watchdog.update(0.0, false, stopped),
+            Some(DeadAirEvent::Cleared)
+        );
+
+        let mut disabled = DeadAirWatchdog::new(0.01, Duration::ZERO);
+        assert_eq!(disabled.update(0.0, true, t0), None);
+        assert_eq!(disabled.update(0.0, true, later), None);
+    }
+
+    #[test]
+    fn level_source_bumps_heartbeat_but_reset_does_not() {
+        let monitor = LevelMonitor::new();
+        monitor.reset();
+        assert!(monitor.heartbeat().last_frame().is_none());
+        let wrapped = LevelSource::new(TestSource::new(vec![0.1; 100], 1000, 1), monitor.clone());
+        let _: Vec<f32> = wrapped.collect();
+        let heartbeat = monitor.heartbeat();
+        assert!(heartbeat.is_fresh_at(SystemTime::now(), Duration::from_secs(5)));
+    }
 }
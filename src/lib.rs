@@ -3,6 +3,7 @@
 //! All audio, playlist, and scheduling logic lives here.
 //! The CLI and future Tauri GUI consume this crate.
 
+pub mod agc;
 pub mod app_core;
 pub mod audio_runtime;
 pub mod ad_inserter;
@@ -10,15 +11,28 @@ pub mod ad_logger;
 pub mod ad_report;
 pub mod ad_scheduler;
 pub mod auto_intro;
+pub mod clock;
+pub mod config_bundle;
+pub mod crossfade_preview;
+pub mod cue;
+pub mod date_tokens;
 pub mod engine;
+pub mod facade;
 pub mod lecture_detector;
 pub mod level_monitor;
+pub mod musical_key;
 pub mod now_playing;
 pub mod player;
+pub mod program_bus;
 pub mod playlist;
 pub mod scheduler;
 pub mod silence;
 pub mod track;
 pub mod rds;
+pub mod resume;
 pub mod waveform;
 pub mod audio_editor;
+pub mod channel_map;
+
+#[cfg(test)]
+mod test_audio;
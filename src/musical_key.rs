@@ -0,0 +1,252 @@
+//! Musical key of a track, for harmonic (Camelot wheel) mixing.
+//!
+//! Keys come from the `INITIALKEY`/`TKEY` tag when present (see
+//! `Track::from_path`). Untagged files can be analysed with `estimate_key`,
+//! which matches an averaged chroma vector against the Krumhansl–Kessler key
+//! profiles.
+
+use rodio::{Decoder, Source};
+use serde::Serialize;
+use std::f32::consts::PI;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Major key names by pitch class (C = 0), spelled the way DJ software
+/// usually shows them.
+const MAJOR_NAMES: [&str; 12] = [
+    "C", "Db", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B",
+];
+
+/// Minor key names by pitch class.
+const MINOR_NAMES: [&str; 12] = [
+    "Cm", "C#m", "Dm", "Ebm", "Em", "Fm", "F#m", "Gm", "G#m", "Am", "Bbm", "Bm",
+];
+
+/// Krumhansl–Kessler probe-tone profiles, tonic first.
+const MAJOR_PROFILE: [f32; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+const MINOR_PROFILE: [f32; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+
+/// Decimated analysis rate is the file rate divided by this.
+const DECIMATE: usize = 4;
+/// Samples (after decimation) per chroma frame.
+const FRAME_LEN: usize = 4096;
+/// Only the first few minutes are analysed.
+const MAX_ANALYSIS_SECS: usize = 120;
+/// MIDI notes folded into the chroma vector (C3..B6).
+const NOTE_RANGE: std::ops::Range<u8> = 48..96;
+
+/// Result of `estimate_key`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeyAnalysis {
+    /// Key name, e.g. "Am" or "F#".
+    pub key: String,
+    /// The same key on the Camelot wheel, e.g. "8A".
+    pub camelot: Option<String>,
+}
+
+/// Parse a key name ("Am", "A minor", "C#", "Bb major", "Ebm") into its
+/// pitch class and whether it is minor.
+fn parse_key(key: &str) -> Option<(u8, bool)> {
+    let key = key.trim();
+    let mut chars = key.chars();
+    let base: i8 = match chars.next()?.to_ascii_uppercase() {
+        'C' => 0,
+        'D' => 2,
+        'E' => 4,
+        'F' => 5,
+        'G' => 7,
+        'A' => 9,
+        'B' => 11,
+        _ => return None,
+    };
+    let rest = chars.as_str();
+    let (shift, rest) = match rest.chars().next() {
+        Some('#' | '♯') => (1, &rest[rest.chars().next()?.len_utf8()..]),
+        Some('b' | '♭') => (-1, &rest[rest.chars().next()?.len_utf8()..]),
+        _ => (0, rest),
+    };
+    let quality: String = rest.split_whitespace().collect::<String>().to_lowercase();
+    let minor = match quality.as_str() {
+        "" | "maj" | "major" => false,
+        "m" | "min" | "minor" => true,
+        _ => return None,
+    };
+    Some(((base + shift).rem_euclid(12) as u8, minor))
+}
+
+/// Convert a key name to Camelot notation ("Am" → "8A", "C" → "8B").
+/// Values already in Camelot notation are normalized and passed through.
+/// None if the key can't be parsed.
+pub fn to_camelot(key: &str) -> Option<String> {
+    let trimmed = key.trim().to_uppercase();
+    if let Some(number) = trimmed
+        .strip_suffix('A')
+        .or_else(|| trimmed.strip_suffix('B'))
+        .and_then(|n| n.parse::<u8>().ok())
+        .filter(|n| (1..=12).contains(n))
+    {
+        return Some(format!("{}{}", number, &trimmed[trimmed.len() - 1..]));
+    }
+
+    let (pitch, minor) = parse_key(key)?;
+    // Walking the wheel clockwise is a fifth (7 semitones) per step; C major
+    // sits at 8B and each minor key shares its relative major's number.
+    let relative_major = if minor { (pitch + 3) % 12 } else { pitch };
+    let number = match (7 * relative_major as u32 + 8) % 12 {
+        0 => 12,
+        n => n,
+    };
+    Some(format!("{}{}", number, if minor { 'A' } else { 'B' }))
+}
+
+/// Pearson correlation of two 12-bin vectors.
+fn correlation(a: &[f32; 12], b: &[f32; 12]) -> f32 {
+    let mean_a = a.iter().sum::<f32>() / 12.0;
+    let mean_b = b.iter().sum::<f32>() / 12.0;
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    if var_a == 0.0 || var_b == 0.0 {
+        return 0.0;
+    }
+    cov / (var_a * var_b).sqrt()
+}
+
+/// Best-matching key for a chroma vector (energy per pitch class, C first).
+pub fn key_from_chroma(chroma: &[f32; 12]) -> String {
+    let mut best = (f32::MIN, MAJOR_NAMES[0]);
+    for tonic in 0..12 {
+        let rotated: [f32; 12] = std::array::from_fn(|i| chroma[(i + tonic) % 12]);
+        let major = correlation(&rotated, &MAJOR_PROFILE);
+        let minor = correlation(&rotated, &MINOR_PROFILE);
+        for (score, names) in [(major, &MAJOR_NAMES), (minor, &MINOR_NAMES)] {
+            if score > best.0 {
+                best = (score, names[tonic]);
+            }
+        }
+    }
+    best.1.to_string()
+}
+
+/// Power of `freq` in `frame` (Goertzel filter).
+fn goertzel_power(frame: &[f32], freq: f32, sample_rate: f32) -> f32 {
+    let coeff = 2.0 * (2.0 * PI * freq / sample_rate).cos();
+    let (mut s1, mut s2) = (0.0_f32, 0.0_f32);
+    for &x in frame {
+        let s0 = x + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    s1 * s1 + s2 * s2 - coeff * s1 * s2
+}
+
+/// Add one frame's note energies into `chroma`.
+fn accumulate_chroma(chroma: &mut [f32; 12], frame: &[f32], sample_rate: f32) {
+    for note in NOTE_RANGE {
+        let freq = 440.0 * 2f32.powf((note as f32 - 69.0) / 12.0);
+        if freq >= sample_rate / 2.0 {
+            break;
+        }
+        chroma[note as usize % 12] += goertzel_power(frame, freq, sample_rate).sqrt();
+    }
+}
+
+/// Estimate the key of an audio file from its first two minutes.
+pub fn estimate_key(path: &Path) -> Result<KeyAnalysis, String> {
+    let file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path.display(), e))?;
+    let source = Decoder::new(BufReader::new(file))
+        .map_err(|e| format!("Cannot decode '{}': {}", path.display(), e))?;
+    let channels = source.channels().max(1) as usize;
+    let file_rate = source.sample_rate() as usize;
+    let rate = (file_rate / DECIMATE).max(1) as f32;
+
+    // Mix to mono and average each run of DECIMATE frames (a crude low-pass)
+    let block = channels * DECIMATE;
+    let max_samples = MAX_ANALYSIS_SECS * file_rate * channels;
+    let mut chroma = [0.0_f32; 12];
+    let mut frame = Vec::with_capacity(FRAME_LEN);
+    let (mut sum, mut count) = (0.0_f32, 0);
+    for sample in source.convert_samples::<f32>().take(max_samples) {
+        sum += sample;
+        count += 1;
+        if count == block {
+            frame.push(sum / block as f32);
+            sum = 0.0;
+            count = 0;
+            if frame.len() == FRAME_LEN {
+                accumulate_chroma(&mut chroma, &frame, rate);
+                frame.clear();
+            }
+        }
+    }
+    if frame.len() >= FRAME_LEN / 4 {
+        accumulate_chroma(&mut chroma, &frame, rate);
+    }
+    if chroma.iter().all(|&c| c == 0.0) {
+        return Err(format!("No tonal content in '{}'", path.display()));
+    }
+
+    let key = key_from_chroma(&chroma);
+    let camelot = to_camelot(&key);
+    Ok(KeyAnalysis { key, camelot })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn camelot_conversion() {
+        assert_eq!(to_camelot("Am").as_deref(), Some("8A"));
+        assert_eq!(to_camelot("A minor").as_deref(), Some("8A"));
+        assert_eq!(to_camelot("C").as_deref(), Some("8B"));
+        assert_eq!(to_camelot("Cmaj").as_deref(), Some("8B"));
+        assert_eq!(to_camelot("B").as_deref(), Some("1B"));
+        assert_eq!(to_camelot("G#m").as_deref(), Some("1A"));
+        assert_eq!(to_camelot("Abm").as_deref(), Some("1A"));
+        assert_eq!(to_camelot("Db").as_deref(), Some("3B"));
+        assert_eq!(to_camelot("C#m").as_deref(), Some("12A"));
+        assert_eq!(to_camelot("E").as_deref(), Some("12B"));
+        // Already Camelot
+        assert_eq!(to_camelot("8a").as_deref(), Some("8A"));
+        assert_eq!(to_camelot("H").as_deref(), None);
+        assert_eq!(to_camelot("Am7").as_deref(), None);
+        assert_eq!(to_camelot("13A").as_deref(), None);
+    }
+
+    #[test]
+    fn key_profiles_match_themselves() {
+        assert_eq!(key_from_chroma(&MAJOR_PROFILE), "C");
+        let a_minor: [f32; 12] = std::array::from_fn(|i| MINOR_PROFILE[(i + 3) % 12]);
+        assert_eq!(key_from_chroma(&a_minor), "Am");
+    }
+
+    #[test]
+    fn estimate_key_of_a_minor_triad() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("am.wav");
+        let rate = 22050;
+        // A3, C4, E4 with the root loudest
+        let tones = [(220.0, 0.5), (261.63, 0.3), (329.63, 0.3)];
+        let samples: Vec<i16> = (0..rate * 4)
+            .map(|n| {
+                let t = n as f32 / rate as f32;
+                let tone = |(f, a): &(f32, f32)| a * (2.0 * PI * f * t).sin();
+                (tones.iter().map(tone).sum::<f32>() * 0.5 * i16::MAX as f32) as i16
+            })
+            .collect();
+        crate::test_audio::write_wav(&path, &samples, rate);
+
+        let analysis = estimate_key(&path).unwrap();
+        assert_eq!(analysis.key, "Am");
+        assert_eq!(analysis.camelot.as_deref(), Some("8A"));
+    }
+}
@@ -1,10 +1,15 @@
 use crate::engine::Engine;
+use crate::track::Track;
 use std::fs;
-use std::path::Path;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+/// Default quiet period before a now-playing update is written.
+pub const DEFAULT_DEBOUNCE_MS: u64 = 500;
 
 /// Snapshot of current playback state for XML export.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct NowPlaying {
     pub playlist_name: Option<String>,
     pub current_artist: Option<String>,
@@ -36,6 +41,11 @@ impl std::fmt::Display for PlaybackState {
 impl NowPlaying {
     /// Build a NowPlaying snapshot from the current engine state.
     /// `elapsed` is optionally provided by the caller (from live playback tracking).
+    ///
+    /// Ads and station IDs are never shown: while one airs (a playlist
+    /// track that is an ad or ID, or an instant roll) the current title is
+    /// `now_playing_ad_placeholder` with no artist or timing, and an ad up
+    /// next is left blank. The state stays "playing".
     pub fn from_engine(engine: &Engine, elapsed: Option<Duration>) -> Self {
         let pl = engine.active_playlist();
 
@@ -59,16 +69,28 @@ impl NowPlaying {
             None => (None, None, None, PlaybackState::Stopped),
         };
 
+        let is_ad = |t: &&Track| engine.is_ad_or_station_id(&t.path);
+        let on_break = engine.ad_break_active.load(Ordering::Relaxed);
+        let on_ad = on_break || current_track.is_some_and(|t| is_ad(&t));
+        let current_track = current_track.filter(|_| !on_ad);
+        let state = if on_ad { PlaybackState::Playing } else { state };
+        let next_track = next_track.filter(|t| !is_ad(t));
+
         let (current_artist, current_title, current_duration) = match current_track {
             Some(t) => (
                 Some(t.artist.clone()),
                 Some(t.title.clone()),
                 Some(t.duration),
             ),
+            None if on_ad => {
+                let placeholder = engine.now_playing_ad_placeholder.trim();
+                let title = (!placeholder.is_empty()).then(|| placeholder.to_string());
+                (None, title, None)
+            }
             None => (None, None, None),
         };
 
-        let current_elapsed = elapsed;
+        let current_elapsed = elapsed.filter(|_| !on_ad);
         let current_remaining = match (current_duration, elapsed) {
             (Some(dur), Some(el)) if dur > el => Some(dur - el),
             (Some(_), Some(_)) => Some(Duration::ZERO),
@@ -159,6 +181,78 @@ impl NowPlaying {
         let xml = self.to_xml();
         fs::write(path, &xml).map_err(|e| format!("Failed to write XML to '{}': {}", path.display(), e))
     }
+
+    /// Whether `other` shows the same playlist, tracks and state. The
+    /// playback position is ignored, so a ticking clock is not a change.
+    pub fn same_content(&self, other: &NowPlaying) -> bool {
+        NowPlaying {
+            current_elapsed: None,
+            current_remaining: None,
+            ..self.clone()
+        } == NowPlaying {
+            current_elapsed: None,
+            current_remaining: None,
+            ..other.clone()
+        }
+    }
+}
+
+/// A now-playing file due to be written. Built under the core lock and
+/// written once it is released (see `AppCore::take_now_playing`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NowPlayingWrite {
+    pub path: PathBuf,
+    pub xml: String,
+}
+
+impl NowPlayingWrite {
+    pub fn write(&self) -> Result<(), String> {
+        fs::write(&self.path, &self.xml)
+            .map_err(|e| format!("Failed to write XML to '{}': {}", self.path.display(), e))
+    }
+}
+
+/// Coalesces bursts of now-playing updates (e.g. rapid skipping) so only
+/// the latest snapshot is written, once no update has arrived for `window`.
+#[derive(Debug)]
+pub struct NowPlayingDebouncer {
+    window: Duration,
+    pending: Option<(NowPlaying, Instant)>,
+}
+
+impl NowPlayingDebouncer {
+    pub fn new(window: Duration) -> Self {
+        NowPlayingDebouncer {
+            window,
+            pending: None,
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    /// Queue `snapshot`, replacing any not yet written, and restart the window.
+    pub fn update(&mut self, snapshot: NowPlaying, now: Instant) {
+        self.pending = Some((snapshot, now));
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// The queued snapshot, if the window has passed since the last update.
+    pub fn take_due(&mut self, now: Instant) -> Option<NowPlaying> {
+        let (_, queued_at) = self.pending.as_ref()?;
+        if now.saturating_duration_since(*queued_at) < self.window {
+            return None;
+        }
+        self.pending.take().map(|(snapshot, _)| snapshot)
+    }
 }
 
 /// Format Duration as integer seconds string.
@@ -188,8 +282,7 @@ mod tests {
             title: title.to_string(),
             artist: artist.to_string(),
             duration: Duration::new(secs, 0),
-            played_duration: None,
-            has_intro: false,
+            ..Default::default()
         }
     }
 
@@ -332,6 +425,130 @@ mod tests {
         assert_eq!(xml_escape("a&b<c>d\"e'f"), "a&amp;b&lt;c&gt;d&quot;e&apos;f");
     }
 
+    #[test]
+    fn debounce_coalesces_burst_into_final_value() {
+        let mut engine = Engine::new();
+        engine.create_playlist("Main".to_string());
+        engine.set_active("Main").unwrap();
+        let mut debouncer = NowPlayingDebouncer::new(Duration::from_millis(500));
+        let t0 = Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+
+        let mut writes = Vec::new();
+        for (i, title) in ["One", "Two", "Three"].iter().enumerate() {
+            let pl = engine.find_playlist_mut("Main").unwrap();
+            pl.tracks.push(make_track("Artist", title, 180));
+            pl.current_index = Some(i);
+            let snapshot = NowPlaying::from_engine(&engine, None);
+            debouncer.update(snapshot, ms(i as u64 * 100));
+            writes.extend(debouncer.take_due(ms(i as u64 * 100 + 50)));
+        }
+        assert!(writes.is_empty());
+        assert!(debouncer.take_due(ms(650)).is_none());
+
+        writes.extend(debouncer.take_due(ms(700)));
+        writes.extend(debouncer.take_due(ms(1500)));
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].current_title.as_deref(), Some("Three"));
+        assert!(!debouncer.is_pending());
+    }
+
+    #[test]
+    fn debounce_writes_each_spaced_update() {
+        let mut engine = Engine::new();
+        engine.create_playlist("Main".to_string());
+        engine.set_active("Main").unwrap();
+        let mut debouncer = NowPlayingDebouncer::new(Duration::from_millis(500));
+        let t0 = Instant::now();
+        let ms = |n| t0 + Duration::from_millis(n);
+
+        let mut writes = Vec::new();
+        for (i, title) in ["One", "Two", "Three"].iter().enumerate() {
+            let pl = engine.find_playlist_mut("Main").unwrap();
+            pl.tracks.push(make_track("Artist", title, 180));
+            pl.current_index = Some(i);
+            let at = i as u64 * 1000;
+            debouncer.update(NowPlaying::from_engine(&engine, None), ms(at));
+            writes.extend(debouncer.take_due(ms(at + 600)));
+        }
+        let titles: Vec<_> = writes.iter().map(|w| w.current_title.as_deref()).collect();
+        assert_eq!(titles, [Some("One"), Some("Two"), Some("Three")]);
+    }
+
+    #[test]
+    fn same_content_ignores_position() {
+        let mut engine = Engine::new();
+        engine.create_playlist("Main".to_string());
+        engine.set_active("Main").unwrap();
+        let pl = engine.find_playlist_mut("Main").unwrap();
+        pl.tracks.push(make_track("Artist", "One", 180));
+        pl.tracks.push(make_track("Artist", "Two", 180));
+        pl.current_index = Some(0);
+
+        let early = NowPlaying::from_engine(&engine, Some(Duration::from_secs(5)));
+        let later = NowPlaying::from_engine(&engine, Some(Duration::from_secs(60)));
+        assert!(early.same_content(&later));
+
+        engine.find_playlist_mut("Main").unwrap().current_index = Some(1);
+        let next = NowPlaying::from_engine(&engine, Some(Duration::from_secs(60)));
+        assert!(!later.same_content(&next));
+    }
+
+    #[test]
+    fn ad_track_shows_placeholder_and_is_not_advertised_as_next() {
+        let mut engine = Engine::new();
+        engine.now_playing_ad_placeholder = "WXYZ 104.7".to_string();
+        engine.ads.push(crate::ad_scheduler::AdConfig::new(
+            "Spot".to_string(),
+            PathBuf::from("ads/spot.mp3"),
+        ));
+        engine.create_playlist("Main".to_string());
+        engine.set_active("Main").unwrap();
+        let pl = engine.active_playlist_mut().unwrap();
+        pl.tracks.push(make_track("Artist A", "Song A", 200));
+        let mut ad = make_track("Sponsor", "Buy Now", 30);
+        ad.path = PathBuf::from("/library/ads/spot.mp3");
+        pl.tracks.push(ad);
+        pl.tracks.push(make_track("Artist B", "Song B", 180));
+        pl.current_index = Some(0);
+
+        // The ad coming up is not announced
+        let np = NowPlaying::from_engine(&engine, Some(Duration::new(10, 0)));
+        assert_eq!(np.current_title.as_deref(), Some("Song A"));
+        assert!(np.next_title.is_none());
+
+        engine.active_playlist_mut().unwrap().current_index = Some(1);
+        let np = NowPlaying::from_engine(&engine, Some(Duration::new(10, 0)));
+        assert_eq!(np.current_title.as_deref(), Some("WXYZ 104.7"));
+        assert!(np.current_artist.is_none());
+        assert!(np.current_duration.is_none() && np.current_remaining.is_none());
+        assert_eq!(np.next_title.as_deref(), Some("Song B"));
+        assert!(!np.to_xml().contains("Buy Now"));
+
+        engine.active_playlist_mut().unwrap().current_index = Some(2);
+        let np = NowPlaying::from_engine(&engine, None);
+        assert_eq!(np.current_title.as_deref(), Some("Song B"));
+    }
+
+    #[test]
+    fn instant_ad_break_hides_the_interrupted_track() {
+        let mut engine = Engine::new();
+        engine.create_playlist("Main".to_string());
+        engine.set_active("Main").unwrap();
+        let pl = engine.active_playlist_mut().unwrap();
+        pl.tracks.push(make_track("Artist A", "Song A", 200));
+        pl.current_index = Some(0);
+
+        engine.ad_break_active.store(true, Ordering::Relaxed);
+        let np = NowPlaying::from_engine(&engine, None);
+        assert!(np.current_title.is_none() && np.current_artist.is_none());
+        assert_eq!(np.state, PlaybackState::Playing);
+
+        engine.ad_break_active.store(false, Ordering::Relaxed);
+        let np = NowPlaying::from_engine(&engine, None);
+        assert_eq!(np.current_title.as_deref(), Some("Song A"));
+    }
+
     #[test]
     fn playback_state_display() {
         assert_eq!(format!("{}", PlaybackState::Stopped), "stopped");
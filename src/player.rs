@@ -1,30 +1,102 @@
+use crate::agc::AgcConfig;
+use crate::auto_intro::IntroMode;
+use crate::channel_map::ChannelMode;
+use crate::engine::CartSlot;
+use crate::lecture_detector::LectureDetector;
 use crate::level_monitor::{LevelMonitor, LevelSource};
+use crate::program_bus::{BusOutput, ProgramBus};
+use crate::scheduler::ScheduleMode;
 use crate::silence::{SilenceDetector, SilenceMonitor};
+use rodio::source::{Amplify, UniformSourceIterator};
 use rodio::{cpal, Decoder, DeviceTrait, OutputStream, OutputStreamHandle, Sink, Source};
+use std::fmt;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 
+/// A decoded track ready for `Player::stop_and_play_prepared`.
+pub type PreparedSource = LevelSource<Box<dyn Source<Item = f32> + Send>>;
+
+/// Upper bound for overlay mixing gain (2.0 = +6 dB).
+pub const OVERLAY_GAIN_MAX: f32 = 2.0;
+
+/// Clamp an overlay gain into `0.0..=OVERLAY_GAIN_MAX`.
+pub fn clamp_overlay_gain(gain: f32) -> f32 {
+    gain.clamp(0.0, OVERLAY_GAIN_MAX)
+}
+
+/// Resample `source` to `rate` Hz, keeping its channel count.
+pub fn resample_to<S: Source<Item = f32>>(source: S, rate: u32) -> UniformSourceIterator<S, f32> {
+    let channels = source.channels();
+    UniformSourceIterator::new(source, channels, rate)
+}
+
+/// Number of frames `frames` at `from_rate` Hz become at `to_rate` Hz
+/// (rounded up, so a partial trailing frame still plays).
+pub fn resampled_frames(frames: usize, from_rate: u32, to_rate: u32) -> usize {
+    (frames as u64 * to_rate as u64).div_ceil(from_rate.max(1) as u64) as usize
+}
+
+/// Scale an overlay's samples by `gain` before the output mixer sums them
+/// with the main track (0.0 = silent, 1.0 = unity).
+pub fn overlay_source<S: Source>(source: S, gain: f32) -> Amplify<S>
+where
+    S::Item: rodio::Sample,
+{
+    source.amplify(clamp_overlay_gain(gain))
+}
+
+/// Scale an auto-intro's samples by `gain_db` (0 = the file's own level)
+/// so intros can be balanced against the music.
+pub fn intro_source<S: Source>(source: S, gain_db: f32) -> Amplify<S>
+where
+    S::Item: rodio::Sample,
+{
+    source.amplify(10f32.powf(gain_db / 20.0))
+}
+
+/// Channels and rate the program bus mixes at when the device doesn't say.
+const FALLBACK_BUS_FORMAT: (u16, u32) = (2, 44_100);
+
 /// Runtime audio player wrapping rodio. Not serializable — created fresh per session.
+/// Every sink it opens plays into one `ProgramBus`.
 pub struct Player {
-    _stream: OutputStream,
-    stream_handle: OutputStreamHandle,
-    sink: Sink,
+    _stream: DeviceStream,
+    bus: ProgramBus,
+    sink: Arc<Sink>,
+    /// The volume ramp in progress, if any (see `start_fade`).
+    fade: Mutex<Option<FadeTask>>,
+    /// Length of the device buffer opened (zero = driver default, unknown).
+    latency: Duration,
+}
+
+/// Keeps the device stream open: rodio's, with the driver's default
+/// buffer, or one opened with a fixed buffer (see `Player::with_buffer`).
+// The streams are never read, only held: dropping one closes the device
+#[allow(dead_code)]
+enum DeviceStream {
+    Rodio(OutputStream),
+    Fixed(cpal::Stream),
+}
+
+/// A volume ramp running on its own thread until it ends or is cancelled.
+struct FadeTask {
+    cancel: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
 }
 
 impl Player {
     /// Initialize audio output on the default device.
     pub fn new() -> Result<Self, String> {
+        use cpal::traits::HostTrait;
         let (stream, handle) = OutputStream::try_default()
             .map_err(|e| format!("Failed to open audio output: {}", e))?;
-        let sink =
-            Sink::try_new(&handle).map_err(|e| format!("Failed to create audio sink: {}", e))?;
-        Ok(Player {
-            _stream: stream,
-            stream_handle: handle,
-            sink,
-        })
+        let device = cpal::default_host().default_output_device();
+        Self::with_stream(stream, &handle, bus_format(device.as_ref()))
     }
 
     /// Initialize audio output on a specific named device.
@@ -32,18 +104,88 @@ impl Player {
         let device = find_output_device(device_name)?;
         let (stream, handle) = OutputStream::try_from_device(&device)
             .map_err(|e| format!("Failed to open device '{}': {}", device_name, e))?;
-        let sink =
-            Sink::try_new(&handle).map_err(|e| format!("Failed to create audio sink: {}", e))?;
+        Self::with_stream(stream, &handle, bus_format(Some(&device)))
+    }
+
+    /// Initialize audio output on `device_name` (None = default device)
+    /// with a device buffer of about `buffer_ms` (0 = the driver's default).
+    /// The device may not allow that size; `output_latency` reports the
+    /// buffer actually opened.
+    pub fn with_buffer(device_name: Option<&str>, buffer_ms: u32) -> Result<Self, String> {
+        use cpal::traits::{HostTrait, StreamTrait};
+        if buffer_ms == 0 {
+            return match device_name {
+                Some(name) => Self::new_with_device(name),
+                None => Self::new(),
+            };
+        }
+        let device = match device_name {
+            Some(name) => find_output_device(name)?,
+            None => cpal::default_host()
+                .default_output_device()
+                .ok_or("No audio output device")?,
+        };
+        let supported = device
+            .default_output_config()
+            .map_err(|e| format!("Cannot read output format: {}", e))?;
+        let (channels, sample_rate) = (supported.channels(), supported.sample_rate().0);
+        let frames = buffer_frames(buffer_ms, sample_rate, supported.buffer_size());
+        let config = cpal::StreamConfig {
+            channels,
+            sample_rate: supported.sample_rate(),
+            buffer_size: cpal::BufferSize::Fixed(frames),
+        };
+        let (bus, output) = ProgramBus::new(channels, sample_rate);
+        let stream = open_fixed_stream(&device, &config, supported.sample_format(), output)?;
+        stream
+            .play()
+            .map_err(|e| format!("Failed to start audio output: {}", e))?;
+        let sink = Arc::new(bus.sink());
+        Ok(Player {
+            _stream: DeviceStream::Fixed(stream),
+            bus,
+            sink,
+            fade: Mutex::new(None),
+            latency: Duration::from_secs_f64(frames as f64 / sample_rate as f64),
+        })
+    }
+
+    fn with_stream(
+        stream: OutputStream,
+        handle: &OutputStreamHandle,
+        (channels, sample_rate): (u16, u32),
+    ) -> Result<Self, String> {
+        let bus = ProgramBus::start(handle, channels, sample_rate)?;
+        let sink = Arc::new(bus.sink());
         Ok(Player {
-            _stream: stream,
-            stream_handle: handle,
+            _stream: DeviceStream::Rodio(stream),
+            bus,
             sink,
+            fade: Mutex::new(None),
+            latency: Duration::ZERO,
         })
     }
 
-    /// Create a new independent sink on the same audio output.
+    /// How far the speakers run behind the audio handed to the device: the
+    /// buffer opened by `with_buffer`. Zero when the driver picked the
+    /// buffer, as its size isn't reported.
+    pub fn output_latency(&self) -> Duration {
+        self.latency
+    }
+
+    /// Create a new independent sink on the program bus.
     pub fn create_sink(&self) -> Result<Sink, String> {
-        Sink::try_new(&self.stream_handle).map_err(|e| format!("Failed to create sink: {}", e))
+        Ok(self.bus.sink())
+    }
+
+    /// Ride the gain of everything the player outputs (None = AGC off).
+    pub fn set_agc(&self, config: Option<AgcConfig>) {
+        self.bus.set_agc(config);
+    }
+
+    /// Map the channels of everything the player outputs.
+    pub fn set_channel_mode(&self, mode: ChannelMode) {
+        self.bus.set_channel_mode(mode);
     }
 
     /// Decode and append an audio file to the default sink, starting playback.
@@ -73,25 +215,104 @@ impl Player {
     /// Stop current playback and play a pre-decoded source with level monitoring.
     /// Use `prepare_file_with_level` to create the source outside the lock,
     /// then call this briefly under the lock.
-    pub fn stop_and_play_prepared(
-        &self,
-        source: LevelSource<rodio::source::SamplesConverter<Decoder<BufReader<File>>, f32>>,
-    ) {
+    pub fn stop_and_play_prepared(&self, source: PreparedSource) {
+        self.cancel_fade();
         self.sink.stop();
         self.sink.append(source);
         self.sink.play();
     }
 
+    /// `stop_and_play_prepared`, fading the source in from silence over
+    /// `fade` (the sign-on fade). Zero starts it at full volume.
+    pub fn stop_and_fade_in_prepared(&self, source: PreparedSource, fade: Duration) {
+        if fade.is_zero() {
+            return self.stop_and_play_prepared(source);
+        }
+        self.cancel_fade();
+        self.sink.stop();
+        self.sink.append(source.fade_in(fade));
+        self.sink.play();
+    }
+
+    /// Start a pre-decoded source on a fresh sink at `gain`, fading it in
+    /// over `fade` while the current track fades out on its old sink, which
+    /// is dropped once silent. Stopping, pausing or playing something else
+    /// cuts the fade-out short. Falls back to a hard cut if no sink can be
+    /// opened.
+    pub fn crossfade_to_prepared(&mut self, source: PreparedSource, gain: f32, fade: Duration) {
+        self.cancel_fade();
+        let sink = match self.create_sink() {
+            Ok(sink) => sink,
+            Err(_) => {
+                self.set_volume(gain);
+                self.stop_and_play_prepared(source);
+                return;
+            }
+        };
+        sink.set_volume(gain);
+        sink.append(source.fade_in(fade));
+        let outgoing = std::mem::replace(&mut self.sink, Arc::new(sink));
+        self.start_fade(move |cancel| {
+            let from = outgoing.volume();
+            ramp_sink_volume_until(&outgoing, from, 0.0, fade, cancel);
+            outgoing.stop();
+        });
+    }
+
+    /// Fade the current track out over `fade`, then stop it so the sink
+    /// reports empty. Stopping, pausing, setting the volume or playing
+    /// something else cancels the fade and restores the volume.
+    pub fn fade_out(&self, fade: Duration) {
+        let sink = self.sink.clone();
+        self.start_fade(move |cancel| {
+            let from = sink.volume();
+            if ramp_sink_volume_until(&sink, from, 0.0, fade, cancel) {
+                sink.stop();
+            } else {
+                sink.set_volume(from);
+            }
+        });
+    }
+
+    /// Run `ramp` on its own thread, replacing (and first cancelling) any
+    /// fade already running. `ramp` should return soon after its flag is set.
+    fn start_fade<F>(&self, ramp: F)
+    where
+        F: FnOnce(&AtomicBool) + Send + 'static,
+    {
+        self.cancel_fade();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let flag = cancel.clone();
+        let thread = std::thread::spawn(move || ramp(&flag));
+        *self.fade.lock().unwrap() = Some(FadeTask { cancel, thread });
+    }
+
+    /// Cancel the running fade, if any, and wait for it to wind up.
+    fn cancel_fade(&self) {
+        let task = self.fade.lock().unwrap().take();
+        if let Some(task) = task {
+            task.cancel.store(true, Ordering::Relaxed);
+            let _ = task.thread.join();
+        }
+    }
+
     /// Prepare a file for playback with level monitoring.
     /// Does file I/O and decoding — call this OUTSIDE any lock.
     /// Then pass the result to `stop_and_play_prepared` under the lock.
+    /// With `output_rate`, audio at any other rate is resampled to it.
+    /// AGC and channel mapping are not applied here but on the program bus
+    /// (`set_agc`, `set_channel_mode`).
     pub fn prepare_file_with_level(
         path: &Path,
         monitor: LevelMonitor,
-    ) -> Result<LevelSource<rodio::source::SamplesConverter<Decoder<BufReader<File>>, f32>>, String>
-    {
-        let source = decode_with_m4a_fallback(path)?;
-        Ok(LevelSource::new(source.convert_samples::<f32>(), monitor))
+        output_rate: Option<u32>,
+    ) -> Result<PreparedSource, String> {
+        let source = decode_with_m4a_fallback(path)?.convert_samples::<f32>();
+        let source: Box<dyn Source<Item = f32> + Send> = match output_rate {
+            Some(rate) if rate != source.sample_rate() => Box::new(resample_to(source, rate)),
+            _ => Box::new(source),
+        };
+        Ok(LevelSource::new(source, monitor))
     }
 
     /// Play an audio file on a new sink, returning ownership of that sink.
@@ -106,6 +327,50 @@ impl Player {
         Ok(sink)
     }
 
+    /// Play an auto-intro on a new sink at `gain_db` (see `intro_source`).
+    pub fn play_intro(&self, path: &Path, gain_db: f32) -> Result<Sink, String> {
+        let sink = self.create_sink()?;
+        let file =
+            File::open(path).map_err(|e| format!("Cannot open '{}': {}", path.display(), e))?;
+        let source = Decoder::new(BufReader::new(file))
+            .map_err(|e| format!("Cannot decode '{}': {}", path.display(), e))?;
+        sink.append(intro_source(source, gain_db));
+        sink.play();
+        Ok(sink)
+    }
+
+    /// Play an auto-intro over the start of the current track, holding the
+    /// track at `duck_volume` until the intro ends and then ramping it back
+    /// up over `ramp` (see `intro_track_gain`). Runs as a fade, so stopping,
+    /// pausing or playing something else cuts the intro and restores the
+    /// track's volume.
+    pub fn play_intro_over(
+        &self,
+        path: &Path,
+        gain_db: f32,
+        duck_volume: f32,
+        ramp: Duration,
+    ) -> Result<(), String> {
+        let intro_len = crate::track::decoded_duration(path).unwrap_or_default();
+        let intro = self.play_intro(path, gain_db)?;
+        let main = self.sink.clone();
+        self.start_fade(move |cancel| {
+            let original = main.volume();
+            let start = Instant::now();
+            while !intro.empty() || start.elapsed() < intro_len + ramp {
+                if cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                let elapsed = start.elapsed();
+                let gain = intro_track_gain(IntroMode::Over, elapsed, intro_len, duck_volume, ramp);
+                main.set_volume(original * gain.unwrap_or(1.0));
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            main.set_volume(original);
+        });
+        Ok(())
+    }
+
     /// Play an audio file on a new sink with a fade-in applied.
     pub fn play_file_new_sink_fadein(&self, path: &Path, fade: Duration) -> Result<Sink, String> {
         let sink = self.create_sink()?;
@@ -167,13 +432,21 @@ impl Player {
         Ok((sink, monitor))
     }
 
+    /// Set the default sink's linear gain (1.0 = unity), ending any fade.
+    pub fn set_volume(&self, volume: f32) {
+        self.cancel_fade();
+        self.sink.set_volume(volume);
+    }
+
     /// Stop playback and clear the sink.
     pub fn stop(&self) {
+        self.cancel_fade();
         self.sink.stop();
     }
 
     /// Pause playback (can be resumed).
     pub fn pause(&self) {
+        self.cancel_fade();
         self.sink.pause();
     }
 
@@ -199,21 +472,45 @@ impl Player {
             .map_err(|e| format!("Seek failed: {}", e))
     }
 
+    /// Current playback position within the playing source.
+    pub fn position(&self) -> Duration {
+        self.sink.get_pos()
+    }
+
     /// Returns true if the sink is paused.
     pub fn is_paused(&self) -> bool {
         self.sink.is_paused()
     }
 
-    /// Play an audio file as an overlay on top of current audio.
-    /// Creates a new independent sink and blocks until playback finishes.
-    pub fn play_overlay(&self, path: &Path) -> Result<(), String> {
-        let sink = self.play_file_new_sink(path)?;
+    /// Play an audio file as an overlay on top of current audio, scaled by
+    /// `overlay_gain` (0.0–2.0). Creates a new independent sink and blocks
+    /// until playback finishes.
+    pub fn play_overlay(&self, path: &Path, overlay_gain: f32) -> Result<(), String> {
+        let sink = self.create_sink()?;
+        let file =
+            File::open(path).map_err(|e| format!("Cannot open '{}': {}", path.display(), e))?;
+        let source = Decoder::new(BufReader::new(file))
+            .map_err(|e| format!("Cannot decode '{}': {}", path.display(), e))?;
+        sink.append(overlay_source(source, overlay_gain));
+        sink.play();
         while !sink.empty() {
             std::thread::sleep(Duration::from_millis(100));
         }
         Ok(())
     }
 
+    /// Start a file on its own sink at `gain` (see `overlay_source`), mixed
+    /// over whatever else is playing, and return at once. The sink is
+    /// detached: it plays to the end even after the main sink stops.
+    pub fn play_over(&self, path: &Path, gain: f32) -> Result<(), String> {
+        let source = decode_with_m4a_fallback(path)?;
+        let sink = self.create_sink()?;
+        sink.append(overlay_source(source, gain));
+        sink.play();
+        sink.detach();
+        Ok(())
+    }
+
     /// Stop current audio, then play a file on a new sink (hard break).
     /// Stops the default sink first, plays the file, and blocks until finished.
     pub fn play_stop_mode(&self, path: &Path) -> Result<(), String> {
@@ -224,9 +521,20 @@ impl Player {
         }
         Ok(())
     }
+
+    /// Fire a cart: overlay carts mix over current audio at `overlay_gain`,
+    /// others hard-break. Blocks until the cart finishes. Plays on this
+    /// player's device; the app fires carts through the audio runtime
+    /// (`AudioHandle::play_over`) so they follow the configured output.
+    pub fn play_cart(&self, cart: &CartSlot, overlay_gain: f32) -> Result<(), String> {
+        match cart.mode() {
+            ScheduleMode::Overlay => self.play_overlay(&cart.path, overlay_gain),
+            _ => self.play_stop_mode(&cart.path),
+        }
+    }
 }
 
-fn decode_with_m4a_fallback(path: &Path) -> Result<Decoder<BufReader<File>>, String> {
+pub(crate) fn decode_with_m4a_fallback(path: &Path) -> Result<Decoder<BufReader<File>>, String> {
     let file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path.display(), e))?;
 
     match Decoder::new(BufReader::new(file)) {
@@ -299,6 +607,136 @@ pub fn should_crossfade(crossfade_secs: f32, track_duration: Duration, has_next:
         && track_duration > Duration::from_secs_f32(crossfade_secs * 2.0)
 }
 
+/// True when a track of `track_duration` is under `min_track_secs` and so
+/// must not crossfade out. `min_track_secs <= 0` disables the check.
+pub fn too_short_to_crossfade(track_duration: Duration, min_track_secs: f32) -> bool {
+    min_track_secs > 0.0 && track_duration < Duration::from_secs_f32(min_track_secs)
+}
+
+/// How `play_playlist` moves from one track into the next.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackTransition {
+    /// Play to the end, then start the next track.
+    Sequential,
+    /// Next track fades in while this one fades out.
+    Crossfade,
+    /// This track fades out, then the next starts at full level with no
+    /// overlap (next track has `no_crossfade_in`).
+    FadeOutOnly,
+}
+
+/// Pick the transition out of a track of `track_duration` into `next`.
+pub fn transition_for(
+    crossfade_secs: f32,
+    track_duration: Duration,
+    next: Option<&crate::track::Track>,
+) -> TrackTransition {
+    if !should_crossfade(crossfade_secs, track_duration, next.is_some()) {
+        TrackTransition::Sequential
+    } else if next.is_some_and(|t| t.no_crossfade_in) {
+        TrackTransition::FadeOutOnly
+    } else {
+        TrackTransition::Crossfade
+    }
+}
+
+/// Like `transition_for`, but with `lectures` set, a transition where
+/// either side is a lecture is a hard cut: crossfading music into a sermon
+/// or news sounds wrong. None keeps the normal crossfade behavior.
+///
+/// A `current` track shorter than `min_track_secs` also exits with a hard
+/// cut, so a jingle or stinger isn't swallowed by the fade (0 = off).
+pub fn transition_between(
+    crossfade_secs: f32,
+    current: &crate::track::Track,
+    next: Option<&crate::track::Track>,
+    lectures: Option<&LectureDetector>,
+    min_track_secs: f32,
+) -> TrackTransition {
+    let spoken = lectures.is_some_and(|detector| {
+        detector.is_lecture(&current.artist) || next.is_some_and(|t| detector.is_lecture(&t.artist))
+    });
+    if spoken || too_short_to_crossfade(current.effective_duration(), min_track_secs) {
+        TrackTransition::Sequential
+    } else {
+        transition_for(crossfade_secs, current.effective_duration(), next)
+    }
+}
+
+/// Elapsed time into `track` at which the crossfade to the next track begins.
+///
+/// The fade ends at the track's `cue_out` (relative to `cue_in`) when set,
+/// otherwise at its duration, and starts `crossfade_secs + lead_secs` before
+/// that. Never earlier than the start of the track.
+pub fn crossfade_start_for(
+    track: &crate::track::Track,
+    crossfade_secs: f32,
+    lead_secs: f32,
+) -> Duration {
+    let end = match track.cue_out {
+        Some(out) => out.saturating_sub(track.cue_in.unwrap_or(Duration::ZERO)),
+        None => track.effective_duration(),
+    };
+    let before_end = Duration::from_secs_f32(crossfade_secs.max(0.0) + lead_secs.max(0.0));
+    end.saturating_sub(before_end)
+}
+
+/// Gain shape used across a crossfade.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum CrossfadeCurve {
+    /// Straight-line gains (matches the live transport's fades).
+    #[default]
+    Linear,
+    /// Constant-power (sin/cos) gains; avoids the mid-fade loudness dip.
+    EqualPower,
+}
+
+impl fmt::Display for CrossfadeCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CrossfadeCurve::Linear => write!(f, "linear"),
+            CrossfadeCurve::EqualPower => write!(f, "equal_power"),
+        }
+    }
+}
+
+impl CrossfadeCurve {
+    /// Parse a curve from a string (case-insensitive).
+    pub fn from_str_loose(s: &str) -> Result<Self, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "linear" | "" => Ok(CrossfadeCurve::Linear),
+            "equal_power" | "equal-power" | "equalpower" => Ok(CrossfadeCurve::EqualPower),
+            _ => Err(format!(
+                "Unknown crossfade curve '{}'. Expected: linear, equal_power",
+                s
+            )),
+        }
+    }
+}
+
+/// `(outgoing, incoming)` gains at `progress` (0.0–1.0) through a crossfade.
+pub fn crossfade_gains(progress: f32, curve: CrossfadeCurve) -> (f32, f32) {
+    let p = progress.clamp(0.0, 1.0);
+    match curve {
+        CrossfadeCurve::Linear => (1.0 - p, p),
+        CrossfadeCurve::EqualPower => {
+            let angle = p * std::f32::consts::FRAC_PI_2;
+            (angle.cos(), angle.sin())
+        }
+    }
+}
+
+/// Fade-in for a track started by `facade::play` or `play_playlist`. Only
+/// the very first track of a run (starting from silence) gets the sign-on
+/// fade; later tracks either crossfade in or start at full volume.
+pub fn signon_fade_for(signed_on: bool, signon_fade_secs: f32) -> Duration {
+    if signed_on || signon_fade_secs <= 0.0 {
+        Duration::ZERO
+    } else {
+        Duration::from_secs_f32(signon_fade_secs)
+    }
+}
+
 /// Perform a linear fade-out on a sink over the given duration.
 /// Blocks the calling thread for the fade duration.
 fn fade_out_sink(sink: &Sink, duration: Duration) {
@@ -307,7 +745,7 @@ fn fade_out_sink(sink: &Sink, duration: Duration) {
     let step_duration = duration / steps as u32;
 
     for step in 1..=steps {
-        let volume = 1.0 - (step as f32 / steps as f32);
+        let (volume, _) = crossfade_gains(step as f32 / steps as f32, CrossfadeCurve::Linear);
         sink.set_volume(volume);
         std::thread::sleep(step_duration);
     }
@@ -348,6 +786,8 @@ pub struct RecurringIntroConfig {
     pub interval_secs: f32,
     /// Volume level to duck the main track to during overlay (0.0–1.0).
     pub duck_volume: f32,
+    /// Seconds to ramp the main track down before, and back up after, the overlay.
+    pub duck_ramp_secs: f32,
 }
 
 impl RecurringIntroConfig {
@@ -361,15 +801,83 @@ impl RecurringIntroConfig {
         Duration::from_secs_f32(self.interval_secs.max(0.0))
     }
 
+    /// Duck ramp length as a `Duration`.
+    pub fn duck_ramp(&self) -> Duration {
+        Duration::from_secs_f32(self.duck_ramp_secs.max(0.0))
+    }
+
     /// Disabled recurring intro config.
     pub fn disabled() -> Self {
         RecurringIntroConfig {
             interval_secs: 0.0,
             duck_volume: 0.3,
+            duck_ramp_secs: 0.5,
         }
     }
 }
 
+/// Gain `elapsed` into a linear ramp from `from` to `to` lasting `ramp`.
+/// A zero-length ramp steps straight to `to`.
+pub fn duck_gain_at(from: f32, to: f32, elapsed: Duration, ramp: Duration) -> f32 {
+    if ramp.is_zero() {
+        return to;
+    }
+    let p = (elapsed.as_secs_f32() / ramp.as_secs_f32()).clamp(0.0, 1.0);
+    from + (to - from) * p
+}
+
+/// Track gain `elapsed` after an auto-intro of length `intro_len` starts,
+/// or None while the track has not started yet. `Before` holds the track
+/// back until the intro ends; `Over` starts it straight away at
+/// `duck_volume` and ramps it back to full over `ramp` once the intro ends.
+pub fn intro_track_gain(
+    mode: IntroMode,
+    elapsed: Duration,
+    intro_len: Duration,
+    duck_volume: f32,
+    ramp: Duration,
+) -> Option<f32> {
+    let after_intro = elapsed.checked_sub(intro_len);
+    match mode {
+        IntroMode::Before => after_intro.map(|_| 1.0),
+        IntroMode::Over => Some(match after_intro {
+            None => duck_volume,
+            Some(t) => duck_gain_at(duck_volume, 1.0, t, ramp),
+        }),
+    }
+}
+
+/// Ramp `sink` volume from `from` to `to` over `ramp` (blocking).
+fn ramp_sink_volume(sink: &Sink, from: f32, to: f32, ramp: Duration) {
+    let start = Instant::now();
+    while start.elapsed() < ramp {
+        sink.set_volume(duck_gain_at(from, to, start.elapsed(), ramp));
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    sink.set_volume(to);
+}
+
+/// `ramp_sink_volume`, returning early (volume left where it was) once
+/// `cancel` is set. Returns whether the ramp ran to the end.
+fn ramp_sink_volume_until(
+    sink: &Sink,
+    from: f32,
+    to: f32,
+    ramp: Duration,
+    cancel: &AtomicBool,
+) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < ramp {
+        if cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        sink.set_volume(duck_gain_at(from, to, start.elapsed(), ramp));
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    sink.set_volume(to);
+    true
+}
+
 /// Result of playing through a playlist.
 pub struct PlaybackResult {
     /// Index of the last track that was started.
@@ -378,28 +886,65 @@ pub struct PlaybackResult {
     pub played_durations: Vec<(usize, Duration)>,
 }
 
-/// Play through a playlist starting at `start_index`, auto-advancing.
-/// Supports crossfading when `crossfade_secs > 0.0`.
-/// Supports silence detection when `silence.enabled()`.
-/// Supports auto-intros when `intros_folder` is provided.
+/// How `play_playlist` plays through its tracks.
+#[derive(Clone, Copy)]
+pub struct PlaylistOptions<'a> {
+    /// Crossfade length between tracks (0 = none).
+    pub crossfade_secs: f32,
+    /// Start crossfades this much earlier than the fade alone would (see
+    /// `crossfade_start_for`).
+    pub crossfade_lead_secs: f32,
+    /// Fade the first track in over this long (0 = full-volume start).
+    pub signon_fade_secs: f32,
+    pub silence: SilenceConfig,
+    /// Folder to look for artist intros in (None = no auto-intros).
+    pub intros_folder: Option<&'a Path>,
+    pub recurring_intro: RecurringIntroConfig,
+    /// Each artist's intro plays at most once per `play_playlist` call.
+    pub intro_once_per_session: bool,
+    /// Intro before the track, or over its start with the track ducked.
+    pub intro_mode: IntroMode,
+    /// Gain for every intro, recurring overlays included.
+    pub intro_gain_db: f32,
+    /// Hard-cut moves into or out of a lecture (see
+    /// `Engine::lecture_crossfade_guard`).
+    pub lecture_hard_cuts: Option<&'a LectureDetector>,
+    /// Tracks shorter than this always end in a hard cut.
+    pub min_crossfade_track_secs: f32,
+}
+
+/// Play through a playlist starting at `start_index`, auto-advancing, as
+/// `options` describe. Tracks flagged `no_crossfade_in` start cleanly after
+/// the previous fade-out.
 /// Blocks until all tracks finish or the process is interrupted.
 /// Returns a `PlaybackResult` with the last index and per-track played durations.
 pub fn play_playlist(
     player: &Player,
     tracks: &[crate::track::Track],
     start_index: usize,
-    crossfade_secs: f32,
-    silence: SilenceConfig,
-    intros_folder: Option<&Path>,
-    recurring_intro: RecurringIntroConfig,
+    options: PlaylistOptions,
 ) -> PlaybackResult {
+    let PlaylistOptions {
+        crossfade_secs,
+        crossfade_lead_secs,
+        signon_fade_secs,
+        silence,
+        intros_folder,
+        recurring_intro,
+        intro_once_per_session,
+        intro_mode,
+        intro_gain_db,
+        lecture_hard_cuts,
+        min_crossfade_track_secs,
+    } = options;
     let crossfade_dur = Duration::from_secs_f32(crossfade_secs.max(0.0));
     let mut current = start_index;
     let mut current_sink: Option<Sink> = None;
     let mut current_monitor: Option<SilenceMonitor> = None;
     let mut current_start_time: Option<Instant> = None;
     let mut played_durations: Vec<(usize, Duration)> = Vec::new();
-    let mut last_intro_artist: Option<String> = None;
+    let mut intro_session = crate::auto_intro::IntroSession::new(intro_once_per_session);
+    let mut signed_on = false;
     // Initialize once before the loop so the interval isn't reset on every
     // track change. The timer only resets when an intro actually plays.
     let mut last_recurring_intro_time: Option<Instant> =
@@ -420,29 +965,25 @@ pub fn play_playlist(
             track.duration_display()
         );
 
-        // Play artist intro if configured and not a consecutive same-artist track
-        if current_sink.is_none() {
-            if let Some(intros_dir) = intros_folder {
-                let same_artist = last_intro_artist
-                    .as_ref()
-                    .map_or(false, |a| a.eq_ignore_ascii_case(&track.artist));
-                if !same_artist {
-                    if let Some(intro_path) =
-                        crate::auto_intro::find_intro(intros_dir, &track.artist)
-                    {
-                        println!("  Playing intro for {}...", track.artist);
-                        match player.play_file_new_sink(&intro_path) {
-                            Ok(intro_sink) => {
-                                while !intro_sink.empty() {
-                                    std::thread::sleep(Duration::from_millis(100));
-                                }
-                                last_intro_artist = Some(track.artist.clone());
-                            }
-                            Err(e) => {
-                                eprintln!("  Intro error: {} — skipping intro", e);
-                            }
-                        }
+        // Artist intro if configured and not already heard (see `IntroSession`)
+        let intro_path = intros_folder
+            .filter(|_| current_sink.is_none() && intro_session.should_play(&track.artist))
+            .and_then(|dir| crate::auto_intro::find_intro(dir, &track.artist));
+        let (intro_before, intro_over) = match intro_mode {
+            IntroMode::Before => (intro_path, None),
+            IntroMode::Over => (None, intro_path),
+        };
+        if let Some(intro_path) = intro_before {
+            println!("  Playing intro for {}...", track.artist);
+            match player.play_intro(&intro_path, intro_gain_db) {
+                Ok(intro_sink) => {
+                    while !intro_sink.empty() {
+                        std::thread::sleep(Duration::from_millis(100));
                     }
+                    intro_session.record(&track.artist);
+                }
+                Err(e) => {
+                    eprintln!("  Intro error: {} — skipping intro", e);
                 }
             }
         }
@@ -455,8 +996,26 @@ pub fn play_playlist(
                 current_start_time.take().unwrap_or_else(Instant::now),
             )
         } else {
-            match start_track(player, &track.path, &silence) {
-                Ok(pair) => (pair.0, pair.1, Instant::now()),
+            let fade = signon_fade_for(signed_on, signon_fade_secs);
+            match start_track(player, &track.path, &silence, fade) {
+                Ok(pair) => {
+                    signed_on = true;
+                    let start_time = Instant::now();
+                    if let Some(intro_path) = &intro_over {
+                        println!("  Playing intro over {}...", track.artist);
+                        let over = play_intro_over(
+                            player,
+                            &pair.0,
+                            intro_path,
+                            &recurring_intro,
+                            intro_gain_db,
+                        );
+                        if over {
+                            intro_session.record(&track.artist);
+                        }
+                    }
+                    (pair.0, pair.1, start_time)
+                }
                 Err(e) => {
                     eprintln!("  Error: {} — skipping", e);
                     current += 1;
@@ -465,15 +1024,20 @@ pub fn play_playlist(
             }
         };
 
-        let track_duration = track.duration;
         let next_index = current + 1;
-        let do_crossfade =
-            should_crossfade(crossfade_secs, track_duration, next_index < tracks.len());
+        let transition = transition_between(
+            crossfade_secs,
+            track,
+            tracks.get(next_index),
+            lecture_hard_cuts,
+            min_crossfade_track_secs,
+        );
+        let do_crossfade = transition != TrackTransition::Sequential;
 
         let mut silence_skipped = false;
 
         if do_crossfade {
-            let crossfade_start = track_duration - crossfade_dur;
+            let crossfade_start = crossfade_start_for(track, crossfade_secs, crossfade_lead_secs);
 
             // Wait until crossfade point, track ends, or silence detected
             loop {
@@ -493,11 +1057,23 @@ pub fn play_playlist(
                     &track.artist,
                     intros_folder,
                     &recurring_intro,
+                    intro_gain_db,
                     &mut last_recurring_intro_time,
                 );
                 std::thread::sleep(Duration::from_millis(50));
             }
 
+            // Next track must start cleanly: fade out, then no overlap
+            if transition == TrackTransition::FadeOutOnly {
+                if !silence_skipped && !sink.empty() {
+                    fade_out_sink(&sink, crossfade_dur);
+                    sink.stop();
+                }
+                played_durations.push((current, start_time.elapsed()));
+                current += 1;
+                continue;
+            }
+
             // Record played duration for this track
             played_durations.push((current, start_time.elapsed()));
 
@@ -552,6 +1128,7 @@ pub fn play_playlist(
                         &track.artist,
                         intros_folder,
                         &recurring_intro,
+                        intro_gain_db,
                         &mut last_recurring_intro_time,
                     );
                     std::thread::sleep(Duration::from_millis(100));
@@ -580,6 +1157,7 @@ fn maybe_play_recurring_intro(
     artist: &str,
     intros_folder: Option<&Path>,
     config: &RecurringIntroConfig,
+    gain_db: f32,
     last_time: &mut Option<Instant>,
 ) {
     if !config.enabled() {
@@ -600,42 +1178,81 @@ fn maybe_play_recurring_intro(
     // Time to play a recurring intro overlay
     if let Some(intro_path) = crate::auto_intro::find_intro(intros_dir, artist) {
         println!("  Recurring intro overlay for {}...", artist);
-        match player.play_file_new_sink(&intro_path) {
+        // Dip the main track before the intro starts
+        let original_volume = main_sink.volume();
+        let duck_volume = config.duck_volume;
+        ramp_sink_volume(main_sink, original_volume, duck_volume, config.duck_ramp());
+        match player.play_intro(&intro_path, gain_db) {
             Ok(overlay_sink) => {
-                // Duck main track volume
-                let original_volume = main_sink.volume();
-                main_sink.set_volume(config.duck_volume);
-
                 // Wait for overlay to finish
                 while !overlay_sink.empty() {
                     std::thread::sleep(Duration::from_millis(50));
                 }
-
-                // Restore main track volume
-                main_sink.set_volume(original_volume);
             }
             Err(e) => {
                 eprintln!("  Recurring intro error: {} — skipping", e);
             }
         }
+        // Bring the main track back up
+        ramp_sink_volume(main_sink, duck_volume, original_volume, config.duck_ramp());
     }
 
     // Reset timer regardless of whether intro was found/played
     *last_time = Some(Instant::now());
 }
 
-/// Start a track, optionally with silence monitoring.
+/// Play `intro_path` on top of the just-started `main_sink`, holding the track
+/// at the duck volume until the intro ends and then ramping it back up (see
+/// `intro_track_gain`). Blocks until done; returns whether the intro played.
+fn play_intro_over(
+    player: &Player,
+    main_sink: &Sink,
+    intro_path: &Path,
+    config: &RecurringIntroConfig,
+    gain_db: f32,
+) -> bool {
+    let original_volume = main_sink.volume();
+    let intro_len = crate::track::decoded_duration(intro_path).unwrap_or_default();
+    let (duck, ramp) = (config.duck_volume, config.duck_ramp());
+    let gain_at =
+        |elapsed| intro_track_gain(IntroMode::Over, elapsed, intro_len, duck, ramp).unwrap_or(1.0);
+    main_sink.set_volume(original_volume * gain_at(Duration::ZERO));
+    let intro_sink = match player.play_intro(intro_path, gain_db) {
+        Ok(sink) => sink,
+        Err(e) => {
+            eprintln!("  Intro error: {} — skipping intro", e);
+            main_sink.set_volume(original_volume);
+            return false;
+        }
+    };
+    let start = Instant::now();
+    while !intro_sink.empty() || start.elapsed() < intro_len + ramp {
+        main_sink.set_volume(original_volume * gain_at(start.elapsed()));
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    main_sink.set_volume(original_volume);
+    true
+}
+
+/// Start a track, optionally with silence monitoring and a fade-in
+/// (`Duration::ZERO` = start at full volume).
 fn start_track(
     player: &Player,
     path: &Path,
     silence: &SilenceConfig,
+    fade: Duration,
 ) -> Result<(Sink, Option<SilenceMonitor>), String> {
-    if silence.enabled() {
-        player
+    match (silence.enabled(), fade.is_zero()) {
+        (true, true) => player
             .play_file_new_sink_monitored(path, silence.threshold, silence.duration())
-            .map(|(s, m)| (s, Some(m)))
-    } else {
-        player.play_file_new_sink(path).map(|s| (s, None))
+            .map(|(s, m)| (s, Some(m))),
+        (true, false) => player
+            .play_file_new_sink_fadein_monitored(path, fade, silence.threshold, silence.duration())
+            .map(|(s, m)| (s, Some(m))),
+        (false, true) => player.play_file_new_sink(path).map(|s| (s, None)),
+        (false, false) => player
+            .play_file_new_sink_fadein(path, fade)
+            .map(|s| (s, None)),
     }
 }
 
@@ -660,6 +1277,62 @@ pub fn list_output_devices() -> Vec<String> {
 }
 
 /// Find an output device by name.
+/// Channels and sample rate of `device`'s default output config.
+fn bus_format(device: Option<&cpal::Device>) -> (u16, u32) {
+    device
+        .and_then(|d| d.default_output_config().ok())
+        .map_or(FALLBACK_BUS_FORMAT, |config| {
+            (config.channels(), config.sample_rate().0)
+        })
+}
+
+/// Device buffer size in frames for about `buffer_ms` at `sample_rate`,
+/// kept within the range the device supports.
+pub fn buffer_frames(buffer_ms: u32, sample_rate: u32, range: &cpal::SupportedBufferSize) -> u32 {
+    let frames = (u64::from(buffer_ms) * u64::from(sample_rate) / 1000).max(1) as u32;
+    match *range {
+        cpal::SupportedBufferSize::Range { min, max } => frames.max(min).min(max),
+        cpal::SupportedBufferSize::Unknown => frames,
+    }
+}
+
+/// Open an output stream on `device` that plays the bus `output`,
+/// converted to the device's sample format.
+fn open_fixed_stream(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    format: cpal::SampleFormat,
+    output: BusOutput,
+) -> Result<cpal::Stream, String> {
+    let stream = match format {
+        cpal::SampleFormat::F32 => build_fixed_stream::<f32>(device, config, output),
+        cpal::SampleFormat::I16 => build_fixed_stream::<i16>(device, config, output),
+        cpal::SampleFormat::U16 => build_fixed_stream::<u16>(device, config, output),
+        other => return Err(format!("Unsupported output sample format {}", other)),
+    };
+    stream.map_err(|e| format!("Failed to open audio output: {}", e))
+}
+
+fn build_fixed_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut output: BusOutput,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: cpal::SizedSample + cpal::FromSample<f32>,
+{
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], _| {
+            for sample in data.iter_mut() {
+                *sample = T::from_sample(output.next().unwrap_or(0.0));
+            }
+        },
+        |e| eprintln!("[Audio] Output stream error: {}", e),
+        None,
+    )
+}
+
 fn find_output_device(name: &str) -> Result<cpal::Device, String> {
     use cpal::traits::HostTrait;
     let host = cpal::default_host();
@@ -679,6 +1352,48 @@ fn find_output_device(name: &str) -> Result<cpal::Device, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::path::PathBuf;
+
+    /// A test source that produces a fixed sequence of samples.
+    struct TestSource {
+        samples: Vec<f32>,
+        pos: usize,
+        sample_rate: u32,
+    }
+
+    impl TestSource {
+        fn constant(value: f32, num_samples: usize, sample_rate: u32) -> Self {
+            TestSource {
+                samples: vec![value; num_samples],
+                pos: 0,
+                sample_rate,
+            }
+        }
+    }
+
+    impl Iterator for TestSource {
+        type Item = f32;
+        fn next(&mut self) -> Option<f32> {
+            let s = self.samples.get(self.pos).copied();
+            self.pos += 1;
+            s
+        }
+    }
+
+    impl Source for TestSource {
+        fn current_frame_len(&self) -> Option<usize> {
+            Some(self.samples.len().saturating_sub(self.pos))
+        }
+        fn channels(&self) -> u16 {
+            1
+        }
+        fn sample_rate(&self) -> u32 {
+            self.sample_rate
+        }
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+    }
 
     #[test]
     fn player_creation_succeeds_or_fails_gracefully() {
@@ -750,11 +1465,61 @@ mod tests {
     #[test]
     fn play_overlay_rejects_missing_file() {
         if let Ok(player) = Player::new() {
-            let result = player.play_overlay(Path::new("nonexistent_overlay.mp3"));
+            let result = player.play_overlay(Path::new("nonexistent_overlay.mp3"), 1.0);
             assert!(result.is_err());
         }
     }
 
+    /// Mix a constant main track with a constant overlay at `gain` through
+    /// rodio's mixer (the same summing the output stream does).
+    fn mix_constant(main: f32, overlay: f32, gain: f32) -> Vec<f32> {
+        let (controller, mixer) = rodio::dynamic_mixer::mixer::<f32>(1, 44_100);
+        controller.add(rodio::buffer::SamplesBuffer::new(1, 44_100, vec![main; 8]));
+        let overlay = rodio::buffer::SamplesBuffer::new(1, 44_100, vec![overlay; 8]);
+        controller.add(overlay_source(overlay, gain));
+        mixer.take(8).collect()
+    }
+
+    #[test]
+    fn overlay_gain_half_contributes_half_amplitude() {
+        let mixed = mix_constant(0.2, 0.4, 0.5);
+        assert_eq!(mixed.len(), 8);
+        assert!(mixed.iter().all(|s| (s - 0.4).abs() < 1e-6));
+    }
+
+    #[test]
+    fn overlay_gain_zero_is_silent() {
+        let mixed = mix_constant(0.2, 0.9, 0.0);
+        assert!(mixed.iter().all(|s| (s - 0.2).abs() < 1e-6));
+    }
+
+    #[test]
+    fn overlay_gain_is_clamped() {
+        assert_eq!(clamp_overlay_gain(-1.0), 0.0);
+        assert_eq!(clamp_overlay_gain(3.5), OVERLAY_GAIN_MAX);
+        assert_eq!(clamp_overlay_gain(0.75), 0.75);
+    }
+
+    fn intro_samples(gain_db: f32) -> Vec<f32> {
+        let source = rodio::buffer::SamplesBuffer::new(1, 44_100, vec![0.1, -0.2, 0.3, 0.0]);
+        intro_source(source, gain_db).collect()
+    }
+
+    #[test]
+    fn intro_gain_scales_amplitude() {
+        let louder = intro_samples(3.0);
+        let factor = 10f32.powf(3.0 / 20.0);
+        for (out, input) in louder.iter().zip([0.1, -0.2, 0.3, 0.0]) {
+            assert!((out - input * factor).abs() < 1e-6, "{} vs {}", out, input);
+        }
+        assert!((louder[1] / -0.2 - 1.4125).abs() < 1e-3);
+    }
+
+    #[test]
+    fn intro_gain_zero_db_is_unchanged() {
+        assert_eq!(intro_samples(0.0), vec![0.1, -0.2, 0.3, 0.0]);
+    }
+
     #[test]
     fn play_stop_mode_rejects_missing_file() {
         if let Ok(player) = Player::new() {
@@ -763,11 +1528,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn play_cart_rejects_missing_file_in_both_modes() {
+        if let Ok(player) = Player::new() {
+            for overlay in [true, false] {
+                let cart = CartSlot {
+                    index: 1,
+                    label: "Missing".to_string(),
+                    path: "nonexistent_cart.mp3".into(),
+                    overlay,
+                };
+                assert!(player.play_cart(&cart, 1.0).is_err());
+            }
+        }
+    }
+
     #[test]
     fn recurring_intro_config_enabled() {
         let cfg = RecurringIntroConfig {
             interval_secs: 900.0,
             duck_volume: 0.3,
+            duck_ramp_secs: 0.5,
         };
         assert!(cfg.enabled());
         assert_eq!(cfg.interval(), Duration::from_secs(900));
@@ -778,6 +1559,7 @@ mod tests {
         let cfg = RecurringIntroConfig {
             interval_secs: 0.0,
             duck_volume: 0.3,
+            duck_ramp_secs: 0.5,
         };
         assert!(!cfg.enabled());
     }
@@ -789,6 +1571,65 @@ mod tests {
         assert_eq!(cfg.duck_volume, 0.3);
     }
 
+    #[test]
+    fn duck_envelope_ramps_down_and_back_up() {
+        let ramp = Duration::from_millis(500);
+        let at = |ms| Duration::from_millis(ms);
+
+        // Duck in: 1.0 → 0.3 across the ramp, never a single step
+        assert_eq!(duck_gain_at(1.0, 0.3, at(0), ramp), 1.0);
+        assert!((duck_gain_at(1.0, 0.3, at(250), ramp) - 0.65).abs() < 1e-6);
+        assert_eq!(duck_gain_at(1.0, 0.3, at(500), ramp), 0.3);
+        assert_eq!(duck_gain_at(1.0, 0.3, at(900), ramp), 0.3);
+        let mut prev = 1.0;
+        for ms in (50..=500).step_by(50) {
+            let gain = duck_gain_at(1.0, 0.3, at(ms), ramp);
+            assert!(gain < prev);
+            assert!(prev - gain < 0.1);
+            prev = gain;
+        }
+
+        // Duck out: back up to 1.0
+        assert_eq!(duck_gain_at(0.3, 1.0, at(0), ramp), 0.3);
+        assert!((duck_gain_at(0.3, 1.0, at(125), ramp) - 0.475).abs() < 1e-6);
+        assert_eq!(duck_gain_at(0.3, 1.0, at(500), ramp), 1.0);
+
+        // No ramp configured: immediate step
+        assert_eq!(duck_gain_at(1.0, 0.3, at(0), Duration::ZERO), 0.3);
+        assert_eq!(
+            RecurringIntroConfig::disabled().duck_ramp(),
+            Duration::from_millis(500)
+        );
+    }
+
+    /// Track gain `ms` into a 3 s intro with a 0.3 duck and 0.5 s ramp.
+    fn intro_gain(mode: IntroMode, ms: u64) -> Option<f32> {
+        let at = Duration::from_millis;
+        intro_track_gain(mode, at(ms), at(3000), 0.3, at(500))
+    }
+
+    #[test]
+    fn intro_before_plays_track_after_intro() {
+        for ms in [0, 1000, 2999] {
+            assert_eq!(intro_gain(IntroMode::Before, ms), None);
+        }
+        // Sequential: full volume from the moment the intro ends, no duck
+        assert_eq!(intro_gain(IntroMode::Before, 3000), Some(1.0));
+        assert_eq!(intro_gain(IntroMode::Before, 3200), Some(1.0));
+    }
+
+    #[test]
+    fn intro_over_ducks_track_for_intro_length() {
+        // Track runs under the whole intro at the duck volume
+        for ms in [0, 1500, 2999] {
+            assert_eq!(intro_gain(IntroMode::Over, ms), Some(0.3));
+        }
+        // Then ramps back to full
+        let mid = intro_gain(IntroMode::Over, 3250).unwrap();
+        assert!((mid - 0.65).abs() < 1e-6);
+        assert_eq!(intro_gain(IntroMode::Over, 3500), Some(1.0));
+    }
+
     #[test]
     fn should_crossfade_basic_cases() {
         // Disabled when crossfade_secs is 0
@@ -809,4 +1650,233 @@ mod tests {
         // Edge: track slightly longer than 2x crossfade
         assert!(should_crossfade(3.0, Duration::from_secs(7), true));
     }
+
+    fn track_of(duration_secs: u64) -> crate::track::Track {
+        crate::track::Track {
+            path: PathBuf::from("test.mp3"),
+            title: "Song".into(),
+            artist: "Artist".into(),
+            duration: Duration::from_secs(duration_secs),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn crossfade_start_without_lead_is_fade_length_before_end() {
+        assert_eq!(
+            crossfade_start_for(&track_of(300), 3.0, 0.0),
+            Duration::from_secs(297)
+        );
+        assert_eq!(
+            crossfade_start_for(&track_of(60), 5.0, 0.0),
+            Duration::from_secs(55)
+        );
+    }
+
+    #[test]
+    fn crossfade_start_moves_earlier_with_lead() {
+        assert_eq!(
+            crossfade_start_for(&track_of(300), 3.0, 7.0),
+            Duration::from_secs(290)
+        );
+        // Negative lead is ignored
+        assert_eq!(
+            crossfade_start_for(&track_of(300), 3.0, -2.0),
+            Duration::from_secs(297)
+        );
+    }
+
+    #[test]
+    fn crossfade_start_never_before_track_begins() {
+        assert_eq!(crossfade_start_for(&track_of(8), 3.0, 10.0), Duration::ZERO);
+        assert_eq!(crossfade_start_for(&track_of(0), 3.0, 0.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn crossfade_start_driven_by_cue_out() {
+        let mut track = track_of(300);
+        track.cue_out = Some(Duration::from_secs(240));
+        assert_eq!(
+            crossfade_start_for(&track, 3.0, 2.0),
+            Duration::from_secs(235)
+        );
+
+        // cue_out is measured from cue_in for cue-sheet segments
+        track.cue_in = Some(Duration::from_secs(100));
+        assert_eq!(
+            crossfade_start_for(&track, 3.0, 2.0),
+            Duration::from_secs(135)
+        );
+    }
+
+    #[test]
+    fn no_crossfade_in_track_gets_fade_out_without_overlap() {
+        let outgoing = track_of(300);
+        let mut bed = track_of(120);
+        bed.no_crossfade_in = true;
+
+        // Outgoing track still fades, but the flagged track is not overlapped
+        assert_eq!(
+            transition_for(3.0, outgoing.duration, Some(&bed)),
+            TrackTransition::FadeOutOnly
+        );
+        // The flagged track itself crossfades out normally
+        let next = track_of(200);
+        assert_eq!(
+            transition_for(3.0, bed.duration, Some(&next)),
+            TrackTransition::Crossfade
+        );
+        // Crossfade disabled or last track: plain sequential
+        assert_eq!(
+            transition_for(0.0, outgoing.duration, Some(&bed)),
+            TrackTransition::Sequential
+        );
+        assert_eq!(
+            transition_for(3.0, outgoing.duration, None),
+            TrackTransition::Sequential
+        );
+    }
+
+    #[test]
+    fn lecture_transitions_are_hard_cuts_when_enabled() {
+        let detector = LectureDetector::new();
+        let music = track_of(300);
+        let mut next_song = track_of(200);
+        next_song.artist = "Band".into();
+        let mut shiur = track_of(1800);
+        shiur.artist = "Rabbi Cohen".into();
+
+        // Music → lecture and lecture → music: hard cut
+        assert_eq!(
+            transition_between(3.0, &music, Some(&shiur), Some(&detector), 0.0),
+            TrackTransition::Sequential
+        );
+        assert_eq!(
+            transition_between(3.0, &shiur, Some(&music), Some(&detector), 0.0),
+            TrackTransition::Sequential
+        );
+        // Music → music still crossfades
+        assert_eq!(
+            transition_between(3.0, &music, Some(&next_song), Some(&detector), 0.0),
+            TrackTransition::Crossfade
+        );
+        // Flag off: current behavior, lectures crossfade like anything else
+        assert_eq!(
+            transition_between(3.0, &music, Some(&shiur), None, 0.0),
+            TrackTransition::Crossfade
+        );
+    }
+
+    #[test]
+    fn short_tracks_hard_cut_under_min_crossfade_length() {
+        let stinger = track_of(2);
+        let song = track_of(240);
+        let next = track_of(200);
+
+        // 2s stinger under a 5s threshold: hard cut out of it
+        assert_eq!(
+            transition_between(0.5, &stinger, Some(&next), None, 5.0),
+            TrackTransition::Sequential
+        );
+        // A 4-minute song still crossfades
+        assert_eq!(
+            transition_between(0.5, &song, Some(&next), None, 5.0),
+            TrackTransition::Crossfade
+        );
+        // Threshold 0: current behavior, the stinger crossfades
+        assert_eq!(
+            transition_between(0.5, &stinger, Some(&next), None, 0.0),
+            TrackTransition::Crossfade
+        );
+        assert!(!too_short_to_crossfade(Duration::from_secs(2), 0.0));
+        assert!(!too_short_to_crossfade(Duration::from_secs(5), 5.0));
+    }
+
+    #[test]
+    fn crossfade_gains_follow_curve() {
+        assert_eq!(crossfade_gains(0.0, CrossfadeCurve::Linear), (1.0, 0.0));
+        assert_eq!(crossfade_gains(0.25, CrossfadeCurve::Linear), (0.75, 0.25));
+        assert_eq!(crossfade_gains(2.0, CrossfadeCurve::Linear), (0.0, 1.0));
+
+        // Equal power keeps out² + in² at 1 throughout the fade
+        for p in [0.0, 0.3, 0.5, 0.9, 1.0] {
+            let (out, inc) = crossfade_gains(p, CrossfadeCurve::EqualPower);
+            assert!((out * out + inc * inc - 1.0).abs() < 1e-5);
+        }
+        assert_eq!(
+            CrossfadeCurve::from_str_loose("Equal-Power").unwrap(),
+            CrossfadeCurve::EqualPower
+        );
+        assert!(CrossfadeCurve::from_str_loose("s-curve").is_err());
+    }
+
+    #[test]
+    fn signon_fade_only_applies_to_first_track() {
+        assert_eq!(signon_fade_for(false, 2.0), Duration::from_secs(2));
+        assert_eq!(signon_fade_for(true, 2.0), Duration::ZERO);
+        // Disabled by default
+        assert_eq!(signon_fade_for(false, 0.0), Duration::ZERO);
+        assert_eq!(signon_fade_for(false, -1.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn signon_fade_ramps_first_track_but_not_second() {
+        // 1000 Hz mono, 1 second of full-scale audio, 0.5s sign-on fade.
+        let first_fade = signon_fade_for(false, 0.5);
+        let first: Vec<f32> = TestSource::constant(1.0, 1000, 1000)
+            .fade_in(first_fade)
+            .collect();
+        assert!(first[0] < 0.05, "first sample should start near silence");
+        assert!(
+            first[250] > 0.3 && first[250] < 0.7,
+            "mid-fade gain should be partial"
+        );
+        assert!(first[100] < first[400], "gain should ramp up");
+        assert!((first[600] - 1.0).abs() < 1e-6, "full gain after the fade");
+
+        // The second track is started after sign-on, so it gets no extra envelope
+        // (start_track only wraps the source in a fade when the fade is non-zero).
+        let second_fade = signon_fade_for(true, 0.5);
+        assert!(second_fade.is_zero());
+        let second: Vec<f32> = TestSource::constant(1.0, 1000, 1000).collect();
+        assert!(second.iter().all(|s| (s - 1.0).abs() < 1e-6));
+    }
+
+    #[test]
+    fn resampled_frames_scales_by_rate_ratio() {
+        // One second at 44.1 kHz is one second at 48 kHz
+        assert_eq!(resampled_frames(44_100, 44_100, 48_000), 48_000);
+        assert_eq!(resampled_frames(48_000, 48_000, 44_100), 44_100);
+        assert_eq!(resampled_frames(22_050, 44_100, 48_000), 24_000);
+        // A partial trailing frame rounds up
+        assert_eq!(resampled_frames(1, 44_100, 48_000), 2);
+        assert_eq!(resampled_frames(1000, 1000, 1000), 1000);
+    }
+
+    #[test]
+    fn resample_to_produces_one_second_at_target_rate() {
+        let resampled = resample_to(TestSource::constant(0.5, 44_100, 44_100), 48_000);
+        assert_eq!(resampled.sample_rate(), 48_000);
+        assert_eq!(resampled.channels(), 1);
+        let samples: Vec<f32> = resampled.collect();
+        let expected = resampled_frames(44_100, 44_100, 48_000);
+        // Linear interpolation may drop the very last frame
+        assert!(
+            samples.len().abs_diff(expected) <= 2,
+            "got {} samples, expected about {}",
+            samples.len(),
+            expected
+        );
+        assert!(samples.iter().all(|s| (s - 0.5).abs() < 1e-3));
+    }
+
+    #[test]
+    fn buffer_frames_stay_within_the_device_range() {
+        let range = cpal::SupportedBufferSize::Range { min: 64, max: 4096 };
+        assert_eq!(buffer_frames(50, 48_000, &range), 2400);
+        assert_eq!(buffer_frames(1, 48_000, &range), 64);
+        assert_eq!(buffer_frames(2000, 48_000, &range), 4096);
+        let unknown = cpal::SupportedBufferSize::Unknown;
+        assert_eq!(buffer_frames(2000, 48_000, &unknown), 96_000);
+    }
 }
@@ -1,5 +1,9 @@
+use crate::lecture_detector::LectureDetector;
 use crate::track::Track;
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
 use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +14,82 @@ pub struct Playlist {
     pub source_path: Option<String>,
     pub tracks: Vec<Track>,
     pub current_index: Option<usize>,
+    /// Automation (true) starts the next track when one ends; live-assist
+    /// (false) stops on the finished track and waits for the operator.
+    #[serde(default = "default_auto_advance")]
+    pub auto_advance: bool,
+    /// Frozen against edits while on air: removing, reordering and
+    /// shuffling are refused. Playback still moves `current_index`.
+    #[serde(default)]
+    pub locked: bool,
+}
+
+fn default_auto_advance() -> bool {
+    true
+}
+
+/// What auto-advance does after the last track of the active playlist.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndOfPlaylistAction {
+    /// Stop playback and clear the current index.
+    #[default]
+    Stop,
+    /// Wrap around to the first track of the same playlist.
+    Loop,
+    /// Activate the named playlist and play it from its first track.
+    Fallback(String),
+}
+
+/// Summary of a playlist's contents, from `Playlist::stats`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlaylistStats {
+    pub total_tracks: usize,
+    /// Sum of every track's effective duration.
+    pub total_duration_secs: f64,
+    /// Distinct artists, compared case-insensitively. Blank artists aren't counted.
+    pub unique_artists: usize,
+    pub lecture_count: usize,
+    pub music_count: usize,
+    /// 0 for an empty playlist.
+    pub average_duration_secs: f64,
+}
+
+impl fmt::Display for EndOfPlaylistAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndOfPlaylistAction::Stop => write!(f, "stop"),
+            EndOfPlaylistAction::Loop => write!(f, "loop"),
+            EndOfPlaylistAction::Fallback(_) => write!(f, "fallback"),
+        }
+    }
+}
+
+impl EndOfPlaylistAction {
+    /// Parse an action name (case-insensitive). `fallback` requires the
+    /// target playlist name.
+    pub fn from_parts(action: &str, fallback: Option<&str>) -> Result<Self, String> {
+        match action.trim().to_lowercase().as_str() {
+            "stop" => Ok(EndOfPlaylistAction::Stop),
+            "loop" => Ok(EndOfPlaylistAction::Loop),
+            "fallback" => match fallback.map(str::trim).filter(|n| !n.is_empty()) {
+                Some(name) => Ok(EndOfPlaylistAction::Fallback(name.to_string())),
+                None => Err("Fallback action requires a playlist name".to_string()),
+            },
+            _ => Err(format!(
+                "Unknown end-of-playlist action '{}'. Expected: stop, loop, fallback",
+                action
+            )),
+        }
+    }
+
+    /// The fallback playlist name, if this is a fallback action.
+    pub fn fallback_playlist(&self) -> Option<&str> {
+        match self {
+            EndOfPlaylistAction::Fallback(name) => Some(name),
+            _ => None,
+        }
+    }
 }
 
 impl Playlist {
@@ -20,9 +100,22 @@ impl Playlist {
             source_path: None,
             tracks: Vec::new(),
             current_index: None,
+            auto_advance: true,
+            locked: false,
         }
     }
 
+    /// Err when the playlist is locked against edits.
+    pub fn ensure_unlocked(&self) -> Result<(), String> {
+        if self.locked {
+            return Err(format!(
+                "Playlist '{}' is locked; unlock it to edit",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+
     /// Add a track by parsing metadata from a file path.
     pub fn add_track(&mut self, path: &Path) -> Result<usize, String> {
         let track = Track::from_path(path)?;
@@ -32,6 +125,7 @@ impl Playlist {
 
     /// Remove a track by index. Returns the removed track.
     pub fn remove_track(&mut self, index: usize) -> Result<Track, String> {
+        self.ensure_unlocked()?;
         if index >= self.tracks.len() {
             return Err(format!(
                 "Index {} out of range (playlist has {} tracks)",
@@ -53,6 +147,7 @@ impl Playlist {
 
     /// Move a track from one position to another.
     pub fn reorder(&mut self, from: usize, to: usize) -> Result<(), String> {
+        self.ensure_unlocked()?;
         if from >= self.tracks.len() || to >= self.tracks.len() {
             return Err(format!(
                 "Index out of range (playlist has {} tracks)",
@@ -89,6 +184,205 @@ impl Playlist {
     pub fn track_count(&self) -> usize {
         self.tracks.len()
     }
+
+    /// Track, artist and duration totals, with tracks classified as lecture
+    /// or music by `detector`.
+    pub fn stats(&self, detector: &LectureDetector) -> PlaylistStats {
+        let total: std::time::Duration = self.tracks.iter().map(Track::effective_duration).sum();
+        let artists: HashSet<String> = self
+            .tracks
+            .iter()
+            .filter(|t| !t.artist.trim().is_empty())
+            .map(|t| t.artist.trim().to_lowercase())
+            .collect();
+        let lecture_count = self
+            .tracks
+            .iter()
+            .filter(|t| detector.is_lecture(&t.artist))
+            .count();
+        let total_tracks = self.tracks.len();
+        let average_duration_secs = if total_tracks == 0 {
+            0.0
+        } else {
+            total.as_secs_f64() / total_tracks as f64
+        };
+        PlaylistStats {
+            total_tracks,
+            total_duration_secs: total.as_secs_f64(),
+            unique_artists: artists.len(),
+            lecture_count,
+            music_count: total_tracks - lecture_count,
+            average_duration_secs,
+        }
+    }
+
+    /// Groups of track indices whose files hold the same audio (see
+    /// `track::audio_fingerprint`), catching copies stored under different
+    /// names. Every file is decoded, so this is slow on long playlists and
+    /// only runs on request. Cue-sheet segments of one file count as
+    /// duplicates only with the same cue points; tracks that can't be
+    /// decoded are left out. Groups hold two or more indices, ordered by
+    /// their first track.
+    pub fn find_duplicates_by_audio(&self) -> Vec<Vec<usize>> {
+        let mut fingerprints: HashMap<&Path, Option<String>> = HashMap::new();
+        let mut group_of = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for (i, track) in self.tracks.iter().enumerate() {
+            let fingerprint = fingerprints
+                .entry(track.path.as_path())
+                .or_insert_with(|| crate::track::audio_fingerprint(&track.path));
+            let Some(fingerprint) = fingerprint.clone() else {
+                continue;
+            };
+            let key = (fingerprint, track.cue_in, track.cue_out);
+            let group = *group_of.entry(key).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(i);
+        }
+        groups.retain(|group| group.len() > 1);
+        groups
+    }
+
+    /// First track at or after `from` that may air. With `skip_advisory`,
+    /// advisory tracks are passed over.
+    pub fn next_airable(&self, from: usize, skip_advisory: bool) -> Option<usize> {
+        (from..self.tracks.len()).find(|&i| !(skip_advisory && self.tracks[i].advisory))
+    }
+
+    /// Last track before `before` that may air, searching backwards. With
+    /// `skip_advisory`, advisory tracks are passed over.
+    pub fn prev_airable(&self, before: usize, skip_advisory: bool) -> Option<usize> {
+        (0..before.min(self.tracks.len()))
+            .rev()
+            .find(|&i| !(skip_advisory && self.tracks[i].advisory))
+    }
+
+    /// When the track at `next` is by `artist` (case-insensitive), move the
+    /// nearest later track by someone else up into `next` so the same
+    /// artist doesn't air twice in a row. With `skip_advisory`, advisory
+    /// tracks aren't candidates. A locked playlist is never reordered.
+    /// Returns true when a track was moved.
+    pub fn pull_forward_other_artist(
+        &mut self,
+        next: usize,
+        artist: &str,
+        skip_advisory: bool,
+    ) -> bool {
+        let same = |t: &Track| t.artist.eq_ignore_ascii_case(artist);
+        if self.locked || !self.tracks.get(next).is_some_and(same) {
+            return false;
+        }
+        let found = (next + 1..self.tracks.len())
+            .find(|&i| !(same(&self.tracks[i]) || (skip_advisory && self.tracks[i].advisory)));
+        match found {
+            Some(i) => {
+                let track = self.tracks.remove(i);
+                self.tracks.insert(next, track);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Projected air time of each track while `current` is playing and is
+    /// `elapsed` in: the current track started at `now - elapsed`, and each
+    /// later track starts after the durations before it. Tracks before
+    /// `current` get None.
+    pub fn projected_start_times(
+        &self,
+        current: usize,
+        elapsed: std::time::Duration,
+        now: DateTime<Local>,
+    ) -> Vec<Option<DateTime<Local>>> {
+        let to_chrono = |d| chrono::Duration::from_std(d).unwrap_or_default();
+        let mut next_start = now - to_chrono(elapsed);
+        self.tracks
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                if i < current {
+                    return None;
+                }
+                let start = next_start;
+                next_start += to_chrono(t.effective_duration());
+                Some(start)
+            })
+            .collect()
+    }
+
+    /// Shuffle so that tracks by the same artist (case-insensitive) have at
+    /// least `min_artist_gap` other tracks between them, when feasible.
+    /// Deterministic for a given `seed`. Returns how many placements had to
+    /// break the gap (0 = every constraint met).
+    pub fn shuffle_with_constraints(&mut self, seed: u64, min_artist_gap: usize) -> usize {
+        self.shuffle_grouped(seed, min_artist_gap, |t| t.artist.to_lowercase())
+    }
+
+    /// Constrained shuffle spacing tracks by an arbitrary group key. Greedy:
+    /// each slot takes a track from the largest group not used in the last
+    /// `min_gap` slots; if every remaining group was, it falls back to the
+    /// least recently used one and counts a violation.
+    pub fn shuffle_grouped<F>(&mut self, seed: u64, min_gap: usize, group_of: F) -> usize
+    where
+        F: Fn(&Track) -> String,
+    {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        let mut order: Vec<usize> = (0..self.tracks.len()).collect();
+        rng.shuffle(&mut order);
+
+        // Bucket the shuffled indices by group
+        let mut groups: Vec<(String, VecDeque<usize>)> = Vec::new();
+        for i in order {
+            let key = group_of(&self.tracks[i]);
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, bucket)) => bucket.push_back(i),
+                None => groups.push((key, VecDeque::from([i]))),
+            }
+        }
+
+        let mut placed: Vec<usize> = Vec::with_capacity(self.tracks.len());
+        // Group indices of the last `min_gap` placements, oldest first
+        let mut recent: VecDeque<usize> = VecDeque::new();
+        let mut violations = 0;
+        while placed.len() < self.tracks.len() {
+            let available: Vec<usize> = (0..groups.len())
+                .filter(|g| !groups[*g].1.is_empty() && !recent.contains(g))
+                .collect();
+            let group = if available.is_empty() {
+                violations += 1;
+                *recent
+                    .iter()
+                    .find(|g| !groups[**g].1.is_empty())
+                    .expect("remaining tracks belong to a recent group")
+            } else {
+                let most = available
+                    .iter()
+                    .map(|g| groups[*g].1.len())
+                    .max()
+                    .unwrap_or(0);
+                let largest: Vec<usize> = available
+                    .into_iter()
+                    .filter(|g| groups[*g].1.len() == most)
+                    .collect();
+                largest[rng.usize(..largest.len())]
+            };
+            placed.extend(groups[group].1.pop_front());
+            recent.retain(|g| *g != group);
+            recent.push_back(group);
+            if recent.len() > min_gap {
+                recent.pop_front();
+            }
+        }
+
+        self.current_index = self
+            .current_index
+            .and_then(|ci| placed.iter().position(|&i| i == ci));
+        let mut old: Vec<Option<Track>> = self.tracks.drain(..).map(Some).collect();
+        self.tracks = placed.iter().filter_map(|&i| old[i].take()).collect();
+        violations
+    }
 }
 
 #[cfg(test)]
@@ -108,9 +402,204 @@ mod tests {
             title: name.into(),
             artist: "X".into(),
             duration: std::time::Duration::new(60, 0),
-            played_duration: None,
-            has_intro: false,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn projected_start_times_add_prior_durations() {
+        use chrono::TimeZone;
+        let mut pl = Playlist::new(1, "Test".to_string());
+        for (name, secs) in [("a", 60), ("b", 200), ("c", 95), ("d", 30)] {
+            let mut t = make_track(name);
+            t.duration = std::time::Duration::from_secs(secs);
+            pl.tracks.push(t);
+        }
+        let now = Local.with_ymd_and_hms(2026, 10, 16, 9, 0, 0).unwrap();
+        let secs = chrono::Duration::seconds;
+
+        // Track b just started: c starts after b, d after b + c
+        let starts = pl.projected_start_times(1, std::time::Duration::ZERO, now);
+        assert_eq!(starts[0], None);
+        assert_eq!(starts[1], Some(now));
+        assert_eq!(starts[2], Some(now + secs(200)));
+        assert_eq!(starts[3], Some(now + secs(200 + 95)));
+
+        // 50 s into b, everything shifts 50 s earlier
+        let starts = pl.projected_start_times(1, std::time::Duration::from_secs(50), now);
+        assert_eq!(starts[1], Some(now - secs(50)));
+        assert_eq!(starts[3], Some(now + secs(245)));
+    }
+
+    fn make_artist_track(title: &str, artist: &str) -> crate::track::Track {
+        let mut track = make_track(title);
+        track.artist = artist.into();
+        track
+    }
+
+    fn playlist_of(artists: &[(&str, usize)]) -> Playlist {
+        let mut pl = Playlist::new(1, "Shuffle".to_string());
+        for (artist, count) in artists {
+            for n in 0..*count {
+                let title = format!("{} {}", artist, n);
+                pl.tracks.push(make_artist_track(&title, artist));
+            }
         }
+        pl
+    }
+
+    #[test]
+    fn stats_count_artists_lectures_and_durations() {
+        let mut pl = Playlist::new(1, "Stats".to_string());
+        for (artist, secs) in [
+            ("Rabbi Cohen", 1800),
+            ("The Band", 240),
+            ("the band", 180),
+            ("Singer", 300),
+            ("Rock Group", 200),
+            ("", 80),
+        ] {
+            let mut track = make_artist_track("t", artist);
+            track.duration = std::time::Duration::from_secs(secs);
+            pl.tracks.push(track);
+        }
+        // Verified length wins over the tag duration
+        pl.tracks[3].verified_duration = Some(std::time::Duration::from_secs(500));
+
+        let mut detector = LectureDetector::new();
+        detector.add_blacklist("Rock Group");
+        let stats = pl.stats(&detector);
+        assert_eq!(stats.total_tracks, 6);
+        assert_eq!(stats.total_duration_secs, 3000.0);
+        assert_eq!(stats.average_duration_secs, 500.0);
+        // "The Band" and "the band" are one artist; the blank one isn't counted
+        assert_eq!(stats.unique_artists, 4);
+        assert_eq!(stats.lecture_count, 1);
+        assert_eq!(stats.music_count, 5);
+    }
+
+    #[test]
+    fn stats_of_empty_playlist_are_zero() {
+        let stats = Playlist::new(1, "Empty".to_string()).stats(&LectureDetector::new());
+        assert_eq!(stats.total_tracks, 0);
+        assert_eq!(stats.total_duration_secs, 0.0);
+        assert_eq!(stats.average_duration_secs, 0.0);
+        assert_eq!(stats.unique_artists, 0);
+    }
+
+    #[test]
+    fn constrained_shuffle_keeps_same_artist_apart() {
+        for seed in 0..25 {
+            let mut pl = playlist_of(&[("A", 3), ("B", 3), ("C", 3), ("D", 1)]);
+            let violations = pl.shuffle_with_constraints(seed, 2);
+            assert_eq!(violations, 0, "seed {}", seed);
+            assert_eq!(pl.track_count(), 10);
+            for window in pl.tracks.windows(3) {
+                assert_ne!(window[0].artist, window[1].artist, "seed {}", seed);
+                assert_ne!(window[0].artist, window[2].artist, "seed {}", seed);
+            }
+        }
+    }
+
+    #[test]
+    fn constrained_shuffle_is_deterministic_per_seed() {
+        let mut first = playlist_of(&[("A", 4), ("B", 4), ("C", 4)]);
+        let mut second = first.clone();
+        first.shuffle_with_constraints(42, 1);
+        second.shuffle_with_constraints(42, 1);
+        let titles = |pl: &Playlist| -> Vec<String> {
+            pl.tracks
+                .iter()
+                .map(|t| t.title.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(titles(&first), titles(&second));
+
+        let mut sorted = titles(&first);
+        sorted.sort();
+        assert_eq!(sorted.len(), 12);
+        sorted.dedup();
+        assert_eq!(sorted.len(), 12);
+    }
+
+    #[test]
+    fn constrained_shuffle_reports_unsatisfiable_gap() {
+        let mut pl = playlist_of(&[("A", 4), ("B", 1)]);
+        let violations = pl.shuffle_with_constraints(7, 1);
+        assert!(violations > 0);
+        assert_eq!(pl.track_count(), 5);
+    }
+
+    #[test]
+    fn constrained_shuffle_follows_current_track() {
+        let mut pl = playlist_of(&[("A", 2), ("B", 2), ("C", 2)]);
+        pl.current_index = Some(3);
+        let current_title = pl.tracks[3].title.clone();
+        pl.shuffle_with_constraints(3, 1);
+        let ci = pl.current_index.unwrap();
+        assert_eq!(pl.tracks[ci].title, current_title);
+    }
+
+    #[test]
+    fn next_airable_skips_advisory_only_when_asked() {
+        let mut pl = Playlist::new(1, "Test".to_string());
+        for name in ["A", "B", "C", "D"] {
+            pl.tracks.push(make_track(name));
+        }
+        pl.tracks[1].advisory = true;
+        pl.tracks[2].advisory = true;
+
+        assert_eq!(pl.next_airable(1, false), Some(1));
+        assert_eq!(pl.next_airable(1, true), Some(3));
+        assert_eq!(pl.next_airable(0, true), Some(0));
+        pl.tracks[3].advisory = true;
+        assert_eq!(pl.next_airable(1, true), None);
+        assert_eq!(pl.next_airable(4, false), None);
+    }
+
+    #[test]
+    fn prev_airable_searches_backwards() {
+        let mut pl = Playlist::new(1, "Test".to_string());
+        for name in ["A", "B", "C", "D"] {
+            pl.tracks.push(make_track(name));
+        }
+        pl.tracks[1].advisory = true;
+        pl.tracks[2].advisory = true;
+
+        assert_eq!(pl.prev_airable(3, false), Some(2));
+        assert_eq!(pl.prev_airable(3, true), Some(0));
+        assert_eq!(pl.prev_airable(9, true), Some(3));
+        assert_eq!(pl.prev_airable(0, false), None);
+        pl.tracks[0].advisory = true;
+        assert_eq!(pl.prev_airable(3, true), None);
+    }
+
+    #[test]
+    fn pull_forward_other_artist_moves_nearest_different_artist() {
+        let mut pl = Playlist::new(1, "Test".to_string());
+        pl.tracks.push(make_artist_track("A", "X"));
+        pl.tracks.push(make_artist_track("B", "x"));
+        pl.tracks.push(make_artist_track("C", "Y"));
+        pl.tracks.push(make_artist_track("D", "Z"));
+
+        assert!(pl.pull_forward_other_artist(1, "X", false));
+        let titles: Vec<&str> = pl.tracks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["A", "C", "B", "D"]);
+
+        // Next track is already by someone else: nothing moves
+        assert!(!pl.pull_forward_other_artist(1, "X", false));
+        // No alternative left after the repeat
+        let mut only_x = playlist_of(&[("X", 3)]);
+        assert!(!only_x.pull_forward_other_artist(1, "X", false));
+        // Advisory alternatives are skipped when asked
+        let mut pl = playlist_of(&[("X", 2), ("Y", 1)]);
+        pl.tracks[2].advisory = true;
+        assert!(!pl.pull_forward_other_artist(1, "X", true));
+        // A locked playlist keeps its order
+        let mut pl = playlist_of(&[("X", 2), ("Y", 1)]);
+        pl.locked = true;
+        assert!(!pl.pull_forward_other_artist(1, "X", false));
+        assert_eq!(pl.tracks[1].artist, "X");
     }
 
     #[test]
@@ -179,4 +668,84 @@ mod tests {
         pl.remove_track(0).unwrap();
         assert_eq!(pl.current_index, Some(1));
     }
+
+    #[test]
+    fn locked_playlist_refuses_remove_and_reorder() {
+        let mut pl = Playlist::new(1, "Live".to_string());
+        pl.tracks = vec![make_track("A"), make_track("B"), make_track("C")];
+        pl.current_index = Some(0);
+        pl.locked = true;
+
+        assert!(pl.remove_track(1).unwrap_err().contains("locked"));
+        assert!(pl.reorder(0, 2).is_err());
+        assert_eq!(pl.track_count(), 3);
+        assert_eq!(pl.tracks[0].title, "A");
+
+        pl.locked = false;
+        pl.reorder(0, 2).unwrap();
+        assert_eq!(pl.tracks[2].title, "A");
+        pl.remove_track(0).unwrap();
+        assert_eq!(pl.track_count(), 2);
+    }
+
+    #[test]
+    fn end_of_playlist_action_parses() {
+        let fallback = EndOfPlaylistAction::from_parts("Fallback", Some("Overnight")).unwrap();
+        assert_eq!(
+            fallback,
+            EndOfPlaylistAction::Fallback("Overnight".to_string())
+        );
+        assert_eq!(fallback.fallback_playlist(), Some("Overnight"));
+        assert_eq!(
+            EndOfPlaylistAction::from_parts("LOOP", None).unwrap(),
+            EndOfPlaylistAction::Loop
+        );
+        assert!(EndOfPlaylistAction::from_parts("fallback", Some("  ")).is_err());
+        assert!(EndOfPlaylistAction::from_parts("repeat", None).is_err());
+        assert_eq!(EndOfPlaylistAction::default().to_string(), "stop");
+    }
+
+    /// Track for a short synthetic WAV at `path` holding `samples`.
+    fn wav_track(path: &Path, samples: &[i16]) -> crate::track::Track {
+        crate::test_audio::write_wav(path, samples, 8000);
+        let mut track = make_track("wav");
+        track.path = path.to_path_buf();
+        track
+    }
+
+    #[test]
+    fn audio_duplicates_group_identical_files_under_other_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let tone: Vec<i16> = (0..800)
+            .map(|n| ((n as f32 * 0.05).sin() * 16000.0) as i16)
+            .collect();
+        let mut pl = Playlist::new(1, "Dupes".to_string());
+        let song = wav_track(&dir.path().join("song.wav"), &tone);
+        let other = wav_track(&dir.path().join("other.wav"), &[8000; 800]);
+        pl.tracks.extend([song, other]);
+        let copy = dir.path().join("Song (1).wav");
+        std::fs::copy(dir.path().join("song.wav"), &copy).unwrap();
+        let mut copied = make_track("copy");
+        copied.path = copy;
+        pl.tracks.push(copied);
+        pl.tracks.push(make_track("missing"));
+
+        assert_eq!(pl.find_duplicates_by_audio(), vec![vec![0, 2]]);
+    }
+
+    #[test]
+    fn audio_duplicates_ignore_different_audio_and_cue_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut pl = Playlist::new(1, "Distinct".to_string());
+        let a = wav_track(&dir.path().join("a.wav"), &[3000; 800]);
+        let b = wav_track(&dir.path().join("b.wav"), &[6000; 800]);
+        pl.tracks.extend([a, b]);
+        assert!(pl.find_duplicates_by_audio().is_empty());
+
+        // Two segments of the same file are different tracks
+        let mut segment = pl.tracks[0].clone();
+        segment.cue_in = Some(std::time::Duration::from_millis(50));
+        pl.tracks.push(segment);
+        assert!(pl.find_duplicates_by_audio().is_empty());
+    }
 }
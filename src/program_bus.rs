@@ -0,0 +1,80 @@
+//! Program bus — one mix of everything the player sends to air.
+//!
+//! Every sink a `Player` opens (the track, its crossfade partner, overlays,
+//! carts and intros) plays into a single mixer instead of straight to the
+//! output stream. The mix then passes once through the bus processing
+//! (AGC, then channel mapping), so processing sees what actually goes out:
+//! after each sink's volume, with its state carried across track changes.
+
+use crate::agc::{AgcConfig, AgcControl, AgcSource};
+use crate::channel_map::{ChannelMapControl, ChannelMapSource, ChannelMode};
+use rodio::dynamic_mixer::{self, DynamicMixer, DynamicMixerController};
+use rodio::source::Zero;
+use rodio::{OutputStreamHandle, Sink};
+use std::sync::Arc;
+
+/// The processed mix a bus sends to air, for callers that feed the device
+/// themselves (see `ProgramBus::new`).
+pub type BusOutput = ChannelMapSource<AgcSource<DynamicMixer<f32>>>;
+
+/// The mixer every program sink feeds, and the controls for the
+/// processing after it.
+pub struct ProgramBus {
+    mixer: Arc<DynamicMixerController<f32>>,
+    agc: AgcControl,
+    channel_map: ChannelMapControl,
+}
+
+impl ProgramBus {
+    /// Start a bus mixing to `channels` at `sample_rate` Hz and play it on
+    /// `stream`.
+    pub fn start(
+        stream: &OutputStreamHandle,
+        channels: u16,
+        sample_rate: u32,
+    ) -> Result<Self, String> {
+        let (bus, output) = Self::new(channels, sample_rate);
+        stream
+            .play_raw(output)
+            .map_err(|e| format!("Failed to start program bus: {}", e))?;
+        Ok(bus)
+    }
+
+    /// A bus mixing to `channels` at `sample_rate` Hz, and the output the
+    /// caller must pull from for it to be heard.
+    pub fn new(channels: u16, sample_rate: u32) -> (Self, BusOutput) {
+        let (mixer, mix) = dynamic_mixer::mixer::<f32>(channels, sample_rate);
+        // Keeps the mix running (and the AGC state alive) while nothing plays
+        mixer.add(Zero::<f32>::new(channels, sample_rate));
+        let agc = AgcControl::default();
+        let channel_map = ChannelMapControl::default();
+        let output = ChannelMapSource::with_control(
+            AgcSource::with_control(mix, agc.clone()),
+            channel_map.clone(),
+        );
+        let bus = ProgramBus {
+            mixer,
+            agc,
+            channel_map,
+        };
+        (bus, output)
+    }
+
+    /// A new sink playing into the bus. Its sources are dropped from the
+    /// mix once it is dropped (or, if detached, once they end).
+    pub fn sink(&self) -> Sink {
+        let (sink, output) = Sink::new_idle();
+        self.mixer.add(output);
+        sink
+    }
+
+    /// Ride the gain of the whole mix (None = AGC off).
+    pub fn set_agc(&self, config: Option<AgcConfig>) {
+        self.agc.set(config);
+    }
+
+    /// Map the channels of the whole mix.
+    pub fn set_channel_mode(&self, mode: ChannelMode) {
+        self.channel_map.set(mode);
+    }
+}
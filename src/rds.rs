@@ -1,3 +1,4 @@
+use crate::clock::{Clock, RealClock};
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Write};
 use std::net::TcpStream;
@@ -15,8 +16,10 @@ const LOOP_SLEEP_MS: u64 = 1000;
 const ERROR_RETRY_DELAY_SECS: u64 = 15;
 /// Resend same message to maintain encoder state (seconds).
 const KEEPALIVE_INTERVAL_SECS: u64 = 60;
-/// Maximum RDS text length.
+/// Maximum RDS text length (RadioText field).
 const MAX_RDS_TEXT_LEN: usize = 64;
+/// Width of one Program Service (PS) frame.
+pub const PS_LEN: usize = 8;
 
 /// A single RDS message configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,6 +80,13 @@ pub struct RdsConfig {
     /// Configured RDS messages.
     #[serde(default)]
     pub messages: Vec<RdsMessage>,
+    /// Send PS as word-aware 8-char frames instead of leaving the encoder
+    /// to scroll the raw text.
+    #[serde(default)]
+    pub segmented_ps: bool,
+    /// Also send the full text as RadioText (up to 64 chars).
+    #[serde(default)]
+    pub radio_text: bool,
 }
 
 impl Default for RdsConfig {
@@ -86,6 +96,8 @@ impl Default for RdsConfig {
             port: default_rds_port(),
             default_message: default_rds_message(),
             messages: Vec::new(),
+            segmented_ps: false,
+            radio_text: false,
         }
     }
 }
@@ -147,14 +159,72 @@ pub fn sanitize_rds_text(text: &str, default_message: &str) -> String {
     let cleaned = text.replace('\r', " ").replace('\n', " ");
     let trimmed = cleaned.trim();
     if trimmed.is_empty() {
-        let fallback = &default_message[..default_message.len().min(MAX_RDS_TEXT_LEN)];
-        return fallback.to_string();
+        return truncate_bytes(default_message, MAX_RDS_TEXT_LEN).to_string();
     }
-    if trimmed.len() > MAX_RDS_TEXT_LEN {
-        trimmed[..MAX_RDS_TEXT_LEN].to_string()
-    } else {
-        trimmed.to_string()
+    truncate_bytes(trimmed, MAX_RDS_TEXT_LEN).to_string()
+}
+
+/// At most `max` bytes of `s`, cut back to a char boundary so multi-byte
+/// text (Hebrew titles, accents) can't panic the slice.
+fn truncate_bytes(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
     }
+    &s[..end]
+}
+
+/// Split text into PS frames of at most 8 characters.
+///
+/// Words are packed into frames whole; only a word longer than a frame is
+/// cut across frames. Counts chars, not bytes.
+pub fn format_ps(text: &str) -> Vec<String> {
+    let mut frames = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let word_len = word.chars().count();
+        let current_len = current.chars().count();
+        if !current.is_empty() && current_len + 1 + word_len <= PS_LEN {
+            current.push(' ');
+            current.push_str(word);
+            continue;
+        }
+        if !current.is_empty() {
+            frames.push(std::mem::take(&mut current));
+        }
+        if word_len <= PS_LEN {
+            current.push_str(word);
+        } else {
+            let chars: Vec<char> = word.chars().collect();
+            let mut chunks = chars.chunks(PS_LEN).peekable();
+            while let Some(chunk) = chunks.next() {
+                if chunks.peek().is_some() {
+                    frames.push(chunk.iter().collect());
+                } else {
+                    current = chunk.iter().collect();
+                }
+            }
+        }
+    }
+    if !current.is_empty() {
+        frames.push(current);
+    }
+    frames
+}
+
+/// Text to send in the PS field: the frames from `format_ps` each padded to
+/// 8 chars so the encoder pages them on word boundaries, or the raw text.
+pub fn ps_text(text: &str, segmented: bool) -> String {
+    if !segmented {
+        return text.to_string();
+    }
+    format_ps(text)
+        .iter()
+        .map(|f| format!("{:<width$}", f, width = PS_LEN))
+        .collect()
 }
 
 /// Replace placeholders in message text.
@@ -220,6 +290,16 @@ pub fn should_display_message(
 /// Send a DPSTEXT command to the RDS encoder via TCP.
 /// Returns Ok(response) on success, Err(message) on failure.
 pub fn send_message_to_rds(ip: &str, port: u16, text: &str) -> Result<String, String> {
+    send_rds_command(ip, port, &format!("DPSTEXT={}", text))
+}
+
+/// Send a RadioText (TEXT) command to the RDS encoder via TCP.
+pub fn send_radio_text_to_rds(ip: &str, port: u16, text: &str) -> Result<String, String> {
+    send_rds_command(ip, port, &format!("TEXT={}", text))
+}
+
+/// Send one `KEY=value` command line to the RDS encoder.
+fn send_rds_command(ip: &str, port: u16, command: &str) -> Result<String, String> {
     let addr = format!("{}:{}", ip, port);
     let timeout = Duration::from_secs(SOCKET_TIMEOUT_SECS);
 
@@ -238,7 +318,7 @@ pub fn send_message_to_rds(ip: &str, port: u16, text: &str) -> Result<String, St
         .set_write_timeout(Some(timeout))
         .map_err(|e| format!("Set write timeout: {}", e))?;
 
-    let command = format!("DPSTEXT={}\r\n", text);
+    let command = format!("{}\r\n", command);
     stream
         .write_all(command.as_bytes())
         .map_err(|e| format!("TCP write failed: {}", e))?;
@@ -266,6 +346,80 @@ pub fn current_hour() -> u8 {
     chrono::Local::now().format("%H").to_string().parse().unwrap_or(0)
 }
 
+/// Steps through the configured messages, serving only those that pass
+/// `should_display_message` for the clock's current day and hour (messages
+/// without a schedule always qualify). With none eligible it serves
+/// `default_message`.
+pub struct RdsRotator {
+    clock: Arc<dyn Clock>,
+    position: usize,
+}
+
+impl Default for RdsRotator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RdsRotator {
+    /// A rotator on the system clock.
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(RealClock))
+    }
+
+    /// A rotator that reads the day and hour from `clock`.
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        RdsRotator { clock, position: 0 }
+    }
+
+    /// Rotation steps taken so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Messages eligible to air right now, in configured order.
+    pub fn eligible<'a>(
+        &self,
+        config: &'a RdsConfig,
+        artist: &str,
+        title: &str,
+        is_lecture: bool,
+    ) -> Vec<&'a RdsMessage> {
+        let day = self.clock.day_name();
+        let hour = self.clock.hour();
+        config
+            .messages
+            .iter()
+            .filter(|m| should_display_message(m, artist, title, is_lecture, &day, hour))
+            .collect()
+    }
+
+    /// Text and display seconds to send at the current position: the
+    /// eligible message there, formatted for the playing track, or the
+    /// default message for 10 seconds. None when the message formats to
+    /// nothing and should be skipped.
+    pub fn current(
+        &self,
+        config: &RdsConfig,
+        artist: &str,
+        title: &str,
+        is_lecture: bool,
+    ) -> Option<(String, u32)> {
+        let eligible = self.eligible(config, artist, title, is_lecture);
+        if eligible.is_empty() {
+            return Some((config.default_message.clone(), 10));
+        }
+        let msg = eligible[self.position % eligible.len()];
+        let formatted = format_message_text(&msg.text, artist, title);
+        (!formatted.is_empty()).then_some((formatted, msg.duration))
+    }
+
+    /// Move on to the next eligible message.
+    pub fn advance(&mut self) {
+        self.position = self.position.wrapping_add(1);
+    }
+}
+
 /// Status of the RDS handler.
 #[derive(Debug, Clone)]
 pub struct RdsStatus {
@@ -299,6 +453,15 @@ impl RdsHandler {
     where
         F: Fn() -> (RdsConfig, bool) + Send + 'static, // returns (config, is_lecture)
         G: Fn() -> (String, String) + Send + 'static,  // returns (artist, title)
+    {
+        Self::start_with_clock(Arc::new(RealClock), get_config, get_now_playing)
+    }
+
+    /// `start`, with message schedules checked against `clock`.
+    pub fn start_with_clock<F, G>(clock: Arc<dyn Clock>, get_config: F, get_now_playing: G) -> Self
+    where
+        F: Fn() -> (RdsConfig, bool) + Send + 'static,
+        G: Fn() -> (String, String) + Send + 'static,
     {
         let running = Arc::new(AtomicBool::new(true));
         let message_index = Arc::new(AtomicUsize::new(0));
@@ -314,8 +477,19 @@ impl RdsHandler {
         let ltime = last_send_time.clone();
         let lstat = last_send_status.clone();
 
+        let rotator = RdsRotator::with_clock(clock);
         let handle = std::thread::spawn(move || {
-            Self::run_loop(r, mi, cmd, lst, ltime, lstat, get_config, get_now_playing);
+            Self::run_loop(
+                r,
+                mi,
+                rotator,
+                cmd,
+                lst,
+                ltime,
+                lstat,
+                get_config,
+                get_now_playing,
+            );
         });
 
         RdsHandler {
@@ -329,9 +503,11 @@ impl RdsHandler {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn run_loop<F, G>(
         running: Arc<AtomicBool>,
         message_index: Arc<AtomicUsize>,
+        mut rotator: RdsRotator,
         current_message_duration: Arc<AtomicU32>,
         last_sent_text: Arc<Mutex<Option<String>>>,
         last_send_time: Arc<Mutex<Instant>>,
@@ -343,15 +519,17 @@ impl RdsHandler {
         G: Fn() -> (String, String),
     {
         while running.load(Ordering::Relaxed) {
-            match Self::loop_iteration(
-                &message_index,
+            let result = Self::loop_iteration(
+                &mut rotator,
                 &current_message_duration,
                 &last_sent_text,
                 &last_send_time,
                 &last_send_status,
                 &get_config,
                 &get_now_playing,
-            ) {
+            );
+            message_index.store(rotator.position(), Ordering::Relaxed);
+            match result {
                 Ok(()) => {}
                 Err(e) => {
                     eprintln!("[RDS] Error: {}", e);
@@ -365,7 +543,7 @@ impl RdsHandler {
     }
 
     fn loop_iteration<F, G>(
-        message_index: &Arc<AtomicUsize>,
+        rotator: &mut RdsRotator,
         current_message_duration: &Arc<AtomicU32>,
         last_sent_text: &Arc<Mutex<Option<String>>>,
         last_send_time: &Arc<Mutex<Instant>>,
@@ -379,29 +557,16 @@ impl RdsHandler {
     {
         let (config, is_lecture) = get_config();
         let (artist, title) = get_now_playing();
-        let day = current_day_name();
-        let hour = current_hour();
-
-        // Filter valid messages
-        let valid_messages: Vec<&RdsMessage> = config
-            .messages
-            .iter()
-            .filter(|m| should_display_message(m, &artist, &title, is_lecture, &day, hour))
-            .collect();
+        let has_messages = !rotator
+            .eligible(&config, &artist, &title, is_lecture)
+            .is_empty();
 
         // Determine display text and duration
-        let (display_text, duration) = if valid_messages.is_empty() {
-            (config.default_message.clone(), 10u32)
-        } else {
-            let idx = message_index.load(Ordering::Relaxed) % valid_messages.len();
-            let msg = valid_messages[idx];
-            let formatted = format_message_text(&msg.text, &artist, &title);
-            if formatted.is_empty() {
-                // Advance index, skip this iteration
-                message_index.fetch_add(1, Ordering::Relaxed);
-                return Ok(());
-            }
-            (formatted, msg.duration)
+        let Some((display_text, duration)) = rotator.current(&config, &artist, &title, is_lecture)
+        else {
+            // Formats to nothing: skip to the next message
+            rotator.advance();
+            return Ok(());
         };
 
         let sanitized = sanitize_rds_text(&display_text, &config.default_message);
@@ -422,7 +587,12 @@ impl RdsHandler {
         let should_send = rotation_due || keepalive_due;
 
         if should_send {
-            let result = send_message_to_rds(&config.ip, config.port, &sanitized);
+            let ps = ps_text(&sanitized, config.segmented_ps);
+            let mut result = send_message_to_rds(&config.ip, config.port, &ps);
+            if result.is_ok() && config.radio_text {
+                std::thread::sleep(Duration::from_millis(COMMAND_DELAY_MS));
+                result = send_radio_text_to_rds(&config.ip, config.port, &sanitized);
+            }
             match &result {
                 Ok(_) => {
                     *last_send_status.lock().unwrap() = Some("success".to_string());
@@ -436,8 +606,8 @@ impl RdsHandler {
             current_message_duration.store(duration, Ordering::Relaxed);
 
             // Only advance index on rotation, not keepalive
-            if rotation_due && !valid_messages.is_empty() {
-                message_index.fetch_add(1, Ordering::Relaxed);
+            if rotation_due && has_messages {
+                rotator.advance();
             }
 
             std::thread::sleep(Duration::from_millis(COMMAND_DELAY_MS));
@@ -531,6 +701,8 @@ mod tests {
         assert_eq!(config.port, 10001);
         assert_eq!(config.default_message, "signalFlow Radio Automation");
         assert!(config.messages.is_empty());
+        assert!(!config.segmented_ps);
+        assert!(!config.radio_text);
     }
 
     #[test]
@@ -554,6 +726,50 @@ mod tests {
         assert_eq!(config.port, 10001);
     }
 
+    // --- format_ps tests ---
+
+    #[test]
+    fn format_ps_chunks_on_word_boundaries() {
+        let frames = format_ps("The Beatles - Hey Jude");
+        assert_eq!(frames, vec!["The", "Beatles", "- Hey", "Jude"]);
+        for frame in &frames {
+            assert!(frame.chars().count() <= PS_LEN);
+            assert_eq!(frame.trim(), frame);
+        }
+        // Rejoining the frames gives back the original words
+        assert_eq!(frames.join(" "), "The Beatles - Hey Jude");
+    }
+
+    #[test]
+    fn format_ps_splits_only_overlong_words() {
+        let frames = format_ps("Supercalifragilistic Song");
+        assert_eq!(frames, vec!["Supercal", "ifragili", "stic", "Song"]);
+        assert!(frames.iter().all(|f| f.chars().count() <= PS_LEN));
+    }
+
+    #[test]
+    fn format_ps_counts_chars_not_bytes() {
+        let frames = format_ps("שלום עולם");
+        assert_eq!(frames, vec!["שלום", "עולם"]);
+    }
+
+    #[test]
+    fn format_ps_empty_text_has_no_frames() {
+        assert!(format_ps("   ").is_empty());
+    }
+
+    #[test]
+    fn ps_text_pads_segmented_frames() {
+        assert_eq!(
+            ps_text("The Beatles - Hey Jude", false),
+            "The Beatles - Hey Jude"
+        );
+        assert_eq!(
+            ps_text("The Beatles - Hey Jude", true),
+            "The     Beatles - Hey   Jude    "
+        );
+    }
+
     // --- sanitize_rds_text tests ---
 
     #[test]
@@ -569,6 +785,18 @@ mod tests {
         assert_eq!(result.len(), 64);
     }
 
+    #[test]
+    fn sanitize_truncation_respects_multibyte_chars() {
+        // 2-byte Hebrew letters: byte 64 falls mid-character for an odd prefix
+        let text = format!("A{}", "ש".repeat(60));
+        let result = sanitize_rds_text(&text, "default");
+        assert_eq!(result.len(), 63);
+        assert!(text.starts_with(&result));
+
+        let fallback = sanitize_rds_text("", &"é".repeat(40));
+        assert_eq!(fallback, "é".repeat(32));
+    }
+
     #[test]
     fn sanitize_empty_uses_default() {
         let result = sanitize_rds_text("", "My Default");
@@ -718,6 +946,69 @@ mod tests {
         assert!(should_display_message(&msg, "A", "T", false, "Sunday", 3));
     }
 
+    // --- RdsRotator tests ---
+
+    fn rotator_at(year: i32, month: u32, day: u32, hour: u32) -> RdsRotator {
+        use chrono::TimeZone;
+        let now = chrono::Local
+            .with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap();
+        RdsRotator::with_clock(Arc::new(crate::clock::FixedClock(now)))
+    }
+
+    #[test]
+    fn rotator_skips_messages_scheduled_for_other_days() {
+        let mut config = RdsConfig::new();
+        config.messages = vec![make_scheduled_msg("Shabbat Shalom", vec!["Friday"], vec![])];
+
+        // 2026-10-12 is a Monday: nothing eligible, so the default airs
+        let mut monday = rotator_at(2026, 10, 12, 9);
+        assert!(monday.eligible(&config, "A", "T", false).is_empty());
+        for _ in 0..3 {
+            let (text, _) = monday.current(&config, "A", "T", false).unwrap();
+            assert_eq!(text, config.default_message);
+            monday.advance();
+        }
+
+        let friday = rotator_at(2026, 10, 16, 9);
+        let (text, duration) = friday.current(&config, "A", "T", false).unwrap();
+        assert_eq!((text.as_str(), duration), ("Shabbat Shalom", 10));
+    }
+
+    #[test]
+    fn rotator_always_includes_unscheduled_messages() {
+        let mut config = RdsConfig::new();
+        config.messages = vec![
+            make_msg("Station WXYZ", true),
+            make_scheduled_msg("Friday only", vec!["Friday"], vec![]),
+            make_scheduled_msg("Morning drive", vec![], vec![7, 8]),
+        ];
+
+        // Monday 7am: the unscheduled message and the morning one alternate
+        let mut rotator = rotator_at(2026, 10, 12, 7);
+        let mut served = Vec::new();
+        for _ in 0..4 {
+            served.push(rotator.current(&config, "A", "T", false).unwrap().0);
+            rotator.advance();
+        }
+        let expected = ["Station WXYZ", "Morning drive"].repeat(2);
+        assert_eq!(served, expected);
+
+        // Sunday 3am: only the unscheduled message is left
+        let rotator = rotator_at(2026, 10, 11, 3);
+        let eligible = rotator.eligible(&config, "A", "T", false);
+        assert_eq!(eligible.len(), 1);
+        assert_eq!(eligible[0].text, "Station WXYZ");
+    }
+
+    #[test]
+    fn rotator_skips_messages_that_format_empty() {
+        let mut config = RdsConfig::new();
+        config.messages = vec![make_msg("{title}", true)];
+        let rotator = rotator_at(2026, 10, 12, 9);
+        assert_eq!(rotator.current(&config, "A", "   ", false), None);
+    }
+
     // --- format_hour_ampm tests ---
 
     #[test]
@@ -0,0 +1,107 @@
+//! Resume marker — where playback was, written periodically so a restart
+//! (e.g. after a reboot mid-program) can pick up from the same offset.
+//!
+//! Kept in a small sidecar file next to the state file rather than in the
+//! engine state itself, so the periodic write stays cheap.
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const RESUME_FILE_NAME: &str = "signalflow_resume.json";
+
+/// Playlist position and approximate elapsed offset at the last save.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResumeMarker {
+    pub playlist: String,
+    pub track_index: usize,
+    pub offset_secs: f64,
+    pub saved_at: DateTime<Local>,
+}
+
+impl ResumeMarker {
+    /// Offset to seek to, kept strictly inside a track of `duration`.
+    /// Unknown (zero) durations are not clamped.
+    pub fn clamped_offset(&self, duration: Duration, end_margin: Duration) -> Duration {
+        let offset = Duration::from_secs_f64(self.offset_secs.max(0.0));
+        if duration.is_zero() {
+            offset
+        } else {
+            offset.min(duration.saturating_sub(end_margin))
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string(self)
+            .map_err(|e| format!("Cannot serialize resume marker: {}", e))?;
+        fs::write(path, json)
+            .map_err(|e| format!("Cannot write resume marker '{}': {}", path.display(), e))
+    }
+
+    /// Read a marker; None if missing or unreadable.
+    pub fn load(path: &Path) -> Option<ResumeMarker> {
+        let data = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    /// Remove a saved marker (missing file is fine).
+    pub fn clear(path: &Path) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Sidecar file for the resume marker, next to `state_path`.
+pub fn resume_path_for(state_path: &Path) -> PathBuf {
+    state_path.with_file_name(RESUME_FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marker(offset_secs: f64) -> ResumeMarker {
+        ResumeMarker {
+            playlist: "Morning".to_string(),
+            track_index: 3,
+            offset_secs,
+            saved_at: Local::now(),
+        }
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = resume_path_for(&dir.path().join("state.json"));
+        assert_eq!(path, dir.path().join("signalflow_resume.json"));
+        assert!(ResumeMarker::load(&path).is_none());
+
+        let saved = marker(754.5);
+        saved.save(&path).unwrap();
+        assert_eq!(ResumeMarker::load(&path), Some(saved));
+
+        ResumeMarker::clear(&path);
+        assert!(ResumeMarker::load(&path).is_none());
+    }
+
+    #[test]
+    fn clamped_offset_stays_inside_track() {
+        let margin = Duration::from_millis(250);
+        let duration = Duration::from_secs(180);
+        assert_eq!(
+            marker(60.0).clamped_offset(duration, margin),
+            Duration::from_secs(60)
+        );
+        assert_eq!(
+            marker(900.0).clamped_offset(duration, margin),
+            duration - margin
+        );
+        let negative = marker(-5.0);
+        assert_eq!(negative.clamped_offset(duration, margin), Duration::ZERO);
+        assert_eq!(
+            marker(900.0).clamped_offset(Duration::ZERO, margin),
+            Duration::from_secs(900)
+        );
+    }
+}
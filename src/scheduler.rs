@@ -1,7 +1,9 @@
-use chrono::{NaiveTime, Timelike};
+use crate::auto_intro::AUDIO_EXTENSIONS;
+use crate::date_tokens;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike, Weekday};
 use serde::{Deserialize, Serialize};
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const DAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
 const DAY_INDEX_MAX: u8 = 6;
@@ -56,6 +58,75 @@ impl ConflictPolicy {
     }
 }
 
+/// What overlay and stop events do when they fire while playback is paused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PausedEventHandling {
+    /// Hold the event and fire it when the operator resumes (or stops).
+    #[default]
+    Queue,
+    /// Fire at once: overlays play over the paused track, stop events
+    /// end it.
+    PlayOver,
+}
+
+impl fmt::Display for PausedEventHandling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PausedEventHandling::Queue => write!(f, "queue"),
+            PausedEventHandling::PlayOver => write!(f, "play-over"),
+        }
+    }
+}
+
+impl PausedEventHandling {
+    /// Parse a handling from a string (case-insensitive, accepts hyphens or underscores).
+    pub fn from_str_loose(s: &str) -> Result<Self, String> {
+        match normalize_token(s).as_str() {
+            "queue" | "hold" => Ok(PausedEventHandling::Queue),
+            "play-over" | "override" => Ok(PausedEventHandling::PlayOver),
+            _ => Err(format!(
+                "Unknown paused-event handling '{}'. Expected: queue, play-over",
+                s
+            )),
+        }
+    }
+}
+
+/// How an event firing during a pause is carried out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PausedDisposition {
+    /// Fire now and leave the pause alone.
+    Fire,
+    /// Fire now; the event takes over the output, so the paused track ends.
+    FireAndStop,
+    /// Wait for the operator to resume.
+    Hold,
+}
+
+/// Decide what an event of `mode` and `priority` does while playback is
+/// paused. Insert and activate events only change playlists, so they always
+/// fire. Overlay and stop events follow `handling`, except that a pause is
+/// manual activity: under `ManualWins`, events below the policy's override
+/// threshold always wait.
+pub fn paused_disposition(
+    mode: ScheduleMode,
+    priority: Priority,
+    policy: ConflictPolicy,
+    handling: PausedEventHandling,
+) -> PausedDisposition {
+    if matches!(mode, ScheduleMode::Insert | ScheduleMode::Activate) {
+        return PausedDisposition::Fire;
+    }
+    if priority < policy.manual_override_threshold() || handling == PausedEventHandling::Queue {
+        return PausedDisposition::Hold;
+    }
+    match mode {
+        ScheduleMode::Stop => PausedDisposition::FireAndStop,
+        _ => PausedDisposition::Fire,
+    }
+}
+
 /// How a scheduled event interacts with current playback.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -66,6 +137,9 @@ pub enum ScheduleMode {
     Stop,
     /// Queue scheduled item as the next track in the active playlist.
     Insert,
+    /// Make the playlist named by `file` the active one (e.g. switch to the
+    /// evening automation playlist at 18:00).
+    Activate,
 }
 
 impl fmt::Display for ScheduleMode {
@@ -74,6 +148,7 @@ impl fmt::Display for ScheduleMode {
             ScheduleMode::Overlay => write!(f, "overlay"),
             ScheduleMode::Stop => write!(f, "stop"),
             ScheduleMode::Insert => write!(f, "insert"),
+            ScheduleMode::Activate => write!(f, "activate"),
         }
     }
 }
@@ -85,12 +160,75 @@ impl ScheduleMode {
             "overlay" | "over" => Ok(ScheduleMode::Overlay),
             "stop" | "kill" | "interrupt" => Ok(ScheduleMode::Stop),
             "insert" | "queue" | "next" => Ok(ScheduleMode::Insert),
+            "activate" | "switch" | "playlist" => Ok(ScheduleMode::Activate),
             _ => Err(format!(
-                "Unknown schedule mode '{}'. Expected: overlay, stop, insert",
+                "Unknown schedule mode '{}'. Expected: overlay, stop, insert, activate",
                 s
             )),
         }
     }
+
+    /// Whether two events in these modes fight when they fire together.
+    /// Two `stop` events each kill the other's audio and two `activate`
+    /// events each switch away from the other's playlist; overlays and
+    /// inserts coexist with anything.
+    pub fn conflicts_with(self, other: ScheduleMode) -> bool {
+        matches!(
+            (self, other),
+            (ScheduleMode::Stop, ScheduleMode::Stop)
+                | (ScheduleMode::Activate, ScheduleMode::Activate)
+        )
+    }
+}
+
+/// Where an `insert` event's file goes in the active playlist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InsertPosition {
+    /// Right after the current track (the next thing to play).
+    #[default]
+    Next,
+    /// After the last track, so the current set plays out first.
+    End,
+    /// At this 0-based playlist position.
+    Index(usize),
+}
+
+impl fmt::Display for InsertPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertPosition::Next => write!(f, "next"),
+            InsertPosition::End => write!(f, "end"),
+            InsertPosition::Index(i) => write!(f, "{}", i),
+        }
+    }
+}
+
+impl InsertPosition {
+    /// Parse "next", "end" or a 0-based playlist index (case-insensitive).
+    pub fn from_str_loose(s: &str) -> Result<Self, String> {
+        match normalize_token(s).as_str() {
+            "next" => Ok(InsertPosition::Next),
+            "end" | "last" | "append" => Ok(InsertPosition::End),
+            other => other.parse().map(InsertPosition::Index).map_err(|_| {
+                format!(
+                    "Unknown insert position '{}'. Expected: next, end, or a track index",
+                    s
+                )
+            }),
+        }
+    }
+
+    /// Playlist index to insert at, given the playlist's current track and
+    /// length. An explicit index past the end is returned as-is for the
+    /// caller to reject.
+    pub fn resolve(self, current_index: Option<usize>, len: usize) -> usize {
+        match self {
+            InsertPosition::Next => current_index.map_or(0, |i| i + 1),
+            InsertPosition::End => len,
+            InsertPosition::Index(i) => i,
+        }
+    }
 }
 
 /// Priority level for scheduled events (higher = more important).
@@ -130,7 +268,7 @@ pub struct ScheduleEvent {
     pub time: NaiveTime,
     /// How to interact with current playback.
     pub mode: ScheduleMode,
-    /// Path to the audio file to play.
+    /// Path to the audio file to play (playlist name for `activate`).
     pub file: PathBuf,
     /// Priority level (higher wins in conflicts).
     #[serde(default)]
@@ -144,6 +282,29 @@ pub struct ScheduleEvent {
     /// Days of the week this event recurs (0=Mon..6=Sun). Empty = every day.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub days: Vec<u8>,
+    /// `file` is a folder; a random audio file from it plays each time.
+    #[serde(default)]
+    pub rotator: bool,
+    /// `activate` events: start playing the playlist after switching to it.
+    #[serde(default)]
+    pub autostart: bool,
+    /// `insert` events: where the file goes in the active playlist.
+    #[serde(default)]
+    pub insert_position: InsertPosition,
+    /// First date the event runs (inclusive). None = no start limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<NaiveDate>,
+    /// Last date the event runs (inclusive). None = no end. A one-shot
+    /// event has both dates on the same day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<NaiveDate>,
+    /// Played instead of `file` when that is missing or unreadable (or, for
+    /// rotators, when the folder has nothing to pick).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_file: Option<PathBuf>,
+    /// Runtime-only: file the rotator picked last, to avoid immediate repeats.
+    #[serde(skip)]
+    pub last_pick: Option<PathBuf>,
 }
 
 fn default_true() -> bool {
@@ -171,6 +332,169 @@ impl ScheduleEvent {
             .collect::<Vec<_>>()
             .join(",")
     }
+
+    /// Whether the event recurs on `day` (empty `days` = daily).
+    pub fn runs_on(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&(day.num_days_from_monday() as u8))
+    }
+
+    /// Whether the event runs on `date`: a matching weekday within the
+    /// event's date range.
+    pub fn runs_on_date(&self, date: NaiveDate) -> bool {
+        self.runs_on(date.weekday())
+            && self.start_date.is_none_or(|start| date >= start)
+            && self.end_date.is_none_or(|end| date <= end)
+    }
+
+    /// When the event next triggers after `now` (local wall time). None when
+    /// disabled or when its date range has run out.
+    pub fn next_fire(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        if !self.enabled {
+            return None;
+        }
+        let first_day = self.start_date.unwrap_or(now.date()).max(now.date());
+        first_day
+            .iter_days()
+            .take(8)
+            .take_while(|date| self.end_date.is_none_or(|end| *date <= end))
+            .map(|date| date.and_time(self.time))
+            .find(|fire| *fire > now && self.runs_on_date(fire.date()))
+    }
+
+    /// Whether both events trigger within the same clock minute.
+    pub fn same_minute_as(&self, other: &ScheduleEvent) -> bool {
+        self.time.hour() == other.time.hour() && self.time.minute() == other.time.minute()
+    }
+
+    /// Whether the events recur on at least one common day (empty = daily).
+    pub fn shares_day_with(&self, other: &ScheduleEvent) -> bool {
+        self.days.is_empty()
+            || other.days.is_empty()
+            || self.days.iter().any(|d| other.days.contains(d))
+    }
+
+    /// The file to play when this event fires. Rotator events pick a random
+    /// audio file from their folder, never the previous pick twice in a row.
+    /// With a `fallback_file` set, an unreadable primary is replaced by the
+    /// fallback; if that is unreadable too, this errors.
+    pub fn resolve_file(&mut self) -> Result<PathBuf, String> {
+        if self.mode == ScheduleMode::Activate {
+            return Err(format!(
+                "Schedule event {} activates a playlist and has no file",
+                self.id
+            ));
+        }
+        let primary = self.resolve_primary();
+        let Some(fallback) = self
+            .fallback_file
+            .as_deref()
+            .map(date_tokens::expand_for_today)
+        else {
+            return primary;
+        };
+        match primary {
+            Ok(path) if is_readable_file(&path) => Ok(path),
+            _ if is_readable_file(&fallback) => Ok(fallback),
+            _ => Err(format!(
+                "Schedule event {}: neither '{}' nor fallback '{}' is readable",
+                self.id,
+                self.file.display(),
+                fallback.display()
+            )),
+        }
+    }
+
+    /// Whether `path` (as returned by `resolve_file`) is the fallback file.
+    pub fn is_fallback(&self, path: &Path) -> bool {
+        self.fallback_file
+            .as_deref()
+            .is_some_and(|f| date_tokens::expand_for_today(f) == path)
+    }
+
+    fn resolve_primary(&mut self) -> Result<PathBuf, String> {
+        let file = date_tokens::expand_for_today(&self.file);
+        if !self.rotator {
+            return Ok(file);
+        }
+        let pick = pick_rotator_file(&file, self.last_pick.as_deref())
+            .ok_or_else(|| format!("No audio files in rotator folder '{}'", file.display()))?;
+        self.last_pick = Some(pick.clone());
+        Ok(pick)
+    }
+
+    /// What firing this event does: play a file, or switch playlists.
+    pub fn action(&mut self) -> Result<ScheduleAction, String> {
+        if self.mode == ScheduleMode::Activate {
+            return Ok(ScheduleAction::Activate {
+                playlist: self.file.to_string_lossy().to_string(),
+                autostart: self.autostart,
+            });
+        }
+        Ok(ScheduleAction::Play {
+            mode: self.mode,
+            file: self.resolve_file()?,
+            position: self.insert_position,
+        })
+    }
+}
+
+/// Outcome of a scheduled event firing.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ScheduleAction {
+    /// Play `file` in an audio mode (overlay, stop, insert). `position` is
+    /// where an insert goes in the active playlist.
+    Play {
+        mode: ScheduleMode,
+        file: PathBuf,
+        position: InsertPosition,
+    },
+    /// Make `playlist` active, starting playback when `autostart` is set.
+    Activate { playlist: String, autostart: bool },
+    /// Event `id` fired while paused and waits for the operator to resume;
+    /// there is nothing to play yet.
+    Held { id: u32 },
+}
+
+/// Audio files directly inside `dir`, sorted by path.
+pub fn rotator_candidates(dir: &Path) -> Vec<PathBuf> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .is_some_and(|ext| {
+                        AUDIO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+                    })
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Whether `path` is a regular file that can be opened for reading.
+fn is_readable_file(path: &Path) -> bool {
+    path.is_file() && std::fs::File::open(path).is_ok()
+}
+
+/// Pick a random audio file from `dir`, skipping `last` when another exists.
+pub fn pick_rotator_file(dir: &Path, last: Option<&Path>) -> Option<PathBuf> {
+    let mut candidates = rotator_candidates(dir);
+    if candidates.len() > 1 {
+        candidates.retain(|p| Some(p.as_path()) != last);
+    }
+    if candidates.is_empty() {
+        return None;
+    }
+    let idx = fastrand::usize(..candidates.len());
+    Some(candidates.swap_remove(idx))
 }
 
 /// The schedule — a list of timed events managed by the engine.
@@ -227,6 +551,13 @@ impl Schedule {
             enabled: true,
             label,
             days: normalize_days(days),
+            rotator: false,
+            autostart: false,
+            insert_position: InsertPosition::Next,
+            start_date: None,
+            end_date: None,
+            fallback_file: None,
+            last_pick: None,
         });
         id
     }
@@ -288,6 +619,7 @@ impl Schedule {
         let mut best_overlay: Option<&ScheduleEvent> = None;
         let mut best_stop: Option<&ScheduleEvent> = None;
         let mut best_insert: Option<&ScheduleEvent> = None;
+        let mut best_activate: Option<&ScheduleEvent> = None;
 
         for &event in events {
             if !event.enabled {
@@ -297,6 +629,7 @@ impl Schedule {
                 ScheduleMode::Overlay => &mut best_overlay,
                 ScheduleMode::Stop => &mut best_stop,
                 ScheduleMode::Insert => &mut best_insert,
+                ScheduleMode::Activate => &mut best_activate,
             };
             match slot {
                 Some(current) if event.priority > current.priority => *slot = Some(event),
@@ -306,7 +639,11 @@ impl Schedule {
         }
 
         let mut winners = Vec::new();
-        // Stop fires first (most disruptive), then insert, then overlay
+        // Playlist switches go first so inserts land in the new playlist,
+        // then stop (most disruptive), insert, overlay
+        if let Some(e) = best_activate {
+            winners.push(e);
+        }
         if let Some(e) = best_stop {
             winners.push(e);
         }
@@ -333,6 +670,23 @@ impl Schedule {
             .collect()
     }
 
+    /// Pairs of enabled events (lower ID first) that fire in the same minute
+    /// on a common day with incompatible modes. Advisory only — the events
+    /// still run and `resolve_time_conflicts` picks the winner.
+    pub fn find_conflicts(&self) -> Vec<(u32, u32)> {
+        let enabled: Vec<&ScheduleEvent> = self.events.iter().filter(|e| e.enabled).collect();
+        let mut conflicts = Vec::new();
+        for (i, a) in enabled.iter().enumerate() {
+            for b in &enabled[i + 1..] {
+                if a.mode.conflicts_with(b.mode) && a.same_minute_as(b) && a.shares_day_with(b) {
+                    conflicts.push((a.id.min(b.id), a.id.max(b.id)));
+                }
+            }
+        }
+        conflicts.sort_unstable();
+        conflicts
+    }
+
     /// Get events that should fire at a given time, considering a tolerance window (in seconds).
     /// Returns enabled events whose time falls within [time - tolerance, time + tolerance].
     pub fn events_at_time(&self, time: NaiveTime, tolerance_secs: i64) -> Vec<&ScheduleEvent> {
@@ -356,6 +710,67 @@ impl Schedule {
     }
 }
 
+/// Minimum priority of a scheduled event that holds off ad rolls.
+pub const AD_ROLL_GUARD_PRIORITY: Priority = Priority(7);
+
+/// Whether an ad roll may start now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdRollDecision {
+    Proceed,
+    /// A high-priority event fires within the guard window of the request.
+    Defer {
+        event_id: u32,
+        event_time: NaiveTime,
+    },
+}
+
+/// Keeps ad rolls clear of high-priority scheduled events (e.g. a news stop
+/// at the top of the hour). Both ad insertion modes consult it before
+/// starting a roll.
+#[derive(Debug, Clone, Copy)]
+pub struct AdRollArbiter {
+    /// Seconds either side of an event during which rolls are deferred (0 = off).
+    pub window_secs: u32,
+    /// Events below this priority don't block rolls.
+    pub min_priority: Priority,
+}
+
+impl AdRollArbiter {
+    pub fn new(window_secs: u32) -> Self {
+        AdRollArbiter {
+            window_secs,
+            min_priority: AD_ROLL_GUARD_PRIORITY,
+        }
+    }
+
+    /// Decide whether a roll requested at `now` may start. Events are matched
+    /// on the day they actually fire, so a window can span midnight.
+    pub fn check(&self, schedule: &Schedule, now: NaiveDateTime) -> AdRollDecision {
+        if self.window_secs == 0 {
+            return AdRollDecision::Proceed;
+        }
+        let window = chrono::Duration::seconds(self.window_secs.into());
+        for event in schedule.events_by_time() {
+            if !event.enabled || event.priority < self.min_priority {
+                continue;
+            }
+            for offset in [-1, 0, 1] {
+                let date = now.date() + chrono::Duration::days(offset);
+                if !event.runs_on_date(date) {
+                    continue;
+                }
+                if (date.and_time(event.time) - now).abs() <= window {
+                    return AdRollDecision::Defer {
+                        event_id: event.id,
+                        event_time: event.time,
+                    };
+                }
+            }
+        }
+        AdRollDecision::Proceed
+    }
+}
+
 /// Parse a time string in HH:MM or HH:MM:SS format.
 pub fn parse_time(s: &str) -> Result<NaiveTime, String> {
     let normalized = s.trim();
@@ -465,6 +880,70 @@ mod tests {
         assert_eq!(format!("{}", ScheduleMode::Overlay), "overlay");
         assert_eq!(format!("{}", ScheduleMode::Stop), "stop");
         assert_eq!(format!("{}", ScheduleMode::Insert), "insert");
+        assert_eq!(format!("{}", ScheduleMode::Activate), "activate");
+    }
+
+    #[test]
+    fn insert_position_parse_and_resolve() {
+        assert_eq!(
+            InsertPosition::from_str_loose("End").unwrap(),
+            InsertPosition::End
+        );
+        assert_eq!(
+            InsertPosition::from_str_loose(" 3 ").unwrap(),
+            InsertPosition::Index(3)
+        );
+        assert!(InsertPosition::from_str_loose("middle").is_err());
+        assert_eq!(InsertPosition::Index(3).to_string(), "3");
+
+        assert_eq!(InsertPosition::Next.resolve(Some(1), 4), 2);
+        assert_eq!(InsertPosition::Next.resolve(None, 4), 0);
+        assert_eq!(InsertPosition::End.resolve(Some(1), 4), 4);
+        assert_eq!(InsertPosition::Index(2).resolve(Some(1), 4), 2);
+    }
+
+    #[test]
+    fn insert_position_defaults_to_next_for_old_events() {
+        let json = r#"{"id":1,"time":"12:00:00","mode":"insert","file":"promo.mp3"}"#;
+        let event: ScheduleEvent = serde_json::from_str(json).unwrap();
+        assert_eq!(event.insert_position, InsertPosition::Next);
+    }
+
+    #[test]
+    fn activate_event_yields_activation_action() {
+        let mut sched = Schedule::new();
+        let id = sched.add_event(
+            NaiveTime::from_hms_opt(18, 0, 0).unwrap(),
+            ScheduleMode::from_str_loose("activate").unwrap(),
+            "Evening".into(),
+            Priority::NORMAL,
+            None,
+            vec![],
+        );
+        let event = sched.find_event_mut(id).unwrap();
+        assert_eq!(
+            event.action().unwrap(),
+            ScheduleAction::Activate {
+                playlist: "Evening".to_string(),
+                autostart: false,
+            }
+        );
+        event.autostart = true;
+        match event.action().unwrap() {
+            ScheduleAction::Activate { autostart, .. } => assert!(autostart),
+            other => panic!("expected activation, got {:?}", other),
+        }
+        assert!(event.resolve_file().is_err());
+
+        event.mode = ScheduleMode::Stop;
+        assert_eq!(
+            event.action().unwrap(),
+            ScheduleAction::Play {
+                mode: ScheduleMode::Stop,
+                file: "Evening".into(),
+                position: InsertPosition::Next,
+            }
+        );
     }
 
     #[test]
@@ -615,6 +1094,13 @@ mod tests {
             enabled: true,
             label: None,
             days: vec![],
+            rotator: false,
+            autostart: false,
+            insert_position: InsertPosition::Next,
+            start_date: None,
+            end_date: None,
+            fallback_file: None,
+            last_pick: None,
         };
         assert_eq!(event.days_display(), "daily");
     }
@@ -630,6 +1116,13 @@ mod tests {
             enabled: true,
             label: None,
             days: vec![0, 1, 2, 3, 4],
+            rotator: false,
+            autostart: false,
+            insert_position: InsertPosition::Next,
+            start_date: None,
+            end_date: None,
+            fallback_file: None,
+            last_pick: None,
         };
         assert_eq!(event.days_display(), "Mon,Tue,Wed,Thu,Fri");
     }
@@ -645,6 +1138,13 @@ mod tests {
             enabled: true,
             label: None,
             days: vec![4, 1, 4, 0],
+            rotator: false,
+            autostart: false,
+            insert_position: InsertPosition::Next,
+            start_date: None,
+            end_date: None,
+            fallback_file: None,
+            last_pick: None,
         };
         assert_eq!(event.days_display(), "Mon,Tue,Fri");
     }
@@ -677,6 +1177,13 @@ mod tests {
             enabled: true,
             label: None,
             days: vec![0, 9],
+            rotator: false,
+            autostart: false,
+            insert_position: InsertPosition::Next,
+            start_date: None,
+            end_date: None,
+            fallback_file: None,
+            last_pick: None,
         };
         assert_eq!(event.days_display(), "Mon,Invalid");
     }
@@ -797,6 +1304,142 @@ mod tests {
         assert_eq!(policy.manual_override_threshold(), Priority(7));
     }
 
+    #[test]
+    fn paused_disposition_follows_handling_and_policy() {
+        use PausedDisposition::*;
+        use ScheduleMode::{Activate, Insert, Overlay, Stop};
+        let wins = ConflictPolicy::ScheduleWins;
+        let queue = PausedEventHandling::Queue;
+        let over = PausedEventHandling::PlayOver;
+        let normal = Priority::NORMAL;
+
+        assert_eq!(paused_disposition(Overlay, normal, wins, queue), Hold);
+        assert_eq!(paused_disposition(Stop, normal, wins, queue), Hold);
+        assert_eq!(paused_disposition(Overlay, normal, wins, over), Fire);
+        assert_eq!(paused_disposition(Stop, normal, wins, over), FireAndStop);
+        // Playlist-only events never wait
+        assert_eq!(paused_disposition(Insert, normal, wins, queue), Fire);
+        assert_eq!(paused_disposition(Activate, normal, wins, queue), Fire);
+
+        // Manual wins: only high-priority events may play over the pause
+        let manual = ConflictPolicy::ManualWins;
+        assert_eq!(paused_disposition(Overlay, normal, manual, over), Hold);
+        let high = Priority::HIGH;
+        assert_eq!(paused_disposition(Overlay, high, manual, over), Fire);
+    }
+
+    #[test]
+    fn paused_event_handling_parses() {
+        assert_eq!(PausedEventHandling::default(), PausedEventHandling::Queue);
+        assert_eq!(
+            PausedEventHandling::from_str_loose("Play_Over").unwrap(),
+            PausedEventHandling::PlayOver
+        );
+        assert!(PausedEventHandling::from_str_loose("ignore").is_err());
+        assert_eq!(PausedEventHandling::PlayOver.to_string(), "play-over");
+    }
+
+    // --- Rotator tests ---
+
+    fn rotator_event(dir: &Path) -> ScheduleEvent {
+        let mut sched = Schedule::new();
+        let id = sched.add_event(
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            ScheduleMode::Overlay,
+            dir.to_path_buf(),
+            Priority::NORMAL,
+            Some("Station ID".to_string()),
+            vec![],
+        );
+        let mut event = sched.find_event(id).unwrap().clone();
+        event.rotator = true;
+        event
+    }
+
+    #[test]
+    fn rotator_never_repeats_back_to_back() {
+        let temp = tempfile::tempdir().unwrap();
+        for name in ["id1.mp3", "id2.wav", "id3.flac"] {
+            std::fs::write(temp.path().join(name), b"x").unwrap();
+        }
+        std::fs::write(temp.path().join("notes.txt"), b"x").unwrap();
+
+        let mut event = rotator_event(temp.path());
+        let mut previous = event.resolve_file().unwrap();
+        for _ in 0..50 {
+            let next = event.resolve_file().unwrap();
+            assert_ne!(next, previous);
+            assert_ne!(next.extension().unwrap(), "txt");
+            previous = next;
+        }
+    }
+
+    #[test]
+    fn rotator_single_file_always_returned() {
+        let temp = tempfile::tempdir().unwrap();
+        let only = temp.path().join("only.mp3");
+        std::fs::write(&only, b"x").unwrap();
+
+        let mut event = rotator_event(temp.path());
+        for _ in 0..5 {
+            assert_eq!(event.resolve_file().unwrap(), only);
+        }
+    }
+
+    #[test]
+    fn rotator_empty_folder_errors_and_plain_event_returns_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut event = rotator_event(temp.path());
+        assert!(event.resolve_file().is_err());
+
+        event.rotator = false;
+        assert_eq!(event.resolve_file().unwrap(), temp.path());
+    }
+
+    #[test]
+    fn resolve_file_expands_date_tokens() {
+        let mut event = make_event(1, ScheduleMode::Overlay, 5);
+        event.file = PathBuf::from("news_{YYYY}{MM}{DD}.mp3");
+        let today = chrono::Local::now().date_naive();
+        assert_eq!(
+            event.resolve_file().unwrap(),
+            PathBuf::from(format!("news_{}.mp3", today.format("%Y%m%d")))
+        );
+    }
+
+    #[test]
+    fn fallback_used_only_when_primary_unreadable() {
+        let temp = tempfile::tempdir().unwrap();
+        let primary = temp.path().join("id.mp3");
+        let fallback = temp.path().join("generic.mp3");
+        std::fs::write(&fallback, b"x").unwrap();
+
+        let mut event = make_event(1, ScheduleMode::Stop, 5);
+        event.file = primary.clone();
+        event.fallback_file = Some(fallback.clone());
+        assert_eq!(event.resolve_file().unwrap(), fallback);
+        assert!(event.is_fallback(&fallback));
+
+        std::fs::write(&primary, b"x").unwrap();
+        assert_eq!(event.resolve_file().unwrap(), primary);
+
+        // An empty rotator folder falls back too
+        let empty = tempfile::tempdir().unwrap();
+        let mut rotator = rotator_event(empty.path());
+        rotator.fallback_file = Some(fallback.clone());
+        assert_eq!(rotator.resolve_file().unwrap(), fallback);
+    }
+
+    #[test]
+    fn fallback_missing_too_is_an_error() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut event = make_event(1, ScheduleMode::Stop, 5);
+        event.file = temp.path().join("id.mp3");
+        event.fallback_file = Some(temp.path().join("generic.mp3"));
+        let err = event.resolve_file().unwrap_err();
+        assert!(err.contains("generic.mp3"));
+    }
+
     // --- Time conflict resolution tests ---
 
     fn make_event(id: u32, mode: ScheduleMode, priority: u8) -> ScheduleEvent {
@@ -809,6 +1452,13 @@ mod tests {
             enabled: true,
             label: None,
             days: vec![],
+            rotator: false,
+            autostart: false,
+            insert_position: InsertPosition::Next,
+            start_date: None,
+            end_date: None,
+            fallback_file: None,
+            last_pick: None,
         }
     }
 
@@ -845,6 +1495,18 @@ mod tests {
         assert_eq!(winners[2].mode, ScheduleMode::Overlay);
     }
 
+    #[test]
+    fn resolve_time_conflicts_activate_fires_first() {
+        let e1 = make_event(1, ScheduleMode::Insert, 5);
+        let e2 = make_event(2, ScheduleMode::Activate, 3);
+        let e3 = make_event(3, ScheduleMode::Activate, 6);
+        let events: Vec<&ScheduleEvent> = vec![&e1, &e2, &e3];
+        let winners = Schedule::resolve_time_conflicts(&events);
+        assert_eq!(winners.len(), 2);
+        assert_eq!(winners[0].id, 3);
+        assert_eq!(winners[1].id, 1);
+    }
+
     #[test]
     fn resolve_time_conflicts_disabled_events_excluded() {
         let e1 = make_event(1, ScheduleMode::Stop, 9);
@@ -959,4 +1621,178 @@ mod tests {
         let missed = sched.events_at_time(NaiveTime::from_hms_opt(12, 0, 10).unwrap(), 5);
         assert!(missed.is_empty());
     }
+
+    fn add_at(sched: &mut Schedule, time: &str, mode: ScheduleMode, days: Vec<u8>) -> u32 {
+        sched.add_event(
+            parse_time(time).unwrap(),
+            mode,
+            "event.mp3".into(),
+            Priority::NORMAL,
+            None,
+            days,
+        )
+    }
+
+    #[test]
+    fn find_conflicts_flags_stop_pair_on_overlapping_days() {
+        let mut sched = Schedule::new();
+        let a = add_at(&mut sched, "14:00", ScheduleMode::Stop, vec![0, 2]);
+        let b = add_at(&mut sched, "14:00:30", ScheduleMode::Stop, vec![2, 4]);
+        let c = add_at(&mut sched, "14:00", ScheduleMode::Stop, vec![]);
+        assert_eq!(sched.find_conflicts(), vec![(a, b), (a, c), (b, c)]);
+
+        sched.toggle_event(c).unwrap();
+        assert_eq!(sched.find_conflicts(), vec![(a, b)]);
+    }
+
+    #[test]
+    fn find_conflicts_ignores_compatible_modes_and_other_days() {
+        let mut sched = Schedule::new();
+        add_at(&mut sched, "14:00", ScheduleMode::Overlay, vec![]);
+        add_at(&mut sched, "14:00", ScheduleMode::Stop, vec![0]);
+        add_at(&mut sched, "14:00", ScheduleMode::Stop, vec![1]);
+        add_at(&mut sched, "14:01", ScheduleMode::Stop, vec![0]);
+        assert!(sched.find_conflicts().is_empty());
+    }
+
+    #[test]
+    fn find_conflicts_flags_activate_pair() {
+        let mut sched = Schedule::new();
+        let a = add_at(&mut sched, "18:00", ScheduleMode::Activate, vec![]);
+        add_at(&mut sched, "18:00", ScheduleMode::Stop, vec![]);
+        let c = add_at(&mut sched, "18:00", ScheduleMode::Activate, vec![4]);
+        assert_eq!(sched.find_conflicts(), vec![(a, c)]);
+    }
+
+    /// Monday 2026-10-12 at `time` (HH:MM).
+    fn monday(time: &str) -> NaiveDateTime {
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        date.and_time(parse_time(time).unwrap())
+    }
+
+    #[test]
+    fn ad_roll_deferred_near_high_priority_event() {
+        let mut sched = Schedule::new();
+        let news = sched.add_event(
+            parse_time("08:00").unwrap(),
+            ScheduleMode::Stop,
+            "news.mp3".into(),
+            Priority(9),
+            Some("News".into()),
+            vec![],
+        );
+        let arbiter = AdRollArbiter::new(120);
+        assert_eq!(
+            arbiter.check(&sched, monday("07:59")),
+            AdRollDecision::Defer {
+                event_id: news,
+                event_time: parse_time("08:00").unwrap(),
+            }
+        );
+        assert_eq!(
+            arbiter.check(&sched, monday("07:55")),
+            AdRollDecision::Proceed
+        );
+        assert_eq!(
+            AdRollArbiter::new(0).check(&sched, monday("07:59")),
+            AdRollDecision::Proceed
+        );
+    }
+
+    #[test]
+    fn ad_roll_not_deferred_by_low_priority_or_other_day() {
+        let mut sched = Schedule::new();
+        sched.add_event(
+            parse_time("08:00").unwrap(),
+            ScheduleMode::Overlay,
+            "jingle.mp3".into(),
+            Priority(3),
+            None,
+            vec![],
+        );
+        // Tuesday-only news
+        sched.add_event(
+            parse_time("08:00").unwrap(),
+            ScheduleMode::Stop,
+            "news.mp3".into(),
+            Priority(9),
+            None,
+            vec![1],
+        );
+        let arbiter = AdRollArbiter::new(120);
+        assert_eq!(
+            arbiter.check(&sched, monday("07:59")),
+            AdRollDecision::Proceed
+        );
+
+        // Monday 23:59 is within the window of Tuesday's 00:00 event
+        let mut sched = Schedule::new();
+        sched.add_event(
+            parse_time("00:00").unwrap(),
+            ScheduleMode::Stop,
+            "news.mp3".into(),
+            Priority::HIGH,
+            None,
+            vec![1],
+        );
+        assert!(matches!(
+            arbiter.check(&sched, monday("23:59")),
+            AdRollDecision::Defer { .. }
+        ));
+        assert_eq!(
+            arbiter.check(&sched, monday("00:01")),
+            AdRollDecision::Proceed
+        );
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn next_fire_finds_upcoming_weekday() {
+        let mut sched = Schedule::new();
+        let id = add_at(&mut sched, "14:00", ScheduleMode::Stop, vec![0]);
+        let event = sched.find_event(id).unwrap();
+        let next_monday = date(2026, 10, 19).and_time(parse_time("14:00").unwrap());
+
+        // Wednesday 2026-10-14 → the following Monday
+        let wednesday = date(2026, 10, 14).and_time(parse_time("09:00").unwrap());
+        assert_eq!(event.next_fire(wednesday), Some(next_monday));
+        // Monday morning → later the same day
+        assert_eq!(event.next_fire(monday("10:00")), Some(monday("14:00")));
+        // Monday after 14:00 → a week later
+        assert_eq!(event.next_fire(monday("14:00")), Some(next_monday));
+    }
+
+    #[test]
+    fn next_fire_none_when_disabled_or_expired() {
+        let mut sched = Schedule::new();
+        let id = add_at(&mut sched, "14:00", ScheduleMode::Stop, vec![]);
+        let event = sched.find_event_mut(id).unwrap();
+        event.enabled = false;
+        assert_eq!(event.next_fire(monday("10:00")), None);
+
+        event.enabled = true;
+        event.end_date = Some(date(2026, 10, 11));
+        assert_eq!(event.next_fire(monday("10:00")), None);
+
+        // One-shot on the Wednesday: fires once, then never again
+        event.start_date = Some(date(2026, 10, 14));
+        event.end_date = Some(date(2026, 10, 14));
+        let once = date(2026, 10, 14).and_time(parse_time("14:00").unwrap());
+        assert_eq!(event.next_fire(monday("10:00")), Some(once));
+        assert_eq!(event.next_fire(once), None);
+    }
+
+    #[test]
+    fn next_fire_waits_for_start_date() {
+        let mut sched = Schedule::new();
+        let id = add_at(&mut sched, "06:00", ScheduleMode::Insert, vec![4]);
+        let event = sched.find_event_mut(id).unwrap();
+        event.start_date = Some(date(2026, 12, 1));
+        // First Friday on or after December 1st
+        let first = date(2026, 12, 4).and_time(parse_time("06:00").unwrap());
+        assert_eq!(event.next_fire(monday("10:00")), Some(first));
+    }
 }
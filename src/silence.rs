@@ -1,8 +1,80 @@
 use rodio::Source;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
+/// Floor reported for a linear level of 0 (digital silence) in dBFS.
+pub const MIN_DBFS: f32 = -120.0;
+
+/// Convert a dBFS level (e.g. -40) to the linear RMS the detector uses.
+pub fn dbfs_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Convert a linear RMS level to dBFS, floored at `MIN_DBFS`.
+pub fn linear_to_dbfs(linear: f32) -> f32 {
+    if linear <= 0.0 {
+        return MIN_DBFS;
+    }
+    (20.0 * linear.log10()).max(MIN_DBFS)
+}
+
+/// Unit the user configured the silence threshold in.
+/// The threshold is always stored linear; this only affects display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThresholdUnit {
+    /// Raw linear RMS (e.g. 0.01).
+    #[default]
+    Linear,
+    /// Decibels relative to full scale (e.g. -40).
+    Dbfs,
+}
+
+impl fmt::Display for ThresholdUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThresholdUnit::Linear => write!(f, "linear"),
+            ThresholdUnit::Dbfs => write!(f, "dbfs"),
+        }
+    }
+}
+
+impl ThresholdUnit {
+    /// Parse a unit from a string (case-insensitive).
+    pub fn from_str_loose(s: &str) -> Result<Self, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "linear" | "rms" | "" => Ok(ThresholdUnit::Linear),
+            "dbfs" | "db" => Ok(ThresholdUnit::Dbfs),
+            _ => Err(format!(
+                "Unknown threshold unit '{}'. Expected: linear, dbfs",
+                s
+            )),
+        }
+    }
+
+    /// Convert a value given in this unit to linear RMS.
+    pub fn to_linear(self, value: f32) -> f32 {
+        match self {
+            ThresholdUnit::Linear => value,
+            ThresholdUnit::Dbfs => dbfs_to_linear(value),
+        }
+    }
+
+    /// Express a linear RMS value in this unit.
+    pub fn express(self, linear: f32) -> f32 {
+        match self {
+            ThresholdUnit::Linear => linear,
+            ThresholdUnit::Dbfs => linear_to_dbfs(linear),
+        }
+    }
+}
+
 /// Shared flag indicating whether silence has been detected.
 #[derive(Clone)]
 pub struct SilenceMonitor {
@@ -133,6 +205,33 @@ where
     }
 }
 
+/// Silence before audio starts: the time up to the first sample whose
+/// absolute level exceeds `threshold` (linear, e.g. 0.01) on any channel.
+/// A source that never rises above it returns its full length.
+pub fn leading_silence<S>(source: S, threshold: f32) -> Duration
+where
+    S: Source<Item = f32>,
+{
+    let channels = source.channels().max(1) as u64;
+    let sample_rate = source.sample_rate().max(1) as f64;
+    let mut silent_samples: u64 = 0;
+    for sample in source {
+        if sample.abs() > threshold {
+            break;
+        }
+        silent_samples += 1;
+    }
+    Duration::from_secs_f64((silent_samples / channels) as f64 / sample_rate)
+}
+
+/// Leading silence of the audio file at `path`; see `leading_silence`.
+pub fn detect_leading_silence(path: &Path, threshold: f32) -> Result<Duration, String> {
+    let file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path.display(), e))?;
+    let source = rodio::Decoder::new(BufReader::new(file))
+        .map_err(|e| format!("Cannot decode '{}': {}", path.display(), e))?;
+    Ok(leading_silence(source.convert_samples::<f32>(), threshold))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -302,4 +401,60 @@ mod tests {
         let _: Vec<f32> = detector.collect();
         assert!(monitor.is_silent(), "Should detect silence in the trailing silent section");
     }
+
+    #[test]
+    fn minus_40_dbfs_is_about_0_01_linear() {
+        assert!((dbfs_to_linear(-40.0) - 0.01).abs() < 1e-6);
+        assert!((dbfs_to_linear(-20.0) - 0.1).abs() < 1e-6);
+        assert!((dbfs_to_linear(0.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn dbfs_linear_round_trip() {
+        for db in [-90.0f32, -60.0, -40.0, -12.5, 0.0] {
+            let back = linear_to_dbfs(dbfs_to_linear(db));
+            assert!((back - db).abs() < 1e-3, "{} dB came back as {}", db, back);
+        }
+        assert!((linear_to_dbfs(0.01) + 40.0).abs() < 1e-4);
+        assert_eq!(linear_to_dbfs(0.0), MIN_DBFS);
+    }
+
+    #[test]
+    fn threshold_unit_parses_and_converts() {
+        let db = ThresholdUnit::from_str_loose("dB").unwrap();
+        assert_eq!(db, ThresholdUnit::Dbfs);
+        let rms = ThresholdUnit::from_str_loose("rms").unwrap();
+        assert_eq!(rms, ThresholdUnit::Linear);
+        assert!(ThresholdUnit::from_str_loose("volts").is_err());
+        assert_eq!(ThresholdUnit::Linear.to_linear(0.02), 0.02);
+        let linear = ThresholdUnit::Dbfs.to_linear(-40.0);
+        assert!((ThresholdUnit::Dbfs.express(linear) + 40.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn leading_silence_measures_lead_in() {
+        // 1.5s of zeros at 1000 Hz, then audio
+        let mut samples = vec![0.0; 1500];
+        samples.extend(vec![0.5; 500]);
+        let lead = leading_silence(TestSource::new(samples, 1000, 1), 0.01);
+        assert_eq!(lead, Duration::from_millis(1500));
+
+        // Stereo: frames, not samples, count toward the time
+        let mut stereo = vec![0.0; 3000];
+        stereo.extend(vec![0.5; 1000]);
+        let lead = leading_silence(TestSource::new(stereo, 1000, 2), 0.01);
+        assert_eq!(lead, Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn leading_silence_is_zero_when_audio_starts_immediately() {
+        let source = TestSource::loud_then_silent(1000, 1000, 1000);
+        assert_eq!(leading_silence(source, 0.01), Duration::ZERO);
+
+        // Low-level noise under the threshold still counts as silence
+        let mut samples = vec![0.005; 250];
+        samples.extend(vec![0.5; 250]);
+        let lead = leading_silence(TestSource::new(samples, 1000, 1), 0.01);
+        assert_eq!(lead, Duration::from_millis(250));
+    }
 }
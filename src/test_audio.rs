@@ -0,0 +1,30 @@
+//! Audio fixtures shared by the unit tests.
+
+use std::path::Path;
+
+/// Write `samples` as a 16-bit mono PCM WAV file at `sample_rate` Hz.
+pub(crate) fn write_wav(path: &Path, samples: &[i16], sample_rate: u32) {
+    let data_len = (samples.len() * 2) as u32;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // mono
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for s in samples {
+        bytes.extend_from_slice(&s.to_le_bytes());
+    }
+    std::fs::write(path, bytes).unwrap();
+}
+
+/// Write `secs` seconds of silence as a mono WAV at 8 kHz.
+pub(crate) fn write_silence(path: &Path, secs: u32) {
+    write_wav(path, &vec![0; 8000 * secs as usize], 8000);
+}
@@ -1,12 +1,12 @@
 use lofty::config::WriteOptions;
 use lofty::file::{AudioFile, TaggedFileExt};
 use lofty::prelude::TagExt;
-use lofty::tag::{Accessor, Tag};
+use lofty::tag::{Accessor, ItemKey, Tag};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Track {
     pub path: PathBuf,
     pub title: String,
@@ -23,15 +23,80 @@ pub struct Track {
     /// Whether an intro file exists for this track's artist.
     #[serde(default)]
     pub has_intro: bool,
+    /// Start offset into `path` (cue-sheet segment). None = start of file.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "option_duration_serde"
+    )]
+    pub cue_in: Option<Duration>,
+    /// End offset into `path` (cue-sheet segment). None = end of file.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "option_duration_serde"
+    )]
+    pub cue_out: Option<Duration>,
+    /// Length measured by decoding the audio (see `decoded_duration`).
+    /// Preferred over the tag-derived `duration` when set.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "option_duration_serde"
+    )]
+    pub verified_duration: Option<Duration>,
+    /// Start cleanly: the previous track fades out but does not overlap
+    /// this one (news beds, anthems).
+    #[serde(default)]
+    pub no_crossfade_in: bool,
+    /// ReplayGain track gain read from the file's tags, in dB.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay_gain_db: Option<f32>,
+    /// Operator trim in dB, applied on top of any ReplayGain.
+    #[serde(default)]
+    pub gain_trim_db: f32,
+    /// Content advisory (explicit) — kept off air during `Engine::clean_hours`.
+    #[serde(default)]
+    pub advisory: bool,
+    /// Musical key from the `INITIALKEY`/`TKEY` tag, as written (e.g. "Am").
+    /// See `musical_key::to_camelot` for the Camelot form.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub musical_key: Option<String>,
+    /// Length of the instrumental intro the host can talk over, from an
+    /// `INTRO` tag or set in the editor.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "option_duration_serde"
+    )]
+    pub talk_over_secs: Option<Duration>,
+}
+
+/// Allowed range for `Track::gain_trim_db`.
+pub const GAIN_TRIM_MIN_DB: f32 = -24.0;
+pub const GAIN_TRIM_MAX_DB: f32 = 12.0;
+
+/// Check a gain trim value, rejecting anything outside
+/// `GAIN_TRIM_MIN_DB..=GAIN_TRIM_MAX_DB`.
+pub fn validate_gain_trim(db: f32) -> Result<f32, String> {
+    if !db.is_finite() || !(GAIN_TRIM_MIN_DB..=GAIN_TRIM_MAX_DB).contains(&db) {
+        return Err(format!(
+            "Gain trim must be between {} and +{} dB, got {}",
+            GAIN_TRIM_MIN_DB, GAIN_TRIM_MAX_DB, db
+        ));
+    }
+    Ok(db)
 }
 
 impl Track {
-    /// Create a Track by reading metadata from an audio file.
+    /// Create a Track by reading metadata from an audio file. A path with
+    /// date tokens is kept as written; tags come from today's file.
     pub fn from_path(path: &Path) -> Result<Self, String> {
         let path = normalize_input_path(path)?;
+        let source = crate::date_tokens::expand_for_today(&path);
 
-        let tagged_file = lofty::read_from_path(&path)
-            .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+        let tagged_file = lofty::read_from_path(&source)
+            .map_err(|e| format!("Failed to read '{}': {}", source.display(), e))?;
 
         let properties = tagged_file.properties();
         let duration = properties.duration();
@@ -42,6 +107,20 @@ impl Track {
 
         let tag_title = tag.and_then(|t| t.title().map(|s| s.to_string()));
         let tag_artist = tag.and_then(|t| t.artist().map(|s| s.to_string()));
+        let replay_gain_db = tag
+            .and_then(|t| t.get_string(&ItemKey::ReplayGainTrackGain))
+            .and_then(parse_replay_gain);
+        let advisory = tag
+            .and_then(|t| t.get_string(&ItemKey::ParentalAdvisory))
+            .is_some_and(parse_advisory);
+        let musical_key = tag
+            .and_then(|t| t.get_string(&ItemKey::InitialKey))
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(str::to_string);
+        let talk_over_secs = tag
+            .and_then(|t| t.get_string(&ItemKey::Unknown("INTRO".to_string())))
+            .and_then(parse_talk_over);
 
         let (title, artist) = match (tag_title, tag_artist) {
             (Some(t), Some(a)) => (t, a),
@@ -60,12 +139,61 @@ impl Track {
             duration,
             played_duration: None,
             has_intro: false,
+            cue_in: None,
+            cue_out: None,
+            verified_duration: None,
+            no_crossfade_in: false,
+            replay_gain_db,
+            gain_trim_db: 0.0,
+            advisory,
+            musical_key,
+            talk_over_secs,
         })
     }
 
-    /// Format calculated duration as MM:SS.
+    /// When the tags gave no length (zero duration) and `measure` is set,
+    /// decode the file to find it. Off by default because decoding a whole
+    /// file makes imports slow. Returns true if the duration was filled in.
+    pub fn fill_unknown_duration(&mut self, measure: bool) -> bool {
+        if !measure || !self.duration.is_zero() {
+            return false;
+        }
+        let source = crate::date_tokens::expand_for_today(&self.path);
+        match decoded_duration(&source) {
+            Some(measured) if !measured.is_zero() => {
+                self.duration = measured;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Duration used for playback timing: the decoded length when verified,
+    /// otherwise the tag duration.
+    pub fn effective_duration(&self) -> Duration {
+        self.verified_duration.unwrap_or(self.duration)
+    }
+
+    /// Time left after `elapsed` of playback, based on `effective_duration`.
+    pub fn remaining_duration(&self, elapsed: Duration) -> Duration {
+        self.effective_duration().saturating_sub(elapsed)
+    }
+
+    /// Net playback gain in dB: the trim, plus ReplayGain (if tagged) when
+    /// `use_replay_gain` is set (`Engine::replay_gain_enabled`).
+    pub fn playback_gain_db(&self, use_replay_gain: bool) -> f32 {
+        let replay_gain = self.replay_gain_db.filter(|_| use_replay_gain);
+        replay_gain.unwrap_or(0.0) + self.gain_trim_db
+    }
+
+    /// Linear amplitude factor for `playback_gain_db`.
+    pub fn playback_gain(&self, use_replay_gain: bool) -> f32 {
+        10f32.powf(self.playback_gain_db(use_replay_gain) / 20.0)
+    }
+
+    /// Format the effective duration as MM:SS.
     pub fn duration_display(&self) -> String {
-        format_duration(self.duration)
+        format_duration(self.effective_duration())
     }
 
     /// Format played duration as MM:SS, if available.
@@ -116,6 +244,87 @@ impl Track {
     }
 }
 
+/// Cheap health check for a track file: a single stat, no open or decode.
+/// Returns why the file can't be played, or None when it looks usable.
+pub fn file_error(path: &Path) -> Option<String> {
+    match std::fs::metadata(path) {
+        Ok(meta) if !meta.is_file() => Some("Not a file".to_string()),
+        Ok(meta) if meta.len() == 0 => Some("File is empty".to_string()),
+        Ok(_) => None,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Some("File not found".to_string()),
+        Err(e) => Some(format!("Cannot access file: {}", e)),
+    }
+}
+
+/// ReplayGain track gain from a file's tags, in dB. Used to fill in tracks
+/// added before the value was read. None if untagged or unreadable.
+pub fn read_replay_gain(path: &Path) -> Option<f32> {
+    let source = crate::date_tokens::expand_for_today(path);
+    let tagged_file = lofty::read_from_path(&source).ok()?;
+    let tag = tagged_file
+        .primary_tag()
+        .or_else(|| tagged_file.first_tag())?;
+    tag.get_string(&ItemKey::ReplayGainTrackGain)
+        .and_then(parse_replay_gain)
+}
+
+/// Check that a file's audio body actually decodes, not just its tags.
+/// Opens a decoder and pulls the first sample; a truncated or corrupt file
+/// whose tags still parse returns false.
+pub fn can_decode(path: &Path) -> bool {
+    let Ok(file) = std::fs::File::open(path) else {
+        return false;
+    };
+    match rodio::Decoder::new(std::io::BufReader::new(file)) {
+        Ok(mut decoder) => decoder.next().is_some(),
+        Err(_) => false,
+    }
+}
+
+/// Measure a file's length by decoding every sample. Slower than reading
+/// tags but not fooled by wrong VBR headers. None if the file can't be decoded.
+pub fn decoded_duration(path: &Path) -> Option<Duration> {
+    use rodio::Source;
+
+    let file = std::fs::File::open(path).ok()?;
+    let decoder = rodio::Decoder::new(std::io::BufReader::new(file)).ok()?;
+    let samples_per_sec = decoder.sample_rate() as u64 * decoder.channels() as u64;
+    if samples_per_sec == 0 {
+        return None;
+    }
+    let samples = decoder.count() as u64;
+    let nanos = samples * 1_000_000_000 / samples_per_sec;
+    Some(Duration::from_nanos(nanos))
+}
+
+/// Fingerprint of a file's decoded audio: an FNV-1a hash of its sample
+/// rate, channel count and PCM samples, as 16 hex digits. Copies of the same
+/// audio match whatever their file names or tags; a re-encode does not.
+/// Decodes the whole file. None if it can't be decoded or is empty.
+pub fn audio_fingerprint(path: &Path) -> Option<String> {
+    use rodio::Source;
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let file = std::fs::File::open(path).ok()?;
+    let decoder = rodio::Decoder::new(std::io::BufReader::new(file)).ok()?;
+    let mut hash = FNV_OFFSET;
+    let mut feed = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+    feed(&decoder.sample_rate().to_le_bytes());
+    feed(&decoder.channels().to_le_bytes());
+    let mut samples = 0u64;
+    for sample in decoder {
+        feed(&sample.to_le_bytes());
+        samples += 1;
+    }
+    (samples > 0).then(|| format!("{:016x}", hash))
+}
+
 fn normalize_input_path(path: &Path) -> Result<PathBuf, String> {
     // Avoid canonicalize — it resolves mapped drives to UNC paths on Windows
     // (e.g. G:\Music → \\NAS\share\Music), losing the drive letter the user expects.
@@ -173,6 +382,42 @@ fn filename_fallback_title(path: &Path) -> String {
     title
 }
 
+/// Parse a ReplayGain tag value such as "-3.21 dB".
+fn parse_replay_gain(value: &str) -> Option<f32> {
+    let value = value.trim();
+    let number = value
+        .strip_suffix("dB")
+        .or_else(|| value.strip_suffix("db"))
+        .unwrap_or(value);
+    let db: f32 = number.trim().parse().ok()?;
+    db.is_finite().then_some(db)
+}
+
+/// Parse an intro-length tag: seconds ("12.5") or minutes and seconds
+/// ("0:12.5").
+fn parse_talk_over(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let secs = match value.split_once(':') {
+        Some((mins, secs)) => {
+            let mins: u32 = mins.trim().parse().ok()?;
+            mins as f64 * 60.0 + secs.trim().parse::<f64>().ok()?
+        }
+        None => value.parse::<f64>().ok()?,
+    };
+    Duration::try_from_secs_f64(secs)
+        .ok()
+        .filter(|d| !d.is_zero())
+}
+
+/// Parse an advisory tag (iTunes `rtng`/`ITUNESADVISORY`): 1 and 4 mean
+/// explicit, 0 (none) and 2 (clean) do not.
+fn parse_advisory(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "1" | "4" | "explicit"
+    )
+}
+
 mod duration_serde {
     use serde::{Deserialize, Deserializer, Serialize, Serializer};
     use std::time::Duration;
@@ -234,8 +479,7 @@ mod tests {
             title: title.to_string(),
             artist: artist.to_string(),
             duration: Duration::new(60, 0),
-            played_duration: None,
-            has_intro: false,
+            ..Default::default()
         }
     }
 
@@ -246,12 +490,63 @@ mod tests {
             title: "Test".to_string(),
             artist: "Artist".to_string(),
             duration: Duration::new(185, 0), // 3:05
-            played_duration: None,
-            has_intro: false,
+            ..Default::default()
         };
         assert_eq!(track.duration_display(), "3:05");
     }
 
+    #[test]
+    fn gain_trim_adds_to_replay_gain() {
+        let mut track = make_track("Test", "Artist");
+        track.replay_gain_db = Some(-3.0);
+        track.gain_trim_db = 6.0;
+        assert!((track.playback_gain_db(true) - 3.0).abs() < 1e-6);
+        // +3 dB is roughly 1.413x amplitude
+        assert!((track.playback_gain(true) - 1.4125).abs() < 1e-3);
+        // With ReplayGain off only the trim applies
+        assert!((track.playback_gain_db(false) - 6.0).abs() < 1e-6);
+
+        track.replay_gain_db = None;
+        track.gain_trim_db = 0.0;
+        assert_eq!(track.playback_gain(true), 1.0);
+    }
+
+    #[test]
+    fn validate_gain_trim_rejects_absurd_values() {
+        assert_eq!(validate_gain_trim(-24.0), Ok(-24.0));
+        assert_eq!(validate_gain_trim(12.0), Ok(12.0));
+        assert!(validate_gain_trim(12.5).is_err());
+        assert!(validate_gain_trim(-60.0).is_err());
+        assert!(validate_gain_trim(f32::NAN).is_err());
+        assert!(validate_gain_trim(f32::INFINITY).is_err());
+    }
+
+    #[test]
+    fn parse_replay_gain_reads_tag_values() {
+        assert_eq!(parse_replay_gain("-3.21 dB"), Some(-3.21));
+        assert_eq!(parse_replay_gain("+1.5 dB"), Some(1.5));
+        assert_eq!(parse_replay_gain("2.0"), Some(2.0));
+        assert_eq!(parse_replay_gain("loud"), None);
+    }
+
+    #[test]
+    fn parse_talk_over_reads_seconds_and_minutes() {
+        assert_eq!(parse_talk_over("12.5"), Some(Duration::from_millis(12500)));
+        assert_eq!(parse_talk_over(" 1:05 "), Some(Duration::from_secs(65)));
+        assert_eq!(parse_talk_over("0"), None);
+        assert_eq!(parse_talk_over("soon"), None);
+    }
+
+    #[test]
+    fn parse_advisory_reads_itunes_ratings() {
+        assert!(parse_advisory("1"));
+        assert!(parse_advisory("4"));
+        assert!(parse_advisory(" Explicit "));
+        assert!(!parse_advisory("0"));
+        assert!(!parse_advisory("2"));
+        assert!(!parse_advisory("clean"));
+    }
+
     #[test]
     fn played_duration_display_none_when_unset() {
         let track = make_track("Test", "Artist");
@@ -271,6 +566,177 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Write a mono 8 kHz WAV holding a repeating ramp of `count` samples.
+    fn write_tiny_wav(path: &Path, count: u16) {
+        let samples: Vec<i16> = (0..count).map(|i| (i % 600) as i16 * 50).collect();
+        crate::test_audio::write_wav(path, &samples, 8000);
+    }
+
+    #[test]
+    fn from_path_reads_initial_key_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Band - Song.wav");
+        write_tiny_wav(&path, 400);
+        assert_eq!(Track::from_path(&path).unwrap().musical_key, None);
+
+        let mut tagged_file = lofty::read_from_path(&path).unwrap();
+        let mut tag = Tag::new(tagged_file.primary_tag_type());
+        tag.insert_text(ItemKey::InitialKey, "Am".to_string());
+        tagged_file.insert_tag(tag);
+        tagged_file
+            .save_to_path(&path, WriteOptions::default())
+            .unwrap();
+
+        let track = Track::from_path(&path).unwrap();
+        assert_eq!(track.musical_key.as_deref(), Some("Am"));
+        assert_eq!(track.title, "Song");
+    }
+
+    #[test]
+    fn from_path_reads_intro_tag() {
+        use lofty::tag::{ItemValue, TagItem};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Band - Song.wav");
+        write_tiny_wav(&path, 400);
+        assert_eq!(Track::from_path(&path).unwrap().talk_over_secs, None);
+
+        let mut tagged_file = lofty::read_from_path(&path).unwrap();
+        let mut tag = Tag::new(tagged_file.primary_tag_type());
+        let intro = ItemKey::Unknown("INTRO".to_string());
+        tag.insert_unchecked(TagItem::new(intro, ItemValue::Text("8.5".to_string())));
+        tagged_file.insert_tag(tag);
+        tagged_file
+            .save_to_path(&path, WriteOptions::default())
+            .unwrap();
+
+        let track = Track::from_path(&path).unwrap();
+        assert_eq!(track.talk_over_secs, Some(Duration::from_millis(8500)));
+    }
+
+    #[test]
+    fn file_error_reports_missing_and_empty_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tiny.wav");
+        write_tiny_wav(&path, 400);
+        assert_eq!(file_error(&path), None);
+
+        let missing = file_error(&dir.path().join("missing.wav"));
+        assert_eq!(missing.as_deref(), Some("File not found"));
+
+        let empty = dir.path().join("empty.mp3");
+        std::fs::write(&empty, b"").unwrap();
+        assert_eq!(file_error(&empty).as_deref(), Some("File is empty"));
+        assert_eq!(file_error(dir.path()).as_deref(), Some("Not a file"));
+    }
+
+    #[test]
+    fn can_decode_accepts_tiny_valid_wav() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tiny.wav");
+        write_tiny_wav(&path, 400);
+
+        assert!(can_decode(&path));
+    }
+
+    #[test]
+    fn decoded_duration_counts_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("half_second.wav");
+        write_tiny_wav(&path, 4000);
+
+        assert_eq!(decoded_duration(&path), Some(Duration::from_millis(500)));
+        assert_eq!(decoded_duration(&dir.path().join("missing.wav")), None);
+    }
+
+    #[test]
+    fn audio_fingerprint_ignores_file_name_and_tags() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("Band - Song.wav");
+        let renamed = dir.path().join("song (copy).wav");
+        let other = dir.path().join("other.wav");
+        write_tiny_wav(&original, 400);
+        std::fs::copy(&original, &renamed).unwrap();
+        write_tiny_wav(&other, 401);
+
+        let fingerprint = audio_fingerprint(&original).unwrap();
+        assert_eq!(fingerprint.len(), 16);
+
+        // Retagging changes the bytes but not the audio
+        let mut tagged_file = lofty::read_from_path(&renamed).unwrap();
+        let mut tag = Tag::new(tagged_file.primary_tag_type());
+        tag.insert_text(ItemKey::TrackArtist, "Someone".to_string());
+        tagged_file.insert_tag(tag);
+        tagged_file
+            .save_to_path(&renamed, WriteOptions::default())
+            .unwrap();
+        assert_eq!(audio_fingerprint(&renamed), Some(fingerprint.clone()));
+
+        assert_ne!(audio_fingerprint(&other), Some(fingerprint));
+        assert_eq!(audio_fingerprint(&dir.path().join("missing.wav")), None);
+    }
+
+    #[test]
+    fn zero_tag_duration_is_measured_only_when_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Band - Song.wav");
+        write_tiny_wav(&path, 4000);
+        let mut track = Track::from_path(&path).unwrap();
+        // As if the tags carried no length
+        track.duration = Duration::ZERO;
+
+        assert!(!track.fill_unknown_duration(false));
+        assert_eq!(track.duration, Duration::ZERO);
+
+        assert!(track.fill_unknown_duration(true));
+        assert_eq!(track.duration, Duration::from_millis(500));
+
+        // A known duration is never re-measured
+        track.duration = Duration::from_secs(9);
+        assert!(!track.fill_unknown_duration(true));
+        assert_eq!(track.duration, Duration::from_secs(9));
+    }
+
+    #[test]
+    fn remaining_duration_prefers_verified_length() {
+        let mut track = make_track("Song", "Artist");
+        assert_eq!(
+            track.remaining_duration(Duration::from_secs(20)),
+            Duration::from_secs(40)
+        );
+        track.verified_duration = Some(Duration::from_secs(50));
+        assert_eq!(track.effective_duration(), Duration::from_secs(50));
+        assert_eq!(
+            track.remaining_duration(Duration::from_secs(20)),
+            Duration::from_secs(30)
+        );
+        let past_end = track.remaining_duration(Duration::from_secs(90));
+        assert_eq!(past_end, Duration::ZERO);
+    }
+
+    #[test]
+    fn can_decode_rejects_tagged_file_with_corrupt_audio() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.mp3");
+        // ID3v2.3 header with a single TIT2 ("Title") frame...
+        let frame_body = b"\x00Broken Song";
+        let mut frame = Vec::new();
+        frame.extend_from_slice(b"TIT2");
+        frame.extend_from_slice(&(frame_body.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&[0, 0]);
+        frame.extend_from_slice(frame_body);
+        let mut bytes = vec![b'I', b'D', b'3', 3, 0, 0];
+        // Syncsafe size (all values < 128 here)
+        bytes.extend_from_slice(&[0, 0, 0, frame.len() as u8]);
+        bytes.extend_from_slice(&frame);
+        // ...followed by an audio body with no valid MPEG frames.
+        bytes.extend((0..4096u32).map(|i| (i % 97) as u8));
+        std::fs::write(&path, bytes).unwrap();
+
+        assert!(!can_decode(&path));
+        assert!(!can_decode(Path::new("nonexistent.mp3")));
+    }
+
     #[test]
     fn normalize_input_path_makes_relative_paths_absolute() {
         let path = normalize_input_path(Path::new("nonexistent.mp3")).unwrap();
@@ -3,6 +3,7 @@ use std::fs::{self, File};
 use std::hash::{Hash, Hasher};
 use std::io::{BufReader, Read as _, Write as _};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Number of amplitude peaks to generate for the waveform overview.
 const DEFAULT_NUM_PEAKS: usize = 200;
@@ -89,6 +90,22 @@ fn downsample_peaks(chunk_peaks: &[f32], num_peaks: usize) -> Vec<f32> {
     peaks
 }
 
+/// Fit an existing peak array to exactly `target_len` entries, so the UI can
+/// ask for a stable width whatever `resolution_ms` produced the peaks.
+/// Longer arrays are max-pooled (see `downsample_peaks`); shorter ones are
+/// stretched by repeating each peak, so peaks keep their relative position.
+pub fn resample_peaks(peaks: &[f32], target_len: usize) -> Vec<f32> {
+    if peaks.len() >= target_len {
+        return downsample_peaks(peaks, target_len);
+    }
+    if peaks.is_empty() {
+        return vec![0.0; target_len];
+    }
+    (0..target_len)
+        .map(|i| peaks[i * peaks.len() / target_len])
+        .collect()
+}
+
 /// Generate waveform peaks with the default number of buckets.
 pub fn generate_peaks_default(path: &Path) -> Result<Vec<f32>, String> {
     generate_peaks(path, DEFAULT_NUM_PEAKS)
@@ -121,7 +138,10 @@ fn cache_key(path: &Path) -> Option<String> {
 
 /// Try to load cached peaks from disk.
 fn load_cached(path: &Path) -> Option<Vec<f32>> {
-    let dir = cache_dir()?;
+    load_cached_in(&cache_dir()?, path)
+}
+
+fn load_cached_in(dir: &Path, path: &Path) -> Option<Vec<f32>> {
     let key = cache_key(path)?;
     let cache_path = dir.join(&key);
 
@@ -144,9 +164,13 @@ fn load_cached(path: &Path) -> Option<Vec<f32>> {
 /// Write peaks to disk cache.
 fn save_cache(path: &Path, peaks: &[f32]) {
     let Some(dir) = cache_dir() else { return };
+    save_cache_in(&dir, path, peaks);
+}
+
+fn save_cache_in(dir: &Path, path: &Path, peaks: &[f32]) {
     let Some(key) = cache_key(path) else { return };
 
-    if fs::create_dir_all(&dir).is_err() {
+    if fs::create_dir_all(dir).is_err() {
         return;
     }
 
@@ -176,6 +200,123 @@ pub fn generate_peaks_cached(path: &Path) -> Result<Vec<f32>, String> {
     Ok(peaks)
 }
 
+/// Low-resolution peaks for playlist-row mini-waveforms.
+/// Reuses the cached overview and downsamples it to `num_peaks`
+/// (capped at the cached resolution), so repeat calls never re-decode.
+pub fn generate_mini_peaks(path: &Path, num_peaks: usize) -> Result<Vec<f32>, String> {
+    let peaks = generate_peaks_cached(path)?;
+    Ok(downsample_peaks(
+        &peaks,
+        num_peaks.clamp(1, DEFAULT_NUM_PEAKS),
+    ))
+}
+
+/// Mini-waveforms for a list of track paths, keyed by position.
+/// Unreadable files yield an empty peak array instead of failing the batch.
+pub fn mini_peaks_for_paths(paths: &[PathBuf], num_peaks: usize) -> Vec<(usize, Vec<f32>)> {
+    paths
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i, generate_mini_peaks(p, num_peaks).unwrap_or_default()))
+        .collect()
+}
+
+// ── Batch precompute ────────────────────────────────────────────────────────
+
+/// Worker threads used when precomputing a playlist's waveforms.
+pub const PRECOMPUTE_WORKERS: usize = 4;
+
+/// Progress update emitted after each track during a precompute run.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PrecomputeProgress {
+    pub done: usize,
+    pub total: usize,
+    pub path: String,
+}
+
+/// Outcome of a precompute run.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct PrecomputeSummary {
+    pub total: usize,
+    /// Tracks decoded and written to the cache on this run.
+    pub computed: usize,
+    /// Tracks whose cache entry (path + size + mtime) was already present.
+    pub already_cached: usize,
+    pub failed: usize,
+}
+
+/// Generate and disk-cache overview peaks for every path using up to
+/// `workers` threads. Already-cached files are skipped without decoding.
+/// `on_progress` is called from the worker threads after each file.
+pub fn precompute_peaks<F>(paths: &[PathBuf], workers: usize, on_progress: F) -> PrecomputeSummary
+where
+    F: Fn(PrecomputeProgress) + Sync,
+{
+    match cache_dir() {
+        Some(dir) => precompute_peaks_in(&dir, paths, workers, on_progress),
+        None => PrecomputeSummary {
+            total: paths.len(),
+            failed: paths.len(),
+            ..Default::default()
+        },
+    }
+}
+
+fn precompute_peaks_in<F>(
+    dir: &Path,
+    paths: &[PathBuf],
+    workers: usize,
+    on_progress: F,
+) -> PrecomputeSummary
+where
+    F: Fn(PrecomputeProgress) + Sync,
+{
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let computed = AtomicUsize::new(0);
+    let already_cached = AtomicUsize::new(0);
+    let failed = AtomicUsize::new(0);
+    let total = paths.len();
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, total.max(1)) {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(path) = paths.get(i) else { break };
+                    if load_cached_in(dir, path).is_some() {
+                        already_cached.fetch_add(1, Ordering::Relaxed);
+                    } else {
+                        match generate_peaks_default(path) {
+                            Ok(peaks) => {
+                                save_cache_in(dir, path, &peaks);
+                                computed.fetch_add(1, Ordering::Relaxed);
+                            }
+                            Err(_) => {
+                                failed.fetch_add(1, Ordering::Relaxed);
+                            }
+                        }
+                    }
+                    on_progress(PrecomputeProgress {
+                        done: done.fetch_add(1, Ordering::Relaxed) + 1,
+                        total,
+                        path: path.to_string_lossy().to_string(),
+                    });
+                }
+            });
+        }
+    });
+
+    PrecomputeSummary {
+        total,
+        computed: computed.into_inner(),
+        already_cached: already_cached.into_inner(),
+        failed: failed.into_inner(),
+    }
+}
+
 // ── Editor peaks (high-resolution) ───────────────────────────────────────────
 
 /// High-resolution peak data for the in-app audio editor.
@@ -249,6 +390,59 @@ pub fn generate_editor_peaks(path: &Path, resolution_ms: u32) -> Result<EditorPe
     })
 }
 
+/// Peaks for the `start_secs..end_secs` window only, for zoomed-in editor
+/// views. Seeks to the window start (falling back to skipping samples when
+/// the decoder can't seek) and stops decoding at the window end.
+///
+/// Returns `num_peaks` evenly spaced peaks. Unlike the overview they are not
+/// normalized, so adjacent windows line up when the view scrolls. Peaks past
+/// the end of the file are zero.
+pub fn generate_peaks_range(
+    path: &Path,
+    start_secs: f64,
+    end_secs: f64,
+    num_peaks: usize,
+) -> Result<Vec<f32>, String> {
+    if !start_secs.is_finite() || !end_secs.is_finite() || start_secs < 0.0 {
+        return Err("Invalid waveform range".to_string());
+    }
+    if end_secs <= start_secs {
+        return Err(format!(
+            "Waveform range end ({:.3}s) must be after start ({:.3}s)",
+            end_secs, start_secs
+        ));
+    }
+    if num_peaks == 0 {
+        return Err("Peak count must be at least 1".to_string());
+    }
+
+    let file = File::open(path)
+        .map_err(|e| format!("Cannot open '{}': {}", path.display(), e))?;
+    let mut source = Decoder::new(BufReader::new(file))
+        .map_err(|e| format!("Cannot decode '{}': {}", path.display(), e))?;
+
+    let channels = source.channels().max(1) as usize;
+    let sample_rate = source.sample_rate() as f64;
+    let skip = if source.try_seek(Duration::from_secs_f64(start_secs)).is_ok() {
+        0
+    } else {
+        (start_secs * sample_rate) as usize * channels
+    };
+    let window_samples = ((end_secs - start_secs) * sample_rate) as usize * channels;
+    let samples_per_peak = window_samples.div_ceil(num_peaks).max(1);
+
+    let mut peaks = vec![0.0_f32; num_peaks];
+    let samples = source
+        .convert_samples::<f32>()
+        .skip(skip)
+        .take(window_samples);
+    for (i, sample) in samples.enumerate() {
+        let bucket = &mut peaks[(i / samples_per_peak).min(num_peaks - 1)];
+        *bucket = bucket.max(sample.abs());
+    }
+    Ok(peaks)
+}
+
 // ── Editor peak cache ────────────────────────────────────────────────────────
 // Cache format: magic "SFEP" (4B) | resolution_ms u32-LE (4B)
 //               | duration_secs f64-LE (8B) | sample_rate u32-LE (4B)
@@ -353,9 +547,84 @@ pub fn generate_editor_peaks_cached(
     Ok(data)
 }
 
+/// One file's outcome in an editor peak batch: `data` on success, else `error`.
+#[derive(serde::Serialize)]
+pub struct EditorBatchEntry {
+    pub path: String,
+    pub data: Option<EditorPeakData>,
+    pub error: Option<String>,
+}
+
+/// Worker threads used for an editor peak batch.
+pub const EDITOR_BATCH_WORKERS: usize = 4;
+
+/// Cached editor peaks for many files on up to `workers` threads, one entry
+/// per path in input order. A file that fails doesn't fail the batch.
+pub fn generate_editor_peaks_batch(
+    paths: &[PathBuf],
+    resolution_ms: u32,
+    workers: usize,
+) -> Vec<EditorBatchEntry> {
+    editor_peaks_batch_with(paths, workers, |path| {
+        generate_editor_peaks_cached(path, resolution_ms)
+    })
+}
+
+fn editor_peaks_batch_with<F>(paths: &[PathBuf], workers: usize, load: F) -> Vec<EditorBatchEntry>
+where
+    F: Fn(&Path) -> Result<EditorPeakData, String> + Sync,
+{
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, Result<EditorPeakData, String>)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers.clamp(1, paths.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = paths.get(i) else { break };
+                        done.push((i, load(path)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_default())
+            .collect()
+    });
+    results.sort_by_key(|(i, _)| *i);
+
+    let mut results = results.into_iter().peekable();
+    paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            // A panicked worker drops its results; report those files as failed
+            let result = match results.next_if(|(j, _)| *j == i) {
+                Some((_, result)) => result,
+                None => Err(format!("Waveform task failed for '{}'", path.display())),
+            };
+            let (data, error) = match result {
+                Ok(data) => (Some(data), None),
+                Err(e) => (None, Some(e)),
+            };
+            EditorBatchEntry {
+                path: path.to_string_lossy().to_string(),
+                data,
+                error,
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_audio::write_wav;
 
     #[test]
     fn generate_peaks_rejects_missing_file() {
@@ -364,6 +633,46 @@ mod tests {
         assert!(result.unwrap_err().contains("Cannot open"));
     }
 
+    #[test]
+    fn peaks_range_only_reflects_requested_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("regions.wav");
+        // 4s at 1kHz: loud burst in 0.5..1.0s, quiet, loud burst in 2.5..3.0s
+        let samples: Vec<i16> = (0..4000)
+            .map(|i| match i {
+                500..1000 | 2500..3000 => 20000,
+                _ => 100,
+            })
+            .collect();
+        write_wav(&path, &samples, 1000);
+
+        // Window 2.0..3.5s contains only the second burst, in its middle third
+        let peaks = generate_peaks_range(&path, 2.0, 3.5, 3).unwrap();
+        assert_eq!(peaks.len(), 3);
+        assert!(peaks[0] < 0.01, "before burst: {}", peaks[0]);
+        assert!(peaks[1] > 0.5, "burst: {}", peaks[1]);
+        assert!(peaks[2] < 0.01, "after burst: {}", peaks[2]);
+
+        // Window 1.5..2.5s sits between the bursts
+        let quiet = generate_peaks_range(&path, 1.5, 2.5, 10).unwrap();
+        assert!(quiet.iter().all(|p| *p < 0.01));
+
+        // Past the end of the file
+        let tail = generate_peaks_range(&path, 3.5, 5.5, 4).unwrap();
+        assert_eq!(&tail[2..], &[0.0, 0.0]);
+    }
+
+    #[test]
+    fn peaks_range_rejects_bad_ranges() {
+        let missing = Path::new("nonexistent.mp3");
+        assert!(generate_peaks_range(missing, 2.0, 1.0, 10).is_err());
+        assert!(generate_peaks_range(missing, -1.0, 1.0, 10).is_err());
+        assert!(generate_peaks_range(missing, 0.0, f64::NAN, 10).is_err());
+        assert!(generate_peaks_range(missing, 0.0, 1.0, 0).is_err());
+        let result = generate_peaks_range(missing, 0.0, 1.0, 10);
+        assert!(result.unwrap_err().contains("Cannot open"));
+    }
+
     #[test]
     fn default_peaks_count() {
         assert_eq!(DEFAULT_NUM_PEAKS, 200);
@@ -400,9 +709,138 @@ mod tests {
         assert!((result[1] - 0.6).abs() < 1e-6);
     }
 
+    #[test]
+    fn resample_many_to_few_keeps_maxima() {
+        // 1000 peaks → 200, five per bucket
+        let mut input = vec![0.1; 1000];
+        input[3] = 1.0; // bucket 0
+        input[502] = 0.9; // bucket 100
+        input[999] = 0.7; // bucket 199
+        let result = resample_peaks(&input, 200);
+        assert_eq!(result.len(), 200);
+        assert_eq!(result[0], 1.0);
+        assert_eq!(result[100], 0.9);
+        assert_eq!(result[199], 0.7);
+        assert_eq!(result[50], 0.1);
+    }
+
+    #[test]
+    fn resample_few_to_many_keeps_peak_in_place() {
+        let input = vec![0.2, 0.2, 1.0, 0.2];
+        let result = resample_peaks(&input, 10);
+        assert_eq!(result.len(), 10);
+        assert_eq!(result.iter().cloned().fold(0.0_f32, f32::max), 1.0);
+        // Third quarter of the input stays in the third quarter of the output
+        let at = result.iter().position(|&p| p == 1.0).unwrap();
+        assert!((5..8).contains(&at), "peak at {}", at);
+
+        assert_eq!(resample_peaks(&[], 3), vec![0.0; 3]);
+        assert!(resample_peaks(&input, 0).is_empty());
+    }
+
+    #[test]
+    fn mini_peaks_for_unreadable_paths_are_empty() {
+        let paths = vec![PathBuf::from("missing_a.mp3"), PathBuf::from("missing_b.mp3")];
+        let result = mini_peaks_for_paths(&paths, 32);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1].0, 1);
+        assert!(result.iter().all(|(_, peaks)| peaks.is_empty()));
+    }
+
     #[test]
     fn cache_key_deterministic() {
         // cache_key returns None for nonexistent files
         assert!(cache_key(Path::new("nonexistent.mp3")).is_none());
     }
+
+    #[test]
+    fn precompute_caches_each_track_and_skips_on_second_run() {
+        let audio = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let samples: Vec<i16> = (0..4000).map(|i| ((i % 50) * 400) as i16).collect();
+        let mut paths: Vec<PathBuf> = (0..5)
+            .map(|i| {
+                let p = audio.path().join(format!("t{}.wav", i));
+                write_wav(&p, &samples, 8000);
+                p
+            })
+            .collect();
+        paths.push(audio.path().join("missing.wav"));
+
+        let progress = std::sync::Mutex::new(Vec::new());
+        let first = precompute_peaks_in(cache.path(), &paths, 3, |p| {
+            progress.lock().unwrap().push(p.done)
+        });
+        assert_eq!(first.total, 6);
+        assert_eq!(first.computed, 5);
+        assert_eq!(first.already_cached, 0);
+        assert_eq!(first.failed, 1);
+        let mut seen = progress.into_inner().unwrap();
+        seen.sort();
+        assert_eq!(seen, vec![1, 2, 3, 4, 5, 6]);
+
+        for p in &paths[..5] {
+            assert!(cache.path().join(cache_key(p).unwrap()).exists());
+        }
+
+        let second = precompute_peaks_in(cache.path(), &paths, 3, |_| {});
+        assert_eq!(second.computed, 0);
+        assert_eq!(second.already_cached, 5);
+        assert_eq!(second.failed, 1);
+    }
+
+    #[test]
+    fn editor_batch_maps_each_path_to_its_own_result() {
+        let dir = tempfile::tempdir().unwrap();
+        let samples: Vec<i16> = (0..2000).map(|i| ((i % 40) * 500) as i16).collect();
+        let paths: Vec<PathBuf> = ["a.wav", "missing.wav", "b.wav", "c.wav", "gone.mp3"]
+            .iter()
+            .map(|name| dir.path().join(name))
+            .collect();
+        for i in [0, 2, 3] {
+            write_wav(&paths[i], &samples, 1000);
+        }
+
+        let batch = editor_peaks_batch_with(&paths, 2, |p| generate_editor_peaks(p, 10));
+        assert_eq!(batch.len(), 5);
+        for (entry, path) in batch.iter().zip(&paths) {
+            assert_eq!(entry.path, path.to_string_lossy());
+        }
+        for i in [0, 2, 3] {
+            let data = batch[i].data.as_ref().unwrap();
+            assert!(batch[i].error.is_none());
+            assert!((data.duration_secs - 2.0).abs() < 0.01);
+            assert_eq!(data.num_peaks, 200);
+        }
+        for i in [1, 4] {
+            assert!(batch[i].data.is_none());
+            let error = batch[i].error.as_deref().unwrap();
+            assert!(error.contains("Cannot open"), "{}", error);
+            assert!(error.contains(paths[i].to_str().unwrap()), "{}", error);
+        }
+    }
+
+    #[test]
+    fn editor_batch_stays_within_worker_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let paths: Vec<PathBuf> = (0..12)
+            .map(|i| PathBuf::from(format!("{}.wav", i)))
+            .collect();
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let batch = editor_peaks_batch_with(&paths, 3, |p| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            max_running.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(5));
+            running.fetch_sub(1, Ordering::SeqCst);
+            Err(p.display().to_string())
+        });
+        assert!(max_running.into_inner() <= 3);
+        let errors: Vec<_> = batch.iter().map(|e| e.error.clone().unwrap()).collect();
+        let expected: Vec<_> = paths.iter().map(|p| p.display().to_string()).collect();
+        assert_eq!(errors, expected);
+
+        assert!(editor_peaks_batch_with(&[], 3, |p| generate_editor_peaks(p, 10)).is_empty());
+    }
 }
@@ -3,7 +3,7 @@
 //! These tests exercise AppCore end-to-end without launching the GUI or Tauri.
 //! They verify that all features are testable via `cargo test` alone.
 
-use signal_flow::app_core::AppCore;
+use signal_flow::app_core::{AppCore, SeekOutcome};
 use signal_flow::audio_runtime::{spawn_audio_runtime, AudioEvent};
 use signal_flow::level_monitor::LevelMonitor;
 use std::path::PathBuf;
@@ -79,8 +79,7 @@ fn add_mock_track(core: &mut AppCore, playlist: &str, artist: &str, title: &str)
         title: title.to_string(),
         artist: artist.to_string(),
         duration: Duration::from_secs(180),
-        played_duration: None,
-        has_intro: false,
+        ..Default::default()
     };
     core.engine
         .find_playlist_mut(playlist)
@@ -229,6 +228,79 @@ fn transport_prepare_play_workflow() {
     assert!(!state.is_playing);
 }
 
+fn playlist_current_index(core: &AppCore, name: &str) -> Option<usize> {
+    core.get_playlists()
+        .into_iter()
+        .find(|p| p.name == name)
+        .and_then(|p| p.current_index)
+}
+
+#[test]
+fn end_of_playlist_stop_resets() {
+    let mut core = make_core();
+    core.create_playlist("Music".to_string()).unwrap();
+    core.set_active_playlist("Music").unwrap();
+    add_mock_track(&mut core, "Music", "Artist", "Only");
+    core.set_end_of_playlist_action("stop", None).unwrap();
+
+    core.prepare_play(Some(0)).unwrap();
+    assert_eq!(core.prepare_skip().unwrap_err(), "__end_of_playlist__");
+    assert!(!core.get_transport_state().is_playing);
+    assert_eq!(playlist_current_index(&core, "Music"), None);
+}
+
+#[test]
+fn end_of_playlist_loop_returns_to_first_track() {
+    let mut core = make_core();
+    core.create_playlist("Music".to_string()).unwrap();
+    core.set_active_playlist("Music").unwrap();
+    add_mock_track(&mut core, "Music", "Artist", "First");
+    add_mock_track(&mut core, "Music", "Artist", "Last");
+    core.set_end_of_playlist_action("loop", None).unwrap();
+    assert_eq!(core.get_config().end_of_playlist_action, "loop");
+
+    core.prepare_play(Some(1)).unwrap();
+    let (_, _, _, title, playlist, idx) = core.prepare_skip().unwrap();
+    assert_eq!(idx, 0);
+    assert_eq!(title, "First");
+    assert_eq!(playlist, "Music");
+    assert!(core.get_transport_state().is_playing);
+}
+
+#[test]
+fn end_of_playlist_fallback_switches_playlist() {
+    let mut core = make_core();
+    core.create_playlist("Live".to_string()).unwrap();
+    core.create_playlist("Overnight".to_string()).unwrap();
+    core.set_active_playlist("Live").unwrap();
+    add_mock_track(&mut core, "Live", "Host", "Show");
+    add_mock_track(&mut core, "Overnight", "Band", "Night One");
+    add_mock_track(&mut core, "Overnight", "Band", "Night Two");
+
+    let missing = core.set_end_of_playlist_action("fallback", Some("Missing"));
+    assert!(missing.is_err());
+    core.set_end_of_playlist_action("fallback", Some("Overnight"))
+        .unwrap();
+    let config = core.get_config();
+    assert_eq!(config.end_of_playlist_action, "fallback");
+    assert_eq!(
+        config.end_of_playlist_fallback.as_deref(),
+        Some("Overnight")
+    );
+
+    core.prepare_play(Some(0)).unwrap();
+    let (_, _, _, title, playlist, idx) = core.prepare_skip().unwrap();
+    assert_eq!(idx, 0);
+    assert_eq!(title, "Night One");
+    assert_eq!(playlist, "Overnight");
+    assert_eq!(
+        core.get_status().active_playlist,
+        Some("Overnight".to_string())
+    );
+    assert_eq!(playlist_current_index(&core, "Live"), None);
+    assert_eq!(playlist_current_index(&core, "Overnight"), Some(0));
+}
+
 #[test]
 fn transport_pause_resume_cycle() {
     let mut core = make_core();
@@ -273,6 +345,24 @@ fn transport_seek_updates_elapsed() {
     assert!(state.elapsed_secs >= 59.5 && state.elapsed_secs <= 61.0);
 }
 
+#[test]
+fn transport_seek_to_end_advances_like_track_end() {
+    let mut core = make_core();
+    core.create_playlist("Test".to_string()).unwrap();
+    core.set_active_playlist("Test").unwrap();
+    add_mock_track(&mut core, "Test", "Artist A", "Song 1");
+    add_mock_track(&mut core, "Test", "Artist B", "Song 2");
+
+    core.prepare_play(None).unwrap();
+    let outcome = core.on_seek(180.0).unwrap();
+    assert_eq!(outcome, SeekOutcome::EndOfTrack);
+
+    // The caller handles EndOfTrack exactly like a natural track end.
+    let (_, _, artist, _, _, idx) = core.prepare_skip().unwrap();
+    assert_eq!(idx, 1);
+    assert_eq!(artist, "Artist B");
+}
+
 #[test]
 fn transport_play_specific_track() {
     let mut core = make_core();